@@ -0,0 +1,48 @@
+//! Tools for operating Hyperlane deployments, using the agent config.
+//!
+//! Chains are configured like they are for the agents, with the config files
+//! in `./config`, `CONFIG_FILES` and `HYP_` environment variables. Only the
+//! chains a command uses are parsed. Results are printed as a single line of
+//! JSON.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+
+use crate::{registry::ConfigArgs, send::SendArgs, status::StatusArgs};
+
+mod registry;
+mod scraper;
+mod send;
+mod settings;
+mod status;
+
+#[derive(Parser)]
+#[command(name = "hyperlane", about = "Operate Hyperlane deployments")]
+struct Cli {
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand)]
+enum Cmd {
+    /// Generate the agent config of chains from the Hyperlane registry
+    Config(ConfigArgs),
+    /// Dispatch a test message and wait for it to be delivered
+    Send(SendArgs),
+    /// Check each stage of the delivery of a message and report the one
+    /// blocking it
+    Status(StatusArgs),
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.cmd {
+        Cmd::Config(args) => registry::run(args).await,
+        Cmd::Send(args) => send::run(args).await,
+        Cmd::Status(args) => status::run(args).await,
+    }
+}
@@ -0,0 +1,123 @@
+//! Generate the agent config of a set of chains from the Hyperlane registry,
+//! which holds the metadata and the deployed core contract addresses of each
+//! chain. The config is parsed and checked like the agents do before it's
+//! written out, so a chain missing from the registry or missing a contract is
+//! reported instead of failing an agent at startup.
+
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use clap::Args;
+use eyre::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use hyperlane_base::settings::{loader::load_settings_from_json, parser::RawAgentConf, Settings};
+use hyperlane_core::config::ConfigPath;
+
+/// The canonical registry
+const DEFAULT_REGISTRY: &str =
+    "https://raw.githubusercontent.com/hyperlane-xyz/hyperlane-registry/main";
+
+#[derive(Args)]
+pub struct ConfigArgs {
+    /// Names of the chains to generate the config of, separated by commas
+    #[arg(long, value_delimiter = ',', required = true)]
+    chains: Vec<String>,
+    /// Url of the registry, or the path of a local checkout
+    #[arg(long, default_value = DEFAULT_REGISTRY)]
+    registry: String,
+    /// File to write the config to. Printed if not specified.
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+pub async fn run(args: ConfigArgs) -> Result<()> {
+    let registry = Registry::new(&args.registry);
+    let mut chains = serde_json::Map::new();
+    for chain in &args.chains {
+        let conf = registry
+            .chain_config(chain)
+            .await
+            .with_context(|| format!("Failed to read chain `{chain}` from the registry"))?;
+        chains.insert(chain.clone(), conf);
+    }
+    let config = json!({ "chains": chains });
+    validate(&config, &args.chains)?;
+
+    match &args.out {
+        Some(path) => {
+            fs::write(path, serde_json::to_string_pretty(&config)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("{}", json!({ "chains": args.chains, "out": path }));
+        }
+        None => println!("{config}"),
+    }
+    Ok(())
+}
+
+/// A registry served over http or checked out locally, with the files of
+/// each chain in `chains/<name>/`
+enum Registry {
+    Remote(String),
+    Local(PathBuf),
+}
+
+impl Registry {
+    fn new(location: &str) -> Self {
+        if location.starts_with("http://") || location.starts_with("https://") {
+            Self::Remote(location.trim_end_matches('/').to_owned())
+        } else {
+            Self::Local(location.into())
+        }
+    }
+
+    async fn read(&self, path: &str) -> Result<String> {
+        match self {
+            Self::Remote(base) => {
+                let url = format!("{base}/{path}");
+                fetch(&url)
+                    .await
+                    .with_context(|| format!("Failed to fetch {url}"))
+            }
+            Self::Local(dir) => {
+                let path = dir.join(path);
+                fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))
+            }
+        }
+    }
+
+    /// The agent config of a chain: its metadata with the addresses of its
+    /// core contracts
+    async fn chain_config(&self, chain: &str) -> Result<Value> {
+        let metadata = self.read(&format!("chains/{chain}/metadata.yaml")).await?;
+        let addresses = self.read(&format!("chains/{chain}/addresses.yaml")).await?;
+        let metadata: Value = serde_yaml::from_str(&metadata).context("Invalid metadata")?;
+        let addresses: Value = serde_yaml::from_str(&addresses).context("Invalid addresses")?;
+        let (Value::Object(mut conf), Value::Object(addresses)) = (metadata, addresses) else {
+            bail!("Expected the metadata and the addresses to be maps");
+        };
+        if let Some(name) = conf.get("name").and_then(Value::as_str) {
+            if name != chain {
+                bail!("Metadata is of chain `{name}`");
+            }
+        }
+        conf.extend(addresses);
+        Ok(Value::Object(conf))
+    }
+}
+
+async fn fetch(url: &str) -> reqwest::Result<String> {
+    reqwest::get(url).await?.error_for_status()?.text().await
+}
+
+/// Parse the config of the chains like the agents do and check it,
+/// reporting every problem at once
+fn validate(config: &Value, chains: &[String]) -> Result<()> {
+    let filter: HashSet<&str> = chains.iter().map(String::as_str).collect();
+    let settings: Settings =
+        load_settings_from_json::<RawAgentConf, _, _>(&config.to_string(), Some(&filter))
+            .context("Generated config is invalid")?;
+    settings
+        .validate(&ConfigPath::default())
+        .context("Generated config is invalid")
+}
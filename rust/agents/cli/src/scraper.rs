@@ -0,0 +1,101 @@
+use eyre::{bail, eyre, Result};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, QueryResult, Statement};
+
+use hyperlane_core::{H256, H512, U256};
+
+/// Gas paid for a message, summed over its payments
+pub struct GasPaymentTotals {
+    pub num_payments: u64,
+    pub total_payment: U256,
+    pub total_gas_amount: U256,
+}
+
+/// The database of a scraper, with what it indexed of messages for an
+/// environment
+pub struct ScraperDb {
+    conn: DatabaseConnection,
+    environment: String,
+}
+
+impl ScraperDb {
+    pub async fn connect(url: &str, environment: &str) -> Result<Self> {
+        Ok(Self {
+            conn: Database::connect(url).await?,
+            environment: environment.to_owned(),
+        })
+    }
+
+    /// Whether the delivery of a message was indexed
+    pub async fn delivered(&self, message_id: H256) -> Result<bool> {
+        let delivery = self
+            .query_message(
+                r#"SELECT "id" FROM "delivered_message" WHERE "msg_id" = $1 AND "environment" = $2"#,
+                message_id,
+            )
+            .await?;
+        Ok(delivery.is_some())
+    }
+
+    /// Hash of the transaction a message was dispatched in, if the message
+    /// was indexed
+    pub async fn dispatch_tx(&self, message_id: H256) -> Result<Option<H512>> {
+        let Some(row) = self
+            .query_message(
+                r#"SELECT "origin_tx_hash" FROM "message_view" WHERE "msg_id" = $1 AND "environment" = $2"#,
+                message_id,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        let hash: Vec<u8> = row.try_get("", "origin_tx_hash")?;
+        Ok(Some(match hash.len() {
+            32 => H256::from_slice(&hash).into(),
+            64 => H512::from_slice(&hash),
+            len => bail!("Invalid dispatch transaction hash of {len} bytes"),
+        }))
+    }
+
+    /// Gas paid for a message in every payment the scraper indexed
+    pub async fn gas_payments(&self, message_id: H256) -> Result<GasPaymentTotals> {
+        let row = self
+            .query_message(
+                r#"SELECT "num_payments"::text, "total_payment"::text, "total_gas_amount"::text
+                FROM "total_gas_payment" WHERE "msg_id" = $1 AND "environment" = $2"#,
+                message_id,
+            )
+            .await?;
+        let Some(row) = row else {
+            return Ok(GasPaymentTotals {
+                num_payments: 0,
+                total_payment: U256::zero(),
+                total_gas_amount: U256::zero(),
+            });
+        };
+        let parse = |column: &str| -> Result<U256> {
+            let value: String = row.try_get("", column)?;
+            U256::from_dec_str(&value).map_err(|e| eyre!("Invalid {column} `{value}`: {e}"))
+        };
+        Ok(GasPaymentTotals {
+            num_payments: parse("num_payments")?.as_u64(),
+            total_payment: parse("total_payment")?,
+            total_gas_amount: parse("total_gas_amount")?,
+        })
+    }
+
+    /// Query a row about a message in the environment, the query taking the
+    /// message id as `$1` and the environment as `$2`
+    async fn query_message(&self, sql: &str, message_id: H256) -> Result<Option<QueryResult>> {
+        Ok(self
+            .conn
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [
+                    message_id.as_bytes().to_vec().into(),
+                    self.environment.clone().into(),
+                ],
+            ))
+            .await?)
+    }
+}
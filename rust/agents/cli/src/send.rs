@@ -0,0 +1,136 @@
+//! Smoke test a deployment by dispatching a message and timing its delivery.
+
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use eyre::{bail, Result};
+use serde_json::json;
+use tokio::time::sleep;
+
+use hyperlane_core::{validate_address, Mailbox, H256, U256};
+
+use crate::{scraper::ScraperDb, settings::CliSettings};
+
+#[derive(Args)]
+pub struct SendArgs {
+    /// Name of the chain to dispatch the message on
+    #[arg(long)]
+    origin: String,
+    /// Name of the chain to deliver the message to
+    #[arg(long)]
+    destination: String,
+    /// Address of the recipient on the destination chain
+    #[arg(long)]
+    recipient: String,
+    /// Body of the message
+    #[arg(long, default_value = "Hello from hyperlane send")]
+    body: String,
+    /// Pay the interchain gas paymaster of the origin chain for this amount
+    /// of destination gas. Not paid if not specified.
+    #[arg(long)]
+    gas_amount: Option<u64>,
+    /// Url of a scraper database to poll for the delivery, instead of the
+    /// destination mailbox
+    #[arg(long)]
+    scraper_db: Option<String>,
+    /// Environment the scraper stored the message in
+    #[arg(long, default_value = "default")]
+    scraper_environment: String,
+    /// Seconds to wait for the delivery before giving up
+    #[arg(long, default_value_t = 600)]
+    timeout: u64,
+    /// Seconds between polls for the delivery
+    #[arg(long, default_value_t = 5)]
+    poll_interval: u64,
+}
+
+/// Where the delivery of the message is polled
+enum DeliverySource {
+    Mailbox(Box<dyn Mailbox>),
+    Scraper(ScraperDb),
+}
+
+impl DeliverySource {
+    async fn delivered(&self, message_id: H256) -> Result<bool> {
+        match self {
+            Self::Mailbox(mailbox) => Ok(mailbox.delivered(message_id).await?),
+            Self::Scraper(db) => db.delivered(message_id).await,
+        }
+    }
+}
+
+pub async fn run(args: SendArgs) -> Result<()> {
+    let cli = CliSettings::load(&[&args.origin, &args.destination])?;
+    let origin = cli.domain(&args.origin)?;
+    let destination = cli.domain(&args.destination)?;
+    let recipient = validate_address(destination.domain_protocol(), &args.recipient)?;
+
+    let mailbox = cli.settings.build_mailbox(&origin, &cli.metrics).await?;
+    let delivery_source = match &args.scraper_db {
+        Some(url) => {
+            DeliverySource::Scraper(ScraperDb::connect(url, &args.scraper_environment).await?)
+        }
+        None => DeliverySource::Mailbox(
+            cli.settings
+                .build_mailbox(&destination, &cli.metrics)
+                .await?,
+        ),
+    };
+
+    let start = Instant::now();
+    let (message_id, dispatch) = mailbox
+        .dispatch(destination.id(), recipient, args.body.as_bytes())
+        .await?;
+    if !dispatch.executed {
+        bail!(
+            "Dispatch transaction {:?} reverted",
+            dispatch.transaction_id
+        );
+    }
+    let dispatch_latency = start.elapsed();
+
+    let gas_payment = match args.gas_amount {
+        Some(gas_amount) => {
+            let paymaster = cli
+                .settings
+                .build_interchain_gas_paymaster(&origin, &cli.metrics)
+                .await?;
+            let payment = paymaster
+                .pay_for_gas(message_id, destination.id(), U256::from(gas_amount))
+                .await?;
+            if !payment.executed {
+                bail!(
+                    "Gas payment transaction {:?} reverted",
+                    payment.transaction_id
+                );
+            }
+            Some(payment)
+        }
+        None => None,
+    };
+
+    let timeout = Duration::from_secs(args.timeout);
+    while !delivery_source.delivered(message_id).await? {
+        if start.elapsed() > timeout {
+            bail!("Message {message_id:?} was not delivered within {timeout:?}");
+        }
+        sleep(Duration::from_secs(args.poll_interval)).await;
+    }
+    let delivery_latency = start.elapsed();
+
+    println!(
+        "{}",
+        json!({
+            "message_id": format!("{message_id:?}"),
+            "origin": origin.name(),
+            "destination": destination.name(),
+            "dispatch_tx": format!("{:?}", dispatch.transaction_id),
+            "gas_payment_tx": gas_payment.map(|p| format!("{:?}", p.transaction_id)),
+            "dispatch_latency_secs": dispatch_latency.as_secs_f64(),
+            "delivery_latency_secs": delivery_latency.as_secs_f64(),
+            // Polling delays noticing the delivery by up to an interval
+            "poll_interval_secs": args.poll_interval,
+        })
+    );
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::{collections::HashSet, sync::Arc};
+
+use eyre::{eyre, Context, Result};
+use hyperlane_base::{
+    settings::{loader::load_settings_without_arguments, parser::RawAgentConf, Settings},
+    CoreMetrics,
+};
+use hyperlane_core::HyperlaneDomain;
+
+/// The agent config of the chains a command uses, with the metrics contracts
+/// are built with
+pub struct CliSettings {
+    pub settings: Settings,
+    pub metrics: Arc<CoreMetrics>,
+}
+
+impl CliSettings {
+    /// Load the agent config of `chains`
+    pub fn load(chains: &[&str]) -> Result<Self> {
+        let chains: HashSet<&str> = chains.iter().copied().collect();
+        Self::load_filtered(Some(&chains))
+    }
+
+    /// Load the agent config of every chain, for commands that only learn
+    /// which chains they use from the chains themselves
+    pub fn load_all() -> Result<Self> {
+        Self::load_filtered(None)
+    }
+
+    fn load_filtered(chains: Option<&HashSet<&str>>) -> Result<Self> {
+        let settings: Settings = load_settings_without_arguments::<RawAgentConf, _, _>(chains)
+            .context("Failed to load the agent config")?;
+        // Metrics aren't served, but the contracts record them
+        let metrics = settings.metrics("hyperlane")?;
+        Ok(Self { settings, metrics })
+    }
+
+    /// The domain of a configured chain
+    pub fn domain(&self, chain: &str) -> Result<HyperlaneDomain> {
+        self.settings.lookup_domain(chain)
+    }
+
+    /// The configured chain with a domain id
+    pub fn domain_by_id(&self, id: u32) -> Result<HyperlaneDomain> {
+        self.settings
+            .chains
+            .values()
+            .map(|chain| &chain.domain)
+            .find(|domain| domain.id() == id)
+            .cloned()
+            .ok_or_else(|| eyre!("No chain setup found for domain {id}"))
+    }
+}
@@ -0,0 +1,303 @@
+//! Triage a message by checking each stage of its delivery in order: its
+//! dispatch, the gas paid for it, the checkpoints the validators of its ISM
+//! signed, and its delivery.
+
+use std::str::FromStr;
+
+use clap::Args;
+use eyre::{bail, eyre, Result};
+use serde_json::{json, Value};
+
+use hyperlane_base::settings::CheckpointSyncerConf;
+use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, ModuleType, H256, H512, U256};
+
+use crate::{
+    scraper::{GasPaymentTotals, ScraperDb},
+    settings::CliSettings,
+};
+
+#[derive(Args)]
+pub struct StatusArgs {
+    /// Id of the message
+    #[arg(long)]
+    message_id: String,
+    /// Name of the chain the message was dispatched on
+    #[arg(long)]
+    origin: String,
+    /// Hash of the transaction the message was dispatched in, 32 or 64
+    /// bytes long. Looked up in the scraper database if not specified.
+    #[arg(long)]
+    dispatch_tx: Option<String>,
+    /// Url of a scraper database to look up the dispatch transaction and the
+    /// gas payments of the message in
+    #[arg(long)]
+    scraper_db: Option<String>,
+    /// Environment the scraper stored the message in
+    #[arg(long, default_value = "default")]
+    scraper_environment: String,
+}
+
+/// The stages of a delivery, in order
+const STAGES: [&str; 4] = ["dispatch", "gas_payment", "checkpoints", "delivery"];
+
+pub async fn run(args: StatusArgs) -> Result<()> {
+    let message_id = parse_h256(&args.message_id)?;
+    let scraper = match &args.scraper_db {
+        Some(url) => Some(ScraperDb::connect(url, &args.scraper_environment).await?),
+        None => None,
+    };
+    let dispatch_tx: H512 = match (&args.dispatch_tx, &scraper) {
+        (Some(tx), _) => parse_h512(tx)?,
+        (None, Some(scraper)) => match scraper.dispatch_tx(message_id).await? {
+            Some(tx) => tx,
+            None => {
+                return print_report(
+                    message_id,
+                    json!({ "dispatch": { "ok": false, "reason": "Not indexed by the scraper" } }),
+                );
+            }
+        },
+        (None, None) => bail!("Either `--dispatch-tx` or `--scraper-db` is required"),
+    };
+
+    let cli = CliSettings::load_all()?;
+    let origin = cli.domain(&args.origin)?;
+    let origin_setup = cli.settings.chain_setup(&origin)?;
+
+    // Dispatch
+    let message = origin_setup
+        .build_message_indexer(&cli.metrics)
+        .await?
+        .fetch_logs_by_tx_hash(dispatch_tx)
+        .await?
+        .into_iter()
+        .map(|(message, _)| message.inner().clone())
+        .find(|message| message.id() == message_id);
+    let Some(message) = message else {
+        return print_report(
+            message_id,
+            json!({
+                "dispatch": {
+                    "ok": false,
+                    "tx": format!("{dispatch_tx:?}"),
+                    "reason": "Not dispatched in the transaction",
+                },
+            }),
+        );
+    };
+    let destination = cli.domain_by_id(message.destination)?;
+    let mut stages = json!({
+        "dispatch": {
+            "ok": true,
+            "tx": format!("{dispatch_tx:?}"),
+            "nonce": message.nonce,
+            "destination": destination.name(),
+            "sender": format!("{:?}", message.sender),
+            "recipient": format!("{:?}", message.recipient),
+        },
+    });
+
+    // Gas payment, in every indexed payment or only those in the dispatch
+    // transaction
+    let (payments, source) = match &scraper {
+        Some(scraper) => (scraper.gas_payments(message_id).await?, "scraper"),
+        None => (
+            dispatch_tx_gas_payments(&cli, &origin, dispatch_tx, &message).await?,
+            "dispatch_tx",
+        ),
+    };
+    stages["gas_payment"] = json!({
+        "ok": payments.num_payments > 0,
+        "source": source,
+        "num_payments": payments.num_payments,
+        "total_payment": payments.total_payment.to_string(),
+        "total_gas_amount": payments.total_gas_amount.to_string(),
+    });
+
+    // Checkpoints
+    stages["checkpoints"] = checkpoints(&cli, &origin, &destination, dispatch_tx, &message).await?;
+
+    // Delivery
+    let delivered = cli
+        .settings
+        .build_mailbox(&destination, &cli.metrics)
+        .await?
+        .delivered(message_id)
+        .await?;
+    stages["delivery"] = json!({ "ok": delivered });
+
+    print_report(message_id, stages)
+}
+
+async fn dispatch_tx_gas_payments(
+    cli: &CliSettings,
+    origin: &HyperlaneDomain,
+    dispatch_tx: H512,
+    message: &HyperlaneMessage,
+) -> Result<GasPaymentTotals> {
+    let payments = cli
+        .settings
+        .chain_setup(origin)?
+        .build_interchain_gas_payment_indexer(&cli.metrics)
+        .await?
+        .fetch_logs_by_tx_hash(dispatch_tx)
+        .await?;
+    let mut totals = GasPaymentTotals {
+        num_payments: 0,
+        total_payment: U256::zero(),
+        total_gas_amount: U256::zero(),
+    };
+    for (payment, _) in payments {
+        let payment = payment.inner();
+        if payment.message_id == message.id() && payment.destination == message.destination {
+            totals.num_payments += 1;
+            totals.total_payment += payment.payment;
+            totals.total_gas_amount += payment.gas_amount;
+        }
+    }
+    Ok(totals)
+}
+
+/// Whether enough of the validators of the recipient's ISM signed a
+/// checkpoint of the message. Only multisig ISMs are checked.
+async fn checkpoints(
+    cli: &CliSettings,
+    origin: &HyperlaneDomain,
+    destination: &HyperlaneDomain,
+    dispatch_tx: H512,
+    message: &HyperlaneMessage,
+) -> Result<Value> {
+    let ism_address = cli
+        .settings
+        .build_mailbox(destination, &cli.metrics)
+        .await?
+        .recipient_ism(message.recipient)
+        .await?;
+    let destination_setup = cli.settings.chain_setup(destination)?;
+    let module_type = destination_setup
+        .build_ism(ism_address, &cli.metrics)
+        .await?
+        .module_type()
+        .await?;
+    if !matches!(
+        module_type,
+        ModuleType::LegacyMultisig | ModuleType::MerkleRootMultisig | ModuleType::MessageIdMultisig
+    ) {
+        return Ok(json!({
+            "ok": true,
+            "ism": format!("{ism_address:?}"),
+            "module_type": format!("{module_type:?}"),
+            "reason": "Only multisig ISMs are checked",
+        }));
+    }
+
+    let leaf_index = cli
+        .settings
+        .chain_setup(origin)?
+        .build_merkle_tree_hook_indexer(&cli.metrics)
+        .await?
+        .fetch_logs_by_tx_hash(dispatch_tx)
+        .await?
+        .into_iter()
+        .map(|(insertion, _)| *insertion.inner())
+        .find(|insertion| insertion.message_id() == message.id())
+        .map(|insertion| insertion.index())
+        .ok_or_else(|| eyre!("The message wasn't inserted into the merkle tree hook"))?;
+
+    let (validators, threshold) = destination_setup
+        .build_multisig_ism(ism_address, &cli.metrics)
+        .await?
+        .validators_and_threshold(message)
+        .await?;
+    let storage_locations = cli
+        .settings
+        .build_validator_announce(origin, &cli.metrics)
+        .await?
+        .get_announced_storage_locations(&validators)
+        .await?;
+
+    let mut signed = 0;
+    let mut validator_statuses = vec![];
+    for (validator, locations) in validators.iter().zip(storage_locations) {
+        // The latest announced location is the one the relayer reads
+        let status = match locations.last() {
+            None => json!({ "signed": false, "reason": "No storage location announced" }),
+            Some(location) => match signed_checkpoint(location, leaf_index, message.id()).await {
+                Ok(true) => {
+                    signed += 1;
+                    json!({ "signed": true, "location": location })
+                }
+                Ok(false) => json!({ "signed": false, "location": location }),
+                Err(err) => json!({
+                    "signed": false,
+                    "location": location,
+                    "reason": err.to_string(),
+                }),
+            },
+        };
+        validator_statuses.push(json!({
+            "validator": format!("{:?}", validator),
+            "status": status,
+        }));
+    }
+
+    Ok(json!({
+        "ok": signed >= threshold as usize,
+        "ism": format!("{ism_address:?}"),
+        "module_type": format!("{module_type:?}"),
+        "leaf_index": leaf_index,
+        "threshold": threshold,
+        "signed": signed,
+        "validators": validator_statuses,
+    }))
+}
+
+/// Whether a storage location has a checkpoint of the message at its leaf
+/// index
+async fn signed_checkpoint(location: &str, leaf_index: u32, message_id: H256) -> Result<bool> {
+    let syncer = CheckpointSyncerConf::from_str(location)?
+        .build(None)
+        .await?;
+    Ok(syncer
+        .fetch_checkpoint(leaf_index)
+        .await?
+        .map_or(false, |checkpoint| {
+            checkpoint.value.message_id == message_id
+        }))
+}
+
+/// Print the stages that were checked and the first one that didn't pass,
+/// unless the message was delivered
+fn print_report(message_id: H256, stages: Value) -> Result<()> {
+    let delivered = stages["delivery"]["ok"].as_bool() == Some(true);
+    let blocking_stage = if delivered {
+        None
+    } else {
+        STAGES
+            .iter()
+            .find(|stage| stages[**stage]["ok"].as_bool() != Some(true))
+    };
+    println!(
+        "{}",
+        json!({
+            "message_id": format!("{message_id:?}"),
+            "stages": stages,
+            "blocking_stage": blocking_stage,
+        })
+    );
+    Ok(())
+}
+
+fn parse_h256(s: &str) -> Result<H256> {
+    H256::from_str(s.trim_start_matches("0x")).map_err(|e| eyre!("Invalid H256 `{s}`: {e}"))
+}
+
+/// Parse a transaction hash, padding 32 byte hashes to 64 bytes like the
+/// chains do for `TxnInfo`
+fn parse_h512(s: &str) -> Result<H512> {
+    let hex = s.trim_start_matches("0x");
+    if hex.len() == 64 {
+        return parse_h256(s).map(Into::into);
+    }
+    H512::from_str(hex).map_err(|e| eyre!("Invalid H512 `{s}`: {e}"))
+}
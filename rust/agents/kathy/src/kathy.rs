@@ -0,0 +1,288 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use derive_more::AsRef;
+use eyre::Result;
+use futures::future::try_join_all;
+use hyperlane_base::{
+    metrics::AgentMetrics, BaseAgent, ChainMetrics, CoreMetrics, HyperlaneAgentCore,
+};
+use hyperlane_core::{HyperlaneChain, InterchainGasPaymaster, Mailbox, H256};
+use prometheus::{HistogramVec, IntCounterVec};
+use tokio::{
+    task::JoinHandle,
+    time::{interval, sleep, MissedTickBehavior},
+};
+use tracing::{debug, info, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::settings::{KathyGasPayment, KathySettings};
+
+/// Buckets of the delivery latency histogram, from seconds to an hour
+const DELIVERY_LATENCY_BUCKETS: &[f64] = &[
+    5., 10., 20., 30., 45., 60., 90., 120., 180., 300., 600., 900., 1800., 3600.,
+];
+
+/// A load generator dispatching messages on configured routes at a target
+/// rate and measuring their delivery latency
+#[derive(Debug, AsRef)]
+pub struct Kathy {
+    #[as_ref]
+    core: HyperlaneAgentCore,
+    routes: Vec<Arc<RouteSender>>,
+    core_metrics: Arc<CoreMetrics>,
+}
+
+#[derive(Debug, Clone)]
+struct KathyMetrics {
+    messages_sent: IntCounterVec,
+    dispatch_failures: IntCounterVec,
+    messages_delivered: IntCounterVec,
+    messages_undelivered: IntCounterVec,
+    delivery_latency: HistogramVec,
+}
+
+impl KathyMetrics {
+    fn new(metrics: &CoreMetrics) -> Result<Self> {
+        let labels = &["origin", "destination"];
+        Ok(Self {
+            messages_sent: metrics.new_int_counter(
+                "kathy_messages_sent",
+                "Messages dispatched by kathy",
+                labels,
+            )?,
+            dispatch_failures: metrics.new_int_counter(
+                "kathy_dispatch_failures",
+                "Messages kathy failed to dispatch or pay for",
+                labels,
+            )?,
+            messages_delivered: metrics.new_int_counter(
+                "kathy_messages_delivered",
+                "Messages dispatched by kathy that were delivered",
+                labels,
+            )?,
+            messages_undelivered: metrics.new_int_counter(
+                "kathy_messages_undelivered",
+                "Messages dispatched by kathy that were not delivered within the timeout",
+                labels,
+            )?,
+            delivery_latency: metrics.new_histogram(
+                "kathy_delivery_latency_seconds",
+                "Seconds from the dispatch of a message by kathy to its delivery",
+                labels,
+                DELIVERY_LATENCY_BUCKETS.to_vec(),
+            )?,
+        })
+    }
+}
+
+/// Dispatches the messages of a single route
+#[derive(Debug)]
+struct RouteSender {
+    origin_mailbox: Arc<dyn Mailbox>,
+    destination_mailbox: Arc<dyn Mailbox>,
+    igp: Option<Arc<dyn InterchainGasPaymaster>>,
+    recipient: H256,
+    gas_payment: KathyGasPayment,
+    period: Duration,
+    min_body_size: usize,
+    max_body_size: usize,
+    delivery_timeout: Duration,
+    poll_interval: Duration,
+    metrics: KathyMetrics,
+    labels: [String; 2],
+}
+
+#[async_trait]
+impl BaseAgent for Kathy {
+    const AGENT_NAME: &'static str = "kathy";
+    type Settings = KathySettings;
+
+    async fn from_settings(
+        settings: Self::Settings,
+        metrics: Arc<CoreMetrics>,
+        _agent_metrics: AgentMetrics,
+        _chain_metrics: ChainMetrics,
+        _tokio_console_server: console_subscriber::Server,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let core = settings.build_hyperlane_core(metrics.clone());
+        let kathy_metrics = KathyMetrics::new(&metrics)?;
+        let period = Duration::from_secs(60) / settings.messages_per_minute;
+
+        let mut routes = Vec::with_capacity(settings.routes.len());
+        for route in &settings.routes {
+            let igp = match settings.gas_payment {
+                KathyGasPayment::None => None,
+                KathyGasPayment::Igp { .. } => Some(
+                    settings
+                        .build_interchain_gas_paymaster(&route.origin, &metrics)
+                        .await?
+                        .into(),
+                ),
+            };
+            routes.push(Arc::new(RouteSender {
+                origin_mailbox: settings
+                    .build_mailbox(&route.origin, &metrics)
+                    .await?
+                    .into(),
+                destination_mailbox: settings
+                    .build_mailbox(&route.destination, &metrics)
+                    .await?
+                    .into(),
+                igp,
+                recipient: route.recipient,
+                gas_payment: settings.gas_payment,
+                period,
+                min_body_size: settings.min_body_size,
+                max_body_size: settings.max_body_size,
+                delivery_timeout: settings.delivery_timeout,
+                poll_interval: settings.poll_interval,
+                metrics: kathy_metrics.clone(),
+                labels: [
+                    route.origin.name().to_owned(),
+                    route.destination.name().to_owned(),
+                ],
+            }));
+        }
+
+        Ok(Self {
+            core,
+            routes,
+            core_metrics: metrics,
+        })
+    }
+
+    #[allow(clippy::async_yields_async)]
+    async fn run(self) {
+        let mut tasks = Vec::with_capacity(self.routes.len() + 1);
+
+        let server = self
+            .core
+            .settings
+            .server(self.core_metrics.clone())
+            .expect("Failed to create server");
+        tasks.push(server.run().instrument(info_span!("Kathy server")));
+
+        for route in &self.routes {
+            tasks.push(route.clone().spawn());
+        }
+
+        if let Err(err) = try_join_all(tasks).await {
+            tracing::error!(error = ?err, "Kathy task panicked");
+        }
+    }
+}
+
+impl RouteSender {
+    fn spawn(self: Arc<Self>) -> Instrumented<JoinHandle<()>> {
+        let span =
+            info_span!("Kathy route", origin = %self.labels[0], destination = %self.labels[1]);
+        tokio::spawn(async move { self.send_forever().await }).instrument(span)
+    }
+
+    async fn send_forever(self: Arc<Self>) {
+        let mut ticker = interval(self.period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        for sequence in 0u64.. {
+            ticker.tick().await;
+            let body = self.body(sequence);
+            let dispatched_at = Instant::now();
+            match self.send(&body).await {
+                Ok(message_id) => {
+                    self.metrics
+                        .messages_sent
+                        .with_label_values(&self.label_values())
+                        .inc();
+                    let sender = self.clone();
+                    tokio::spawn(
+                        async move { sender.await_delivery(message_id, dispatched_at).await }
+                            .in_current_span(),
+                    );
+                }
+                Err(err) => {
+                    warn!(sequence, error = ?err, "Failed to send message");
+                    self.metrics
+                        .dispatch_failures
+                        .with_label_values(&self.label_values())
+                        .inc();
+                }
+            }
+        }
+    }
+
+    /// Dispatch a message and pay for it if configured to, returning its id
+    async fn send(&self, body: &[u8]) -> Result<H256> {
+        let destination = self.destination_mailbox.domain().id();
+        let (message_id, dispatch) = self
+            .origin_mailbox
+            .dispatch(destination, self.recipient, body)
+            .await?;
+        if !dispatch.executed {
+            eyre::bail!(
+                "Dispatch transaction {:?} reverted",
+                dispatch.transaction_id
+            );
+        }
+        if let (Some(igp), KathyGasPayment::Igp { gas_amount }) = (&self.igp, self.gas_payment) {
+            let payment = igp.pay_for_gas(message_id, destination, gas_amount).await?;
+            if !payment.executed {
+                eyre::bail!(
+                    "Gas payment transaction {:?} reverted",
+                    payment.transaction_id
+                );
+            }
+        }
+        info!(?message_id, body_size = body.len(), "Dispatched message");
+        Ok(message_id)
+    }
+
+    /// Poll the destination until the message is delivered or the delivery
+    /// timeout passes
+    async fn await_delivery(&self, message_id: H256, dispatched_at: Instant) {
+        while dispatched_at.elapsed() < self.delivery_timeout {
+            sleep(self.poll_interval).await;
+            match self.destination_mailbox.delivered(message_id).await {
+                Ok(true) => {
+                    let latency = dispatched_at.elapsed();
+                    debug!(?message_id, ?latency, "Message delivered");
+                    self.metrics
+                        .messages_delivered
+                        .with_label_values(&self.label_values())
+                        .inc();
+                    self.metrics
+                        .delivery_latency
+                        .with_label_values(&self.label_values())
+                        .observe(latency.as_secs_f64());
+                    return;
+                }
+                Ok(false) => {}
+                Err(err) => debug!(?message_id, error = ?err, "Failed to check delivery"),
+            }
+        }
+        warn!(?message_id, timeout = ?self.delivery_timeout, "Message was not delivered");
+        self.metrics
+            .messages_undelivered
+            .with_label_values(&self.label_values())
+            .inc();
+    }
+
+    /// The body of the message with this sequence number. Sizes are spread
+    /// evenly across the configured range, and the sequence number keeps the
+    /// bodies of a route distinct.
+    fn body(&self, sequence: u64) -> Vec<u8> {
+        let range = (self.max_body_size - self.min_body_size + 1) as u64;
+        let size = self.min_body_size + (sequence.wrapping_mul(7919) % range) as usize;
+        let mut body = format!("kathy {sequence} ").into_bytes();
+        body.resize(size, b'.');
+        body
+    }
+
+    fn label_values(&self) -> [&str; 2] {
+        [&self.labels[0], &self.labels[1]]
+    }
+}
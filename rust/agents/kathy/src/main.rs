@@ -0,0 +1,20 @@
+//! Kathy is a load generator. It continuously dispatches messages between
+//! configured pairs of chains at a target rate and measures how long they
+//! take to be delivered, so that a deployment can be canaried or load
+//! tested with realistic traffic.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use eyre::Result;
+use hyperlane_base::agent_main;
+
+use crate::kathy::Kathy;
+
+mod kathy;
+mod settings;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    agent_main::<Kathy>().await
+}
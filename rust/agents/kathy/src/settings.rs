@@ -0,0 +1,240 @@
+//! Kathy configuration.
+//!
+//! The correct settings shape is defined in the TypeScript SDK metadata. While the exact shape
+//! and validations it defines are not applied here, we should mirror them.
+//! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
+
+use std::{collections::HashSet, time::Duration};
+
+use derive_more::{AsMut, AsRef, Deref, DerefMut};
+use eyre::{eyre, Context};
+use hyperlane_base::{
+    impl_loadable_from_settings,
+    settings::{
+        parser::{RawAgentConf, ValueParser},
+        Settings,
+    },
+};
+use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, H256, U256};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Settings for `Kathy`
+#[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
+pub struct KathySettings {
+    #[as_ref]
+    #[as_mut]
+    #[deref]
+    #[deref_mut]
+    base: Settings,
+
+    /// Pairs of chains to send messages between
+    pub routes: Vec<KathyRoute>,
+    /// Messages dispatched per minute on each route
+    pub messages_per_minute: u32,
+    /// Smallest size in bytes of the body of the messages
+    pub min_body_size: usize,
+    /// Largest size in bytes of the body of the messages
+    pub max_body_size: usize,
+    /// How the dispatched messages are paid for
+    pub gas_payment: KathyGasPayment,
+    /// How long to wait for a message to be delivered before counting it as
+    /// undelivered
+    pub delivery_timeout: Duration,
+    /// How often the destination is polled for the delivery of a message
+    pub poll_interval: Duration,
+}
+
+/// A pair of chains kathy sends messages between
+#[derive(Debug, Clone)]
+pub struct KathyRoute {
+    /// The chain messages are dispatched on
+    pub origin: HyperlaneDomain,
+    /// The chain messages are delivered to
+    pub destination: HyperlaneDomain,
+    /// The recipient of the messages on the destination
+    pub recipient: H256,
+}
+
+/// How the dispatched messages are paid for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KathyGasPayment {
+    /// Messages are not paid for, e.g. to check that unpaid messages are not
+    /// relayed
+    None,
+    /// Each message is paid for on the interchain gas paymaster of its origin
+    Igp {
+        /// Destination gas to pay for
+        gas_amount: U256,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+struct RawKathySettings(Value);
+
+impl_loadable_from_settings!(Kathy, RawKathySettings -> KathySettings);
+
+impl FromRawConf<RawKathySettings> for KathySettings {
+    fn from_config_filtered(
+        raw: RawKathySettings,
+        cwp: &ConfigPath,
+        _filter: (),
+    ) -> ConfigResult<Self> {
+        let mut err = ConfigParsingError::default();
+
+        let p = ValueParser::new(cwp.clone(), &raw.0);
+
+        let raw_routes: Vec<(&str, &str, H256)> = p
+            .chain(&mut err)
+            .get_key("routes")
+            .into_array_iter()
+            .map(|routes| {
+                routes
+                    .filter_map(|route| {
+                        let origin = route.chain(&mut err).get_key("origin").parse_string().end();
+                        let destination = route
+                            .chain(&mut err)
+                            .get_key("destination")
+                            .parse_string()
+                            .end();
+                        let recipient = route
+                            .chain(&mut err)
+                            .get_key("recipient")
+                            .parse_address_hash()
+                            .end();
+                        Some((origin?, destination?, recipient?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if raw_routes.is_empty() {
+            err.push(cwp + "routes", eyre!("At least one route is required"));
+        }
+
+        let route_chain_names: HashSet<&str> = raw_routes
+            .iter()
+            .flat_map(|(origin, destination, _)| [*origin, *destination])
+            .collect();
+
+        let base = p
+            .parse_from_raw_config::<Settings, RawAgentConf, Option<&HashSet<&str>>>(
+                Some(&route_chain_names),
+                "Parsing base config",
+            )
+            .take_config_err(&mut err);
+
+        let routes = if let Some(base) = &base {
+            raw_routes
+                .into_iter()
+                .filter_map(|(origin, destination, recipient)| {
+                    let origin = base
+                        .lookup_domain(origin)
+                        .context("Missing configuration for the origin of a route")
+                        .take_err(&mut err, || cwp + "routes");
+                    let destination = base
+                        .lookup_domain(destination)
+                        .context("Missing configuration for the destination of a route")
+                        .take_err(&mut err, || cwp + "routes");
+                    Some(KathyRoute {
+                        origin: origin?,
+                        destination: destination?,
+                        recipient,
+                    })
+                })
+                .collect()
+        } else {
+            Default::default()
+        };
+
+        let messages_per_minute = p
+            .chain(&mut err)
+            .get_opt_key("messagesPerMinute")
+            .parse_u32()
+            .unwrap_or(1);
+        if messages_per_minute == 0 {
+            err.push(cwp + "messages_per_minute", eyre!("Must be larger than 0"));
+        }
+
+        let min_body_size = p
+            .chain(&mut err)
+            .get_opt_key("minBodySize")
+            .parse_u64()
+            .unwrap_or(32) as usize;
+        let max_body_size = p
+            .chain(&mut err)
+            .get_opt_key("maxBodySize")
+            .parse_u64()
+            .map(|size| size as usize)
+            .unwrap_or(min_body_size);
+        if min_body_size > max_body_size {
+            err.push(
+                cwp + "min_body_size",
+                eyre!("Must not be larger than `maxBodySize`"),
+            );
+        }
+
+        let gas_payment = p
+            .chain(&mut err)
+            .get_opt_key("gasPayment")
+            .and_then(parse_gas_payment)
+            .unwrap_or(KathyGasPayment::None);
+
+        let delivery_timeout = p
+            .chain(&mut err)
+            .get_opt_key("deliveryTimeout")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(10 * 60));
+
+        let poll_interval = p
+            .chain(&mut err)
+            .get_opt_key("pollInterval")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(5));
+
+        cfg_unwrap_all!(&p.cwp, err: [base]);
+
+        err.into_result(Self {
+            base,
+            routes,
+            messages_per_minute,
+            min_body_size,
+            max_body_size,
+            gas_payment,
+            delivery_timeout,
+            poll_interval,
+        })
+    }
+}
+
+fn parse_gas_payment(p: ValueParser) -> ConfigResult<KathyGasPayment> {
+    let mut err = ConfigParsingError::default();
+
+    let payment_type = p
+        .chain(&mut err)
+        .get_opt_key("type")
+        .parse_string()
+        .unwrap_or("none");
+
+    let payment = match payment_type {
+        "none" => Some(KathyGasPayment::None),
+        "igp" => p
+            .chain(&mut err)
+            .get_key("gasAmount")
+            .parse_u256()
+            .end()
+            .map(|gas_amount| KathyGasPayment::Igp { gas_amount }),
+        _ => {
+            err.push(
+                &p.cwp + "type",
+                eyre!("Unknown gas payment type `{payment_type}`"),
+            );
+            None
+        }
+    };
+
+    cfg_unwrap_all!(&p.cwp, err: [payment]);
+    err.into_result(payment)
+}
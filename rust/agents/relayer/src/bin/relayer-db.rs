@@ -0,0 +1,377 @@
+//! Inspect the state the relayer keeps in its local rocksdb.
+//!
+//! The db is opened read-only, so this can be run against the db of a running
+//! relayer. Every record is printed as a single line of JSON. Dead-lettered
+//! messages are replayed through the api of the running relayer, which owns
+//! the db.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use std::{path::PathBuf, str::FromStr};
+
+use clap::{Args, Parser, Subcommand};
+use ethers::utils::hex;
+use eyre::{bail, eyre, Result};
+use serde_json::{json, Value};
+
+use hyperlane_base::db::{HyperlaneRocksDB, DB};
+use hyperlane_core::{
+    DeadLetter, GasPaymentKey, HyperlaneDomain, HyperlaneDomainProtocol,
+    HyperlaneDomainTechnicalStack, HyperlaneMessage, H256,
+};
+
+#[derive(Parser)]
+#[command(about = "Inspect the local rocksdb of a relayer")]
+struct Cli {
+    /// Path to the relayer database
+    #[arg(long)]
+    db: PathBuf,
+    /// Name of the origin chain whose records to inspect
+    #[arg(long)]
+    chain: String,
+    /// Domain id of the origin chain
+    #[arg(long)]
+    domain: u32,
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand)]
+enum Cmd {
+    /// Highest indexed and processed message nonces
+    Nonces,
+    /// Indexed messages, with their processing status
+    Messages(MessageFilter),
+    /// Messages that have not been processed yet, with their retry counts
+    Pending(MessageFilter),
+    /// Gas payments and expenditures recorded for a message
+    GasPayments(GasPaymentsArgs),
+    /// Messages the relayer gave up on
+    #[command(name = "deadletter", subcommand)]
+    DeadLetter(DeadLetterCmd),
+}
+
+#[derive(Subcommand)]
+enum DeadLetterCmd {
+    /// Dead-lettered messages, with why they were dead-lettered
+    List(DeadLetterFilter),
+    /// A dead-lettered message, with its body and gas payment
+    Show(DeadLetterArgs),
+    /// Send dead-lettered messages to their destination again. They are
+    /// retried right away and may expire again from the time of the replay.
+    Replay(ReplayArgs),
+}
+
+#[derive(Args)]
+struct DeadLetterFilter {
+    #[command(flatten)]
+    messages: MessageFilter,
+    /// Also include messages that were replayed since they were dead-lettered
+    #[arg(long)]
+    include_replayed: bool,
+}
+
+#[derive(Args)]
+struct DeadLetterArgs {
+    /// Nonce of the message
+    #[arg(
+        long,
+        required_unless_present = "message_id",
+        conflicts_with = "message_id"
+    )]
+    nonce: Option<u32>,
+    /// Id of the message
+    #[arg(long)]
+    message_id: Option<String>,
+}
+
+#[derive(Args)]
+struct ReplayArgs {
+    #[command(flatten)]
+    messages: MessageFilter,
+    /// Url of the api of the running relayer
+    #[arg(long, default_value = "http://localhost:9090")]
+    relayer_url: String,
+}
+
+#[derive(Args)]
+struct MessageFilter {
+    /// Lowest nonce to include
+    #[arg(long, default_value_t = 0)]
+    from_nonce: u32,
+    /// Highest nonce to include. Defaults to the highest indexed nonce.
+    #[arg(long)]
+    to_nonce: Option<u32>,
+    /// Only include messages to this destination domain
+    #[arg(long)]
+    destination: Option<u32>,
+    /// Only include the message with this id
+    #[arg(long)]
+    message_id: Option<String>,
+}
+
+#[derive(Args)]
+struct GasPaymentsArgs {
+    /// Id of the message
+    #[arg(long)]
+    message_id: String,
+    /// Destination domain paid for. Defaults to the destination of the
+    /// indexed message.
+    #[arg(long)]
+    destination: Option<u32>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let domain = HyperlaneDomain::from_config(
+        cli.domain,
+        &cli.chain,
+        // Only the name and id are needed to locate the records of the domain
+        HyperlaneDomainProtocol::Ethereum,
+        HyperlaneDomainTechnicalStack::Other,
+    )?;
+    let db = HyperlaneRocksDB::new(&domain, DB::from_path_read_only(&cli.db)?);
+
+    match cli.cmd {
+        Cmd::Nonces => print_nonces(&db),
+        Cmd::Messages(filter) => print_messages(&db, &filter, false),
+        Cmd::Pending(filter) => print_messages(&db, &filter, true),
+        Cmd::GasPayments(args) => print_gas_payments(&db, &args),
+        Cmd::DeadLetter(DeadLetterCmd::List(filter)) => print_dead_letters(&db, &filter),
+        Cmd::DeadLetter(DeadLetterCmd::Show(args)) => print_dead_letter(&db, &args),
+        Cmd::DeadLetter(DeadLetterCmd::Replay(args)) => replay_dead_letters(&db, &args).await,
+    }
+}
+
+fn print_nonces(db: &HyperlaneRocksDB) -> Result<()> {
+    let highest_seen = db.retrieve_highest_seen_message_nonce()?;
+    let mut highest_processed = None;
+    let mut lowest_unprocessed = None;
+    for nonce in 0..=highest_seen.unwrap_or_default() {
+        if db.retrieve_processed_by_nonce(&nonce)?.unwrap_or(false) {
+            highest_processed = Some(nonce);
+        } else if lowest_unprocessed.is_none() && highest_seen.is_some() {
+            lowest_unprocessed = Some(nonce);
+        }
+    }
+    println!(
+        "{}",
+        json!({
+            "domain": db.domain().name(),
+            "highest_seen_message_nonce": highest_seen,
+            "highest_processed_message_nonce": highest_processed,
+            "lowest_unprocessed_message_nonce": lowest_unprocessed,
+        })
+    );
+    Ok(())
+}
+
+fn print_messages(
+    db: &HyperlaneRocksDB,
+    filter: &MessageFilter,
+    unprocessed_only: bool,
+) -> Result<()> {
+    for message in filtered_messages(db, filter)? {
+        let processed = db
+            .retrieve_processed_by_nonce(&message.nonce)?
+            .unwrap_or(false);
+        if unprocessed_only && processed {
+            continue;
+        }
+        println!("{}", message_record(db, &message, processed)?);
+    }
+    Ok(())
+}
+
+/// The indexed messages matching the filter, in nonce order
+fn filtered_messages(
+    db: &HyperlaneRocksDB,
+    filter: &MessageFilter,
+) -> Result<Vec<HyperlaneMessage>> {
+    let message_id = filter.message_id.as_deref().map(parse_h256).transpose()?;
+    let Some(highest_seen) = db.retrieve_highest_seen_message_nonce()? else {
+        return Ok(vec![]);
+    };
+    let to_nonce = filter.to_nonce.unwrap_or(highest_seen).min(highest_seen);
+
+    let mut messages = vec![];
+    for nonce in filter.from_nonce..=to_nonce {
+        let Some(message) = db.retrieve_message_by_nonce(nonce)? else {
+            continue;
+        };
+        if filter
+            .destination
+            .map_or(false, |d| d != message.destination)
+            || message_id.map_or(false, |id| id != message.id())
+        {
+            continue;
+        }
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+/// The matching messages that are in the dead-letter store, with their dead
+/// letter
+fn dead_lettered_messages(
+    db: &HyperlaneRocksDB,
+    filter: &MessageFilter,
+    include_replayed: bool,
+) -> Result<Vec<(HyperlaneMessage, DeadLetter)>> {
+    let mut dead_lettered = vec![];
+    for message in filtered_messages(db, filter)? {
+        let dead_letter = if include_replayed {
+            db.retrieve_dead_letter_by_nonce(&message.nonce)?
+        } else {
+            db.retrieve_unreplayed_dead_letter_by_nonce(message.nonce)?
+        };
+        if let Some(dead_letter) = dead_letter {
+            dead_lettered.push((message, dead_letter));
+        }
+    }
+    Ok(dead_lettered)
+}
+
+fn print_dead_letters(db: &HyperlaneRocksDB, filter: &DeadLetterFilter) -> Result<()> {
+    for (message, dead_letter) in
+        dead_lettered_messages(db, &filter.messages, filter.include_replayed)?
+    {
+        println!("{}", dead_letter_record(db, &message, &dead_letter)?);
+    }
+    Ok(())
+}
+
+fn print_dead_letter(db: &HyperlaneRocksDB, args: &DeadLetterArgs) -> Result<()> {
+    let message = match (args.nonce, args.message_id.as_deref()) {
+        (Some(nonce), _) => db.retrieve_message_by_nonce(nonce)?,
+        (None, Some(message_id)) => db.retrieve_message_by_id(&parse_h256(message_id)?)?,
+        (None, None) => unreachable!("clap requires a nonce or a message id"),
+    }
+    .ok_or_else(|| eyre!("Message is not indexed"))?;
+    let dead_letter = db
+        .retrieve_dead_letter_by_nonce(&message.nonce)?
+        .ok_or_else(|| eyre!("Message {:?} was never dead-lettered", message.id()))?;
+
+    let mut record = dead_letter_record(db, &message, &dead_letter)?;
+    let payment = db.retrieve_gas_payment_by_gas_payment_key(GasPaymentKey {
+        message_id: message.id(),
+        destination: message.destination,
+    })?;
+    let expenditure = db.retrieve_gas_expenditure_by_message_id(message.id())?;
+    record["body"] = json!(format!("0x{}", hex::encode(&message.body)));
+    record["payment"] = json!(payment.payment.to_string());
+    record["gas_amount"] = json!(payment.gas_amount.to_string());
+    record["tokens_used"] = json!(expenditure.tokens_used.to_string());
+    println!("{record}");
+    Ok(())
+}
+
+fn dead_letter_record(
+    db: &HyperlaneRocksDB,
+    message: &HyperlaneMessage,
+    dead_letter: &DeadLetter,
+) -> Result<Value> {
+    let processed = db
+        .retrieve_processed_by_nonce(&message.nonce)?
+        .unwrap_or(false);
+    let mut record = message_record(db, message, processed)?;
+    record["reason"] = json!(dead_letter.reason);
+    record["dead_lettered_at"] = json!(dead_letter.dead_lettered_at);
+    record["replayed_at"] = json!(db.retrieve_dead_letter_replayed_at_by_nonce(&message.nonce)?);
+    Ok(record)
+}
+
+/// Ask the running relayer to replay the matching dead-lettered messages. The
+/// db is read-only here, so the relayer records the replays itself.
+async fn replay_dead_letters(db: &HyperlaneRocksDB, args: &ReplayArgs) -> Result<()> {
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/dead_letter_replay",
+        args.relayer_url.trim_end_matches('/')
+    );
+    for (message, dead_letter) in dead_lettered_messages(db, &args.messages, false)? {
+        let response = client
+            .get(&url)
+            .query(&[("origin_domain", message.origin), ("nonce", message.nonce)])
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            bail!(
+                "Relayer failed to replay message {:?}: {status} {text}",
+                message.id()
+            );
+        }
+        println!(
+            "{}",
+            json!({
+                "id": format!("{:?}", message.id()),
+                "nonce": message.nonce,
+                "destination": message.destination,
+                "reason": dead_letter.reason,
+                "replay": "queued",
+            })
+        );
+    }
+    Ok(())
+}
+
+fn message_record(
+    db: &HyperlaneRocksDB,
+    message: &HyperlaneMessage,
+    processed: bool,
+) -> Result<Value> {
+    let id = message.id();
+    Ok(json!({
+        "id": format!("{id:?}"),
+        "nonce": message.nonce,
+        "origin": message.origin,
+        "destination": message.destination,
+        "sender": format!("{:?}", message.sender),
+        "recipient": format!("{:?}", message.recipient),
+        "dispatched_block_number": db.retrieve_dispatched_block_number_by_nonce(&message.nonce)?,
+        "processed": processed,
+        "operation_id": db.retrieve_operation_id_by_message_id(&id)?,
+        "retry_count": db.retrieve_pending_message_retry_count_by_message_id(&id)?,
+        "deadline_missed": db.retrieve_deadline_missed_by_message_id(&id)?,
+        "merkle_leaf_index": db.retrieve_merkle_leaf_index_by_message_id(&id)?,
+    }))
+}
+
+fn print_gas_payments(db: &HyperlaneRocksDB, args: &GasPaymentsArgs) -> Result<()> {
+    let message_id = parse_h256(&args.message_id)?;
+    let destination = match args.destination {
+        Some(destination) => destination,
+        None => {
+            db.retrieve_message_by_id(&message_id)?
+                .ok_or_else(|| {
+                    eyre!("Message {message_id:?} is not indexed, specify `--destination`")
+                })?
+                .destination
+        }
+    };
+    let payment = db.retrieve_gas_payment_by_gas_payment_key(GasPaymentKey {
+        message_id,
+        destination,
+    })?;
+    let expenditure = db.retrieve_gas_expenditure_by_message_id(message_id)?;
+    println!(
+        "{}",
+        json!({
+            "message_id": format!("{message_id:?}"),
+            "destination": destination,
+            "payment": payment.payment.to_string(),
+            "gas_amount": payment.gas_amount.to_string(),
+            "tokens_used": expenditure.tokens_used.to_string(),
+            "gas_used": expenditure.gas_used.to_string(),
+        })
+    );
+    Ok(())
+}
+
+fn parse_h256(s: &str) -> Result<H256> {
+    H256::from_str(s.trim_start_matches("0x")).map_err(|e| eyre!("Invalid H256 `{s}`: {e}"))
+}
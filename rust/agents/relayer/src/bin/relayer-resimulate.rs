@@ -0,0 +1,28 @@
+//! Re-simulate the delivery of indexed messages at a past block of their
+//! destination, with the metadata builders of this relayer, to find out
+//! whether it would have delivered them then.
+//!
+//! Chains are configured like they are for the relayer, with the config files
+//! in `./config`, `CONFIG_FILES` and `HYP_` environment variables. The db is
+//! opened read-only, so this can be run against the db of a running relayer.
+//! The result of each message is printed as a single line of JSON.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use clap::Parser;
+use eyre::Result;
+
+use relayer::{resimulate, ResimulateArgs};
+
+#[derive(Parser)]
+#[command(about = "Re-simulate the delivery of messages at a past block")]
+struct Cli {
+    #[command(flatten)]
+    args: ResimulateArgs,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    resimulate(Cli::parse().args).await
+}
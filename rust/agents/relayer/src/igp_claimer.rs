@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use eyre::{eyre, Result};
+use hyperlane_base::CoreMetrics;
+use hyperlane_core::{
+    metrics::agent::u256_as_scaled_f64, HyperlaneDomain, HyperlaneDomainProtocol,
+    InterchainGasPaymaster, H160, U256,
+};
+use prometheus::{CounterVec, GaugeVec};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::settings::IgpClaimConf;
+
+/// Metrics for claiming interchain gas paymaster fees
+#[derive(Clone, Debug)]
+pub struct IgpClaimerMetrics {
+    /// Fees held by the paymaster, in the native token.
+    claimable_balance: GaugeVec,
+    /// Total fees claimed from the paymaster, in the native token.
+    claimed_amount: CounterVec,
+}
+
+impl IgpClaimerMetrics {
+    pub fn new(metrics: &CoreMetrics) -> Result<Self> {
+        Ok(Self {
+            claimable_balance: metrics.new_gauge(
+                "igp_claimable_balance",
+                "Fees held by the interchain gas paymaster, in the native token",
+                &["chain"],
+            )?,
+            claimed_amount: metrics.new_counter(
+                "igp_claimed_amount",
+                "Total fees claimed from the interchain gas paymaster, in the native token",
+                &["chain"],
+            )?,
+        })
+    }
+}
+
+/// Periodically sweeps the fees held by an origin chain's interchain gas
+/// paymaster to its beneficiary once they exceed a threshold.
+#[derive(Debug)]
+pub struct IgpClaimer {
+    paymaster: Box<dyn InterchainGasPaymaster>,
+    conf: IgpClaimConf,
+    interval: Duration,
+    metrics: IgpClaimerMetrics,
+}
+
+impl IgpClaimer {
+    pub fn new(
+        paymaster: Box<dyn InterchainGasPaymaster>,
+        conf: IgpClaimConf,
+        interval: Duration,
+        metrics: IgpClaimerMetrics,
+    ) -> Self {
+        Self {
+            paymaster,
+            conf,
+            interval,
+            metrics,
+        }
+    }
+
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("IgpClaimer", chain = self.domain().name());
+        tokio::spawn(async move { self.run().await }).instrument(span)
+    }
+
+    async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.claim_if_above_threshold().await {
+                warn!(?err, "Failed to claim interchain gas paymaster fees");
+            }
+        }
+    }
+
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.conf.domain
+    }
+
+    async fn claim_if_above_threshold(&self) -> Result<()> {
+        let chain = self.domain().name();
+        let protocol = self.domain().domain_protocol();
+        if protocol != HyperlaneDomainProtocol::Ethereum {
+            return Err(eyre!("Claiming fees is only supported on EVM chains"));
+        }
+
+        let paymaster_address = format!("{:?}", H160::from(self.paymaster.address()));
+        let balance = self
+            .paymaster
+            .provider()
+            .get_balance(paymaster_address)
+            .await?;
+        self.metrics
+            .claimable_balance
+            .with_label_values(&[chain])
+            .set(u256_as_scaled_f64(balance, protocol));
+
+        if balance <= self.conf.threshold {
+            return Ok(());
+        }
+
+        let beneficiary = self.paymaster.beneficiary().await?;
+        if let Some(expected) = self.conf.beneficiary {
+            if beneficiary != expected {
+                return Err(eyre!(
+                    "Paymaster beneficiary {beneficiary:?} does not match the configured beneficiary {expected:?}, not claiming"
+                ));
+            }
+        }
+
+        info!(
+            ?balance,
+            ?beneficiary,
+            "Claiming interchain gas paymaster fees"
+        );
+        let outcome = self.paymaster.claim().await?;
+        if !outcome.executed {
+            return Err(eyre!(
+                "Claim transaction {:?} reverted",
+                outcome.transaction_id
+            ));
+        }
+        self.record_claim(balance);
+        info!(?outcome, "Claimed interchain gas paymaster fees");
+        Ok(())
+    }
+
+    fn record_claim(&self, amount: U256) {
+        self.metrics
+            .claimed_amount
+            .with_label_values(&[self.domain().name()])
+            .inc_by(u256_as_scaled_f64(amount, self.domain().domain_protocol()));
+    }
+}
@@ -1,10 +1,13 @@
+mod igp_claimer;
 mod merkle_tree;
+mod missed_deliveries;
 mod msg;
 mod processor;
-mod prover;
 mod relayer;
+mod resimulate;
 mod server;
 mod settings;
 
 pub use msg::GAS_EXPENDITURE_LOG_MESSAGE;
 pub use relayer::*;
+pub use resimulate::{resimulate, ResimulateArgs};
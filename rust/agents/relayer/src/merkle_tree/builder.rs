@@ -5,12 +5,14 @@ use tracing::{debug, error, instrument};
 
 use hyperlane_base::db::DbError;
 use hyperlane_core::{
-    accumulator::{incremental::IncrementalMerkle, merkle::Proof},
+    accumulator::{
+        incremental::IncrementalMerkle,
+        merkle::Proof,
+        prover::{Prover, ProverError},
+    },
     ChainCommunicationError, H256,
 };
 
-use crate::prover::{Prover, ProverError};
-
 /// Struct to sync prover.
 #[derive(Debug)]
 pub struct MerkleTreeBuilder {
@@ -77,7 +79,7 @@ impl MerkleTreeBuilder {
         root_index: u32,
     ) -> Result<Proof, MerkleTreeBuilderError> {
         self.prover
-            .prove_against_previous(leaf_index as usize, root_index as usize)
+            .prove(leaf_index as usize, root_index as usize)
             .map_err(MerkleTreeBuilderError::from)
     }
 
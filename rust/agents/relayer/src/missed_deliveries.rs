@@ -0,0 +1,392 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use ethers::utils::hex;
+use eyre::{eyre, Result};
+use hyperlane_base::{db::HyperlaneRocksDB, CoreMetrics};
+use hyperlane_core::{Decode, HyperlaneMessage, IndexMode, SequenceAwareIndexer, H256};
+use prometheus::IntCounterVec;
+use serde::Deserialize;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{debug, info, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::settings::MissedDeliveryFeedConf;
+
+/// Most messages requested from the scraper at a time
+const PAGE_SIZE: u32 = 1000;
+
+/// A message the scraper has no delivery of, as served by its API
+#[derive(Debug, Deserialize)]
+struct UndeliveredMessage {
+    id: H256,
+    nonce: u32,
+    /// The encoded message, hex encoded
+    message: String,
+    block_number: u64,
+}
+
+/// An origin whose undelivered messages are looked for
+#[derive(Debug)]
+pub struct MissedDeliveryOrigin {
+    /// The database messages of the origin are stored in
+    pub db: HyperlaneRocksDB,
+    /// Indexer of the messages dispatched by the origin mailbox, which the
+    /// messages of the feed are checked against
+    pub indexer: Arc<dyn SequenceAwareIndexer<HyperlaneMessage>>,
+}
+
+/// Periodically asks a scraper for the messages that were dispatched a while
+/// ago but never delivered, and stores the ones the relayer's own indexing
+/// missed so the message processors relay them like any other.
+///
+/// Stored messages are never overwritten, so a message from the feed is only
+/// stored once the origin mailbox is found to have dispatched it.
+#[derive(Debug)]
+pub struct MissedDeliveryFeed {
+    conf: MissedDeliveryFeedConf,
+    client: reqwest::Client,
+    /// The origins, by domain id
+    origins: HashMap<u32, MissedDeliveryOrigin>,
+    /// Domain ids of the chains messages are relayed to
+    destinations: HashSet<u32>,
+    /// Missed messages found in the feed, by origin
+    messages_found: IntCounterVec,
+    /// Messages of the feed the origin mailbox didn't dispatch, by origin
+    messages_rejected: IntCounterVec,
+}
+
+impl MissedDeliveryFeed {
+    pub fn new(
+        conf: MissedDeliveryFeedConf,
+        origins: HashMap<u32, MissedDeliveryOrigin>,
+        destinations: HashSet<u32>,
+        metrics: &CoreMetrics,
+    ) -> Result<Self> {
+        Ok(Self {
+            conf,
+            client: reqwest::Client::new(),
+            origins,
+            destinations,
+            messages_found: metrics.new_int_counter(
+                "missed_deliveries_found",
+                "Messages the relayer's indexing missed that were found in the scraper's feed of undelivered messages",
+                &["origin"],
+            )?,
+            messages_rejected: metrics.new_int_counter(
+                "missed_deliveries_rejected",
+                "Messages in the scraper's feed of undelivered messages that the origin mailbox didn't dispatch",
+                &["origin"],
+            )?,
+        })
+    }
+
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("MissedDeliveryFeed", url = %self.conf.url);
+        tokio::spawn(async move { self.run().await }).instrument(span)
+    }
+
+    async fn run(self) {
+        let mut interval = tokio::time::interval(self.conf.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            for (&domain, origin) in &self.origins {
+                if let Err(err) = self.store_missed_messages(domain, origin).await {
+                    warn!(?err, origin = domain, "Failed to get undelivered messages");
+                }
+            }
+        }
+    }
+
+    /// Page through the undelivered messages of an origin, storing those
+    /// that aren't stored yet
+    async fn store_missed_messages(
+        &self,
+        domain: u32,
+        origin: &MissedDeliveryOrigin,
+    ) -> Result<()> {
+        let mut from_nonce = 0;
+        loop {
+            let page = self.undelivered_messages(domain, from_nonce).await?;
+            let Some(last) = page.last() else {
+                return Ok(());
+            };
+            from_nonce = last.nonce + 1;
+            let page_len = page.len();
+            for undelivered in page {
+                self.store_missed_message(domain, origin, &undelivered)
+                    .await?;
+            }
+            if page_len < PAGE_SIZE as usize {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Store a message of the feed if it isn't stored yet and the origin
+    /// mailbox dispatched it. Returns whether it was stored.
+    async fn store_missed_message(
+        &self,
+        domain: u32,
+        origin: &MissedDeliveryOrigin,
+        undelivered: &UndeliveredMessage,
+    ) -> Result<bool> {
+        let message = decode_message(undelivered)?;
+        if message.origin != domain || !self.destinations.contains(&message.destination) {
+            return Ok(false);
+        }
+        if origin
+            .db
+            .retrieve_message_id_by_nonce(&message.nonce)?
+            .is_some()
+        {
+            return Ok(false);
+        }
+        if !was_dispatched(origin, &message, undelivered.block_number).await? {
+            warn!(
+                ?message,
+                block_number = undelivered.block_number,
+                "Undelivered message was not dispatched by the origin mailbox, ignoring it"
+            );
+            self.messages_rejected
+                .with_label_values(&[origin.db.domain().name()])
+                .inc();
+            return Ok(false);
+        }
+        if !origin
+            .db
+            .store_message(&message, undelivered.block_number)?
+        {
+            return Ok(false);
+        }
+        info!(?message, "Relaying undelivered message missed by indexing");
+        self.messages_found
+            .with_label_values(&[origin.db.domain().name()])
+            .inc();
+        Ok(true)
+    }
+
+    async fn undelivered_messages(
+        &self,
+        origin: u32,
+        from_nonce: u32,
+    ) -> Result<Vec<UndeliveredMessage>> {
+        let mut request = self
+            .client
+            .get(format!("{}/undelivered", self.conf.url))
+            .query(&[
+                ("older_than", self.conf.min_age.as_secs()),
+                ("origin", origin as u64),
+                ("from_nonce", from_nonce as u64),
+                ("limit", PAGE_SIZE as u64),
+            ]);
+        if let Some(api_key) = &self.conf.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let page: Vec<UndeliveredMessage> =
+            request.send().await?.error_for_status()?.json().await?;
+        debug!(
+            origin,
+            from_nonce,
+            count = page.len(),
+            "Got undelivered messages"
+        );
+        Ok(page)
+    }
+}
+
+/// Whether the origin mailbox dispatched `message` in block `block_number`
+async fn was_dispatched(
+    origin: &MissedDeliveryOrigin,
+    message: &HyperlaneMessage,
+    block_number: u64,
+) -> Result<bool> {
+    // Indexers of sequence indexed chains take ranges of nonces
+    let range = match origin.db.domain().index_mode() {
+        IndexMode::Block => {
+            let block: u32 = block_number.try_into()?;
+            block..=block
+        }
+        IndexMode::Sequence => message.nonce..=message.nonce,
+    };
+    let dispatched = origin.indexer.fetch_logs_in_range(range).await?;
+    Ok(dispatched.iter().any(|(dispatched, meta)| {
+        dispatched.inner() == message && meta.block_number == block_number
+    }))
+}
+
+/// Decode a message from the feed, checking it is the one its id is of
+fn decode_message(undelivered: &UndeliveredMessage) -> Result<HyperlaneMessage> {
+    let raw = hex::decode(undelivered.message.trim_start_matches("0x"))?;
+    let message = HyperlaneMessage::read_from(&mut raw.as_slice())?;
+    if message.id() != undelivered.id || message.nonce != undelivered.nonce {
+        return Err(eyre!(
+            "Undelivered message {:?} does not match its encoding",
+            undelivered.id
+        ));
+    }
+    Ok(message)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{ops::RangeInclusive, time::Duration};
+
+    use async_trait::async_trait;
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{
+        utils::bytes_to_hex, ChainResult, HyperlaneDomain, Indexed, Indexer, KnownHyperlaneDomain,
+        LogMeta, RawHyperlaneMessage,
+    };
+    use prometheus::Registry;
+
+    use super::*;
+
+    /// An origin mailbox that dispatched the given messages, indexed by
+    /// block or by nonce
+    #[derive(Debug)]
+    struct DispatchedMessages(Vec<(HyperlaneMessage, u64)>, IndexMode);
+
+    #[async_trait]
+    impl Indexer<HyperlaneMessage> for DispatchedMessages {
+        async fn fetch_logs_in_range(
+            &self,
+            range: RangeInclusive<u32>,
+        ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|(message, block)| match self.1 {
+                    IndexMode::Block => range.contains(&(*block as u32)),
+                    IndexMode::Sequence => range.contains(&message.nonce),
+                })
+                .map(|(message, block)| {
+                    (
+                        message.clone().into(),
+                        LogMeta {
+                            block_number: *block,
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect())
+        }
+
+        async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+            Ok(u32::MAX)
+        }
+    }
+
+    #[async_trait]
+    impl SequenceAwareIndexer<HyperlaneMessage> for DispatchedMessages {
+        async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+            Ok((Some(self.0.len() as u32), u32::MAX))
+        }
+    }
+
+    fn undelivered(message: &HyperlaneMessage, block_number: u64) -> UndeliveredMessage {
+        UndeliveredMessage {
+            id: message.id(),
+            nonce: message.nonce,
+            message: bytes_to_hex(&RawHyperlaneMessage::from(message)),
+            block_number,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_stores_dispatched_messages() {
+        test_only_stores_dispatched_messages_of(KnownHyperlaneDomain::Test1).await;
+    }
+
+    #[tokio::test]
+    async fn test_only_stores_dispatched_messages_of_sequence_indexed_origin() {
+        test_only_stores_dispatched_messages_of(KnownHyperlaneDomain::SealevelTest1).await;
+    }
+
+    async fn test_only_stores_dispatched_messages_of(domain: KnownHyperlaneDomain) {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::Known(domain);
+            let dispatched = HyperlaneMessage {
+                nonce: 0,
+                origin: domain.id(),
+                destination: 2,
+                ..Default::default()
+            };
+            let forged = HyperlaneMessage {
+                nonce: 1,
+                body: vec![1],
+                ..dispatched.clone()
+            };
+            let origin = MissedDeliveryOrigin {
+                db: HyperlaneRocksDB::new(&domain, db),
+                indexer: Arc::new(DispatchedMessages(
+                    vec![(dispatched.clone(), 100)],
+                    domain.index_mode(),
+                )),
+            };
+            let metrics = CoreMetrics::new("test", 9090, Registry::new()).unwrap();
+            let feed = MissedDeliveryFeed::new(
+                MissedDeliveryFeedConf {
+                    url: "http://localhost".to_owned(),
+                    api_key: None,
+                    min_age: Duration::from_secs(3600),
+                    interval: Duration::from_secs(60),
+                },
+                HashMap::new(),
+                HashSet::from([2]),
+                &metrics,
+            )
+            .unwrap();
+
+            // Not dispatched by the origin mailbox
+            assert!(!feed
+                .store_missed_message(domain.id(), &origin, &undelivered(&forged, 100))
+                .await
+                .unwrap());
+            // Dispatched, but not in the block the feed says
+            assert!(!feed
+                .store_missed_message(domain.id(), &origin, &undelivered(&dispatched, 101))
+                .await
+                .unwrap());
+            assert_eq!(origin.db.retrieve_message_by_nonce(0).unwrap(), None);
+
+            assert!(feed
+                .store_missed_message(domain.id(), &origin, &undelivered(&dispatched, 100))
+                .await
+                .unwrap());
+            assert_eq!(
+                origin.db.retrieve_message_by_nonce(0).unwrap(),
+                Some(dispatched.clone())
+            );
+            // Already stored
+            assert!(!feed
+                .store_missed_message(domain.id(), &origin, &undelivered(&dispatched, 100))
+                .await
+                .unwrap());
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_decode_message_checks_id() {
+        let message = HyperlaneMessage {
+            nonce: 7,
+            origin: 1,
+            destination: 2,
+            body: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let mut undelivered = UndeliveredMessage {
+            id: message.id(),
+            nonce: 7,
+            message: bytes_to_hex(&RawHyperlaneMessage::from(&message)),
+            block_number: 100,
+        };
+        assert_eq!(decode_message(&undelivered).unwrap(), message);
+
+        undelivered.id = H256::zero();
+        assert!(decode_message(&undelivered).is_err());
+    }
+}
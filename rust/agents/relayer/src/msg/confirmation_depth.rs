@@ -0,0 +1,85 @@
+use derive_new::new;
+use hyperlane_core::{HyperlaneMessage, U256};
+
+use crate::settings::{ConfirmationDepth, ConfirmationDepthConf};
+
+/// Decides how many confirmations the delivery of a message to a destination
+/// waits for before it is complete, e.g. so high-value warp transfers wait for
+/// finality while chatty apps are done once included.
+#[derive(Debug, Default, new)]
+pub struct ConfirmationDepths {
+    /// Confirmation depth per app. If a message matches multiple apps,
+    /// whichever is first in the list is used.
+    confs: Vec<ConfirmationDepthConf>,
+    /// Reorg period of the destination, which finalized deliveries wait for
+    reorg_period: u32,
+}
+
+impl ConfirmationDepths {
+    /// Returns the confirmations of the first app the message matches, or
+    /// None if its delivery is complete as soon as it is seen.
+    pub fn confirmations(&self, message: &HyperlaneMessage) -> Option<u64> {
+        let conf = self.confs.iter().find(|conf| {
+            conf.matching_list.msg_matches(message, true)
+                && conf
+                    .min_transfer_amount
+                    .map_or(true, |min| transfer_amount(message) >= Some(min))
+        })?;
+        Some(match conf.depth {
+            ConfirmationDepth::Blocks(blocks) => blocks as u64,
+            ConfirmationDepth::Finalized => self.reorg_period as u64 + 1,
+        })
+    }
+}
+
+/// The amount of a warp transfer, whose body starts with the recipient and
+/// the amount as 32 bytes each. None if the body is too short to be one.
+fn transfer_amount(message: &HyperlaneMessage) -> Option<U256> {
+    message.body.get(32..64).map(U256::from_big_endian)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn transfer(destination: u32, amount: u64) -> HyperlaneMessage {
+        let mut body = vec![0u8; 32];
+        body.extend_from_slice(&[0u8; 24]);
+        body.extend_from_slice(&amount.to_be_bytes());
+        HyperlaneMessage {
+            destination,
+            body,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_confirmations_uses_first_matching_app() {
+        let depths = ConfirmationDepths::new(
+            vec![
+                ConfirmationDepthConf {
+                    matching_list: Default::default(),
+                    min_transfer_amount: Some(U256::from(1000)),
+                    depth: ConfirmationDepth::Finalized,
+                },
+                ConfirmationDepthConf {
+                    matching_list: serde_json::from_str(r#"[{"destinationdomain": 2}]"#).unwrap(),
+                    min_transfer_amount: None,
+                    depth: ConfirmationDepth::Blocks(3),
+                },
+            ],
+            20,
+        );
+
+        assert_eq!(depths.confirmations(&transfer(2, 1000)), Some(21));
+        assert_eq!(depths.confirmations(&transfer(2, 999)), Some(3));
+        assert_eq!(depths.confirmations(&transfer(3, 999)), None);
+        // Messages too short to be transfers have no amount
+        let message = HyperlaneMessage {
+            destination: 3,
+            ..Default::default()
+        };
+        assert_eq!(depths.confirmations(&message), None);
+        assert_eq!(ConfirmationDepths::default().confirmations(&message), None);
+    }
+}
@@ -0,0 +1,116 @@
+use derive_new::new;
+use hyperlane_core::HyperlaneMessage;
+
+use crate::settings::{first_matching_app, DeliveryDeadlineConf};
+
+/// The delivery deadlines of the messages of apps. Messages nearing theirs
+/// are retried sooner and submitted with higher fees.
+#[derive(Debug, Default, new)]
+pub struct DeliveryDeadlines {
+    /// Deadline per app. If a message matches multiple apps, whichever is
+    /// first in the list is used.
+    confs: Vec<DeliveryDeadlineConf>,
+}
+
+impl DeliveryDeadlines {
+    /// Whether the message has a deadline, without requiring when it was
+    /// dispatched
+    pub fn has_deadline(&self, message: &HyperlaneMessage) -> bool {
+        self.conf(message).is_some()
+    }
+
+    /// The deadline of a message dispatched at `dispatched_at`, a unix
+    /// timestamp in seconds, or None if the message has none.
+    pub fn status(&self, message: &HyperlaneMessage, dispatched_at: u64) -> Option<DeadlineStatus> {
+        self.conf(message).map(|conf| {
+            let deadline = conf.deadline.as_secs();
+            DeadlineStatus {
+                // Escalate over the second half of the time the message has
+                escalates_at: dispatched_at.saturating_add(deadline / 2),
+                deadline: dispatched_at.saturating_add(deadline),
+                max_fee_bump_percent: conf.max_fee_bump_percent,
+            }
+        })
+    }
+
+    fn conf(&self, message: &HyperlaneMessage) -> Option<&DeliveryDeadlineConf> {
+        first_matching_app(&self.confs, message)
+    }
+}
+
+/// How close a message is to its delivery deadline. Timestamps are unix
+/// timestamps in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineStatus {
+    /// From when the message is retried sooner and its fees are bumped
+    pub escalates_at: u64,
+    /// When the message should have been delivered by
+    pub deadline: u64,
+    /// Percentage by which fees are bumped at the deadline
+    pub max_fee_bump_percent: u64,
+}
+
+impl DeadlineStatus {
+    /// Whether the message nears its deadline, or missed it
+    pub fn is_escalated(&self, now: u64) -> bool {
+        now >= self.escalates_at
+    }
+
+    /// Whether the deadline passed
+    pub fn is_missed(&self, now: u64) -> bool {
+        now > self.deadline
+    }
+
+    /// Percentage by which to bump the fees of the message, growing linearly
+    /// from 0 once escalated up to the maximum at the deadline
+    pub fn fee_bump_percent(&self, now: u64) -> u64 {
+        if !self.is_escalated(now) {
+            return 0;
+        }
+        let window = self.deadline.saturating_sub(self.escalates_at);
+        if window == 0 || now >= self.deadline {
+            return self.max_fee_bump_percent;
+        }
+        self.max_fee_bump_percent * (now - self.escalates_at) / window
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_fees_are_bumped_as_deadline_nears() {
+        let deadlines = DeliveryDeadlines::new(vec![DeliveryDeadlineConf {
+            matching_list: serde_json::from_str(r#"[{"destinationdomain": 2}]"#).unwrap(),
+            deadline: Duration::from_secs(600),
+            max_fee_bump_percent: 50,
+        }]);
+        let message = HyperlaneMessage {
+            destination: 2,
+            ..Default::default()
+        };
+        let other = HyperlaneMessage {
+            destination: 3,
+            ..Default::default()
+        };
+
+        assert!(!deadlines.has_deadline(&other));
+        assert_eq!(deadlines.status(&other, 1_000), None);
+        let status = deadlines.status(&message, 1_000).unwrap();
+        assert_eq!(status.escalates_at, 1_300);
+        assert_eq!(status.deadline, 1_600);
+
+        assert!(!status.is_escalated(1_299));
+        assert_eq!(status.fee_bump_percent(1_299), 0);
+        assert!(status.is_escalated(1_300));
+        assert_eq!(status.fee_bump_percent(1_300), 0);
+        assert_eq!(status.fee_bump_percent(1_450), 25);
+        assert!(!status.is_missed(1_600));
+        assert_eq!(status.fee_bump_percent(1_600), 50);
+        assert!(status.is_missed(1_601));
+        assert_eq!(status.fee_bump_percent(5_000), 50);
+    }
+}
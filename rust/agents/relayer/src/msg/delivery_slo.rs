@@ -0,0 +1,244 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use eyre::Result;
+use hyperlane_base::CoreMetrics;
+use hyperlane_core::HyperlaneDomain;
+use prometheus::GaugeVec;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info_span, instrument::Instrumented, Instrument};
+
+/// Quantiles of the delivery time exported per lane
+const QUANTILES: [(f64, &str); 2] = [(0.5, "0.5"), (0.95, "0.95")];
+
+/// How often deliveries that left the window are evicted from the metrics of
+/// lanes that have no new deliveries
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Delivery time SLO metrics of each lane, precomputed from the relayer's own
+/// timestamps so dashboards and recording rules can use them as is
+#[derive(Clone, Debug)]
+pub struct DeliverySloMetrics {
+    /// Quantiles of the time from a message being dispatched to its delivery
+    /// being confirmed, over the SLO window
+    latency: GaugeVec,
+    /// Share of the deliveries in the SLO window that took at most the
+    /// target time
+    within_target: GaugeVec,
+}
+
+impl DeliverySloMetrics {
+    pub fn new(metrics: &CoreMetrics) -> Result<Self> {
+        Ok(Self {
+            latency: metrics.new_gauge(
+                "lane_delivery_latency_seconds",
+                "Quantiles of the seconds from a message being dispatched to its delivery being confirmed, over the SLO window",
+                &["origin", "destination", "quantile"],
+            )?,
+            within_target: metrics.new_gauge(
+                "lane_delivered_within_target_ratio",
+                "Share of the deliveries in the SLO window that took at most the target time",
+                &["origin", "destination"],
+            )?,
+        })
+    }
+}
+
+/// Delivery times of the recent messages of a lane, from which its SLO
+/// metrics are computed. Only kept in memory, so a restart starts a new
+/// window.
+#[derive(Debug)]
+pub struct DeliverySlo {
+    origin: String,
+    destination: String,
+    target: Duration,
+    window: Duration,
+    /// When each delivery was confirmed, and how long it took
+    deliveries: Mutex<VecDeque<(Instant, Duration)>>,
+    metrics: DeliverySloMetrics,
+}
+
+impl DeliverySlo {
+    pub fn new(
+        metrics: DeliverySloMetrics,
+        origin: &HyperlaneDomain,
+        destination: &HyperlaneDomain,
+        target: Duration,
+        window: Duration,
+    ) -> Self {
+        Self {
+            origin: origin.name().to_owned(),
+            destination: destination.name().to_owned(),
+            target,
+            window,
+            deliveries: Mutex::new(VecDeque::new()),
+            metrics,
+        }
+    }
+
+    /// Record how long the delivery of a message took, and update the
+    /// metrics of the lane with the deliveries in the window
+    pub fn record_delivery(&self, latency: Duration) {
+        let now = Instant::now();
+        let mut deliveries = self.deliveries.lock().unwrap();
+        deliveries.push_back((now, latency));
+        self.update_metrics(deliveries, now);
+    }
+
+    /// Drop the deliveries that left the window from the metrics of the
+    /// lane, which otherwise only happens when a delivery is recorded
+    pub fn evict_expired(&self) {
+        self.update_metrics(self.deliveries.lock().unwrap(), Instant::now());
+    }
+
+    fn update_metrics(
+        &self,
+        mut deliveries: MutexGuard<VecDeque<(Instant, Duration)>>,
+        now: Instant,
+    ) {
+        while let Some((confirmed_at, _)) = deliveries.front() {
+            if now.duration_since(*confirmed_at) <= self.window {
+                break;
+            }
+            deliveries.pop_front();
+        }
+        let mut latencies: Vec<Duration> = deliveries.iter().map(|(_, l)| *l).collect();
+        drop(deliveries);
+        latencies.sort_unstable();
+
+        // A lane without deliveries in the window has no SLO metrics
+        if latencies.is_empty() {
+            for (_, label) in QUANTILES {
+                let _ = self.metrics.latency.remove_label_values(&[
+                    &self.origin,
+                    &self.destination,
+                    label,
+                ]);
+            }
+            let _ = self
+                .metrics
+                .within_target
+                .remove_label_values(&[&self.origin, &self.destination]);
+            return;
+        }
+        for (quantile, label) in QUANTILES {
+            self.metrics
+                .latency
+                .with_label_values(&[&self.origin, &self.destination, label])
+                .set(quantile_of(&latencies, quantile).as_secs_f64());
+        }
+        let within_target = latencies.partition_point(|l| *l <= self.target);
+        self.metrics
+            .within_target
+            .with_label_values(&[&self.origin, &self.destination])
+            .set(within_target as f64 / latencies.len() as f64);
+    }
+}
+
+/// Periodically evict the deliveries that left the window from the metrics
+/// of the lanes, so idle lanes don't keep reporting stale latencies
+pub fn spawn_evictor(slos: Vec<Arc<DeliverySlo>>) -> Instrumented<JoinHandle<()>> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            for slo in &slos {
+                slo.evict_expired();
+            }
+        }
+    })
+    .instrument(info_span!("DeliverySloEvictor"))
+}
+
+/// The nearest-rank quantile of sorted, non-empty latencies
+fn quantile_of(sorted: &[Duration], quantile: f64) -> Duration {
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::Registry;
+
+    use super::*;
+
+    #[test]
+    fn test_record_delivery_updates_lane_metrics() {
+        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let metrics = DeliverySloMetrics::new(&core_metrics).unwrap();
+        let slo = DeliverySlo::new(
+            metrics.clone(),
+            &KnownHyperlaneDomain::Ethereum.into(),
+            &KnownHyperlaneDomain::Arbitrum.into(),
+            Duration::from_secs(60),
+            Duration::from_secs(3600),
+        );
+
+        for secs in 1..=20 {
+            slo.record_delivery(Duration::from_secs(secs * 5));
+        }
+
+        let latency = |quantile| {
+            metrics
+                .latency
+                .with_label_values(&["ethereum", "arbitrum", quantile])
+                .get()
+        };
+        assert_eq!(latency("0.5"), 50.);
+        assert_eq!(latency("0.95"), 95.);
+        assert_eq!(
+            metrics
+                .within_target
+                .with_label_values(&["ethereum", "arbitrum"])
+                .get(),
+            0.6
+        );
+    }
+
+    #[test]
+    fn test_evict_expired_clears_idle_lane() {
+        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let metrics = DeliverySloMetrics::new(&core_metrics).unwrap();
+        let slo = DeliverySlo::new(
+            metrics.clone(),
+            &KnownHyperlaneDomain::Ethereum.into(),
+            &KnownHyperlaneDomain::Arbitrum.into(),
+            Duration::from_secs(60),
+            Duration::from_millis(50),
+        );
+        slo.record_delivery(Duration::from_secs(30));
+        slo.evict_expired();
+        assert_eq!(
+            metrics
+                .within_target
+                .with_label_values(&["ethereum", "arbitrum"])
+                .get(),
+            1.
+        );
+
+        std::thread::sleep(Duration::from_millis(100));
+        slo.evict_expired();
+        assert!(slo.deliveries.lock().unwrap().is_empty());
+        assert!(metrics
+            .latency
+            .remove_label_values(&["ethereum", "arbitrum", "0.5"])
+            .is_err());
+        assert!(metrics
+            .within_target
+            .remove_label_values(&["ethereum", "arbitrum"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_quantile_of() {
+        let sorted: Vec<_> = [1, 2, 3, 4].map(Duration::from_secs).into();
+        assert_eq!(quantile_of(&sorted, 0.5), Duration::from_secs(2));
+        assert_eq!(quantile_of(&sorted, 0.95), Duration::from_secs(4));
+        assert_eq!(quantile_of(&sorted[..1], 0.), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use derive_new::new;
+use hyperlane_core::HyperlaneMessage;
+
+use crate::settings::{first_matching_app, MessageExpiryConf};
+
+/// Decides when the relayer should give up on a message and move it to the
+/// dead-letter store instead of retrying it forever.
+#[derive(Debug, Default, new)]
+pub struct MessageExpiryPolicy {
+    /// Maximum message age per app. If a message matches multiple apps,
+    /// whichever is first in the list is used.
+    confs: Vec<MessageExpiryConf>,
+}
+
+impl MessageExpiryPolicy {
+    /// Returns the maximum age of the first app the message matches, or None
+    /// if the message never expires.
+    pub fn max_age(&self, message: &HyperlaneMessage) -> Option<Duration> {
+        first_matching_app(&self.confs, message).map(|conf| conf.max_age)
+    }
+
+    /// Whether a message dispatched at `dispatched_at` is expired at `now`,
+    /// both being unix timestamps in seconds.
+    pub fn is_expired(&self, message: &HyperlaneMessage, dispatched_at: u64, now: u64) -> bool {
+        self.max_age(message).map_or(false, |max_age| {
+            now.saturating_sub(dispatched_at) > max_age.as_secs()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_expired_uses_first_matching_app() {
+        let policy = MessageExpiryPolicy::new(vec![
+            MessageExpiryConf {
+                matching_list: serde_json::from_str(r#"[{"destinationdomain": 2}]"#).unwrap(),
+                max_age: Duration::from_secs(60),
+            },
+            MessageExpiryConf {
+                matching_list: serde_json::from_str(r#"[{}]"#).unwrap(),
+                max_age: Duration::from_secs(3600),
+            },
+        ]);
+        let message = HyperlaneMessage {
+            destination: 2,
+            ..Default::default()
+        };
+        let other = HyperlaneMessage {
+            destination: 3,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.max_age(&message), Some(Duration::from_secs(60)));
+        assert!(!policy.is_expired(&message, 1_000, 1_060));
+        assert!(policy.is_expired(&message, 1_000, 1_061));
+        assert!(!policy.is_expired(&other, 1_000, 1_061));
+        assert!(policy.is_expired(&other, 1_000, 5_000));
+        // Clock skew between the origin and the relayer never expires a message
+        assert!(!policy.is_expired(&message, 2_000, 1_000));
+        assert!(!MessageExpiryPolicy::default().is_expired(&message, 0, u64::MAX));
+    }
+}
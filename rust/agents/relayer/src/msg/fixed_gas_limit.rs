@@ -0,0 +1,105 @@
+use derive_new::new;
+use hyperlane_core::{HyperlaneMessage, U256};
+
+use crate::settings::{first_matching_app, FixedGasLimitConf};
+
+/// Fixed gas limits of apps whose estimates can't be relied on, e.g. because
+/// their recipients intentionally revert on static calls.
+#[derive(Debug, Default, new)]
+pub struct FixedGasLimits {
+    /// Gas limit per app. If a message matches multiple apps, whichever is
+    /// first in the list is used.
+    confs: Vec<FixedGasLimitConf>,
+}
+
+impl FixedGasLimits {
+    /// Returns the gas limit of the first app the message matches, or None if
+    /// the gas limit of the message should be estimated.
+    pub fn gas_limit(&self, message: &HyperlaneMessage) -> Option<U256> {
+        first_matching_app(&self.confs, message).map(|conf| conf.gas_limit)
+    }
+}
+
+/// How a fixed gas limit compares to the estimate it replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedGasLimitComparison {
+    /// The estimate is the fixed limit
+    Equal,
+    /// The fixed limit is above the estimate
+    Higher,
+    /// The fixed limit is below the estimate, so delivery may run out of gas
+    Lower,
+    /// Estimation failed, e.g. because the recipient reverts on static calls
+    EstimationFailed,
+}
+
+impl FixedGasLimitComparison {
+    /// Compare a fixed gas limit to the estimate, if there is one
+    pub fn new(fixed: U256, estimate: Option<U256>) -> Self {
+        match estimate {
+            None => Self::EstimationFailed,
+            Some(estimate) if fixed == estimate => Self::Equal,
+            Some(estimate) if fixed > estimate => Self::Higher,
+            Some(_) => Self::Lower,
+        }
+    }
+
+    /// The metric label of the comparison
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equal => "equal",
+            Self::Higher => "higher",
+            Self::Lower => "lower",
+            Self::EstimationFailed => "estimation_failed",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gas_limit_uses_first_matching_app() {
+        let limits = FixedGasLimits::new(vec![
+            FixedGasLimitConf {
+                matching_list: serde_json::from_str(r#"[{"destinationdomain": 2}]"#).unwrap(),
+                gas_limit: U256::from(500_000),
+            },
+            FixedGasLimitConf {
+                matching_list: serde_json::from_str(r#"[{"destinationdomain": [2, 3]}]"#).unwrap(),
+                gas_limit: U256::from(1),
+            },
+        ]);
+        let message = |destination| HyperlaneMessage {
+            destination,
+            ..Default::default()
+        };
+
+        assert_eq!(limits.gas_limit(&message(2)), Some(U256::from(500_000)));
+        assert_eq!(limits.gas_limit(&message(3)), Some(U256::from(1)));
+        assert_eq!(limits.gas_limit(&message(4)), None);
+        assert_eq!(FixedGasLimits::default().gas_limit(&message(2)), None);
+    }
+
+    #[test]
+    fn test_comparison() {
+        let fixed = U256::from(100);
+        assert_eq!(
+            FixedGasLimitComparison::new(fixed, Some(U256::from(100))),
+            FixedGasLimitComparison::Equal
+        );
+        assert_eq!(
+            FixedGasLimitComparison::new(fixed, Some(U256::from(99))),
+            FixedGasLimitComparison::Higher
+        );
+        assert_eq!(
+            FixedGasLimitComparison::new(fixed, Some(U256::from(101))),
+            FixedGasLimitComparison::Lower
+        );
+        assert_eq!(
+            FixedGasLimitComparison::new(fixed, None),
+            FixedGasLimitComparison::EstimationFailed
+        );
+    }
+}
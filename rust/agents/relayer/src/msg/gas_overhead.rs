@@ -0,0 +1,82 @@
+use derive_new::new;
+use hyperlane_core::{HyperlaneMessage, TxCostEstimate, U256};
+
+use crate::settings::{first_matching_app, GasOverheadConf};
+
+/// Adds a static amount of gas to the estimates of apps whose gas usage is
+/// underreported by estimation, e.g. because `handle` is reentrant or has a
+/// deep call graph.
+#[derive(Debug, Default, new)]
+pub struct GasOverheads {
+    /// Gas overhead per app. If a message matches multiple apps, whichever is
+    /// first in the list is used.
+    confs: Vec<GasOverheadConf>,
+}
+
+impl GasOverheads {
+    /// Returns the overhead of the first app the message matches, or None if
+    /// the message does not belong to an app with an overhead.
+    pub fn overhead(&self, message: &HyperlaneMessage) -> Option<U256> {
+        first_matching_app(&self.confs, message).map(|conf| conf.overhead)
+    }
+
+    /// Add the overhead of the message's app to the cost estimate. The
+    /// overhead is L2 execution gas, so on chains that report it separately it
+    /// is added to both the L2 and the total gas limit.
+    pub fn apply(
+        &self,
+        mut tx_cost_estimate: TxCostEstimate,
+        message: &HyperlaneMessage,
+    ) -> TxCostEstimate {
+        let Some(overhead) = self.overhead(message) else {
+            return tx_cost_estimate;
+        };
+        tx_cost_estimate.gas_limit = tx_cost_estimate.gas_limit.saturating_add(overhead);
+        tx_cost_estimate.l2_gas_limit = tx_cost_estimate
+            .l2_gas_limit
+            .map(|l2_gas_limit| l2_gas_limit.saturating_add(overhead));
+        tx_cost_estimate
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_apply_uses_first_matching_app() {
+        let overheads = GasOverheads::new(vec![
+            GasOverheadConf {
+                matching_list: serde_json::from_str(r#"[{"destinationdomain": 2}]"#).unwrap(),
+                overhead: U256::from(50_000),
+            },
+            GasOverheadConf {
+                matching_list: serde_json::from_str(r#"[{}]"#).unwrap(),
+                overhead: U256::from(1),
+            },
+        ]);
+        let message = HyperlaneMessage {
+            destination: 2,
+            ..Default::default()
+        };
+        let estimate = TxCostEstimate {
+            gas_limit: U256::from(100_000),
+            l2_gas_limit: Some(U256::from(80_000)),
+            ..Default::default()
+        };
+
+        let applied = overheads.apply(estimate.clone(), &message);
+        assert_eq!(applied.gas_limit, U256::from(150_000));
+        assert_eq!(applied.l2_gas_limit, Some(U256::from(130_000)));
+
+        let other = HyperlaneMessage {
+            destination: 3,
+            ..Default::default()
+        };
+        assert_eq!(overheads.overhead(&other), Some(U256::from(1)));
+        assert_eq!(
+            GasOverheads::default().apply(estimate.clone(), &message),
+            estimate
+        );
+    }
+}
@@ -90,6 +90,13 @@ impl GasPaymentEnforcer {
             .db
             .retrieve_gas_payment_by_gas_payment_key(gas_payment_key)?;
         let current_expenditure = self.db.retrieve_gas_expenditure_by_message_id(msg_id)?;
+        // What delivering the message costs in origin tokens, by the gas data
+        // of the destination the origin's IGP quotes payments with
+        let destination_gas = self
+            .db
+            .retrieve_destination_gas_update_by_domain(&message.destination)?;
+        let implied_cost =
+            destination_gas.map(|gas| gas.quote(tx_cost_estimate.enforceable_gas_limit()));
 
         for (policy, whitelist) in &self.policies {
             if !whitelist.msg_matches(message, true) {
@@ -113,16 +120,26 @@ impl GasPaymentEnforcer {
                 ?policy,
                 ?current_payment,
                 ?current_expenditure,
+                ?destination_gas,
+                ?implied_cost,
                 "Evaluating if message meets gas payment requirement",
             );
-            return policy
+            let requirement = policy
                 .message_meets_gas_payment_requirement(
                     message,
                     &current_payment,
                     &current_expenditure,
                     tx_cost_estimate,
                 )
-                .await;
+                .await?;
+            debug!(
+                msg=%message,
+                payment=%current_payment.payment,
+                ?implied_cost,
+                meets_requirement=requirement.is_some(),
+                "Evaluated gas payment requirement",
+            );
+            return Ok(requirement);
         }
 
         error!(
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use crate::settings::LaneWeights;
+
+/// Picks which (origin -> destination) lane the next operation should be taken
+/// from, so that a burst of messages from one origin cannot starve the other
+/// origins sending to the same destination.
+///
+/// Uses smooth weighted round-robin: every time a lane is picked, each
+/// non-empty lane accumulates its weight, the lane with the highest
+/// accumulated weight is selected and the sum of all weights is subtracted
+/// from it. Over time each lane is selected proportionally to its weight,
+/// and selections of the same lane are spread out rather than grouped.
+#[derive(Debug)]
+pub struct WeightedLaneScheduler {
+    destination: u32,
+    weights: LaneWeights,
+    current_weights: HashMap<u32, i64>,
+}
+
+impl WeightedLaneScheduler {
+    pub fn new(destination: u32, weights: LaneWeights) -> Self {
+        Self {
+            destination,
+            weights,
+            current_weights: HashMap::new(),
+        }
+    }
+
+    /// Select the origin lane to pop the next operation from, given the origins
+    /// that currently have operations ready.
+    pub fn next_lane(&mut self, lanes: impl IntoIterator<Item = u32>) -> Option<u32> {
+        let mut total_weight = 0;
+        let mut selected: Option<(u32, i64)> = None;
+        for origin in lanes {
+            let weight = self.weights.weight(origin, self.destination) as i64;
+            total_weight += weight;
+            let current = self.current_weights.entry(origin).or_default();
+            *current += weight;
+            if selected.map_or(true, |(_, max)| *current > max) {
+                selected = Some((origin, *current));
+            }
+        }
+        let (origin, _) = selected?;
+        if let Some(current) = self.current_weights.get_mut(&origin) {
+            *current -= total_weight;
+        }
+        Some(origin)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lanes_are_selected_proportionally_to_weights() {
+        let weights = LaneWeights {
+            default_weight: 1,
+            weights: [((1, 100), 3)].into_iter().collect(),
+        };
+        let mut scheduler = WeightedLaneScheduler::new(100, weights);
+
+        let selections: Vec<_> = (0..8)
+            .map(|_| scheduler.next_lane([1, 2]).unwrap())
+            .collect();
+        assert_eq!(selections.iter().filter(|&&o| o == 1).count(), 6);
+        assert_eq!(selections.iter().filter(|&&o| o == 2).count(), 2);
+        // The lower-weight lane is served within every window of `total_weight` picks
+        assert!(selections[..4].contains(&2));
+        assert!(selections[4..].contains(&2));
+    }
+
+    #[test]
+    fn test_no_lanes_selects_nothing() {
+        let mut scheduler = WeightedLaneScheduler::new(100, LaneWeights::default());
+        assert_eq!(scheduler.next_lane([]), None);
+    }
+}
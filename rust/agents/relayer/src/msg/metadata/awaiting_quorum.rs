@@ -0,0 +1,149 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use derive_new::new;
+use hyperlane_base::MultisigCheckpointSyncer;
+use hyperlane_core::{HyperlaneDomain, H256};
+
+/// How long a message is known to await a quorum before its metadata is
+/// built again regardless, in case the ISM of its recipient changed
+const AWAITING_QUORUM_TTL: Duration = Duration::from_secs(60 * 10);
+
+/// The quorum of signatures a multisig ISM needs to verify a message, recorded
+/// when its metadata couldn't be built for lack of one
+#[derive(Debug, Clone, new)]
+pub struct QuorumRequirement {
+    validators: Vec<H256>,
+    threshold: u8,
+    /// Index of the message in the origin's merkle tree, which validators
+    /// must have signed a checkpoint at or after
+    leaf_index: u32,
+    /// Checkpoint syncers of the validators, kept so their announced storage
+    /// locations aren't fetched again
+    checkpoint_syncer: MultisigCheckpointSyncer,
+}
+
+impl QuorumRequirement {
+    /// Whether enough validators published a checkpoint including the
+    /// message for its metadata to be built. Only reads the latest index of
+    /// each validator, which is far cheaper than building the metadata.
+    pub async fn is_available(
+        &self,
+        origin: &HyperlaneDomain,
+        destination: &HyperlaneDomain,
+    ) -> bool {
+        let latest_indices = self
+            .checkpoint_syncer
+            .get_validator_latest_checkpoints_and_update_metrics(
+                &self.validators,
+                origin,
+                destination,
+            )
+            .await;
+        let signed = latest_indices
+            .iter()
+            .filter(|index| **index >= self.leaf_index)
+            .count();
+        signed >= self.threshold as usize
+    }
+}
+
+/// Messages whose metadata couldn't be built because a multisig ISM lacked a
+/// quorum of signatures, with the quorums they await. Until one of them is
+/// available, the message's metadata isn't built again.
+#[derive(Debug, Default)]
+pub struct AwaitingQuorum {
+    messages: Mutex<HashMap<H256, (Instant, Vec<QuorumRequirement>)>>,
+}
+
+impl AwaitingQuorum {
+    /// Record a quorum the message awaits. A message verified by several
+    /// multisig ISMs, e.g. through an aggregation ISM, may await several.
+    /// Returns how many messages await a quorum.
+    pub fn record(&self, message_id: H256, requirement: QuorumRequirement) -> usize {
+        let mut messages = self.messages.lock().unwrap();
+        messages.retain(|_, (since, _)| since.elapsed() < AWAITING_QUORUM_TTL);
+        messages
+            .entry(message_id)
+            .or_insert_with(|| (Instant::now(), vec![]))
+            .1
+            .push(requirement);
+        messages.len()
+    }
+
+    /// The quorums the message awaits, if it awaits any
+    pub fn requirements(&self, message_id: &H256) -> Option<Vec<QuorumRequirement>> {
+        let mut messages = self.messages.lock().unwrap();
+        let (since, requirements) = messages.get(message_id)?;
+        if since.elapsed() >= AWAITING_QUORUM_TTL {
+            messages.remove(message_id);
+            return None;
+        }
+        Some(requirements.clone())
+    }
+
+    /// Stop awaiting a quorum for the message. Returns how many messages
+    /// still await one.
+    pub fn remove(&self, message_id: &H256) -> usize {
+        let mut messages = self.messages.lock().unwrap();
+        messages.remove(message_id);
+        messages.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use hyperlane_base::{CheckpointSyncer, CoreMetrics, LocalStorage};
+    use hyperlane_core::{KnownHyperlaneDomain, H160};
+    use prometheus::Registry;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quorum_is_available_once_enough_validators_signed() {
+        let dir = std::env::temp_dir().join(format!("awaiting_quorum_{:x}", H256::random()));
+        let validators: Vec<H160> = (0..3).map(|_| H160::random()).collect();
+        let syncers: Vec<Arc<dyn CheckpointSyncer>> = (0..3)
+            .map(|i| {
+                Arc::new(LocalStorage::new(dir.join(i.to_string()), None).unwrap())
+                    as Arc<dyn CheckpointSyncer>
+            })
+            .collect();
+        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let requirement = QuorumRequirement::new(
+            validators.iter().map(|v| H256::from(*v)).collect(),
+            2,
+            4,
+            MultisigCheckpointSyncer::new(
+                validators.iter().copied().zip(syncers.clone()).collect(),
+                Arc::new(core_metrics),
+                None,
+            ),
+        );
+        let origin: HyperlaneDomain = KnownHyperlaneDomain::Ethereum.into();
+        let destination: HyperlaneDomain = KnownHyperlaneDomain::Arbitrum.into();
+
+        // One validator signed the message, another hasn't yet, and the last
+        // one never published anything
+        syncers[0].write_latest_index(5).await.unwrap();
+        syncers[1].write_latest_index(3).await.unwrap();
+        assert!(!requirement.is_available(&origin, &destination).await);
+
+        syncers[1].write_latest_index(4).await.unwrap();
+        assert!(requirement.is_available(&origin, &destination).await);
+
+        let awaiting = AwaitingQuorum::default();
+        let message_id = H256::random();
+        assert_eq!(awaiting.record(message_id, requirement), 1);
+        assert_eq!(awaiting.requirements(&message_id).unwrap().len(), 1);
+        assert_eq!(awaiting.remove(&message_id), 0);
+        assert!(awaiting.requirements(&message_id).is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
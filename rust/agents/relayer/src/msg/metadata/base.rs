@@ -11,10 +11,11 @@ use crate::{
     merkle_tree::builder::MerkleTreeBuilder,
     msg::metadata::{
         multisig::{MerkleRootMultisigMetadataBuilder, MessageIdMultisigMetadataBuilder},
-        AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, NullMetadataBuilder,
+        AggregationIsmMetadataBuilder, AwaitingQuorum, CcipReadIsmMetadataBuilder,
+        CustomIsmMetadataBuilder, NullMetadataBuilder, QuorumRequirement,
         RoutingIsmMetadataBuilder,
     },
-    settings::matching_list::MatchingList,
+    settings::{matching_list::MatchingList, CustomIsmConf, CustomIsmStrategy},
 };
 use async_trait::async_trait;
 use derive_new::new;
@@ -25,13 +26,13 @@ use hyperlane_base::{
     CheckpointSyncer, CoreMetrics, MultisigCheckpointSyncer,
 };
 use hyperlane_core::{
-    accumulator::merkle::Proof, AggregationIsm, CcipReadIsm, Checkpoint, HyperlaneDomain,
-    HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType, MultisigIsm, RoutingIsm,
-    ValidatorAnnounce, H160, H256,
+    accumulator::merkle::Proof, utils::fmt_address_for_domain, AggregationIsm, CcipReadIsm,
+    Checkpoint, HyperlaneDomain, HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType,
+    MultisigIsm, RoutingIsm, ValidatorAnnounce, H160, H256,
 };
 
-use tokio::sync::RwLock;
-use tracing::{debug, info, instrument, warn};
+use tokio::{sync::RwLock, task::JoinHandle, time::sleep};
+use tracing::{debug, info, info_span, instrument, instrument::Instrumented, warn, Instrument};
 
 #[derive(Debug, thiserror::Error)]
 pub enum MetadataBuilderError {
@@ -55,7 +56,8 @@ pub trait MetadataBuilder: Send + Sync {
 }
 
 /// Allows fetching the default ISM, caching the value for a period of time
-/// to avoid fetching it all the time.
+/// to avoid fetching it all the time. Shared by everything relaying to the
+/// mailbox, and refreshed as soon as the default ISM changes if watched.
 /// TODO: make this generic
 #[derive(Debug)]
 pub struct DefaultIsmCache {
@@ -67,6 +69,14 @@ impl DefaultIsmCache {
     /// Time to live for the cached default ISM. 10 mins.
     const TTL: Duration = Duration::from_secs(60 * 10);
 
+    /// How often the default ISM is refetched if the mailbox can't be watched
+    /// for new ones
+    const POLL_INTERVAL: Duration = if cfg!(test) {
+        Duration::from_millis(10)
+    } else {
+        Duration::from_secs(30)
+    };
+
     pub fn new(mailbox: Arc<dyn Mailbox>) -> Self {
         Self {
             value: RwLock::new(None),
@@ -102,21 +112,59 @@ impl DefaultIsmCache {
 
         Ok(default_ism)
     }
+
+    /// Watch the mailbox for events setting a new default ISM in the
+    /// background, replacing the cached value as soon as one is emitted
+    /// rather than once it expires
+    pub fn spawn_watcher(self: Arc<Self>) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("DefaultIsmWatcher", mailbox = ?self.mailbox.address());
+        tokio::spawn(async move {
+            let mut polling = false;
+            loop {
+                if !self.mailbox.wait_for_default_ism_set().await {
+                    if !polling {
+                        debug!("Not watching for new default ISMs, polling for them instead");
+                        polling = true;
+                    }
+                    sleep(Self::POLL_INTERVAL).await;
+                }
+                self.refresh().await;
+            }
+        })
+        .instrument(span)
+    }
+
+    async fn refresh(&self) {
+        let default_ism = match self.mailbox.default_ism().await {
+            Ok(default_ism) => default_ism,
+            Err(err) => {
+                warn!(?err, "Failed to fetch the default ISM");
+                return;
+            }
+        };
+        let mut value = self.value.write().await;
+        if let Some((cached, _)) = *value {
+            if cached != default_ism {
+                info!(previous = ?cached, current = ?default_ism, "Default ISM was set");
+            }
+        }
+        *value = Some((default_ism, Instant::now()));
+    }
 }
 
 #[derive(Debug)]
 pub struct IsmAwareAppContextClassifier {
-    default_ism: DefaultIsmCache,
+    default_ism: Arc<DefaultIsmCache>,
     app_context_classifier: AppContextClassifier,
 }
 
 impl IsmAwareAppContextClassifier {
     pub fn new(
-        destination_mailbox: Arc<dyn Mailbox>,
+        default_ism: Arc<DefaultIsmCache>,
         app_matching_lists: Vec<(MatchingList, String)>,
     ) -> Self {
         Self {
-            default_ism: DefaultIsmCache::new(destination_mailbox),
+            default_ism,
             app_context_classifier: AppContextClassifier::new(app_matching_lists),
         }
     }
@@ -225,7 +273,58 @@ impl MessageMetadataBuilder {
         }
     }
 
-    #[instrument(err, skip(self), fields(destination_domain=self.destination_domain().name()), ret)]
+    /// The quorum of signatures the ISM needs to verify the message if it is
+    /// a multisig ISM, so whether one is available can be checked without
+    /// building its metadata
+    pub async fn quorum_requirement(
+        &self,
+        ism_address: H256,
+        message: &HyperlaneMessage,
+    ) -> Result<Option<QuorumRequirement>> {
+        const CTX: &str = "When fetching the quorum of a multisig ISM";
+        let module_type = self
+            .build_ism(ism_address)
+            .await
+            .context(CTX)?
+            .module_type()
+            .await
+            .context(CTX)?;
+        if !matches!(
+            module_type,
+            ModuleType::MerkleRootMultisig | ModuleType::MessageIdMultisig
+        ) {
+            return Ok(None);
+        }
+        let Some(leaf_index) = self
+            .get_merkle_leaf_id_by_message_id(message.id())
+            .await
+            .context(CTX)?
+        else {
+            return Ok(None);
+        };
+        let (validators, threshold) = self
+            .build_multisig_ism(ism_address)
+            .await
+            .context(CTX)?
+            .validators_and_threshold(message)
+            .await
+            .context(CTX)?;
+        if validators.is_empty() {
+            return Ok(None);
+        }
+        let checkpoint_syncer = self
+            .build_checkpoint_syncer(&validators, self.app_context.clone())
+            .await
+            .context(CTX)?;
+        Ok(Some(QuorumRequirement::new(
+            validators,
+            threshold,
+            leaf_index,
+            checkpoint_syncer,
+        )))
+    }
+
+    #[instrument(err, skip(self, ism_address), fields(destination_domain=self.destination_domain().name(), ism=%fmt_address_for_domain(self.destination_domain().id(), ism_address)), ret)]
     pub async fn build_ism_and_metadata(
         &self,
         ism_address: H256,
@@ -253,7 +352,10 @@ impl MessageMetadataBuilder {
             ModuleType::Aggregation => Box::new(AggregationIsmMetadataBuilder::new(cloned)),
             ModuleType::Null => Box::new(NullMetadataBuilder::new()),
             ModuleType::CcipRead => Box::new(CcipReadIsmMetadataBuilder::new(cloned)),
-            _ => return Err(MetadataBuilderError::UnsupportedModuleType(module_type).into()),
+            _ => match self.custom_ism_strategy(ism.as_ref()).await? {
+                Some(strategy) => Box::new(CustomIsmMetadataBuilder::new(strategy)),
+                None => return Err(MetadataBuilderError::UnsupportedModuleType(module_type).into()),
+            },
         };
         let meta = metadata_builder
             .build(ism_address, message)
@@ -279,8 +381,11 @@ pub struct BaseMetadataBuilder {
     metrics: Arc<CoreMetrics>,
     db: HyperlaneRocksDB,
     app_context_classifier: IsmAwareAppContextClassifier,
+    custom_isms: Arc<Vec<CustomIsmConf>>,
     #[new(value = "7")]
     max_depth: u32,
+    #[new(default)]
+    awaiting_quorum: AwaitingQuorum,
 }
 
 impl Debug for BaseMetadataBuilder {
@@ -321,6 +426,55 @@ impl BaseMetadataBuilder {
         Ok(proof)
     }
 
+    /// Record that the message awaits a quorum of signatures, so its
+    /// metadata isn't built again until one is available
+    pub fn record_awaiting_quorum(&self, message_id: H256, requirement: QuorumRequirement) {
+        let awaiting = self.awaiting_quorum.record(message_id, requirement);
+        self.set_messages_awaiting_quorum(awaiting);
+    }
+
+    /// The quorums of signatures the message awaits, if any
+    pub fn quorum_requirements(&self, message_id: &H256) -> Option<Vec<QuorumRequirement>> {
+        self.awaiting_quorum.requirements(message_id)
+    }
+
+    pub fn stop_awaiting_quorum(&self, message_id: &H256) {
+        let awaiting = self.awaiting_quorum.remove(message_id);
+        self.set_messages_awaiting_quorum(awaiting);
+    }
+
+    fn set_messages_awaiting_quorum(&self, awaiting: usize) {
+        self.metrics
+            .messages_awaiting_quorum()
+            .with_label_values(&[self.origin_domain.name(), self.destination_domain().name()])
+            .set(awaiting as i64);
+    }
+
+    /// How to build the metadata of an ISM of a module type the relayer
+    /// doesn't know, if it is configured as a custom ISM
+    pub async fn custom_ism_strategy(
+        &self,
+        ism: &dyn InterchainSecurityModule,
+    ) -> Result<Option<CustomIsmStrategy>> {
+        if self.custom_isms.is_empty() {
+            return Ok(None);
+        }
+        // Only fetched if needed to match, as most chains can't report it
+        let raw_module_type = if self.custom_isms.iter().any(|c| c.module_type.is_some()) {
+            ism.raw_module_type()
+                .await
+                .context("When fetching raw module type")?
+        } else {
+            None
+        };
+        let address = ism.address();
+        Ok(self
+            .custom_isms
+            .iter()
+            .find(|custom_ism| custom_ism.matches(&address, raw_module_type))
+            .map(|custom_ism| custom_ism.strategy.clone()))
+    }
+
     pub async fn highest_known_leaf_index(&self) -> Option<u32> {
         self.origin_prover_sync.read().await.count().checked_sub(1)
     }
@@ -432,3 +586,44 @@ impl BaseMetadataBuilder {
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use hyperlane_test::mocks::MockMailboxContract;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_refreshes_default_ism_when_set() {
+        let mut mailbox = MockMailboxContract::new();
+        mailbox.expect__address().return_const(H256::zero());
+        let fetches = Arc::new(AtomicU64::new(0));
+        let default_ism_fetches = fetches.clone();
+        mailbox.expect__default_ism().returning(move || {
+            let fetch = default_ism_fetches.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(H256::from_low_u64_be(fetch))
+        });
+        // A single new default ISM is set, after which the mailbox stops
+        // being watched and is polled instead
+        let mut events = 1;
+        mailbox
+            .expect__wait_for_default_ism_set()
+            .returning(move || {
+                let watched = events > 0;
+                events -= 1;
+                watched
+            });
+        let cache = Arc::new(DefaultIsmCache::new(Arc::new(mailbox)));
+        assert_eq!(cache.get().await.unwrap(), H256::from_low_u64_be(1));
+
+        let watcher = cache.clone().spawn_watcher();
+        sleep(DefaultIsmCache::POLL_INTERVAL * 5).await;
+        watcher.into_inner().abort();
+        // New default ISMs are used before the cached one expires
+        let polled = fetches.load(Ordering::SeqCst);
+        assert!(polled > 2);
+        assert_eq!(cache.get().await.unwrap(), H256::from_low_u64_be(polled));
+    }
+}
@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use derive_new::new;
+use ethers::core::utils::hex::decode as hex_decode;
+use eyre::Context;
+use hyperlane_core::{utils::bytes_to_hex, HyperlaneMessage, RawHyperlaneMessage, H256};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{info, instrument};
+
+use crate::settings::CustomIsmStrategy;
+
+use super::MetadataBuilder;
+
+#[derive(Deserialize)]
+struct MetadataServiceResponse {
+    metadata: String,
+}
+
+/// Builds the metadata of an ISM of a module type the relayer doesn't know,
+/// following the strategy configured for it
+#[derive(Clone, Debug, new)]
+pub struct CustomIsmMetadataBuilder {
+    strategy: CustomIsmStrategy,
+}
+
+#[async_trait]
+impl MetadataBuilder for CustomIsmMetadataBuilder {
+    #[instrument(err, skip(self))]
+    async fn build(
+        &self,
+        ism_address: H256,
+        message: &HyperlaneMessage,
+    ) -> eyre::Result<Option<Vec<u8>>> {
+        match &self.strategy {
+            CustomIsmStrategy::Null => Ok(Some(vec![])),
+            CustomIsmStrategy::MessageBody { offset, length } => {
+                let metadata = message
+                    .body
+                    .get(*offset..)
+                    .and_then(|metadata| match length {
+                        Some(length) => metadata.get(..*length),
+                        None => Some(metadata),
+                    });
+                if metadata.is_none() {
+                    info!("Could not fetch metadata: message body is too short to contain it");
+                }
+                Ok(metadata.map(<[u8]>::to_vec))
+            }
+            CustomIsmStrategy::Http { url } => fetch_from_service(url, ism_address, message)
+                .await
+                .context("When fetching metadata from custom ISM service"),
+        }
+    }
+}
+
+/// Ask the service for the metadata of the message. The service answers
+/// `404 Not Found` while the metadata isn't available yet.
+async fn fetch_from_service(
+    url: &str,
+    ism_address: H256,
+    message: &HyperlaneMessage,
+) -> eyre::Result<Option<Vec<u8>>> {
+    let body = json!({
+        "ism": bytes_to_hex(ism_address.as_bytes()),
+        "messageId": bytes_to_hex(message.id().as_bytes()),
+        "message": bytes_to_hex(&RawHyperlaneMessage::from(message)),
+    });
+    let response = Client::new().post(url).json(&body).send().await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        info!("Could not fetch metadata: custom ISM service has none yet");
+        return Ok(None);
+    }
+    let response: MetadataServiceResponse = response.error_for_status()?.json().await?;
+    Ok(Some(hex_decode(
+        response.metadata.trim_start_matches("0x"),
+    )?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metadata_copied_from_message_body() {
+        let message = HyperlaneMessage {
+            body: (0..10).collect(),
+            ..Default::default()
+        };
+        let build = |offset, length| {
+            CustomIsmMetadataBuilder::new(CustomIsmStrategy::MessageBody { offset, length })
+        };
+
+        let metadata = build(4, None).build(H256::zero(), &message).await.unwrap();
+        assert_eq!(metadata, Some(vec![4, 5, 6, 7, 8, 9]));
+        let metadata = build(4, Some(2))
+            .build(H256::zero(), &message)
+            .await
+            .unwrap();
+        assert_eq!(metadata, Some(vec![4, 5]));
+        // Not part of the body yet, e.g. if the app appends it in a later
+        // version
+        let metadata = build(8, Some(4))
+            .build(H256::zero(), &message)
+            .await
+            .unwrap();
+        assert_eq!(metadata, None);
+    }
+}
@@ -0,0 +1,107 @@
+use derive_new::new;
+use hyperlane_core::{HyperlaneMessage, StandardHookMetadata, TxCostEstimate, H160};
+
+use crate::settings::{first_matching_app, HookMetadataConf};
+
+/// Constructs the `StandardHookMetadata` an app expects its messages to be
+/// quoted and delivered with.
+#[derive(Debug, Default, new)]
+pub struct HookMetadataBuilder {
+    /// Hook metadata per app. If a message matches multiple apps, whichever
+    /// is first in the list is used.
+    confs: Vec<HookMetadataConf>,
+}
+
+impl HookMetadataBuilder {
+    /// Returns the hook metadata of the first app the message matches, or
+    /// None if the message does not belong to an app with hook metadata.
+    pub fn build(&self, message: &HyperlaneMessage) -> Option<StandardHookMetadata> {
+        let conf = first_matching_app(&self.confs, message)?;
+        Some(StandardHookMetadata {
+            msg_value: conf.msg_value,
+            gas_limit: conf.gas_limit.unwrap_or_default(),
+            // Refunds go to the sender unless the app configured otherwise
+            refund_address: conf
+                .refund_address
+                .unwrap_or_else(|| H160::from(message.sender)),
+            custom: conf.custom.clone(),
+        })
+    }
+}
+
+/// Raise the gas limits of a cost estimate to at least the gas limit the app
+/// requested in its hook metadata. Recipients can use more gas than
+/// estimation suggests, e.g. when `handle` branches on state.
+pub fn apply_hook_gas_limit(
+    mut tx_cost_estimate: TxCostEstimate,
+    hook_metadata: &StandardHookMetadata,
+) -> TxCostEstimate {
+    if hook_metadata.gas_limit.is_zero() {
+        return tx_cost_estimate;
+    }
+    tx_cost_estimate.gas_limit = tx_cost_estimate.gas_limit.max(hook_metadata.gas_limit);
+    tx_cost_estimate.l2_gas_limit = tx_cost_estimate
+        .l2_gas_limit
+        .map(|l2_gas_limit| l2_gas_limit.max(hook_metadata.gas_limit));
+    tx_cost_estimate
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{H256, U256};
+
+    use super::*;
+
+    #[test]
+    fn test_build_uses_first_matching_app() {
+        let message = HyperlaneMessage {
+            sender: H256::repeat_byte(0x11),
+            destination: 2,
+            ..Default::default()
+        };
+        let builder = HookMetadataBuilder::new(vec![
+            HookMetadataConf {
+                matching_list: serde_json::from_str(r#"[{"destinationdomain": 3}]"#).unwrap(),
+                gas_limit: Some(U256::from(1)),
+                ..Default::default()
+            },
+            HookMetadataConf {
+                matching_list: serde_json::from_str(r#"[{"destinationdomain": 2}]"#).unwrap(),
+                gas_limit: Some(U256::from(500_000)),
+                custom: vec![0xff],
+                ..Default::default()
+            },
+        ]);
+
+        let metadata = builder.build(&message).unwrap();
+        assert_eq!(metadata.gas_limit, U256::from(500_000));
+        assert_eq!(metadata.refund_address, H160::repeat_byte(0x11));
+        assert_eq!(metadata.custom, vec![0xff]);
+
+        let other = HyperlaneMessage {
+            destination: 4,
+            ..Default::default()
+        };
+        assert_eq!(builder.build(&other), None);
+    }
+
+    #[test]
+    fn test_apply_hook_gas_limit() {
+        let estimate = TxCostEstimate {
+            gas_limit: U256::from(100_000),
+            l2_gas_limit: Some(U256::from(50_000)),
+            ..Default::default()
+        };
+        let metadata = StandardHookMetadata {
+            gas_limit: U256::from(250_000),
+            ..Default::default()
+        };
+        let applied = apply_hook_gas_limit(estimate.clone(), &metadata);
+        assert_eq!(applied.gas_limit, U256::from(250_000));
+        assert_eq!(applied.l2_gas_limit, Some(U256::from(250_000)));
+        assert_eq!(
+            apply_hook_gas_limit(estimate, &StandardHookMetadata::default()).gas_limit,
+            U256::from(100_000)
+        );
+    }
+}
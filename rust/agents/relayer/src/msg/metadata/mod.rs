@@ -1,15 +1,22 @@
 mod aggregation;
+mod awaiting_quorum;
 mod base;
 mod ccip_read;
+mod custom;
+mod hook;
 mod multisig;
 mod null_metadata;
 mod routing;
 
 use aggregation::AggregationIsmMetadataBuilder;
+pub(crate) use awaiting_quorum::{AwaitingQuorum, QuorumRequirement};
 pub(crate) use base::MetadataBuilder;
 pub(crate) use base::{
-    AppContextClassifier, BaseMetadataBuilder, IsmAwareAppContextClassifier, MessageMetadataBuilder,
+    AppContextClassifier, BaseMetadataBuilder, DefaultIsmCache, IsmAwareAppContextClassifier,
+    MessageMetadataBuilder,
 };
 use ccip_read::CcipReadIsmMetadataBuilder;
+use custom::CustomIsmMetadataBuilder;
+pub(crate) use hook::{apply_hook_gas_limit, HookMetadataBuilder};
 use null_metadata::NullMetadataBuilder;
 use routing::RoutingIsmMetadataBuilder;
@@ -14,7 +14,7 @@ use tracing::{debug, info};
 
 use crate::msg::metadata::base::MessageMetadataBuilder;
 
-use crate::msg::metadata::MetadataBuilder;
+use crate::msg::metadata::{MetadataBuilder, QuorumRequirement};
 
 #[derive(new, AsRef, Deref)]
 pub struct MultisigMetadata {
@@ -133,6 +133,20 @@ impl<T: MultisigIsmMetadataBuilder> MetadataBuilder for T {
                 ?message, ?validators, threshold, ism=%multisig_ism.address(),
                 "Could not fetch metadata: Unable to reach quorum"
             );
+            // Until enough validators sign a checkpoint including the message,
+            // only their latest indices are checked rather than building its
+            // metadata again
+            if let Some(leaf_index) = self
+                .as_ref()
+                .get_merkle_leaf_id_by_message_id(message.id())
+                .await
+                .context(CTX)?
+            {
+                self.as_ref().record_awaiting_quorum(
+                    message.id(),
+                    QuorumRequirement::new(validators, threshold, leaf_index, checkpoint_syncer),
+                );
+            }
             Ok(None)
         }
     }
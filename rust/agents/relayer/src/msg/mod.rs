@@ -26,11 +26,19 @@
 //!   switch everyone to new one)
 
 pub(crate) mod blacklist;
+pub(crate) mod confirmation_depth;
+pub(crate) mod deadline;
+pub(crate) mod delivery_slo;
+pub(crate) mod expiry;
+pub(crate) mod fixed_gas_limit;
+pub(crate) mod gas_overhead;
 pub(crate) mod gas_payment;
+pub(crate) mod lane_scheduler;
 pub(crate) mod metadata;
 pub(crate) mod op_queue;
 pub(crate) mod op_submitter;
 pub(crate) mod pending_message;
 pub(crate) mod processor;
+pub(crate) mod tenant;
 
 pub use gas_payment::GAS_EXPENDITURE_LOG_MESSAGE;
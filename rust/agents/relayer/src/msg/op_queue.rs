@@ -1,4 +1,9 @@
-use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    sync::Arc,
+    time::Instant,
+};
 
 use derive_new::new;
 use hyperlane_core::{PendingOperation, QueueOperation};
@@ -8,6 +13,8 @@ use tracing::{debug, info, instrument};
 
 use crate::server::MessageRetryRequest;
 
+use super::lane_scheduler::WeightedLaneScheduler;
+
 /// Queue of generic operations that can be submitted to a destination chain.
 /// Includes logic for maintaining queue metrics by the destination and `app_context` of an operation
 #[derive(Debug, Clone, new)]
@@ -16,17 +23,92 @@ pub struct OpQueue {
     queue_metrics_label: String,
     retry_rx: Arc<Mutex<Receiver<MessageRetryRequest>>>,
     #[new(default)]
-    queue: Arc<Mutex<BinaryHeap<Reverse<QueueOperation>>>>,
+    queue: Arc<Mutex<QueuedOperations>>,
+    #[new(default)]
+    lane_scheduler: Option<Arc<Mutex<WeightedLaneScheduler>>>,
+}
+
+/// The operations of a queue, in a heap per origin lane so that they can be
+/// popped fairly across lanes without rebuilding the queue
+#[derive(Debug, Default)]
+struct QueuedOperations {
+    /// Operations that were urgent when pushed, only popped once ready.
+    /// Operations becoming urgent while queued are moved here the next time
+    /// they are pushed, after their next attempt.
+    urgent: BinaryHeap<Reverse<QueueOperation>>,
+    lanes: BTreeMap<u32, BinaryHeap<Reverse<QueueOperation>>>,
+}
+
+impl QueuedOperations {
+    fn push(&mut self, op: QueueOperation) {
+        if op.is_urgent() {
+            self.urgent.push(Reverse(op));
+            return;
+        }
+        self.lanes
+            .entry(op.origin_domain_id())
+            .or_default()
+            .push(Reverse(op));
+    }
+
+    /// Pop the next operation of a lane, forgetting the lane once it is empty
+    fn pop_lane(&mut self, origin: u32) -> Option<QueueOperation> {
+        let lane = self.lanes.get_mut(&origin)?;
+        let op = lane.pop().map(|Reverse(op)| op);
+        if lane.is_empty() {
+            self.lanes.remove(&origin);
+        }
+        op
+    }
+
+    /// The lane whose next operation comes first in priority order
+    fn first_lane(&self) -> Option<u32> {
+        self.lanes
+            .iter()
+            .filter_map(|(origin, lane)| lane.peek().map(|Reverse(op)| (*origin, op)))
+            .min_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(origin, _)| origin)
+    }
+
+    /// The lanes whose next operation is ready to be attempted
+    fn ready_lanes(&self, now: Instant) -> impl Iterator<Item = u32> + '_ {
+        self.lanes
+            .iter()
+            .filter(move |(_, lane)| {
+                lane.peek().map_or(false, |Reverse(op)| {
+                    op.next_attempt_after().map_or(true, |t| t <= now)
+                })
+            })
+            .map(|(origin, _)| *origin)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.urgent.is_empty() && self.lanes.values().all(BinaryHeap::is_empty)
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = QueueOperation> {
+        std::mem::take(&mut self.urgent)
+            .into_iter()
+            .chain(std::mem::take(&mut self.lanes).into_values().flatten())
+            .map(|Reverse(op)| op)
+    }
 }
 
 impl OpQueue {
+    /// Pop ready operations in a weighted-fair order across origin lanes rather
+    /// than strictly in priority order.
+    pub fn with_lane_scheduler(mut self, lane_scheduler: WeightedLaneScheduler) -> Self {
+        self.lane_scheduler = Some(Arc::new(Mutex::new(lane_scheduler)));
+        self
+    }
+
     /// Push an element onto the queue and update metrics
     #[instrument(skip(self), ret, fields(queue_label=%self.queue_metrics_label), level = "debug")]
     pub async fn push(&self, op: QueueOperation) {
         // increment the metric before pushing onto the queue, because we lose ownership afterwards
         self.get_operation_metric(op.as_ref()).inc();
 
-        self.queue.lock().await.push(Reverse(op));
+        self.queue.lock().await.push(op);
     }
 
     /// Pop an element from the queue and update metrics
@@ -41,15 +123,18 @@ impl OpQueue {
     pub async fn pop_many(&mut self, limit: usize) -> Vec<QueueOperation> {
         self.process_retry_requests().await;
         let mut queue = self.queue.lock().await;
-        let mut popped = vec![];
-        while let Some(Reverse(op)) = queue.pop() {
+        let mut popped = Self::pop_urgent(&mut queue, limit);
+        let remaining = limit - popped.len();
+        popped.extend(match &self.lane_scheduler {
+            Some(lane_scheduler) => {
+                Self::pop_fairly(&mut queue, &mut *lane_scheduler.lock().await, remaining)
+            }
+            None => Self::pop_in_order(&mut queue, remaining),
+        });
+        for op in &popped {
             // even if the metric is decremented here, the operation may fail to process and be re-added to the queue.
             // in those cases, the queue length will look like it has spikes whose sizes are at most `limit`
             self.get_operation_metric(op.as_ref()).dec();
-            popped.push(op);
-            if popped.len() >= limit {
-                break;
-            }
         }
         // This function is called very often by the op_submitter tasks, so only log when there are operations to pop
         // to avoid spamming the logs
@@ -63,6 +148,69 @@ impl OpQueue {
         popped
     }
 
+    /// Pops the urgent operations that are ready to be attempted, in priority order, ahead
+    /// of any other operation
+    fn pop_urgent(queue: &mut QueuedOperations, limit: usize) -> Vec<QueueOperation> {
+        let now = Instant::now();
+        let mut popped = vec![];
+        while popped.len() < limit {
+            let is_ready = queue.urgent.peek().map_or(false, |Reverse(op)| {
+                op.next_attempt_after().map_or(true, |t| t <= now)
+            });
+            if !is_ready {
+                break;
+            }
+            popped.extend(queue.urgent.pop().map(|Reverse(op)| op));
+        }
+        popped
+    }
+
+    fn pop_in_order(queue: &mut QueuedOperations, limit: usize) -> Vec<QueueOperation> {
+        let mut popped = vec![];
+        while popped.len() < limit {
+            let Some(origin) = queue.first_lane() else {
+                break;
+            };
+            popped.extend(queue.pop_lane(origin));
+        }
+        popped
+    }
+
+    /// Pops the operations that are ready to be attempted by alternating between origin
+    /// lanes according to the scheduler weights. Any remaining capacity is filled with
+    /// operations that are not ready yet, in priority order.
+    fn pop_fairly(
+        queue: &mut QueuedOperations,
+        lane_scheduler: &mut WeightedLaneScheduler,
+        limit: usize,
+    ) -> Vec<QueueOperation> {
+        let now = Instant::now();
+        let mut popped = vec![];
+        while popped.len() < limit {
+            let Some(origin) = lane_scheduler.next_lane(queue.ready_lanes(now)) else {
+                break;
+            };
+            popped.extend(queue.pop_lane(origin));
+        }
+        let remaining = limit - popped.len();
+        popped.extend(Self::pop_in_order(queue, remaining));
+        popped
+    }
+
+    /// Whether there are no operations in the queue
+    pub async fn is_empty(&self) -> bool {
+        self.queue.lock().await.is_empty()
+    }
+
+    /// Remove every operation from the queue and update metrics
+    pub async fn drain(&self) -> Vec<QueueOperation> {
+        let drained: Vec<_> = self.queue.lock().await.drain().collect();
+        for op in &drained {
+            self.get_operation_metric(op.as_ref()).dec();
+        }
+        drained
+    }
+
     pub async fn process_retry_requests(&mut self) {
         // TODO: could rate-limit ourselves here, but we expect the volume of messages over this channel to
         // be very low.
@@ -76,23 +224,20 @@ impl OpQueue {
             return;
         }
         let mut queue = self.queue.lock().await;
-        let mut reprioritized_queue: BinaryHeap<_> = queue
-            .drain()
-            .map(|Reverse(mut op)| {
-                // Can check for equality here because of the PartialEq implementation for MessageRetryRequest,
-                // but can't use `contains` because the types are different
-                if message_retry_requests.iter().any(|r| r == op) {
-                    info!(
-                        operation = %op,
-                        queue_label = %self.queue_metrics_label,
-                        "Retrying OpQueue operation"
-                    );
-                    op.reset_attempts()
-                }
-                Reverse(op)
-            })
-            .collect();
-        queue.append(&mut reprioritized_queue);
+        let ops: Vec<_> = queue.drain().collect();
+        for mut op in ops {
+            // Can check for equality here because of the PartialEq implementation for MessageRetryRequest,
+            // but can't use `contains` because the types are different
+            if message_retry_requests.iter().any(|r| r == op) {
+                info!(
+                    operation = %op,
+                    queue_label = %self.queue_metrics_label,
+                    "Retrying OpQueue operation"
+                );
+                op.reset_attempts()
+            }
+            queue.push(op);
+        }
     }
 
     /// Get the metric associated with this operation
@@ -104,23 +249,35 @@ impl OpQueue {
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use super::*;
     use hyperlane_core::{
-        HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain, PendingOperationResult,
-        TryBatchAs, TxOutcome, H256, U256,
+        BatchItem, ChainCommunicationError, ChainResult, HyperlaneDomain, HyperlaneMessage,
+        KnownHyperlaneDomain, Mailbox, MessageSubmissionData, PendingOperationResult, TryBatchAs,
+        TxOutcome, H256, U256,
     };
     use std::{
         collections::VecDeque,
+        sync::Mutex as StdMutex,
         time::{Duration, Instant},
     };
     use tokio::sync;
 
     #[derive(Debug, Clone)]
-    struct MockPendingOperation {
-        id: H256,
+    pub(crate) struct MockPendingOperation {
+        pub(crate) id: H256,
         seconds_to_next_attempt: u64,
+        /// Overrides `seconds_to_next_attempt` for the operation to be ready
+        ready_since: Option<Duration>,
+        urgent: bool,
+        origin_domain_id: u32,
         destination_domain: HyperlaneDomain,
+        /// Mailbox the operation is batched and its delivery checked with
+        pub(crate) mailbox: Option<Arc<dyn Mailbox>>,
+        /// Whether the operation was seen delivered, once checked
+        pub(crate) delivered: Arc<StdMutex<Option<bool>>>,
+        /// Whether the operation's simulation succeeds
+        pub(crate) simulates: bool,
     }
 
     impl MockPendingOperation {
@@ -128,12 +285,58 @@ mod test {
             Self {
                 id: H256::random(),
                 seconds_to_next_attempt,
+                ready_since: None,
+                urgent: false,
+                origin_domain_id: 0,
                 destination_domain,
+                mailbox: None,
+                delivered: Default::default(),
+                simulates: true,
+            }
+        }
+
+        fn ready(
+            seconds_since_ready: u64,
+            origin_domain_id: u32,
+            destination_domain: HyperlaneDomain,
+        ) -> Self {
+            Self {
+                ready_since: Some(Duration::from_secs(seconds_since_ready)),
+                origin_domain_id,
+                ..Self::new(0, destination_domain)
+            }
+        }
+
+        /// An operation from test1 to test2, ready to be submitted or
+        /// confirmed with `mailbox`
+        pub(crate) fn with_mailbox(mailbox: Option<Arc<dyn Mailbox>>) -> Self {
+            Self {
+                mailbox,
+                ..Self::ready(
+                    1,
+                    KnownHyperlaneDomain::Test1 as u32,
+                    HyperlaneDomain::Known(KnownHyperlaneDomain::Test2),
+                )
             }
         }
     }
 
-    impl TryBatchAs<HyperlaneMessage> for MockPendingOperation {}
+    impl TryBatchAs<HyperlaneMessage> for MockPendingOperation {
+        fn try_batch(&self) -> ChainResult<BatchItem<HyperlaneMessage>> {
+            let mailbox = self
+                .mailbox
+                .clone()
+                .ok_or(ChainCommunicationError::BatchingFailed)?;
+            Ok(BatchItem::new(
+                HyperlaneMessage::default(),
+                MessageSubmissionData {
+                    metadata: vec![],
+                    gas_limit: U256::zero(),
+                },
+                mailbox,
+            ))
+        }
+    }
 
     #[async_trait::async_trait]
     impl PendingOperation for MockPendingOperation {
@@ -146,15 +349,19 @@ mod test {
         }
 
         fn priority(&self) -> u32 {
-            todo!()
+            0
         }
 
         fn get_operation_labels(&self) -> (String, String) {
             Default::default()
         }
 
+        fn is_urgent(&self) -> bool {
+            self.urgent
+        }
+
         fn origin_domain_id(&self) -> u32 {
-            todo!()
+            self.origin_domain_id
         }
 
         fn destination_domain(&self) -> &HyperlaneDomain {
@@ -162,32 +369,40 @@ mod test {
         }
 
         fn app_context(&self) -> Option<String> {
-            todo!()
+            None
         }
 
         async fn prepare(&mut self) -> PendingOperationResult {
-            todo!()
+            PendingOperationResult::NotReady
+        }
+
+        async fn simulate_submission(&mut self) -> bool {
+            self.simulates
         }
 
         /// Submit this operation to the blockchain and report if it was successful
         /// or not.
-        async fn submit(&mut self) {
-            todo!()
-        }
+        async fn submit(&mut self) {}
 
-        fn set_submission_outcome(&mut self, _outcome: TxOutcome) {
-            todo!()
-        }
+        fn set_submission_outcome(&mut self, _outcome: TxOutcome) {}
 
         fn get_tx_cost_estimate(&self) -> Option<U256> {
-            todo!()
+            None
         }
 
         /// This will be called after the operation has been submitted and is
         /// responsible for checking if the operation has reached a point at
         /// which we consider it safe from reorgs.
         async fn confirm(&mut self) -> PendingOperationResult {
-            todo!()
+            // Delivered operations wait for more confirmations
+            match *self.delivered.lock().unwrap() {
+                Some(true) => PendingOperationResult::Confirm,
+                _ => PendingOperationResult::NotReady,
+            }
+        }
+
+        fn set_delivered(&mut self, delivered: bool) {
+            *self.delivered.lock().unwrap() = Some(delivered);
         }
 
         fn set_operation_outcome(
@@ -195,10 +410,12 @@ mod test {
             _submission_outcome: TxOutcome,
             _submission_estimated_cost: U256,
         ) {
-            todo!()
         }
 
         fn next_attempt_after(&self) -> Option<Instant> {
+            if let Some(ready_since) = self.ready_since {
+                return Instant::now().checked_sub(ready_since);
+            }
             Some(
                 Instant::now()
                     .checked_add(Duration::from_secs(self.seconds_to_next_attempt))
@@ -206,13 +423,12 @@ mod test {
             )
         }
 
-        fn set_next_attempt_after(&mut self, _delay: Duration) {
-            todo!()
+        fn set_next_attempt_after(&mut self, delay: Duration) {
+            self.ready_since = None;
+            self.seconds_to_next_attempt = delay.as_secs();
         }
 
-        fn set_retries(&mut self, _retries: u32) {
-            todo!()
-        }
+        fn set_retries(&mut self, _retries: u32) {}
     }
 
     fn dummy_metrics_and_label() -> (IntGaugeVec, String) {
@@ -345,4 +561,122 @@ mod test {
         assert_eq!(popped[3], op_ids[0]);
         assert_eq!(popped[4], op_ids[1]);
     }
+
+    #[tokio::test]
+    async fn test_pops_ready_operations_fairly_across_lanes() {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let broadcaster = sync::broadcast::Sender::new(100);
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        let mut op_queue = OpQueue::new(
+            metrics,
+            queue_metrics_label,
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        )
+        .with_lane_scheduler(WeightedLaneScheduler::new(
+            destination_domain.id(),
+            Default::default(),
+        ));
+
+        let ops = vec![
+            MockPendingOperation::ready(4, 1, destination_domain.clone()),
+            MockPendingOperation::ready(3, 1, destination_domain.clone()),
+            MockPendingOperation::ready(2, 1, destination_domain.clone()),
+            MockPendingOperation::ready(1, 2, destination_domain.clone()),
+            MockPendingOperation {
+                origin_domain_id: 2,
+                ..MockPendingOperation::new(10, destination_domain.clone())
+            },
+        ];
+        let op_ids: Vec<_> = ops.iter().map(|op| op.id).collect();
+        for op in ops {
+            op_queue.push(Box::new(op)).await;
+        }
+
+        // The lane of origin 2 isn't starved by the older operations of origin 1
+        let popped: Vec<_> = op_queue
+            .pop_many(3)
+            .await
+            .iter()
+            .map(|op| op.id())
+            .collect();
+        assert_eq!(popped, vec![op_ids[0], op_ids[3], op_ids[1]]);
+        // Operations that aren't ready fill the remaining capacity
+        let popped: Vec<_> = op_queue
+            .pop_many(3)
+            .await
+            .iter()
+            .map(|op| op.id())
+            .collect();
+        assert_eq!(popped, vec![op_ids[2], op_ids[4]]);
+        assert!(op_queue.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_pops_ready_urgent_operations_first() {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let broadcaster = sync::broadcast::Sender::new(100);
+        let mut op_queue = OpQueue::new(
+            metrics,
+            queue_metrics_label,
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        );
+
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        let ops = vec![
+            MockPendingOperation::ready(5, 1, destination_domain.clone()),
+            MockPendingOperation {
+                urgent: true,
+                ..MockPendingOperation::ready(1, 1, destination_domain.clone())
+            },
+            MockPendingOperation {
+                urgent: true,
+                ..MockPendingOperation::new(10, destination_domain.clone())
+            },
+        ];
+        let op_ids: Vec<_> = ops.iter().map(|op| op.id).collect();
+        for op in ops {
+            op_queue.push(Box::new(op)).await;
+        }
+
+        assert_eq!(op_queue.pop().await.unwrap().id(), op_ids[1]);
+        // Urgent operations that aren't ready yet wait until they are
+        let popped: Vec<_> = op_queue
+            .pop_many(3)
+            .await
+            .iter()
+            .map(|op| op.id())
+            .collect();
+        assert_eq!(popped, vec![op_ids[0]]);
+        assert!(!op_queue.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_drain() {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let broadcaster = sync::broadcast::Sender::new(100);
+        let op_queue = OpQueue::new(
+            metrics.clone(),
+            queue_metrics_label.clone(),
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        );
+
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        for seconds_to_next_attempt in 1..=3 {
+            op_queue
+                .push(Box::new(MockPendingOperation::new(
+                    seconds_to_next_attempt,
+                    destination_domain.clone(),
+                )))
+                .await;
+        }
+
+        assert_eq!(op_queue.drain().await.len(), 3);
+        assert!(op_queue.is_empty().await);
+        assert_eq!(
+            metrics
+                .with_label_values(&["", &queue_metrics_label, ""])
+                .get(),
+            0
+        );
+    }
 }
@@ -1,5 +1,6 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use derive_new::new;
 use futures::future::join_all;
@@ -8,22 +9,25 @@ use hyperlane_core::total_estimated_cost;
 use prometheus::{IntCounter, IntGaugeVec};
 use tokio::sync::broadcast::Sender;
 use tokio::sync::mpsc;
+use tokio::sync::watch;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
 use tokio_metrics::TaskMonitor;
 use tracing::{debug, info_span, instrument, instrument::Instrumented, trace, Instrument};
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 
 use hyperlane_base::CoreMetrics;
 use hyperlane_core::{
     BatchItem, ChainCommunicationError, ChainResult, HyperlaneDomain, HyperlaneDomainProtocol,
-    HyperlaneMessage, PendingOperationResult, QueueOperation, TxOutcome,
+    HyperlaneMessage, PendingOperationResult, QueueOperation, TxOutcome, H256,
 };
 
 use crate::msg::pending_message::CONFIRM_DELAY;
 use crate::server::MessageRetryRequest;
+use crate::settings::LaneWeights;
 
+use super::lane_scheduler::WeightedLaneScheduler;
 use super::op_queue::OpQueue;
 
 /// SerialSubmitter accepts operations over a channel. It is responsible for
@@ -85,6 +89,21 @@ pub struct SerialSubmitter {
     metrics: SerialSubmitterMetrics,
     /// Max batch size for submitting messages
     max_batch_size: u32,
+    /// Max number of transactions submitted but not confirmed yet. If not
+    /// set, operations are submitted as soon as they are prepared.
+    max_inflight_transactions: Option<u32>,
+    /// Max number of deliveries checked in a single call when confirming
+    /// operations. If not set, each operation checks its own delivery.
+    confirmation_batch_size: Option<u32>,
+    /// Weights for fairly scheduling operations across origins. If not set,
+    /// operations are processed strictly in priority order.
+    lane_weights: Option<LaneWeights>,
+    /// Set once the relayer is shutting down. New operations are then no
+    /// longer prepared or submitted, and the ones submitted are confirmed
+    /// before the state of every queued operation is persisted.
+    shutdown: watch::Receiver<bool>,
+    /// How long to keep confirming submitted operations once shutting down
+    shutdown_timeout: Duration,
     /// tokio task monitor
     task_monitor: TaskMonitor,
 }
@@ -106,28 +125,55 @@ impl SerialSubmitter {
             rx: rx_prepare,
             retry_tx,
             max_batch_size,
+            max_inflight_transactions,
+            confirmation_batch_size,
+            lane_weights,
+            shutdown,
+            shutdown_timeout,
             task_monitor,
         } = self;
-        let prepare_queue = OpQueue::new(
+        let inflight = InflightTransactions::new(max_inflight_transactions);
+        let mut prepare_queue = OpQueue::new(
             metrics.submitter_queue_length.clone(),
             "prepare_queue".to_string(),
             Arc::new(Mutex::new(retry_tx.subscribe())),
         );
-        let submit_queue = OpQueue::new(
+        let mut submit_queue = OpQueue::new(
             metrics.submitter_queue_length.clone(),
             "submit_queue".to_string(),
             Arc::new(Mutex::new(retry_tx.subscribe())),
         );
+        if let Some(lane_weights) = lane_weights {
+            prepare_queue = prepare_queue.with_lane_scheduler(WeightedLaneScheduler::new(
+                domain.id(),
+                lane_weights.clone(),
+            ));
+            submit_queue = submit_queue
+                .with_lane_scheduler(WeightedLaneScheduler::new(domain.id(), lane_weights));
+        }
         let confirm_queue = OpQueue::new(
             metrics.submitter_queue_length.clone(),
             "confirm_queue".to_string(),
             Arc::new(Mutex::new(retry_tx.subscribe())),
         );
 
-        let tasks = [
+        let queues = [
+            prepare_queue.clone(),
+            submit_queue.clone(),
+            confirm_queue.clone(),
+        ];
+        // Intake stops as soon as the relayer is shutting down, while
+        // confirmation only stops once nothing submitted is left to confirm
+        let (draining_tx, draining_rx) = watch::channel(false);
+        let intake_tasks = [
             tokio::spawn(TaskMonitor::instrument(
                 &task_monitor,
-                receive_task(domain.clone(), rx_prepare, prepare_queue.clone()),
+                receive_task(
+                    domain.clone(),
+                    rx_prepare,
+                    prepare_queue.clone(),
+                    shutdown.clone(),
+                ),
             )),
             tokio::spawn(TaskMonitor::instrument(
                 &task_monitor,
@@ -138,6 +184,7 @@ impl SerialSubmitter {
                     confirm_queue.clone(),
                     max_batch_size,
                     metrics.clone(),
+                    shutdown.clone(),
                 ),
             )),
             tokio::spawn(TaskMonitor::instrument(
@@ -147,39 +194,117 @@ impl SerialSubmitter {
                     submit_queue,
                     confirm_queue.clone(),
                     max_batch_size,
+                    inflight.clone(),
                     metrics.clone(),
-                ),
-            )),
-            tokio::spawn(TaskMonitor::instrument(
-                &task_monitor,
-                confirm_task(
-                    domain.clone(),
-                    prepare_queue,
-                    confirm_queue,
-                    max_batch_size,
-                    metrics,
+                    shutdown,
                 ),
             )),
         ];
+        let mut confirm = tokio::spawn(TaskMonitor::instrument(
+            &task_monitor,
+            confirm_task(
+                domain.clone(),
+                prepare_queue,
+                confirm_queue,
+                max_batch_size,
+                confirmation_batch_size,
+                inflight,
+                metrics,
+                draining_rx,
+            ),
+        ));
 
-        if let Err(err) = try_join_all(tasks).await {
-            tracing::error!(
-                error=?err,
-                ?domain,
-                "SerialSubmitter task panicked for domain"
-            );
+        tokio::select! {
+            res = try_join_all(intake_tasks) => {
+                if let Err(err) = res {
+                    error!(
+                        error=?err,
+                        ?domain,
+                        "SerialSubmitter task panicked for domain"
+                    );
+                    return;
+                }
+            }
+            res = &mut confirm => {
+                // The confirm task only returns once draining, so it panicked
+                error!(
+                    error=?res.err(),
+                    ?domain,
+                    "SerialSubmitter task panicked for domain"
+                );
+                return;
+            }
         }
+
+        info!(
+            ?domain,
+            "Stopped submitting operations, confirming the ones submitted"
+        );
+        let _ = draining_tx.send(true);
+        match timeout(shutdown_timeout, &mut confirm).await {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                error!(
+                    error=?err,
+                    ?domain,
+                    "SerialSubmitter task panicked for domain"
+                );
+            }
+            Err(_) => {
+                confirm.abort();
+                warn!(
+                    ?domain,
+                    ?shutdown_timeout,
+                    "Timed out confirming submitted operations"
+                );
+            }
+        }
+        persist_queued_operations(&domain, &queues).await;
     }
 }
 
+/// Persist the state of the operations left in the queues, so they are
+/// resumed where they were left off once the relayer restarts. Operations
+/// still in the confirm queue were submitted but not confirmed, so they are
+/// logged for their submission to be followed up on.
+#[instrument(skip_all, fields(%domain))]
+async fn persist_queued_operations(domain: &HyperlaneDomain, queues: &[OpQueue; 3]) {
+    let [prepare_queue, submit_queue, confirm_queue] = queues;
+    let mut persisted = 0;
+    for op in confirm_queue.drain().await {
+        warn!(
+            ?op,
+            "Operation submitted but not confirmed before shutting down"
+        );
+        op.persist();
+        persisted += 1;
+    }
+    for queue in [prepare_queue, submit_queue] {
+        for op in queue.drain().await {
+            op.persist();
+            persisted += 1;
+        }
+    }
+    info!(persisted, "Persisted queued operations");
+}
+
 #[instrument(skip_all, fields(%domain))]
 async fn receive_task(
     domain: HyperlaneDomain,
     mut rx: mpsc::UnboundedReceiver<QueueOperation>,
     prepare_queue: OpQueue,
+    mut shutdown: watch::Receiver<bool>,
 ) {
-    // Pull any messages sent to this submitter
-    while let Some(op) = rx.recv().await {
+    // Pull any messages sent to this submitter until shutting down
+    loop {
+        let op = tokio::select! {
+            biased;
+            _ = shutdown.wait_for(|shutdown| *shutdown) => break,
+            op = rx.recv() => op,
+        };
+        let Some(op) = op else {
+            break;
+        };
         trace!(?op, "Received new operation");
         // make sure things are getting wired up correctly; if this works in testing it
         // should also be valid in production.
@@ -196,10 +321,11 @@ async fn prepare_task(
     confirm_queue: OpQueue,
     max_batch_size: u32,
     metrics: SerialSubmitterMetrics,
+    shutdown: watch::Receiver<bool>,
 ) {
     // Prepare at most `max_batch_size` ops at a time to avoid getting rate-limited
     let ops_to_prepare = max_batch_size as usize;
-    loop {
+    while !*shutdown.borrow() {
         // Pop messages here according to the configured batch.
         let mut batch = prepare_queue.pop_many(ops_to_prepare).await;
         if batch.is_empty() {
@@ -228,7 +354,12 @@ async fn prepare_task(
         for (op, prepare_result) in batch.into_iter().zip(res.into_iter()) {
             match prepare_result {
                 PendingOperationResult::Success => {
-                    debug!(?op, "Operation prepared");
+                    debug!(
+                        ?op,
+                        message_id = ?op.id(),
+                        operation_status = "prepared",
+                        "Operation prepared"
+                    );
                     metrics.ops_prepared.inc();
                     // TODO: push multiple messages at once
                     submit_queue.push(op).await;
@@ -244,7 +375,12 @@ async fn prepare_task(
                     metrics.ops_dropped.inc();
                 }
                 PendingOperationResult::Confirm => {
-                    debug!(?op, "Pushing operation to confirm queue");
+                    debug!(
+                        ?op,
+                        message_id = ?op.id(),
+                        operation_status = "confirming",
+                        "Pushing operation to confirm queue"
+                    );
                     confirm_queue.push(op).await;
                 }
             }
@@ -262,40 +398,76 @@ async fn submit_task(
     mut submit_queue: OpQueue,
     mut confirm_queue: OpQueue,
     max_batch_size: u32,
+    inflight: InflightTransactions,
     metrics: SerialSubmitterMetrics,
+    shutdown: watch::Receiver<bool>,
 ) {
     let recv_limit = max_batch_size as usize;
-    loop {
-        let mut batch = submit_queue.pop_many(recv_limit).await;
+    while !*shutdown.borrow() {
+        if inflight.is_full() {
+            // Wait for submitted transactions to be confirmed rather than
+            // chaining more nonces of the signer on top of them
+            sleep(Duration::from_millis(100)).await;
+            continue;
+        }
+        let batch = submit_queue.pop_many(recv_limit).await;
 
-        match batch.len().cmp(&1) {
-            std::cmp::Ordering::Less => {
-                // The queue is empty, so give some time before checking again to prevent burning CPU
-                sleep(Duration::from_millis(100)).await;
-                continue;
+        if batch.is_empty() {
+            // The queue is empty, so give some time before checking again to prevent burning CPU
+            sleep(Duration::from_millis(100)).await;
+            continue;
+        }
+        for batch in split_by_tenant(batch) {
+            // Urgent operations may be submitted with bumped fees, which batches
+            // aren't, so they are submitted on their own ahead of the batch
+            let (urgent, mut batch): (Vec<_>, Vec<_>) =
+                batch.into_iter().partition(|op| op.is_urgent());
+            for op in urgent {
+                submit_single_operation(op, &mut confirm_queue, &inflight, &metrics).await;
             }
-            std::cmp::Ordering::Equal => {
+            if batch.len() == 1 {
                 let op = batch.pop().unwrap();
-                submit_single_operation(op, &mut confirm_queue, &metrics).await;
-            }
-            std::cmp::Ordering::Greater => {
+                submit_single_operation(op, &mut confirm_queue, &inflight, &metrics).await;
+            } else if !batch.is_empty() {
                 OperationBatch::new(batch, domain.clone())
-                    .submit(&mut confirm_queue, &metrics)
+                    .submit(&mut confirm_queue, &inflight, &metrics)
                     .await;
             }
         }
     }
 }
 
-#[instrument(skip(confirm_queue, metrics), ret, level = "debug")]
+/// Split a batch into one per tenant, since the operations of each tenant are
+/// signed by a different key
+fn split_by_tenant(batch: Vec<QueueOperation>) -> Vec<Vec<QueueOperation>> {
+    let mut batches: Vec<(Option<String>, Vec<QueueOperation>)> = vec![];
+    for op in batch {
+        let tenant = op.tenant().map(str::to_owned);
+        match batches.iter_mut().find(|(t, _)| *t == tenant) {
+            Some((_, ops)) => ops.push(op),
+            None => batches.push((tenant, vec![op])),
+        }
+    }
+    batches.into_iter().map(|(_, ops)| ops).collect()
+}
+
+#[instrument(skip(confirm_queue, inflight, metrics), ret, level = "debug")]
 async fn submit_single_operation(
     mut op: QueueOperation,
     confirm_queue: &mut OpQueue,
+    inflight: &InflightTransactions,
     metrics: &SerialSubmitterMetrics,
 ) {
     let destination = op.destination_domain().clone();
+    inflight.reserve().await;
     op.submit().await;
-    debug!(?op, "Operation submitted");
+    inflight.insert([op.id()]);
+    debug!(
+        ?op,
+        message_id = ?op.id(),
+        operation_status = "submitted",
+        "Operation submitted"
+    );
     op.set_next_attempt_after(CONFIRM_DELAY);
     confirm_queue.push(op).await;
     metrics.ops_submitted.inc();
@@ -317,25 +489,38 @@ async fn confirm_task(
     prepare_queue: OpQueue,
     mut confirm_queue: OpQueue,
     max_batch_size: u32,
+    confirmation_batch_size: Option<u32>,
+    inflight: InflightTransactions,
     metrics: SerialSubmitterMetrics,
+    draining: watch::Receiver<bool>,
 ) {
-    let recv_limit = max_batch_size as usize;
+    // Pop enough operations for a whole batch of delivery checks
+    let recv_limit = max_batch_size.max(confirmation_batch_size.unwrap_or_default()) as usize;
     loop {
         // Pick the next message to try confirming.
-        let batch = confirm_queue.pop_many(recv_limit).await;
+        let mut batch = confirm_queue.pop_many(recv_limit).await;
 
         if batch.is_empty() {
+            if *draining.borrow() {
+                // Nothing submitted is left to confirm
+                return;
+            }
             // queue is empty so give some time before checking again to prevent burning CPU
             sleep(Duration::from_millis(200)).await;
             continue;
         }
 
+        if let Some(confirmation_batch_size) = confirmation_batch_size {
+            check_deliveries(&mut batch, confirmation_batch_size as usize).await;
+        }
+
         let futures = batch.into_iter().map(|op| {
             confirm_operation(
                 op,
                 domain.clone(),
                 prepare_queue.clone(),
                 confirm_queue.clone(),
+                inflight.clone(),
                 metrics.clone(),
             )
         });
@@ -353,20 +538,68 @@ async fn confirm_task(
     }
 }
 
+/// Check the deliveries of the operations ready to be confirmed a batch at a
+/// time, rather than each operation checking its own. Operations whose batch
+/// couldn't be checked still check their delivery themselves.
+async fn check_deliveries(ops: &mut [QueueOperation], batch_size: usize) {
+    let now = Instant::now();
+    let mut ready = ops
+        .iter_mut()
+        .filter(|op| op.next_attempt_after().map_or(true, |after| now >= after))
+        .collect::<Vec<_>>();
+    for batch in ready.chunks_mut(batch_size) {
+        // We already assume that the relayer submits to a single mailbox per
+        // destination, so any operation's mailbox checks the whole batch
+        let Some(mailbox) = batch
+            .iter()
+            .find_map(|op| op.try_batch().ok())
+            .map(|item| item.mailbox)
+        else {
+            continue;
+        };
+        let ids = batch.iter().map(|op| op.id()).collect::<Vec<_>>();
+        match mailbox.delivered_batch(&ids).await {
+            Ok(delivered) => {
+                for (op, delivered) in batch.iter_mut().zip(delivered) {
+                    op.set_delivered(delivered);
+                }
+            }
+            Err(err) => {
+                warn!(
+                    ?err,
+                    batch_size = ids.len(),
+                    "Failed to check deliveries as a batch, checking them one by one"
+                );
+            }
+        }
+    }
+}
+
 async fn confirm_operation(
     mut op: QueueOperation,
     domain: HyperlaneDomain,
     prepare_queue: OpQueue,
     confirm_queue: OpQueue,
+    inflight: InflightTransactions,
     metrics: SerialSubmitterMetrics,
 ) -> PendingOperationResult {
     trace!(?op, "Confirming operation");
     debug_assert_eq!(*op.destination_domain(), domain);
 
     let operation_result = op.confirm().await;
+    // Once the delivery is on chain its transaction no longer takes up a slot,
+    // even while the delivery waits to be confirmed deep enough
+    if !matches!(operation_result, PendingOperationResult::NotReady) {
+        inflight.remove(&op.id());
+    }
     match operation_result {
         PendingOperationResult::Success => {
-            debug!(?op, "Operation confirmed");
+            debug!(
+                ?op,
+                message_id = ?op.id(),
+                operation_status = "confirmed",
+                "Operation confirmed"
+            );
             metrics.ops_confirmed.inc();
         }
         PendingOperationResult::NotReady | PendingOperationResult::Confirm => {
@@ -384,6 +617,60 @@ async fn confirm_operation(
     operation_result
 }
 
+/// Transactions submitted to the destination whose operations aren't all
+/// confirmed yet. A batch counts as a single transaction, however many
+/// operations it carries.
+#[derive(Debug, Clone)]
+struct InflightTransactions {
+    /// The transaction carrying each operation in flight, numbered in the
+    /// order they were submitted
+    txs: Arc<std::sync::Mutex<(u64, HashMap<H256, u64>)>>,
+    limit: Option<u32>,
+}
+
+impl InflightTransactions {
+    fn new(limit: Option<u32>) -> Self {
+        Self {
+            txs: Default::default(),
+            limit,
+        }
+    }
+
+    fn len(&self) -> usize {
+        let txs = self.txs.lock().unwrap();
+        txs.1.values().collect::<HashSet<_>>().len()
+    }
+
+    /// Whether no more transactions can be submitted
+    fn is_full(&self) -> bool {
+        self.limit
+            .map_or(false, |limit| self.len() >= limit as usize)
+    }
+
+    /// Wait until another transaction can be submitted
+    async fn reserve(&self) {
+        while self.is_full() {
+            sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Record a transaction submitted with the operations of `ids`
+    fn insert(&self, ids: impl IntoIterator<Item = H256>) {
+        if self.limit.is_some() {
+            let mut txs = self.txs.lock().unwrap();
+            let (next_tx, ops) = &mut *txs;
+            ops.extend(ids.into_iter().map(|id| (id, *next_tx)));
+            *next_tx += 1;
+        }
+    }
+
+    /// Stop counting an operation that was confirmed or is retried, and its
+    /// transaction once none of its operations are left
+    fn remove(&self, id: &H256) {
+        self.txs.lock().unwrap().1.remove(id);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SerialSubmitterMetrics {
     submitter_queue_length: IntGaugeVec,
@@ -426,9 +713,32 @@ struct OperationBatch {
 }
 
 impl OperationBatch {
-    async fn submit(self, confirm_queue: &mut OpQueue, metrics: &SerialSubmitterMetrics) {
+    async fn submit(
+        mut self,
+        confirm_queue: &mut OpQueue,
+        inflight: &InflightTransactions,
+        metrics: &SerialSubmitterMetrics,
+    ) {
+        // Operations whose simulation fails are left out of the batch, and
+        // confirmed like operations that failed when submitted serially
+        let mut operations = Vec::with_capacity(self.operations.len());
+        for mut op in self.operations {
+            if op.simulate_submission().await {
+                operations.push(op);
+            } else {
+                op.set_next_attempt_after(CONFIRM_DELAY);
+                confirm_queue.push(op).await;
+            }
+        }
+        self.operations = operations;
+        if self.operations.is_empty() {
+            return;
+        }
+
+        inflight.reserve().await;
         match self.try_submit_as_batch(metrics).await {
             Ok(outcome) => {
+                inflight.insert(self.operations.iter().map(|op| op.id()));
                 info!(outcome=?outcome, batch_size=self.operations.len(), batch=?self.operations, "Submitted transaction batch");
                 let total_estimated_cost = total_estimated_cost(&self.operations);
                 for mut op in self.operations {
@@ -442,7 +752,7 @@ impl OperationBatch {
                 warn!(error=?e, batch=?self.operations, "Error when submitting batch. Falling back to serial submission.");
             }
         }
-        self.submit_serially(confirm_queue, metrics).await;
+        self.submit_serially(confirm_queue, inflight, metrics).await;
     }
 
     #[instrument(skip(metrics), ret, level = "debug")]
@@ -467,9 +777,205 @@ impl OperationBatch {
         Ok(outcome)
     }
 
-    async fn submit_serially(self, confirm_queue: &mut OpQueue, metrics: &SerialSubmitterMetrics) {
+    async fn submit_serially(
+        self,
+        confirm_queue: &mut OpQueue,
+        inflight: &InflightTransactions,
+        metrics: &SerialSubmitterMetrics,
+    ) {
         for op in self.operations.into_iter() {
-            submit_single_operation(op, confirm_queue, metrics).await;
+            submit_single_operation(op, confirm_queue, inflight, metrics).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex as StdMutex;
+
+    use hyperlane_core::{KnownHyperlaneDomain, Mailbox, PendingOperation, U256};
+    use hyperlane_test::mocks::MockMailboxContract;
+    use prometheus::Registry;
+
+    use super::*;
+    use crate::msg::op_queue::test::MockPendingOperation;
+
+    /// A mailbox on which the messages of `delivered` were delivered, and
+    /// which fails to check the deliveries of the others
+    fn mailbox(delivered: Vec<H256>, undelivered: Vec<H256>) -> Arc<dyn Mailbox> {
+        let mut mailbox = MockMailboxContract::new();
+        mailbox.expect__delivered().returning(move |id| {
+            if delivered.contains(&id) {
+                Ok(true)
+            } else if undelivered.contains(&id) {
+                Ok(false)
+            } else {
+                Err(ChainCommunicationError::from_other_str("unknown message"))
+            }
+        });
+        Arc::new(mailbox)
+    }
+
+    fn queue_operations(
+        ops: Vec<MockPendingOperation>,
+    ) -> (Vec<QueueOperation>, Vec<Arc<StdMutex<Option<bool>>>>) {
+        let delivered = ops.iter().map(|op| op.delivered.clone()).collect();
+        (
+            ops.into_iter()
+                .map(|op| Box::new(op) as QueueOperation)
+                .collect(),
+            delivered,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_delivered_batch_checks_each_message() {
+        let (a, b) = (H256::random(), H256::random());
+        let mailbox = mailbox(vec![a], vec![b]);
+        assert_eq!(
+            mailbox.delivered_batch(&[a, b]).await.unwrap(),
+            vec![true, false]
+        );
+        assert!(mailbox.delivered_batch(&[a, H256::random()]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_deliveries() {
+        let ops: Vec<_> = (0..5)
+            .map(|_| MockPendingOperation::with_mailbox(None))
+            .collect();
+        let ids: Vec<_> = ops.iter().map(|op| op.id).collect();
+        // The last batch of two has operations the mailbox fails to check
+        let mailbox = mailbox(vec![ids[0], ids[2]], vec![ids[1]]);
+        let mut ops: Vec<_> = ops
+            .into_iter()
+            .map(|op| MockPendingOperation {
+                mailbox: Some(mailbox.clone()),
+                ..op
+            })
+            .collect();
+        // Operations not ready to be confirmed aren't checked
+        ops[2].set_next_attempt_after(Duration::from_secs(60));
+
+        let (mut ops, delivered) = queue_operations(ops);
+        check_deliveries(&mut ops, 2).await;
+        let delivered: Vec<_> = delivered
+            .iter()
+            .map(|delivered| *delivered.lock().unwrap())
+            .collect();
+        assert_eq!(delivered, vec![Some(true), Some(false), None, None, None]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_leaves_out_failed_simulations() {
+        let mut mailbox = MockMailboxContract::new();
+        mailbox.expect__process_batch().times(1).returning(|batch| {
+            assert_eq!(batch.len(), 2);
+            Ok(TxOutcome {
+                transaction_id: Default::default(),
+                executed: true,
+                gas_used: U256::zero(),
+                gas_price: Default::default(),
+            })
+        });
+        let mailbox: Arc<dyn Mailbox> = Arc::new(mailbox);
+        let operations = [true, false, true]
+            .into_iter()
+            .map(|simulates| {
+                Box::new(MockPendingOperation {
+                    simulates,
+                    ..MockPendingOperation::with_mailbox(Some(mailbox.clone()))
+                }) as QueueOperation
+            })
+            .collect();
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Test2);
+        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let metrics = SerialSubmitterMetrics::new(&core_metrics, &domain);
+        let mut confirm_queue = OpQueue::new(
+            core_metrics.submitter_queue_length(),
+            "confirm_queue".to_owned(),
+            Arc::new(Mutex::new(Sender::new(1).subscribe())),
+        );
+
+        let inflight = InflightTransactions::new(Some(10));
+        OperationBatch::new(operations, domain)
+            .submit(&mut confirm_queue, &inflight, &metrics)
+            .await;
+        assert_eq!(metrics.ops_submitted.get(), 2);
+        // The batch is a single transaction
+        assert_eq!(inflight.len(), 1);
+        // The operation that wasn't submitted is confirmed too
+        assert_eq!(confirm_queue.pop_many(10).await.len(), 3);
+    }
+
+    #[test]
+    fn test_inflight_transactions() {
+        let ids: Vec<_> = (0..4).map(H256::from_low_u64_be).collect();
+        let inflight = InflightTransactions::new(Some(2));
+        inflight.insert([ids[0], ids[1], ids[2]]);
+        assert_eq!(inflight.len(), 1);
+        assert!(!inflight.is_full());
+        inflight.insert([ids[3]]);
+        assert!(inflight.is_full());
+
+        // A batch is in flight until all of its operations are confirmed
+        inflight.remove(&ids[0]);
+        inflight.remove(&ids[1]);
+        assert!(inflight.is_full());
+        inflight.remove(&ids[2]);
+        assert_eq!(inflight.len(), 1);
+        assert!(!inflight.is_full());
+
+        let unlimited = InflightTransactions::new(None);
+        unlimited.insert([ids[0]]);
+        assert_eq!(unlimited.len(), 0);
+        assert!(!unlimited.is_full());
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_delivery_leaves_inflight_transactions() {
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Test2);
+        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let metrics = SerialSubmitterMetrics::new(&core_metrics, &domain);
+        let queue = |name: &str| {
+            OpQueue::new(
+                core_metrics.submitter_queue_length(),
+                name.to_owned(),
+                Arc::new(Mutex::new(Sender::new(1).subscribe())),
+            )
+        };
+        let (prepare_queue, mut confirm_queue) = (queue("prepare_queue"), queue("confirm_queue"));
+
+        for delivered in [false, true] {
+            let inflight = InflightTransactions::new(Some(1));
+            let mut op = MockPendingOperation::with_mailbox(None);
+            inflight.insert([op.id]);
+            op.set_delivered(delivered);
+            confirm_operation(
+                Box::new(op),
+                domain.clone(),
+                prepare_queue.clone(),
+                confirm_queue.clone(),
+                inflight.clone(),
+                metrics.clone(),
+            )
+            .await;
+            // Only the delivery seen on chain, still waiting for more
+            // confirmations, releases its slot
+            assert_eq!(inflight.is_full(), !delivered);
+            assert_eq!(confirm_queue.pop_many(10).await.len(), 1);
         }
     }
+
+    #[tokio::test]
+    async fn test_check_deliveries_without_mailbox() {
+        let (mut ops, delivered) = queue_operations(vec![
+            MockPendingOperation::with_mailbox(None),
+            MockPendingOperation::with_mailbox(None),
+        ]);
+        check_deliveries(&mut ops, 10).await;
+        assert!(delivered
+            .iter()
+            .all(|delivered| delivered.lock().unwrap().is_none()));
+    }
 }
@@ -1,7 +1,7 @@
 use std::{
     fmt::{Debug, Formatter},
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use async_trait::async_trait;
@@ -9,16 +9,28 @@ use derive_new::new;
 use eyre::Result;
 use hyperlane_base::{db::HyperlaneRocksDB, CoreMetrics};
 use hyperlane_core::{
-    gas_used_by_operation, make_op_try, BatchItem, ChainCommunicationError, ChainResult,
-    HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, MessageSubmissionData,
-    PendingOperation, PendingOperationResult, TryBatchAs, TxOutcome, H256, U256,
+    gas_used_by_operation, make_op_try, utils::fmt_address_for_domain, BatchItem,
+    ChainCommunicationError, ChainResult, DeadLetter, DeadLetterReason, FixedPointNumber,
+    HyperlaneChain, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Mailbox,
+    MessageSubmissionData, OperationId, PendingOperation, PendingOperationResult,
+    ProcessSimulation, TryBatchAs, TxCostEstimate, TxOutcome, H256, U256,
 };
-use prometheus::{IntCounter, IntGauge};
+use prometheus::{IntCounter, IntCounterVec, IntGauge};
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use super::{
+    confirmation_depth::ConfirmationDepths,
+    deadline::{DeadlineStatus, DeliveryDeadlines},
+    delivery_slo::DeliverySlo,
+    expiry::MessageExpiryPolicy,
+    fixed_gas_limit::{FixedGasLimitComparison, FixedGasLimits},
+    gas_overhead::GasOverheads,
     gas_payment::GasPaymentEnforcer,
-    metadata::{BaseMetadataBuilder, MessageMetadataBuilder, MetadataBuilder},
+    metadata::{
+        apply_hook_gas_limit, BaseMetadataBuilder, HookMetadataBuilder, MessageMetadataBuilder,
+        MetadataBuilder,
+    },
+    tenant::Tenant,
 };
 
 pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
@@ -29,8 +41,21 @@ pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
     Duration::from_secs(60)
 };
 
+/// How often a message awaiting a quorum of validator signatures checks
+/// whether one is available
+const QUORUM_CHECK_DELAY: Duration = Duration::from_secs(10);
+
+/// Longest a message nearing its delivery deadline waits between attempts
+const URGENT_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// How long a message whose recipient is not a contract waits before
+/// checking again whether one was deployed
+const RECIPIENT_NOT_CONTRACT_DELAY: Duration = Duration::from_secs(60 * 60);
+
 /// The message context contains the links needed to submit a message. Each
-/// instance is for a unique origin -> destination pairing.
+/// instance is for a unique origin -> destination pairing, and tenant if the
+/// relayer has any.
+#[derive(Clone)]
 pub struct MessageContext {
     /// Mailbox on the destination chain.
     pub destination_mailbox: Arc<dyn Mailbox>,
@@ -42,16 +67,43 @@ pub struct MessageContext {
     /// Used to determine if messages from the origin have made sufficient gas
     /// payments.
     pub origin_gas_payment_enforcer: Arc<GasPaymentEnforcer>,
+    /// Used to construct the post-dispatch hook metadata of apps that require
+    /// it for quoting and delivery.
+    pub hook_metadata_builder: Arc<HookMetadataBuilder>,
+    /// Static gas added to the estimates of apps that estimation underreports.
+    pub gas_overheads: Arc<GasOverheads>,
+    /// Gas limits of apps whose estimates can't be relied on.
+    pub fixed_gas_limits: Arc<FixedGasLimits>,
+    /// Decides when messages from the origin are moved to the dead-letter
+    /// store instead of being retried.
+    pub expiry_policy: Arc<MessageExpiryPolicy>,
+    /// Delivery deadlines of the messages from the origin. Messages nearing
+    /// theirs are retried sooner and submitted with higher fees.
+    pub delivery_deadlines: Arc<DeliveryDeadlines>,
+    /// Decides how many confirmations deliveries to the destination wait for.
+    pub confirmation_depths: Arc<ConfirmationDepths>,
+    /// Delivery time SLO metrics of the lane.
+    pub delivery_slo: Arc<DeliverySlo>,
+    /// Provider of the origin chain, used to determine when a message was
+    /// dispatched. Only set if messages from the origin can expire or have a
+    /// delivery deadline.
+    pub origin_provider: Option<Arc<dyn HyperlaneProvider>>,
     /// Hard limit on transaction gas when submitting a transaction to the
     /// destination.
     pub transaction_gas_limit: Option<U256>,
     pub metrics: MessageSubmissionMetrics,
+    /// Tenant the messages are relayed for, whose signer, gas payment
+    /// policies and spend budgets are used.
+    pub tenant: Option<Arc<Tenant>>,
 }
 
 /// A message that the submitter can and should try to submit.
 #[derive(new)]
 pub struct PendingMessage {
     pub message: HyperlaneMessage,
+    /// Id the message is logged with, persisted when the message was indexed
+    #[new(value = "OperationId::random()")]
+    pub operation_id: OperationId,
     ctx: Arc<MessageContext>,
     app_context: Option<String>,
     #[new(default)]
@@ -66,6 +118,26 @@ pub struct PendingMessage {
     next_attempt_after: Option<Instant>,
     #[new(default)]
     submission_outcome: Option<TxOutcome>,
+    /// Unix timestamp in seconds of the block the message was dispatched in,
+    /// cached once fetched from the origin
+    #[new(default)]
+    dispatched_at: Option<u64>,
+    /// How close the message is to its delivery deadline, if it has one and
+    /// when it was dispatched is known
+    #[new(default)]
+    deadline: Option<DeadlineStatus>,
+    /// Whether the missed delivery deadline of the message was counted,
+    /// persisted so that it's only counted once across restarts
+    #[new(default)]
+    deadline_missed: bool,
+    /// Whether the message was delivered, if checked along with the
+    /// deliveries of other messages before confirming it
+    #[new(default)]
+    delivered: Option<bool>,
+    /// Why the delivery simulation of the message failed when it was last
+    /// about to be submitted, handled when confirming it
+    #[new(default)]
+    failed_simulation: Option<ProcessSimulation>,
 }
 
 impl Debug for PendingMessage {
@@ -83,8 +155,8 @@ impl Debug for PendingMessage {
                 }
             })
             .unwrap_or(0);
-        write!(f, "PendingMessage {{ num_retries: {}, since_last_attempt_s: {last_attempt}, next_attempt_after_s: {next_attempt}, message: {:?} }}",
-               self.num_retries, self.message)
+        write!(f, "PendingMessage {{ operation_id: {}, num_retries: {}, since_last_attempt_s: {last_attempt}, next_attempt_after_s: {next_attempt}, message: {:?} }}",
+               self.operation_id, self.num_retries, self.message)
     }
 }
 
@@ -136,7 +208,20 @@ impl PendingOperation for PendingMessage {
         self.app_context.clone()
     }
 
-    #[instrument(skip(self), ret, fields(id=?self.id()), level = "debug")]
+    fn operation_id(&self) -> Option<OperationId> {
+        Some(self.operation_id)
+    }
+
+    fn tenant(&self) -> Option<&str> {
+        self.ctx.tenant.as_ref().map(|tenant| tenant.name.as_str())
+    }
+
+    fn is_urgent(&self) -> bool {
+        self.deadline
+            .map_or(false, |deadline| deadline.is_escalated(unix_timestamp_s()))
+    }
+
+    #[instrument(skip(self), ret, fields(id=?self.id(), op_id=%self.operation_id), level = "debug")]
     async fn prepare(&mut self) -> PendingOperationResult {
         make_op_try!(|| self.on_reprepare());
 
@@ -145,6 +230,19 @@ impl PendingOperation for PendingMessage {
             return PendingOperationResult::NotReady;
         }
 
+        if let Err(err) = self.update_deadline().await {
+            warn!(?err, "Failed to check the delivery deadline of the message");
+        }
+
+        // Until a quorum of validators signed the message, it can neither be
+        // delivered by this relayer nor by another one, so only check whether
+        // one is available
+        if !self.is_quorum_available().await {
+            debug!("Message is awaiting a quorum of validator signatures");
+            self.set_next_attempt_after(QUORUM_CHECK_DELAY);
+            return PendingOperationResult::NotReady;
+        }
+
         // If the message has already been processed, e.g. due to another relayer having
         // already processed, then mark it as already-processed, and move on to
         // the next tick.
@@ -162,19 +260,45 @@ impl PendingOperation for PendingMessage {
             return PendingOperationResult::Confirm;
         }
 
+        // Stop retrying messages that are older than their app allows. Only
+        // undelivered messages expire, as one delivered late still is.
+        if op_try!(self.is_expired().await, "checking if message is expired") {
+            op_try!(
+                critical: self.move_to_dead_letter_store(DeadLetterReason::Expired),
+                "moving message to the dead-letter store"
+            );
+            return PendingOperationResult::Drop;
+        }
+
+        // Wait for the budget of the tenant to be replenished before spending more
+        if let Some(tenant) = &self.ctx.tenant {
+            if let Some(wait) = tenant.over_budget(self.message.destination) {
+                info!(
+                    tenant = tenant.name,
+                    ?wait,
+                    "Tenant spent its budget on the destination"
+                );
+                self.next_attempt_after = Some(Instant::now() + wait);
+                return PendingOperationResult::NotReady;
+            }
+        }
+
         let provider = self.ctx.destination_mailbox.provider();
 
-        // We cannot deliver to an address that is not a contract so check and drop if it isn't.
+        // We cannot deliver to an address that is not a contract. One may still be deployed
+        // there, e.g. at a precomputed address, so check again later until the message expires.
         let is_contract = op_try!(
             provider.is_contract(&self.message.recipient).await,
             "checking if message recipient is a contract"
         );
         if !is_contract {
             info!(
-                recipient=?self.message.recipient,
-                "Dropping message because recipient is not a contract"
+                recipient=%fmt_address_for_domain(self.message.destination, self.message.recipient),
+                delay=?RECIPIENT_NOT_CONTRACT_DELAY,
+                "Recipient is not a contract, checking again later"
             );
-            return PendingOperationResult::Drop;
+            self.set_next_attempt_after(RECIPIENT_NOT_CONTRACT_DELAY);
+            return PendingOperationResult::NotReady;
         }
 
         let ism_address = op_try!(
@@ -195,6 +319,34 @@ impl PendingOperation for PendingMessage {
             "getting the message metadata builder"
         );
 
+        // Don't try building the metadata of a message verified by a multisig
+        // ISM before a quorum of its validators signed it
+        if self
+            .ctx
+            .metadata_builder
+            .quorum_requirements(&self.message.id())
+            .is_none()
+        {
+            let requirement = op_try!(
+                message_metadata_builder
+                    .quorum_requirement(ism_address, &self.message)
+                    .await,
+                "checking the quorum of the ISM"
+            );
+            if let Some(requirement) = requirement {
+                let builder = &self.ctx.metadata_builder;
+                if !requirement
+                    .is_available(builder.origin_domain(), builder.destination_domain())
+                    .await
+                {
+                    debug!("Message is awaiting a quorum of validator signatures");
+                    builder.record_awaiting_quorum(self.message.id(), requirement);
+                    self.set_next_attempt_after(QUORUM_CHECK_DELAY);
+                    return PendingOperationResult::NotReady;
+                }
+            }
+        }
+
         let Some(metadata) = op_try!(
             message_metadata_builder
                 .build(ism_address, &self.message)
@@ -204,18 +356,67 @@ impl PendingOperation for PendingMessage {
             info!("Could not fetch metadata");
             return self.on_reprepare();
         };
+        // An aggregation ISM may not need the quorum one of its multisig ISMs
+        // lacked
+        self.ctx
+            .metadata_builder
+            .stop_awaiting_quorum(&self.message.id());
 
-        // Estimate transaction costs for the process call. If there are issues, it's
-        // likely that gas estimation has failed because the message is
-        // reverting. This is defined behavior, so we just log the error and
-        // move onto the next tick.
-        let tx_cost_estimate = op_try!(
-            self.ctx
-                .destination_mailbox
-                .process_estimate_costs(&self.message, &metadata)
-                .await,
-            "estimating costs for process call"
-        );
+        let estimate = self
+            .ctx
+            .destination_mailbox
+            .process_estimate_costs(&self.message, &metadata)
+            .await;
+        let tx_cost_estimate = match self.ctx.fixed_gas_limits.gas_limit(&self.message) {
+            // Some recipients revert on static calls, so their estimate is only
+            // compared to the fixed limit they are delivered with.
+            Some(gas_limit) => {
+                let estimate = estimate.ok();
+                let comparison = FixedGasLimitComparison::new(
+                    gas_limit,
+                    estimate.as_ref().map(|estimate| estimate.gas_limit),
+                );
+                debug!(
+                    ?gas_limit,
+                    ?estimate,
+                    comparison = comparison.as_str(),
+                    "Using the fixed gas limit of the message app"
+                );
+                self.ctx.metrics.record_fixed_gas_limit(comparison);
+                TxCostEstimate {
+                    gas_limit,
+                    gas_price: estimate
+                        .map(|estimate| estimate.gas_price)
+                        .unwrap_or_default(),
+                    l2_gas_limit: None,
+                }
+            }
+            // If there are issues, it's likely that gas estimation has failed
+            // because the message is reverting. This is defined behavior, so we
+            // just log the error and move onto the next tick.
+            None => op_try!(
+                estimate.map_err(|err| {
+                    self.ctx.metrics.record_simulation_revert(&err);
+                    err
+                }),
+                "estimating costs for process call"
+            ),
+        };
+
+        // Apps using hook metadata may require a higher gas limit than estimated,
+        // both when checking their payment and when delivering.
+        let tx_cost_estimate = match self.ctx.hook_metadata_builder.build(&self.message) {
+            Some(hook_metadata) => {
+                debug!(?hook_metadata, "Applying hook metadata of message app");
+                apply_hook_gas_limit(tx_cost_estimate, &hook_metadata)
+            }
+            None => tx_cost_estimate,
+        };
+        // Some apps use more gas than estimation reports, e.g. when `handle` is reentrant.
+        let tx_cost_estimate = self
+            .ctx
+            .gas_overheads
+            .apply(tx_cost_estimate, &self.message);
 
         // If the gas payment requirement hasn't been met, move to the next tick.
         let Some(gas_limit) = op_try!(
@@ -250,6 +451,46 @@ impl PendingOperation for PendingMessage {
         PendingOperationResult::Success
     }
 
+    #[instrument]
+    async fn simulate_submission(&mut self) -> bool {
+        let Some(state) = self.submission_data.as_ref() else {
+            return true;
+        };
+        // State may have moved since the message was prepared, so its delivery
+        // is simulated again rather than paying for a reverting transaction.
+        // Apps with a fixed gas limit are skipped as their recipients may
+        // revert on static calls.
+        let simulation = match self.ctx.fixed_gas_limits.gas_limit(&self.message) {
+            Some(_) => Ok(None),
+            None => {
+                self.ctx
+                    .destination_mailbox
+                    .simulate_process(&self.message, &state.metadata, state.gas_limit)
+                    .await
+            }
+        };
+        match simulation {
+            Ok(Some(simulation)) => {
+                self.ctx.metrics.record_process_simulation(&simulation);
+                if !simulation.would_succeed() {
+                    warn!(
+                        ?simulation,
+                        "Not submitting message whose delivery simulation failed"
+                    );
+                    self.failed_simulation = Some(simulation);
+                    return false;
+                }
+            }
+            Ok(None) => {}
+            Err(err) => warn!(
+                ?err,
+                "Failed to simulate message delivery, submitting it anyway"
+            ),
+        }
+        self.failed_simulation = None;
+        true
+    }
+
     #[instrument]
     async fn submit(&mut self) {
         if self.submitted {
@@ -262,17 +503,50 @@ impl PendingOperation for PendingMessage {
             .clone()
             .expect("Pending message must be prepared before it can be submitted");
 
+        if let Err(err) = self.update_deadline().await {
+            warn!(?err, "Failed to check the delivery deadline of the message");
+        }
+
+        if !self.simulate_submission().await {
+            return;
+        }
+
+        // Messages nearing their delivery deadline are submitted with higher
+        // fees to be included sooner.
+        let fee_bump_percent = self
+            .deadline
+            .map_or(0, |deadline| deadline.fee_bump_percent(unix_timestamp_s()));
+        if fee_bump_percent > 0 {
+            info!(
+                fee_bump_percent,
+                "Bumping fees of message nearing its delivery deadline"
+            );
+        }
+
         // We use the estimated gas limit from the prior call to
         // `process_estimate_costs` to avoid a second gas estimation.
         let tx_outcome = self
             .ctx
             .destination_mailbox
-            .process(&self.message, &state.metadata, Some(state.gas_limit))
+            .process_with_fee_bump(
+                &self.message,
+                &state.metadata,
+                Some(state.gas_limit),
+                fee_bump_percent,
+            )
             .await;
         match tx_outcome {
             Ok(outcome) => {
                 self.set_operation_outcome(outcome, state.gas_limit);
             }
+            Err(ChainCommunicationError::TransactionCancelled(tx_hash)) => {
+                // The stuck transaction was replaced by a no-op using the same nonce,
+                // so the message is not delivered and the confirm step will reprepare it.
+                warn!(
+                    ?tx_hash,
+                    "Stuck transaction processing message was cancelled"
+                );
+            }
             Err(e) => {
                 error!(error=?e, "Error when processing message");
             }
@@ -287,6 +561,7 @@ impl PendingOperation for PendingMessage {
         self.submission_data.as_ref().map(|d| d.gas_limit)
     }
 
+    #[instrument(skip(self), fields(id=?self.id(), op_id=%self.operation_id), level = "debug")]
     async fn confirm(&mut self) -> PendingOperationResult {
         make_op_try!(|| {
             // Provider error; just try again later
@@ -299,14 +574,37 @@ impl PendingOperation for PendingMessage {
             return PendingOperationResult::NotReady;
         }
 
-        let is_delivered = op_try!(
-            self.ctx
-                .destination_mailbox
-                .delivered(self.message.id())
-                .await,
-            "Confirming message delivery"
-        );
+        // Deliveries confirmed after the deadline count as missing it too
+        if let Err(err) = self.update_deadline().await {
+            warn!(?err, "Failed to check the delivery deadline of the message");
+        }
+
+        let is_delivered = match self.delivered.take() {
+            Some(delivered) => delivered,
+            None => op_try!(
+                self.ctx
+                    .destination_mailbox
+                    .delivered(self.message.id())
+                    .await,
+                "Confirming message delivery"
+            ),
+        };
         if is_delivered {
+            // Deliveries of some apps are only complete once deep enough in
+            // the chain; until then they may still be reorged out, which
+            // sends them back to the prepare queue
+            let pending_confirmations = op_try!(
+                self.pending_confirmations().await,
+                "Checking delivery confirmations"
+            );
+            if let Some(pending) = pending_confirmations {
+                debug!(
+                    pending_confirmations = pending,
+                    "Waiting for more confirmations of the delivery"
+                );
+                self.set_next_attempt_after(CONFIRM_DELAY);
+                return PendingOperationResult::Confirm;
+            }
             op_try!(
                 critical: self.record_message_process_success(),
                 "recording message process success"
@@ -315,7 +613,13 @@ impl PendingOperation for PendingMessage {
                 submission=?self.submission_outcome,
                 "Message successfully processed"
             );
+            if let Some(tenant) = &self.ctx.tenant {
+                tenant.record_delivery(self.destination_domain());
+            }
+            self.record_delivery_latency().await;
             PendingOperationResult::Success
+        } else if let Some(simulation) = self.failed_simulation.take() {
+            self.on_failed_simulation(simulation)
         } else {
             warn!(
                 tx_outcome=?self.submission_outcome,
@@ -326,6 +630,10 @@ impl PendingOperation for PendingMessage {
         }
     }
 
+    fn set_delivered(&mut self, delivered: bool) {
+        self.delivered = Some(delivered);
+    }
+
     fn set_operation_outcome(
         &mut self,
         submission_outcome: TxOutcome,
@@ -359,6 +667,14 @@ impl PendingOperation for PendingMessage {
         {
             error!(error=?e, "Error when recording tx outcome");
         }
+        if let Some(tenant) = &self.ctx.tenant {
+            match FixedPointNumber::try_from(gas_used_by_operation)
+                .and_then(|gas_used| (gas_used * operation_outcome.gas_price.clone()).try_into())
+            {
+                Ok(spend) => tenant.record_spend(self.destination_domain(), spend),
+                Err(e) => warn!(error = %e, "Error when recording the spend of the tenant"),
+            }
+        }
         // set the outcome in `Self` as well, for later logging
         self.set_submission_outcome(operation_outcome);
         debug!(
@@ -382,20 +698,61 @@ impl PendingOperation for PendingMessage {
         self.reset_attempts();
     }
 
+    fn persist(&self) {
+        self.persist_retries();
+        self.persist_operation_id();
+    }
+
     fn set_retries(&mut self, retries: u32) {
         self.set_retries(retries);
     }
 }
 
 impl PendingMessage {
+    /// Whether a quorum the message awaits is available, or the message
+    /// awaits none. Stops awaiting a quorum once one is available.
+    async fn is_quorum_available(&self) -> bool {
+        let builder = &self.ctx.metadata_builder;
+        let Some(requirements) = builder.quorum_requirements(&self.message.id()) else {
+            return true;
+        };
+        for requirement in requirements {
+            if requirement
+                .is_available(builder.origin_domain(), builder.destination_domain())
+                .await
+            {
+                builder.stop_awaiting_quorum(&self.message.id());
+                return true;
+            }
+        }
+        false
+    }
+
     /// Constructor that tries reading the retry count from the HyperlaneDB in order to recompute the `next_attempt_after`.
     /// In case of failure, behaves like `Self::new(...)`.
+    /// The operation id stored when the message was indexed is reused, or
+    /// the new one persisted if the message was indexed without one.
     pub fn from_persisted_retries(
         message: HyperlaneMessage,
         ctx: Arc<MessageContext>,
         app_context: Option<String>,
     ) -> Self {
         let mut pm = Self::new(message, ctx, app_context);
+        match pm
+            .ctx
+            .origin_db
+            .retrieve_operation_id_by_message_id(&pm.message.id())
+        {
+            Ok(Some(operation_id)) => pm.operation_id = operation_id,
+            _ => pm.persist_operation_id(),
+        }
+        pm.deadline_missed = pm
+            .ctx
+            .origin_db
+            .retrieve_deadline_missed_by_message_id(&pm.message.id())
+            .ok()
+            .flatten()
+            .unwrap_or_default();
         match pm
             .ctx
             .origin_db
@@ -420,12 +777,168 @@ impl PendingMessage {
         PendingOperationResult::Reprepare
     }
 
+    /// Reprepare a message that wasn't submitted because its delivery
+    /// simulation failed. Stale metadata and gas limits are rebuilt right
+    /// away, while reverts of the recipient or mailbox back off like failed
+    /// deliveries.
+    fn on_failed_simulation(&mut self, simulation: ProcessSimulation) -> PendingOperationResult {
+        match simulation {
+            ProcessSimulation::IsmVerifyFailed { .. } | ProcessSimulation::OutOfGas => {
+                info!(
+                    ?simulation,
+                    "Repreparing message to rebuild its metadata and gas limit"
+                );
+                self.submitted = false;
+                self.submission_data = None;
+                self.next_attempt_after = None;
+                PendingOperationResult::Reprepare
+            }
+            ProcessSimulation::RecipientReverted { .. }
+            | ProcessSimulation::Reverted { .. }
+            | ProcessSimulation::WouldSucceed => {
+                info!(?simulation, "Backing off message whose delivery reverts");
+                self.on_reprepare()
+            }
+        }
+    }
+
     fn is_ready(&self) -> bool {
         self.next_attempt_after
             .map(|a| Instant::now() >= a)
             .unwrap_or(true)
     }
 
+    /// How many more confirmations the delivery of the message waits for, or
+    /// None if it has enough. Deliveries to chains whose mailbox doesn't
+    /// record when messages were processed are complete once seen.
+    async fn pending_confirmations(&self) -> ChainResult<Option<u64>> {
+        let Some(required) = self.ctx.confirmation_depths.confirmations(&self.message) else {
+            return Ok(None);
+        };
+        let mailbox = &self.ctx.destination_mailbox;
+        let Some(processed_at) = mailbox.processed_at(self.message.id()).await? else {
+            return Ok(None);
+        };
+        let Some(chain_info) = mailbox.provider().get_chain_metrics().await? else {
+            return Ok(None);
+        };
+        let confirmations = (chain_info.latest_block.number + 1).saturating_sub(processed_at);
+        Ok((confirmations < required).then(|| required - confirmations))
+    }
+
+    /// Whether the message is older than the maximum age of its app, counted
+    /// from its last replay if it was replayed from the dead-letter store.
+    /// Messages whose dispatch block is unknown, e.g. because they were indexed
+    /// by an older relayer version, never expire.
+    async fn is_expired(&mut self) -> Result<bool> {
+        let ctx = self.ctx.clone();
+        if ctx.expiry_policy.max_age(&self.message).is_none() {
+            return Ok(false);
+        }
+        let Some(dispatched_at) = self.dispatched_at().await? else {
+            return Ok(false);
+        };
+        // A replayed message gets its whole maximum age again from its replay
+        let replayed_at = ctx
+            .origin_db
+            .retrieve_dead_letter_replayed_at_by_nonce(&self.message.nonce)?
+            .unwrap_or_default();
+        Ok(ctx.expiry_policy.is_expired(
+            &self.message,
+            dispatched_at.max(replayed_at),
+            unix_timestamp_s(),
+        ))
+    }
+
+    /// Unix timestamp in seconds of the block the message was dispatched in,
+    /// or None if it is unknown or the origin provider isn't set
+    async fn dispatched_at(&mut self) -> Result<Option<u64>> {
+        if self.dispatched_at.is_some() {
+            return Ok(self.dispatched_at);
+        }
+        let Some(origin_provider) = self.ctx.origin_provider.as_ref() else {
+            return Ok(None);
+        };
+        let Some(block_hash) = self
+            .ctx
+            .origin_db
+            .retrieve_dispatched_block_hash_by_nonce(&self.message.nonce)?
+        else {
+            return Ok(None);
+        };
+        let dispatched_at = origin_provider
+            .get_block_by_hash(&block_hash)
+            .await?
+            .timestamp;
+        self.dispatched_at = Some(dispatched_at);
+        Ok(self.dispatched_at)
+    }
+
+    /// Work out how close the message is to its delivery deadline, counting
+    /// the deadline as missed the first time it is found to have passed.
+    /// Messages whose dispatch block is unknown are never escalated.
+    async fn update_deadline(&mut self) -> Result<()> {
+        if self.deadline.is_none() && self.ctx.delivery_deadlines.has_deadline(&self.message) {
+            if let Some(dispatched_at) = self.dispatched_at().await? {
+                self.deadline = self
+                    .ctx
+                    .delivery_deadlines
+                    .status(&self.message, dispatched_at);
+            }
+        }
+        let Some(deadline) = self.deadline else {
+            return Ok(());
+        };
+        if !self.deadline_missed && deadline.is_missed(unix_timestamp_s()) {
+            warn!(
+                deadline = deadline.deadline,
+                "Message missed its delivery deadline"
+            );
+            self.ctx.metrics.deadline_misses.inc();
+            self.deadline_missed = true;
+            self.ctx
+                .origin_db
+                .store_deadline_missed_by_message_id(&self.message.id(), &true)?;
+        }
+        Ok(())
+    }
+
+    /// Record how long the message took from being dispatched to its
+    /// delivery being confirmed, in the SLO metrics of its lane. Messages
+    /// whose dispatch block is unknown are skipped.
+    async fn record_delivery_latency(&mut self) {
+        match self.dispatched_at().await {
+            Ok(Some(dispatched_at)) => {
+                let latency = unix_timestamp_s().saturating_sub(dispatched_at);
+                self.ctx
+                    .delivery_slo
+                    .record_delivery(Duration::from_secs(latency));
+            }
+            Ok(None) => {}
+            Err(err) => warn!(?err, "Failed to get when the message was dispatched"),
+        }
+    }
+
+    /// Store the message in the dead-letter store of the origin, so it is no
+    /// longer picked up by the processor until it is replayed.
+    fn move_to_dead_letter_store(&self, reason: DeadLetterReason) -> Result<()> {
+        let dead_letter = DeadLetter {
+            message_id: self.message.id(),
+            reason,
+            dead_lettered_at: unix_timestamp_s(),
+        };
+        self.ctx
+            .origin_db
+            .store_dead_letter_by_nonce(&self.message.nonce, &dead_letter)?;
+        warn!(
+            ?dead_letter,
+            operation_id = %self.operation_id,
+            dispatched_at = self.dispatched_at,
+            "Moved message to the dead-letter store"
+        );
+        Ok(())
+    }
+
     /// Record in HyperlaneDB and various metrics that this process has observed
     /// the successful processing of a message. An `Ok(())` value returned by
     /// this function is the 'commit' point in a message's lifetime for
@@ -451,10 +964,26 @@ impl PendingMessage {
     fn inc_attempts(&mut self) {
         self.set_retries(self.num_retries + 1);
         self.last_attempted_at = Instant::now();
-        self.next_attempt_after = PendingMessage::calculate_msg_backoff(self.num_retries)
+        let backoff = PendingMessage::calculate_msg_backoff(self.num_retries);
+        self.next_attempt_after = self
+            .max_deadline_backoff()
+            .map_or(backoff, |max| backoff.map(|backoff| backoff.min(max)))
             .map(|dur| self.last_attempted_at + dur);
     }
 
+    /// Longest the message may back off for given its delivery deadline:
+    /// until it escalates, and briefly once it has.
+    fn max_deadline_backoff(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| {
+            let now = unix_timestamp_s();
+            if deadline.is_escalated(now) {
+                URGENT_RETRY_DELAY
+            } else {
+                Duration::from_secs(deadline.escalates_at - now)
+            }
+        })
+    }
+
     fn set_retries(&mut self, retries: u32) {
         self.num_retries = retries;
         self.persist_retries();
@@ -466,7 +995,17 @@ impl PendingMessage {
             .origin_db
             .store_pending_message_retry_count_by_message_id(&self.message.id(), &self.num_retries)
         {
-            warn!(message_id = ?self.message.id(), err = %e, "Persisting the `num_retries` failed for message");
+            warn!(message_id = ?self.message.id(), operation_id = %self.operation_id, err = %e, "Persisting the `num_retries` failed for message");
+        }
+    }
+
+    fn persist_operation_id(&self) {
+        if let Err(e) = self
+            .ctx
+            .origin_db
+            .store_operation_id_by_message_id(&self.message.id(), &self.operation_id)
+        {
+            warn!(message_id = ?self.message.id(), operation_id = %self.operation_id, err = %e, "Persisting the operation id failed for message");
         }
     }
 
@@ -490,11 +1029,24 @@ impl PendingMessage {
     }
 }
 
-#[derive(Debug)]
+pub(crate) fn unix_timestamp_s() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone)]
 pub struct MessageSubmissionMetrics {
     // Fields are public for testing purposes
     pub last_known_nonce: IntGauge,
     pub messages_processed: IntCounter,
+    pub origin: String,
+    pub destination: String,
+    pub simulation_reverts: IntCounterVec,
+    pub fixed_gas_limit_comparisons: IntCounterVec,
+    pub process_simulations: IntCounterVec,
+    pub deadline_misses: IntCounter,
 }
 
 impl MessageSubmissionMetrics {
@@ -514,9 +1066,40 @@ impl MessageSubmissionMetrics {
             messages_processed: metrics
                 .messages_processed_count()
                 .with_label_values(&[origin, destination]),
+            origin: origin.to_owned(),
+            destination: destination.to_owned(),
+            simulation_reverts: metrics.simulation_reverts_count(),
+            fixed_gas_limit_comparisons: metrics.fixed_gas_limit_comparisons_count(),
+            process_simulations: metrics.process_simulations_count(),
+            deadline_misses: metrics
+                .delivery_deadline_misses_count()
+                .with_label_values(&[origin, destination]),
+        }
+    }
+
+    /// Count a delivery simulation that reverted, if its error was decoded
+    fn record_simulation_revert(&self, err: &ChainCommunicationError) {
+        if let ChainCommunicationError::SimulationReverted { error, .. } = err {
+            self.simulation_reverts
+                .with_label_values(&[&self.origin, &self.destination, error])
+                .inc();
         }
     }
 
+    /// Count a fixed gas limit used instead of an estimate
+    fn record_fixed_gas_limit(&self, comparison: FixedGasLimitComparison) {
+        self.fixed_gas_limit_comparisons
+            .with_label_values(&[&self.origin, &self.destination, comparison.as_str()])
+            .inc();
+    }
+
+    /// Count a delivery simulation run before submitting the message
+    fn record_process_simulation(&self, simulation: &ProcessSimulation) {
+        self.process_simulations
+            .with_label_values(&[&self.origin, &self.destination, simulation.as_str()])
+            .inc();
+    }
+
     fn update_nonce(&self, msg: &HyperlaneMessage) {
         // this is technically a race condition between `.get` and `.set` but worst case
         // the gauge should get corrected on the next update and is not an issue
@@ -16,11 +16,18 @@ use hyperlane_base::{
 };
 use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, QueueOperation};
 use prometheus::IntGauge;
-use tokio::sync::mpsc::UnboundedSender;
-use tracing::{debug, instrument, trace};
+use tokio::sync::{
+    broadcast::{error::TryRecvError, Receiver},
+    mpsc::UnboundedSender,
+};
+use tracing::{debug, info, instrument, trace, warn};
 
-use super::{blacklist::AddressBlacklist, metadata::AppContextClassifier, pending_message::*};
-use crate::{processor::ProcessorExt, settings::matching_list::MatchingList};
+use super::{
+    blacklist::AddressBlacklist, metadata::AppContextClassifier, pending_message::*, tenant::Tenant,
+};
+use crate::{
+    processor::ProcessorExt, server::DeadLetterReplayRequest, settings::matching_list::MatchingList,
+};
 
 /// Finds unprocessed messages from an origin and submits then through a channel
 /// for to the appropriate destination.
@@ -38,8 +45,13 @@ pub struct MessageProcessor {
     send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
     /// Needed context to send a message for each destination chain
     destination_ctxs: HashMap<u32, Arc<MessageContext>>,
+    /// Context to send the messages of each tenant with, for each destination
+    /// chain. Messages matching no tenant use `destination_ctxs`.
+    tenant_ctxs: Vec<(Arc<Tenant>, HashMap<u32, Arc<MessageContext>>)>,
     metric_app_contexts: Vec<(MatchingList, String)>,
     nonce_iterator: ForwardBackwardIterator,
+    /// Requests to replay dead-lettered messages, of any origin
+    dead_letter_replays: Receiver<DeadLetterReplayRequest>,
 }
 
 #[derive(Debug)]
@@ -203,8 +215,19 @@ impl DirectionalNonceIterator {
                 domain = self.db.domain().name(),
                 "Message already marked as processed in DB"
             );
+            return Ok(true);
         }
-        Ok(processed)
+        // Dead-lettered messages are not retried until they are replayed
+        if let Some(dead_letter) = self.db.retrieve_dead_letter_by_nonce(nonce)? {
+            trace!(
+                nonce,
+                domain = self.db.domain().name(),
+                ?dead_letter,
+                "Message is in the dead-letter store, skipping"
+            );
+            return Ok(true);
+        }
+        Ok(false)
     }
 }
 
@@ -238,6 +261,8 @@ impl ProcessorExt for MessageProcessor {
     /// One round of processing, extracted from infinite work loop for
     /// testing purposes.
     async fn tick(&mut self) -> Result<()> {
+        self.replay_dead_letters().await?;
+
         // Forever, scan HyperlaneRocksDB looking for new messages to send. When criteria are
         // satisfied or the message is disqualified, push the message onto
         // self.tx_msg and then continue the scan at the next highest
@@ -274,6 +299,12 @@ impl ProcessorExt for MessageProcessor {
                 return Ok(());
             }
 
+            // Skip if the message has a format the relayer can't deliver
+            if let Err(err) = msg.message_version() {
+                warn!(?msg, ?err, "Message has an unsupported version, skipping");
+                return Ok(());
+            }
+
             // Skip if the message is intended for this origin
             if destination == self.domain().id() {
                 debug!(?msg, "Message destined for self, skipping");
@@ -286,17 +317,17 @@ impl ProcessorExt for MessageProcessor {
                 return Ok(());
             }
 
-            debug!(%msg, "Sending message to submitter");
-
             let app_context_classifier =
                 AppContextClassifier::new(self.metric_app_contexts.clone());
 
             let app_context = app_context_classifier.get_app_context(&msg).await?;
+            let ctx = self.destination_ctx(&msg).clone();
             // Finally, build the submit arg and dispatch it to the submitter.
-            let pending_msg = PendingMessage::from_persisted_retries(
-                msg,
-                self.destination_ctxs[&destination].clone(),
-                app_context,
+            let pending_msg = PendingMessage::from_persisted_retries(msg, ctx, app_context);
+            debug!(
+                msg = %pending_msg.message,
+                operation_id = %pending_msg.operation_id,
+                "Sending message to submitter"
             );
             self.send_channels[&destination].send(Box::new(pending_msg) as QueueOperation)?;
         } else {
@@ -316,7 +347,9 @@ impl MessageProcessor {
         metrics: MessageProcessorMetrics,
         send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
         destination_ctxs: HashMap<u32, Arc<MessageContext>>,
+        tenant_ctxs: Vec<(Arc<Tenant>, HashMap<u32, Arc<MessageContext>>)>,
         metric_app_contexts: Vec<(MatchingList, String)>,
+        dead_letter_replays: Receiver<DeadLetterReplayRequest>,
     ) -> Self {
         Self {
             message_whitelist,
@@ -325,11 +358,85 @@ impl MessageProcessor {
             metrics,
             send_channels,
             destination_ctxs,
+            tenant_ctxs,
             metric_app_contexts,
             nonce_iterator: ForwardBackwardIterator::new(Arc::new(db) as Arc<dyn ProcessMessage>),
+            dead_letter_replays,
+        }
+    }
+
+    /// Context to send a message with, that of the first tenant it matches if
+    /// any. Panics if the destination is not serviced.
+    fn destination_ctx(&self, msg: &HyperlaneMessage) -> &Arc<MessageContext> {
+        let ctxs = self
+            .tenant_ctxs
+            .iter()
+            .find(|(tenant, _)| tenant.matches(msg))
+            .map_or(&self.destination_ctxs, |(_, ctxs)| ctxs);
+        &ctxs[&msg.destination]
+    }
+
+    /// Send the dead-lettered messages of this origin that were requested to
+    /// be replayed to their destination again. The nonce iterator has already
+    /// passed them, so they are not picked up otherwise until a restart.
+    async fn replay_dead_letters(&mut self) -> Result<()> {
+        loop {
+            let request = match self.dead_letter_replays.try_recv() {
+                Ok(request) => request,
+                Err(TryRecvError::Lagged(skipped)) => {
+                    warn!(skipped, "Dropped dead-letter replay requests");
+                    continue;
+                }
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return Ok(()),
+            };
+            if request.origin_domain == self.domain().id() {
+                self.replay_dead_letter(request.nonce).await?;
+            }
         }
     }
 
+    async fn replay_dead_letter(&mut self, nonce: u32) -> Result<()> {
+        let db = self.nonce_iterator.high_nonce_iter.db.clone();
+        let Some(dead_letter) = db.retrieve_dead_letter_by_nonce(nonce)? else {
+            warn!(
+                nonce,
+                "Message is not in the dead-letter store, not replaying"
+            );
+            return Ok(());
+        };
+        let Some(msg) = db.retrieve_message_by_nonce(nonce)? else {
+            warn!(
+                ?dead_letter,
+                "Dead-lettered message is not indexed, not replaying"
+            );
+            return Ok(());
+        };
+        let destination = msg.destination;
+        let Some(send_channel) = self.send_channels.get(&destination) else {
+            warn!(
+                ?msg,
+                "Dead-lettered message is destined for an unknown domain, not replaying"
+            );
+            return Ok(());
+        };
+        let ctx = self.destination_ctx(&msg);
+
+        // Retry right away, and keep the message out of the dead-letter store
+        // on restarts unless it fails again
+        ctx.origin_db
+            .store_pending_message_retry_count_by_message_id(&msg.id(), &0)?;
+        ctx.origin_db
+            .store_dead_letter_replayed_at_by_nonce(&nonce, &unix_timestamp_s())?;
+        info!(?msg, ?dead_letter, "Replaying dead-lettered message");
+
+        let app_context = AppContextClassifier::new(self.metric_app_contexts.clone())
+            .get_app_context(&msg)
+            .await?;
+        let pending_msg = PendingMessage::from_persisted_retries(msg, ctx.clone(), app_context);
+        send_channel.send(Box::new(pending_msg) as QueueOperation)?;
+        Ok(())
+    }
+
     async fn try_get_unprocessed_message(&mut self) -> Result<Option<HyperlaneMessage>> {
         trace!(nonce_iterator=?self.nonce_iterator, "Trying to get the next processor message");
         let next_message = self
@@ -386,8 +493,9 @@ mod test {
     use crate::{
         merkle_tree::builder::MerkleTreeBuilder,
         msg::{
+            delivery_slo::{DeliverySlo, DeliverySloMetrics},
             gas_payment::GasPaymentEnforcer,
-            metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+            metadata::{BaseMetadataBuilder, DefaultIsmCache, IsmAwareAppContextClassifier},
         },
         processor::Processor,
     };
@@ -397,10 +505,12 @@ mod test {
         db::{test_utils, DbResult, HyperlaneRocksDB},
         settings::{ChainConf, ChainConnectionConf, Settings},
     };
+    use hyperlane_core::{DeadLetter, DeadLetterReason};
     use hyperlane_test::mocks::{MockMailboxContract, MockValidatorAnnounceContract};
-    use prometheus::{IntCounter, Registry};
+    use prometheus::{IntCounter, IntCounterVec, Opts, Registry};
     use tokio::{
         sync::{
+            broadcast,
             mpsc::{self, UnboundedReceiver},
             RwLock,
         },
@@ -426,6 +536,24 @@ mod test {
         MessageSubmissionMetrics {
             last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
             messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
+            origin: "origin".to_owned(),
+            destination: "destination".to_owned(),
+            simulation_reverts: IntCounterVec::new(
+                Opts::new("simulation_reverts", "help string"),
+                &["origin", "remote", "error"],
+            )
+            .unwrap(),
+            fixed_gas_limit_comparisons: IntCounterVec::new(
+                Opts::new("fixed_gas_limit_comparisons", "help string"),
+                &["origin", "remote", "comparison"],
+            )
+            .unwrap(),
+            process_simulations: IntCounterVec::new(
+                Opts::new("process_simulations", "help string"),
+                &["origin", "remote", "outcome"],
+            )
+            .unwrap(),
+            deadline_misses: IntCounter::new("deadline_misses", "help string").unwrap(),
         }
     }
 
@@ -441,9 +569,24 @@ mod test {
                 },
                 transaction_overrides: Default::default(),
                 operation_batch: Default::default(),
+                transaction_replacement: None,
+                log_subscription_url: None,
+                rpc_rate_limits: Default::default(),
+                circuit_breaker: None,
+                block_time: None,
+                finality_tag: None,
+                transaction_confirmations: 1,
+                revert_trace_method: None,
+                fee_adapter: None,
+                historical_block: None,
             }),
             metrics_conf: Default::default(),
             index: Default::default(),
+            circuit_breaker: None,
+            known_contracts: Default::default(),
+            max_inflight_transactions: None,
+            confirmation_batch_size: None,
+            chain_id: None,
         }
     }
 
@@ -471,7 +614,13 @@ mod test {
             false,
             Arc::new(core_metrics),
             db.clone(),
-            IsmAwareAppContextClassifier::new(Arc::new(MockMailboxContract::default()), vec![]),
+            IsmAwareAppContextClassifier::new(
+                Arc::new(DefaultIsmCache::new(Arc::new(
+                    MockMailboxContract::default(),
+                ))),
+                vec![],
+            ),
+            Arc::new(vec![]),
         )
     }
 
@@ -479,18 +628,39 @@ mod test {
         origin_domain: &HyperlaneDomain,
         destination_domain: &HyperlaneDomain,
         db: &HyperlaneRocksDB,
-    ) -> (MessageProcessor, UnboundedReceiver<QueueOperation>) {
+    ) -> (
+        MessageProcessor,
+        UnboundedReceiver<QueueOperation>,
+        broadcast::Sender<DeadLetterReplayRequest>,
+    ) {
         let base_metadata_builder = dummy_metadata_builder(origin_domain, destination_domain, db);
+        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
         let message_context = Arc::new(MessageContext {
             destination_mailbox: Arc::new(MockMailboxContract::default()),
             origin_db: db.clone(),
             metadata_builder: Arc::new(base_metadata_builder),
             origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], db.clone())),
+            hook_metadata_builder: Default::default(),
+            gas_overheads: Default::default(),
+            fixed_gas_limits: Default::default(),
+            expiry_policy: Default::default(),
+            delivery_deadlines: Default::default(),
+            confirmation_depths: Default::default(),
+            delivery_slo: Arc::new(DeliverySlo::new(
+                DeliverySloMetrics::new(&core_metrics).unwrap(),
+                origin_domain,
+                destination_domain,
+                Duration::from_secs(300),
+                Duration::from_secs(3600),
+            )),
+            origin_provider: None,
             transaction_gas_limit: Default::default(),
             metrics: dummy_submission_metrics(),
+            tenant: None,
         });
 
         let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
+        let (replay_sender, replay_receiver) = broadcast::channel(16);
         (
             MessageProcessor::new(
                 db.clone(),
@@ -501,8 +671,11 @@ mod test {
                 HashMap::from([(destination_domain.id(), send_channel)]),
                 HashMap::from([(destination_domain.id(), message_context)]),
                 vec![],
+                vec![],
+                replay_receiver,
             ),
             receive_channel,
+            replay_sender,
         )
     }
 
@@ -562,7 +735,7 @@ mod test {
         db: &HyperlaneRocksDB,
         num_operations: usize,
     ) -> Vec<QueueOperation> {
-        let (message_processor, mut receive_channel) =
+        let (message_processor, mut receive_channel, _replay_sender) =
             dummy_message_processor(origin_domain, destination_domain, db);
 
         let processor = Processor::new(Box::new(message_processor), TaskMonitor::new());
@@ -595,6 +768,7 @@ mod test {
             fn retrieve_highest_seen_message_nonce(&self) -> DbResult<Option<u32>>;
             fn retrieve_message_by_nonce(&self, nonce: u32) -> DbResult<Option<HyperlaneMessage>>;
             fn retrieve_processed_by_nonce(&self, nonce: u32) -> DbResult<Option<bool>>;
+            fn retrieve_dead_letter_by_nonce(&self, nonce: u32) -> DbResult<Option<DeadLetter>>;
             fn domain(&self) -> &HyperlaneDomain;
         }
     }
@@ -654,6 +828,79 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_dead_letter_replay() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let message = dummy_hyperlane_message(&destination_domain, 0);
+            add_db_entry(&db, &message, 20);
+            db.store_dead_letter_by_nonce(
+                &message.nonce,
+                &DeadLetter {
+                    message_id: message.id(),
+                    reason: DeadLetterReason::Expired,
+                    dead_lettered_at: 1_700_000_000,
+                },
+            )
+            .unwrap();
+
+            let (mut message_processor, mut receive_channel, replay_sender) =
+                dummy_message_processor(&origin_domain, &destination_domain, &db);
+            // The iterator skips the dead-lettered message
+            message_processor.tick().await.unwrap();
+            assert!(receive_channel.try_recv().is_err());
+
+            // Requests for other origins are ignored
+            for origin_domain in [origin_domain.id() + 1, origin_domain.id()] {
+                replay_sender
+                    .send(DeadLetterReplayRequest {
+                        origin_domain,
+                        nonce: message.nonce,
+                    })
+                    .unwrap();
+            }
+            message_processor.tick().await.unwrap();
+            let replayed = receive_channel.try_recv().unwrap();
+            assert_eq!(replayed.id(), message.id());
+            assert!(receive_channel.try_recv().is_err());
+
+            // The message is retried right away, and is no longer dead-lettered
+            assert_eq!(replayed.next_attempt_after(), None);
+            assert_eq!(
+                db.retrieve_unreplayed_dead_letter_by_nonce(message.nonce)
+                    .unwrap(),
+                None
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_skips_unsupported_message_version() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let unsupported = HyperlaneMessage {
+                version: 1,
+                ..dummy_hyperlane_message(&destination_domain, 0)
+            };
+            let supported = dummy_hyperlane_message(&destination_domain, 1);
+            add_db_entry(&db, &unsupported, 0);
+            add_db_entry(&db, &supported, 0);
+
+            let (mut message_processor, mut receive_channel, _replay_sender) =
+                dummy_message_processor(&origin_domain, &destination_domain, &db);
+            message_processor.tick().await.unwrap();
+            assert!(receive_channel.try_recv().is_err());
+            message_processor.tick().await.unwrap();
+            assert_eq!(receive_channel.try_recv().unwrap().id(), supported.id());
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn test_forward_backward_iterator() {
         let mut mock_db = MockDb::new();
@@ -696,6 +943,9 @@ mod test {
         mock_db
             .expect_retrieve_processed_by_nonce()
             .returning(|_| Ok(Some(false)));
+        mock_db
+            .expect_retrieve_dead_letter_by_nonce()
+            .returning(|_| Ok(None));
         let dummy_metrics = dummy_processor_metrics(0);
         let db = Arc::new(mock_db);
 
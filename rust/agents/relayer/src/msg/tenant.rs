@@ -0,0 +1,288 @@
+use std::{
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+use eyre::Result;
+use hyperlane_base::{
+    db::{HyperlaneRocksDB, DB},
+    CoreMetrics,
+};
+use hyperlane_core::{metrics::agent::u256_as_scaled_f64, HyperlaneDomain, HyperlaneMessage, U256};
+use prometheus::{CounterVec, GaugeVec, IntCounterVec};
+use tracing::warn;
+
+use crate::{
+    msg::pending_message::unix_timestamp_s,
+    settings::{matching_list::MatchingList, TenantBudgetConf, TenantConf},
+};
+
+/// Metrics of the messages relayed for each tenant
+#[derive(Clone, Debug)]
+pub struct TenantMetrics {
+    /// Messages of a tenant delivered to a destination
+    messages_delivered: IntCounterVec,
+    /// Gas spent delivering the messages of a tenant, in the native token
+    gas_spent: CounterVec,
+    /// What a tenant may still spend on a destination in the current budget
+    /// period, in the native token
+    budget_remaining: GaugeVec,
+}
+
+impl TenantMetrics {
+    pub fn new(metrics: &CoreMetrics) -> Result<Self> {
+        Ok(Self {
+            messages_delivered: metrics.new_int_counter(
+                "tenant_messages_delivered",
+                "Messages of a tenant delivered to a destination",
+                &["tenant", "destination"],
+            )?,
+            gas_spent: metrics.new_counter(
+                "tenant_gas_spent",
+                "Gas spent delivering the messages of a tenant, in the native token",
+                &["tenant", "destination"],
+            )?,
+            budget_remaining: metrics.new_gauge(
+                "tenant_budget_remaining",
+                "What a tenant may still spend on gas on a destination in the current budget period, in the native token",
+                &["tenant", "destination"],
+            )?,
+        })
+    }
+}
+
+/// A customer of the relayer, whose messages are relayed with their own
+/// signers, gas payment policies and spend budgets
+#[derive(Debug)]
+pub struct Tenant {
+    pub name: String,
+    pub matching_list: MatchingList,
+    budgets: Vec<Budget>,
+    metrics: TenantMetrics,
+}
+
+/// A budget of a tenant, with what was spent in its current period. The
+/// period is stored in the database of the destination, so a restart carries
+/// on with it rather than starting a new one.
+#[derive(Debug)]
+struct Budget {
+    conf: TenantBudgetConf,
+    /// Start of the current period, in unix seconds, and what was spent since
+    period: Mutex<(u64, U256)>,
+    db: HyperlaneRocksDB,
+}
+
+impl Budget {
+    fn new(tenant: &str, conf: &TenantBudgetConf, db: &DB, now: u64) -> Self {
+        let db = HyperlaneRocksDB::new(&conf.domain, db.clone());
+        let period = db
+            .retrieve_tenant_budget_period(tenant)
+            .unwrap_or_else(|err| {
+                warn!(?err, tenant, destination = ?conf.domain, "Failed to read the budget period of a tenant, starting a new one");
+                None
+            })
+            .unwrap_or((now, U256::zero()));
+        Self {
+            conf: conf.clone(),
+            period: Mutex::new(period),
+            db,
+        }
+    }
+
+    /// What was spent in the current period, starting a new one if it ended
+    fn current(&self, now: u64) -> MutexGuard<(u64, U256)> {
+        let mut period = self.period.lock().unwrap();
+        if now.saturating_sub(period.0) >= self.conf.period.as_secs() {
+            *period = (now, U256::zero());
+        }
+        period
+    }
+}
+
+impl Tenant {
+    pub fn new(conf: &TenantConf, metrics: TenantMetrics, db: &DB) -> Self {
+        let now = unix_timestamp_s();
+        let budgets = conf
+            .budgets
+            .iter()
+            .map(|budget| Budget::new(&conf.name, budget, db, now))
+            .collect();
+        let tenant = Self {
+            name: conf.name.clone(),
+            matching_list: conf.matching_list.clone(),
+            budgets,
+            metrics,
+        };
+        for budget in &tenant.budgets {
+            let spent = budget.current(now).1;
+            tenant.set_budget_remaining(budget, budget.conf.max_spend.saturating_sub(spent));
+        }
+        tenant
+    }
+
+    /// Whether the message is relayed for this tenant
+    pub fn matches(&self, message: &HyperlaneMessage) -> bool {
+        self.matching_list.msg_matches(message, false)
+    }
+
+    /// If the tenant spent its budget on the destination, how long until its
+    /// budget period restarts
+    pub fn over_budget(&self, destination: u32) -> Option<Duration> {
+        let now = unix_timestamp_s();
+        self.budgets
+            .iter()
+            .filter(|budget| budget.conf.domain.id() == destination)
+            .filter_map(|budget| {
+                let (started_at, spent) = *budget.current(now);
+                (spent >= budget.conf.max_spend).then(|| {
+                    let elapsed = Duration::from_secs(now.saturating_sub(started_at));
+                    budget.conf.period.saturating_sub(elapsed)
+                })
+            })
+            .max()
+    }
+
+    /// Record the gas spent delivering a message of the tenant, in the
+    /// destination's native token
+    pub fn record_spend(&self, destination: &HyperlaneDomain, spend: U256) {
+        let now = unix_timestamp_s();
+        for budget in &self.budgets {
+            if budget.conf.domain != *destination {
+                continue;
+            }
+            let mut period = budget.current(now);
+            period.1 = period.1.saturating_add(spend);
+            let (started_at, spent) = *period;
+            // Stored before the lock is released, so that concurrent spends
+            // are stored in order
+            if let Err(err) = budget
+                .db
+                .store_tenant_budget_period(&self.name, started_at, spent)
+            {
+                warn!(
+                    ?err,
+                    tenant = self.name,
+                    ?destination,
+                    "Failed to store the budget period of a tenant"
+                );
+            }
+            drop(period);
+            self.set_budget_remaining(budget, budget.conf.max_spend.saturating_sub(spent));
+        }
+        self.metrics
+            .gas_spent
+            .with_label_values(&[&self.name, destination.name()])
+            .inc_by(u256_as_scaled_f64(spend, destination.domain_protocol()));
+    }
+
+    /// Record the delivery of a message of the tenant
+    pub fn record_delivery(&self, destination: &HyperlaneDomain) {
+        self.metrics
+            .messages_delivered
+            .with_label_values(&[&self.name, destination.name()])
+            .inc();
+    }
+
+    fn set_budget_remaining(&self, budget: &Budget, remaining: U256) {
+        let destination = &budget.conf.domain;
+        self.metrics
+            .budget_remaining
+            .with_label_values(&[&self.name, destination.name()])
+            .set(u256_as_scaled_f64(remaining, destination.domain_protocol()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::Registry;
+
+    use super::*;
+
+    fn dummy_tenant(budgets: Vec<TenantBudgetConf>, db: &DB) -> Tenant {
+        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let conf = TenantConf {
+            name: "acme".to_owned(),
+            matching_list: serde_json::from_str(r#"[{"origindomain": 1}]"#).unwrap(),
+            gas_payment_enforcement: vec![],
+            signers: Default::default(),
+            budgets,
+        };
+        Tenant::new(&conf, TenantMetrics::new(&core_metrics).unwrap(), db)
+    }
+
+    fn budget(domain: &HyperlaneDomain, period: Duration) -> TenantBudgetConf {
+        TenantBudgetConf {
+            domain: domain.clone(),
+            max_spend: U256::from(100),
+            period,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_over_budget_once_spent() {
+        test_utils::run_test_db(|db| async move {
+            let destination: HyperlaneDomain = KnownHyperlaneDomain::Ethereum.into();
+            let other: HyperlaneDomain = KnownHyperlaneDomain::Arbitrum.into();
+            let tenant = dummy_tenant(vec![budget(&destination, Duration::from_secs(3600))], &db);
+
+            tenant.record_spend(&destination, U256::from(60));
+            assert_eq!(tenant.over_budget(destination.id()), None);
+
+            tenant.record_spend(&destination, U256::from(40));
+            let wait = tenant.over_budget(destination.id()).unwrap();
+            assert!(wait > Duration::from_secs(3500) && wait <= Duration::from_secs(3600));
+
+            // Only the destination of the budget is limited
+            tenant.record_spend(&other, U256::from(1000));
+            assert_eq!(tenant.over_budget(other.id()), None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_budget_period_survives_restarts() {
+        test_utils::run_test_db(|db| async move {
+            let destination: HyperlaneDomain = KnownHyperlaneDomain::Ethereum.into();
+            let budgets = vec![budget(&destination, Duration::from_secs(3600))];
+            let tenant = dummy_tenant(budgets.clone(), &db);
+            tenant.record_spend(&destination, U256::from(100));
+            let wait = tenant.over_budget(destination.id()).unwrap();
+            drop(tenant);
+
+            // Still over budget until the end of the same period
+            let restarted = dummy_tenant(budgets, &db);
+            assert!(restarted.over_budget(destination.id()).unwrap() <= wait);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_budget_resets_every_period() {
+        test_utils::run_test_db(|db| async move {
+            let destination: HyperlaneDomain = KnownHyperlaneDomain::Ethereum.into();
+            let tenant = dummy_tenant(vec![budget(&destination, Duration::ZERO)], &db);
+
+            tenant.record_spend(&destination, U256::from(1000));
+            assert_eq!(tenant.over_budget(destination.id()), None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_matches() {
+        test_utils::run_test_db(|db| async move {
+            let tenant = dummy_tenant(vec![], &db);
+            assert!(tenant.matches(&HyperlaneMessage {
+                origin: 1,
+                ..Default::default()
+            }));
+            assert!(!tenant.matches(&HyperlaneMessage {
+                origin: 2,
+                ..Default::default()
+            }));
+        })
+        .await;
+    }
+}
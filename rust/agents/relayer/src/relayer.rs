@@ -2,6 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::{Debug, Formatter},
     sync::Arc,
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -9,21 +10,23 @@ use derive_more::AsRef;
 use eyre::Result;
 use futures_util::future::try_join_all;
 use hyperlane_base::{
-    db::{HyperlaneRocksDB, DB},
+    check_rocks_db,
+    db::{DbMaintenance, HyperlaneRocksDB, DB, HYPERLANE_DB_MIGRATIONS},
     metrics::{AgentMetrics, MetricsUpdater},
     settings::ChainConf,
-    BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore,
-    SyncOptions,
+    shutdown_signal, BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics,
+    DoctorReport, HyperlaneAgentCore, SyncOptions,
 };
 use hyperlane_core::{
-    HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion, QueueOperation,
-    H512, U256,
+    DestinationGasUpdate, HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage,
+    HyperlaneProvider, InterchainGasPayment, Mailbox, MerkleTreeInsertion, QueueOperation, H512,
+    U256,
 };
 use tokio::{
     sync::{
         broadcast::{Receiver, Sender},
         mpsc::{self, UnboundedReceiver, UnboundedSender},
-        RwLock,
+        watch, RwLock,
     },
     task::JoinHandle,
 };
@@ -31,17 +34,28 @@ use tokio_metrics::TaskMonitor;
 use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument};
 
 use crate::{
+    igp_claimer::{IgpClaimer, IgpClaimerMetrics},
     merkle_tree::builder::MerkleTreeBuilder,
+    missed_deliveries::{MissedDeliveryFeed, MissedDeliveryOrigin},
     msg::{
         blacklist::AddressBlacklist,
+        confirmation_depth::ConfirmationDepths,
+        deadline::DeliveryDeadlines,
+        delivery_slo::{self, DeliverySlo, DeliverySloMetrics},
+        expiry::MessageExpiryPolicy,
+        fixed_gas_limit::FixedGasLimits,
+        gas_overhead::GasOverheads,
         gas_payment::GasPaymentEnforcer,
-        metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+        metadata::{
+            BaseMetadataBuilder, DefaultIsmCache, HookMetadataBuilder, IsmAwareAppContextClassifier,
+        },
         op_submitter::{SerialSubmitter, SerialSubmitterMetrics},
         pending_message::{MessageContext, MessageSubmissionMetrics},
         processor::{MessageProcessor, MessageProcessorMetrics},
+        tenant::{Tenant, TenantMetrics},
     },
-    server::{self as relayer_server, MessageRetryRequest},
-    settings::{matching_list::MatchingList, RelayerSettings},
+    server::{self as relayer_server, DeadLetterReplayRequest, MessageRetryRequest},
+    settings::{matching_list::MatchingList, LaneWeights, RelayerSettings},
 };
 use crate::{
     merkle_tree::processor::{MerkleTreeProcessor, MerkleTreeProcessorMetrics},
@@ -65,12 +79,23 @@ pub struct Relayer {
     message_syncs: HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<HyperlaneMessage>>>,
     interchain_gas_payment_syncs:
         HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<InterchainGasPayment>>>,
+    /// Syncs of the destination gas data set in the gas oracle of each origin
+    /// that has a storage gas oracle configured
+    destination_gas_syncs: HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<DestinationGasUpdate>>>,
     /// Context data for each (origin, destination) chain pair a message can be
     /// sent between
     msg_ctxs: HashMap<ContextKey, Arc<MessageContext>>,
+    /// Context data of each tenant, for each chain pair, in the order tenants
+    /// are matched in
+    tenant_msg_ctxs: Vec<(Arc<Tenant>, HashMap<ContextKey, Arc<MessageContext>>)>,
+    /// Default ISM of each destination mailbox, shared by the message
+    /// contexts of every origin
+    default_ism_caches: HashMap<HyperlaneDomain, Arc<DefaultIsmCache>>,
     prover_syncs: HashMap<HyperlaneDomain, Arc<RwLock<MerkleTreeBuilder>>>,
     merkle_tree_hook_syncs: HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<MerkleTreeInsertion>>>,
     dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
+    /// Taken when `run` is called
+    db_maintenance: Option<DbMaintenance>,
     message_whitelist: Arc<MatchingList>,
     message_blacklist: Arc<MatchingList>,
     address_blacklist: Arc<AddressBlacklist>,
@@ -78,6 +103,10 @@ pub struct Relayer {
     skip_transaction_gas_limit_for: HashSet<u32>,
     allow_local_checkpoint_syncers: bool,
     metric_app_contexts: Vec<(MatchingList, String)>,
+    lane_weights: Option<LaneWeights>,
+    igp_claimers: Vec<IgpClaimer>,
+    missed_delivery_feed: Option<MissedDeliveryFeed>,
+    shutdown_timeout: Duration,
     core_metrics: Arc<CoreMetrics>,
     // TODO: decide whether to consolidate `agent_metrics` and `chain_metrics` into a single struct
     // or move them in `core_metrics`, like the validator metrics
@@ -123,11 +152,17 @@ impl BaseAgent for Relayer {
     {
         let core = settings.build_hyperlane_core(core_metrics.clone());
         let db = DB::from_path(&settings.db)?;
+        db.migrate(HYPERLANE_DB_MIGRATIONS)?;
         let dbs = settings
             .origin_chains
             .iter()
             .map(|origin| (origin.clone(), HyperlaneRocksDB::new(origin, db.clone())))
             .collect::<HashMap<_, _>>();
+        let db_maintenance = dbs.values().cloned().fold(
+            DbMaintenance::new(settings.db_maintenance.clone(), &core_metrics)?
+                .with_db(Self::AGENT_NAME, db.clone()),
+            DbMaintenance::with_message_db,
+        );
 
         let mailboxes = settings
             .build_mailboxes(settings.destination_chains.iter(), &core_metrics)
@@ -166,6 +201,27 @@ impl BaseAgent for Relayer {
             .map(|(k, v)| (k, v as _))
             .collect();
 
+        // Only EVM gas oracles are indexed, to log what enforced messages
+        // cost on their destination
+        let mut destination_gas_syncs = HashMap::new();
+        for origin in &settings.origin_chains {
+            let chain_setup = settings.chain_setup(origin)?;
+            if origin.domain_protocol() != HyperlaneDomainProtocol::Ethereum
+                || chain_setup.addresses.storage_gas_oracle.is_none()
+            {
+                continue;
+            }
+            let sync = settings
+                .watermark_contract_sync::<DestinationGasUpdate, _>(
+                    origin,
+                    &core_metrics,
+                    &contract_sync_metrics,
+                    Arc::new(dbs[origin].clone()),
+                )
+                .await?;
+            destination_gas_syncs.insert(origin.clone(), sync as Arc<dyn ContractSyncer<_>>);
+        }
+
         let merkle_tree_hook_syncs = settings
             .contract_syncs::<MerkleTreeInsertion, _>(
                 settings.origin_chains.iter(),
@@ -195,6 +251,26 @@ impl BaseAgent for Relayer {
             "Whitelist configuration"
         );
 
+        let hook_metadata_builder = Arc::new(HookMetadataBuilder::new(settings.hook_metadata));
+        let custom_isms = Arc::new(settings.custom_isms);
+        let gas_overheads = Arc::new(GasOverheads::new(settings.gas_overheads));
+        let fixed_gas_limits = Arc::new(FixedGasLimits::new(settings.fixed_gas_limits));
+
+        // origin providers determine when messages were dispatched, which the
+        // delivery latency SLO, expiry and delivery deadlines are all based on
+        let expiry_policy = Arc::new(MessageExpiryPolicy::new(settings.message_expiry));
+        let delivery_deadlines = Arc::new(DeliveryDeadlines::new(settings.delivery_deadlines));
+        let mut origin_providers: HashMap<HyperlaneDomain, Arc<dyn HyperlaneProvider>> =
+            HashMap::new();
+        for origin in &settings.origin_chains {
+            let provider = core
+                .settings
+                .chain_setup(origin)?
+                .build_provider(&core_metrics)
+                .await?;
+            origin_providers.insert(origin.clone(), Arc::from(provider));
+        }
+
         // provers by origin chain
         let prover_syncs = settings
             .origin_chains
@@ -225,11 +301,19 @@ impl BaseAgent for Relayer {
             })
             .collect();
 
+        let delivery_slo_metrics = DeliverySloMetrics::new(&core_metrics)?;
         let mut msg_ctxs = HashMap::new();
         let mut destination_chains = HashMap::new();
+        let mut default_ism_caches = HashMap::new();
         for destination in &settings.destination_chains {
             let destination_chain_setup = core.settings.chain_setup(destination).unwrap().clone();
             destination_chains.insert(destination.clone(), destination_chain_setup.clone());
+            let default_ism_cache = Arc::new(DefaultIsmCache::new(mailboxes[destination].clone()));
+            default_ism_caches.insert(destination.clone(), default_ism_cache.clone());
+            let confirmation_depths = Arc::new(ConfirmationDepths::new(
+                settings.confirmation_depths.clone(),
+                destination_chain_setup.reorg_period,
+            ));
             let transaction_gas_limit: Option<U256> =
                 if skip_transaction_gas_limit_for.contains(&destination.id()) {
                     None
@@ -248,9 +332,10 @@ impl BaseAgent for Relayer {
                     core.metrics.clone(),
                     db,
                     IsmAwareAppContextClassifier::new(
-                        mailboxes[destination].clone(),
+                        default_ism_cache.clone(),
                         settings.metric_app_contexts.clone(),
                     ),
+                    custom_isms.clone(),
                 );
 
                 msg_ctxs.insert(
@@ -263,21 +348,141 @@ impl BaseAgent for Relayer {
                         origin_db: dbs.get(origin).unwrap().clone(),
                         metadata_builder: Arc::new(metadata_builder),
                         origin_gas_payment_enforcer: gas_payment_enforcers[origin].clone(),
+                        hook_metadata_builder: hook_metadata_builder.clone(),
+                        gas_overheads: gas_overheads.clone(),
+                        fixed_gas_limits: fixed_gas_limits.clone(),
+                        expiry_policy: expiry_policy.clone(),
+                        delivery_deadlines: delivery_deadlines.clone(),
+                        confirmation_depths: confirmation_depths.clone(),
+                        delivery_slo: Arc::new(DeliverySlo::new(
+                            delivery_slo_metrics.clone(),
+                            origin,
+                            destination,
+                            settings.slo_delivery_target,
+                            settings.slo_window,
+                        )),
+                        origin_provider: origin_providers.get(origin).cloned(),
                         transaction_gas_limit,
                         metrics: MessageSubmissionMetrics::new(&core_metrics, origin, destination),
+                        tenant: None,
                     }),
                 );
             }
         }
 
+        // Tenants share the contexts above, but submit with their own signers
+        // and enforce their own gas payment policies if they have any
+        let tenant_metrics = TenantMetrics::new(&core_metrics)?;
+        let mut tenant_msg_ctxs = Vec::with_capacity(settings.tenants.len());
+        for tenant_conf in &settings.tenants {
+            let tenant = Arc::new(Tenant::new(tenant_conf, tenant_metrics.clone(), &db));
+            let mut tenant_mailboxes: HashMap<u32, Arc<dyn Mailbox>> = HashMap::new();
+            for (destination, signer) in &tenant_conf.signers {
+                let mut chain_setup = core.settings.chain_setup(destination)?.clone();
+                chain_setup.signer = Some(signer.clone());
+                let mailbox = chain_setup.build_mailbox(&core_metrics).await?;
+                tenant_mailboxes.insert(destination.id(), Arc::from(mailbox));
+            }
+            let policies = &tenant_conf.gas_payment_enforcement;
+            let tenant_enforcers: HashMap<_, _> = if policies.is_empty() {
+                HashMap::new()
+            } else {
+                settings
+                    .origin_chains
+                    .iter()
+                    .map(|origin| {
+                        (
+                            origin.id(),
+                            Arc::new(GasPaymentEnforcer::new(
+                                policies.clone(),
+                                dbs[origin].clone(),
+                            )),
+                        )
+                    })
+                    .collect()
+            };
+            info!(
+                tenant = tenant.name,
+                gas_enforcement_policies = ?policies,
+                signers = ?tenant_mailboxes.keys(),
+                "Tenant configuration"
+            );
+
+            let ctxs = msg_ctxs
+                .iter()
+                .map(|(key, ctx)| {
+                    let mut ctx = MessageContext::clone(ctx);
+                    if let Some(mailbox) = tenant_mailboxes.get(&key.destination) {
+                        ctx.destination_mailbox = mailbox.clone();
+                    }
+                    if let Some(enforcer) = tenant_enforcers.get(&key.origin) {
+                        ctx.origin_gas_payment_enforcer = enforcer.clone();
+                    }
+                    ctx.tenant = Some(tenant.clone());
+                    (*key, Arc::new(ctx))
+                })
+                .collect();
+            tenant_msg_ctxs.push((tenant, ctxs));
+        }
+
+        let igp_claimer_metrics = IgpClaimerMetrics::new(&core_metrics)?;
+        let mut igp_claimers = Vec::with_capacity(settings.igp_claims.len());
+        for claim_conf in settings.igp_claims {
+            let paymaster = core
+                .settings
+                .chain_setup(&claim_conf.domain)?
+                .build_interchain_gas_paymaster(&core_metrics)
+                .await?;
+            igp_claimers.push(IgpClaimer::new(
+                paymaster,
+                claim_conf,
+                settings.igp_claim_interval,
+                igp_claimer_metrics.clone(),
+            ));
+        }
+
+        let missed_delivery_feed = match &settings.missed_delivery_feed {
+            Some(conf) => {
+                let mut origins = HashMap::new();
+                for origin in &settings.origin_chains {
+                    let indexer = settings
+                        .chain_setup(origin)?
+                        .build_message_indexer(&core_metrics)
+                        .await?;
+                    origins.insert(
+                        origin.id(),
+                        MissedDeliveryOrigin {
+                            db: dbs[origin].clone(),
+                            indexer: indexer.into(),
+                        },
+                    );
+                }
+                Some(MissedDeliveryFeed::new(
+                    conf.clone(),
+                    origins,
+                    settings
+                        .destination_chains
+                        .iter()
+                        .map(|destination| destination.id())
+                        .collect(),
+                    &core_metrics,
+                )?)
+            }
+            None => None,
+        };
+
         Ok(Self {
             dbs,
+            db_maintenance: Some(db_maintenance),
             origin_chains: settings.origin_chains,
             destination_chains,
             msg_ctxs,
+            tenant_msg_ctxs,
+            default_ism_caches,
             core,
             message_syncs,
             interchain_gas_payment_syncs,
+            destination_gas_syncs,
             prover_syncs,
             merkle_tree_hook_syncs,
             message_whitelist,
@@ -287,6 +492,10 @@ impl BaseAgent for Relayer {
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers: settings.allow_local_checkpoint_syncers,
             metric_app_contexts: settings.metric_app_contexts,
+            lane_weights: settings.lane_weights,
+            igp_claimers,
+            missed_delivery_feed,
+            shutdown_timeout: settings.shutdown_timeout,
             core_metrics,
             agent_metrics,
             chain_metrics,
@@ -312,7 +521,19 @@ impl BaseAgent for Relayer {
 
         // run server
         let sender = Sender::<MessageRetryRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
-        let custom_routes = relayer_server::routes(sender.clone());
+        let replay_sender = Sender::<DeadLetterReplayRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
+        let custom_routes = relayer_server::routes(
+            sender.clone(),
+            replay_sender.clone(),
+            self.origin_chains
+                .iter()
+                .map(|origin| self.dbs[origin].clone())
+                .collect(),
+            self.destination_chains
+                .keys()
+                .map(|destination| (destination.id(), destination.domain_protocol()))
+                .collect(),
+        );
 
         let server = self
             .core
@@ -324,23 +545,32 @@ impl BaseAgent for Relayer {
             .instrument(info_span!("Relayer server"));
         tasks.push(server_task);
 
+        if let Some(db_maintenance) = self.db_maintenance.take() {
+            tasks.push(db_maintenance.spawn());
+        }
+
         // send channels by destination chain
         let mut send_channels = HashMap::with_capacity(self.destination_chains.len());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let mut submitter_tasks = Vec::with_capacity(self.destination_chains.len());
         for (dest_domain, dest_conf) in &self.destination_chains {
             let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
             send_channels.insert(dest_domain.id(), send_channel);
 
-            tasks.push(
+            submitter_tasks.push(
                 self.run_destination_submitter(
                     dest_domain,
                     receive_channel,
                     sender.clone(),
+                    shutdown_rx.clone(),
                     // Default to submitting one message at a time if there is no batch config
                     self.core.settings.chains[dest_domain.name()]
                         .connection
                         .operation_batch_config()
                         .map(|c| c.max_batch_size)
                         .unwrap_or(1),
+                    self.core.settings.chains[dest_domain.name()].max_inflight_transactions,
+                    self.core.settings.chains[dest_domain.name()].confirmation_batch_size,
                     task_monitor.clone(),
                 ),
             );
@@ -355,6 +585,7 @@ impl BaseAgent for Relayer {
             .await
             .unwrap();
             tasks.push(metrics_updater.spawn());
+            tasks.push(self.default_ism_caches[dest_domain].clone().spawn_watcher());
         }
 
         for origin in &self.origin_chains {
@@ -363,22 +594,34 @@ impl BaseAgent for Relayer {
                 .get(origin)
                 .and_then(|sync| sync.get_broadcaster());
             tasks.push(self.run_message_sync(origin, task_monitor.clone()).await);
-            tasks.push(
-                self.run_interchain_gas_payment_sync(
+            // Chains may not support indexing gas payments or merkle tree
+            // insertions yet
+            if let Some(sync) = self
+                .run_interchain_gas_payment_sync(
                     origin,
                     maybe_broadcaster.clone().map(|b| b.subscribe()),
                     task_monitor.clone(),
                 )
-                .await,
-            );
-            tasks.push(
-                self.run_merkle_tree_hook_syncs(
+                .await
+            {
+                tasks.push(sync);
+            }
+            if let Some(sync) = self
+                .run_merkle_tree_hook_syncs(
                     origin,
                     maybe_broadcaster.map(|b| b.subscribe()),
                     task_monitor.clone(),
                 )
-                .await,
-            );
+                .await
+            {
+                tasks.push(sync);
+            }
+            if let Some(sync) = self
+                .run_destination_gas_sync(origin, task_monitor.clone())
+                .await
+            {
+                tasks.push(sync);
+            }
         }
 
         // each message process attempts to send messages from a chain
@@ -386,17 +629,67 @@ impl BaseAgent for Relayer {
             tasks.push(self.run_message_processor(
                 origin,
                 send_channels.clone(),
+                replay_sender.subscribe(),
                 task_monitor.clone(),
             ));
             tasks.push(self.run_merkle_tree_processor(origin, task_monitor.clone()));
         }
 
-        if let Err(err) = try_join_all(tasks).await {
-            tracing::error!(
-                error=?err,
-                "Relayer task panicked"
-            );
+        for igp_claimer in self.igp_claimers.drain(..) {
+            tasks.push(igp_claimer.spawn());
+        }
+        if let Some(missed_delivery_feed) = self.missed_delivery_feed.take() {
+            tasks.push(missed_delivery_feed.spawn());
         }
+        tasks.push(delivery_slo::spawn_evictor(
+            self.msg_ctxs
+                .values()
+                .map(|ctx| ctx.delivery_slo.clone())
+                .collect(),
+        ));
+
+        // Submitters only stop on their own once asked to shut down, so they are
+        // given the time to confirm what they submitted before exiting
+        let mut submitters = try_join_all(submitter_tasks);
+        tokio::select! {
+            res = try_join_all(tasks) => {
+                if let Err(err) = res {
+                    tracing::error!(
+                        error=?err,
+                        "Relayer task panicked"
+                    );
+                }
+            }
+            res = &mut submitters => {
+                if let Err(err) = res {
+                    tracing::error!(
+                        error=?err,
+                        "Relayer task panicked"
+                    );
+                }
+            }
+            _ = shutdown_signal() => {
+                info!(
+                    timeout = ?self.shutdown_timeout,
+                    "Received shutdown signal, draining destination submitters"
+                );
+                let _ = shutdown_tx.send(true);
+                if let Err(err) = submitters.await {
+                    tracing::error!(
+                        error=?err,
+                        "Destination submitter panicked while shutting down"
+                    );
+                }
+            }
+        }
+    }
+
+    async fn doctor(
+        settings: &Self::Settings,
+        _metrics: Arc<CoreMetrics>,
+        report: &mut DoctorReport,
+    ) {
+        report.record("db", "rocksdb", check_rocks_db(&settings.db));
     }
 }
 
@@ -423,15 +716,11 @@ impl Relayer {
         origin: &HyperlaneDomain,
         tx_id_receiver: Option<Receiver<H512>>,
         task_monitor: TaskMonitor,
-    ) -> Instrumented<JoinHandle<()>> {
+    ) -> Option<Instrumented<JoinHandle<()>>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index_settings();
-        let contract_sync = self
-            .interchain_gas_payment_syncs
-            .get(origin)
-            .unwrap()
-            .clone();
+        let contract_sync = self.interchain_gas_payment_syncs.get(origin)?.clone();
         let cursor = contract_sync.cursor(index_settings).await;
-        tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
+        let task = tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
             contract_sync
                 .clone()
                 .sync(
@@ -440,7 +729,26 @@ impl Relayer {
                 )
                 .await
         }))
-        .instrument(info_span!("IgpSync"))
+        .instrument(info_span!("IgpSync"));
+        Some(task)
+    }
+
+    async fn run_destination_gas_sync(
+        &self,
+        origin: &HyperlaneDomain,
+        task_monitor: TaskMonitor,
+    ) -> Option<Instrumented<JoinHandle<()>>> {
+        let index_settings = self.as_ref().settings.chains[origin.name()].index_settings();
+        let contract_sync = self.destination_gas_syncs.get(origin)?.clone();
+        let cursor = contract_sync.cursor(index_settings).await;
+        let task = tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
+            contract_sync
+                .clone()
+                .sync("destination_gas", cursor.into())
+                .await
+        }))
+        .instrument(info_span!("DestinationGasSync"));
+        Some(task)
     }
 
     async fn run_merkle_tree_hook_syncs(
@@ -448,11 +756,11 @@ impl Relayer {
         origin: &HyperlaneDomain,
         tx_id_receiver: Option<Receiver<H512>>,
         task_monitor: TaskMonitor,
-    ) -> Instrumented<JoinHandle<()>> {
+    ) -> Option<Instrumented<JoinHandle<()>>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index.clone();
-        let contract_sync = self.merkle_tree_hook_syncs.get(origin).unwrap().clone();
+        let contract_sync = self.merkle_tree_hook_syncs.get(origin)?.clone();
         let cursor = contract_sync.cursor(index_settings).await;
-        tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
+        let task = tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
             contract_sync
                 .clone()
                 .sync(
@@ -461,13 +769,15 @@ impl Relayer {
                 )
                 .await
         }))
-        .instrument(info_span!("MerkleTreeHookSync"))
+        .instrument(info_span!("MerkleTreeHookSync"));
+        Some(task)
     }
 
     fn run_message_processor(
         &self,
         origin: &HyperlaneDomain,
         send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
+        dead_letter_replays: Receiver<DeadLetterReplayRequest>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let metrics = MessageProcessorMetrics::new(
@@ -490,6 +800,18 @@ impl Relayer {
                 )
             })
             .collect();
+        let tenant_ctxs = self
+            .tenant_msg_ctxs
+            .iter()
+            .map(|(tenant, msg_ctxs)| {
+                let ctxs = msg_ctxs
+                    .iter()
+                    .filter(|(key, _)| key.origin == origin.id())
+                    .map(|(key, ctx)| (key.destination, ctx.clone()))
+                    .collect();
+                (tenant.clone(), ctxs)
+            })
+            .collect();
 
         let message_processor = MessageProcessor::new(
             self.dbs.get(origin).unwrap().clone(),
@@ -499,7 +821,9 @@ impl Relayer {
             metrics,
             send_channels,
             destination_ctxs,
+            tenant_ctxs,
             self.metric_app_contexts.clone(),
+            dead_letter_replays,
         );
 
         let span = info_span!("MessageProcessor", origin=%message_processor.domain());
@@ -526,13 +850,16 @@ impl Relayer {
     }
 
     #[allow(clippy::too_many_arguments)]
-    #[tracing::instrument(skip(self, receiver))]
+    #[tracing::instrument(skip(self, receiver, shutdown))]
     fn run_destination_submitter(
         &self,
         destination: &HyperlaneDomain,
         receiver: UnboundedReceiver<QueueOperation>,
         retry_receiver_channel: Sender<MessageRetryRequest>,
+        shutdown: watch::Receiver<bool>,
         batch_size: u32,
+        max_inflight_transactions: Option<u32>,
+        confirmation_batch_size: Option<u32>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let serial_submitter = SerialSubmitter::new(
@@ -541,6 +868,11 @@ impl Relayer {
             retry_receiver_channel,
             SerialSubmitterMetrics::new(&self.core.metrics, destination),
             batch_size,
+            max_inflight_transactions,
+            confirmation_batch_size,
+            self.lane_weights.clone(),
+            shutdown,
+            self.shutdown_timeout,
             task_monitor.clone(),
         );
         let span = info_span!("SerialSubmitter", destination=%destination);
@@ -0,0 +1,231 @@
+//! Re-simulate the delivery of indexed messages against the state their
+//! destination had at a past block, with the metadata builders of this
+//! relayer. Reports whether the relayer as it is today would have been able
+//! to deliver them then, e.g. to check a fix against a past incident.
+
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc};
+
+use clap::Args;
+use eyre::{bail, eyre, Context, Result};
+use hyperlane_base::{
+    db::{HyperlaneRocksDB, DB},
+    settings::{ChainConf, ChainConnectionConf},
+};
+use hyperlane_core::{HyperlaneMessage, Mailbox, ValidatorAnnounce, H256};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+
+use crate::{
+    merkle_tree::builder::MerkleTreeBuilder,
+    msg::metadata::{
+        BaseMetadataBuilder, DefaultIsmCache, IsmAwareAppContextClassifier, MessageMetadataBuilder,
+    },
+    settings::RelayerSettings,
+};
+
+/// Which messages to re-simulate, and at which block
+#[derive(Args, Debug)]
+pub struct ResimulateArgs {
+    /// Name of the origin chain of the messages
+    #[arg(long)]
+    origin: String,
+    /// Block of the destination to simulate at. It must be before the
+    /// message was delivered, and the rpc of the destination must be an
+    /// archive node that still has its state.
+    #[arg(long)]
+    block: u64,
+    /// Id of the message
+    #[arg(long, conflicts_with_all = ["from_nonce", "to_nonce"])]
+    message_id: Option<String>,
+    /// Lowest nonce to re-simulate
+    #[arg(long, required_unless_present = "message_id")]
+    from_nonce: Option<u32>,
+    /// Highest nonce to re-simulate. Defaults to `from_nonce`.
+    #[arg(long)]
+    to_nonce: Option<u32>,
+    /// Path to the relayer database. Defaults to that of the relayer config.
+    #[arg(long)]
+    db: Option<PathBuf>,
+}
+
+/// What a destination is simulated with
+struct Destination {
+    mailbox: Arc<dyn Mailbox>,
+    metadata_builder: Arc<BaseMetadataBuilder>,
+}
+
+/// Re-simulate the messages, printing a line of JSON for each. Messages are
+/// read from the database of the relayer, which must have indexed them, and
+/// chains are configured like they are for the relayer.
+pub async fn resimulate(args: ResimulateArgs) -> Result<()> {
+    let settings =
+        RelayerSettings::load_without_arguments().context("Failed to load the relayer config")?;
+    let origin = settings.lookup_domain(&args.origin)?;
+    // Metrics aren't served, but the contracts record them
+    let metrics = settings.metrics("relayer_resimulate")?;
+    let db_path = args.db.clone().unwrap_or_else(|| settings.db.clone());
+    let db = HyperlaneRocksDB::new(&origin, DB::from_path_read_only(&db_path)?);
+
+    let messages = selected_messages(&db, &args)?;
+    let prover_sync = Arc::new(RwLock::new(origin_merkle_tree(&db).await?));
+    let validator_announce: Arc<dyn ValidatorAnnounce> = settings
+        .chain_setup(&origin)?
+        .build_validator_announce(&metrics)
+        .await?
+        .into();
+    let custom_isms = Arc::new(settings.custom_isms.clone());
+
+    let mut destinations: HashMap<u32, Destination> = HashMap::new();
+    for message in messages {
+        if !destinations.contains_key(&message.destination) {
+            let destination_setup =
+                historical_chain_setup(&settings, message.destination, args.block)?;
+            let mailbox: Arc<dyn Mailbox> = destination_setup.build_mailbox(&metrics).await?.into();
+            let metadata_builder = BaseMetadataBuilder::new(
+                origin.clone(),
+                destination_setup,
+                prover_sync.clone(),
+                validator_announce.clone(),
+                settings.allow_local_checkpoint_syncers,
+                metrics.clone(),
+                db.clone(),
+                IsmAwareAppContextClassifier::new(
+                    Arc::new(DefaultIsmCache::new(mailbox.clone())),
+                    settings.metric_app_contexts.clone(),
+                ),
+                custom_isms.clone(),
+            );
+            destinations.insert(
+                message.destination,
+                Destination {
+                    mailbox,
+                    metadata_builder: Arc::new(metadata_builder),
+                },
+            );
+        }
+        let destination = &destinations[&message.destination];
+        println!(
+            "{}",
+            resimulate_message(&message, destination, args.block).await
+        );
+    }
+    Ok(())
+}
+
+/// The setup of the destination, with reads and gas estimates made at the
+/// block
+fn historical_chain_setup(
+    settings: &RelayerSettings,
+    domain_id: u32,
+    block: u64,
+) -> Result<ChainConf> {
+    let mut setup = settings
+        .chains
+        .values()
+        .find(|chain| chain.domain.id() == domain_id)
+        .cloned()
+        .ok_or_else(|| eyre!("No chain setup found for domain {domain_id}"))?;
+    match &mut setup.connection {
+        ChainConnectionConf::Ethereum(conn) => conn.historical_block = Some(block),
+        _ => bail!(
+            "Only EVM destinations can be re-simulated, {} is not one",
+            setup.domain
+        ),
+    }
+    Ok(setup)
+}
+
+/// The indexed messages to re-simulate, in nonce order
+fn selected_messages(
+    db: &HyperlaneRocksDB,
+    args: &ResimulateArgs,
+) -> Result<Vec<HyperlaneMessage>> {
+    if let Some(message_id) = &args.message_id {
+        let message_id = H256::from_str(message_id.trim_start_matches("0x"))
+            .map_err(|e| eyre!("Invalid message id `{message_id}`: {e}"))?;
+        let message = db
+            .retrieve_message_by_id(&message_id)?
+            .ok_or_else(|| eyre!("Message {message_id:?} is not indexed"))?;
+        return Ok(vec![message]);
+    }
+    let from_nonce = args.from_nonce.unwrap_or_default();
+    let to_nonce = args.to_nonce.unwrap_or(from_nonce);
+    let mut messages = vec![];
+    for nonce in from_nonce..=to_nonce {
+        let message = db
+            .retrieve_message_by_nonce(nonce)?
+            .ok_or_else(|| eyre!("Message with nonce {nonce} is not indexed"))?;
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+/// The merkle tree of the origin, with every insertion the relayer indexed
+async fn origin_merkle_tree(db: &HyperlaneRocksDB) -> Result<MerkleTreeBuilder> {
+    let mut tree = MerkleTreeBuilder::new();
+    let mut leaf_index = 0;
+    while let Some(insertion) = db.retrieve_merkle_tree_insertion_by_leaf_index(&leaf_index)? {
+        tree.ingest_message_id(insertion.message_id()).await?;
+        leaf_index += 1;
+    }
+    Ok(tree)
+}
+
+/// Build the metadata of the message and simulate its delivery, like the
+/// relayer prepares a message. The report names the first step that failed.
+async fn resimulate_message(
+    message: &HyperlaneMessage,
+    destination: &Destination,
+    block: u64,
+) -> Value {
+    let mut report = json!({
+        "id": format!("{:?}", message.id()),
+        "nonce": message.nonce,
+        "destination": destination.metadata_builder.destination_domain().name(),
+        "block": block,
+    });
+    let failed = |mut report: Value, step: &str, reason: String| {
+        report["ok"] = json!(false);
+        report["failed_step"] = json!(step);
+        report["reason"] = json!(reason);
+        report
+    };
+
+    let ism_address = match destination.mailbox.recipient_ism(message.recipient).await {
+        Ok(ism_address) => ism_address,
+        Err(err) => return failed(report, "ism", format!("{err:#}")),
+    };
+    report["ism"] = json!(format!("{ism_address:?}"));
+
+    let ism_with_metadata = match MessageMetadataBuilder::new(
+        ism_address,
+        message,
+        destination.metadata_builder.clone(),
+    )
+    .await
+    {
+        Ok(builder) => builder.build_ism_and_metadata(ism_address, message).await,
+        Err(err) => Err(err),
+    };
+    let ism_with_metadata = match ism_with_metadata {
+        Ok(ism_with_metadata) => ism_with_metadata,
+        Err(err) => return failed(report, "metadata", format!("{err:#}")),
+    };
+    report["module_type"] = json!(format!("{:?}", ism_with_metadata.module_type));
+    let Some(metadata) = ism_with_metadata.metadata else {
+        return failed(report, "metadata", "Could not fetch metadata".to_owned());
+    };
+
+    match destination
+        .mailbox
+        .process_estimate_costs(message, &metadata)
+        .await
+    {
+        Ok(estimate) => {
+            report["ok"] = json!(true);
+            report["gas_limit"] = json!(estimate.gas_limit.to_string());
+            report
+        }
+        Err(err) => failed(report, "simulation", format!("{err:#}")),
+    }
+}
@@ -1,28 +1,51 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
 use axum::{
-    extract::{Query, State},
-    routing, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing, Json, Router,
 };
 use derive_new::new;
-use hyperlane_core::{ChainCommunicationError, QueueOperation, H256};
-use serde::Deserialize;
-use std::str::FromStr;
+use hyperlane_base::db::HyperlaneRocksDB;
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, Decode, HyperlaneDomainProtocol,
+    InterchainAccountMessage, OperationId, QueueOperation, H256,
+};
+use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast::Sender;
+use tracing::warn;
 
 const MESSAGE_RETRY_API_BASE: &str = "/message_retry";
+pub const DEAD_LETTER_REPLAY_API_BASE: &str = "/dead_letter_replay";
+const INTERCHAIN_ACCOUNT_API_BASE: &str = "/interchain_account";
 pub const ENDPOINT_MESSAGES_QUEUE_SIZE: usize = 1_000;
 
 /// Returns a vector of agent-specific endpoint routes to be served.
 /// Can be extended with additional routes and feature flags to enable/disable individually.
-pub fn routes(tx: Sender<MessageRetryRequest>) -> Vec<(&'static str, Router)> {
+pub fn routes(
+    tx: Sender<MessageRetryRequest>,
+    replay_tx: Sender<DeadLetterReplayRequest>,
+    origin_dbs: Vec<HyperlaneRocksDB>,
+    destination_protocols: HashMap<u32, HyperlaneDomainProtocol>,
+) -> Vec<(&'static str, Router)> {
     let message_retry_api = MessageRetryApi::new(tx);
+    let dead_letter_replay_api = DeadLetterReplayApi::new(replay_tx);
+    let interchain_account_api =
+        InterchainAccountApi::new(Arc::new(origin_dbs), Arc::new(destination_protocols));
 
-    vec![message_retry_api.get_route()]
+    vec![
+        message_retry_api.get_route(),
+        dead_letter_replay_api.get_route(),
+        interchain_account_api.get_route(),
+    ]
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MessageRetryRequest {
     MessageId(H256),
     DestinationDomain(u32),
+    /// The operation id the message was logged with
+    OperationId(OperationId),
 }
 
 impl PartialEq<QueueOperation> for &MessageRetryRequest {
@@ -32,6 +55,9 @@ impl PartialEq<QueueOperation> for &MessageRetryRequest {
             MessageRetryRequest::DestinationDomain(destination_domain) => {
                 destination_domain == &other.destination_domain().id()
             }
+            MessageRetryRequest::OperationId(operation_id) => {
+                Some(*operation_id) == other.operation_id()
+            }
         }
     }
 }
@@ -45,6 +71,7 @@ pub struct MessageRetryApi {
 struct RawMessageRetryRequest {
     message_id: Option<String>,
     destination_domain: Option<u32>,
+    operation_id: Option<String>,
 }
 
 impl TryFrom<RawMessageRetryRequest> for Vec<MessageRetryRequest> {
@@ -58,6 +85,9 @@ impl TryFrom<RawMessageRetryRequest> for Vec<MessageRetryRequest> {
         if let Some(destination_domain) = request.destination_domain {
             retry_requests.push(MessageRetryRequest::DestinationDomain(destination_domain));
         }
+        if let Some(operation_id) = request.operation_id {
+            retry_requests.push(MessageRetryRequest::OperationId(operation_id.parse()?));
+        }
         Ok(retry_requests)
     }
 }
@@ -76,7 +106,7 @@ async fn retry_message(
     };
 
     if retry_requests.is_empty() {
-        return "No retry requests found. Please provide either a message_id or destination_domain.".to_string();
+        return "No retry requests found. Please provide either a message_id, destination_domain or operation_id.".to_string();
     }
 
     if let Err(err) = retry_requests
@@ -102,11 +132,149 @@ impl MessageRetryApi {
     }
 }
 
+/// Request to send a dead-lettered message to its destination again, handled
+/// by the message processor of its origin
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct DeadLetterReplayRequest {
+    pub origin_domain: u32,
+    pub nonce: u32,
+}
+
+#[derive(new, Clone)]
+pub struct DeadLetterReplayApi {
+    tx: Sender<DeadLetterReplayRequest>,
+}
+
+async fn replay_dead_letter(
+    State(tx): State<Sender<DeadLetterReplayRequest>>,
+    Query(request): Query<DeadLetterReplayRequest>,
+) -> Result<String, (StatusCode, String)> {
+    tx.send(request).map_err(|err| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Failed to send replay request to the processor: {}", err),
+        )
+    })?;
+    Ok("Queued dead-lettered message for replay".to_string())
+}
+
+impl DeadLetterReplayApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(replay_dead_letter))
+            .with_state(self.tx.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (DEAD_LETTER_REPLAY_API_BASE, self.router())
+    }
+}
+
+/// Shows what the interchain account message of the id in the path will
+/// execute on its destination
+#[derive(new, Clone)]
+pub struct InterchainAccountApi {
+    origin_dbs: Arc<Vec<HyperlaneRocksDB>>,
+    /// Protocols of the destinations, as the interchain account can only be
+    /// derived on EVM chains
+    destination_protocols: Arc<HashMap<u32, HyperlaneDomainProtocol>>,
+}
+
+#[derive(Debug, Serialize)]
+struct InterchainAccountResponse {
+    origin: u32,
+    destination: u32,
+    owner: String,
+    ism: String,
+    /// The account making the calls, if the destination is an EVM chain
+    account: Option<String>,
+    calls: Vec<InterchainAccountCallResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct InterchainAccountCallResponse {
+    to: String,
+    value: String,
+    data: String,
+}
+
+async fn interchain_account(
+    State(api): State<InterchainAccountApi>,
+    Path(id): Path<String>,
+) -> Result<Json<InterchainAccountResponse>, (StatusCode, String)> {
+    let id = H256::from_str(&id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid message id `{id}`"),
+        )
+    })?;
+    let mut message = None;
+    for db in api.origin_dbs.iter() {
+        message = db.retrieve_message_by_id(&id).map_err(|err| {
+            warn!(error = ?err, "Failed to retrieve message");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to retrieve message".to_owned(),
+            )
+        })?;
+        if message.is_some() {
+            break;
+        }
+    }
+    let Some(message) = message else {
+        return Err((StatusCode::NOT_FOUND, format!("Message {id:?} not found")));
+    };
+    let ica = InterchainAccountMessage::read_from(&mut message.body.as_slice()).map_err(|_| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Message {id:?} is not an interchain account message"),
+        )
+    })?;
+    let account = (api.destination_protocols.get(&message.destination)
+        == Some(&HyperlaneDomainProtocol::Ethereum))
+    .then(|| {
+        format!(
+            "{:?}",
+            ica.account(message.origin, message.sender, message.recipient)
+        )
+    });
+    Ok(Json(InterchainAccountResponse {
+        origin: message.origin,
+        destination: message.destination,
+        owner: format!("{:?}", ica.owner),
+        ism: format!("{:?}", ica.ism),
+        account,
+        calls: ica
+            .calls
+            .into_iter()
+            .map(|call| InterchainAccountCallResponse {
+                to: format!("{:?}", call.to),
+                value: call.value.to_string(),
+                data: bytes_to_hex(&call.data),
+            })
+            .collect(),
+    }))
+}
+
+impl InterchainAccountApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/:id", routing::get(interchain_account))
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (INTERCHAIN_ACCOUNT_API_BASE, self.router())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use axum::http::StatusCode;
     use ethers::utils::hex::ToHex;
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{Encode, HyperlaneDomain, HyperlaneMessage, InterchainAccountCall, U256};
     use std::net::SocketAddr;
     use tokio::sync::broadcast::{Receiver, Sender};
 
@@ -174,4 +342,127 @@ mod tests {
             MessageRetryRequest::DestinationDomain(destination_domain)
         );
     }
+
+    #[tokio::test]
+    async fn test_operation_id_retry() {
+        let (addr, mut rx) = setup_test_server();
+
+        let operation_id = OperationId::random();
+
+        let response = reqwest::get(format!(
+            "http://{}{}?operation_id={}",
+            addr, MESSAGE_RETRY_API_BASE, operation_id
+        ))
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            MessageRetryRequest::OperationId(operation_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_replay() {
+        let broadcast_tx = Sender::<DeadLetterReplayRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
+        let mut rx = broadcast_tx.subscribe();
+        let (path, replay_router) = DeadLetterReplayApi::new(broadcast_tx).get_route();
+        let app = Router::new().nest(path, replay_router);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let response = reqwest::get(format!(
+            "http://{}{}?origin_domain=42&nonce=7",
+            addr, DEAD_LETTER_REPLAY_API_BASE
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            DeadLetterReplayRequest {
+                origin_domain: 42,
+                nonce: 7
+            }
+        );
+
+        // Both the origin and the nonce are needed to find the message
+        let response = reqwest::get(format!(
+            "http://{}{}?nonce=7",
+            addr, DEAD_LETTER_REPLAY_API_BASE
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_interchain_account() {
+        test_utils::run_test_db(|db| async move {
+            let origin = HyperlaneDomain::new_test_domain("test_interchain_account");
+            let db = HyperlaneRocksDB::new(&origin, db);
+            let ica = InterchainAccountMessage {
+                owner: H256::repeat_byte(0x11),
+                ism: H256::zero(),
+                calls: vec![InterchainAccountCall {
+                    to: H256::repeat_byte(0x22),
+                    value: U256::from(5),
+                    data: vec![0xab, 0xcd],
+                }],
+            };
+            let message = HyperlaneMessage {
+                origin: origin.id(),
+                destination: 42,
+                body: ica.to_vec(),
+                ..Default::default()
+            };
+            let transfer = HyperlaneMessage {
+                nonce: 1,
+                body: vec![0; 64],
+                ..message.clone()
+            };
+            db.store_message(&message, 1).unwrap();
+            db.store_message(&transfer, 2).unwrap();
+
+            let (path, router) = InterchainAccountApi::new(
+                Arc::new(vec![db]),
+                Arc::new(HashMap::from([(42, HyperlaneDomainProtocol::Ethereum)])),
+            )
+            .get_route();
+            let app = Router::new().nest(path, router);
+            let server =
+                axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+            let addr = server.local_addr();
+            tokio::spawn(server);
+            let get = |id: H256| {
+                reqwest::get(format!(
+                    "http://{}{}/{:?}",
+                    addr, INTERCHAIN_ACCOUNT_API_BASE, id
+                ))
+            };
+
+            let response = get(message.id()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let response: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(
+                response["account"],
+                format!(
+                    "{:?}",
+                    ica.account(message.origin, message.sender, message.recipient)
+                )
+            );
+            assert_eq!(response["calls"][0]["value"], "5");
+            assert_eq!(response["calls"][0]["data"], "0xabcd");
+
+            let response = get(transfer.id()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let response = get(H256::random()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        })
+        .await;
+    }
 }
@@ -8,7 +8,7 @@ use std::{
     marker::PhantomData,
 };
 
-use hyperlane_core::{config::StrOrInt, utils::hex_or_base58_to_h256, HyperlaneMessage, H256};
+use hyperlane_core::{config::StrOrInt, validate_hex_or_base58_address, HyperlaneMessage, H256};
 use serde::{
     de::{Error, SeqAccess, Visitor},
     Deserialize, Deserializer,
@@ -311,7 +311,7 @@ fn to_serde_err<IE: ToString, OE: Error>(e: IE) -> OE {
 }
 
 fn parse_addr<E: Error>(addr_str: &str) -> Result<H256, E> {
-    hex_or_base58_to_h256(addr_str).map_err(to_serde_err)
+    validate_hex_or_base58_address(addr_str).map_err(to_serde_err)
 }
 
 #[cfg(test)]
@@ -455,9 +455,22 @@ mod test {
         ).unwrap();
     }
 
+    #[test]
+    fn rejects_invalid_addresses() {
+        // The checksum of the second address is wrong
+        assert!(serde_json::from_str::<MatchingList>(
+            r#"[{"senderaddress":["0x6AD4DEBA8A147d000C09de6465267a9047d1c217","0x6AD4DEBA8A147d000C09de6465267a9047d1c218"]}]"#,
+        )
+        .is_err());
+        assert!(serde_json::from_str::<MatchingList>(
+            r#"[{"recipientaddress":"0x6AD4DEBA8A147d000C09de6465267a9047d1c2"}]"#,
+        )
+        .is_err());
+    }
+
     #[test]
     fn supports_sequence_h256s() {
-        let json_str = r#"[{"origindomain":1399811151,"senderaddress":["0x6AD4DEBA8A147d000C09de6465267a9047d1c217","0x6AD4DEBA8A147d000C09de6465267a9047d1c218"],"destinationdomain":11155111,"recipientaddress":["0x6AD4DEBA8A147d000C09de6465267a9047d1c217","0x6AD4DEBA8A147d000C09de6465267a9047d1c218"]}]"#;
+        let json_str = r#"[{"origindomain":1399811151,"senderaddress":["0x6AD4DEBA8A147d000C09de6465267a9047d1c217","0x6aD4DEba8A147D000C09DE6465267a9047D1C218"],"destinationdomain":11155111,"recipientaddress":["0x6AD4DEBA8A147d000C09de6465267a9047d1c217","0x6aD4DEba8A147D000C09DE6465267a9047D1C218"]}]"#;
 
         // Test parsing directly into MatchingList
         serde_json::from_str::<MatchingList>(json_str).unwrap();
@@ -4,20 +4,27 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
 
-use convert_case::Case;
+use convert_case::{Case, Casing};
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use ethers::utils::hex;
 use eyre::{eyre, Context};
 use hyperlane_base::{
     impl_loadable_from_settings,
     settings::{
-        parser::{recase_json_value, RawAgentConf, ValueParser},
-        Settings,
+        loader::load_settings_without_arguments,
+        parser::{parse_signer, recase_json_value, RawAgentConf, ValueParser},
+        Settings, SignerConf,
     },
 };
-use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, U256};
+use hyperlane_core::{
+    cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneMessage, H160, H256, U256,
+};
 use itertools::Itertools;
 use serde::Deserialize;
 use serde_json::Value;
@@ -61,6 +68,288 @@ pub struct RelayerSettings {
     pub allow_local_checkpoint_syncers: bool,
     /// App contexts used for metrics.
     pub metric_app_contexts: Vec<(MatchingList, String)>,
+    /// Weights for fair scheduling across (origin -> destination) lanes. If not
+    /// set, operations to a destination are processed in priority order.
+    pub lane_weights: Option<LaneWeights>,
+    /// Post-dispatch hook metadata that messages of an app are quoted and
+    /// delivered with.
+    pub hook_metadata: Vec<HookMetadataConf>,
+    /// Origin chains whose interchain gas paymaster fees are periodically
+    /// claimed.
+    pub igp_claims: Vec<IgpClaimConf>,
+    /// How often to check whether fees should be claimed.
+    pub igp_claim_interval: Duration,
+    /// Maximum age of the messages of an app before they are moved to the
+    /// dead-letter store instead of being retried.
+    pub message_expiry: Vec<MessageExpiryConf>,
+    /// Time the messages of an app should be delivered in. Messages nearing
+    /// it are retried sooner and submitted with higher fees. Includes the
+    /// deadlines of the hook metadata of apps.
+    pub delivery_deadlines: Vec<DeliveryDeadlineConf>,
+    /// Static gas added to the estimates of an app's messages.
+    pub gas_overheads: Vec<GasOverheadConf>,
+    /// Gas limits an app's messages are delivered with instead of their
+    /// estimate.
+    pub fixed_gas_limits: Vec<FixedGasLimitConf>,
+    /// How many confirmations the delivery of an app's messages waits for
+    /// before it is complete.
+    pub confirmation_depths: Vec<ConfirmationDepthConf>,
+    /// How long to keep confirming the operations already submitted after
+    /// being asked to shut down.
+    pub shutdown_timeout: Duration,
+    /// Customers whose messages are relayed with their own signers, gas
+    /// payment policies and spend budgets. A message is relayed for the first
+    /// tenant it matches, and with the relayer's own config if it matches none.
+    pub tenants: Vec<TenantConf>,
+    /// Scraper feed of undelivered messages, whose messages the relayer's own
+    /// indexing missed are relayed too.
+    pub missed_delivery_feed: Option<MissedDeliveryFeedConf>,
+    /// Target time to deliver messages in, of which the share of deliveries
+    /// that met it is exported per lane.
+    pub slo_delivery_target: Duration,
+    /// Time window over which delivery times are aggregated into the SLO
+    /// metrics of each lane.
+    pub slo_window: Duration,
+    /// How the metadata of ISMs of module types the relayer doesn't know is
+    /// built. The first config an ISM matches is used.
+    pub custom_isms: Vec<CustomIsmConf>,
+}
+
+/// Config for relaying the undelivered messages a scraper knows of
+#[derive(Debug, Clone)]
+pub struct MissedDeliveryFeedConf {
+    /// Base URL of the scraper's HTTP API
+    pub url: String,
+    /// API key sent to the scraper, if it requires one
+    pub api_key: Option<String>,
+    /// Only messages dispatched longer than this ago are considered missed
+    pub min_age: Duration,
+    /// How often the feed is polled
+    pub interval: Duration,
+}
+
+/// Config for building the metadata of ISMs of a module type the relayer
+/// doesn't know, e.g. app-specific ones, without a relayer change
+#[derive(Debug, Clone)]
+pub struct CustomIsmConf {
+    /// Module type the ISMs report. Matches any module type the relayer
+    /// doesn't know if not set.
+    pub module_type: Option<u8>,
+    /// Addresses of the ISMs. Matches any ISM of the module type if empty.
+    pub isms: Vec<H256>,
+    pub strategy: CustomIsmStrategy,
+}
+
+impl CustomIsmConf {
+    /// Whether the ISM at the address, which reports the module type if
+    /// known, is one of these
+    pub fn matches(&self, ism: &H256, module_type: Option<u8>) -> bool {
+        (self.isms.is_empty() || self.isms.contains(ism))
+            && self
+                .module_type
+                .map_or(true, |expected| module_type == Some(expected))
+    }
+}
+
+/// How the metadata of a custom ISM is built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomIsmStrategy {
+    /// The ISM needs no metadata
+    Null,
+    /// The metadata is fetched from an HTTP service, which is sent the
+    /// message and the ISM
+    Http { url: String },
+    /// The metadata is part of the message body, from `offset` to the end
+    /// or for `length` bytes
+    MessageBody {
+        offset: usize,
+        length: Option<usize>,
+    },
+}
+
+/// Config for a tenant of the relayer
+#[derive(Debug, Clone)]
+pub struct TenantConf {
+    /// Name the tenant's metrics are labeled with
+    pub name: String,
+    /// Messages matching this list are relayed for the tenant
+    pub matching_list: MatchingList,
+    /// Gas payment policies of the tenant's messages. If empty, the
+    /// relayer's are used.
+    pub gas_payment_enforcement: Vec<GasPaymentEnforcementConf>,
+    /// Signers the tenant's messages are delivered with, by destination.
+    /// Destinations without one use the relayer's signer.
+    pub signers: HashMap<HyperlaneDomain, SignerConf>,
+    /// Most the tenant's messages may spend on gas on a destination
+    pub budgets: Vec<TenantBudgetConf>,
+}
+
+/// Config for how much a tenant's messages may spend on gas on a destination
+#[derive(Debug, Clone)]
+pub struct TenantBudgetConf {
+    /// The destination chain
+    pub domain: HyperlaneDomain,
+    /// Most native tokens spent per period, in the smallest denomination
+    pub max_spend: U256,
+    /// How often the spend is reset
+    pub period: Duration,
+}
+
+/// Config for the fixed gas limit of an app
+#[derive(Debug, Clone)]
+pub struct FixedGasLimitConf {
+    /// Messages matching this list belong to the app, e.g. by their recipient
+    pub matching_list: MatchingList,
+    /// Gas limit the app's messages are delivered with
+    pub gas_limit: U256,
+}
+
+/// Config for how many confirmations the delivery of an app's messages waits
+/// for
+#[derive(Debug, Clone)]
+pub struct ConfirmationDepthConf {
+    /// Messages matching this list belong to the app. Matches every message
+    /// if not set.
+    pub matching_list: MatchingList,
+    /// If set, only warp transfers of at least this amount belong to the app
+    pub min_transfer_amount: Option<U256>,
+    /// Confirmations the delivery waits for
+    pub depth: ConfirmationDepth,
+}
+
+/// How many confirmations a delivery waits for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationDepth {
+    /// A number of blocks, including the one the message was delivered in
+    Blocks(u32),
+    /// Until the block the message was delivered in is past the
+    /// destination's reorg period
+    Finalized,
+}
+
+/// Config for the gas overhead of an app
+#[derive(Debug, Clone)]
+pub struct GasOverheadConf {
+    /// Messages matching this list belong to the app
+    pub matching_list: MatchingList,
+    /// Gas added to the estimated gas limit of the app's messages
+    pub overhead: U256,
+}
+
+/// Config for expiring the messages of an app
+#[derive(Debug, Clone)]
+pub struct MessageExpiryConf {
+    /// Messages matching this list belong to the app
+    pub matching_list: MatchingList,
+    /// Messages dispatched longer than this ago are no longer retried
+    pub max_age: Duration,
+}
+
+/// Default maximum percentage by which the fees of a message are bumped as
+/// its delivery deadline nears
+pub const DEFAULT_MAX_DEADLINE_FEE_BUMP_PERCENT: u64 = 50;
+
+/// Config for the delivery deadline of the messages of an app
+#[derive(Debug, Clone)]
+pub struct DeliveryDeadlineConf {
+    /// Messages matching this list belong to the app
+    pub matching_list: MatchingList,
+    /// Messages should be delivered within this time of being dispatched
+    pub deadline: Duration,
+    /// Percentage by which fees are bumped when the deadline is reached
+    pub max_fee_bump_percent: u64,
+}
+
+/// Config for claiming the fees held by the interchain gas paymaster of a chain
+#[derive(Debug, Clone)]
+pub struct IgpClaimConf {
+    /// The chain of the paymaster
+    pub domain: HyperlaneDomain,
+    /// Fees are only claimed once the paymaster holds more than this amount
+    pub threshold: U256,
+    /// If set, fees are only claimed if they are swept to this address
+    pub beneficiary: Option<H256>,
+}
+
+/// Config for the hook metadata of an app
+#[derive(Debug, Clone, Default)]
+pub struct HookMetadataConf {
+    /// Messages matching this list belong to the app
+    pub matching_list: MatchingList,
+    /// Value forwarded to the hooks
+    pub msg_value: U256,
+    /// Gas limit the app expects `handle` to be called with
+    pub gas_limit: Option<U256>,
+    /// Refund address. Defaults to the message sender.
+    pub refund_address: Option<H160>,
+    /// Hook-specific metadata
+    pub custom: Vec<u8>,
+    /// Time the app's messages should be delivered in, if any
+    pub deadline: Option<Duration>,
+}
+
+/// Config of an app, whose messages are those matching its list
+pub trait AppConf {
+    /// Messages matching this list belong to the app
+    fn matching_list(&self) -> &MatchingList;
+}
+
+/// Returns the conf of the first app the message belongs to. Apps are
+/// matched in the order they are configured, so earlier apps take precedence.
+pub fn first_matching_app<'a, T: AppConf>(
+    confs: &'a [T],
+    message: &HyperlaneMessage,
+) -> Option<&'a T> {
+    confs
+        .iter()
+        .find(|conf| conf.matching_list().msg_matches(message, false))
+}
+
+macro_rules! impl_app_conf {
+    ($($conf:ty),*) => {
+        $(impl AppConf for $conf {
+            fn matching_list(&self) -> &MatchingList {
+                &self.matching_list
+            }
+        })*
+    };
+}
+
+impl_app_conf!(
+    FixedGasLimitConf,
+    GasOverheadConf,
+    MessageExpiryConf,
+    DeliveryDeadlineConf,
+    HookMetadataConf
+);
+
+/// Weights used to share a destination's submission capacity between the
+/// origins sending messages to it.
+#[derive(Debug, Clone)]
+pub struct LaneWeights {
+    /// Weight of any lane without an explicit weight
+    pub default_weight: u32,
+    /// Explicit weights keyed by `(origin, destination)` domain ids
+    pub weights: HashMap<(u32, u32), u32>,
+}
+
+impl Default for LaneWeights {
+    fn default() -> Self {
+        Self {
+            default_weight: 1,
+            weights: HashMap::new(),
+        }
+    }
+}
+
+impl LaneWeights {
+    /// Get the weight of the lane from `origin` to `destination`
+    pub fn weight(&self, origin: u32, destination: u32) -> u32 {
+        self.weights
+            .get(&(origin, destination))
+            .copied()
+            .unwrap_or(self.default_weight)
+    }
 }
 
 /// Config for gas payment enforcement
@@ -95,6 +384,14 @@ struct RawRelayerSettings(Value);
 
 impl_loadable_from_settings!(Relayer, RawRelayerSettings -> RelayerSettings);
 
+impl RelayerSettings {
+    /// Load the settings from the config files and the environment alone, for
+    /// tools that parse command line arguments of their own
+    pub fn load_without_arguments() -> ConfigResult<Self> {
+        load_settings_without_arguments::<RawRelayerSettings, _, _>(())
+    }
+}
+
 impl FromRawConf<RawRelayerSettings> for RelayerSettings {
     fn from_config_filtered(
         raw: RawRelayerSettings,
@@ -125,61 +422,7 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .parse_from_str("Expected database path")
             .unwrap_or_else(|| std::env::current_dir().unwrap().join("hyperlane_db"));
 
-        let (raw_gas_payment_enforcement_path, raw_gas_payment_enforcement) = p
-            .get_opt_key("gasPaymentEnforcement")
-            .take_config_err_flat(&mut err)
-            .and_then(parse_json_array)
-            .unwrap_or_else(|| (&p.cwp + "gas_payment_enforcement", Value::Array(vec![])));
-
-        let gas_payment_enforcement_parser = ValueParser::new(
-            raw_gas_payment_enforcement_path,
-            &raw_gas_payment_enforcement,
-        );
-        let mut gas_payment_enforcement = gas_payment_enforcement_parser.into_array_iter().map(|itr| {
-            itr.filter_map(|policy| {
-                let policy_type = policy.chain(&mut err).get_opt_key("type").parse_string().end();
-                let minimum_is_defined = matches!(policy.get_opt_key("minimum"), Ok(Some(_)));
-
-                let matching_list = policy.chain(&mut err).get_opt_key("matchingList").and_then(parse_matching_list).unwrap_or_default();
-
-                let parse_minimum = |p| GasPaymentEnforcementPolicy::Minimum { payment: p };
-                match policy_type {
-                    Some("minimum") => policy.chain(&mut err).get_opt_key("payment").parse_u256().end().map(parse_minimum),
-                    None if minimum_is_defined => policy.chain(&mut err).get_opt_key("payment").parse_u256().end().map(parse_minimum),
-                    Some("none") | None => Some(GasPaymentEnforcementPolicy::None),
-                    Some("onChainFeeQuoting") => {
-                        let gas_fraction = policy.chain(&mut err)
-                            .get_opt_key("gasFraction")
-                            .parse_string()
-                            .map(|v| v.replace(' ', ""))
-                            .unwrap_or_else(|| "1/2".to_owned());
-                        let (numerator, denominator) = gas_fraction
-                            .split_once('/')
-                            .ok_or_else(|| eyre!("Invalid `gas_fraction` for OnChainFeeQuoting gas payment enforcement policy; expected `numerator / denominator`"))
-                            .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                            .unwrap_or(("1", "1"));
-
-                        Some(GasPaymentEnforcementPolicy::OnChainFeeQuoting {
-                            gas_fraction_numerator: numerator
-                                .parse()
-                                .context("Error parsing gas fraction numerator")
-                                .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                                .unwrap_or(1),
-                            gas_fraction_denominator: denominator
-                                .parse()
-                                .context("Error parsing gas fraction denominator")
-                                .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                                .unwrap_or(1),
-                        })
-                    }
-                    Some(pt) => Err(eyre!("Unknown gas payment enforcement policy type `{pt}`"))
-                        .take_err(&mut err, || cwp + "type"),
-                }.map(|policy| GasPaymentEnforcementConf {
-                    policy,
-                    matching_list,
-                })
-            }).collect_vec()
-        }).unwrap_or_default();
+        let mut gas_payment_enforcement = parse_gas_payment_enforcement(&p, &mut err);
 
         if gas_payment_enforcement.is_empty() {
             gas_payment_enforcement.push(GasPaymentEnforcementConf::default());
@@ -223,8 +466,234 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .parse_bool()
             .unwrap_or(false);
 
+        let (raw_igp_claims_path, raw_igp_claims) = p
+            .get_opt_key("igpClaims")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "igp_claims", Value::Array(vec![])));
+
+        let igp_claims_parser = ValueParser::new(raw_igp_claims_path, &raw_igp_claims);
+        let raw_igp_claims = igp_claims_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|claim| {
+                    let chain = claim.chain(&mut err).get_key("chain").parse_string().end();
+                    let threshold = claim
+                        .chain(&mut err)
+                        .get_opt_key("threshold")
+                        .parse_u256()
+                        .unwrap_or_default();
+                    let beneficiary = claim
+                        .chain(&mut err)
+                        .get_opt_key("beneficiary")
+                        .parse_address_hash()
+                        .end();
+                    Some((chain?, threshold, beneficiary, claim.cwp.clone()))
+                })
+                .collect_vec()
+            })
+            .unwrap_or_default();
+
+        let igp_claim_interval = p
+            .chain(&mut err)
+            .get_opt_key("igpClaimInterval")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60 * 60));
+
+        let shutdown_timeout = p
+            .chain(&mut err)
+            .get_opt_key("shutdownTimeout")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60));
+
+        let missed_delivery_feed = p
+            .get_opt_key("missedDeliveryFeed")
+            .take_config_err_flat(&mut err)
+            .and_then(|feed| {
+                let url = feed
+                    .chain(&mut err)
+                    .get_key("url")
+                    .parse_string()
+                    .end()
+                    .map(|url| url.trim_end_matches('/').to_owned());
+                let api_key = feed
+                    .chain(&mut err)
+                    .get_opt_key("apiKey")
+                    .parse_string()
+                    .end()
+                    .map(str::to_owned);
+                let min_age = feed
+                    .chain(&mut err)
+                    .get_opt_key("minAge")
+                    .parse_u64()
+                    .map(Duration::from_secs)
+                    .unwrap_or(Duration::from_secs(60 * 60));
+                let interval = feed
+                    .chain(&mut err)
+                    .get_opt_key("interval")
+                    .parse_u64()
+                    .map(Duration::from_secs)
+                    .unwrap_or(Duration::from_secs(5 * 60));
+                Some(MissedDeliveryFeedConf {
+                    url: url?,
+                    api_key,
+                    min_age,
+                    interval,
+                })
+            });
+
+        let slo_delivery_target = p
+            .chain(&mut err)
+            .get_opt_key("sloDeliveryTarget")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(5 * 60));
+
+        let slo_window = p
+            .chain(&mut err)
+            .get_opt_key("sloWindow")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60 * 60));
+
+        let (raw_tenants_path, raw_tenants) = p
+            .get_opt_key("tenants")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "tenants", Value::Array(vec![])));
+
+        let tenants_parser = ValueParser::new(raw_tenants_path, &raw_tenants);
+        let raw_tenants = tenants_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|tenant| {
+                    let name = tenant.chain(&mut err).get_key("name").parse_string().end();
+                    let matching_list = tenant
+                        .chain(&mut err)
+                        .get_key("matchingList")
+                        .and_then(parse_matching_list)
+                        .end();
+                    let gas_payment_enforcement = parse_gas_payment_enforcement(&tenant, &mut err);
+                    let signers = tenant
+                        .chain(&mut err)
+                        .get_opt_key("signers")
+                        .into_array_iter()
+                        .map(|itr| {
+                            itr.filter_map(|signer| {
+                                let chain =
+                                    signer.chain(&mut err).get_key("chain").parse_string().end();
+                                let conf = signer
+                                    .chain(&mut err)
+                                    .get_key("signer")
+                                    .and_then(parse_signer)
+                                    .end();
+                                Some((chain?, conf?, signer.cwp.clone()))
+                            })
+                            .collect_vec()
+                        })
+                        .unwrap_or_default();
+                    let budgets = tenant
+                        .chain(&mut err)
+                        .get_opt_key("budgets")
+                        .into_array_iter()
+                        .map(|itr| {
+                            itr.filter_map(|budget| {
+                                let chain =
+                                    budget.chain(&mut err).get_key("chain").parse_string().end();
+                                let max_spend = budget
+                                    .chain(&mut err)
+                                    .get_key("maxSpend")
+                                    .parse_u256()
+                                    .end();
+                                let period = budget
+                                    .chain(&mut err)
+                                    .get_opt_key("period")
+                                    .parse_u64()
+                                    .map(Duration::from_secs)
+                                    .unwrap_or(Duration::from_secs(60 * 60 * 24));
+                                Some((chain?, max_spend?, period, budget.cwp.clone()))
+                            })
+                            .collect_vec()
+                        })
+                        .unwrap_or_default();
+                    Some((
+                        name?.to_owned(),
+                        matching_list?,
+                        gas_payment_enforcement,
+                        signers,
+                        budgets,
+                    ))
+                })
+                .collect_vec()
+            })
+            .unwrap_or_default();
+
         cfg_unwrap_all!(cwp, err: [base]);
 
+        let igp_claims = raw_igp_claims
+            .into_iter()
+            .filter_map(|(chain, threshold, beneficiary, claim_cwp)| {
+                let domain = base
+                    .lookup_domain(chain)
+                    .context("Missing configuration for a chain in `igpClaims`")
+                    .into_config_result(|| &claim_cwp + "chain")
+                    .take_config_err(&mut err)?;
+                Some(IgpClaimConf {
+                    domain,
+                    threshold,
+                    beneficiary,
+                })
+            })
+            .collect();
+
+        let mut tenant_names = HashSet::new();
+        let tenants = raw_tenants
+            .into_iter()
+            .filter_map(
+                |(name, matching_list, gas_payment_enforcement, raw_signers, raw_budgets)| {
+                    if !tenant_names.insert(name.clone()) {
+                        Err(eyre!("Duplicate tenant `{name}`"))
+                            .take_err(&mut err, || cwp + "tenants")?;
+                    }
+                    let signers = raw_signers
+                        .into_iter()
+                        .filter_map(|(chain, signer, signer_cwp)| {
+                            let domain = base
+                                .lookup_domain(chain)
+                                .context("Missing configuration for a chain in tenant `signers`")
+                                .into_config_result(|| &signer_cwp + "chain")
+                                .take_config_err(&mut err)?;
+                            Some((domain, signer))
+                        })
+                        .collect();
+                    let budgets = raw_budgets
+                        .into_iter()
+                        .filter_map(|(chain, max_spend, period, budget_cwp)| {
+                            let domain = base
+                                .lookup_domain(chain)
+                                .context("Missing configuration for a chain in tenant `budgets`")
+                                .into_config_result(|| &budget_cwp + "chain")
+                                .take_config_err(&mut err)?;
+                            Some(TenantBudgetConf {
+                                domain,
+                                max_spend,
+                                period,
+                            })
+                        })
+                        .collect();
+                    Some(TenantConf {
+                        name,
+                        matching_list,
+                        gas_payment_enforcement,
+                        signers,
+                        budgets,
+                    })
+                },
+            )
+            .collect();
+
         let skip_transaction_gas_limit_for = skip_transaction_gas_limit_for_names
             .into_iter()
             .filter_map(|chain| {
@@ -273,6 +742,247 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             })
             .unwrap_or_default();
 
+        let fair_scheduling = p
+            .chain(&mut err)
+            .get_opt_key("fairScheduling")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let default_lane_weight = p
+            .chain(&mut err)
+            .get_opt_key("defaultLaneWeight")
+            .parse_u32()
+            .unwrap_or(1)
+            .max(1);
+
+        let (raw_lane_weights_path, raw_lane_weights) = p
+            .get_opt_key("laneWeights")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "lane_weights", Value::Array(vec![])));
+
+        let lane_weights_parser = ValueParser::new(raw_lane_weights_path, &raw_lane_weights);
+        let lane_weights = lane_weights_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|lane| {
+                    let origin = lane.chain(&mut err).get_key("origin").parse_u32().end();
+                    let destination = lane
+                        .chain(&mut err)
+                        .get_key("destination")
+                        .parse_u32()
+                        .end();
+                    let weight = lane.chain(&mut err).get_key("weight").parse_u32().end();
+                    Some(((origin?, destination?), weight?.max(1)))
+                })
+                .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
+
+        let lane_weights = fair_scheduling.then_some(LaneWeights {
+            default_weight: default_lane_weight,
+            weights: lane_weights,
+        });
+
+        let hook_metadata =
+            parse_app_confs(&p, "hookMetadata", &mut err, |app, matching_list, err| {
+                let msg_value = app
+                    .chain(err)
+                    .get_opt_key("msgValue")
+                    .parse_u256()
+                    .unwrap_or_default();
+                let gas_limit = app.chain(err).get_opt_key("gasLimit").parse_u256().end();
+                let refund_address = app
+                    .chain(err)
+                    .get_opt_key("refundAddress")
+                    .parse_address_hash()
+                    .end()
+                    .map(H160::from);
+                let custom = app
+                    .chain(err)
+                    .get_opt_key("custom")
+                    .parse_string()
+                    .end()
+                    .and_then(|custom| {
+                        hex::decode(custom.trim_start_matches("0x"))
+                            .context("Expected hex encoded custom hook metadata")
+                            .take_err(err, || &app.cwp + "custom")
+                    })
+                    .unwrap_or_default();
+                let deadline = app
+                    .chain(err)
+                    .get_opt_key("deadline")
+                    .parse_u64()
+                    .end()
+                    .map(Duration::from_secs);
+
+                Some(HookMetadataConf {
+                    matching_list: matching_list?,
+                    msg_value,
+                    gas_limit,
+                    refund_address,
+                    custom,
+                    deadline,
+                })
+            });
+
+        let message_expiry =
+            parse_app_confs(&p, "messageExpiry", &mut err, |app, matching_list, err| {
+                let max_age = app
+                    .chain(err)
+                    .get_key("maxAge")
+                    .parse_u64()
+                    .end()
+                    .map(Duration::from_secs);
+
+                Some(MessageExpiryConf {
+                    matching_list: matching_list?,
+                    max_age: max_age?,
+                })
+            });
+
+        let mut delivery_deadlines = parse_app_confs(
+            &p,
+            "deliveryDeadlines",
+            &mut err,
+            |app, matching_list, err| {
+                let deadline = app
+                    .chain(err)
+                    .get_key("deadline")
+                    .parse_u64()
+                    .end()
+                    .map(Duration::from_secs);
+                let max_fee_bump_percent = app
+                    .chain(err)
+                    .get_opt_key("maxFeeBumpPercent")
+                    .parse_u64()
+                    .unwrap_or(DEFAULT_MAX_DEADLINE_FEE_BUMP_PERCENT);
+
+                Some(DeliveryDeadlineConf {
+                    matching_list: matching_list?,
+                    deadline: deadline?,
+                    max_fee_bump_percent,
+                })
+            },
+        );
+        // Deadlines given with the hook metadata of an app apply after the
+        // explicit ones
+        delivery_deadlines.extend(hook_metadata.iter().filter_map(|app| {
+            Some(DeliveryDeadlineConf {
+                matching_list: app.matching_list.clone(),
+                deadline: app.deadline?,
+                max_fee_bump_percent: DEFAULT_MAX_DEADLINE_FEE_BUMP_PERCENT,
+            })
+        }));
+
+        let gas_overheads =
+            parse_app_confs(&p, "gasOverheads", &mut err, |app, matching_list, err| {
+                let overhead = app.chain(err).get_key("overhead").parse_u256().end();
+
+                Some(GasOverheadConf {
+                    matching_list: matching_list?,
+                    overhead: overhead?,
+                })
+            });
+
+        let fixed_gas_limits =
+            parse_app_confs(&p, "fixedGasLimits", &mut err, |app, matching_list, err| {
+                let gas_limit = app.chain(err).get_key("gasLimit").parse_u256().end();
+
+                Some(FixedGasLimitConf {
+                    matching_list: matching_list?,
+                    gas_limit: gas_limit?,
+                })
+            });
+
+        let (raw_confirmation_depths_path, raw_confirmation_depths) = p
+            .get_opt_key("confirmationDepths")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "confirmation_depths", Value::Array(vec![])));
+
+        let confirmation_depths_parser =
+            ValueParser::new(raw_confirmation_depths_path, &raw_confirmation_depths);
+        let confirmation_depths = confirmation_depths_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|app| {
+                    let matching_list = app
+                        .chain(&mut err)
+                        .get_opt_key("matchingList")
+                        .and_then(parse_matching_list)
+                        .unwrap_or_default();
+                    let min_transfer_amount = app
+                        .chain(&mut err)
+                        .get_opt_key("minTransferAmount")
+                        .parse_u256()
+                        .end();
+                    let depth = app
+                        .chain(&mut err)
+                        .get_key("confirmations")
+                        .and_then(|confirmations| match confirmations.val.as_str() {
+                            Some("finalized") => Ok(ConfirmationDepth::Finalized),
+                            _ => confirmations.parse_u32().map(ConfirmationDepth::Blocks),
+                        })
+                        .end();
+
+                    Some(ConfirmationDepthConf {
+                        matching_list,
+                        min_transfer_amount,
+                        depth: depth?,
+                    })
+                })
+                .collect_vec()
+            })
+            .unwrap_or_default();
+
+        let (raw_custom_isms_path, raw_custom_isms) = p
+            .get_opt_key("customIsms")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "custom_isms", Value::Array(vec![])));
+
+        let custom_isms_parser = ValueParser::new(raw_custom_isms_path, &raw_custom_isms);
+        let custom_isms = custom_isms_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|ism| parse_custom_ism(ism).take_config_err(&mut err))
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
+        // Messages are delivered to every relayed chain and fees are claimed
+        // from the paymasters in `igpClaims`, both of which need a signer
+        let signing_chains: HashSet<&HyperlaneDomain> = relay_chains
+            .iter()
+            .chain(igp_claims.iter().map(|claim| &claim.domain))
+            .collect();
+        for domain in signing_chains {
+            if let Some(chain) = base.chains.get(domain.name()) {
+                if chain.signer.is_none() {
+                    err.push(
+                        cwp + "chains" + domain.name() + "signer",
+                        eyre!("Relaying to a chain or claiming its gas payments requires a signer; set one for the chain or a `defaultSigner`"),
+                    );
+                }
+            }
+        }
+        let enforces_gas_payments = gas_payment_enforcement
+            .iter()
+            .any(|conf| !matches!(conf.policy, GasPaymentEnforcementPolicy::None));
+        if enforces_gas_payments {
+            for domain in &relay_chains {
+                if let Some(chain) = base.chains.get(domain.name()) {
+                    if chain.addresses.interchain_gas_paymaster == H256::zero() {
+                        err.push(
+                            cwp + "chains" + domain.name() + "interchain_gas_paymaster",
+                            eyre!("Enforcing gas payments requires the interchain gas paymaster of every relayed chain"),
+                        );
+                    }
+                }
+            }
+        }
+
         err.into_result(RelayerSettings {
             base,
             db,
@@ -286,10 +996,176 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers,
             metric_app_contexts,
+            lane_weights,
+            hook_metadata,
+            igp_claims,
+            igp_claim_interval,
+            message_expiry,
+            delivery_deadlines,
+            gas_overheads,
+            fixed_gas_limits,
+            confirmation_depths,
+            shutdown_timeout,
+            tenants,
+            missed_delivery_feed,
+            slo_delivery_target,
+            slo_window,
+            custom_isms,
         })
     }
 }
 
+fn parse_custom_ism(p: ValueParser) -> ConfigResult<CustomIsmConf> {
+    let mut err = ConfigParsingError::default();
+
+    let module_type = p
+        .chain(&mut err)
+        .get_opt_key("moduleType")
+        .parse_u32()
+        .end()
+        .and_then(|module_type| {
+            u8::try_from(module_type)
+                .context("Expected a module type of at most 255")
+                .take_err(&mut err, || &p.cwp + "module_type")
+        });
+    let isms = p
+        .chain(&mut err)
+        .get_opt_key("isms")
+        .into_array_iter()
+        .map(|itr| {
+            itr.filter_map(|ism| ism.chain(&mut err).parse_address_hash().end())
+                .collect_vec()
+        })
+        .unwrap_or_default();
+    if module_type.is_none() && isms.is_empty() {
+        err.push(
+            p.cwp.clone(),
+            eyre!("Expected a `moduleType` or `isms` to match ISMs with"),
+        );
+    }
+
+    let strategy = p
+        .chain(&mut err)
+        .get_key("strategy")
+        .and_then(parse_custom_ism_strategy)
+        .end();
+
+    cfg_unwrap_all!(&p.cwp, err: [strategy]);
+    err.into_result(CustomIsmConf {
+        module_type,
+        isms,
+        strategy,
+    })
+}
+
+fn parse_custom_ism_strategy(p: ValueParser) -> ConfigResult<CustomIsmStrategy> {
+    let mut err = ConfigParsingError::default();
+
+    let strategy_type = p
+        .chain(&mut err)
+        .get_key("type")
+        .parse_string()
+        .end()
+        .unwrap_or_default();
+
+    let strategy = match strategy_type {
+        "null" => Some(CustomIsmStrategy::Null),
+        "http" => p
+            .chain(&mut err)
+            .get_key("url")
+            .parse_string()
+            .end()
+            .map(|url| CustomIsmStrategy::Http {
+                url: url.to_owned(),
+            }),
+        "messageBody" => {
+            let offset = p
+                .chain(&mut err)
+                .get_opt_key("offset")
+                .parse_u64()
+                .unwrap_or_default();
+            let length = p.chain(&mut err).get_opt_key("length").parse_u64().end();
+            Some(CustomIsmStrategy::MessageBody {
+                offset: offset as usize,
+                length: length.map(|length| length as usize),
+            })
+        }
+        "" => None,
+        _ => {
+            err.push(
+                &p.cwp + "type",
+                eyre!("Unknown custom ISM strategy `{strategy_type}`"),
+            );
+            None
+        }
+    };
+
+    cfg_unwrap_all!(&p.cwp, err: [strategy]);
+    err.into_result(strategy)
+}
+
+/// Parse the `gasPaymentEnforcement` policies of the relayer or of a tenant
+fn parse_gas_payment_enforcement(
+    p: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Vec<GasPaymentEnforcementConf> {
+    let (raw_gas_payment_enforcement_path, raw_gas_payment_enforcement) = p
+        .get_opt_key("gasPaymentEnforcement")
+        .take_config_err_flat(err)
+        .and_then(parse_json_array)
+        .unwrap_or_else(|| (&p.cwp + "gas_payment_enforcement", Value::Array(vec![])));
+
+    let gas_payment_enforcement_parser = ValueParser::new(
+        raw_gas_payment_enforcement_path,
+        &raw_gas_payment_enforcement,
+    );
+    gas_payment_enforcement_parser.into_array_iter().map(|itr| {
+        itr.filter_map(|policy| {
+            let policy_type = policy.chain(err).get_opt_key("type").parse_string().end();
+            let minimum_is_defined = matches!(policy.get_opt_key("minimum"), Ok(Some(_)));
+
+            let matching_list = policy.chain(err).get_opt_key("matchingList").and_then(parse_matching_list).unwrap_or_default();
+
+            let parse_minimum = |p| GasPaymentEnforcementPolicy::Minimum { payment: p };
+            match policy_type {
+                Some("minimum") => policy.chain(err).get_opt_key("payment").parse_u256().end().map(parse_minimum),
+                None if minimum_is_defined => policy.chain(err).get_opt_key("payment").parse_u256().end().map(parse_minimum),
+                Some("none") | None => Some(GasPaymentEnforcementPolicy::None),
+                Some("onChainFeeQuoting") => {
+                    let gas_fraction = policy.chain(err)
+                        .get_opt_key("gasFraction")
+                        .parse_string()
+                        .map(|v| v.replace(' ', ""))
+                        .unwrap_or_else(|| "1/2".to_owned());
+                    let (numerator, denominator) = gas_fraction
+                        .split_once('/')
+                        .ok_or_else(|| eyre!("Invalid `gas_fraction` for OnChainFeeQuoting gas payment enforcement policy; expected `numerator / denominator`"))
+                        .take_err(err, || &policy.cwp + "gas_fraction")
+                        .unwrap_or(("1", "1"));
+
+                    Some(GasPaymentEnforcementPolicy::OnChainFeeQuoting {
+                        gas_fraction_numerator: numerator
+                            .parse()
+                            .context("Error parsing gas fraction numerator")
+                            .take_err(err, || &policy.cwp + "gas_fraction")
+                            .unwrap_or(1),
+                        gas_fraction_denominator: denominator
+                            .parse()
+                            .context("Error parsing gas fraction denominator")
+                            .take_err(err, || &policy.cwp + "gas_fraction")
+                            .unwrap_or(1),
+                    })
+                }
+                Some(pt) => Err(eyre!("Unknown gas payment enforcement policy type `{pt}`"))
+                    .take_err(err, || &p.cwp + "type"),
+            }.map(|policy| GasPaymentEnforcementConf {
+                policy,
+                matching_list,
+            })
+        }).collect_vec()
+    }).unwrap_or_default()
+}
+
 fn parse_json_array(p: ValueParser) -> Option<(ConfigPath, Value)> {
     let mut err = ConfigParsingError::default();
 
@@ -310,6 +1186,37 @@ fn parse_json_array(p: ValueParser) -> Option<(ConfigPath, Value)> {
     }
 }
 
+/// Parse a JSON array setting of per-app confs. Each app is identified by its
+/// `matchingList`, which is parsed and passed to `parse_app` along with the
+/// app's value.
+fn parse_app_confs<T>(
+    p: &ValueParser,
+    key: &str,
+    err: &mut ConfigParsingError,
+    mut parse_app: impl FnMut(&ValueParser, Option<MatchingList>, &mut ConfigParsingError) -> Option<T>,
+) -> Vec<T> {
+    let (raw_confs_path, raw_confs) = p
+        .get_opt_key(key)
+        .take_config_err_flat(err)
+        .and_then(parse_json_array)
+        .unwrap_or_else(|| (&p.cwp + key.to_case(Case::Snake), Value::Array(vec![])));
+
+    ValueParser::new(raw_confs_path, &raw_confs)
+        .into_array_iter()
+        .map(|itr| {
+            itr.filter_map(|app| {
+                let matching_list = app
+                    .chain(err)
+                    .get_key("matchingList")
+                    .and_then(parse_matching_list)
+                    .end();
+                parse_app(&app, matching_list, err)
+            })
+            .collect_vec()
+        })
+        .unwrap_or_default()
+}
+
 fn parse_matching_list(p: ValueParser) -> ConfigResult<MatchingList> {
     let mut err = ConfigParsingError::default();
 
@@ -345,7 +1252,6 @@ fn parse_address_list(
 #[cfg(test)]
 mod test {
     use super::*;
-    use hyperlane_core::H160;
 
     #[test]
     fn test_parse_address_blacklist() {
@@ -374,4 +1280,60 @@ mod test {
         assert_eq!(res, vec![valid_address1, valid_address2]);
         assert!(!err.is_ok());
     }
+
+    #[test]
+    fn test_first_matching_app() {
+        let confs = vec![
+            GasOverheadConf {
+                matching_list: serde_json::from_str(r#"[{"destinationdomain": 2}]"#).unwrap(),
+                overhead: U256::from(1),
+            },
+            GasOverheadConf {
+                matching_list: serde_json::from_str(r#"[{"destinationdomain": [2, 3]}]"#).unwrap(),
+                overhead: U256::from(2),
+            },
+        ];
+        let message = |destination| HyperlaneMessage {
+            destination,
+            ..Default::default()
+        };
+
+        let overhead = |destination| {
+            first_matching_app(&confs, &message(destination)).map(|conf| conf.overhead)
+        };
+        assert_eq!(overhead(2), Some(U256::from(1)));
+        assert_eq!(overhead(3), Some(U256::from(2)));
+        assert_eq!(overhead(4), None);
+    }
+
+    #[test]
+    fn test_parse_app_confs() {
+        let raw = serde_json::json!({
+            "gasoverheads": r#"[
+                {"matchingList": [{"destinationDomain": 2}], "overhead": 50000},
+                {"overhead": 1},
+                {"matchingList": [{"destinationDomain": 3}]}
+            ]"#
+        });
+        let p = ValueParser::new(ConfigPath::default(), &raw);
+        let mut err = ConfigParsingError::default();
+        let confs = parse_app_confs(&p, "gasOverheads", &mut err, |app, matching_list, err| {
+            let overhead = app.chain(err).get_key("overhead").parse_u256().end();
+            Some(GasOverheadConf {
+                matching_list: matching_list?,
+                overhead: overhead?,
+            })
+        });
+
+        assert_eq!(confs.len(), 1);
+        assert_eq!(confs[0].overhead, U256::from(50_000));
+        assert!(!err.is_ok());
+
+        let mut err = ConfigParsingError::default();
+        let confs = parse_app_confs(&p, "fixedGasLimits", &mut err, |_, matching_list, _| {
+            matching_list
+        });
+        assert!(confs.is_empty());
+        assert!(err.is_ok());
+    }
 }
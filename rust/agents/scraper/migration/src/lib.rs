@@ -13,6 +13,17 @@ mod m20230309_000003_create_table_transaction;
 mod m20230309_000004_create_table_delivered_message;
 mod m20230309_000004_create_table_gas_payment;
 mod m20230309_000005_create_table_message;
+mod m20261015_000001_add_message_version;
+mod m20261015_000002_add_cursor_stream;
+mod m20261015_000003_create_table_message_stats_daily;
+mod m20261015_000004_create_table_indexed_range;
+mod m20261015_000005_create_table_checkpoint;
+mod m20261015_000006_create_table_destination_gas_update;
+mod m20261015_000007_add_message_decoded_body;
+mod m20261015_000008_add_message_gas_prices;
+mod m20261015_000009_add_environment;
+mod m20261015_000010_add_message_stats_counted;
+mod m20261015_000011_pad_non_evm_addresses;
 
 pub struct Migrator;
 
@@ -30,6 +41,17 @@ impl MigratorTrait for Migrator {
             Box::new(m20230309_000004_create_table_gas_payment::Migration),
             Box::new(m20230309_000004_create_table_delivered_message::Migration),
             Box::new(m20230309_000005_create_table_message::Migration),
+            Box::new(m20261015_000001_add_message_version::Migration),
+            Box::new(m20261015_000002_add_cursor_stream::Migration),
+            Box::new(m20261015_000003_create_table_message_stats_daily::Migration),
+            Box::new(m20261015_000004_create_table_indexed_range::Migration),
+            Box::new(m20261015_000005_create_table_checkpoint::Migration),
+            Box::new(m20261015_000006_create_table_destination_gas_update::Migration),
+            Box::new(m20261015_000007_add_message_decoded_body::Migration),
+            Box::new(m20261015_000008_add_message_gas_prices::Migration),
+            Box::new(m20261015_000009_add_environment::Migration),
+            Box::new(m20261015_000010_add_message_stats_counted::Migration),
+            Box::new(m20261015_000011_pad_non_evm_addresses::Migration),
         ]
     }
 }
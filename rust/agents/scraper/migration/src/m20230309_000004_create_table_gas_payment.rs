@@ -4,6 +4,7 @@ use sea_orm_migration::prelude::*;
 use crate::l20230309_types::*;
 use crate::m20230309_000001_create_table_domain::Domain;
 use crate::m20230309_000003_create_table_transaction::Transaction;
+use crate::m20261015_000009_add_environment::Environment;
 
 #[derive(DeriveMigrationName)]
 pub struct Migration;
@@ -73,27 +74,7 @@ impl MigrationTrait for Migration {
 
         manager
             .get_connection()
-            .execute_unprepared(&format!(
-                r#"
-            CREATE VIEW "{tgp_table}" AS
-            SELECT
-                "gp"."{gp_mid}" AS "{tgp_mid}",
-                COUNT("gp"."{gp_mid}") AS "{tgp_num_payments}",
-                SUM("gp"."{gp_payment}") AS "{tgp_payment}",
-                SUM("gp"."{gp_gas_amount}") AS "{tgp_gas_amount}"
-            FROM "{gp_table}" AS "gp"
-            GROUP BY "gp"."{gp_mid}"
-            "#,
-                gp_table = GasPayment::Table.to_string(),
-                gp_mid = GasPayment::MsgId.to_string(),
-                gp_payment = GasPayment::Payment.to_string(),
-                gp_gas_amount = GasPayment::GasAmount.to_string(),
-                tgp_table = TotalGasPayment::Table.to_string(),
-                tgp_mid = TotalGasPayment::MsgId.to_string(),
-                tgp_num_payments = TotalGasPayment::NumPayments.to_string(),
-                tgp_payment = TotalGasPayment::TotalPayment.to_string(),
-                tgp_gas_amount = TotalGasPayment::TotalGasAmount.to_string(),
-            ))
+            .execute_unprepared(&total_gas_payment_view(false))
             .await?;
 
         Ok(())
@@ -114,6 +95,37 @@ impl MigrationTrait for Migration {
     }
 }
 
+/// The `total_gas_payment` view, with the payments of each environment
+/// totalled separately if `by_environment`
+pub(crate) fn total_gas_payment_view(by_environment: bool) -> String {
+    let environment = if by_environment {
+        format!(r#""gp"."{}", "#, Environment::Environment.to_string())
+    } else {
+        String::new()
+    };
+    format!(
+        r#"
+        CREATE VIEW "{tgp_table}" AS
+        SELECT
+            {environment}"gp"."{gp_mid}" AS "{tgp_mid}",
+            COUNT("gp"."{gp_mid}") AS "{tgp_num_payments}",
+            SUM("gp"."{gp_payment}") AS "{tgp_payment}",
+            SUM("gp"."{gp_gas_amount}") AS "{tgp_gas_amount}"
+        FROM "{gp_table}" AS "gp"
+        GROUP BY {environment}"gp"."{gp_mid}"
+        "#,
+        gp_table = GasPayment::Table.to_string(),
+        gp_mid = GasPayment::MsgId.to_string(),
+        gp_payment = GasPayment::Payment.to_string(),
+        gp_gas_amount = GasPayment::GasAmount.to_string(),
+        tgp_table = TotalGasPayment::Table.to_string(),
+        tgp_mid = TotalGasPayment::MsgId.to_string(),
+        tgp_num_payments = TotalGasPayment::NumPayments.to_string(),
+        tgp_payment = TotalGasPayment::TotalPayment.to_string(),
+        tgp_gas_amount = TotalGasPayment::TotalGasAmount.to_string(),
+    )
+}
+
 /// Learn more at https://docs.rs/sea-query#iden
 #[derive(Iden)]
 pub enum GasPayment {
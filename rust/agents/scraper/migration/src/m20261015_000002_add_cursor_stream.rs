@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000003_create_table_cursor::Cursor;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Cursors stored before the streams of a domain were tracked
+        // separately are shared by all of them, and keep a null stream
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Cursor::Table)
+                    .add_column(ColumnDef::new(CursorStream::Stream).text())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Cursor::Table)
+                    .drop_column(CursorStream::Stream)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum CursorStream {
+    /// Event stream the cursor is for, e.g. `gas_payment`
+    Stream,
+}
@@ -0,0 +1,123 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000001_create_table_domain::Domain;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MessageStatsDaily::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(MessageStatsDaily::Day).date().not_null())
+                    .col(
+                        ColumnDef::new(MessageStatsDaily::Origin)
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageStatsDaily::Destination)
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageStatsDaily::Dispatched)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(MessageStatsDaily::Delivered)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(MessageStatsDaily::TotalLatencySecs)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(MessageStatsDaily::TimeUpdated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(MessageStatsDaily::Day)
+                            .col(MessageStatsDaily::Origin)
+                            .col(MessageStatsDaily::Destination),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(MessageStatsDaily::Origin)
+                            .to(Domain::Table, Domain::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Roll up the messages scraped before the table existed, the scraper
+        // keeps it up to date from then on
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"
+                INSERT INTO "{table}" ("{day}", "{origin}", "{destination}", "{dispatched}", "{delivered}", "{total_latency}")
+                SELECT
+                    DATE_TRUNC('day', "send_occurred_at")::date,
+                    "origin_domain_id",
+                    "destination_domain_id",
+                    COUNT(*),
+                    COUNT("delivery_occurred_at"),
+                    COALESCE(SUM(EXTRACT(EPOCH FROM "delivery_latency")), 0)::bigint
+                FROM "message_view"
+                WHERE "send_occurred_at" IS NOT NULL
+                GROUP BY 1, 2, 3
+                "#,
+                table = MessageStatsDaily::Table.to_string(),
+                day = MessageStatsDaily::Day.to_string(),
+                origin = MessageStatsDaily::Origin.to_string(),
+                destination = MessageStatsDaily::Destination.to_string(),
+                dispatched = MessageStatsDaily::Dispatched.to_string(),
+                delivered = MessageStatsDaily::Delivered.to_string(),
+                total_latency = MessageStatsDaily::TotalLatencySecs.to_string(),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MessageStatsDaily::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum MessageStatsDaily {
+    Table,
+    /// Day the messages were dispatched on, in UTC
+    Day,
+    /// Domain the messages were dispatched from
+    Origin,
+    /// Domain the messages were sent to
+    Destination,
+    /// Number of messages dispatched
+    Dispatched,
+    /// Number of the dispatched messages that were delivered
+    Delivered,
+    /// Sum of the seconds between the dispatch and the delivery of the
+    /// delivered messages
+    TotalLatencySecs,
+    /// Time the row was last recomputed
+    TimeUpdated,
+}
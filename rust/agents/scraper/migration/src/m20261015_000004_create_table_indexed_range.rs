@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000001_create_table_domain::Domain;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IndexedRange::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IndexedRange::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IndexedRange::Domain).unsigned().not_null())
+                    .col(ColumnDef::new(IndexedRange::Stream).text().not_null())
+                    .col(
+                        ColumnDef::new(IndexedRange::StartBlock)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IndexedRange::EndBlock)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IndexedRange::TimeUpdated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(IndexedRange::Domain)
+                            .to(Domain::Table, Domain::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .table(IndexedRange::Table)
+                    .name("indexed_range_stream_idx")
+                    .col(IndexedRange::Domain)
+                    .col(IndexedRange::Stream)
+                    .col(IndexedRange::StartBlock)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IndexedRange::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum IndexedRange {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Hyperlane domain ID the blocks are from
+    Domain,
+    /// Event stream the blocks were indexed for, e.g. `gas_payment`
+    Stream,
+    /// First block of the range
+    StartBlock,
+    /// Last block of the range, inclusive
+    EndBlock,
+    /// Time the range was last extended
+    TimeUpdated,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+use crate::l20230309_types::*;
+use crate::m20230309_000001_create_table_domain::Domain;
+use crate::m20230309_000003_create_table_transaction::Transaction;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Checkpoint::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Checkpoint::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Checkpoint::TimeCreated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .col(ColumnDef::new(Checkpoint::Domain).unsigned().not_null())
+                    .col(ColumnDef::new_with_type(Checkpoint::MerkleTreeHook, Address).not_null())
+                    .col(ColumnDef::new(Checkpoint::LeafIndex).unsigned().not_null())
+                    .col(ColumnDef::new_with_type(Checkpoint::MsgId, Hash).not_null())
+                    .col(ColumnDef::new_with_type(Checkpoint::Root, Hash).null())
+                    .col(ColumnDef::new(Checkpoint::TxId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(Checkpoint::Domain)
+                            .to(Domain::Table, Domain::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(Checkpoint::TxId)
+                            .to(Transaction::Table, Transaction::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .unique()
+                            .col(Checkpoint::Domain)
+                            .col(Checkpoint::MerkleTreeHook)
+                            .col(Checkpoint::LeafIndex),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Checkpoint::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum Checkpoint {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Time of record creation
+    TimeCreated,
+    /// Hyperlane domain ID of the origin chain
+    Domain,
+    /// Address of the merkle tree hook the message was inserted into
+    MerkleTreeHook,
+    /// Index of the message's leaf in the tree
+    LeafIndex,
+    /// Unique id of the message inserted
+    MsgId,
+    /// Root of the tree once the leaf was inserted, which validators sign
+    /// checkpoints of. Null until every preceding leaf has been scraped.
+    Root,
+    /// Transaction the leaf was inserted in
+    TxId,
+}
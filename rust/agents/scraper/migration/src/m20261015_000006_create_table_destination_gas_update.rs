@@ -0,0 +1,106 @@
+use sea_orm_migration::prelude::*;
+
+use crate::l20230309_types::*;
+use crate::m20230309_000001_create_table_domain::Domain;
+use crate::m20230309_000003_create_table_transaction::Transaction;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DestinationGasUpdate::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DestinationGasUpdate::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DestinationGasUpdate::TimeCreated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .col(
+                        ColumnDef::new(DestinationGasUpdate::Domain)
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DestinationGasUpdate::Destination)
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(DestinationGasUpdate::TokenExchangeRate, Wei)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new_with_type(DestinationGasUpdate::GasPrice, Wei).not_null())
+                    .col(
+                        ColumnDef::new(DestinationGasUpdate::TxId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DestinationGasUpdate::LogIndex)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(DestinationGasUpdate::Domain)
+                            .to(Domain::Table, Domain::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(DestinationGasUpdate::TxId)
+                            .to(Transaction::Table, Transaction::Id),
+                    )
+                    .index(
+                        Index::create()
+                            // don't need domain because TxId includes it
+                            .col(DestinationGasUpdate::TxId)
+                            .col(DestinationGasUpdate::LogIndex)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DestinationGasUpdate::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum DestinationGasUpdate {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Time of record creation
+    TimeCreated,
+    /// Hyperlane domain ID of the chain the gas oracle is on
+    Domain,
+    /// Hyperlane domain ID of the chain the gas data is for
+    Destination,
+    /// Value of the destination's native token in the origin's, scaled by
+    /// 1e10
+    TokenExchangeRate,
+    /// Gas price on the destination, in its native token
+    GasPrice,
+    /// Transaction the gas data was set in
+    TxId,
+    /// Index of the log in the transaction's block
+    LogIndex,
+}
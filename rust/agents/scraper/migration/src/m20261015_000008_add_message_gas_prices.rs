@@ -0,0 +1,78 @@
+use std::borrow::BorrowMut as _;
+
+use sea_orm_migration::prelude::*;
+
+use crate::l20230309_types::*;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000005_create_table_message::Message;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column(
+                        ColumnDef::new_with_type(MessageGasPrices::OriginGasPrice, Wei)
+                            .borrow_mut(),
+                    )
+                    .add_column(ColumnDef::new(MessageGasPrices::OriginTokenUsd).decimal())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DeliveredMessage::Table)
+                    .add_column(
+                        ColumnDef::new_with_type(MessageGasPrices::DestinationGasPrice, Wei)
+                            .borrow_mut(),
+                    )
+                    .add_column(ColumnDef::new(MessageGasPrices::DestinationTokenUsd).decimal())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DeliveredMessage::Table)
+                    .drop_column(MessageGasPrices::DestinationGasPrice)
+                    .drop_column(MessageGasPrices::DestinationTokenUsd)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .drop_column(MessageGasPrices::OriginGasPrice)
+                    .drop_column(MessageGasPrices::OriginTokenUsd)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum MessageGasPrices {
+    /// Gas price the message was dispatched with, in the smallest unit of
+    /// the origin's native token
+    OriginGasPrice,
+    /// USD price of one native token of the origin when the dispatch was
+    /// scraped, if the scraper has a price feed
+    OriginTokenUsd,
+    /// Gas price the message was delivered with, in the smallest unit of the
+    /// destination's native token
+    DestinationGasPrice,
+    /// USD price of one native token of the destination when the delivery
+    /// was scraped, if the scraper has a price feed
+    DestinationTokenUsd,
+}
@@ -0,0 +1,290 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000003_create_table_cursor::Cursor;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000004_create_table_gas_payment::{
+    total_gas_payment_view, GasPayment, TotalGasPayment,
+};
+use crate::m20230309_000005_create_table_message::{message_view, Message};
+use crate::m20261015_000003_create_table_message_stats_daily::MessageStatsDaily;
+use crate::m20261015_000004_create_table_indexed_range::IndexedRange;
+use crate::m20261015_000005_create_table_checkpoint::Checkpoint;
+use crate::m20261015_000006_create_table_destination_gas_update::DestinationGasUpdate;
+
+/// Environment of the rows stored before deployments could share a database
+const DEFAULT_ENVIRONMENT: &str = "default";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// The tables whose rows belong to a deployment. Domains, blocks and
+/// transactions are chain data every deployment shares.
+fn deployment_tables() -> Vec<DynIden> {
+    vec![
+        Message::Table.into_iden(),
+        DeliveredMessage::Table.into_iden(),
+        GasPayment::Table.into_iden(),
+        Cursor::Table.into_iden(),
+        IndexedRange::Table.into_iden(),
+        MessageStatsDaily::Table.into_iden(),
+        Checkpoint::Table.into_iden(),
+        DestinationGasUpdate::Table.into_iden(),
+    ]
+}
+
+/// The unique keys of the deployment tables, by table. Each is made unique
+/// per environment.
+fn unique_keys() -> Vec<(String, Vec<String>)> {
+    vec![
+        (
+            Message::Table.to_string(),
+            vec![
+                Message::OriginMailbox.to_string(),
+                Message::Origin.to_string(),
+                Message::Nonce.to_string(),
+            ],
+        ),
+        (
+            DeliveredMessage::Table.to_string(),
+            vec![DeliveredMessage::MsgId.to_string()],
+        ),
+        (
+            GasPayment::Table.to_string(),
+            vec![
+                GasPayment::MsgId.to_string(),
+                GasPayment::TxId.to_string(),
+                GasPayment::LogIndex.to_string(),
+            ],
+        ),
+        (
+            Checkpoint::Table.to_string(),
+            vec![
+                Checkpoint::Domain.to_string(),
+                Checkpoint::MerkleTreeHook.to_string(),
+                Checkpoint::LeafIndex.to_string(),
+            ],
+        ),
+        (
+            DestinationGasUpdate::Table.to_string(),
+            vec![
+                DestinationGasUpdate::TxId.to_string(),
+                DestinationGasUpdate::LogIndex.to_string(),
+            ],
+        ),
+    ]
+}
+
+/// The columns of a unique key, made unique per environment
+fn environment_key(columns: &[String]) -> Vec<String> {
+    std::iter::once(Environment::Environment.to_string())
+        .chain(columns.iter().cloned())
+        .collect()
+}
+
+/// Replace the unique key of `table` on the `from` columns with one on the
+/// `to` columns. Keys are named the way Postgres names unnamed keys.
+fn replace_unique_key(table: &str, from: &[String], to: &[String]) -> String {
+    let columns = to
+        .iter()
+        .map(|column| format!(r#""{column}""#))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"
+        ALTER TABLE "{table}" DROP CONSTRAINT "{table}_{from}_key";
+        ALTER TABLE "{table}" ADD CONSTRAINT "{table}_{to}_key" UNIQUE ({columns});
+        "#,
+        from = from.join("_"),
+        to = to.join("_"),
+    )
+}
+
+/// Drop the views and create them again, by environment or not
+fn recreate_views(by_environment: bool) -> String {
+    format!(
+        r#"
+        DROP VIEW "{message_view}";
+        DROP VIEW "{total_gas_payment}";
+        {create_total_gas_payment};
+        {create_message_view};
+        "#,
+        message_view = format!("{}_view", Message::Table.to_string()),
+        total_gas_payment = TotalGasPayment::Table.to_string(),
+        create_total_gas_payment = total_gas_payment_view(by_environment),
+        create_message_view = message_view(by_environment),
+    )
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in deployment_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(
+                            ColumnDef::new(Environment::Environment)
+                                .text()
+                                .not_null()
+                                .default(DEFAULT_ENVIRONMENT),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // Messages, deliveries and payments are only joined with those of
+        // their environment
+        manager
+            .get_connection()
+            .execute_unprepared(&recreate_views(true))
+            .await?;
+
+        for (table, columns) in unique_keys() {
+            manager
+                .get_connection()
+                .execute_unprepared(&replace_unique_key(
+                    &table,
+                    &columns,
+                    &environment_key(&columns),
+                ))
+                .await?;
+        }
+
+        // Deployments between the same domains have their own daily stats
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"
+                ALTER TABLE "{table}" DROP CONSTRAINT "{table}_pkey";
+                ALTER TABLE "{table}" ADD PRIMARY KEY ("{environment}", "{day}", "{origin}", "{destination}");
+                "#,
+                table = MessageStatsDaily::Table.to_string(),
+                environment = Environment::Environment.to_string(),
+                day = MessageStatsDaily::Day.to_string(),
+                origin = MessageStatsDaily::Origin.to_string(),
+                destination = MessageStatsDaily::Destination.to_string(),
+            ))
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Message::Table)
+                    .name("message_environment_idx")
+                    .col(Environment::Environment)
+                    .col(Message::Origin)
+                    .col(Message::Nonce)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Message::Table)
+                    .name("message_environment_idx")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"
+                ALTER TABLE "{table}" DROP CONSTRAINT "{table}_pkey";
+                ALTER TABLE "{table}" ADD PRIMARY KEY ("{day}", "{origin}", "{destination}");
+                "#,
+                table = MessageStatsDaily::Table.to_string(),
+                day = MessageStatsDaily::Day.to_string(),
+                origin = MessageStatsDaily::Origin.to_string(),
+                destination = MessageStatsDaily::Destination.to_string(),
+            ))
+            .await?;
+
+        for (table, columns) in unique_keys() {
+            manager
+                .get_connection()
+                .execute_unprepared(&replace_unique_key(
+                    &table,
+                    &environment_key(&columns),
+                    &columns,
+                ))
+                .await?;
+        }
+
+        manager
+            .get_connection()
+            .execute_unprepared(&recreate_views(false))
+            .await?;
+
+        for table in deployment_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Environment::Environment)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum Environment {
+    /// Environment of the deployment the row was scraped for, e.g. `mainnet3`
+    Environment,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unique_keys_are_replaced_by_environment() {
+        let keys = unique_keys()
+            .into_iter()
+            .map(|(table, columns)| {
+                replace_unique_key(&table, &columns, &environment_key(&columns))
+            })
+            .collect::<Vec<_>>();
+        assert!(keys[0].contains(r#"DROP CONSTRAINT "message_origin_mailbox_origin_nonce_key";"#));
+        assert!(keys[0].contains(
+            r#"ADD CONSTRAINT "message_environment_origin_mailbox_origin_nonce_key" UNIQUE ("environment", "origin_mailbox", "origin", "nonce");"#
+        ));
+        assert!(keys[1].contains(r#"DROP CONSTRAINT "delivered_message_msg_id_key";"#));
+        assert!(keys[1].contains(r#"UNIQUE ("environment", "msg_id");"#));
+        assert!(keys[2].contains(r#"DROP CONSTRAINT "gas_payment_msg_id_tx_id_log_index_key";"#));
+        assert!(keys[3]
+            .contains(r#"DROP CONSTRAINT "checkpoint_domain_merkle_tree_hook_leaf_index_key";"#));
+        assert!(
+            keys[4].contains(r#"DROP CONSTRAINT "destination_gas_update_tx_id_log_index_key";"#)
+        );
+    }
+
+    #[test]
+    fn test_unique_keys_are_restored() {
+        let columns = vec!["msg_id".to_owned()];
+        let sql = replace_unique_key("delivered_message", &environment_key(&columns), &columns);
+        assert!(sql.contains(r#"DROP CONSTRAINT "delivered_message_environment_msg_id_key";"#));
+        assert!(sql.contains(r#"ADD CONSTRAINT "delivered_message_msg_id_key" UNIQUE ("msg_id");"#));
+    }
+
+    #[test]
+    fn test_views_join_by_environment() {
+        let views = recreate_views(true);
+        assert!(views.contains(r#"GROUP BY "gp"."environment", "gp"."msg_id""#));
+        assert!(views.contains(r#"AND "tgp"."environment" = "msg"."environment""#));
+        assert!(views.contains(r#"AND "dmsg"."environment" = "msg"."environment""#));
+        assert!(views.contains(r#""msg"."environment" AS "environment""#));
+
+        assert!(!recreate_views(false).contains("environment"));
+    }
+}
@@ -0,0 +1,81 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000005_create_table_message::Message;
+use crate::m20261015_000009_add_environment::Environment;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column(
+                        ColumnDef::new(MessageStatsCounted::StatsDispatched)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column(
+                        ColumnDef::new(MessageStatsCounted::StatsDelivered)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The daily stats were recounted from every stored message and
+        // delivery so far, so those are all counted already
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"
+                UPDATE "{msg_table}" AS "msg" SET
+                    "{dispatched}" = TRUE,
+                    "{delivered}" = EXISTS (
+                        SELECT 1 FROM "{dmsg_table}" AS "dmsg"
+                        WHERE "dmsg"."{dmsg_mid}" = "msg"."{msg_mid}"
+                            AND "dmsg"."{env}" = "msg"."{env}"
+                    )
+                "#,
+                msg_table = Message::Table.to_string(),
+                msg_mid = Message::MsgId.to_string(),
+                dmsg_table = DeliveredMessage::Table.to_string(),
+                dmsg_mid = DeliveredMessage::MsgId.to_string(),
+                env = Environment::Environment.to_string(),
+                dispatched = MessageStatsCounted::StatsDispatched.to_string(),
+                delivered = MessageStatsCounted::StatsDelivered.to_string(),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .drop_column(MessageStatsCounted::StatsDispatched)
+                    .drop_column(MessageStatsCounted::StatsDelivered)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum MessageStatsCounted {
+    /// Whether the message was counted in the dispatched messages of its day
+    StatsDispatched,
+    /// Whether the delivery of the message was counted in the delivered
+    /// messages of its day
+    StatsDelivered,
+}
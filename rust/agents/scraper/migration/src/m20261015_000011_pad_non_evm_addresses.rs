@@ -0,0 +1,137 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000002_create_table_block::Block;
+use crate::m20230309_000003_create_table_transaction::Transaction;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000005_create_table_message::Message;
+
+/// The 12 zero bytes a 20 byte address is padded with to 32 bytes
+const PADDING: &str = r"'\x000000000000000000000000'::bytea";
+
+/// Addresses used to be stored as 20 bytes whenever they were zero padded,
+/// whatever their chain. Only EVM addresses are anymore, the addresses of
+/// other chains are always stored as 32 bytes, so this pads the ones stored
+/// as 20 bytes.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+impl Migration {
+    /// Pad or unpad the addresses of `column`, in the rows of `table`
+    /// matching `domain_condition`
+    async fn update_addresses(
+        manager: &SchemaManager<'_>,
+        pad: bool,
+        table: &str,
+        column: &str,
+        from: &str,
+        domain_condition: &str,
+    ) -> Result<(), DbErr> {
+        let (value, length) = if pad {
+            (format!(r#"{PADDING} || "{column}""#), 20)
+        } else {
+            (format!(r#"substring("{column}" from 13)"#), 32)
+        };
+        let unpadded = if pad {
+            String::new()
+        } else {
+            format!(r#" AND substring("{column}" for 12) = {PADDING}"#)
+        };
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"
+                UPDATE "{table}" SET "{column}" = {value}
+                {from}
+                WHERE length("{table}"."{column}") = {length}{unpadded} AND {domain_condition}
+                "#,
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// A query of the domains whose addresses aren't EVM addresses. They are
+    /// told apart by their mailboxes, which are never zero padded so were
+    /// always stored as 32 bytes, while EVM addresses are always 20 bytes.
+    fn non_evm_domains() -> String {
+        format!(
+            r#"SELECT "{}" FROM "{}" WHERE length("{}") = 32
+            UNION SELECT "{}" FROM "{}" WHERE length("{}") = 32"#,
+            Message::Origin.to_string(),
+            Message::Table.to_string(),
+            Message::OriginMailbox.to_string(),
+            DeliveredMessage::Domain.to_string(),
+            DeliveredMessage::Table.to_string(),
+            DeliveredMessage::DestinationMailbox.to_string(),
+        )
+    }
+
+    async fn update_all_addresses(manager: &SchemaManager<'_>, pad: bool) -> Result<(), DbErr> {
+        let domains = Self::non_evm_domains();
+        let msg_table = Message::Table.to_string();
+        let message_origin = format!(
+            r#""{msg_table}"."{}" IN ({domains})"#,
+            Message::Origin.to_string()
+        );
+        let message_destination = format!(
+            r#""{msg_table}"."{}" IN ({domains})"#,
+            Message::Destination.to_string()
+        );
+        for (column, condition) in [
+            (Message::Sender, &message_origin),
+            (Message::OriginMailbox, &message_origin),
+            (Message::Recipient, &message_destination),
+        ] {
+            Self::update_addresses(manager, pad, &msg_table, &column.to_string(), "", condition)
+                .await?;
+        }
+
+        let dmsg_table = DeliveredMessage::Table.to_string();
+        Self::update_addresses(
+            manager,
+            pad,
+            &dmsg_table,
+            &DeliveredMessage::DestinationMailbox.to_string(),
+            "",
+            &format!(
+                r#""{dmsg_table}"."{}" IN ({domains})"#,
+                DeliveredMessage::Domain.to_string()
+            ),
+        )
+        .await?;
+
+        // Transactions have the domain of their block
+        let txn_table = Transaction::Table.to_string();
+        let block_table = Block::Table.to_string();
+        let from = format!(r#"FROM "{block_table}""#);
+        let condition = format!(
+            r#""{txn_table}"."{}" = "{block_table}"."{}" AND "{block_table}"."{}" IN ({domains})"#,
+            Transaction::BlockId.to_string(),
+            Block::Id.to_string(),
+            Block::Domain.to_string(),
+        );
+        for column in [Transaction::Sender, Transaction::Recipient] {
+            Self::update_addresses(
+                manager,
+                pad,
+                &txn_table,
+                &column.to_string(),
+                &from,
+                &condition,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        Self::update_all_addresses(manager, true).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        Self::update_all_addresses(manager, false).await
+    }
+}
@@ -4,17 +4,36 @@ use async_trait::async_trait;
 use derive_more::AsRef;
 use futures::future::try_join_all;
 use hyperlane_base::{
-    metrics::AgentMetrics, settings::IndexSettings, BaseAgent, ChainMetrics, ContractSyncMetrics,
-    ContractSyncer, CoreMetrics, HyperlaneAgentCore, MetricsUpdater, SyncOptions,
+    metrics::AgentMetrics,
+    settings::{IndexSettings, UnsupportedByProtocol},
+    BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics, DoctorReport,
+    HyperlaneAgentCore, MetricsUpdater, SyncOptions,
+};
+use hyperlane_core::{
+    Delivery, DestinationGasUpdate, HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage,
+    IndexMode, InterchainGasPayment, MerkleTreeInsertion, H512,
 };
-use hyperlane_core::{Delivery, HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, H512};
 use tokio::{
     sync::broadcast::{Receiver, Sender},
     task::JoinHandle,
 };
-use tracing::{info_span, instrument::Instrumented, trace, Instrument};
+use tracing::{info_span, instrument::Instrumented, trace, warn, Instrument};
 
-use crate::{chain_scraper::HyperlaneSqlDb, db::ScraperDb, settings::ScraperSettings};
+use crate::{
+    api_auth::ApiAuth,
+    body_decoder::BodyDecoders,
+    chain_scraper::{
+        HyperlaneSqlDb, DESTINATION_GAS, GAS_PAYMENT, MERKLE_TREE_INSERTION, MESSAGE_DELIVERY,
+        MESSAGE_DISPATCH,
+    },
+    checkpoint_roots::CheckpointRooter,
+    db::ScraperDb,
+    gap_rescraper::{GapRescraper, RescrapedChain},
+    price_feed::{build_price_feed, spawn_price_refresh, PriceFeed},
+    reconciler::{DeliveryReconciler, ReconciledOrigin},
+    server as scraper_server,
+    settings::ScraperSettings,
+};
 
 /// A message explorer scraper agent
 #[derive(Debug, AsRef)]
@@ -24,6 +43,11 @@ pub struct Scraper {
     core: HyperlaneAgentCore,
     contract_sync_metrics: Arc<ContractSyncMetrics>,
     scrapers: HashMap<u32, ChainScraper>,
+    reconciler: DeliveryReconciler,
+    gap_rescraper: GapRescraper,
+    checkpoint_rooter: CheckpointRooter,
+    price_feed: Option<Arc<dyn PriceFeed>>,
+    db: ScraperDb,
     settings: ScraperSettings,
     core_metrics: Arc<CoreMetrics>,
     agent_metrics: AgentMetrics,
@@ -52,25 +76,62 @@ impl BaseAgent for Scraper {
     where
         Self: Sized,
     {
-        let db = ScraperDb::connect(&settings.db).await?;
+        let db = ScraperDb::connect(&settings.db, &settings.environment).await?;
         let core = settings.build_hyperlane_core(metrics.clone());
 
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
         let mut scrapers: HashMap<u32, ChainScraper> = HashMap::new();
+        let mut reconciled_origins = Vec::with_capacity(settings.chains_to_scrape.len());
+        let mut rescraped_chains = Vec::with_capacity(settings.chains_to_scrape.len());
+        let address_formats = Arc::new(
+            settings
+                .chains
+                .values()
+                .map(|chain| (chain.domain.id(), chain.connection.address_format()))
+                .collect::<HashMap<_, _>>(),
+        );
+        let body_decoders = Arc::new(BodyDecoders::new(settings.body_decoders.clone()));
+        let price_feed = settings
+            .price_feed
+            .as_ref()
+            .map(build_price_feed)
+            .transpose()?;
 
         for domain in settings.chains_to_scrape.iter() {
             let chain_setup = settings.chain_setup(domain).expect("Missing chain config");
             let db = HyperlaneSqlDb::new(
                 db.clone(),
                 chain_setup.addresses.mailbox,
+                chain_setup.addresses.merkle_tree_hook,
                 domain.clone(),
                 settings
                     .build_provider(domain, &metrics.clone())
                     .await?
                     .into(),
                 &chain_setup.index.clone(),
+                address_formats.clone(),
+                body_decoders.clone(),
+                price_feed.clone(),
             )
             .await?;
+            reconciled_origins.push(ReconciledOrigin {
+                db: db.clone(),
+                indexer: chain_setup.build_message_indexer(&metrics).await?,
+                mode: chain_setup.index.mode,
+                chunk_size: chain_setup.index.chunk_size,
+            });
+            rescraped_chains.push(RescrapedChain {
+                db: db.clone(),
+                message_indexer: match chain_setup.index.mode {
+                    IndexMode::Block => Some(chain_setup.build_message_indexer(&metrics).await?),
+                    IndexMode::Sequence => None,
+                },
+                delivery_indexer: chain_setup.build_delivery_indexer(&metrics).await?,
+                payment_indexer: chain_setup
+                    .build_interchain_gas_payment_indexer(&metrics)
+                    .await?,
+                chunk_size: chain_setup.index.chunk_size,
+            });
             scrapers.insert(
                 domain.id(),
                 ChainScraper {
@@ -82,11 +143,30 @@ impl BaseAgent for Scraper {
         }
 
         trace!(domain_count = scrapers.len(), "Created scrapers");
+        let reconciler = DeliveryReconciler::new(
+            db.clone(),
+            settings.chains_to_scrape.clone(),
+            reconciled_origins,
+            &metrics,
+        )?;
+        let gap_rescraper = GapRescraper::new(rescraped_chains, &metrics)?;
+        let checkpoint_rooter = CheckpointRooter::new(
+            scrapers
+                .values()
+                .map(|scraper| scraper.db.clone())
+                .collect(),
+            &metrics,
+        )?;
 
         Ok(Self {
             core,
             contract_sync_metrics,
             scrapers,
+            reconciler,
+            gap_rescraper,
+            checkpoint_rooter,
+            price_feed,
+            db,
             settings,
             core_metrics: metrics,
             agent_metrics,
@@ -104,7 +184,12 @@ impl BaseAgent for Scraper {
             .settings
             .server(self.core_metrics.clone())
             .expect("Failed to create server");
-        let server_task = server.run().instrument(info_span!("Relayer server"));
+        let server_task = server
+            .run_with_custom_routes(scraper_server::routes(
+                self.db.clone(),
+                ApiAuth::new(&self.settings.api_keys),
+            ))
+            .instrument(info_span!("Scraper server"));
         tasks.push(server_task);
 
         for (domain, scraper) in self.scrapers.iter() {
@@ -122,10 +207,41 @@ impl BaseAgent for Scraper {
             .unwrap();
             tasks.push(metrics_updater.spawn());
         }
+        tasks.push(self.reconciler.spawn());
+        tasks.push(self.gap_rescraper.spawn());
+        tasks.push(self.checkpoint_rooter.spawn());
+        if let Some(price_feed) = self.price_feed {
+            tasks.push(spawn_price_refresh(price_feed));
+        }
         if let Err(err) = try_join_all(tasks).await {
             tracing::error!(error = ?err, "Scraper task panicked");
         }
     }
+
+    async fn doctor(
+        settings: &Self::Settings,
+        _metrics: Arc<CoreMetrics>,
+        report: &mut DoctorReport,
+    ) {
+        report.record("db", "postgres", check_db(settings).await);
+    }
+}
+
+/// Check that the database can be connected to and has every migration
+/// applied
+async fn check_db(settings: &ScraperSettings) -> eyre::Result<String> {
+    let db = ScraperDb::connect(&settings.db, &settings.environment).await?;
+    let pending = db.pending_migrations().await?;
+    if !pending.is_empty() {
+        eyre::bail!(
+            "Migrations {} are pending, apply them with `init-db`",
+            pending.join(", ")
+        );
+    }
+    Ok(format!(
+        "connected, every migration is applied, scraping into environment `{}`",
+        settings.environment
+    ))
 }
 
 impl Scraper {
@@ -137,7 +253,7 @@ impl Scraper {
         let index_settings = scraper.index_settings.clone();
         let domain = scraper.domain.clone();
 
-        let mut tasks = Vec::with_capacity(2);
+        let mut tasks = Vec::with_capacity(5);
         let (message_indexer, maybe_broadcaster) = self
             .build_message_indexer(
                 domain.clone(),
@@ -159,17 +275,45 @@ impl Scraper {
             )
             .await,
         );
-        tasks.push(
+        tasks.extend(
             self.build_interchain_gas_payment_indexer(
-                domain,
+                domain.clone(),
                 self.core_metrics.clone(),
                 self.contract_sync_metrics.clone(),
-                db,
+                db.clone(),
                 index_settings.clone(),
                 maybe_broadcaster.map(|b| b.subscribe()),
             )
             .await,
         );
+        // Only EVM gas oracles are indexed
+        let has_gas_oracle = self
+            .settings
+            .chain_setup(&domain)
+            .map(|setup| setup.addresses.storage_gas_oracle.is_some())
+            .unwrap_or_default();
+        if has_gas_oracle && domain.domain_protocol() == HyperlaneDomainProtocol::Ethereum {
+            tasks.push(
+                self.build_destination_gas_indexer(
+                    domain.clone(),
+                    self.core_metrics.clone(),
+                    self.contract_sync_metrics.clone(),
+                    db.clone(),
+                    index_settings.clone(),
+                )
+                .await,
+            );
+        }
+        tasks.extend(
+            self.build_merkle_tree_insertion_indexer(
+                domain,
+                self.core_metrics.clone(),
+                self.contract_sync_metrics.clone(),
+                db,
+                index_settings,
+            )
+            .await,
+        );
 
         tokio::spawn(async move {
             // If any of the tasks panic, we want to propagate it, so we unwrap
@@ -201,9 +345,9 @@ impl Scraper {
             .unwrap();
         let cursor = sync.cursor(index_settings.clone()).await;
         let maybe_broadcaser = sync.get_broadcaster();
-        let task = tokio::spawn(async move { sync.sync("message_dispatch", cursor.into()).await })
+        let task = tokio::spawn(async move { sync.sync(MESSAGE_DISPATCH, cursor.into()).await })
             .instrument(
-                info_span!("ChainContractSync", chain=%domain.name(), event="message_dispatch"),
+                info_span!("ChainContractSync", chain=%domain.name(), event=MESSAGE_DISPATCH),
             );
         (task, maybe_broadcaser)
     }
@@ -229,7 +373,7 @@ impl Scraper {
             .await
             .unwrap();
 
-        let label = "message_delivery";
+        let label = MESSAGE_DELIVERY;
         let cursor = sync.cursor(index_settings.clone()).await;
         tokio::spawn(async move {
             sync.sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
@@ -246,25 +390,104 @@ impl Scraper {
         db: HyperlaneSqlDb,
         index_settings: IndexSettings,
         tx_id_receiver: Option<Receiver<H512>>,
+    ) -> Option<Instrumented<JoinHandle<()>>> {
+        let label = GAS_PAYMENT;
+        let sync = skip_unsupported(
+            self.as_ref()
+                .settings
+                .watermark_contract_sync::<InterchainGasPayment, _>(
+                    &domain,
+                    &metrics.clone(),
+                    &contract_sync_metrics.clone(),
+                    Arc::new(db.clone()),
+                )
+                .await,
+            &domain,
+            label,
+        )?;
+
+        let cursor = sync.cursor(index_settings.clone()).await;
+        let task = tokio::spawn(async move {
+            sync.sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
+                .await
+        })
+        .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=label));
+        Some(task)
+    }
+
+    async fn build_merkle_tree_insertion_indexer(
+        &self,
+        domain: HyperlaneDomain,
+        metrics: Arc<CoreMetrics>,
+        contract_sync_metrics: Arc<ContractSyncMetrics>,
+        db: HyperlaneSqlDb,
+        index_settings: IndexSettings,
+    ) -> Option<Instrumented<JoinHandle<()>>> {
+        let label = MERKLE_TREE_INSERTION;
+        let sync = skip_unsupported(
+            self.as_ref()
+                .settings
+                .sequenced_contract_sync::<MerkleTreeInsertion, _>(
+                    &domain,
+                    &metrics.clone(),
+                    &contract_sync_metrics.clone(),
+                    db.into(),
+                )
+                .await,
+            &domain,
+            label,
+        )?;
+
+        let cursor = sync.cursor(index_settings).await;
+        let task = tokio::spawn(async move { sync.sync(label, cursor.into()).await })
+            .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=label));
+        Some(task)
+    }
+
+    async fn build_destination_gas_indexer(
+        &self,
+        domain: HyperlaneDomain,
+        metrics: Arc<CoreMetrics>,
+        contract_sync_metrics: Arc<ContractSyncMetrics>,
+        db: HyperlaneSqlDb,
+        index_settings: IndexSettings,
     ) -> Instrumented<JoinHandle<()>> {
         let sync = self
             .as_ref()
             .settings
-            .watermark_contract_sync::<InterchainGasPayment, _>(
+            .watermark_contract_sync::<DestinationGasUpdate, _>(
                 &domain,
                 &metrics.clone(),
                 &contract_sync_metrics.clone(),
-                Arc::new(db.clone()),
+                Arc::new(db),
             )
             .await
             .unwrap();
 
-        let label = "gas_payment";
-        let cursor = sync.cursor(index_settings.clone()).await;
-        tokio::spawn(async move {
-            sync.sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
-                .await
-        })
-        .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=label))
+        let label = DESTINATION_GAS;
+        let cursor = sync.cursor(index_settings).await;
+        tokio::spawn(async move { sync.sync(label, cursor.into()).await })
+            .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=label))
+    }
+}
+
+/// The contract sync, or None if the chain doesn't support indexing the event
+/// yet, in which case the chain's other events are still scraped
+fn skip_unsupported<T>(sync: eyre::Result<T>, domain: &HyperlaneDomain, label: &str) -> Option<T> {
+    match sync {
+        Ok(sync) => Some(sync),
+        Err(err) if UnsupportedByProtocol::caused(&err) => {
+            warn!(
+                chain = domain.name(),
+                event = label,
+                ?err,
+                "Not scraping unsupported event"
+            );
+            None
+        }
+        Err(err) => panic!(
+            "Failed to build the {label} contract sync of {}: {err:?}",
+            domain.name()
+        ),
     }
 }
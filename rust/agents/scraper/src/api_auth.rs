@@ -0,0 +1,276 @@
+//! The scraper's HTTP API can be restricted to a set of API keys, each with
+//! its own rate limit, so it can be exposed to partner teams without a
+//! gateway in front of it. Keys are sent as `Authorization: Bearer <key>` or
+//! in the `x-api-key` header.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::{debug, warn};
+
+use crate::settings::ApiKeyConf;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// The API keys requests are checked against, and the requests left to each
+#[derive(Debug, Clone)]
+pub struct ApiAuth {
+    keys: Arc<Vec<ApiKey>>,
+}
+
+#[derive(Debug)]
+struct ApiKey {
+    name: String,
+    key: String,
+    limiter: Option<Mutex<RateLimiter>>,
+}
+
+impl ApiAuth {
+    /// Restrict the API to the given keys, or leave it open if there are none
+    pub fn new(keys: &[ApiKeyConf]) -> Option<Self> {
+        if keys.is_empty() {
+            return None;
+        }
+        let keys = keys
+            .iter()
+            .map(|conf| ApiKey {
+                name: conf.name.clone(),
+                key: conf.key.clone(),
+                limiter: conf
+                    .requests_per_minute
+                    .map(|limit| Mutex::new(RateLimiter::per_minute(limit))),
+            })
+            .collect();
+        Some(Self {
+            keys: Arc::new(keys),
+        })
+    }
+
+    /// Check the key of a request and take a request from its limit
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), Response> {
+        let Some(sent) = sent_key(headers) else {
+            return Err((StatusCode::UNAUTHORIZED, "Missing API key").into_response());
+        };
+        // Every key is compared in full, so how long checking takes doesn't
+        // reveal how much of a key was guessed
+        let key = self.keys.iter().fold(None, |found, key| {
+            if constant_time_eq(&key.key, sent) {
+                Some(key)
+            } else {
+                found
+            }
+        });
+        let Some(key) = key else {
+            debug!("Rejected request with unknown API key");
+            return Err((StatusCode::UNAUTHORIZED, "Invalid API key").into_response());
+        };
+        let Some(limiter) = &key.limiter else {
+            return Ok(());
+        };
+        let Err(retry_after) = limiter.lock().unwrap().take() else {
+            return Ok(());
+        };
+        warn!(
+            key = key.name,
+            ?retry_after,
+            "API key exceeded its rate limit"
+        );
+        let mut response = (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded").into_response();
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(retry_after.as_secs().max(1)),
+        );
+        Err(response)
+    }
+}
+
+/// Middleware rejecting the requests without a valid API key, or over the
+/// rate limit of their key
+pub async fn authorize<B>(
+    State(auth): State<ApiAuth>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    match auth.authorize(request.headers()) {
+        Ok(()) => next.run(request).await,
+        Err(response) => response,
+    }
+}
+
+fn sent_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get(API_KEY_HEADER) {
+        return key.to_str().ok();
+    }
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Token bucket allowing bursts of up to a minute's worth of requests
+#[derive(Debug)]
+struct RateLimiter {
+    capacity: f64,
+    tokens: f64,
+    per_second: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    fn per_minute(limit: u32) -> Self {
+        Self {
+            capacity: limit as f64,
+            tokens: limit as f64,
+            per_second: limit as f64 / 60.,
+            updated_at: Instant::now(),
+        }
+    }
+
+    /// Take a request, or get how long until one can be made
+    fn take(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity);
+        self.updated_at = now;
+        if self.tokens >= 1. {
+            self.tokens -= 1.;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1. - self.tokens) / self.per_second,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn api_auth() -> ApiAuth {
+        ApiAuth::new(&[
+            ApiKeyConf {
+                name: "limited".to_owned(),
+                key: "limited-key".to_owned(),
+                requests_per_minute: Some(2),
+            },
+            ApiKeyConf {
+                name: "unlimited".to_owned(),
+                key: "unlimited-key".to_owned(),
+                requests_per_minute: None,
+            },
+        ])
+        .unwrap()
+    }
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    fn status(result: Result<(), Response>) -> StatusCode {
+        match result {
+            Ok(()) => StatusCode::OK,
+            Err(response) => response.status(),
+        }
+    }
+
+    #[test]
+    fn test_open_without_keys() {
+        assert!(ApiAuth::new(&[]).is_none());
+    }
+
+    #[test]
+    fn test_authorizes_known_keys() {
+        let auth = api_auth();
+        assert_eq!(
+            status(auth.authorize(&headers(API_KEY_HEADER, "unlimited-key"))),
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(auth.authorize(&headers("authorization", "Bearer unlimited-key"))),
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(auth.authorize(&HeaderMap::new())),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(auth.authorize(&headers(API_KEY_HEADER, "unlimited"))),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(auth.authorize(&headers("authorization", "unlimited-key"))),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[test]
+    fn test_rate_limits_each_key() {
+        let auth = api_auth();
+        let limited = headers(API_KEY_HEADER, "limited-key");
+        assert!(auth.authorize(&limited).is_ok());
+        assert!(auth.authorize(&limited).is_ok());
+        let response = auth.authorize(&limited).unwrap_err();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((29..=30).contains(&retry_after));
+
+        // Other keys have their own limit
+        let unlimited = headers(API_KEY_HEADER, "unlimited-key");
+        for _ in 0..10 {
+            assert!(auth.authorize(&unlimited).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_rate_limiter_refills() {
+        let mut limiter = RateLimiter::per_minute(2);
+        assert!(limiter.take().is_ok());
+        assert!(limiter.take().is_ok());
+        let retry_after = limiter.take().unwrap_err();
+        assert!(retry_after <= Duration::from_secs(30));
+        assert!(retry_after > Duration::from_secs(29));
+
+        // Half a minute refills a request
+        limiter.updated_at -= Duration::from_secs(30);
+        assert!(limiter.take().is_ok());
+        assert!(limiter.take().is_err());
+
+        // Requests don't accumulate past a minute's worth
+        limiter.updated_at -= Duration::from_secs(600);
+        assert!(limiter.take().is_ok());
+        assert!(limiter.take().is_ok());
+        assert!(limiter.take().is_err());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("key", "key"));
+        assert!(!constant_time_eq("key", "kez"));
+        assert!(!constant_time_eq("key", "keys"));
+    }
+}
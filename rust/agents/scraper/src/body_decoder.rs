@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use ethers::{
+    abi::{decode, ParamType, Token},
+    types::I256,
+};
+use hyperlane_core::{
+    utils::bytes_to_hex, AddressFormat, Decode, HyperlaneMessage, InterchainAccountMessage, H256,
+    U256,
+};
+use serde_json::{json, Map, Value};
+use tracing::debug;
+
+use crate::settings::{BodyDecoderConf, BodySchema};
+
+/// Decodes the bodies of the messages of known apps, so they can be queried
+/// as JSON rather than as raw bytes.
+#[derive(Debug, Default)]
+pub struct BodyDecoders {
+    decoders: Vec<BodyDecoderConf>,
+    /// Index in `decoders` of the first decoder of each contract, by its
+    /// domain and address
+    by_address: HashMap<(u32, H256), usize>,
+}
+
+impl BodyDecoders {
+    pub fn new(decoders: Vec<BodyDecoderConf>) -> Self {
+        let mut by_address = HashMap::new();
+        for (index, decoder) in decoders.iter().enumerate() {
+            for contract in &decoder.addresses {
+                by_address.entry(*contract).or_insert(index);
+            }
+        }
+        Self {
+            decoders,
+            by_address,
+        }
+    }
+
+    /// Decode the body of a message with the first decoder whose app sent or
+    /// received it. None if no app did, or if the body doesn't match the
+    /// app's schema. `recipient_format` is the address format of the
+    /// destination.
+    pub fn decode(
+        &self,
+        message: &HyperlaneMessage,
+        recipient_format: &AddressFormat,
+    ) -> Option<Value> {
+        let index = [
+            (message.origin, message.sender),
+            (message.destination, message.recipient),
+        ]
+        .iter()
+        .filter_map(|contract| self.by_address.get(contract))
+        .min()?;
+        let decoder = &self.decoders[*index];
+        let fields = match &decoder.schema {
+            BodySchema::WarpRoute => decode_warp_route(&message.body),
+            BodySchema::Abi(params) => decode_abi(params, &message.body),
+            BodySchema::InterchainAccount => {
+                decode_interchain_account(message, *recipient_format == AddressFormat::Hex20)
+            }
+        };
+        if fields.is_none() {
+            debug!(
+                app = %decoder.app,
+                id = ?message.id(),
+                "Message body does not match the app's schema"
+            );
+        }
+        Some(json!({ "app": decoder.app, "fields": fields? }))
+    }
+}
+
+fn decode_warp_route(body: &[u8]) -> Option<Value> {
+    if body.len() < 64 {
+        return None;
+    }
+    let mut fields = json!({
+        "recipient": bytes_to_hex(&body[..32]),
+        "amount": U256::from_big_endian(&body[32..64]).to_string(),
+    });
+    if body.len() > 64 {
+        fields["metadata"] = bytes_to_hex(&body[64..]).into();
+    }
+    Some(fields)
+}
+
+/// The calls of an interchain account message, with the account making them
+/// if it can be derived
+fn decode_interchain_account(message: &HyperlaneMessage, evm_destination: bool) -> Option<Value> {
+    let ica = InterchainAccountMessage::read_from(&mut message.body.as_slice()).ok()?;
+    let account = evm_destination.then(|| {
+        format!(
+            "{:?}",
+            ica.account(message.origin, message.sender, message.recipient)
+        )
+    });
+    let calls = ica
+        .calls
+        .iter()
+        .map(|call| {
+            json!({
+                "to": format!("{:?}", call.to),
+                "value": call.value.to_string(),
+                "data": bytes_to_hex(&call.data),
+            })
+        })
+        .collect::<Vec<_>>();
+    Some(json!({
+        "owner": format!("{:?}", ica.owner),
+        "ism": format!("{:?}", ica.ism),
+        "account": account,
+        "calls": calls,
+    }))
+}
+
+fn decode_abi(params: &[(String, ParamType)], body: &[u8]) -> Option<Value> {
+    let types = params.iter().map(|(_, ty)| ty.clone()).collect::<Vec<_>>();
+    let tokens = decode(&types, body).ok()?;
+    let fields = params
+        .iter()
+        .zip(tokens)
+        .map(|((name, _), token)| (name.clone(), token_to_json(token)))
+        .collect::<Map<_, _>>();
+    Some(fields.into())
+}
+
+/// Integers are kept as decimal strings, as JSON numbers can't hold them all
+fn token_to_json(token: Token) -> Value {
+    match token {
+        Token::Address(address) => format!("{address:?}").into(),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => bytes_to_hex(&bytes).into(),
+        Token::Int(int) => I256::from_raw(int).to_string().into(),
+        Token::Uint(uint) => uint.to_string().into(),
+        Token::Bool(b) => b.into(),
+        Token::String(s) => s.into(),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            tokens.into_iter().map(token_to_json).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::types::H160;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_warp_route() {
+        let mut body = [H256::repeat_byte(1).as_bytes(), &[0; 32][..]].concat();
+        body[63] = 100;
+        assert_eq!(
+            decode_warp_route(&body),
+            Some(json!({
+                "recipient": bytes_to_hex(&[1; 32]),
+                "amount": "100",
+            }))
+        );
+
+        body.extend([0xab, 0xcd]);
+        assert_eq!(decode_warp_route(&body).unwrap()["metadata"], "0xabcd");
+        assert_eq!(decode_warp_route(&body[..63]), None);
+    }
+
+    #[test]
+    fn test_decode_abi() {
+        let params = vec![
+            ("to".to_owned(), ParamType::Address),
+            ("amount".to_owned(), ParamType::Uint(256)),
+            ("memo".to_owned(), ParamType::String),
+        ];
+        let body = ethers::abi::encode(&[
+            Token::Address(H160::repeat_byte(2)),
+            Token::Uint(U256::MAX),
+            Token::String("hi".to_owned()),
+        ]);
+        assert_eq!(
+            decode_abi(&params, &body),
+            Some(json!({
+                "to": format!("{:?}", H160::repeat_byte(2)),
+                "amount": U256::MAX.to_string(),
+                "memo": "hi",
+            }))
+        );
+        // Bodies that don't match the types aren't decoded
+        assert_eq!(decode_abi(&params, &body[..64]), None);
+    }
+
+    #[test]
+    fn test_token_to_json() {
+        assert_eq!(
+            token_to_json(Token::Int(I256::from(-5i64).into_raw())),
+            json!("-5")
+        );
+        assert_eq!(token_to_json(Token::Bytes(vec![1, 2])), json!("0x0102"));
+        assert_eq!(token_to_json(Token::Bool(true)), json!(true));
+        assert_eq!(
+            token_to_json(Token::Array(vec![
+                Token::Uint(1.into()),
+                Token::Tuple(vec![Token::String("a".to_owned())]),
+            ])),
+            json!(["1", ["a"]])
+        );
+    }
+
+    #[test]
+    fn test_decoders_are_matched_by_domain() {
+        let contract = H256::repeat_byte(3);
+        let decoders = BodyDecoders::new(vec![BodyDecoderConf {
+            app: "warp".to_owned(),
+            addresses: vec![(1, contract)],
+            schema: BodySchema::WarpRoute,
+        }]);
+        let message = HyperlaneMessage {
+            origin: 1,
+            destination: 2,
+            sender: contract,
+            body: vec![0; 64],
+            ..Default::default()
+        };
+        assert_eq!(
+            decoders.decode(&message, &AddressFormat::Hex20).unwrap()["app"],
+            "warp"
+        );
+        // The same address on another domain is another contract
+        let other = HyperlaneMessage {
+            origin: 3,
+            ..message.clone()
+        };
+        assert_eq!(decoders.decode(&other, &AddressFormat::Hex20), None);
+        let received = HyperlaneMessage {
+            origin: 2,
+            destination: 1,
+            sender: H256::zero(),
+            recipient: contract,
+            ..message
+        };
+        assert!(decoders.decode(&received, &AddressFormat::Hex20).is_some());
+    }
+}
@@ -1,23 +1,27 @@
 //! This module (and children) are responsible for scraping blockchain data and
 //! keeping things updated.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, ops::RangeInclusive, sync::Arc};
 
 use async_trait::async_trait;
 use eyre::Result;
 use hyperlane_base::settings::IndexSettings;
 use hyperlane_core::{
-    unwrap_or_none_result, BlockInfo, Delivery, HyperlaneDomain, HyperlaneLogStore,
-    HyperlaneMessage, HyperlaneProvider, HyperlaneSequenceAwareIndexerStoreReader,
-    HyperlaneWatermarkedLogStore, Indexed, InterchainGasPayment, LogMeta, H256,
+    unwrap_or_none_result, AddressFormat, BlockInfo, Delivery, DestinationGasUpdate,
+    HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, HyperlaneProvider,
+    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, IndexMode, Indexed,
+    InterchainGasPayment, LogMeta, MerkleTreeInsertion, H256,
 };
 use itertools::Itertools;
 use tracing::trace;
 
+use crate::body_decoder::BodyDecoders;
 use crate::db::{
-    BasicBlock, BlockCursor, ScraperDb, StorableDelivery, StorableMessage, StorablePayment,
-    StorableTxn,
+    BasicBlock, BlockCursor, BlockGap, NonceGap, ScraperDb, StorableDelivery,
+    StorableDestinationGasUpdate, StorableInsertion, StorableMessage, StorablePayment, StorableTxn,
+    StoredLeaf, TokenUsd,
 };
+use crate::price_feed::PriceFeed;
 
 /// Maximum number of records to query at a time. This came about because when a
 /// lot of messages are sent in a short period of time we were ending up with a
@@ -25,15 +29,35 @@ use crate::db::{
 /// actually save it to the database.
 const CHUNK_SIZE: usize = 50;
 
+/// Event streams scraped from each chain, which their cursors and indexed
+/// ranges are stored for
+pub const MESSAGE_DISPATCH: &str = "message_dispatch";
+pub const MESSAGE_DELIVERY: &str = "message_delivery";
+pub const GAS_PAYMENT: &str = "gas_payment";
+pub const MERKLE_TREE_INSERTION: &str = "merkle_tree_insertion";
+pub const DESTINATION_GAS: &str = "destination_gas";
+
 /// A chain scraper is comprised of all the information and contract/provider
 /// connections needed to scrape the contracts on a single blockchain.
 #[derive(Clone, Debug)]
 pub struct HyperlaneSqlDb {
     mailbox_address: H256,
+    merkle_tree_hook_address: H256,
     domain: HyperlaneDomain,
+    /// Address formats of the chains the scraper is configured with
+    address_formats: Arc<HashMap<u32, AddressFormat>>,
+    address_format: AddressFormat,
+    /// Decoders of the message bodies of known apps
+    body_decoders: Arc<BodyDecoders>,
+    /// Source of the USD price of the chain's native token, if any
+    price_feed: Option<Arc<dyn PriceFeed>>,
     db: ScraperDb,
     provider: Arc<dyn HyperlaneProvider>,
-    cursor: Arc<BlockCursor>,
+    /// How messages are indexed. Only block ranges are recorded.
+    index_mode: IndexMode,
+    delivery_cursor: Arc<BlockCursor>,
+    payment_cursor: Arc<BlockCursor>,
+    destination_gas_cursor: Arc<BlockCursor>,
 }
 
 #[allow(unused)]
@@ -41,20 +65,44 @@ impl HyperlaneSqlDb {
     pub async fn new(
         db: ScraperDb,
         mailbox_address: H256,
+        merkle_tree_hook_address: H256,
         domain: HyperlaneDomain,
         provider: Arc<dyn HyperlaneProvider>,
         index_settings: &IndexSettings,
+        address_formats: Arc<HashMap<u32, AddressFormat>>,
+        body_decoders: Arc<BodyDecoders>,
+        price_feed: Option<Arc<dyn PriceFeed>>,
     ) -> Result<Self> {
-        let cursor = Arc::new(
-            db.block_cursor(domain.id(), index_settings.from as u64)
+        let delivery_cursor = Arc::new(
+            db.block_cursor(domain.id(), MESSAGE_DELIVERY, index_settings.from as u64)
+                .await?,
+        );
+        let payment_cursor = Arc::new(
+            db.block_cursor(domain.id(), GAS_PAYMENT, index_settings.from as u64)
+                .await?,
+        );
+        let destination_gas_cursor = Arc::new(
+            db.block_cursor(domain.id(), DESTINATION_GAS, index_settings.from as u64)
                 .await?,
         );
+        let address_format = address_formats
+            .get(&domain.id())
+            .cloned()
+            .unwrap_or_else(|| AddressFormat::for_domain(&domain));
         Ok(Self {
             db,
             domain,
+            address_formats,
+            address_format,
+            body_decoders,
+            price_feed,
             provider,
+            index_mode: index_settings.mode,
             mailbox_address,
-            cursor,
+            merkle_tree_hook_address,
+            delivery_cursor,
+            payment_cursor,
+            destination_gas_cursor,
         })
     }
 
@@ -64,10 +112,101 @@ impl HyperlaneSqlDb {
 
     pub async fn last_message_nonce(&self) -> Result<Option<u32>> {
         self.db
-            .last_message_nonce(self.domain.id(), &self.mailbox_address)
+            .last_message_nonce(
+                self.domain.id(),
+                &self.mailbox_address,
+                &self.address_format,
+            )
+            .await
+    }
+
+    /// The first `limit` gaps in the nonces of the messages stored from the
+    /// mailbox
+    pub async fn message_nonce_gaps(&self, limit: u32) -> Result<Vec<NonceGap>> {
+        self.db
+            .message_nonce_gaps(
+                self.domain.id(),
+                &self.mailbox_address,
+                &self.address_format,
+                limit,
+            )
+            .await
+    }
+
+    /// The first `limit` holes between the block ranges indexed for `stream`
+    pub async fn indexed_range_gaps(&self, stream: &str, limit: u32) -> Result<Vec<BlockGap>> {
+        self.db
+            .indexed_range_gaps(self.domain.id(), stream, limit)
+            .await
+    }
+
+    /// Up to `limit` leaves stored from the merkle tree hook, from
+    /// `from_index` on
+    pub async fn merkle_tree_leaves(&self, from_index: u32, limit: u32) -> Result<Vec<StoredLeaf>> {
+        self.db
+            .merkle_tree_leaves(
+                self.domain.id(),
+                &self.merkle_tree_hook_address,
+                &self.address_format,
+                from_index,
+                limit,
+            )
+            .await
+    }
+
+    /// Store the roots the merkle tree hook had once each of the leaves at
+    /// the given indices was inserted
+    pub async fn store_checkpoint_roots(&self, roots: &[(u32, H256)]) -> Result<()> {
+        self.db
+            .store_checkpoint_roots(
+                self.domain.id(),
+                &self.merkle_tree_hook_address,
+                &self.address_format,
+                roots,
+            )
             .await
     }
 
+    /// Record that the events of `stream` were stored for every block of
+    /// `range`
+    async fn store_indexed_blocks(&self, stream: &str, range: RangeInclusive<u32>) -> Result<()> {
+        self.db
+            .store_indexed_range(
+                self.domain.id(),
+                stream,
+                (*range.start() as u64)..=(*range.end() as u64),
+            )
+            .await
+    }
+
+    /// The current USD price of the chain's native token, if the price feed
+    /// has a recent one. The feed is refreshed in the background, so storing
+    /// what the price values never waits on it.
+    fn native_token_usd(&self) -> Option<TokenUsd> {
+        let price_feed = self.price_feed.as_ref()?;
+        price_feed
+            .native_token_usd(&self.domain)
+            .map(|price| TokenUsd::current(price, price_feed.max_block_age()))
+    }
+
+    /// The address format of the recipient of a message, from the
+    /// destination chain. Recipients on chains that are neither configured
+    /// nor known are stored as EVM addresses if they look like one, as
+    /// before the format was taken into account.
+    fn recipient_format(&self, message: &HyperlaneMessage) -> AddressFormat {
+        self.address_formats
+            .get(&message.destination)
+            .cloned()
+            .or_else(|| AddressFormat::for_domain_id(message.destination))
+            .unwrap_or_else(|| {
+                if hex::is_h160(message.recipient.as_fixed_bytes()) {
+                    AddressFormat::Hex20
+                } else {
+                    AddressFormat::Hex32
+                }
+            })
+    }
+
     /// Takes a list of txn and block hashes and ensure they are all in the
     /// database. If any are not it will fetch the data and insert them.
     ///
@@ -159,7 +298,9 @@ impl HyperlaneSqlDb {
                 });
             }
 
-            self.db.store_txns(txns_to_insert.drain(..)).await?;
+            self.db
+                .store_txns(&self.address_format, txns_to_insert.drain(..))
+                .await?;
             let ids = self.db.get_txn_ids(hashes_to_insert.drain(..)).await?;
 
             for (hash, (txn_id, _block_id)) in chunk.iter_mut() {
@@ -286,7 +427,10 @@ impl HyperlaneLogStore<HyperlaneMessage> for HyperlaneSqlDb {
                         .expect("256-bit transaction ids are the maximum supported at this time"),
                 )
                 .unwrap();
+            let recipient_format = self.recipient_format(m.0.inner());
             StorableMessage {
+                decoded_body: self.body_decoders.decode(m.0.inner(), &recipient_format),
+                recipient_format,
                 msg: m.0.inner().clone(),
                 meta: &m.1,
                 txn_id: txn.id,
@@ -294,10 +438,26 @@ impl HyperlaneLogStore<HyperlaneMessage> for HyperlaneSqlDb {
         });
         let stored = self
             .db
-            .store_dispatched_messages(self.domain().id(), &self.mailbox_address, storable)
+            .store_dispatched_messages(
+                self.domain().id(),
+                &self.mailbox_address,
+                &self.address_format,
+                storable,
+            )
+            .await?;
+        self.db
+            .store_dispatch_gas_prices(txns.values().map(|txn| txn.id), self.native_token_usd())
             .await?;
         Ok(stored as u32)
     }
+
+    async fn store_indexed_range(&self, range: RangeInclusive<u32>) -> Result<()> {
+        match self.index_mode {
+            IndexMode::Block => self.store_indexed_blocks(MESSAGE_DISPATCH, range).await,
+            // Nonce gaps are re-indexed by the reconciler
+            IndexMode::Sequence => Ok(()),
+        }
+    }
 }
 
 #[async_trait]
@@ -330,10 +490,22 @@ impl HyperlaneLogStore<Delivery> for HyperlaneSqlDb {
 
         let stored = self
             .db
-            .store_deliveries(self.domain().id(), self.mailbox_address, storable)
+            .store_deliveries(
+                self.domain().id(),
+                self.mailbox_address,
+                &self.address_format,
+                storable,
+            )
+            .await?;
+        self.db
+            .store_delivery_gas_prices(txns.values().map(|txn| txn.id), self.native_token_usd())
             .await?;
         Ok(stored as u32)
     }
+
+    async fn store_indexed_range(&self, range: RangeInclusive<u32>) -> Result<()> {
+        self.store_indexed_blocks(MESSAGE_DELIVERY, range).await
+    }
 }
 
 #[async_trait]
@@ -370,6 +542,98 @@ impl HyperlaneLogStore<InterchainGasPayment> for HyperlaneSqlDb {
         let stored = self.db.store_payments(self.domain().id(), storable).await?;
         Ok(stored as u32)
     }
+
+    async fn store_indexed_range(&self, range: RangeInclusive<u32>) -> Result<()> {
+        self.store_indexed_blocks(GAS_PAYMENT, range).await
+    }
+}
+
+#[async_trait]
+impl HyperlaneLogStore<DestinationGasUpdate> for HyperlaneSqlDb {
+    /// Store the gas data set in the origin's gas oracle
+    async fn store_logs(
+        &self,
+        updates: &[(Indexed<DestinationGasUpdate>, LogMeta)],
+    ) -> Result<u32> {
+        if updates.is_empty() {
+            return Ok(0);
+        }
+        let txns: HashMap<H256, TxnWithId> = self
+            .ensure_blocks_and_txns(updates.iter().map(|r| &r.1))
+            .await?
+            .map(|t| (t.hash, t))
+            .collect();
+        let storable = updates.iter().map(|(update, meta)| {
+            let txn_id = txns
+                .get(
+                    &meta
+                        .transaction_id
+                        .try_into()
+                        .expect("256-bit transaction ids are the maximum supported at this time"),
+                )
+                .unwrap()
+                .id;
+            StorableDestinationGasUpdate {
+                update: update.inner(),
+                meta,
+                txn_id,
+            }
+        });
+
+        let stored = self
+            .db
+            .store_destination_gas_updates(self.domain().id(), storable)
+            .await?;
+        Ok(stored as u32)
+    }
+
+    async fn store_indexed_range(&self, range: RangeInclusive<u32>) -> Result<()> {
+        self.store_indexed_blocks(DESTINATION_GAS, range).await
+    }
+}
+
+#[async_trait]
+impl HyperlaneLogStore<MerkleTreeInsertion> for HyperlaneSqlDb {
+    /// Store the leaves inserted into the origin's merkle tree hook
+    async fn store_logs(
+        &self,
+        insertions: &[(Indexed<MerkleTreeInsertion>, LogMeta)],
+    ) -> Result<u32> {
+        if insertions.is_empty() {
+            return Ok(0);
+        }
+        let txns: HashMap<H256, TxnWithId> = self
+            .ensure_blocks_and_txns(insertions.iter().map(|r| &r.1))
+            .await?
+            .map(|t| (t.hash, t))
+            .collect();
+        let storable = insertions.iter().map(|(insertion, meta)| {
+            let txn_id = txns
+                .get(
+                    &meta
+                        .transaction_id
+                        .try_into()
+                        .expect("256-bit transaction ids are the maximum supported at this time"),
+                )
+                .unwrap()
+                .id;
+            StorableInsertion {
+                insertion: insertion.inner(),
+                txn_id,
+            }
+        });
+
+        let stored = self
+            .db
+            .store_merkle_tree_insertions(
+                self.domain().id(),
+                &self.merkle_tree_hook_address,
+                &self.address_format,
+                storable,
+            )
+            .await?;
+        Ok(stored as u32)
+    }
 }
 
 #[async_trait]
@@ -378,7 +642,12 @@ impl HyperlaneSequenceAwareIndexerStoreReader<HyperlaneMessage> for HyperlaneSql
     async fn retrieve_by_sequence(&self, sequence: u32) -> Result<Option<HyperlaneMessage>> {
         let message = self
             .db
-            .retrieve_message_by_nonce(self.domain().id(), &self.mailbox_address, sequence)
+            .retrieve_message_by_nonce(
+                self.domain().id(),
+                &self.mailbox_address,
+                &self.address_format,
+                sequence,
+            )
             .await?;
         Ok(message)
     }
@@ -387,7 +656,45 @@ impl HyperlaneSequenceAwareIndexerStoreReader<HyperlaneMessage> for HyperlaneSql
     async fn retrieve_log_block_number_by_sequence(&self, sequence: u32) -> Result<Option<u64>> {
         let tx_id = unwrap_or_none_result!(
             self.db
-                .retrieve_dispatched_tx_id(self.domain().id(), &self.mailbox_address, sequence)
+                .retrieve_dispatched_tx_id(
+                    self.domain().id(),
+                    &self.mailbox_address,
+                    &self.address_format,
+                    sequence,
+                )
+                .await?
+        );
+        let block_id = unwrap_or_none_result!(self.db.retrieve_block_id(tx_id).await?);
+        Ok(self.db.retrieve_block_number(block_id).await?)
+    }
+}
+
+#[async_trait]
+impl HyperlaneSequenceAwareIndexerStoreReader<MerkleTreeInsertion> for HyperlaneSqlDb {
+    /// Gets a leaf of the merkle tree hook by its index.
+    async fn retrieve_by_sequence(&self, sequence: u32) -> Result<Option<MerkleTreeInsertion>> {
+        let insertion = self
+            .db
+            .retrieve_merkle_tree_insertion(
+                self.domain().id(),
+                &self.merkle_tree_hook_address,
+                &self.address_format,
+                sequence,
+            )
+            .await?;
+        Ok(insertion.map(|(insertion, _)| insertion))
+    }
+
+    /// Gets the block number at which the leaf was inserted.
+    async fn retrieve_log_block_number_by_sequence(&self, sequence: u32) -> Result<Option<u64>> {
+        let (_, tx_id) = unwrap_or_none_result!(
+            self.db
+                .retrieve_merkle_tree_insertion(
+                    self.domain().id(),
+                    &self.merkle_tree_hook_address,
+                    &self.address_format,
+                    sequence,
+                )
                 .await?
         );
         let block_id = unwrap_or_none_result!(self.db.retrieve_block_id(tx_id).await?);
@@ -396,17 +703,42 @@ impl HyperlaneSequenceAwareIndexerStoreReader<HyperlaneMessage> for HyperlaneSql
 }
 
 #[async_trait]
-impl<T> HyperlaneWatermarkedLogStore<T> for HyperlaneSqlDb
-where
-    HyperlaneSqlDb: HyperlaneLogStore<T>,
-{
+impl HyperlaneWatermarkedLogStore<Delivery> for HyperlaneSqlDb {
+    /// Gets the block number high watermark
+    async fn retrieve_high_watermark(&self) -> Result<Option<u32>> {
+        Ok(Some(self.delivery_cursor.height().await.try_into()?))
+    }
+    /// Stores the block number high watermark
+    async fn store_high_watermark(&self, block_number: u32) -> Result<()> {
+        self.delivery_cursor.update(block_number.into()).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HyperlaneWatermarkedLogStore<InterchainGasPayment> for HyperlaneSqlDb {
+    /// Gets the block number high watermark
+    async fn retrieve_high_watermark(&self) -> Result<Option<u32>> {
+        Ok(Some(self.payment_cursor.height().await.try_into()?))
+    }
+    /// Stores the block number high watermark
+    async fn store_high_watermark(&self, block_number: u32) -> Result<()> {
+        self.payment_cursor.update(block_number.into()).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HyperlaneWatermarkedLogStore<DestinationGasUpdate> for HyperlaneSqlDb {
     /// Gets the block number high watermark
     async fn retrieve_high_watermark(&self) -> Result<Option<u32>> {
-        Ok(Some(self.cursor.height().await.try_into()?))
+        Ok(Some(self.destination_gas_cursor.height().await.try_into()?))
     }
     /// Stores the block number high watermark
     async fn store_high_watermark(&self, block_number: u32) -> Result<()> {
-        self.cursor.update(block_number.into()).await;
+        self.destination_gas_cursor
+            .update(block_number.into())
+            .await;
         Ok(())
     }
 }
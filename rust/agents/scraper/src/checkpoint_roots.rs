@@ -0,0 +1,199 @@
+//! Validators sign checkpoints of the root of each origin's merkle tree hook.
+//! This recomputes those roots from the leaves scraped, in order, so which
+//! root validators should have signed for an index can be audited without
+//! trusting them or the hook.
+
+use std::time::Duration;
+
+use hyperlane_base::CoreMetrics;
+use hyperlane_core::{accumulator::incremental::IncrementalMerkle, H256};
+use prometheus::IntGaugeVec;
+use tokio::{task::JoinHandle, time::sleep};
+use tracing::{debug, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::{chain_scraper::HyperlaneSqlDb, db::StoredLeaf};
+
+/// How often roots are computed for the leaves scraped since
+const ROOT_INTERVAL: Duration = Duration::from_secs(30);
+/// Most leaves read at once
+const LEAVES_PER_QUERY: u32 = 1000;
+
+/// The tree of an origin, with the leaves ingested so far
+#[derive(Debug)]
+struct OriginTree {
+    db: HyperlaneSqlDb,
+    tree: IncrementalMerkle,
+}
+
+/// Computes the root of each origin's merkle tree hook after every leaf
+/// scraped, as long as no leaf before it is missing
+#[derive(Debug)]
+pub struct CheckpointRooter {
+    origins: Vec<OriginTree>,
+    rooted_leaves: IntGaugeVec,
+}
+
+impl CheckpointRooter {
+    pub fn new(origins: Vec<HyperlaneSqlDb>, metrics: &CoreMetrics) -> eyre::Result<Self> {
+        let rooted_leaves = metrics.new_int_gauge(
+            "checkpoint_rooted_leaves",
+            "Leaves of the merkle tree hook of an origin the checkpoint roots were computed for",
+            &["chain"],
+        )?;
+        let origins = origins
+            .into_iter()
+            .map(|db| OriginTree {
+                db,
+                tree: IncrementalMerkle::default(),
+            })
+            .collect();
+        Ok(Self {
+            origins,
+            rooted_leaves,
+        })
+    }
+
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        tokio::spawn(async move { self.run().await }).instrument(info_span!("CheckpointRooter"))
+    }
+
+    async fn run(mut self) {
+        loop {
+            for origin in &mut self.origins {
+                let name = origin.db.domain().name().to_owned();
+                if let Err(err) = Self::root_new_leaves(origin).await {
+                    warn!(origin = name, ?err, "Failed to compute checkpoint roots");
+                }
+                self.rooted_leaves
+                    .with_label_values(&[&name])
+                    .set(origin.tree.count() as i64);
+            }
+            sleep(ROOT_INTERVAL).await;
+        }
+    }
+
+    /// Ingest the leaves stored after the ones ingested so far, up to the
+    /// first one missing, and store the roots not stored yet
+    async fn root_new_leaves(origin: &mut OriginTree) -> eyre::Result<()> {
+        loop {
+            let next = origin.tree.count() as u32;
+            let leaves = origin.db.merkle_tree_leaves(next, LEAVES_PER_QUERY).await?;
+            let fetched = leaves.len();
+            let roots = ingest_leaves(&mut origin.tree, leaves);
+            origin.db.store_checkpoint_roots(&roots).await?;
+            debug!(
+                origin = origin.db.domain().name(),
+                count = origin.tree.count(),
+                stored = roots.len(),
+                "Computed checkpoint roots"
+            );
+            let ingested = origin.tree.count() - next as usize;
+            if fetched < LEAVES_PER_QUERY as usize || ingested < fetched {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Ingest the leaves following those of `tree`, up to the first one missing,
+/// and get the roots of the ones whose root isn't stored yet, by index
+fn ingest_leaves(tree: &mut IncrementalMerkle, leaves: Vec<StoredLeaf>) -> Vec<(u32, H256)> {
+    let next = tree.count() as u32;
+    let mut roots = vec![];
+    for (leaf, index) in leaves.into_iter().zip(next..) {
+        if leaf.index != index {
+            break;
+        }
+        tree.ingest(leaf.message_id);
+        if !leaf.rooted {
+            roots.push((leaf.index, tree.root()));
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::utils::hash_message;
+    use hyperlane_core::test_utils;
+
+    use super::*;
+
+    fn leaves(test_case: &test_utils::MerkleTestCase) -> Vec<StoredLeaf> {
+        test_case
+            .leaves
+            .iter()
+            .zip(0..)
+            .map(|(leaf, index)| StoredLeaf {
+                index,
+                message_id: hash_message(leaf).into(),
+                rooted: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_computes_known_roots() {
+        for test_case in test_utils::load_merkle_test_json() {
+            if test_case.leaves.is_empty() {
+                continue;
+            }
+            let mut tree = IncrementalMerkle::default();
+            let roots = ingest_leaves(&mut tree, leaves(&test_case));
+            assert_eq!(roots.len(), test_case.leaves.len());
+            assert_eq!(
+                roots.last().unwrap(),
+                &(test_case.leaves.len() as u32 - 1, test_case.expected_root),
+                "{}",
+                test_case.test_name
+            );
+        }
+    }
+
+    #[test]
+    fn test_computes_roots_in_batches() {
+        for test_case in test_utils::load_merkle_test_json() {
+            let leaves = leaves(&test_case);
+            if leaves.len() < 2 {
+                continue;
+            }
+            let mut tree = IncrementalMerkle::default();
+            let mut first = leaves.clone();
+            let rest = first.split_off(leaves.len() / 2);
+            // The roots of leaves already rooted aren't computed again
+            first[0].rooted = true;
+            let roots = ingest_leaves(&mut tree, first);
+            assert_eq!(
+                roots.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+                (1..leaves.len() as u32 / 2).collect::<Vec<_>>()
+            );
+
+            let roots = ingest_leaves(&mut tree, rest);
+            assert_eq!(
+                roots.last().unwrap(),
+                &(leaves.len() as u32 - 1, test_case.expected_root),
+                "{}",
+                test_case.test_name
+            );
+        }
+    }
+
+    #[test]
+    fn test_stops_at_missing_leaf() {
+        let test_case = test_utils::load_merkle_test_json()
+            .into_iter()
+            .find(|test_case| test_case.leaves.len() >= 3)
+            .unwrap();
+        let mut leaves = leaves(&test_case);
+        leaves.remove(1);
+
+        let mut tree = IncrementalMerkle::default();
+        let roots = ingest_leaves(&mut tree, leaves.clone());
+        assert_eq!(roots.len(), 1);
+        assert_eq!(tree.count(), 1);
+
+        let mut expected = IncrementalMerkle::default();
+        expected.ingest(leaves[0].message_id);
+        assert_eq!(roots, vec![(0, expected.root())]);
+    }
+}
@@ -1,30 +1,17 @@
 use num_bigint::{BigInt, Sign};
 use sea_orm::prelude::BigDecimal;
 
-use hyperlane_core::{H256, U256};
+use hyperlane_core::{AddressFormat, H256, U256};
 
-// Creates a big-endian hex representation of the address
-pub fn address_to_bytes(data: &H256) -> Vec<u8> {
-    if hex::is_h160(data.as_fixed_bytes()) {
-        // take the last 20 bytes
-        data.as_fixed_bytes()[12..32].into()
-    } else {
-        h256_to_bytes(data)
-    }
+// Creates the canonical byte representation of an address of a chain using
+// `format`, which is 20 bytes for EVM addresses and 32 bytes otherwise
+pub fn address_to_bytes(format: &AddressFormat, data: &H256) -> Vec<u8> {
+    format.to_bytes(data)
 }
 
-// Creates a big-endian hex representation of the address
+// Reads an address stored as 20 or 32 bytes
 pub fn bytes_to_address(data: Vec<u8>) -> eyre::Result<H256> {
-    if (data.len() != 20) && (data.len() != 32) {
-        return Err(eyre::eyre!("Invalid address length"));
-    }
-    if data.len() == 20 {
-        let mut prefix = vec![0; 12];
-        prefix.extend(data);
-        Ok(H256::from_slice(&prefix[..]))
-    } else {
-        Ok(H256::from_slice(&data[..]))
-    }
+    AddressFormat::from_bytes(&data)
 }
 
 // Creates a big-endian hex representation of the address hash
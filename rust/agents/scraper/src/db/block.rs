@@ -8,7 +8,7 @@ use tracing::{debug, trace};
 use hyperlane_core::{BlockInfo, H256};
 use migration::OnConflict;
 
-use crate::conversions::{address_to_bytes, h256_to_bytes};
+use crate::conversions::h256_to_bytes;
 use crate::date_time;
 use crate::db::ScraperDb;
 
@@ -45,7 +45,7 @@ impl ScraperDb {
             .select_only()
             .column_as(block::Column::Height, QueryAs::Height)
             .into_values::<i64, QueryAs>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
         match block_height {
             Some(height) => Ok(Some(height.try_into()?)),
@@ -68,7 +68,7 @@ impl ScraperDb {
             .column_as(block::Column::Id, "id")
             .column_as(block::Column::Hash, "hash")
             .into_model::<BasicBlock>()
-            .all(&self.0)
+            .all(&self.conn)
             .await
             .context("When querying blocks")?;
 
@@ -85,7 +85,7 @@ impl ScraperDb {
         let models = blocks
             .map(|info| block::ActiveModel {
                 id: NotSet,
-                hash: Set(address_to_bytes(&info.hash)),
+                hash: Set(h256_to_bytes(&info.hash)),
                 time_created: Set(date_time::now()),
                 domain: Unchanged(domain as i32),
                 height: Unchanged(info.number as i64),
@@ -102,7 +102,7 @@ impl ScraperDb {
                     .do_nothing()
                     .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await
         {
             Ok(_) => Ok(()),
@@ -1,7 +1,7 @@
 use std::time::{Duration, Instant};
 
 use eyre::Result;
-use sea_orm::{prelude::*, ActiveValue, Insert, Order, QueryOrder, QuerySelect};
+use sea_orm::{prelude::*, ActiveValue, Condition, Insert, Order, QueryOrder, QuerySelect};
 use tokio::sync::RwLock;
 use tracing::{debug, info, instrument, warn};
 
@@ -22,31 +22,57 @@ struct BlockCursorInner {
 /// A tool to wrap the logic of fetching and updating the cursor position in the
 /// database. We may end up reading the same block range again later but this
 /// prevents us from starting from the beginning after a restart.
+///
+/// Each event stream of a domain has its own cursor, so that a stream that
+/// is ahead of another doesn't make it skip blocks after a restart. So does
+/// each environment scraping the domain.
 #[derive(Debug)]
 pub struct BlockCursor {
     db: DbConn,
+    /// The environment this block cursor is for.
+    environment: String,
     /// The hyperlane domain this block cursor is for.
     domain: u32,
+    /// The event stream this block cursor is for, e.g. `gas_payment`.
+    stream: &'static str,
     inner: RwLock<BlockCursorInner>,
 }
 
 impl BlockCursor {
-    async fn new(db: DbConn, domain: u32, default_height: u64) -> Result<Self> {
+    async fn new(
+        db: DbConn,
+        environment: String,
+        domain: u32,
+        stream: &'static str,
+        default_height: u64,
+    ) -> Result<Self> {
         #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
         enum QueryAs {
             Height,
         }
 
-        let height = (cursor::Entity::find())
-            .filter(cursor::Column::Domain.eq(domain))
-            .order_by(cursor::Column::Height, Order::Desc)
-            .select_only()
-            .column_as(cursor::Column::Height, QueryAs::Height)
-            .into_values::<i64, QueryAs>()
-            .one(&db)
+        let latest_height = |stream: Condition| {
+            (cursor::Entity::find())
+                .filter(cursor::Column::Domain.eq(domain))
+                .filter(cursor::Column::Environment.eq(environment.as_str()))
+                .filter(stream)
+                .order_by(cursor::Column::Height, Order::Desc)
+                .select_only()
+                .column_as(cursor::Column::Height, QueryAs::Height)
+                .into_values::<i64, QueryAs>()
+                .one(&db)
+        };
+        let mut height = latest_height(Condition::all().add(cursor::Column::Stream.eq(stream)))
             .await?
-            .map(|h| h as u64)
-            .unwrap_or(default_height);
+            .map(|h| h as u64);
+        if height.is_none() {
+            // Resume from the cursor shared by all the streams of the domain
+            // before they had their own
+            height = latest_height(Condition::all().add(cursor::Column::Stream.is_null()))
+                .await?
+                .map(|h| h as u64);
+        }
+        let height = height.unwrap_or(default_height);
         if height < default_height {
             warn!(
                 height,
@@ -58,7 +84,9 @@ impl BlockCursor {
         }
         Ok(Self {
             db,
+            environment,
             domain,
+            stream,
             inner: RwLock::new(BlockCursorInner {
                 height,
                 last_saved_at: Instant::now(),
@@ -88,6 +116,8 @@ impl BlockCursor {
                 domain: ActiveValue::Set(self.domain as i32),
                 time_created: ActiveValue::NotSet,
                 height: ActiveValue::Set(height as i64),
+                stream: ActiveValue::Set(Some(self.stream.to_owned())),
+                environment: ActiveValue::Set(self.environment.clone()),
             };
             debug!(?model, "Inserting cursor");
             if let Err(e) = Insert::one(model).exec(&self.db).await {
@@ -100,7 +130,19 @@ impl BlockCursor {
 }
 
 impl ScraperDb {
-    pub async fn block_cursor(&self, domain: u32, default_height: u64) -> Result<BlockCursor> {
-        BlockCursor::new(self.0.clone(), domain, default_height).await
+    pub async fn block_cursor(
+        &self,
+        domain: u32,
+        stream: &'static str,
+        default_height: u64,
+    ) -> Result<BlockCursor> {
+        BlockCursor::new(
+            self.conn.clone(),
+            self.environment.clone(),
+            domain,
+            stream,
+            default_height,
+        )
+        .await
     }
 }
@@ -0,0 +1,276 @@
+use eyre::Result;
+use sea_orm::{ConnectionTrait, DbBackend, Statement, TransactionTrait, Value};
+use serde::Deserialize;
+use tracing::{instrument, trace};
+
+use hyperlane_core::{AddressFormat, MerkleTreeInsertion, H256};
+
+use crate::conversions::{address_to_bytes, bytes_to_address, h256_to_bytes};
+use crate::db::ScraperDb;
+
+/// Most checkpoints returned by a single query
+pub const MAX_CHECKPOINTS_PER_QUERY: u32 = 1000;
+
+/// A leaf inserted into a merkle tree hook, with the tx it was inserted in
+#[derive(Debug, Clone)]
+pub struct StorableInsertion<'a> {
+    pub insertion: &'a MerkleTreeInsertion,
+    pub txn_id: i64,
+}
+
+/// A leaf of a merkle tree hook, as stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredLeaf {
+    pub index: u32,
+    pub message_id: H256,
+    /// Whether the root of the tree with this leaf was stored already
+    pub rooted: bool,
+}
+
+/// The root a merkle tree hook of an origin had once a leaf was inserted,
+/// i.e. the root of the checkpoint of that leaf's index
+#[derive(Debug, Clone)]
+pub struct CheckpointRoot {
+    pub merkle_tree_hook: H256,
+    pub index: u32,
+    pub root: H256,
+    pub message_id: H256,
+    pub block_number: u64,
+    /// Seconds since the unix epoch of the block the leaf was inserted in
+    pub block_timestamp: i64,
+}
+
+/// Which checkpoint roots to get, by leaf index
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CheckpointQuery {
+    pub origin: u32,
+    /// First leaf index
+    pub from: Option<u32>,
+    /// Last leaf index
+    pub to: Option<u32>,
+    /// Most checkpoints returned, at most `MAX_CHECKPOINTS_PER_QUERY`
+    pub limit: Option<u32>,
+}
+
+const INSERT_CHECKPOINT: &str = r#"
+    INSERT INTO "checkpoint"
+        ("domain", "merkle_tree_hook", "leaf_index", "msg_id", "tx_id", "environment")
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT ("environment", "domain", "merkle_tree_hook", "leaf_index") DO NOTHING
+"#;
+
+const UPDATE_CHECKPOINT_ROOT: &str = r#"
+    UPDATE "checkpoint" SET "root" = $4
+    WHERE "domain" = $1 AND "merkle_tree_hook" = $2 AND "leaf_index" = $3 AND "environment" = $5
+"#;
+
+impl ScraperDb {
+    /// Store the leaves inserted into a merkle tree hook. Their roots are
+    /// stored separately, once every leaf before them is. Returns the number
+    /// of leaves that weren't stored yet.
+    #[instrument(skip_all)]
+    pub async fn store_merkle_tree_insertions(
+        &self,
+        domain: u32,
+        merkle_tree_hook: &H256,
+        address_format: &AddressFormat,
+        insertions: impl Iterator<Item = StorableInsertion<'_>>,
+    ) -> Result<u64> {
+        let merkle_tree_hook = address_to_bytes(address_format, merkle_tree_hook);
+        let txn = self.conn.begin().await?;
+        let mut stored = 0;
+        for StorableInsertion { insertion, txn_id } in insertions {
+            stored += txn
+                .execute(Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    INSERT_CHECKPOINT,
+                    [
+                        (domain as i32).into(),
+                        merkle_tree_hook.clone().into(),
+                        (insertion.index() as i32).into(),
+                        h256_to_bytes(&insertion.message_id()).into(),
+                        txn_id.into(),
+                        self.environment.clone().into(),
+                    ],
+                ))
+                .await?
+                .rows_affected();
+        }
+        txn.commit().await?;
+        trace!(domain, stored, "Stored merkle tree insertions");
+        Ok(stored)
+    }
+
+    /// Get the leaf of a merkle tree hook at `leaf_index`, and the tx it was
+    /// inserted in
+    #[instrument(skip(self))]
+    pub async fn retrieve_merkle_tree_insertion(
+        &self,
+        domain: u32,
+        merkle_tree_hook: &H256,
+        address_format: &AddressFormat,
+        leaf_index: u32,
+    ) -> Result<Option<(MerkleTreeInsertion, i64)>> {
+        let row = self
+            .conn
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT "msg_id", "tx_id" FROM "checkpoint"
+                WHERE "domain" = $1 AND "merkle_tree_hook" = $2 AND "leaf_index" = $3
+                    AND "environment" = $4
+                "#,
+                [
+                    (domain as i32).into(),
+                    address_to_bytes(address_format, merkle_tree_hook).into(),
+                    (leaf_index as i32).into(),
+                    self.environment.clone().into(),
+                ],
+            ))
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let message_id: Vec<u8> = row.try_get("", "msg_id")?;
+        let tx_id: i64 = row.try_get("", "tx_id")?;
+        Ok(Some((
+            MerkleTreeInsertion::new(leaf_index, H256::from_slice(&message_id)),
+            tx_id,
+        )))
+    }
+
+    /// Get up to `limit` leaves of a merkle tree hook from `from_index` on,
+    /// by index. Leaves missing in between are skipped.
+    #[instrument(skip(self))]
+    pub async fn merkle_tree_leaves(
+        &self,
+        domain: u32,
+        merkle_tree_hook: &H256,
+        address_format: &AddressFormat,
+        from_index: u32,
+        limit: u32,
+    ) -> Result<Vec<StoredLeaf>> {
+        let rows = self
+            .conn
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT "leaf_index", "msg_id", "root" IS NOT NULL AS "rooted"
+                FROM "checkpoint"
+                WHERE "domain" = $1 AND "merkle_tree_hook" = $2 AND "leaf_index" >= $3
+                    AND "environment" = $5
+                ORDER BY "leaf_index"
+                LIMIT $4
+                "#,
+                [
+                    (domain as i32).into(),
+                    address_to_bytes(address_format, merkle_tree_hook).into(),
+                    (from_index as i32).into(),
+                    (limit as i64).into(),
+                    self.environment.clone().into(),
+                ],
+            ))
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                let message_id: Vec<u8> = row.try_get("", "msg_id")?;
+                Ok(StoredLeaf {
+                    index: row.try_get::<i32>("", "leaf_index")? as u32,
+                    message_id: H256::from_slice(&message_id),
+                    rooted: row.try_get("", "rooted")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Store the roots a merkle tree hook had once each of the leaves at the
+    /// given indices was inserted
+    #[instrument(skip_all)]
+    pub async fn store_checkpoint_roots(
+        &self,
+        domain: u32,
+        merkle_tree_hook: &H256,
+        address_format: &AddressFormat,
+        roots: &[(u32, H256)],
+    ) -> Result<()> {
+        if roots.is_empty() {
+            return Ok(());
+        }
+        let merkle_tree_hook = address_to_bytes(address_format, merkle_tree_hook);
+        let txn = self.conn.begin().await?;
+        for (index, root) in roots {
+            let values: Vec<Value> = vec![
+                (domain as i32).into(),
+                merkle_tree_hook.clone().into(),
+                (*index as i32).into(),
+                h256_to_bytes(root).into(),
+                self.environment.clone().into(),
+            ];
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                UPDATE_CHECKPOINT_ROOT,
+                values,
+            ))
+            .await?;
+        }
+        txn.commit().await?;
+        trace!(domain, count = roots.len(), "Stored checkpoint roots");
+        Ok(())
+    }
+
+    /// Get the checkpoint roots of an origin matching the query, by leaf
+    /// index
+    #[instrument(skip(self))]
+    pub async fn checkpoint_roots(&self, query: &CheckpointQuery) -> Result<Vec<CheckpointRoot>> {
+        let limit = query
+            .limit
+            .unwrap_or(MAX_CHECKPOINTS_PER_QUERY)
+            .min(MAX_CHECKPOINTS_PER_QUERY);
+        let rows = self
+            .conn
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    "checkpoint"."merkle_tree_hook",
+                    "checkpoint"."leaf_index",
+                    "checkpoint"."root",
+                    "checkpoint"."msg_id",
+                    "block"."height",
+                    EXTRACT(EPOCH FROM "block"."timestamp")::bigint AS "block_timestamp"
+                FROM "checkpoint"
+                    JOIN "transaction" ON "transaction"."id" = "checkpoint"."tx_id"
+                    JOIN "block" ON "block"."id" = "transaction"."block_id"
+                WHERE "checkpoint"."domain" = $1
+                    AND "checkpoint"."environment" = $5
+                    AND "checkpoint"."root" IS NOT NULL
+                    AND ($2::integer IS NULL OR "checkpoint"."leaf_index" >= $2)
+                    AND ($3::integer IS NULL OR "checkpoint"."leaf_index" <= $3)
+                ORDER BY "checkpoint"."leaf_index", "checkpoint"."merkle_tree_hook"
+                LIMIT $4
+                "#,
+                [
+                    (query.origin as i32).into(),
+                    query.from.map(|i| i as i32).into(),
+                    query.to.map(|i| i as i32).into(),
+                    (limit as i64).into(),
+                    self.environment.clone().into(),
+                ],
+            ))
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                let root: Vec<u8> = row.try_get("", "root")?;
+                let message_id: Vec<u8> = row.try_get("", "msg_id")?;
+                Ok(CheckpointRoot {
+                    merkle_tree_hook: bytes_to_address(row.try_get("", "merkle_tree_hook")?)?,
+                    index: row.try_get::<i32>("", "leaf_index")? as u32,
+                    root: H256::from_slice(&root),
+                    message_id: H256::from_slice(&message_id),
+                    block_number: row.try_get::<i64>("", "height")? as u64,
+                    block_timestamp: row.try_get("", "block_timestamp")?,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,63 @@
+use eyre::Result;
+use sea_orm::{ConnectionTrait, DbBackend, Statement, TransactionTrait};
+use tracing::{instrument, trace};
+
+use hyperlane_core::{DestinationGasUpdate, LogMeta};
+
+use crate::conversions::u256_to_decimal;
+use crate::db::ScraperDb;
+
+/// Gas data set in an origin's gas oracle, with the tx it was set in
+#[derive(Debug, Clone)]
+pub struct StorableDestinationGasUpdate<'a> {
+    pub update: &'a DestinationGasUpdate,
+    pub meta: &'a LogMeta,
+    pub txn_id: i64,
+}
+
+const INSERT_DESTINATION_GAS_UPDATE: &str = r#"
+    INSERT INTO "destination_gas_update"
+        ("domain", "destination", "token_exchange_rate", "gas_price", "tx_id", "log_index", "environment")
+    VALUES ($1, $2, $3, $4, $5, $6, $7)
+    ON CONFLICT ("environment", "tx_id", "log_index") DO NOTHING
+"#;
+
+impl ScraperDb {
+    /// Store the gas data set in the gas oracle of `domain` for its
+    /// destinations. Returns the number of updates that weren't stored yet.
+    #[instrument(skip_all)]
+    pub async fn store_destination_gas_updates(
+        &self,
+        domain: u32,
+        updates: impl Iterator<Item = StorableDestinationGasUpdate<'_>>,
+    ) -> Result<u64> {
+        let txn = self.conn.begin().await?;
+        let mut stored = 0;
+        for StorableDestinationGasUpdate {
+            update,
+            meta,
+            txn_id,
+        } in updates
+        {
+            stored += txn
+                .execute(Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    INSERT_DESTINATION_GAS_UPDATE,
+                    [
+                        (domain as i32).into(),
+                        (update.destination as i32).into(),
+                        u256_to_decimal(update.token_exchange_rate).into(),
+                        u256_to_decimal(update.gas_price).into(),
+                        txn_id.into(),
+                        (meta.log_index.as_u64() as i64).into(),
+                        self.environment.clone().into(),
+                    ],
+                ))
+                .await?
+                .rows_affected();
+        }
+        txn.commit().await?;
+        trace!(domain, stored, "Stored destination gas updates");
+        Ok(stored)
+    }
+}
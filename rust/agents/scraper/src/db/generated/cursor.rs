@@ -17,6 +17,8 @@ pub struct Model {
     pub domain: i32,
     pub time_created: TimeDateTime,
     pub height: i64,
+    pub stream: Option<String>,
+    pub environment: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -25,6 +27,8 @@ pub enum Column {
     Domain,
     TimeCreated,
     Height,
+    Stream,
+    Environment,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -52,6 +56,8 @@ impl ColumnTrait for Column {
             Self::Domain => ColumnType::Integer.def(),
             Self::TimeCreated => ColumnType::DateTime.def(),
             Self::Height => ColumnType::BigInteger.def(),
+            Self::Stream => ColumnType::Text.def().null(),
+            Self::Environment => ColumnType::Text.def(),
         }
     }
 }
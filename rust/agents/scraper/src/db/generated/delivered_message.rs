@@ -19,6 +19,7 @@ pub struct Model {
     pub domain: i32,
     pub destination_mailbox: Vec<u8>,
     pub destination_tx_id: i64,
+    pub environment: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -29,6 +30,7 @@ pub enum Column {
     Domain,
     DestinationMailbox,
     DestinationTxId,
+    Environment,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -55,10 +57,11 @@ impl ColumnTrait for Column {
         match self {
             Self::Id => ColumnType::BigInteger.def(),
             Self::TimeCreated => ColumnType::DateTime.def(),
-            Self::MsgId => ColumnType::Binary(BlobSize::Blob(None)).def().unique(),
+            Self::MsgId => ColumnType::Binary(BlobSize::Blob(None)).def(),
             Self::Domain => ColumnType::Integer.def(),
             Self::DestinationMailbox => ColumnType::Binary(BlobSize::Blob(None)).def(),
             Self::DestinationTxId => ColumnType::BigInteger.def(),
+            Self::Environment => ColumnType::Text.def(),
         }
     }
 }
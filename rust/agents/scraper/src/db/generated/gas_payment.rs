@@ -21,6 +21,7 @@ pub struct Model {
     pub gas_amount: BigDecimal,
     pub tx_id: i64,
     pub log_index: i64,
+    pub environment: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -33,6 +34,7 @@ pub enum Column {
     GasAmount,
     TxId,
     LogIndex,
+    Environment,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -65,6 +67,7 @@ impl ColumnTrait for Column {
             Self::GasAmount => ColumnType::Decimal(Some((78u32, 0u32))).def(),
             Self::TxId => ColumnType::BigInteger.def(),
             Self::LogIndex => ColumnType::BigInteger.def(),
+            Self::Environment => ColumnType::Text.def(),
         }
     }
 }
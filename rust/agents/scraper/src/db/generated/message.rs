@@ -24,6 +24,8 @@ pub struct Model {
     pub msg_body: Option<Vec<u8>>,
     pub origin_mailbox: Vec<u8>,
     pub origin_tx_id: i64,
+    pub version: i16,
+    pub environment: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -39,6 +41,8 @@ pub enum Column {
     MsgBody,
     OriginMailbox,
     OriginTxId,
+    Version,
+    Environment,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -74,6 +78,8 @@ impl ColumnTrait for Column {
             Self::MsgBody => ColumnType::Binary(BlobSize::Blob(None)).def().null(),
             Self::OriginMailbox => ColumnType::Binary(BlobSize::Blob(None)).def(),
             Self::OriginTxId => ColumnType::BigInteger.def(),
+            Self::Version => ColumnType::SmallInteger.def(),
+            Self::Environment => ColumnType::Text.def(),
         }
     }
 }
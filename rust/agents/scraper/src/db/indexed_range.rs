@@ -0,0 +1,245 @@
+use std::ops::RangeInclusive;
+
+use eyre::Result;
+use sea_orm::{ConnectionTrait, DbBackend, Statement, Value};
+use tracing::{instrument, trace};
+
+use crate::db::ScraperDb;
+
+/// Blocks missing between two ranges indexed for a stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockGap {
+    /// First missing block
+    pub from: u64,
+    /// Last missing block, inclusive
+    pub to: u64,
+}
+
+/// Extend a range the stored one overlaps or is next to, if any. Backward
+/// cursors store ranges below the ones they stored before.
+const EXTEND_INDEXED_RANGE: &str = r#"
+    UPDATE "indexed_range" SET
+        "start_block" = LEAST("start_block", $3),
+        "end_block" = GREATEST("end_block", $4),
+        "time_updated" = NOW()
+    WHERE "id" = (
+        SELECT "id" FROM "indexed_range"
+        WHERE "domain" = $1 AND "stream" = $2 AND "environment" = $5
+            AND "start_block" <= $4 + 1 AND "end_block" >= $3 - 1
+        ORDER BY "end_block" DESC
+        LIMIT 1
+    )
+"#;
+
+const INSERT_INDEXED_RANGE: &str = r#"
+    INSERT INTO "indexed_range" ("domain", "stream", "start_block", "end_block", "environment")
+    VALUES ($1, $2, $3, $4, $5)
+"#;
+
+/// The blocks between the end of the ranges starting before each range and
+/// its start, where there are any
+const FIND_INDEXED_RANGE_GAPS: &str = r#"
+    SELECT "r"."previous_end" + 1 AS "from_block", "r"."start_block" - 1 AS "to_block"
+    FROM (
+        SELECT
+            "start_block",
+            MAX("end_block") OVER (
+                ORDER BY "start_block"
+                ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+            ) AS "previous_end"
+        FROM "indexed_range"
+        WHERE "domain" = $1 AND "stream" = $2 AND "environment" = $4
+    ) AS "r"
+    WHERE "r"."start_block" > "r"."previous_end" + 1
+    ORDER BY "r"."start_block"
+    LIMIT $3
+"#;
+
+impl ScraperDb {
+    /// Record that the events of a stream were stored for every block of
+    /// `range`. Consecutive ranges are merged, so a cursor only ever extends
+    /// a single row.
+    #[instrument(skip(self))]
+    pub async fn store_indexed_range(
+        &self,
+        domain: u32,
+        stream: &str,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        let values = || -> Vec<Value> {
+            vec![
+                (domain as i32).into(),
+                stream.into(),
+                (*range.start() as i64).into(),
+                (*range.end() as i64).into(),
+                self.environment.clone().into(),
+            ]
+        };
+        let extended = self
+            .conn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                EXTEND_INDEXED_RANGE,
+                values(),
+            ))
+            .await?
+            .rows_affected();
+        if extended == 0 {
+            self.conn
+                .execute(Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    INSERT_INDEXED_RANGE,
+                    values(),
+                ))
+                .await?;
+        }
+        trace!(extended = extended > 0, "Stored indexed range");
+        Ok(())
+    }
+
+    /// Get the first `limit` holes between the ranges indexed for a stream.
+    /// Blocks before the first range recorded aren't a hole, so upgrading a
+    /// scraper doesn't take its whole history for one.
+    #[instrument(skip(self))]
+    pub async fn indexed_range_gaps(
+        &self,
+        domain: u32,
+        stream: &str,
+        limit: u32,
+    ) -> Result<Vec<BlockGap>> {
+        let values: Vec<Value> = vec![
+            (domain as i32).into(),
+            stream.into(),
+            (limit as i64).into(),
+            self.environment.clone().into(),
+        ];
+        let rows = self
+            .conn
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                FIND_INDEXED_RANGE_GAPS,
+                values,
+            ))
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(BlockGap {
+                    from: row.try_get::<i64>("", "from_block")? as u64,
+                    to: row.try_get::<i64>("", "to_block")? as u64,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use sea_orm::{MockDatabase, MockExecResult, Transaction};
+
+    use super::*;
+
+    fn scraper_db(db: MockDatabase) -> ScraperDb {
+        ScraperDb {
+            conn: db.into_connection(),
+            environment: "mainnet3".to_owned(),
+        }
+    }
+
+    fn range_values() -> Vec<Value> {
+        vec![
+            1i32.into(),
+            "message_delivery".into(),
+            10i64.into(),
+            20i64.into(),
+            "mainnet3".to_owned().into(),
+        ]
+    }
+
+    fn exec_result(rows_affected: u64) -> MockExecResult {
+        MockExecResult {
+            last_insert_id: 0,
+            rows_affected,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extends_indexed_range() {
+        let db = scraper_db(
+            MockDatabase::new(DbBackend::Postgres).append_exec_results(vec![exec_result(1)]),
+        );
+        db.store_indexed_range(1, "message_delivery", 10..=20)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.conn.into_transaction_log(),
+            vec![Transaction::from_sql_and_values(
+                DbBackend::Postgres,
+                EXTEND_INDEXED_RANGE,
+                range_values(),
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inserts_unconnected_indexed_range() {
+        let db = scraper_db(
+            MockDatabase::new(DbBackend::Postgres)
+                .append_exec_results(vec![exec_result(0), exec_result(1)]),
+        );
+        db.store_indexed_range(1, "message_delivery", 10..=20)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.conn.into_transaction_log(),
+            vec![
+                Transaction::from_sql_and_values(
+                    DbBackend::Postgres,
+                    EXTEND_INDEXED_RANGE,
+                    range_values(),
+                ),
+                Transaction::from_sql_and_values(
+                    DbBackend::Postgres,
+                    INSERT_INDEXED_RANGE,
+                    range_values(),
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_indexed_range_gaps() {
+        let gap = |from: i64, to: i64| {
+            BTreeMap::from([
+                ("from_block", Value::from(from)),
+                ("to_block", Value::from(to)),
+            ])
+        };
+        let db = scraper_db(
+            MockDatabase::new(DbBackend::Postgres)
+                .append_query_results(vec![vec![gap(21, 29), gap(51, 51)]]),
+        );
+        let gaps = db
+            .indexed_range_gaps(1, "message_delivery", 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            gaps,
+            vec![BlockGap { from: 21, to: 29 }, BlockGap { from: 51, to: 51 }]
+        );
+        assert_eq!(
+            db.conn.into_transaction_log(),
+            vec![Transaction::from_sql_and_values(
+                DbBackend::Postgres,
+                FIND_INDEXED_RANGE_GAPS,
+                vec![
+                    1i32.into(),
+                    "message_delivery".into(),
+                    10i64.into(),
+                    "mainnet3".to_owned().into(),
+                ],
+            )]
+        );
+    }
+}
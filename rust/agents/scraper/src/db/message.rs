@@ -1,9 +1,14 @@
+use std::time::Duration;
+
 use eyre::Result;
 use itertools::Itertools;
-use sea_orm::{prelude::*, ActiveValue::*, DeriveColumn, EnumIter, Insert, QuerySelect};
+use sea_orm::{
+    prelude::*, ActiveValue::*, DbBackend, DeriveColumn, EnumIter, Insert, QuerySelect, Statement,
+    TransactionTrait,
+};
 use tracing::{debug, instrument, trace};
 
-use hyperlane_core::{HyperlaneMessage, LogMeta, H256};
+use hyperlane_core::{AddressFormat, HyperlaneMessage, LogMeta, H256};
 use migration::OnConflict;
 
 use crate::conversions::{address_to_bytes, bytes_to_address, h256_to_bytes};
@@ -22,9 +27,78 @@ pub struct StorableDelivery<'a> {
 
 pub struct StorableMessage<'a> {
     pub msg: HyperlaneMessage,
+    /// The address format of the destination chain, for the recipient
+    pub recipient_format: AddressFormat,
     pub meta: &'a LogMeta,
     /// The database id of the transaction the message was sent in
     pub txn_id: i64,
+    /// The body decoded as JSON, if it is that of a known app
+    pub decoded_body: Option<serde_json::Value>,
+}
+
+/// The decoded body isn't part of the generated entity, as it is JSON
+const UPDATE_DECODED_BODIES: &str = r#"
+    UPDATE "message" AS "msg" SET "decoded_body" = "v"."body"::jsonb
+    FROM (VALUES {values}) AS "v"("nonce", "body")
+    WHERE "msg"."origin_mailbox" = $1
+        AND "msg"."origin" = $2
+        AND "msg"."environment" = $3
+        AND "msg"."nonce" = "v"."nonce"
+"#;
+
+/// Most decoded bodies set per statement, well within the parameters a
+/// Postgres statement can have
+const DECODED_BODIES_CHUNK_SIZE: usize = 1000;
+
+/// Gas prices are copied from the transactions messages were dispatched and
+/// delivered in, so costs can be queried without joining them. Prices that
+/// were stored already are kept, and the token price only values
+/// transactions of blocks from `$4` on.
+const UPDATE_DISPATCH_GAS_PRICE: &str = r#"
+    UPDATE "message" SET
+        "origin_gas_price" = COALESCE(
+            "message"."origin_gas_price", t."effective_gas_price", t."gas_price"
+        ),
+        "origin_token_usd" = COALESCE(
+            "message"."origin_token_usd",
+            CASE WHEN $4::timestamp IS NULL OR b."timestamp" >= $4::timestamp THEN $1::numeric END
+        )
+    FROM "transaction" AS t JOIN "block" AS b ON b."id" = t."block_id"
+    WHERE "message"."origin_tx_id" = $2 AND "message"."environment" = $3 AND t."id" = $2
+"#;
+const UPDATE_DELIVERY_GAS_PRICE: &str = r#"
+    UPDATE "delivered_message" SET
+        "destination_gas_price" = COALESCE(
+            "delivered_message"."destination_gas_price", t."effective_gas_price", t."gas_price"
+        ),
+        "destination_token_usd" = COALESCE(
+            "delivered_message"."destination_token_usd",
+            CASE WHEN $4::timestamp IS NULL OR b."timestamp" >= $4::timestamp THEN $1::numeric END
+        )
+    FROM "transaction" AS t JOIN "block" AS b ON b."id" = t."block_id"
+    WHERE "delivered_message"."destination_tx_id" = $2
+        AND "delivered_message"."environment" = $3
+        AND t."id" = $2
+"#;
+
+/// The USD price of a native token, valuing the gas of the transactions of
+/// blocks from `since` on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenUsd {
+    pub price: f64,
+    /// Oldest time of the blocks the price values, or None if it values
+    /// blocks of any age
+    pub since: Option<TimeDateTime>,
+}
+
+impl TokenUsd {
+    /// The current price, valuing blocks up to `max_block_age` old
+    pub fn current(price: f64, max_block_age: Option<Duration>) -> Self {
+        Self {
+            price,
+            since: max_block_age.map(|age| date_time::now() - age),
+        }
+    }
 }
 
 impl ScraperDb {
@@ -34,6 +108,7 @@ impl ScraperDb {
         &self,
         origin_domain: u32,
         origin_mailbox: &H256,
+        address_format: &AddressFormat,
     ) -> Result<Option<u32>> {
         #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
         enum QueryAs {
@@ -42,17 +117,20 @@ impl ScraperDb {
 
         let last_nonce = message::Entity::find()
             .filter(message::Column::Origin.eq(origin_domain))
-            .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
+            .filter(
+                message::Column::OriginMailbox.eq(address_to_bytes(address_format, origin_mailbox)),
+            )
+            .filter(message::Column::Environment.eq(self.environment.as_str()))
             .select_only()
             .column_as(message::Column::Nonce.max(), QueryAs::Nonce)
             .into_values::<i32, QueryAs>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?
             .map(|idx| idx as u32);
         debug!(
             ?last_nonce,
             origin_domain,
-            ?origin_mailbox,
+            origin_mailbox = %address_format.encode(origin_mailbox),
             "Queried last message nonce from database"
         );
         Ok(last_nonce)
@@ -64,6 +142,7 @@ impl ScraperDb {
         &self,
         origin_domain: u32,
         origin_mailbox: &H256,
+        address_format: &AddressFormat,
         nonce: u32,
     ) -> Result<Option<HyperlaneMessage>> {
         #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -72,32 +151,50 @@ impl ScraperDb {
         }
         if let Some(message) = message::Entity::find()
             .filter(message::Column::Origin.eq(origin_domain))
-            .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
+            .filter(
+                message::Column::OriginMailbox.eq(address_to_bytes(address_format, origin_mailbox)),
+            )
+            .filter(message::Column::Environment.eq(self.environment.as_str()))
             .filter(message::Column::Nonce.eq(nonce))
-            .one(&self.0)
+            .one(&self.conn)
             .await?
         {
-            Ok(Some(HyperlaneMessage {
-                // We do not write version to the DB.
-                version: 3,
-                origin: message.origin as u32,
-                destination: message.destination as u32,
-                nonce: message.nonce as u32,
-                sender: bytes_to_address(message.sender)?,
-                recipient: bytes_to_address(message.recipient)?,
-                body: message.msg_body.unwrap_or(Vec::new()),
-            }))
+            Ok(Some(model_to_message(message)?))
         } else {
             Ok(None)
         }
     }
 
+    /// Get a message by its id, and whether it was delivered.
+    #[instrument(skip(self))]
+    pub async fn retrieve_message_by_id(
+        &self,
+        id: &H256,
+    ) -> Result<Option<(HyperlaneMessage, bool)>> {
+        let Some(message) = message::Entity::find()
+            .filter(message::Column::MsgId.eq(h256_to_bytes(id)))
+            .filter(message::Column::Environment.eq(self.environment.as_str()))
+            .one(&self.conn)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let delivered = delivered_message::Entity::find()
+            .filter(delivered_message::Column::MsgId.eq(h256_to_bytes(id)))
+            .filter(delivered_message::Column::Environment.eq(self.environment.as_str()))
+            .count(&self.conn)
+            .await?
+            > 0;
+        Ok(Some((model_to_message(message)?, delivered)))
+    }
+
     /// Get the tx id associated with a dispatched message.
     #[instrument(skip(self))]
     pub async fn retrieve_dispatched_tx_id(
         &self,
         origin_domain: u32,
         origin_mailbox: &H256,
+        address_format: &AddressFormat,
         nonce: u32,
     ) -> Result<Option<i64>> {
         #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -107,13 +204,16 @@ impl ScraperDb {
 
         let tx_id = message::Entity::find()
             .filter(message::Column::Origin.eq(origin_domain))
-            .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
+            .filter(
+                message::Column::OriginMailbox.eq(address_to_bytes(address_format, origin_mailbox)),
+            )
+            .filter(message::Column::Environment.eq(self.environment.as_str()))
             .filter(message::Column::Nonce.eq(nonce))
             .select_only()
             .column_as(message::Column::OriginTxId.max(), QueryAs::Nonce)
             .group_by(message::Column::Origin)
             .into_values::<i64, QueryAs>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
         Ok(tx_id)
     }
@@ -122,7 +222,8 @@ impl ScraperDb {
         Ok(delivered_message::Entity::find()
             .filter(delivered_message::Column::Domain.eq(domain))
             .filter(delivered_message::Column::DestinationMailbox.eq(destination_mailbox.clone()))
-            .count(&self.0)
+            .filter(delivered_message::Column::Environment.eq(self.environment.as_str()))
+            .count(&self.conn)
             .await?)
     }
 
@@ -133,23 +234,29 @@ impl ScraperDb {
         &self,
         domain: u32,
         destination_mailbox: H256,
+        address_format: &AddressFormat,
         deliveries: impl Iterator<Item = StorableDelivery<'_>>,
     ) -> Result<u64> {
-        let destination_mailbox = address_to_bytes(&destination_mailbox);
+        let destination_mailbox = address_to_bytes(address_format, &destination_mailbox);
         let deliveries_count_before = self
             .deliveries_count(domain, destination_mailbox.clone())
             .await?;
         // we have a race condition where a message may not have been scraped yet even
         // though we have received news of delivery on this chain, so the
         // message IDs are looked up in a separate "thread".
+        let mut message_ids = Vec::new();
         let models: Vec<delivered_message::ActiveModel> = deliveries
-            .map(|delivery| delivered_message::ActiveModel {
-                id: NotSet,
-                time_created: Set(date_time::now()),
-                msg_id: Unchanged(h256_to_bytes(&delivery.message_id)),
-                domain: Unchanged(domain as i32),
-                destination_mailbox: Unchanged(destination_mailbox.clone()),
-                destination_tx_id: Set(delivery.txn_id),
+            .map(|delivery| {
+                message_ids.push(delivery.message_id);
+                delivered_message::ActiveModel {
+                    id: NotSet,
+                    time_created: Set(date_time::now()),
+                    msg_id: Unchanged(h256_to_bytes(&delivery.message_id)),
+                    domain: Unchanged(domain as i32),
+                    destination_mailbox: Unchanged(destination_mailbox.clone()),
+                    destination_tx_id: Set(delivery.txn_id),
+                    environment: Set(self.environment.clone()),
+                }
             })
             .collect_vec();
 
@@ -158,15 +265,19 @@ impl ScraperDb {
 
         Insert::many(models)
             .on_conflict(
-                OnConflict::columns([delivered_message::Column::MsgId])
-                    .update_columns([
-                        delivered_message::Column::TimeCreated,
-                        delivered_message::Column::DestinationTxId,
-                    ])
-                    .to_owned(),
+                OnConflict::columns([
+                    delivered_message::Column::Environment,
+                    delivered_message::Column::MsgId,
+                ])
+                .update_columns([
+                    delivered_message::Column::TimeCreated,
+                    delivered_message::Column::DestinationTxId,
+                ])
+                .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await?;
+        self.refresh_daily_stats(message_ids.into_iter()).await?;
         let deliveries_count_after = self.deliveries_count(domain, destination_mailbox).await?;
         let difference = deliveries_count_after.saturating_sub(deliveries_count_before);
         if difference > 0 {
@@ -182,7 +293,8 @@ impl ScraperDb {
         Ok(message::Entity::find()
             .filter(message::Column::Origin.eq(domain))
             .filter(message::Column::OriginMailbox.eq(origin_mailbox))
-            .count(&self.0)
+            .filter(message::Column::Environment.eq(self.environment.as_str()))
+            .count(&self.conn)
             .await?)
     }
 
@@ -193,30 +305,45 @@ impl ScraperDb {
         &self,
         domain: u32,
         origin_mailbox: &H256,
+        address_format: &AddressFormat,
         messages: impl Iterator<Item = StorableMessage<'_>>,
     ) -> Result<u64> {
-        let origin_mailbox = address_to_bytes(origin_mailbox);
+        let origin_mailbox = address_to_bytes(address_format, origin_mailbox);
         let messages_count_before = self
             .dispatched_messages_count(domain, origin_mailbox.clone())
             .await?;
         // we have a race condition where a message may not have been scraped yet even
+        let mut message_ids = Vec::new();
+        let mut decoded_bodies = Vec::new();
         let models = messages
-            .map(|storable| message::ActiveModel {
-                id: NotSet,
-                time_created: Set(date_time::now()),
-                msg_id: Unchanged(h256_to_bytes(&storable.msg.id())),
-                origin: Unchanged(storable.msg.origin as i32),
-                destination: Set(storable.msg.destination as i32),
-                nonce: Unchanged(storable.msg.nonce as i32),
-                sender: Set(address_to_bytes(&storable.msg.sender)),
-                recipient: Set(address_to_bytes(&storable.msg.recipient)),
-                msg_body: Set(if storable.msg.body.is_empty() {
-                    None
-                } else {
-                    Some(storable.msg.body)
-                }),
-                origin_mailbox: Unchanged(origin_mailbox.clone()),
-                origin_tx_id: Set(storable.txn_id),
+            .map(|storable| {
+                let message_id = storable.msg.id();
+                message_ids.push(message_id);
+                if let Some(decoded_body) = storable.decoded_body {
+                    decoded_bodies.push((storable.msg.nonce, decoded_body));
+                }
+                message::ActiveModel {
+                    id: NotSet,
+                    time_created: Set(date_time::now()),
+                    msg_id: Unchanged(h256_to_bytes(&message_id)),
+                    origin: Unchanged(storable.msg.origin as i32),
+                    destination: Set(storable.msg.destination as i32),
+                    nonce: Unchanged(storable.msg.nonce as i32),
+                    sender: Set(address_to_bytes(address_format, &storable.msg.sender)),
+                    recipient: Set(address_to_bytes(
+                        &storable.recipient_format,
+                        &storable.msg.recipient,
+                    )),
+                    msg_body: Set(if storable.msg.body.is_empty() {
+                        None
+                    } else {
+                        Some(storable.msg.body)
+                    }),
+                    origin_mailbox: Unchanged(origin_mailbox.clone()),
+                    origin_tx_id: Set(storable.txn_id),
+                    version: Set(storable.msg.version as i16),
+                    environment: Set(self.environment.clone()),
+                }
             })
             .collect_vec();
 
@@ -226,6 +353,7 @@ impl ScraperDb {
         Insert::many(models)
             .on_conflict(
                 OnConflict::columns([
+                    message::Column::Environment,
                     message::Column::OriginMailbox,
                     message::Column::Origin,
                     message::Column::Nonce,
@@ -237,11 +365,15 @@ impl ScraperDb {
                     message::Column::Recipient,
                     message::Column::MsgBody,
                     message::Column::OriginTxId,
+                    message::Column::Version,
                 ])
                 .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await?;
+        self.store_decoded_bodies(domain, &origin_mailbox, decoded_bodies)
+            .await?;
+        self.refresh_daily_stats(message_ids.into_iter()).await?;
         let messages_count_after = self
             .dispatched_messages_count(domain, origin_mailbox)
             .await?;
@@ -251,4 +383,163 @@ impl ScraperDb {
         }
         Ok(difference)
     }
+
+    /// Set the gas price of the messages dispatched in each of the
+    /// transactions, and the USD price of the origin's native token if known.
+    /// Prices already set are kept.
+    pub async fn store_dispatch_gas_prices(
+        &self,
+        txn_ids: impl Iterator<Item = i64>,
+        token_usd: Option<TokenUsd>,
+    ) -> Result<()> {
+        self.store_gas_prices(UPDATE_DISPATCH_GAS_PRICE, txn_ids, token_usd)
+            .await
+    }
+
+    /// Set the gas price of the deliveries made in each of the transactions,
+    /// and the USD price of the destination's native token if known. Prices
+    /// already set are kept.
+    pub async fn store_delivery_gas_prices(
+        &self,
+        txn_ids: impl Iterator<Item = i64>,
+        token_usd: Option<TokenUsd>,
+    ) -> Result<()> {
+        self.store_gas_prices(UPDATE_DELIVERY_GAS_PRICE, txn_ids, token_usd)
+            .await
+    }
+
+    async fn store_gas_prices(
+        &self,
+        update: &str,
+        txn_ids: impl Iterator<Item = i64>,
+        token_usd: Option<TokenUsd>,
+    ) -> Result<()> {
+        let txn = self.conn.begin().await?;
+        for txn_id in txn_ids.unique() {
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                update,
+                [
+                    token_usd.map(|token_usd| token_usd.price).into(),
+                    txn_id.into(),
+                    self.environment.clone().into(),
+                    token_usd.and_then(|token_usd| token_usd.since).into(),
+                ],
+            ))
+            .await?;
+        }
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Set the decoded bodies of stored messages, by nonce
+    async fn store_decoded_bodies(
+        &self,
+        domain: u32,
+        origin_mailbox: &[u8],
+        decoded_bodies: Vec<(u32, serde_json::Value)>,
+    ) -> Result<()> {
+        if decoded_bodies.is_empty() {
+            return Ok(());
+        }
+        let txn = self.conn.begin().await?;
+        for chunk in decoded_bodies.chunks(DECODED_BODIES_CHUNK_SIZE) {
+            txn.execute(update_decoded_bodies(
+                domain,
+                origin_mailbox,
+                &self.environment,
+                chunk,
+            ))
+            .await?;
+        }
+        txn.commit().await?;
+        Ok(())
+    }
+}
+
+/// The statement setting the decoded bodies of the messages with the given
+/// nonces
+fn update_decoded_bodies(
+    domain: u32,
+    origin_mailbox: &[u8],
+    environment: &str,
+    decoded_bodies: &[(u32, serde_json::Value)],
+) -> Statement {
+    let mut values: Vec<Value> = vec![
+        origin_mailbox.to_vec().into(),
+        (domain as i32).into(),
+        environment.to_owned().into(),
+    ];
+    let mut rows = Vec::with_capacity(decoded_bodies.len());
+    for (nonce, decoded_body) in decoded_bodies {
+        rows.push(format!(
+            "(${}::integer, ${}::text)",
+            values.len() + 1,
+            values.len() + 2
+        ));
+        values.push((*nonce as i32).into());
+        values.push(decoded_body.to_string().into());
+    }
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        &UPDATE_DECODED_BODIES.replace("{values}", &rows.join(", ")),
+        values,
+    )
+}
+
+fn model_to_message(message: message::Model) -> Result<HyperlaneMessage> {
+    Ok(HyperlaneMessage {
+        version: message.version as u8,
+        origin: message.origin as u32,
+        destination: message.destination as u32,
+        nonce: message.nonce as u32,
+        sender: bytes_to_address(message.sender)?,
+        recipient: bytes_to_address(message.recipient)?,
+        body: message.msg_body.unwrap_or(Vec::new()),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_update_decoded_bodies() {
+        let statement = update_decoded_bodies(
+            1,
+            &[0xab],
+            "mainnet3",
+            &[(4, json!({ "app": "a" })), (7, json!({ "app": "b" }))],
+        );
+        assert!(statement
+            .sql
+            .contains(r#"(VALUES ($4::integer, $5::text), ($6::integer, $7::text))"#));
+        assert_eq!(
+            statement.values.unwrap().0,
+            vec![
+                Value::from(vec![0xabu8]),
+                Value::from(1i32),
+                Value::from("mainnet3".to_owned()),
+                Value::from(4i32),
+                Value::from(r#"{"app":"a"}"#.to_owned()),
+                Value::from(7i32),
+                Value::from(r#"{"app":"b"}"#.to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_current_token_usd_values_recent_blocks() {
+        let before = date_time::now();
+        let token_usd = TokenUsd::current(2.5, Some(Duration::from_secs(300)));
+        let after = date_time::now();
+        assert_eq!(token_usd.price, 2.5);
+        let since = token_usd.since.unwrap();
+        assert!(since >= before - Duration::from_secs(300));
+        assert!(since <= after - Duration::from_secs(300));
+
+        assert_eq!(TokenUsd::current(2.5, None).since, None);
+    }
 }
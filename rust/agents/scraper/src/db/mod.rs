@@ -1,11 +1,18 @@
 pub use block::*;
 pub use block_cursor::BlockCursor;
+pub use checkpoint::*;
+pub use destination_gas::*;
 use eyre::Result;
+pub use indexed_range::*;
 pub use message::*;
+use migration::{Migrator, MigratorTrait};
 pub use payment::*;
+pub use reconciliation::*;
 use sea_orm::{Database, DbConn};
+pub use stats::*;
 use tracing::instrument;
 pub use txn::*;
+pub use undelivered::*;
 
 #[allow(clippy::all)]
 mod generated;
@@ -13,19 +20,46 @@ mod generated;
 // These modules implement additional functionality for the ScraperDb
 mod block;
 mod block_cursor;
+mod checkpoint;
+mod destination_gas;
+mod indexed_range;
 mod message;
 mod payment;
+mod reconciliation;
+mod stats;
 mod txn;
+mod undelivered;
 
 /// Database interface to the message explorer database for the scraper. This is
 /// focused on writing data to the database.
+///
+/// Several deployments can share a database, each scraped into its own
+/// environment. Chain data, i.e. domains, blocks and transactions, is shared,
+/// while the rows of a deployment are only read and written in its
+/// environment.
 #[derive(Clone, Debug)]
-pub struct ScraperDb(DbConn);
+pub struct ScraperDb {
+    conn: DbConn,
+    environment: String,
+}
 
 impl ScraperDb {
     #[instrument]
-    pub async fn connect(url: &str) -> Result<Self> {
-        let db = Database::connect(url).await?;
-        Ok(Self(db))
+    pub async fn connect(url: &str, environment: &str) -> Result<Self> {
+        let conn = Database::connect(url).await?;
+        Ok(Self {
+            conn,
+            environment: environment.to_owned(),
+        })
+    }
+
+    /// Names of the migrations the database is missing. The scraper doesn't
+    /// apply them itself, `init-db` does.
+    pub async fn pending_migrations(&self) -> Result<Vec<String>> {
+        Ok(Migrator::get_pending_migrations(&self.conn)
+            .await?
+            .iter()
+            .map(|migration| migration.name().to_owned())
+            .collect())
     }
 }
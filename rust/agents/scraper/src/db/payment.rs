@@ -38,6 +38,7 @@ impl ScraperDb {
                 gas_amount: Set(u256_to_decimal(storable.payment.gas_amount)),
                 tx_id: Unchanged(storable.txn_id),
                 log_index: Unchanged(storable.meta.log_index.as_u64() as i64),
+                environment: Set(self.environment.clone()),
             })
             .collect_vec();
 
@@ -48,6 +49,7 @@ impl ScraperDb {
             .on_conflict(
                 OnConflict::columns([
                     // don't need domain because TxId includes it
+                    gas_payment::Column::Environment,
                     gas_payment::Column::MsgId,
                     gas_payment::Column::TxId,
                     gas_payment::Column::LogIndex,
@@ -59,7 +61,7 @@ impl ScraperDb {
                 ])
                 .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await?;
         let payment_count_after = self.payments_count(domain).await?;
         let difference = payment_count_after.saturating_sub(payment_count_before);
@@ -72,7 +74,8 @@ impl ScraperDb {
     async fn payments_count(&self, domain: u32) -> Result<u64> {
         Ok(gas_payment::Entity::find()
             .filter(gas_payment::Column::Domain.eq(domain))
-            .count(&self.0)
+            .filter(gas_payment::Column::Environment.eq(self.environment.as_str()))
+            .count(&self.conn)
             .await?)
     }
 }
@@ -0,0 +1,141 @@
+use std::{collections::HashMap, time::Duration};
+
+use eyre::Result;
+use itertools::Itertools;
+use sea_orm::{ConnectionTrait, DbBackend, Statement, Value};
+use tracing::instrument;
+
+use hyperlane_core::{AddressFormat, H256};
+
+use crate::conversions::address_to_bytes;
+use crate::db::ScraperDb;
+
+/// Deliveries without their message, whose message was paid for from one of
+/// the `{origins}`
+const UNMATCHED_DELIVERIES: &str = r#"
+    SELECT "delivered_message"."domain", COUNT(*) AS "unmatched"
+    FROM "delivered_message"
+        LEFT JOIN "message" ON "message"."msg_id" = "delivered_message"."msg_id"
+            AND "message"."environment" = "delivered_message"."environment"
+    WHERE "message"."id" IS NULL
+        AND "delivered_message"."environment" = $2
+        AND "delivered_message"."time_created" < NOW() - make_interval(secs => $1)
+        AND EXISTS (
+            SELECT 1 FROM "gas_payment"
+            WHERE "gas_payment"."msg_id" = "delivered_message"."msg_id"
+                AND "gas_payment"."environment" = "delivered_message"."environment"
+                AND "gas_payment"."domain" IN ({origins})
+        )
+    GROUP BY "delivered_message"."domain"
+"#;
+
+/// Missing nonces between two messages stored from a mailbox
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceGap {
+    /// Nonce of the message stored before the gap
+    pub before: u32,
+    /// Nonce of the message stored after the gap
+    pub after: u32,
+    /// Height of the block the message before the gap was dispatched in
+    pub from_block: u64,
+    /// Height of the block the message after the gap was dispatched in
+    pub to_block: u64,
+}
+
+impl ScraperDb {
+    /// Count the deliveries stored more than `grace_period` ago whose message
+    /// isn't stored although it was dispatched from one of the `origins`, by
+    /// destination domain. Deliveries can be scraped before their messages, so
+    /// the recent ones aren't counted.
+    ///
+    /// Deliveries don't record the origin of their message, so it is only
+    /// known from the gas payments scraped for it. Deliveries without one may
+    /// be of messages from chains that aren't scraped, so aren't counted.
+    #[instrument(skip(self))]
+    pub async fn unmatched_deliveries(
+        &self,
+        origins: &[u32],
+        grace_period: Duration,
+    ) -> Result<HashMap<u32, u64>> {
+        if origins.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let origins = origins.iter().map(u32::to_string).join(", ");
+        let rows = self
+            .conn
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &UNMATCHED_DELIVERIES.replace("{origins}", &origins),
+                [
+                    (grace_period.as_secs() as f64).into(),
+                    self.environment.clone().into(),
+                ],
+            ))
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                let domain: i32 = row.try_get("", "domain")?;
+                let unmatched: i64 = row.try_get("", "unmatched")?;
+                Ok((domain as u32, unmatched as u64))
+            })
+            .collect()
+    }
+
+    /// Get the first `limit` gaps in the nonces of the messages stored from a
+    /// mailbox, with the blocks the messages around them were dispatched in.
+    #[instrument(skip(self))]
+    pub async fn message_nonce_gaps(
+        &self,
+        origin_domain: u32,
+        origin_mailbox: &H256,
+        address_format: &AddressFormat,
+        limit: u32,
+    ) -> Result<Vec<NonceGap>> {
+        let values: Vec<Value> = vec![
+            (origin_domain as i32).into(),
+            address_to_bytes(address_format, origin_mailbox).into(),
+            (limit as i64).into(),
+            self.environment.clone().into(),
+        ];
+        let rows = self
+            .conn
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    "m"."nonce",
+                    "m"."next_nonce",
+                    "block"."height" AS "from_block",
+                    "next_block"."height" AS "to_block"
+                FROM (
+                    SELECT
+                        "nonce",
+                        "origin_tx_id",
+                        LEAD("nonce") OVER (ORDER BY "nonce") AS "next_nonce",
+                        LEAD("origin_tx_id") OVER (ORDER BY "nonce") AS "next_tx_id"
+                    FROM "message"
+                    WHERE "origin" = $1 AND "origin_mailbox" = $2 AND "environment" = $4
+                ) AS "m"
+                    JOIN "transaction" ON "transaction"."id" = "m"."origin_tx_id"
+                    JOIN "block" ON "block"."id" = "transaction"."block_id"
+                    JOIN "transaction" AS "next_tx" ON "next_tx"."id" = "m"."next_tx_id"
+                    JOIN "block" AS "next_block" ON "next_block"."id" = "next_tx"."block_id"
+                WHERE "m"."next_nonce" > "m"."nonce" + 1
+                ORDER BY "m"."nonce"
+                LIMIT $3
+                "#,
+                values,
+            ))
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(NonceGap {
+                    before: row.try_get::<i32>("", "nonce")? as u32,
+                    after: row.try_get::<i32>("", "next_nonce")? as u32,
+                    from_block: row.try_get::<i64>("", "from_block")? as u64,
+                    to_block: row.try_get::<i64>("", "to_block")? as u64,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,234 @@
+use eyre::Result;
+use itertools::Itertools;
+use sea_orm::{ConnectionTrait, DbBackend, Statement, TransactionTrait, Value};
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+
+use hyperlane_core::H256;
+
+use crate::conversions::h256_to_bytes;
+use crate::db::ScraperDb;
+
+/// Messages dispatched from a domain to another on a day, and how many of
+/// them were delivered
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyStats {
+    /// Day the messages were dispatched on, as `YYYY-MM-DD` in UTC
+    pub day: String,
+    pub origin: u32,
+    pub destination: u32,
+    pub dispatched: u64,
+    pub delivered: u64,
+    /// Average seconds from the dispatch of the delivered messages to their
+    /// delivery
+    pub average_latency_secs: Option<f64>,
+}
+
+/// Which daily statistics to get, every bound being optional
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DailyStatsQuery {
+    pub origin: Option<u32>,
+    pub destination: Option<u32>,
+    /// First day, as `YYYY-MM-DD`
+    pub from: Option<String>,
+    /// Last day, as `YYYY-MM-DD`
+    pub to: Option<String>,
+}
+
+/// Count the dispatch of the messages `{ids}` that aren't counted yet in
+/// the stats of their day and route. Messages are marked as counted as they
+/// are, so concurrent refreshes never count a message twice.
+const COUNT_DISPATCHED: &str = r#"
+    WITH "counted" AS (
+        UPDATE "message" AS "msg" SET "stats_dispatched" = TRUE
+        FROM "transaction" AS "origin_tx", "block" AS "origin_block"
+        WHERE "msg"."msg_id" IN ({ids})
+            AND "msg"."environment" = {environment}
+            AND NOT "msg"."stats_dispatched"
+            AND "origin_tx"."id" = "msg"."origin_tx_id"
+            AND "origin_block"."id" = "origin_tx"."block_id"
+        RETURNING
+            DATE_TRUNC('day', "origin_block"."timestamp")::date AS "day",
+            "msg"."origin",
+            "msg"."destination"
+    )
+    INSERT INTO "message_stats_daily" ("environment", "day", "origin", "destination", "dispatched")
+    SELECT {environment}, "day", "origin", "destination", COUNT(*)
+    FROM "counted"
+    GROUP BY "day", "origin", "destination"
+    ON CONFLICT ("environment", "day", "origin", "destination") DO UPDATE SET
+        "dispatched" = "message_stats_daily"."dispatched" + EXCLUDED."dispatched",
+        "time_updated" = NOW()
+"#;
+
+/// Count the delivery of the messages `{ids}` that are delivered but aren't
+/// counted as such yet, with how long the deliveries took
+const COUNT_DELIVERED: &str = r#"
+    WITH "counted" AS (
+        UPDATE "message" AS "msg" SET "stats_delivered" = TRUE
+        FROM
+            "transaction" AS "origin_tx",
+            "block" AS "origin_block",
+            "delivered_message" AS "dmsg",
+            "transaction" AS "dest_tx",
+            "block" AS "dest_block"
+        WHERE "msg"."msg_id" IN ({ids})
+            AND "msg"."environment" = {environment}
+            AND NOT "msg"."stats_delivered"
+            AND "origin_tx"."id" = "msg"."origin_tx_id"
+            AND "origin_block"."id" = "origin_tx"."block_id"
+            AND "dmsg"."msg_id" = "msg"."msg_id"
+            AND "dmsg"."environment" = "msg"."environment"
+            AND "dest_tx"."id" = "dmsg"."destination_tx_id"
+            AND "dest_block"."id" = "dest_tx"."block_id"
+        RETURNING
+            DATE_TRUNC('day', "origin_block"."timestamp")::date AS "day",
+            "msg"."origin",
+            "msg"."destination",
+            EXTRACT(EPOCH FROM "dest_block"."timestamp" - "origin_block"."timestamp") AS "latency"
+    )
+    INSERT INTO "message_stats_daily"
+        ("environment", "day", "origin", "destination", "delivered", "total_latency_secs")
+    SELECT {environment}, "day", "origin", "destination", COUNT(*), SUM("latency")::bigint
+    FROM "counted"
+    GROUP BY "day", "origin", "destination"
+    ON CONFLICT ("environment", "day", "origin", "destination") DO UPDATE SET
+        "delivered" = "message_stats_daily"."delivered" + EXCLUDED."delivered",
+        "total_latency_secs" =
+            "message_stats_daily"."total_latency_secs" + EXCLUDED."total_latency_secs",
+        "time_updated" = NOW()
+"#;
+
+/// Lock the rows of the messages `{ids}` in the order of their ids, so that
+/// refreshes of the same messages, e.g. from the origin and the destination
+/// scrapers, don't deadlock
+const LOCK_MESSAGES: &str = r#"
+    SELECT "id" FROM "message"
+    WHERE "msg_id" IN ({ids}) AND "environment" = {environment}
+    ORDER BY "id"
+    FOR UPDATE
+"#;
+
+/// The statements counting the messages with the given ids in their daily
+/// stats, in the order they are run in, and their values
+fn count_statements(message_ids: &[H256], environment: &str) -> Vec<Statement> {
+    let mut values: Vec<Value> = message_ids
+        .iter()
+        .map(|id| h256_to_bytes(id).into())
+        .collect();
+    let ids = (1..=values.len()).map(|i| format!("${i}")).join(", ");
+    let environment_placeholder = format!("${}", values.len() + 1);
+    values.push(environment.to_owned().into());
+    [LOCK_MESSAGES, COUNT_DISPATCHED, COUNT_DELIVERED]
+        .into_iter()
+        .map(|sql| {
+            Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &sql.replace("{ids}", &ids)
+                    .replace("{environment}", &environment_placeholder),
+                values.clone(),
+            )
+        })
+        .collect()
+}
+
+impl ScraperDb {
+    /// Count the messages that were just stored or delivered in the daily
+    /// statistics of their days and routes. Messages are only counted once
+    /// they are stored, and deliveries once both the message and the
+    /// delivery are.
+    #[instrument(skip_all)]
+    pub async fn refresh_daily_stats(&self, message_ids: impl Iterator<Item = H256>) -> Result<()> {
+        let message_ids: Vec<H256> = message_ids.collect();
+        if message_ids.is_empty() {
+            return Ok(());
+        }
+        trace!(count = message_ids.len(), "Refreshing daily stats");
+        let txn = self.conn.begin().await?;
+        for statement in count_statements(&message_ids, &self.environment) {
+            txn.execute(statement).await?;
+        }
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Get the daily statistics matching the query, by day and route
+    #[instrument(skip(self))]
+    pub async fn daily_stats(&self, query: &DailyStatsQuery) -> Result<Vec<DailyStats>> {
+        let rows = self
+            .conn
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    "day"::text AS "day",
+                    "origin",
+                    "destination",
+                    "dispatched",
+                    "delivered",
+                    "total_latency_secs"
+                FROM "message_stats_daily"
+                WHERE "environment" = $5
+                    AND ($1::integer IS NULL OR "origin" = $1)
+                    AND ($2::integer IS NULL OR "destination" = $2)
+                    AND ($3::date IS NULL OR "day" >= $3::date)
+                    AND ($4::date IS NULL OR "day" <= $4::date)
+                ORDER BY "day", "origin", "destination"
+                "#,
+                [
+                    query.origin.map(|d| d as i32).into(),
+                    query.destination.map(|d| d as i32).into(),
+                    query.from.clone().into(),
+                    query.to.clone().into(),
+                    self.environment.clone().into(),
+                ],
+            ))
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                let delivered: i64 = row.try_get("", "delivered")?;
+                let total_latency_secs: i64 = row.try_get("", "total_latency_secs")?;
+                Ok(DailyStats {
+                    day: row.try_get("", "day")?,
+                    origin: row.try_get::<i32>("", "origin")? as u32,
+                    destination: row.try_get::<i32>("", "destination")? as u32,
+                    dispatched: row.try_get::<i64>("", "dispatched")? as u64,
+                    delivered: delivered as u64,
+                    average_latency_secs: (delivered > 0)
+                        .then(|| total_latency_secs as f64 / delivered as f64),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_count_statements() {
+        let ids = [H256::repeat_byte(1), H256::repeat_byte(2)];
+        let statements = count_statements(&ids, "mainnet3");
+        assert_eq!(statements.len(), 3);
+        for statement in &statements {
+            assert!(statement.sql.contains(r#""msg_id" IN ($1, $2)"#));
+            assert!(statement.sql.contains(r#""environment" = $3"#));
+            assert!(!statement.sql.contains('{'));
+            let values = &statement.values.as_ref().unwrap().0;
+            assert_eq!(
+                values,
+                &vec![
+                    Value::from(h256_to_bytes(&ids[0])),
+                    Value::from(h256_to_bytes(&ids[1])),
+                    Value::from("mainnet3".to_owned()),
+                ]
+            );
+        }
+        assert!(statements[0].sql.contains("FOR UPDATE"));
+        assert!(statements[1]
+            .sql
+            .contains(r#"NOT "msg"."stats_dispatched""#));
+        assert!(statements[2].sql.contains(r#"NOT "msg"."stats_delivered""#));
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use derive_more::Deref;
 use eyre::{eyre, Context, Result};
-use hyperlane_core::{TxnInfo, H256};
+use hyperlane_core::{AddressFormat, TxnInfo, H256};
 use sea_orm::{
     prelude::*, sea_query::OnConflict, ActiveValue::*, DeriveColumn, EnumIter, Insert, NotSet,
     QuerySelect,
@@ -34,7 +34,7 @@ impl ScraperDb {
             .select_only()
             .column_as(transaction::Column::BlockId, QueryAs::BlockId)
             .into_values::<i64, QueryAs>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
         Ok(block_id)
     }
@@ -58,7 +58,7 @@ impl ScraperDb {
             .column_as(transaction::Column::Id, QueryAs::Id)
             .column_as(transaction::Column::Hash, QueryAs::Hash)
             .into_values::<(i64, Vec<u8>), QueryAs>()
-            .all(&self.0)
+            .all(&self.conn)
             .await
             .context("When querying transactions")?
             .into_iter()
@@ -71,7 +71,11 @@ impl ScraperDb {
 
     /// Store a new transaction into the database (or update an existing one).
     #[instrument(skip_all)]
-    pub async fn store_txns(&self, txns: impl Iterator<Item = StorableTxn>) -> Result<()> {
+    pub async fn store_txns(
+        &self,
+        address_format: &AddressFormat,
+        txns: impl Iterator<Item = StorableTxn>,
+    ) -> Result<()> {
         let models = txns
             .map(|txn| {
                 let receipt = txn
@@ -92,8 +96,11 @@ impl ScraperDb {
                     gas_price: Set(txn.gas_price.map(u256_to_decimal)),
                     effective_gas_price: Set(receipt.effective_gas_price.map(u256_to_decimal)),
                     nonce: Set(txn.nonce as i64),
-                    sender: Set(address_to_bytes(&txn.sender)),
-                    recipient: Set(txn.recipient.as_ref().map(address_to_bytes)),
+                    sender: Set(address_to_bytes(address_format, &txn.sender)),
+                    recipient: Set(txn
+                        .recipient
+                        .as_ref()
+                        .map(|recipient| address_to_bytes(address_format, recipient))),
                     max_fee_per_gas: Set(txn.max_fee_per_gas.map(u256_to_decimal)),
                     cumulative_gas_used: Set(u256_to_decimal(receipt.cumulative_gas_used)),
                 })
@@ -110,7 +117,7 @@ impl ScraperDb {
                     .do_nothing()
                     .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await
         {
             Ok(_) => Ok(()),
@@ -0,0 +1,108 @@
+use eyre::Result;
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
+use serde::Deserialize;
+use tracing::instrument;
+
+use hyperlane_core::HyperlaneMessage;
+
+use crate::conversions::bytes_to_address;
+use crate::db::ScraperDb;
+
+/// Most undelivered messages returned by a single query
+pub const MAX_UNDELIVERED_PER_QUERY: u32 = 1000;
+
+/// A message no delivery was scraped for, with the block it was dispatched in
+#[derive(Debug, Clone)]
+pub struct UndeliveredMessage {
+    pub message: HyperlaneMessage,
+    pub block_number: u64,
+    /// Seconds since the unix epoch of the block the message was dispatched in
+    pub block_timestamp: i64,
+}
+
+/// Which undelivered messages to get, by origin and nonce
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UndeliveredQuery {
+    /// Only messages dispatched at least this many seconds ago
+    pub older_than: u64,
+    pub origin: Option<u32>,
+    pub destination: Option<u32>,
+    /// First nonce, to page through the messages of an origin
+    pub from_nonce: Option<u32>,
+    /// Most messages returned, at most `MAX_UNDELIVERED_PER_QUERY`
+    pub limit: Option<u32>,
+}
+
+impl ScraperDb {
+    /// Get the messages matching the query that no delivery was stored for,
+    /// by origin and nonce
+    #[instrument(skip(self))]
+    pub async fn undelivered_messages(
+        &self,
+        query: &UndeliveredQuery,
+    ) -> Result<Vec<UndeliveredMessage>> {
+        let limit = query
+            .limit
+            .unwrap_or(MAX_UNDELIVERED_PER_QUERY)
+            .min(MAX_UNDELIVERED_PER_QUERY);
+        let rows = self
+            .conn
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    "message"."version",
+                    "message"."origin",
+                    "message"."destination",
+                    "message"."nonce",
+                    "message"."sender",
+                    "message"."recipient",
+                    "message"."msg_body",
+                    "block"."height",
+                    EXTRACT(EPOCH FROM "block"."timestamp")::bigint AS "block_timestamp"
+                FROM "message"
+                    JOIN "transaction" ON "transaction"."id" = "message"."origin_tx_id"
+                    JOIN "block" ON "block"."id" = "transaction"."block_id"
+                    LEFT JOIN "delivered_message"
+                        ON "delivered_message"."msg_id" = "message"."msg_id"
+                        AND "delivered_message"."environment" = "message"."environment"
+                WHERE "delivered_message"."id" IS NULL
+                    AND "message"."environment" = $6
+                    AND "block"."timestamp" < NOW() - make_interval(secs => $1)
+                    AND ($2::integer IS NULL OR "message"."origin" = $2)
+                    AND ($3::integer IS NULL OR "message"."destination" = $3)
+                    AND ($4::integer IS NULL OR "message"."nonce" >= $4)
+                ORDER BY "message"."origin", "message"."nonce"
+                LIMIT $5
+                "#,
+                [
+                    (query.older_than as f64).into(),
+                    query.origin.map(|d| d as i32).into(),
+                    query.destination.map(|d| d as i32).into(),
+                    query.from_nonce.map(|n| n as i32).into(),
+                    (limit as i64).into(),
+                    self.environment.clone().into(),
+                ],
+            ))
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                let body: Option<Vec<u8>> = row.try_get("", "msg_body")?;
+                let message = HyperlaneMessage {
+                    version: row.try_get::<i16>("", "version")? as u8,
+                    origin: row.try_get::<i32>("", "origin")? as u32,
+                    destination: row.try_get::<i32>("", "destination")? as u32,
+                    nonce: row.try_get::<i32>("", "nonce")? as u32,
+                    sender: bytes_to_address(row.try_get("", "sender")?)?,
+                    recipient: bytes_to_address(row.try_get("", "recipient")?)?,
+                    body: body.unwrap_or_default(),
+                };
+                Ok(UndeliveredMessage {
+                    message,
+                    block_number: row.try_get::<i64>("", "height")? as u64,
+                    block_timestamp: row.try_get("", "block_timestamp")?,
+                })
+            })
+            .collect()
+    }
+}
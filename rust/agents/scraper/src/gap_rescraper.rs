@@ -0,0 +1,169 @@
+//! The block ranges indexed for each event stream are stored as they are,
+//! rather than trusting that the cursors never skipped any block, e.g. after
+//! a crash in the middle of a chunk. This periodically looks for holes
+//! between those ranges and scrapes them again.
+
+use std::{fmt::Debug, ops::RangeInclusive, time::Duration};
+
+use hyperlane_base::CoreMetrics;
+use hyperlane_core::{
+    Delivery, HyperlaneLogStore, HyperlaneMessage, Indexer, InterchainGasPayment,
+    SequenceAwareIndexer,
+};
+use prometheus::IntGaugeVec;
+use tokio::{task::JoinHandle, time::sleep};
+use tracing::{info, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::{
+    chain_scraper::{HyperlaneSqlDb, GAS_PAYMENT, MESSAGE_DELIVERY, MESSAGE_DISPATCH},
+    db::BlockGap,
+};
+
+/// How often holes are looked for
+const RESCRAPE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Maximum number of holes of a stream scraped again per run
+const MAX_GAPS_PER_STREAM: u32 = 10;
+
+/// A chain whose event streams are checked for holes
+#[derive(Debug)]
+pub struct RescrapedChain {
+    pub db: HyperlaneSqlDb,
+    /// None if messages are indexed by nonce, since their gaps are
+    /// re-indexed by the reconciler
+    pub message_indexer: Option<Box<dyn SequenceAwareIndexer<HyperlaneMessage>>>,
+    pub delivery_indexer: Box<dyn SequenceAwareIndexer<Delivery>>,
+    pub payment_indexer: Box<dyn SequenceAwareIndexer<InterchainGasPayment>>,
+    /// Most blocks fetched at once
+    pub chunk_size: u32,
+}
+
+/// Finds the holes between the block ranges indexed for each event stream
+/// and scrapes them again
+#[derive(Debug)]
+pub struct GapRescraper {
+    chains: Vec<RescrapedChain>,
+    indexed_range_gaps: IntGaugeVec,
+}
+
+impl GapRescraper {
+    pub fn new(chains: Vec<RescrapedChain>, metrics: &CoreMetrics) -> eyre::Result<Self> {
+        let indexed_range_gaps = metrics.new_int_gauge(
+            "indexed_range_gaps",
+            "Holes found between the block ranges indexed for an event stream, at most 10",
+            &["chain", "stream"],
+        )?;
+        Ok(Self {
+            chains,
+            indexed_range_gaps,
+        })
+    }
+
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        tokio::spawn(async move { self.run().await }).instrument(info_span!("GapRescraper"))
+    }
+
+    async fn run(self) {
+        loop {
+            for chain in &self.chains {
+                if let Some(indexer) = &chain.message_indexer {
+                    self.rescrape(chain, MESSAGE_DISPATCH, indexer.as_ref())
+                        .await;
+                }
+                self.rescrape(chain, MESSAGE_DELIVERY, chain.delivery_indexer.as_ref())
+                    .await;
+                self.rescrape(chain, GAS_PAYMENT, chain.payment_indexer.as_ref())
+                    .await;
+            }
+            sleep(RESCRAPE_INTERVAL).await;
+        }
+    }
+
+    async fn rescrape<T, I>(&self, chain: &RescrapedChain, stream: &'static str, indexer: &I)
+    where
+        T: Debug + Send + Sync,
+        I: Indexer<T> + ?Sized,
+        HyperlaneSqlDb: HyperlaneLogStore<T>,
+    {
+        let name = chain.db.domain().name();
+        let gaps = match chain
+            .db
+            .indexed_range_gaps(stream, MAX_GAPS_PER_STREAM)
+            .await
+        {
+            Ok(gaps) => gaps,
+            Err(err) => {
+                warn!(
+                    chain = name,
+                    stream,
+                    ?err,
+                    "Failed to look for indexed range gaps"
+                );
+                return;
+            }
+        };
+        self.indexed_range_gaps
+            .with_label_values(&[name, stream])
+            .set(gaps.len() as i64);
+
+        for gap in gaps {
+            let range = match gap_range(&gap) {
+                Ok(range) => range,
+                Err(err) => {
+                    warn!(chain = name, stream, ?gap, ?err, "Invalid hole block range");
+                    continue;
+                }
+            };
+            let mut stored = 0;
+            for start in range.clone().step_by(chain.chunk_size.max(1) as usize) {
+                let end = start
+                    .saturating_add(chain.chunk_size.saturating_sub(1))
+                    .min(*range.end());
+                let result: eyre::Result<()> = async {
+                    let logs = indexer.fetch_logs_in_range(start..=end).await?;
+                    stored += HyperlaneLogStore::<T>::store_logs(&chain.db, &logs).await?;
+                    HyperlaneLogStore::<T>::store_indexed_range(&chain.db, start..=end).await
+                }
+                .await;
+                if let Err(err) = result {
+                    warn!(
+                        chain = name,
+                        stream,
+                        ?gap,
+                        ?err,
+                        "Failed to scrape a hole again"
+                    );
+                    return;
+                }
+            }
+            info!(
+                chain = name,
+                stream,
+                ?gap,
+                stored,
+                "Scraped a hole between indexed ranges again"
+            );
+        }
+    }
+}
+
+/// The blocks of a hole, which indexers fetch by `u32` block numbers
+fn gap_range(gap: &BlockGap) -> eyre::Result<RangeInclusive<u32>> {
+    Ok(gap.from.try_into()?..=gap.to.try_into()?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gap_range() {
+        let gap = BlockGap { from: 21, to: 29 };
+        assert_eq!(gap_range(&gap).unwrap(), 21..=29);
+
+        let gap = BlockGap {
+            from: u32::MAX as u64,
+            to: u32::MAX as u64 + 1,
+        };
+        assert!(gap_range(&gap).is_err());
+    }
+}
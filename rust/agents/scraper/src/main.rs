@@ -20,9 +20,16 @@ use hyperlane_base::agent_main;
 mod db;
 
 mod agent;
+mod api_auth;
+mod body_decoder;
 mod chain_scraper;
+mod checkpoint_roots;
 mod conversions;
 mod date_time;
+mod gap_rescraper;
+mod price_feed;
+mod reconciler;
+mod server;
 mod settings;
 
 #[tokio::main(flavor = "current_thread")]
@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use hyperlane_core::HyperlaneDomain;
+use itertools::Itertools;
+use reqwest::{Client, Url};
+use serde_json::Value;
+use tokio::{task::JoinHandle, time::sleep};
+use tracing::{info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::settings::PriceFeedConf;
+
+/// How long a fetched price is used for, after which it is too old to value
+/// gas with
+const PRICE_TTL: Duration = Duration::from_secs(60 * 5);
+/// How often prices are fetched again, well within their TTL so that a
+/// failed fetch or two doesn't leave gas unvalued
+const PRICE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// How long a request to CoinGecko may take
+const COINGECKO_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A source of the USD prices of the native tokens of chains, which are
+/// stored with messages and deliveries to value their gas
+#[async_trait]
+pub trait PriceFeed: Debug + Send + Sync {
+    /// The current USD price of one native token of the chain, as of the
+    /// last refresh. None if the feed doesn't know the chain or has no
+    /// recent price for it.
+    fn native_token_usd(&self, domain: &HyperlaneDomain) -> Option<f64>;
+
+    /// How old a block can be for the current price to value its gas. None
+    /// if prices never change.
+    fn max_block_age(&self) -> Option<Duration>;
+
+    /// Fetch the current prices
+    async fn refresh(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub fn build_price_feed(conf: &PriceFeedConf) -> Result<Arc<dyn PriceFeed>> {
+    Ok(match conf {
+        PriceFeedConf::Fixed(prices) => Arc::new(FixedPrices(prices.clone())),
+        PriceFeedConf::CoinGecko {
+            url,
+            api_key,
+            token_ids,
+        } => Arc::new(CoinGecko {
+            client: Client::builder()
+                .timeout(COINGECKO_REQUEST_TIMEOUT)
+                .build()?,
+            url: url.clone(),
+            api_key: api_key.clone(),
+            token_ids: token_ids.clone(),
+            prices: Default::default(),
+        }),
+    })
+}
+
+/// Refresh the prices of the feed in the background, so that storing what
+/// they value never waits on the feed
+pub fn spawn_price_refresh(feed: Arc<dyn PriceFeed>) -> Instrumented<JoinHandle<()>> {
+    tokio::spawn(async move {
+        loop {
+            if let Err(err) = feed.refresh().await {
+                warn!(?err, "Failed to refresh the USD prices of native tokens");
+            }
+            sleep(PRICE_REFRESH_INTERVAL).await;
+        }
+    })
+    .instrument(info_span!("PriceFeed"))
+}
+
+/// Prices that never change, by chain name
+#[derive(Debug)]
+struct FixedPrices(HashMap<String, f64>);
+
+#[async_trait]
+impl PriceFeed for FixedPrices {
+    fn native_token_usd(&self, domain: &HyperlaneDomain) -> Option<f64> {
+        self.0.get(domain.name()).copied()
+    }
+
+    fn max_block_age(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Prices from the simple price API of CoinGecko, cached for a while so
+/// that scraping many chains doesn't exceed its rate limits
+#[derive(Debug)]
+struct CoinGecko {
+    client: Client,
+    url: Url,
+    api_key: Option<String>,
+    /// CoinGecko ids of the native tokens, by chain name
+    token_ids: HashMap<String, String>,
+    /// Latest prices by CoinGecko id, with when they were fetched
+    prices: Mutex<HashMap<String, (Instant, f64)>>,
+}
+
+#[async_trait]
+impl PriceFeed for CoinGecko {
+    fn native_token_usd(&self, domain: &HyperlaneDomain) -> Option<f64> {
+        let token_id = self.token_ids.get(domain.name())?;
+        let (fetched_at, price) = self.prices.lock().unwrap().get(token_id).copied()?;
+        (fetched_at.elapsed() < PRICE_TTL).then_some(price)
+    }
+
+    fn max_block_age(&self) -> Option<Duration> {
+        Some(PRICE_TTL)
+    }
+
+    /// Fetches the prices of every token in a single request
+    async fn refresh(&self) -> Result<()> {
+        if self.token_ids.is_empty() {
+            return Ok(());
+        }
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .map_err(|_| eyre!("CoinGecko url can't be a base"))?
+            .extend(["simple", "price"]);
+        url.query_pairs_mut()
+            .append_pair("ids", &self.token_ids.values().unique().join(","))
+            .append_pair("vs_currencies", "usd");
+        let mut request = self.client.get(url);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-cg-pro-api-key", api_key);
+        }
+        // Prices are returned as `{"<id>": {"usd": <price>}}`
+        let response: Value = request.send().await?.error_for_status()?.json().await?;
+        let fetched_at = Instant::now();
+        let mut prices = self.prices.lock().unwrap();
+        let mut missing = vec![];
+        for token_id in self.token_ids.values().unique() {
+            match response[token_id]["usd"].as_f64() {
+                Some(price) => {
+                    prices.insert(token_id.clone(), (fetched_at, price));
+                }
+                None => missing.push(token_id.as_str()),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(eyre!(
+                "CoinGecko returned no USD price for `{}`",
+                missing.join("`, `")
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixed_prices_value_blocks_of_any_age() {
+        let feed = build_price_feed(&PriceFeedConf::Fixed(HashMap::from([(
+            "test1".to_owned(),
+            2.5,
+        )])));
+        let test1 = HyperlaneDomain::Known(KnownHyperlaneDomain::Test1);
+        let test2 = HyperlaneDomain::Known(KnownHyperlaneDomain::Test2);
+        assert_eq!(feed.native_token_usd(&test1), Some(2.5));
+        assert_eq!(feed.native_token_usd(&test2), None);
+        assert_eq!(feed.max_block_age(), None);
+    }
+
+    #[tokio::test]
+    async fn test_coingecko_prices_value_recent_blocks() {
+        let feed = CoinGecko {
+            client: Client::new(),
+            // Unreachable, so refreshing fails
+            url: Url::parse("http://127.0.0.1:1").unwrap(),
+            api_key: None,
+            token_ids: HashMap::from([("test1".to_owned(), "ethereum".to_owned())]),
+            prices: Mutex::new(HashMap::from([(
+                "ethereum".to_owned(),
+                (Instant::now(), 3000.0),
+            )])),
+        };
+        let test1 = HyperlaneDomain::Known(KnownHyperlaneDomain::Test1);
+        let test2 = HyperlaneDomain::Known(KnownHyperlaneDomain::Test2);
+        assert_eq!(feed.native_token_usd(&test1), Some(3000.0));
+        assert_eq!(feed.native_token_usd(&test2), None);
+        assert_eq!(feed.max_block_age(), Some(PRICE_TTL));
+
+        // A failed refresh keeps the cached price
+        assert!(feed.refresh().await.is_err());
+        assert_eq!(feed.native_token_usd(&test1), Some(3000.0));
+
+        // Prices older than their TTL aren't used
+        if let Some(stale) = Instant::now().checked_sub(PRICE_TTL) {
+            feed.prices
+                .lock()
+                .unwrap()
+                .insert("ethereum".to_owned(), (stale, 3000.0));
+            assert_eq!(feed.native_token_usd(&test1), None);
+        }
+    }
+}
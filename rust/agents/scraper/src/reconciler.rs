@@ -0,0 +1,233 @@
+//! Deliveries can be scraped before their messages, and a message missed by
+//! indexing never is. This periodically counts the deliveries whose message
+//! still isn't stored once the race should be over, and re-indexes the gaps
+//! in the messages stored from each origin, and the messages the origin
+//! mailbox dispatched after the last one stored, so data gaps are filled
+//! rather than silently kept.
+
+use std::{ops::RangeInclusive, time::Duration};
+
+use hyperlane_base::CoreMetrics;
+use hyperlane_core::{
+    HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, HyperlaneSequenceAwareIndexerStoreReader,
+    IndexMode, Indexer, SequenceAwareIndexer,
+};
+use prometheus::IntGaugeVec;
+use tokio::{task::JoinHandle, time::sleep};
+use tracing::{info, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::{
+    chain_scraper::HyperlaneSqlDb,
+    db::{NonceGap, ScraperDb},
+};
+
+/// How often unmatched deliveries are looked for
+const RECONCILIATION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// How long a delivery may wait for its message to be scraped before it is
+/// counted as unmatched
+const GRACE_PERIOD: Duration = Duration::from_secs(10 * 60);
+/// Maximum number of nonce gaps of an origin re-indexed per reconciliation
+const MAX_GAPS_PER_ORIGIN: u32 = 10;
+
+/// An origin whose messages are re-indexed
+#[derive(Debug)]
+pub struct ReconciledOrigin {
+    pub db: HyperlaneSqlDb,
+    pub indexer: Box<dyn SequenceAwareIndexer<HyperlaneMessage>>,
+    pub mode: IndexMode,
+    /// Most blocks or nonces fetched at once
+    pub chunk_size: u32,
+}
+
+/// Finds deliveries scraped without their message and re-indexes the
+/// messages the scraper is missing
+#[derive(Debug)]
+pub struct DeliveryReconciler {
+    db: ScraperDb,
+    /// The scraped chains, whose unmatched deliveries are reported
+    domains: Vec<HyperlaneDomain>,
+    origins: Vec<ReconciledOrigin>,
+    unmatched_deliveries: IntGaugeVec,
+}
+
+impl DeliveryReconciler {
+    pub fn new(
+        db: ScraperDb,
+        domains: Vec<HyperlaneDomain>,
+        origins: Vec<ReconciledOrigin>,
+        metrics: &CoreMetrics,
+    ) -> eyre::Result<Self> {
+        let unmatched_deliveries = metrics.new_int_gauge(
+            "unmatched_deliveries",
+            "Deliveries scraped without their message, once it should have been scraped too",
+            &["chain"],
+        )?;
+        Ok(Self {
+            db,
+            domains,
+            origins,
+            unmatched_deliveries,
+        })
+    }
+
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        tokio::spawn(async move { self.run().await }).instrument(info_span!("DeliveryReconciler"))
+    }
+
+    async fn run(self) {
+        loop {
+            self.reconcile().await;
+            sleep(RECONCILIATION_INTERVAL).await;
+        }
+    }
+
+    async fn reconcile(&self) {
+        let origins: Vec<_> = self
+            .origins
+            .iter()
+            .map(|origin| origin.db.domain().id())
+            .collect();
+        let unmatched = match self.db.unmatched_deliveries(&origins, GRACE_PERIOD).await {
+            Ok(unmatched) => unmatched,
+            Err(err) => {
+                warn!(?err, "Failed to count unmatched deliveries");
+                return;
+            }
+        };
+        for domain in &self.domains {
+            let count = unmatched.get(&domain.id()).copied().unwrap_or_default();
+            self.unmatched_deliveries
+                .with_label_values(&[domain.name()])
+                .set(count as i64);
+        }
+        if unmatched.values().sum::<u64>() == 0 {
+            return;
+        }
+
+        info!(
+            ?unmatched,
+            "Found deliveries without their message, re-indexing message gaps"
+        );
+        for origin in &self.origins {
+            if let Err(err) = Self::reindex_gaps(origin).await {
+                warn!(
+                    origin = origin.db.domain().name(),
+                    ?err,
+                    "Failed to re-index message gaps"
+                );
+            }
+        }
+    }
+
+    /// Fetch and store the messages missing between the messages stored from
+    /// the origin, and after the last of them
+    async fn reindex_gaps(origin: &ReconciledOrigin) -> eyre::Result<()> {
+        let mut gaps = origin.db.message_nonce_gaps(MAX_GAPS_PER_ORIGIN).await?;
+        gaps.extend(Self::tail_gap(origin).await?);
+        for gap in gaps {
+            let range = gap_range(&origin.mode, &gap)?;
+            let mut stored = 0;
+            for start in range.clone().step_by(origin.chunk_size.max(1) as usize) {
+                let end = start
+                    .saturating_add(origin.chunk_size.saturating_sub(1))
+                    .min(*range.end());
+                let logs = origin.indexer.fetch_logs_in_range(start..=end).await?;
+                stored += origin.db.store_logs(&logs).await?;
+            }
+            info!(
+                origin = origin.db.domain().name(),
+                ?gap,
+                stored,
+                "Re-indexed the messages of a nonce gap"
+            );
+        }
+        Ok(())
+    }
+
+    /// The messages the origin mailbox dispatched after the last one stored
+    async fn tail_gap(origin: &ReconciledOrigin) -> eyre::Result<Option<NonceGap>> {
+        let Some(last_nonce) = origin.db.last_message_nonce().await? else {
+            return Ok(None);
+        };
+        let (Some(count), tip) = origin.indexer.latest_sequence_count_and_tip().await? else {
+            return Ok(None);
+        };
+        let Some(last_block) = origin
+            .db
+            .retrieve_log_block_number_by_sequence(last_nonce)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(tail_gap(last_nonce, last_block, count, tip))
+    }
+}
+
+/// The gap between the last message stored, dispatched in `last_block`, and
+/// the `count` messages the mailbox dispatched as of block `tip`, if any
+fn tail_gap(last_nonce: u32, last_block: u64, count: u32, tip: u32) -> Option<NonceGap> {
+    (count > last_nonce.saturating_add(1)).then_some(NonceGap {
+        before: last_nonce,
+        after: count,
+        from_block: last_block,
+        to_block: tip as u64,
+    })
+}
+
+/// The blocks or nonces to fetch the messages of a gap from
+fn gap_range(mode: &IndexMode, gap: &NonceGap) -> eyre::Result<RangeInclusive<u32>> {
+    Ok(match mode {
+        IndexMode::Block => gap.from_block.try_into()?..=gap.to_block.try_into()?,
+        IndexMode::Sequence => gap.before + 1..=gap.after - 1,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn gap() -> NonceGap {
+        NonceGap {
+            before: 4,
+            after: 8,
+            from_block: 100,
+            to_block: 120,
+        }
+    }
+
+    #[test]
+    fn test_gap_range() {
+        assert_eq!(gap_range(&IndexMode::Block, &gap()).unwrap(), 100..=120);
+        assert_eq!(gap_range(&IndexMode::Sequence, &gap()).unwrap(), 5..=7);
+    }
+
+    #[test]
+    fn test_gap_range_rejects_blocks_out_of_range() {
+        let gap = NonceGap {
+            to_block: u32::MAX as u64 + 1,
+            ..gap()
+        };
+        assert!(gap_range(&IndexMode::Block, &gap).is_err());
+        assert_eq!(gap_range(&IndexMode::Sequence, &gap).unwrap(), 5..=7);
+    }
+
+    #[test]
+    fn test_tail_gap() {
+        // Every dispatched message is stored
+        assert_eq!(tail_gap(9, 100, 10, 150), None);
+        assert_eq!(tail_gap(9, 100, 5, 150), None);
+
+        let gap = tail_gap(9, 100, 13, 150).unwrap();
+        assert_eq!(
+            gap,
+            NonceGap {
+                before: 9,
+                after: 13,
+                from_block: 100,
+                to_block: 150,
+            }
+        );
+        assert_eq!(gap_range(&IndexMode::Sequence, &gap).unwrap(), 10..=12);
+        assert_eq!(gap_range(&IndexMode::Block, &gap).unwrap(), 100..=150);
+    }
+}
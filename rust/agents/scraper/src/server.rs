@@ -0,0 +1,238 @@
+use std::str::FromStr;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware, routing, Json, Router,
+};
+use hyperlane_core::{
+    utils::{bytes_to_hex, fmt_address_for_domain},
+    RawHyperlaneMessage, H256,
+};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::{
+    api_auth::{self, ApiAuth},
+    db::{CheckpointQuery, DailyStats, DailyStatsQuery, ScraperDb, UndeliveredQuery},
+};
+
+const STATS_API_BASE: &str = "/stats";
+const MESSAGES_API_BASE: &str = "/messages";
+const CHECKPOINTS_API_BASE: &str = "/checkpoints";
+const UNDELIVERED_API_BASE: &str = "/undelivered";
+
+/// Returns a vector of agent-specific endpoint routes to be served. If `auth`
+/// is set, they require one of its API keys.
+pub fn routes(db: ScraperDb, auth: Option<ApiAuth>) -> Vec<(&'static str, Router)> {
+    let routes = vec![
+        (STATS_API_BASE, stats_router(db.clone())),
+        (MESSAGES_API_BASE, messages_router(db.clone())),
+        (CHECKPOINTS_API_BASE, checkpoints_router(db.clone())),
+        (UNDELIVERED_API_BASE, undelivered_router(db)),
+    ];
+    let Some(auth) = auth else {
+        return routes;
+    };
+    routes
+        .into_iter()
+        .map(|(route, router)| {
+            let router = router.layer(middleware::from_fn_with_state(
+                auth.clone(),
+                api_auth::authorize,
+            ));
+            (route, router)
+        })
+        .collect()
+}
+
+/// Statistics served from the rollups the scraper maintains, so they don't
+/// scan the message tables
+fn stats_router(db: ScraperDb) -> Router {
+    Router::new()
+        .route("/daily", routing::get(daily_stats))
+        .with_state(db)
+}
+
+/// Daily message counts and average delivery latency of each route, filtered
+/// by the `origin`, `destination`, `from` and `to` query parameters
+async fn daily_stats(
+    State(db): State<ScraperDb>,
+    Query(query): Query<DailyStatsQuery>,
+) -> Result<Json<Vec<DailyStats>>, (StatusCode, String)> {
+    for day in [&query.from, &query.to].into_iter().flatten() {
+        if !is_day(day) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Invalid day `{day}`, expected YYYY-MM-DD"),
+            ));
+        }
+    }
+    db.daily_stats(&query).await.map(Json).map_err(|err| {
+        warn!(error = ?err, "Failed to query daily stats");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to query daily stats".to_owned(),
+        )
+    })
+}
+
+fn messages_router(db: ScraperDb) -> Router {
+    Router::new()
+        .route("/:id", routing::get(message))
+        .with_state(db)
+}
+
+/// A scraped message, with its sender and recipient written the way their
+/// chains do
+#[derive(Debug, Serialize)]
+struct MessageResponse {
+    id: String,
+    nonce: u32,
+    origin: u32,
+    destination: u32,
+    sender: String,
+    recipient: String,
+    body: String,
+    delivered: bool,
+}
+
+/// The message of the id in the path
+async fn message(
+    State(db): State<ScraperDb>,
+    Path(id): Path<String>,
+) -> Result<Json<MessageResponse>, (StatusCode, String)> {
+    let id = H256::from_str(&id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid message id `{id}`"),
+        )
+    })?;
+    let retrieved = db.retrieve_message_by_id(&id).await.map_err(|err| {
+        warn!(error = ?err, "Failed to query message");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to query message".to_owned(),
+        )
+    })?;
+    let Some((message, delivered)) = retrieved else {
+        return Err((StatusCode::NOT_FOUND, format!("Message {id:?} not found")));
+    };
+    Ok(Json(MessageResponse {
+        id: format!("{id:?}"),
+        nonce: message.nonce,
+        origin: message.origin,
+        destination: message.destination,
+        sender: fmt_address_for_domain(message.origin, message.sender),
+        recipient: fmt_address_for_domain(message.destination, message.recipient),
+        body: bytes_to_hex(&message.body),
+        delivered,
+    }))
+}
+
+/// The history of the roots of each origin's merkle tree hook, for auditing
+/// the checkpoints validators sign
+fn checkpoints_router(db: ScraperDb) -> Router {
+    Router::new()
+        .route("/", routing::get(checkpoints))
+        .with_state(db)
+}
+
+/// The root a merkle tree hook had once a leaf was inserted, which is the
+/// root of the checkpoint of the leaf's index
+#[derive(Debug, Serialize)]
+struct CheckpointResponse {
+    merkle_tree_hook: String,
+    index: u32,
+    root: String,
+    message_id: String,
+    block_number: u64,
+    block_timestamp: i64,
+}
+
+/// The checkpoint roots of the `origin` query parameter, by leaf index,
+/// filtered by the `from` and `to` indices and capped at `limit`
+async fn checkpoints(
+    State(db): State<ScraperDb>,
+    Query(query): Query<CheckpointQuery>,
+) -> Result<Json<Vec<CheckpointResponse>>, (StatusCode, String)> {
+    let roots = db.checkpoint_roots(&query).await.map_err(|err| {
+        warn!(error = ?err, "Failed to query checkpoint roots");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to query checkpoint roots".to_owned(),
+        )
+    })?;
+    Ok(Json(
+        roots
+            .into_iter()
+            .map(|checkpoint| CheckpointResponse {
+                merkle_tree_hook: fmt_address_for_domain(query.origin, checkpoint.merkle_tree_hook),
+                index: checkpoint.index,
+                root: format!("{:?}", checkpoint.root),
+                message_id: format!("{:?}", checkpoint.message_id),
+                block_number: checkpoint.block_number,
+                block_timestamp: checkpoint.block_timestamp,
+            })
+            .collect(),
+    ))
+}
+
+/// Messages that were dispatched a while ago but not delivered, which
+/// relayers can use to find the messages their own indexing missed
+fn undelivered_router(db: ScraperDb) -> Router {
+    Router::new()
+        .route("/", routing::get(undelivered))
+        .with_state(db)
+}
+
+/// A message no delivery was scraped for
+#[derive(Debug, Serialize)]
+struct UndeliveredMessageResponse {
+    id: String,
+    nonce: u32,
+    origin: u32,
+    destination: u32,
+    /// The encoded message, which its id is the hash of
+    message: String,
+    block_number: u64,
+    block_timestamp: i64,
+}
+
+/// The messages dispatched more than `older_than` seconds ago that weren't
+/// delivered, by origin and nonce, filtered by the `origin`, `destination` and
+/// `from_nonce` query parameters and capped at `limit`
+async fn undelivered(
+    State(db): State<ScraperDb>,
+    Query(query): Query<UndeliveredQuery>,
+) -> Result<Json<Vec<UndeliveredMessageResponse>>, (StatusCode, String)> {
+    let undelivered = db.undelivered_messages(&query).await.map_err(|err| {
+        warn!(error = ?err, "Failed to query undelivered messages");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to query undelivered messages".to_owned(),
+        )
+    })?;
+    Ok(Json(
+        undelivered
+            .into_iter()
+            .map(|undelivered| {
+                let message = &undelivered.message;
+                UndeliveredMessageResponse {
+                    id: format!("{:?}", message.id()),
+                    nonce: message.nonce,
+                    origin: message.origin,
+                    destination: message.destination,
+                    message: bytes_to_hex(&RawHyperlaneMessage::from(message)),
+                    block_number: undelivered.block_number,
+                    block_timestamp: undelivered.block_timestamp,
+                }
+            })
+            .collect(),
+    ))
+}
+
+fn is_day(day: &str) -> bool {
+    let lengths: Vec<usize> = day.split('-').map(str::len).collect();
+    lengths == [4, 2, 2] && day.bytes().all(|b| b == b'-' || b.is_ascii_digit())
+}
@@ -4,10 +4,14 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, default::Default};
+use std::{
+    collections::{HashMap, HashSet},
+    default::Default,
+};
 
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
-use eyre::Context;
+use ethers::abi::{param_type::Reader, ParamType};
+use eyre::{eyre, Context};
 use hyperlane_base::{
     impl_loadable_from_settings,
     settings::{
@@ -15,10 +19,16 @@ use hyperlane_base::{
         Settings,
     },
 };
-use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain};
+use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, H256};
+use reqwest::Url;
 use serde::Deserialize;
 use serde_json::Value;
 
+const COINGECKO_URL: &str = "https://api.coingecko.com/api/v3";
+
+/// Environment of a scraper that doesn't share its database
+const DEFAULT_ENVIRONMENT: &str = "default";
+
 /// Settings for `Scraper`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
 pub struct ScraperSettings {
@@ -29,7 +39,67 @@ pub struct ScraperSettings {
     base: Settings,
 
     pub db: String,
+    /// Environment the rows of this scraper are stored in, so the scrapers
+    /// of several deployments can share a database
+    pub environment: String,
     pub chains_to_scrape: Vec<HyperlaneDomain>,
+    /// Keys the HTTP API requires one of. If empty, the API is open.
+    pub api_keys: Vec<ApiKeyConf>,
+    /// How the bodies of the messages of known apps are decoded
+    pub body_decoders: Vec<BodyDecoderConf>,
+    /// Where the USD prices of native tokens, stored with messages and
+    /// deliveries, come from. If not set, they aren't stored.
+    pub price_feed: Option<PriceFeedConf>,
+}
+
+/// A key the HTTP API can be called with
+#[derive(Debug, Clone)]
+pub struct ApiKeyConf {
+    /// Name the key is logged with, so the key itself never is
+    pub name: String,
+    pub key: String,
+    /// Most requests a minute made with the key. If not set, unlimited.
+    pub requests_per_minute: Option<u32>,
+}
+
+/// Decodes the bodies of the messages an app sends or receives
+#[derive(Debug, Clone)]
+pub struct BodyDecoderConf {
+    /// Name of the app, stored with the decoded bodies
+    pub app: String,
+    /// Contracts of the app, by domain and address. A message is the app's
+    /// if its sender or its recipient is one of them.
+    pub addresses: Vec<(u32, H256)>,
+    pub schema: BodySchema,
+}
+
+/// How a message body is laid out
+#[derive(Debug, Clone)]
+pub enum BodySchema {
+    /// A warp route transfer: the recipient and the amount as 32 bytes each,
+    /// followed by optional metadata
+    WarpRoute,
+    /// ABI encoded parameters, by name
+    Abi(Vec<(String, ParamType)>),
+    /// A message between interchain account routers: the owner, the ISM and
+    /// the calls the account makes
+    InterchainAccount,
+}
+
+/// A source of the USD prices of the native tokens of chains
+#[derive(Debug, Clone)]
+pub enum PriceFeedConf {
+    /// Prices that never change, by chain name. Mostly useful for testnets,
+    /// whose tokens have no market price.
+    Fixed(HashMap<String, f64>),
+    /// The simple price API of CoinGecko
+    CoinGecko {
+        url: Url,
+        /// Sent as the `x-cg-pro-api-key` header, if set
+        api_key: Option<String>,
+        /// CoinGecko ids of the native tokens, by chain name
+        token_ids: HashMap<String, String>,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -69,6 +139,14 @@ impl FromRawConf<RawScraperSettings> for ScraperSettings {
             .end()
             .map(|v| v.to_owned());
 
+        let environment = p
+            .chain(&mut err)
+            .get_opt_key("environment")
+            .parse_string()
+            .end()
+            .unwrap_or(DEFAULT_ENVIRONMENT)
+            .to_owned();
+
         let chains_to_scrape = if let (Some(base), Some(chains)) = (&base, chains_names_to_scrape) {
             chains
                 .into_iter()
@@ -83,12 +161,293 @@ impl FromRawConf<RawScraperSettings> for ScraperSettings {
             Default::default()
         };
 
+        let api_keys = p
+            .chain(&mut err)
+            .get_opt_key("apiKeys")
+            .parse_string()
+            .end()
+            .map(|keys| parse_api_keys(keys, &mut err, || &p.cwp + "apiKeys"))
+            .unwrap_or_default();
+
+        let body_decoders = p
+            .chain(&mut err)
+            .get_opt_key("bodyDecoders")
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|decoder| parse_body_decoder(decoder).take_config_err(&mut err))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let price_feed = p
+            .chain(&mut err)
+            .get_opt_key("priceFeed")
+            .and_then(parse_price_feed)
+            .end();
+
         cfg_unwrap_all!(&p.cwp, err: [base, db]);
 
         err.into_result(Self {
             base,
             db,
+            environment,
             chains_to_scrape,
+            api_keys,
+            body_decoders,
+            price_feed,
         })
     }
 }
+
+/// Parse comma separated `<name>:<key>[:<requests per minute>]` API keys
+fn parse_api_keys(
+    keys: &str,
+    err: &mut ConfigParsingError,
+    err_path: impl Fn() -> ConfigPath,
+) -> Vec<ApiKeyConf> {
+    keys.split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .filter_map(|key| {
+            let mut parts = key.split(':');
+            let (Some(name), Some(key), requests_per_minute, None) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return Err(eyre!(
+                    "Expected an API key as `<name>:<key>[:<requests per minute>]`"
+                ))
+                .take_err(err, &err_path);
+            };
+            if name.is_empty() || key.is_empty() {
+                return Err(eyre!("API key names and keys must not be empty"))
+                    .take_err(err, &err_path);
+            }
+            let requests_per_minute = match requests_per_minute {
+                Some(limit) => Some(
+                    limit
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|limit| *limit > 0)
+                        .ok_or_else(|| {
+                            eyre!("Expected the requests per minute of API key `{name}` to be a positive integer")
+                        })
+                        .take_err(err, &err_path)?,
+                ),
+                None => None,
+            };
+            Some(ApiKeyConf {
+                name: name.to_owned(),
+                key: key.to_owned(),
+                requests_per_minute,
+            })
+        })
+        .collect()
+}
+
+fn parse_body_decoder(p: ValueParser) -> ConfigResult<BodyDecoderConf> {
+    let mut err = ConfigParsingError::default();
+
+    let app = p
+        .chain(&mut err)
+        .get_key("app")
+        .parse_string()
+        .end()
+        .map(|app| app.to_owned());
+
+    let addresses = p
+        .chain(&mut err)
+        .get_key("addresses")
+        .into_array_iter()
+        .map(|itr| {
+            itr.filter_map(|contract| {
+                let domain = contract.chain(&mut err).get_key("domain").parse_u32().end();
+                let address = contract
+                    .chain(&mut err)
+                    .get_key("address")
+                    .parse_address_hash()
+                    .end();
+                Some((domain?, address?))
+            })
+            .collect::<Vec<_>>()
+        });
+
+    let schema = p
+        .chain(&mut err)
+        .get_key("schema")
+        .and_then(parse_body_schema)
+        .end();
+
+    cfg_unwrap_all!(&p.cwp, err: [app, addresses, schema]);
+    err.into_result(BodyDecoderConf {
+        app,
+        addresses,
+        schema,
+    })
+}
+
+fn parse_body_schema(p: ValueParser) -> ConfigResult<BodySchema> {
+    let mut err = ConfigParsingError::default();
+
+    let schema_type = p
+        .chain(&mut err)
+        .get_key("type")
+        .parse_string()
+        .end()
+        .unwrap_or_default();
+
+    let schema = match schema_type {
+        "warpRoute" => Some(BodySchema::WarpRoute),
+        "interchainAccount" => Some(BodySchema::InterchainAccount),
+        "abi" => p
+            .chain(&mut err)
+            .get_key("params")
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|param| {
+                    let name = param.chain(&mut err).get_key("name").parse_string().end();
+                    let param_type = param
+                        .chain(&mut err)
+                        .get_key("type")
+                        .parse_string()
+                        .end()
+                        .and_then(|ty| {
+                            Reader::read(ty)
+                                .map_err(|e| eyre!("Invalid ABI type `{ty}`: {e}"))
+                                .take_err(&mut err, || &param.cwp + "type")
+                        });
+                    Some((name?.to_owned(), param_type?))
+                })
+                .collect()
+            })
+            .map(BodySchema::Abi),
+        "" => None,
+        _ => {
+            err.push(
+                &p.cwp + "type",
+                eyre!("Unknown body schema type `{schema_type}`"),
+            );
+            None
+        }
+    };
+
+    cfg_unwrap_all!(&p.cwp, err: [schema]);
+    err.into_result(schema)
+}
+
+fn parse_price_feed(p: ValueParser) -> ConfigResult<PriceFeedConf> {
+    let mut err = ConfigParsingError::default();
+
+    let feed_type = p
+        .chain(&mut err)
+        .get_key("type")
+        .parse_string()
+        .end()
+        .unwrap_or_default();
+
+    let price_feed = match feed_type {
+        "fixed" => p
+            .chain(&mut err)
+            .get_key("prices")
+            .into_obj_iter()
+            .map(|itr| {
+                itr.filter_map(|(chain, price)| {
+                    price
+                        .chain(&mut err)
+                        .parse_f64()
+                        .end()
+                        .map(|price| (chain, price))
+                })
+                .collect()
+            })
+            .map(PriceFeedConf::Fixed),
+        "coinGecko" => {
+            let url = p
+                .chain(&mut err)
+                .get_opt_key("url")
+                .parse_from_str("Invalid CoinGecko url")
+                .end()
+                .unwrap_or_else(|| Url::parse(COINGECKO_URL).unwrap());
+            let api_key = p
+                .chain(&mut err)
+                .get_opt_key("apiKey")
+                .parse_string()
+                .end()
+                .map(|key| key.to_owned());
+            let token_ids = p
+                .chain(&mut err)
+                .get_key("tokenIds")
+                .into_obj_iter()
+                .map(|itr| {
+                    itr.filter_map(|(chain, id)| {
+                        id.chain(&mut err)
+                            .parse_string()
+                            .end()
+                            .map(|id| (chain, id.to_owned()))
+                    })
+                    .collect()
+                });
+            token_ids.map(|token_ids| PriceFeedConf::CoinGecko {
+                url,
+                api_key,
+                token_ids,
+            })
+        }
+        "" => None,
+        _ => {
+            err.push(
+                &p.cwp + "type",
+                eyre!("Unknown price feed type `{feed_type}`"),
+            );
+            None
+        }
+    };
+
+    cfg_unwrap_all!(&p.cwp, err: [price_feed]);
+    err.into_result(price_feed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(keys: &str) -> (Vec<ApiKeyConf>, ConfigParsingError) {
+        let mut err = ConfigParsingError::default();
+        let keys = parse_api_keys(keys, &mut err, || ConfigPath::default() + "apiKeys");
+        (keys, err)
+    }
+
+    #[test]
+    fn test_parse_api_keys() {
+        let (keys, err) = parse(" partner:abc:60, ops:def ,");
+        assert!(err.is_ok());
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].name, "partner");
+        assert_eq!(keys[0].key, "abc");
+        assert_eq!(keys[0].requests_per_minute, Some(60));
+        assert_eq!(keys[1].name, "ops");
+        assert_eq!(keys[1].key, "def");
+        assert_eq!(keys[1].requests_per_minute, None);
+
+        let (keys, err) = parse("");
+        assert!(err.is_ok());
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_api_keys() {
+        for keys in [
+            "abc",
+            "partner:abc:60:1",
+            ":abc",
+            "partner:",
+            "partner:abc:0",
+            "partner:abc:many",
+        ] {
+            let (parsed, err) = parse(&format!("ops:def,{keys}"));
+            assert!(!err.is_ok(), "{keys}");
+            // Valid keys are still parsed
+            assert_eq!(parsed.len(), 1, "{keys}");
+            assert_eq!(parsed[0].name, "ops");
+        }
+    }
+}
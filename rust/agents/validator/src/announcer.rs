@@ -0,0 +1,203 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::Result;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+use hyperlane_base::{settings::ChainConf, CheckpointSyncer};
+use hyperlane_core::{
+    Announcement, ChainResult, HyperlaneDomain, HyperlaneSigner, HyperlaneSignerExt,
+    SignedAnnouncement, TxOutcome, ValidatorAnnounce, H256, U256,
+};
+use hyperlane_ethereum::SingletonSignerHandle;
+
+/// A chain the validator announces its storage location on
+#[derive(Debug)]
+pub(crate) struct AnnouncementChain {
+    pub(crate) domain: HyperlaneDomain,
+    pub(crate) conf: ChainConf,
+    pub(crate) validator_announce: Arc<dyn ValidatorAnnounce>,
+}
+
+/// Keeps the validator's storage location announced on every configured
+/// chain, announcing wherever it is missing, e.g. because the chain was just
+/// added, the chain signer lacked funds or the storage location changed.
+#[derive(Debug, Clone)]
+pub(crate) struct ValidatorAnnouncer {
+    /// The origin chain first, followed by the other chains to announce on
+    chains: Arc<Vec<AnnouncementChain>>,
+    signer: SingletonSignerHandle,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    /// How long to wait between attempts while the origin is not announced
+    interval: Duration,
+    /// How often to check the announcements on all chains
+    check_interval: Duration,
+}
+
+impl ValidatorAnnouncer {
+    pub(crate) fn new(
+        origin: AnnouncementChain,
+        others: Vec<AnnouncementChain>,
+        signer: SingletonSignerHandle,
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        interval: Duration,
+        check_interval: Duration,
+    ) -> Self {
+        Self {
+            chains: Arc::new(std::iter::once(origin).chain(others).collect()),
+            signer,
+            checkpoint_syncer,
+            interval,
+            check_interval,
+        }
+    }
+
+    /// Announce on the origin chain, waiting until the announcement is
+    /// visible on chain.
+    ///
+    /// This is to avoid a situation in which the validator is signing
+    /// checkpoints but has not announced their locations, which makes them
+    /// functionally unusable.
+    pub(crate) async fn announce_origin(&self) -> Result<()> {
+        let origin = &self.chains[0];
+        let signed_announcement = self.sign_announcement(origin).await?;
+        self.checkpoint_syncer
+            .write_announcement(&signed_announcement)
+            .await?;
+
+        while !self.ensure_announced(origin, &signed_announcement).await? {
+            sleep(self.interval).await;
+        }
+        Ok(())
+    }
+
+    /// Periodically check the announcement on every chain, announcing where
+    /// it is missing.
+    pub(crate) async fn run(self) {
+        loop {
+            for chain in self.chains.iter() {
+                let result = match self.sign_announcement(chain).await {
+                    Ok(signed_announcement) => {
+                        self.ensure_announced(chain, &signed_announcement).await
+                    }
+                    Err(err) => Err(err),
+                };
+                if let Err(err) = result {
+                    warn!(chain=%chain.domain, ?err, "Failed to check validator announcement");
+                }
+            }
+            sleep(self.check_interval).await;
+        }
+    }
+
+    /// The announcement for a chain, which is signed over that chain's
+    /// mailbox.
+    async fn sign_announcement(&self, chain: &AnnouncementChain) -> Result<SignedAnnouncement> {
+        let announcement = Announcement {
+            validator: self.signer.eth_address(),
+            mailbox_address: chain.conf.addresses.mailbox,
+            mailbox_domain: chain.domain.id(),
+            storage_location: self.checkpoint_syncer.announcement_location(),
+        };
+        Ok(self.signer.sign(announcement).await?)
+    }
+
+    /// Returns whether the storage location is announced on the chain,
+    /// submitting an announcement if it isn't and the chain signer can pay
+    /// for it.
+    async fn ensure_announced(
+        &self,
+        chain: &AnnouncementChain,
+        signed_announcement: &SignedAnnouncement,
+    ) -> Result<bool> {
+        let announcement = &signed_announcement.value;
+        let announcement_location = &announcement.storage_location;
+        let validators: [H256; 1] = [announcement.validator.into()];
+        info!(chain=%chain.domain, "Checking for validator announcement");
+        let locations = chain
+            .validator_announce
+            .get_announced_storage_locations(&validators)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        if locations.contains(announcement_location) {
+            info!(
+                chain=%chain.domain,
+                ?locations,
+                ?announcement_location,
+                "Validator has announced signature storage location"
+            );
+            return Ok(true);
+        }
+        info!(
+            chain=%chain.domain,
+            announced_locations=?locations,
+            "Validator has not announced signature storage location"
+        );
+
+        let Some(chain_signer) = chain.conf.chain_signer().await? else {
+            warn!(chain=%chain.domain, "Cannot announce validator without a signer; make sure a signer is set for the chain");
+            return Ok(false);
+        };
+        let chain_signer = chain_signer.address_string();
+        info!(chain=%chain.domain, eth_validator_address=?announcement.validator, ?chain_signer, "Attempting self announce");
+        let balance_delta = chain
+            .validator_announce
+            .announce_tokens_needed(signed_announcement.clone())
+            .await
+            .unwrap_or_default();
+        if balance_delta > U256::zero() {
+            warn!(
+                chain=%chain.domain,
+                tokens_needed=%balance_delta,
+                eth_validator_address=?announcement.validator,
+                ?chain_signer,
+                "Please send tokens to your chain signer address to announce",
+            );
+        } else {
+            let result = chain
+                .validator_announce
+                .announce(signed_announcement.clone())
+                .await;
+            log_on_announce_failure(result, &chain.domain, &chain_signer);
+        }
+        Ok(false)
+    }
+}
+
+fn log_on_announce_failure(
+    result: ChainResult<TxOutcome>,
+    chain: &HyperlaneDomain,
+    chain_signer: &String,
+) {
+    match result {
+        Ok(outcome) => {
+            if outcome.executed {
+                info!(
+                    %chain,
+                    tx_outcome=?outcome,
+                    ?chain_signer,
+                    "Successfully announced validator",
+                );
+            } else {
+                error!(
+                    %chain,
+                    txid=?outcome.transaction_id,
+                    gas_used=?outcome.gas_used,
+                    gas_price=?outcome.gas_price,
+                    ?chain_signer,
+                    "Transaction attempting to announce validator reverted. Make sure you have enough funds in your account to pay for transaction fees."
+                );
+            }
+        }
+        Err(err) => {
+            error!(
+                %chain,
+                ?err,
+                ?chain_signer,
+                "Failed to announce validator. Make sure you have enough funds in your account to pay for gas."
+            );
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::Result;
+use prometheus::IntGauge;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use hyperlane_base::{CheckpointSyncer, CoreMetrics};
+use hyperlane_core::{CheckpointAttestation, HyperlaneChain, HyperlaneDomain, H256};
+
+/// Periodically posts the latest checkpoint the validator signed to a
+/// CheckpointAttestation contract, in addition to the checkpoint syncer, so
+/// its liveness can be verified on-chain.
+#[derive(Debug)]
+pub(crate) struct CheckpointAttester {
+    attestation: Arc<dyn CheckpointAttestation>,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    validator: H256,
+    origin: u32,
+    merkle_tree_hook: H256,
+    interval: Duration,
+    latest_attested: IntGauge,
+}
+
+impl CheckpointAttester {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        attestation: Arc<dyn CheckpointAttestation>,
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        validator: H256,
+        origin: &HyperlaneDomain,
+        merkle_tree_hook: H256,
+        interval: Duration,
+        metrics: &CoreMetrics,
+    ) -> Self {
+        Self {
+            attestation,
+            checkpoint_syncer,
+            validator,
+            origin: origin.id(),
+            merkle_tree_hook,
+            interval,
+            latest_attested: metrics
+                .latest_checkpoint()
+                .with_label_values(&["validator_attested", origin.name()]),
+        }
+    }
+
+    pub(crate) async fn run(self) {
+        loop {
+            if let Err(err) = self.attest_latest_checkpoint().await {
+                warn!(
+                    chain=%self.attestation.domain(),
+                    ?err,
+                    "Failed to attest the latest checkpoint"
+                );
+            }
+            sleep(self.interval).await;
+        }
+    }
+
+    /// Attest the latest checkpoint in the checkpoint syncer, unless it
+    /// already is
+    async fn attest_latest_checkpoint(&self) -> Result<()> {
+        let Some(index) = self.checkpoint_syncer.latest_index().await? else {
+            debug!("No checkpoint signed yet, nothing to attest");
+            return Ok(());
+        };
+        let attested = self
+            .attestation
+            .latest_attested_index(self.validator, self.origin, self.merkle_tree_hook)
+            .await?;
+        if let Some(attested) = attested {
+            self.latest_attested.set(attested as i64);
+            if attested >= index {
+                debug!(index, attested, "Latest checkpoint is already attested");
+                return Ok(());
+            }
+        }
+        let Some(checkpoint) = self.checkpoint_syncer.fetch_checkpoint(index).await? else {
+            warn!(
+                index,
+                "Latest signed checkpoint is missing from the checkpoint syncer"
+            );
+            return Ok(());
+        };
+
+        let outcome = self.attestation.attest(&checkpoint).await?;
+        if outcome.executed {
+            info!(
+                chain=%self.attestation.domain(),
+                ?checkpoint,
+                tx_outcome=?outcome,
+                "Attested latest checkpoint"
+            );
+            self.latest_attested.set(index as i64);
+        } else {
+            warn!(
+                chain=%self.attestation.domain(),
+                ?checkpoint,
+                txid=?outcome.transaction_id,
+                "Transaction attesting the latest checkpoint reverted"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, sync::Arc};
+
+    use hyperlane_base::{CheckpointSyncer, CoreMetrics, LocalStorage};
+    use hyperlane_core::{
+        Checkpoint, CheckpointWithMessageId, HyperlaneDomain, KnownHyperlaneDomain, Signature,
+        SignedCheckpointWithMessageId, TxOutcome, H256, U256,
+    };
+    use hyperlane_test::mocks::MockCheckpointAttestationContract;
+    use prometheus::Registry;
+
+    use super::*;
+
+    const MERKLE_TREE_HOOK: H256 = H256::repeat_byte(1);
+
+    fn checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: MERKLE_TREE_HOOK,
+                    mailbox_domain: 13371,
+                    root: H256::repeat_byte(2),
+                    index,
+                },
+                message_id: H256::repeat_byte(3),
+            },
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
+        }
+    }
+
+    fn tx_outcome(executed: bool) -> TxOutcome {
+        TxOutcome {
+            transaction_id: Default::default(),
+            executed,
+            gas_used: U256::zero(),
+            gas_price: U256::zero().try_into().unwrap(),
+        }
+    }
+
+    /// A checkpoint syncer in a fresh directory, with checkpoints up to
+    /// `latest_index` if any
+    async fn checkpoint_syncer(name: &str, latest_index: Option<u32>) -> Arc<LocalStorage> {
+        let path: PathBuf =
+            std::env::temp_dir().join(format!("attester_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let syncer = LocalStorage::new(path, None).unwrap();
+        if let Some(latest_index) = latest_index {
+            for index in 0..=latest_index {
+                syncer.write_checkpoint(&checkpoint(index)).await.unwrap();
+            }
+            syncer.write_latest_index(latest_index).await.unwrap();
+        }
+        Arc::new(syncer)
+    }
+
+    fn attester(
+        attestation: MockCheckpointAttestationContract,
+        checkpoint_syncer: Arc<LocalStorage>,
+    ) -> CheckpointAttester {
+        let metrics = CoreMetrics::new("test", 9090, Registry::new()).unwrap();
+        CheckpointAttester::new(
+            Arc::new(attestation),
+            checkpoint_syncer,
+            H256::repeat_byte(4),
+            &HyperlaneDomain::Known(KnownHyperlaneDomain::Test1),
+            MERKLE_TREE_HOOK,
+            Duration::from_secs(60),
+            &metrics,
+        )
+    }
+
+    fn mock_attestation() -> MockCheckpointAttestationContract {
+        let mut attestation = MockCheckpointAttestationContract::new();
+        attestation
+            .expect__domain()
+            .return_const(HyperlaneDomain::Known(KnownHyperlaneDomain::Test2));
+        attestation
+    }
+
+    #[tokio::test]
+    async fn test_attests_latest_checkpoint() {
+        let mut attestation = mock_attestation();
+        attestation
+            .expect__latest_attested_index()
+            .withf(|_, origin, hook| *origin == 13371 && *hook == MERKLE_TREE_HOOK)
+            .returning(|_, _, _| Ok(Some(3)));
+        attestation
+            .expect__attest()
+            .withf(|checkpoint| checkpoint.value.index == 5)
+            .times(1)
+            .returning(|_| Ok(tx_outcome(true)));
+
+        let attester = attester(attestation, checkpoint_syncer("latest", Some(5)).await);
+        attester.attest_latest_checkpoint().await.unwrap();
+        assert_eq!(attester.latest_attested.get(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_skips_attested_checkpoint() {
+        let mut attestation = mock_attestation();
+        attestation
+            .expect__latest_attested_index()
+            .returning(|_, _, _| Ok(Some(5)));
+        attestation.expect__attest().never();
+
+        let attester = attester(attestation, checkpoint_syncer("attested", Some(5)).await);
+        attester.attest_latest_checkpoint().await.unwrap();
+        assert_eq!(attester.latest_attested.get(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_nothing_to_attest_without_checkpoints() {
+        let mut attestation = mock_attestation();
+        attestation.expect__latest_attested_index().never();
+        attestation.expect__attest().never();
+
+        let attester = attester(attestation, checkpoint_syncer("empty", None).await);
+        attester.attest_latest_checkpoint().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reverted_attestation_is_not_reported() {
+        let mut attestation = mock_attestation();
+        attestation
+            .expect__latest_attested_index()
+            .returning(|_, _, _| Ok(None));
+        attestation
+            .expect__attest()
+            .times(1)
+            .returning(|_| Ok(tx_outcome(false)));
+
+        let attester = attester(attestation, checkpoint_syncer("reverted", Some(2)).await);
+        attester.attest_latest_checkpoint().await.unwrap();
+        assert_eq!(attester.latest_attested.get(), 0);
+    }
+}
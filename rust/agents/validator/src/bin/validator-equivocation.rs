@@ -0,0 +1,358 @@
+//! Find and verify equivocation by validators.
+//!
+//! A validator equivocates when it signs two different checkpoints for the
+//! same index of the same merkle tree. `scan` reads checkpoints from storage
+//! locations and prints one signed evidence bundle per equivocating validator
+//! as a single line of JSON. `verify` checks such a bundle, so that slashing
+//! tooling doesn't have to trust whoever submitted it.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+
+use clap::{Args, Parser, Subcommand};
+use ethers::utils::keccak256;
+use eyre::{bail, eyre, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use hyperlane_base::{
+    settings::{BuildableWithSignerConf, CheckpointSyncerConf, SignerConf},
+    CheckpointSyncer,
+};
+use hyperlane_core::{
+    HyperlaneSignerExt, Signable, SignedCheckpointWithMessageId, SignedType, H160, H256,
+};
+use hyperlane_ethereum::Signers;
+
+#[derive(Parser)]
+#[command(about = "Find and verify equivocation by validators")]
+struct Cli {
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand)]
+enum Cmd {
+    /// Scan storage locations for conflicting checkpoints and print signed
+    /// evidence bundles
+    Scan(ScanArgs),
+    /// Verify an evidence bundle
+    Verify(VerifyArgs),
+}
+
+#[derive(Args)]
+struct ScanArgs {
+    /// Storage location to read checkpoints from, e.g.
+    /// `s3://bucket/region/folder`. Can be repeated.
+    #[arg(long = "location", required = true)]
+    locations: Vec<String>,
+    /// Only look for equivocation by this validator
+    #[arg(long)]
+    validator: Option<String>,
+    /// Lowest checkpoint index to scan
+    #[arg(long, default_value_t = 0)]
+    from_index: u32,
+    /// Highest checkpoint index to scan. Defaults to the highest latest index
+    /// of the storage locations.
+    #[arg(long)]
+    to_index: Option<u32>,
+    /// Environment variable holding the hex private key the evidence
+    /// bundles are signed with
+    #[arg(long, conflicts_with = "key_file")]
+    key_env: Option<String>,
+    /// File holding the hex private key the evidence bundles are signed
+    /// with, with trailing newlines ignored
+    #[arg(long)]
+    key_file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Path to a file with the evidence bundle
+    bundle: PathBuf,
+    /// Also require the bundle to be signed by this address
+    #[arg(long)]
+    reporter: Option<String>,
+}
+
+/// Two checkpoints a validator signed for the same index
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Conflict {
+    /// The storage locations the checkpoints were found at
+    locations: [String; 2],
+    checkpoints: [SignedCheckpointWithMessageId; 2],
+}
+
+/// Proof that `validator` equivocated, signed by the reporter
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct EquivocationEvidence {
+    validator: H160,
+    conflicts: Vec<Conflict>,
+}
+
+impl Signable for EquivocationEvidence {
+    /// Commits to the validator and every conflicting signed checkpoint, but
+    /// not to the locations, which are only hints of where to find them.
+    fn signing_hash(&self) -> H256 {
+        let mut data = self.validator.as_bytes().to_vec();
+        for checkpoint in self.conflicts.iter().flat_map(|c| &c.checkpoints) {
+            data.extend_from_slice(checkpoint.value.signing_hash().as_bytes());
+            data.extend_from_slice(&<[u8; 65]>::from(checkpoint.signature));
+        }
+        keccak256(data).into()
+    }
+}
+
+type SignedEquivocationEvidence = SignedType<EquivocationEvidence>;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    match Cli::parse().cmd {
+        Cmd::Scan(args) => scan(args).await,
+        Cmd::Verify(args) => verify(args),
+    }
+}
+
+async fn scan(args: ScanArgs) -> Result<()> {
+    let validator_filter = args.validator.as_deref().map(parse_h160).transpose()?;
+    let reporter = Signers::build(&SignerConf::HexKey {
+        key: read_key(&args)?,
+    })
+    .await?;
+
+    let mut syncers: Vec<(String, Box<dyn CheckpointSyncer>)> = vec![];
+    for location in args.locations {
+        let syncer = CheckpointSyncerConf::from_str(&location)?
+            .build(None)
+            .await?;
+        syncers.push((location, syncer));
+    }
+    let to_index = match args.to_index {
+        Some(index) => index,
+        None => {
+            let mut highest = None;
+            for (location, syncer) in &syncers {
+                match syncer.latest_index().await {
+                    Ok(index) => highest = highest.max(index),
+                    Err(err) => eprintln!("Failed to read the latest index of {location}: {err}"),
+                }
+            }
+            highest.ok_or_else(|| eyre!("No latest index found, specify `--to-index`"))?
+        }
+    };
+
+    let mut conflicts: BTreeMap<H160, Vec<Conflict>> = BTreeMap::new();
+    for index in args.from_index..=to_index {
+        // The distinct checkpoints found for the index, by their signer
+        let mut found: BTreeMap<H160, Vec<(&str, SignedCheckpointWithMessageId)>> = BTreeMap::new();
+        for (location, syncer) in &syncers {
+            let checkpoint = match syncer.fetch_checkpoint(index).await {
+                Ok(Some(checkpoint)) => checkpoint,
+                Ok(None) => continue,
+                Err(err) => {
+                    eprintln!("Failed to fetch checkpoint {index} from {location}: {err}");
+                    continue;
+                }
+            };
+            let Ok(signer) = checkpoint.recover() else {
+                eprintln!("Invalid signature on checkpoint {index} from {location}");
+                continue;
+            };
+            if validator_filter.map_or(false, |v| v != signer) {
+                continue;
+            }
+            let previous = found.entry(signer).or_default();
+            for (previous_location, previous_checkpoint) in previous.iter() {
+                if is_conflict(previous_checkpoint, &checkpoint) {
+                    conflicts.entry(signer).or_default().push(Conflict {
+                        locations: [previous_location.to_string(), location.clone()],
+                        checkpoints: [previous_checkpoint.clone(), checkpoint.clone()],
+                    });
+                }
+            }
+            if previous.iter().all(|(_, c)| c.value != checkpoint.value) {
+                previous.push((location.as_str(), checkpoint));
+            }
+        }
+    }
+
+    for (validator, conflicts) in conflicts {
+        let evidence = reporter
+            .sign(EquivocationEvidence {
+                validator,
+                conflicts,
+            })
+            .await?;
+        println!("{}", serde_json::to_string(&evidence)?);
+    }
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<()> {
+    let evidence: SignedEquivocationEvidence =
+        serde_json::from_slice(&std::fs::read(&args.bundle)?)?;
+    let reporter = check_evidence(&evidence)?;
+    if let Some(expected) = args.reporter.as_deref().map(parse_h160).transpose()? {
+        if reporter != expected {
+            bail!("Bundle was signed by {reporter:?}, not {expected:?}");
+        }
+    }
+    println!(
+        "{}",
+        json!({
+            "valid": true,
+            "validator": format!("{:?}", evidence.value.validator),
+            "reporter": format!("{reporter:?}"),
+            "conflicts": evidence.value.conflicts.len(),
+        })
+    );
+    Ok(())
+}
+
+/// Checks that every conflict in the bundle is a pair of checkpoints the
+/// validator signed for the same index, returning the reporter.
+fn check_evidence(evidence: &SignedEquivocationEvidence) -> Result<H160> {
+    let reporter = evidence.recover()?;
+    let EquivocationEvidence {
+        validator,
+        conflicts,
+    } = &evidence.value;
+    if conflicts.is_empty() {
+        bail!("Bundle has no conflicts");
+    }
+    for [first, second] in conflicts.iter().map(|c| &c.checkpoints) {
+        for checkpoint in [first, second] {
+            if checkpoint.recover()? != *validator {
+                bail!(
+                    "Checkpoint {} was not signed by {validator:?}",
+                    checkpoint.value.index
+                );
+            }
+        }
+        if !is_conflict(first, second) {
+            bail!(
+                "Checkpoints {} and {} don't conflict",
+                first.value.index,
+                second.value.index
+            );
+        }
+    }
+    Ok(reporter)
+}
+
+/// Whether the checkpoints are for the same index of the same merkle tree but
+/// differ in root or message id
+fn is_conflict(a: &SignedCheckpointWithMessageId, b: &SignedCheckpointWithMessageId) -> bool {
+    let (a, b) = (&a.value, &b.value);
+    a.merkle_tree_hook_address == b.merkle_tree_hook_address
+        && a.mailbox_domain == b.mailbox_domain
+        && a.index == b.index
+        && (a.root != b.root || a.message_id != b.message_id)
+}
+
+fn parse_h160(s: &str) -> Result<H160> {
+    H160::from_str(s.trim_start_matches("0x")).map_err(|e| eyre!("Invalid address `{s}`: {e}"))
+}
+
+/// Read the reporter key from the environment variable or file it is in, so
+/// that it isn't passed on the command line
+fn read_key(args: &ScanArgs) -> Result<H256> {
+    let key = match (&args.key_env, &args.key_file) {
+        (Some(var), _) => {
+            std::env::var(var).map_err(|_| eyre!("Key variable `{var}` is not set"))?
+        }
+        (None, Some(path)) => std::fs::read_to_string(path)
+            .map_err(|e| eyre!("Failed to read key file {}: {e}", path.display()))?,
+        (None, None) => bail!("Either `--key-env` or `--key-file` is required"),
+    };
+    // The key itself is left out of the error
+    H256::from_str(
+        key.trim_end_matches(&['\r', '\n'][..])
+            .trim_start_matches("0x"),
+    )
+    .map_err(|e| eyre!("Invalid hex private key: {e}"))
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, HyperlaneSigner};
+
+    use super::*;
+
+    async fn signer(key: u8) -> Signers {
+        Signers::build(&SignerConf::HexKey {
+            key: H256::repeat_byte(key),
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn signed_checkpoint(signer: &Signers, root: u8) -> SignedCheckpointWithMessageId {
+        signer
+            .sign(CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::repeat_byte(1),
+                    mailbox_domain: 1,
+                    root: H256::repeat_byte(root),
+                    index: 5,
+                },
+                message_id: H256::repeat_byte(2),
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_check_evidence() {
+        let validator = signer(0x11).await;
+        let reporter = signer(0x22).await;
+        let conflict = Conflict {
+            locations: ["file:///a".into(), "file:///b".into()],
+            checkpoints: [
+                signed_checkpoint(&validator, 3).await,
+                signed_checkpoint(&validator, 4).await,
+            ],
+        };
+        let evidence = reporter
+            .sign(EquivocationEvidence {
+                validator: validator.eth_address(),
+                conflicts: vec![conflict.clone()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(check_evidence(&evidence).unwrap(), reporter.eth_address());
+
+        // Survives a round trip through JSON
+        let decoded: SignedEquivocationEvidence =
+            serde_json::from_str(&serde_json::to_string(&evidence).unwrap()).unwrap();
+        assert_eq!(check_evidence(&decoded).unwrap(), reporter.eth_address());
+
+        // The same checkpoint twice is not equivocation
+        let duplicate = reporter
+            .sign(EquivocationEvidence {
+                validator: validator.eth_address(),
+                conflicts: vec![Conflict {
+                    checkpoints: [
+                        conflict.checkpoints[0].clone(),
+                        conflict.checkpoints[0].clone(),
+                    ],
+                    ..conflict.clone()
+                }],
+            })
+            .await
+            .unwrap();
+        assert!(check_evidence(&duplicate).is_err());
+
+        // Nor are checkpoints signed by someone else
+        let framed = reporter
+            .sign(EquivocationEvidence {
+                validator: reporter.eth_address(),
+                conflicts: vec![conflict],
+            })
+            .await
+            .unwrap();
+        assert!(check_evidence(&framed).is_err());
+    }
+}
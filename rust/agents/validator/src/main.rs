@@ -9,8 +9,12 @@ use hyperlane_base::agent_main;
 
 use crate::validator::Validator;
 
+mod announcer;
+mod attester;
 mod server;
 mod settings;
+mod signing_record;
+mod simulation;
 mod submit;
 mod validator;
 
@@ -11,11 +11,15 @@ use eyre::{eyre, Context};
 use hyperlane_base::{
     impl_loadable_from_settings,
     settings::{
+        ipfs_gateway_url,
         parser::{RawAgentConf, RawAgentSignerConf, ValueParser},
         CheckpointSyncerConf, Settings, SignerConf,
     },
+    CheckpointRetentionConf,
+};
+use hyperlane_core::{
+    cfg_unwrap_all, config::*, FinalityTag, HyperlaneDomain, HyperlaneDomainProtocol, H256,
 };
-use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol};
 use serde::Deserialize;
 use serde_json::Value;
 
@@ -30,16 +34,60 @@ pub struct ValidatorSettings {
 
     /// Database path
     pub db: PathBuf,
+    /// Path of the database recording every checkpoint root signed
+    pub signing_db: PathBuf,
     /// Chain to validate messages on
     pub origin_chain: HyperlaneDomain,
     /// The validator attestation signer
     pub validator: SignerConf,
     /// The checkpoint syncer configuration
     pub checkpoint_syncer: CheckpointSyncerConf,
+    /// Checkpoint syncers every checkpoint is also written to, as mirrors of
+    /// the announced one
+    pub checkpoint_syncer_mirrors: Vec<CheckpointSyncerConf>,
     /// The reorg_period in blocks
     pub reorg_period: u64,
+    /// Only sign checkpoints behind this block tag of the origin chain
+    /// instead of lagging the tip by `reorg_period`. Set by the chain's
+    /// `blocks.finalityTag`, or the deprecated `useFinalizedTag`.
+    pub finality_tag: Option<FinalityTag>,
     /// How frequently to check for new checkpoints
     pub interval: Duration,
+    /// Chains other than the origin to announce the storage location on
+    pub announce_chains: Vec<HyperlaneDomain>,
+    /// How frequently to check the announcements on all chains
+    pub announce_interval: Duration,
+    /// Contract to also post the latest signed checkpoint to
+    pub checkpoint_attestation: Option<CheckpointAttestationConf>,
+    /// Rehearse validating the origin without going live, if set
+    pub simulation: Option<SimulationConf>,
+}
+
+/// Config of a validator that indexes and signs checkpoints like a live one,
+/// but only writes them to local storage and never announces itself, so
+/// operators can rehearse on a new chain before going live
+#[derive(Debug, Clone)]
+pub struct SimulationConf {
+    /// Directory the signed checkpoints are written to, in place of the
+    /// checkpoint syncer
+    pub checkpoint_path: PathBuf,
+    /// Validators already live on the origin whose checkpoints the signed
+    /// ones are compared with
+    pub compare_validators: Vec<H256>,
+    /// How frequently to compare checkpoints
+    pub compare_interval: Duration,
+}
+
+/// A CheckpointAttestation contract the validator posts its latest signed
+/// checkpoint to, so its liveness can be verified on-chain
+#[derive(Debug, Clone)]
+pub struct CheckpointAttestationConf {
+    /// Chain the contract is on
+    pub chain: HyperlaneDomain,
+    /// Address of the contract
+    pub address: H256,
+    /// How frequently to post the latest signed checkpoint
+    pub interval: Duration,
 }
 
 #[derive(Debug, Deserialize)]
@@ -64,7 +112,31 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .parse_string()
             .end();
 
-        let origin_chain_name_set = origin_chain_name.map(|s| HashSet::from([s]));
+        let announce_chain_names: Vec<&str> = p
+            .chain(&mut err)
+            .get_opt_key("announceChains")
+            .parse_string()
+            .end()
+            .map(|v| v.split(',').filter(|chain| !chain.is_empty()).collect())
+            .unwrap_or_default();
+
+        let checkpoint_attestation = p.chain(&mut err).get_opt_key("checkpointAttestation").end();
+        let attestation_chain_name = checkpoint_attestation.as_ref().and_then(|attestation| {
+            attestation
+                .chain(&mut err)
+                .get_key("chain")
+                .parse_string()
+                .end()
+        });
+
+        let origin_chain_name_set = origin_chain_name.map(|s| {
+            announce_chain_names
+                .iter()
+                .copied()
+                .chain(attestation_chain_name)
+                .chain([s])
+                .collect::<HashSet<_>>()
+        });
 
         let base: Option<Settings> = p
             .parse_from_raw_config::<Settings, RawAgentConf, Option<&HashSet<&str>>>(
@@ -90,6 +162,9 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
                 "Expected valid validator configuration",
             )
             .end();
+        if let Some(validator) = &validator {
+            reject_ledger(validator, cwp + "validator", &mut err);
+        }
 
         let db = p
             .chain(&mut err)
@@ -101,11 +176,51 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
                     .join(format!("validator_db_{}", origin_chain_name.unwrap_or("")))
             });
 
-        let checkpoint_syncer = p
+        let signing_db = p
             .chain(&mut err)
-            .get_key("checkpointSyncer")
-            .and_then(parse_checkpoint_syncer)
-            .end();
+            .get_opt_key("signingDb")
+            .parse_from_str("Expected signing db file path")
+            .unwrap_or_else(|| {
+                std::env::current_dir().unwrap().join(format!(
+                    "validator_signing_db_{}",
+                    origin_chain_name.unwrap_or("")
+                ))
+            });
+
+        let simulation = p
+            .get_opt_key("simulation")
+            .take_config_err_flat(&mut err)
+            .map(|simulation| parse_simulation(simulation, origin_chain_name, &mut err));
+
+        // Simulated checkpoints are only written locally, so they are never
+        // read by relayers
+        let (checkpoint_syncer, checkpoint_syncer_mirrors) = match &simulation {
+            Some(simulation) => (
+                Some(CheckpointSyncerConf::LocalStorage {
+                    path: simulation.checkpoint_path.clone(),
+                }),
+                vec![],
+            ),
+            None => {
+                let checkpoint_syncer = p
+                    .chain(&mut err)
+                    .get_key("checkpointSyncer")
+                    .and_then(parse_checkpoint_syncer)
+                    .end();
+                let checkpoint_syncer_mirrors = p
+                    .chain(&mut err)
+                    .get_opt_key("checkpointSyncerMirrors")
+                    .into_array_iter()
+                    .map(|itr| {
+                        itr.filter_map(|syncer| {
+                            parse_checkpoint_syncer(syncer).take_config_err(&mut err)
+                        })
+                        .collect()
+                    })
+                    .unwrap_or_default();
+                (checkpoint_syncer, checkpoint_syncer_mirrors)
+            }
+        };
 
         let interval = p
             .chain(&mut err)
@@ -114,6 +229,19 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .map(Duration::from_secs)
             .unwrap_or(Duration::from_secs(5));
 
+        let announce_interval = p
+            .chain(&mut err)
+            .get_opt_key("announceInterval")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60 * 60));
+
+        let use_finalized_tag = p
+            .chain(&mut err)
+            .get_opt_key("useFinalizedTag")
+            .parse_bool()
+            .unwrap_or(false);
+
         cfg_unwrap_all!(cwp, err: [origin_chain_name]);
 
         let reorg_period = p
@@ -125,24 +253,90 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .parse_u64()
             .unwrap_or(1);
 
+        let announce_chains = base
+            .as_ref()
+            .map(|base| {
+                announce_chain_names
+                    .into_iter()
+                    .filter(|chain| *chain != origin_chain_name)
+                    .filter_map(|chain| {
+                        base.lookup_domain(chain)
+                            .context("Missing configuration for an announce chain")
+                            .take_err(&mut err, || cwp + "announceChains")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let checkpoint_attestation = match (&base, checkpoint_attestation) {
+            (Some(base), Some(attestation)) => {
+                let chain = attestation_chain_name.and_then(|chain| {
+                    base.lookup_domain(chain)
+                        .context("Missing configuration for the checkpoint attestation chain")
+                        .take_err(&mut err, || cwp + "checkpoint_attestation.chain")
+                });
+                let address = attestation
+                    .chain(&mut err)
+                    .get_key("address")
+                    .parse_address_hash()
+                    .end();
+                let interval = attestation
+                    .chain(&mut err)
+                    .get_opt_key("interval")
+                    .parse_u64()
+                    .map(Duration::from_secs)
+                    .unwrap_or(Duration::from_secs(5 * 60));
+                chain
+                    .zip(address)
+                    .map(|(chain, address)| CheckpointAttestationConf {
+                        chain,
+                        address,
+                        interval,
+                    })
+            }
+            _ => None,
+        };
+
         cfg_unwrap_all!(cwp, err: [base, origin_chain, validator, checkpoint_syncer]);
 
         let mut base: Settings = base;
-        // If the origin chain is an EVM chain, then we can use the validator as the signer if needed.
-        if origin_chain.domain_protocol() == HyperlaneDomainProtocol::Ethereum {
-            if let Some(origin) = base.chains.get_mut(origin_chain.name()) {
-                origin.signer.get_or_insert_with(|| validator.clone());
+        let finality_tag = if use_finalized_tag {
+            Some(FinalityTag::Finalized)
+        } else {
+            base.chains
+                .get(origin_chain.name())
+                .and_then(|chain| chain.connection.finality_tag())
+        };
+        // If the origin or the attestation chain is an EVM chain, then we can use the validator as
+        // the signer if needed.
+        let signed_chains = std::iter::once(&origin_chain).chain(
+            checkpoint_attestation
+                .as_ref()
+                .map(|attestation| &attestation.chain),
+        );
+        for chain in signed_chains {
+            if chain.domain_protocol() == HyperlaneDomainProtocol::Ethereum {
+                if let Some(conf) = base.chains.get_mut(chain.name()) {
+                    conf.signer.get_or_insert_with(|| validator.clone());
+                }
             }
         }
 
         err.into_result(Self {
             base,
             db,
+            signing_db,
             origin_chain,
             validator,
             checkpoint_syncer,
+            checkpoint_syncer_mirrors,
             reorg_period,
+            finality_tag,
             interval,
+            announce_chains,
+            announce_interval,
+            checkpoint_attestation,
+            simulation,
         })
     }
 }
@@ -180,12 +374,69 @@ fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncer
                 .parse_string()
                 .end()
                 .map(str::to_owned);
+            let batch_checkpoints = syncer
+                .chain(&mut err)
+                .get_opt_key("batchCheckpoints")
+                .parse_bool()
+                .unwrap_or(false);
+            let keep_latest = syncer
+                .chain(&mut err)
+                .get_opt_key("retention")
+                .get_opt_key("keepLatest")
+                .parse_u32()
+                .end();
+            let batch_storage_class = syncer
+                .chain(&mut err)
+                .get_opt_key("retention")
+                .get_opt_key("batchStorageClass")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            if keep_latest.is_some() && !batch_checkpoints {
+                // Pruned checkpoints can only be read from their batch
+                err.push(
+                    &syncer.cwp + "retention" + "keep_latest",
+                    eyre!("Requires `batchCheckpoints`"),
+                );
+            }
 
             cfg_unwrap_all!(&syncer.cwp, err: [bucket, region]);
             err.into_result(CheckpointSyncerConf::S3 {
                 bucket,
                 region,
                 folder,
+                batch_checkpoints,
+                retention: CheckpointRetentionConf {
+                    keep_latest,
+                    batch_storage_class,
+                },
+            })
+        }
+        Some("ipfs") => {
+            let api_url = syncer
+                .chain(&mut err)
+                .get_key("apiUrl")
+                .parse_from_str("Expected IPFS api url")
+                .end();
+            let key = syncer
+                .chain(&mut err)
+                .get_key("key")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let gateway_url = syncer
+                .chain(&mut err)
+                .get_opt_key("gatewayUrl")
+                .parse_from_str("Expected IPFS gateway url")
+                .end()
+                .or_else(|| ipfs_gateway_url().take_err(&mut err, || &syncer.cwp + "gateway_url"));
+
+            cfg_unwrap_all!(&syncer.cwp, err: [api_url, key, gateway_url]);
+            err.into_result(CheckpointSyncerConf::Ipfs {
+                gateway_url,
+                name: None,
+                api_url: Some(api_url),
+                key: Some(key),
             })
         }
         Some(_) => {
@@ -194,3 +445,130 @@ fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncer
         None => Err(err),
     }
 }
+
+/// Parse the simulation config, whose checkpoints are written to a directory
+/// named after the origin by default
+fn parse_simulation(
+    simulation: ValueParser,
+    origin_chain_name: Option<&str>,
+    err: &mut ConfigParsingError,
+) -> SimulationConf {
+    let checkpoint_path = simulation
+        .chain(err)
+        .get_opt_key("path")
+        .parse_from_str("Expected simulated checkpoints directory path")
+        .unwrap_or_else(|| {
+            std::env::current_dir().unwrap().join(format!(
+                "validator_simulation_{}",
+                origin_chain_name.unwrap_or("")
+            ))
+        });
+    let compare_validators = simulation
+        .chain(err)
+        .get_opt_key("compareValidators")
+        .into_array_iter()
+        .map(|itr| {
+            itr.filter_map(|validator| validator.chain(err).parse_address_hash().end())
+                .collect()
+        })
+        .unwrap_or_default();
+    let compare_interval = simulation
+        .chain(err)
+        .get_opt_key("compareInterval")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(60));
+    SimulationConf {
+        checkpoint_path,
+        compare_validators,
+        compare_interval,
+    }
+}
+
+/// A Ledger needs every signature approved on the device, which nobody is
+/// around to do for the checkpoints of a validator. Announcements are rare
+/// enough that the chain signers sending them may be Ledgers.
+fn reject_ledger(signer: &SignerConf, cwp: ConfigPath, err: &mut ConfigParsingError) {
+    if matches!(signer, SignerConf::Ledger { .. }) {
+        err.push(
+            cwp + "type",
+            eyre!("A Ledger cannot sign checkpoints, as it must approve every signature"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::H160;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_simulation() {
+        let validator = H160::repeat_byte(1);
+        let raw = json!({
+            "path": "/tmp/simulation",
+            "comparevalidators": [format!("{validator:?}")],
+            "compareinterval": "30",
+        });
+        let mut err = ConfigParsingError::default();
+        let conf = parse_simulation(
+            ValueParser::new(ConfigPath::default(), &raw),
+            Some("test1"),
+            &mut err,
+        );
+        assert!(err.is_ok());
+        assert_eq!(conf.checkpoint_path, PathBuf::from("/tmp/simulation"));
+        assert_eq!(conf.compare_validators, vec![H256::from(validator)]);
+        assert_eq!(conf.compare_interval, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_parse_simulation_defaults() {
+        let raw = json!({});
+        let mut err = ConfigParsingError::default();
+        let conf = parse_simulation(
+            ValueParser::new(ConfigPath::default(), &raw),
+            Some("test1"),
+            &mut err,
+        );
+        assert!(err.is_ok());
+        assert!(conf.checkpoint_path.ends_with("validator_simulation_test1"));
+        assert!(conf.compare_validators.is_empty());
+        assert_eq!(conf.compare_interval, Duration::from_secs(60));
+
+        let raw = json!({ "comparevalidators": ["0xinvalid"] });
+        let mut err = ConfigParsingError::default();
+        let conf = parse_simulation(
+            ValueParser::new(ConfigPath::default(), &raw),
+            None,
+            &mut err,
+        );
+        assert!(!err.is_ok());
+        assert!(conf.compare_validators.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_ledger() {
+        let mut err = ConfigParsingError::default();
+        reject_ledger(
+            &SignerConf::HexKey {
+                key: H256::repeat_byte(1),
+            },
+            ConfigPath::default() + "validator",
+            &mut err,
+        );
+        assert!(err.is_ok());
+
+        reject_ledger(
+            &SignerConf::Ledger {
+                derivation_path: "m/44'/60'/0'/0/0".into(),
+            },
+            ConfigPath::default() + "validator",
+            &mut err,
+        );
+        let err = err.into_result(()).unwrap_err().to_string();
+        assert!(err.contains("config_path: `validator.type`"), "{err}");
+    }
+}
@@ -0,0 +1,182 @@
+use hyperlane_base::db::{DbError, HyperlaneRocksDB, TypedDB, DB};
+use hyperlane_core::{Checkpoint, HyperlaneDomain, H256};
+
+const SIGNED_ROOT_BY_INDEX: &str = "signed_root_by_index_";
+
+/// Errors produced by the SigningRecord
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum SigningRecordError {
+    /// Error reading or writing the record
+    #[error("{0}")]
+    Db(#[from] DbError),
+    /// A different root was already signed for the index
+    #[error("Already signed root {signed:?} for index {index}, refusing to sign {attempted:?}")]
+    ConflictingRoot {
+        index: u32,
+        signed: H256,
+        attempted: H256,
+    },
+}
+
+/// Local record of every (index, root) the validator has signed, kept in its
+/// own rocksdb so that it outlives resets of the message db. Rocksdb locks
+/// the db, so a second validator instance pointed at the same record fails to
+/// start instead of signing alongside the first one.
+#[derive(Debug, Clone)]
+pub(crate) struct SigningRecord {
+    db: TypedDB,
+    /// Message db roots were recorded in before they had their own db. They
+    /// are only keyed by index, as a validator signs for a single merkle tree
+    /// hook, and are carried over as they're read.
+    legacy: Option<HyperlaneRocksDB>,
+}
+
+impl SigningRecord {
+    pub(crate) fn new(domain: &HyperlaneDomain, db: DB) -> Self {
+        Self {
+            db: TypedDB::new(domain, db),
+            legacy: None,
+        }
+    }
+
+    /// Also refuse to sign roots conflicting with the ones recorded in the
+    /// message db by validators without a signing record db
+    pub(crate) fn with_legacy_roots(mut self, message_db: HyperlaneRocksDB) -> Self {
+        self.legacy = Some(message_db);
+        self
+    }
+
+    /// Key by the merkle tree hook too, as a redeployed hook starts from
+    /// index 0 again.
+    fn key(checkpoint: &Checkpoint) -> Vec<u8> {
+        checkpoint
+            .merkle_tree_hook_address
+            .as_bytes()
+            .iter()
+            .chain(&checkpoint.index.to_be_bytes())
+            .copied()
+            .collect()
+    }
+
+    /// The root signed for the checkpoint's index, if any
+    pub(crate) fn signed_root(&self, checkpoint: &Checkpoint) -> Result<Option<H256>, DbError> {
+        if let Some(root) = self
+            .db
+            .retrieve_decodable(SIGNED_ROOT_BY_INDEX, Self::key(checkpoint))?
+        {
+            return Ok(Some(root));
+        }
+        let Some(legacy) = &self.legacy else {
+            return Ok(None);
+        };
+        let root = legacy.retrieve_signed_checkpoint_root_by_index(&checkpoint.index)?;
+        if let Some(root) = &root {
+            self.db
+                .store_encodable(SIGNED_ROOT_BY_INDEX, Self::key(checkpoint), root)?;
+        }
+        Ok(root)
+    }
+
+    /// Records the checkpoint's root as signed. Must be called before
+    /// signing, so a root is never signed without being recorded. Fails if a
+    /// different root was already signed for the same index.
+    pub(crate) fn record(&self, checkpoint: &Checkpoint) -> Result<(), SigningRecordError> {
+        match self.signed_root(checkpoint)? {
+            Some(signed) if signed != checkpoint.root => Err(SigningRecordError::ConflictingRoot {
+                index: checkpoint.index,
+                signed,
+                attempted: checkpoint.root,
+            }),
+            Some(_) => Ok(()),
+            None => Ok(self.db.store_encodable(
+                SIGNED_ROOT_BY_INDEX,
+                Self::key(checkpoint),
+                &checkpoint.root,
+            )?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_refuses_conflicting_roots() {
+        test_utils::run_test_db(|db| async move {
+            let record =
+                SigningRecord::new(&HyperlaneDomain::Known(KnownHyperlaneDomain::Test1), db);
+            let checkpoint = Checkpoint {
+                merkle_tree_hook_address: H256::repeat_byte(1),
+                mailbox_domain: 1,
+                root: H256::repeat_byte(2),
+                index: 7,
+            };
+
+            assert_eq!(record.signed_root(&checkpoint).unwrap(), None);
+            record.record(&checkpoint).unwrap();
+            // Signing the same root again is fine
+            record.record(&checkpoint).unwrap();
+            assert_eq!(
+                record.signed_root(&checkpoint).unwrap(),
+                Some(checkpoint.root)
+            );
+
+            let conflicting = Checkpoint {
+                root: H256::repeat_byte(3),
+                ..checkpoint
+            };
+            assert!(matches!(
+                record.record(&conflicting),
+                Err(SigningRecordError::ConflictingRoot { index: 7, .. })
+            ));
+
+            // Another merkle tree hook has its own indices
+            let other_hook = Checkpoint {
+                merkle_tree_hook_address: H256::repeat_byte(4),
+                ..conflicting
+            };
+            record.record(&other_hook).unwrap();
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_carries_over_legacy_roots() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Test1);
+            let message_db = HyperlaneRocksDB::new(&domain, db.clone());
+            let checkpoint = Checkpoint {
+                merkle_tree_hook_address: H256::repeat_byte(1),
+                mailbox_domain: 1,
+                root: H256::repeat_byte(2),
+                index: 7,
+            };
+            message_db
+                .store_signed_checkpoint_root_by_index(&checkpoint.index, &checkpoint.root)
+                .unwrap();
+
+            let record = SigningRecord::new(&domain, db.clone()).with_legacy_roots(message_db);
+            let conflicting = Checkpoint {
+                root: H256::repeat_byte(3),
+                ..checkpoint
+            };
+            assert!(matches!(
+                record.record(&conflicting),
+                Err(SigningRecordError::ConflictingRoot { index: 7, .. })
+            ));
+            record.record(&checkpoint).unwrap();
+
+            // The root was carried over to the signing record
+            let record = SigningRecord::new(&domain, db);
+            assert_eq!(
+                record.signed_root(&checkpoint).unwrap(),
+                Some(checkpoint.root)
+            );
+        })
+        .await;
+    }
+}
@@ -0,0 +1,316 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+
+use eyre::Result;
+use prometheus::{IntCounterVec, IntGaugeVec};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use hyperlane_base::{settings::CheckpointSyncerConf, CheckpointSyncer, CoreMetrics};
+use hyperlane_core::{HyperlaneDomain, ValidatorAnnounce, H256};
+
+/// Metrics comparing the checkpoints of a simulated validator with those of
+/// validators already live on its origin
+#[derive(Debug, Clone)]
+pub(crate) struct SimulationMetrics {
+    /// Comparisons of a checkpoint with that of a live validator, by result
+    comparisons: IntCounterVec,
+    /// Index of the latest checkpoint compared with a live validator
+    compared_index: IntGaugeVec,
+}
+
+impl SimulationMetrics {
+    pub(crate) fn new(metrics: &CoreMetrics) -> Result<Self> {
+        Ok(Self {
+            comparisons: metrics.new_int_counter(
+                "validator_simulation_checkpoint_comparisons",
+                "Comparisons of the roots signed by a simulated validator with those of a live validator, by result: `match`, `mismatch` or `unavailable`",
+                &["chain", "validator", "result"],
+            )?,
+            compared_index: metrics.new_int_gauge(
+                "validator_simulation_compared_index",
+                "Index of the latest checkpoint of a simulated validator compared with that of a live validator",
+                &["chain", "validator"],
+            )?,
+        })
+    }
+}
+
+/// Periodically compares the checkpoints a simulated validator signed with
+/// those of validators already live on the origin, at the latest index both
+/// signed, so operators can tell it would sign the same roots once live.
+#[derive(Debug)]
+pub(crate) struct CheckpointComparer {
+    origin: HyperlaneDomain,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    validator_announce: Arc<dyn ValidatorAnnounce>,
+    validators: Vec<H256>,
+    interval: Duration,
+    metrics: SimulationMetrics,
+    /// Checkpoint syncers of the live validators, by storage location
+    live_syncers: HashMap<String, Arc<dyn CheckpointSyncer>>,
+}
+
+impl CheckpointComparer {
+    pub(crate) fn new(
+        origin: HyperlaneDomain,
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        validator_announce: Arc<dyn ValidatorAnnounce>,
+        validators: Vec<H256>,
+        interval: Duration,
+        metrics: SimulationMetrics,
+    ) -> Self {
+        Self {
+            origin,
+            checkpoint_syncer,
+            validator_announce,
+            validators,
+            interval,
+            metrics,
+            live_syncers: HashMap::new(),
+        }
+    }
+
+    pub(crate) async fn run(mut self) {
+        if self.validators.is_empty() {
+            info!("No live validators to compare simulated checkpoints with");
+            return;
+        }
+        loop {
+            if let Err(err) = self.compare_checkpoints().await {
+                warn!(?err, "Failed to compare simulated checkpoints");
+            }
+            sleep(self.interval).await;
+        }
+    }
+
+    async fn compare_checkpoints(&mut self) -> Result<()> {
+        let Some(own_index) = self.checkpoint_syncer.latest_index().await? else {
+            debug!("No checkpoint signed yet, nothing to compare");
+            return Ok(());
+        };
+        let storage_locations = self
+            .validator_announce
+            .get_announced_storage_locations(&self.validators)
+            .await?;
+        let validators = self.validators.clone();
+        for (validator, locations) in validators.iter().zip(storage_locations) {
+            // The latest announced location is the one relayers read
+            let Some(location) = locations.last() else {
+                warn!(
+                    ?validator,
+                    "Live validator has no announced storage location"
+                );
+                self.record(validator, "unavailable");
+                continue;
+            };
+            if let Err(err) = self.compare_with(validator, location, own_index).await {
+                warn!(
+                    ?validator,
+                    location,
+                    ?err,
+                    "Failed to compare with live validator"
+                );
+                self.record(validator, "unavailable");
+            }
+        }
+        Ok(())
+    }
+
+    /// Compare the checkpoint at the latest index both the simulated and
+    /// the live validator signed
+    async fn compare_with(
+        &mut self,
+        validator: &H256,
+        location: &str,
+        own_index: u32,
+    ) -> Result<()> {
+        let syncer = self.live_syncer(location).await?;
+        let Some(live_index) = syncer.latest_index().await? else {
+            self.record(validator, "unavailable");
+            return Ok(());
+        };
+        let index = own_index.min(live_index);
+        let own = self.checkpoint_syncer.fetch_checkpoint(index).await?;
+        let live = syncer.fetch_checkpoint(index).await?;
+        let (Some(own), Some(live)) = (own, live) else {
+            debug!(?validator, index, "Checkpoint missing, not comparing");
+            self.record(validator, "unavailable");
+            return Ok(());
+        };
+        if own.value == live.value {
+            debug!(?validator, index, "Simulated checkpoint matches");
+            self.record(validator, "match");
+        } else {
+            warn!(
+                ?validator,
+                index,
+                simulated = ?own.value,
+                live = ?live.value,
+                "Simulated checkpoint differs from live validator"
+            );
+            self.record(validator, "mismatch");
+        }
+        self.metrics
+            .compared_index
+            .with_label_values(&[self.origin.name(), &format!("{validator:?}")])
+            .set(index as i64);
+        Ok(())
+    }
+
+    async fn live_syncer(&mut self, location: &str) -> Result<Arc<dyn CheckpointSyncer>> {
+        if let Some(syncer) = self.live_syncers.get(location) {
+            return Ok(syncer.clone());
+        }
+        let syncer: Arc<dyn CheckpointSyncer> = CheckpointSyncerConf::from_str(location)?
+            .build(None)
+            .await?
+            .into();
+        self.live_syncers
+            .insert(location.to_owned(), syncer.clone());
+        Ok(syncer)
+    }
+
+    fn record(&self, validator: &H256, result: &str) {
+        self.metrics
+            .comparisons
+            .with_label_values(&[self.origin.name(), &format!("{validator:?}"), result])
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use hyperlane_base::LocalStorage;
+    use hyperlane_core::{
+        Checkpoint, CheckpointWithMessageId, KnownHyperlaneDomain, Signature,
+        SignedCheckpointWithMessageId, U256,
+    };
+    use hyperlane_test::mocks::MockValidatorAnnounceContract;
+    use prometheus::Registry;
+
+    use super::*;
+
+    const MATCHING: H256 = H256::repeat_byte(1);
+    const MISMATCHING: H256 = H256::repeat_byte(2);
+    const UNANNOUNCED: H256 = H256::repeat_byte(3);
+
+    fn checkpoint(index: u32, root: H256) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::repeat_byte(4),
+                    mailbox_domain: 13371,
+                    root,
+                    index,
+                },
+                message_id: H256::repeat_byte(5),
+            },
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
+        }
+    }
+
+    /// A checkpoint syncer in a fresh directory, with checkpoints of `root`
+    /// up to `latest_index`, and the `file://` location it is announced at
+    async fn checkpoint_syncer(
+        name: &str,
+        latest_index: u32,
+        root: H256,
+    ) -> (Arc<LocalStorage>, String) {
+        let path: PathBuf =
+            std::env::temp_dir().join(format!("simulation_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let syncer = LocalStorage::new(path.clone(), None).unwrap();
+        for index in 0..=latest_index {
+            syncer
+                .write_checkpoint(&checkpoint(index, root))
+                .await
+                .unwrap();
+        }
+        syncer.write_latest_index(latest_index).await.unwrap();
+        (Arc::new(syncer), format!("file://{}", path.display()))
+    }
+
+    fn comparisons(metrics: &SimulationMetrics, validator: H256, result: &str) -> u64 {
+        metrics
+            .comparisons
+            .with_label_values(&["test1", &format!("{validator:?}"), result])
+            .get()
+    }
+
+    #[tokio::test]
+    async fn test_compares_with_live_validators() {
+        let (own, _) = checkpoint_syncer("own", 5, H256::repeat_byte(6)).await;
+        let (_, matching) = checkpoint_syncer("matching", 3, H256::repeat_byte(6)).await;
+        let (_, mismatching) = checkpoint_syncer("mismatching", 7, H256::repeat_byte(7)).await;
+
+        let mut validator_announce = MockValidatorAnnounceContract::new();
+        validator_announce
+            .expect__get_announced_storage_locations()
+            .returning(move |validators| {
+                assert_eq!(validators, [MATCHING, MISMATCHING, UNANNOUNCED]);
+                Ok(vec![
+                    vec![matching.clone()],
+                    vec!["file:///unused".to_owned(), mismatching.clone()],
+                    vec![],
+                ])
+            });
+        let metrics =
+            SimulationMetrics::new(&CoreMetrics::new("test", 9090, Registry::new()).unwrap())
+                .unwrap();
+        let mut comparer = CheckpointComparer::new(
+            HyperlaneDomain::Known(KnownHyperlaneDomain::Test1),
+            own,
+            Arc::new(validator_announce),
+            vec![MATCHING, MISMATCHING, UNANNOUNCED],
+            Duration::from_secs(60),
+            metrics.clone(),
+        );
+
+        comparer.compare_checkpoints().await.unwrap();
+
+        assert_eq!(comparisons(&metrics, MATCHING, "match"), 1);
+        assert_eq!(comparisons(&metrics, MISMATCHING, "mismatch"), 1);
+        assert_eq!(comparisons(&metrics, UNANNOUNCED, "unavailable"), 1);
+        assert_eq!(comparisons(&metrics, MATCHING, "mismatch"), 0);
+        // Compared at the latest index both validators signed
+        let compared_index = |validator: H256| {
+            metrics
+                .compared_index
+                .with_label_values(&["test1", &format!("{validator:?}")])
+                .get()
+        };
+        assert_eq!(compared_index(MATCHING), 3);
+        assert_eq!(compared_index(MISMATCHING), 5);
+    }
+
+    #[tokio::test]
+    async fn test_skips_comparison_without_own_checkpoint() {
+        let path = std::env::temp_dir().join(format!("simulation_empty_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let own = Arc::new(LocalStorage::new(path, None).unwrap());
+
+        // The announced locations are not even fetched
+        let validator_announce = MockValidatorAnnounceContract::new();
+        let metrics =
+            SimulationMetrics::new(&CoreMetrics::new("test", 9090, Registry::new()).unwrap())
+                .unwrap();
+        let mut comparer = CheckpointComparer::new(
+            HyperlaneDomain::Known(KnownHyperlaneDomain::Test1),
+            own,
+            Arc::new(validator_announce),
+            vec![MATCHING],
+            Duration::from_secs(60),
+            metrics.clone(),
+        );
+
+        comparer.compare_checkpoints().await.unwrap();
+
+        assert_eq!(comparisons(&metrics, MATCHING, "unavailable"), 0);
+    }
+}
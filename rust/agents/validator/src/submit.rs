@@ -1,10 +1,10 @@
 use std::num::NonZeroU64;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::vec;
 
 use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
-use hyperlane_core::{ChainResult, MerkleTreeHook};
+use hyperlane_core::{ChainCommunicationError, ChainResult, FinalityTag, MerkleTreeHook};
 use prometheus::IntGauge;
 use tokio::time::sleep;
 use tracing::{debug, error, info};
@@ -16,34 +16,80 @@ use hyperlane_core::{
 };
 use hyperlane_ethereum::SingletonSignerHandle;
 
+use crate::signing_record::{SigningRecord, SigningRecordError};
+
+/// How deep a merkle tree insertion has to be before the validator signs a
+/// checkpoint including it.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum CheckpointFinality {
+    /// Lag the chain tip by this many blocks
+    ReorgPeriod(Option<NonZeroU64>),
+    /// Only sign what is behind this block tag of the chain
+    Tag(FinalityTag),
+}
+
+impl CheckpointFinality {
+    pub(crate) fn new(reorg_period: u64, finality_tag: Option<FinalityTag>) -> Self {
+        match finality_tag {
+            Some(tag) => Self::Tag(tag),
+            None => Self::ReorgPeriod(NonZeroU64::new(reorg_period)),
+        }
+    }
+
+    /// The latest checkpoint that is final enough to sign
+    pub(crate) async fn latest_checkpoint(
+        &self,
+        merkle_tree_hook: &dyn MerkleTreeHook,
+    ) -> ChainResult<Checkpoint> {
+        match self {
+            Self::ReorgPeriod(lag) => merkle_tree_hook.latest_checkpoint(*lag).await,
+            Self::Tag(tag) => merkle_tree_hook.latest_finalized_checkpoint(*tag).await,
+        }
+    }
+
+    /// The merkle tree as of the latest block that is final enough to sign
+    pub(crate) async fn tree(
+        &self,
+        merkle_tree_hook: &dyn MerkleTreeHook,
+    ) -> ChainResult<IncrementalMerkle> {
+        match self {
+            Self::ReorgPeriod(lag) => merkle_tree_hook.tree(*lag).await,
+            Self::Tag(tag) => merkle_tree_hook.finalized_tree(*tag).await,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct ValidatorSubmitter {
     interval: Duration,
-    reorg_period: Option<NonZeroU64>,
+    finality: CheckpointFinality,
     signer: SingletonSignerHandle,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
     message_db: HyperlaneRocksDB,
+    signing_record: SigningRecord,
     metrics: ValidatorSubmitterMetrics,
 }
 
 impl ValidatorSubmitter {
     pub(crate) fn new(
         interval: Duration,
-        reorg_period: u64,
+        finality: CheckpointFinality,
         merkle_tree_hook: Arc<dyn MerkleTreeHook>,
         signer: SingletonSignerHandle,
         checkpoint_syncer: Arc<dyn CheckpointSyncer>,
         message_db: HyperlaneRocksDB,
+        signing_record: SigningRecord,
         metrics: ValidatorSubmitterMetrics,
     ) -> Self {
         Self {
-            reorg_period: NonZeroU64::new(reorg_period),
+            finality,
             interval,
             merkle_tree_hook,
             signer,
             checkpoint_syncer,
             message_db,
+            signing_record,
             metrics,
         }
     }
@@ -90,10 +136,12 @@ impl ValidatorSubmitter {
         };
 
         loop {
-            // Lag by reorg period because this is our correctness checkpoint.
+            // Lag by reorg period (or only look at finalized blocks) because this
+            // is our correctness checkpoint.
             let latest_checkpoint = call_and_retry_indefinitely(|| {
                 let merkle_tree_hook = self.merkle_tree_hook.clone();
-                Box::pin(async move { merkle_tree_hook.latest_checkpoint(self.reorg_period).await })
+                let finality = self.finality;
+                Box::pin(async move { finality.latest_checkpoint(merkle_tree_hook.as_ref()).await })
             })
             .await;
 
@@ -101,6 +149,15 @@ impl ValidatorSubmitter {
                 .latest_checkpoint_observed
                 .set(latest_checkpoint.index as i64);
 
+            // The unlagged tip, to tell how far signing is behind the chain
+            match self.merkle_tree_hook.count(None).await {
+                Ok(count) if count > 0 => {
+                    self.metrics.latest_merkle_index.set(count as i64 - 1);
+                }
+                Ok(_) => {}
+                Err(err) => debug!(?err, "Failed to get the merkle tree count at the tip"),
+            }
+
             if should_log_checkpoint_info() {
                 info!(
                     ?latest_checkpoint,
@@ -187,9 +244,13 @@ impl ValidatorSubmitter {
                 // If we haven't yet indexed the next merkle tree insertion but know that
                 // it will soon exist (because we know the correctness checkpoint), wait a bit and
                 // try again.
+                self.metrics
+                    .missing_insertions
+                    .set((correctness_checkpoint.index + 1 - tree.count() as u32) as i64);
                 sleep(Duration::from_millis(100)).await
             }
         }
+        self.metrics.missing_insertions.set(0);
 
         // At this point we know that correctness_checkpoint.index == tree.index().
         assert_eq!(
@@ -236,6 +297,28 @@ impl ValidatorSubmitter {
             .checkpoint_syncer
             .fetch_checkpoint(checkpoint.index)
             .await?;
+        // Signing a different root for an index we have already signed is
+        // equivocation, which most likely means the origin chain reorged
+        // deeper than the configured finality. Refuse and bail loudly.
+        if let Some(existing) = &existing {
+            if existing.value.root != checkpoint.root {
+                error!(
+                    ?checkpoint,
+                    previous_root = ?existing.value.root,
+                    "Refusing to sign a conflicting root for an already submitted index"
+                );
+                panic!("Refusing to sign a conflicting root for an already submitted index");
+            }
+        }
+        // Record the root before signing so that it is never signed without
+        // being persisted, even if the validator dies right after.
+        match self.signing_record.record(&checkpoint.checkpoint) {
+            Err(err @ SigningRecordError::ConflictingRoot { .. }) => {
+                error!(?checkpoint, ?err, "Refusing to sign a conflicting root");
+                panic!("Refusing to sign a conflicting root: {err}");
+            }
+            recorded => recorded.map_err(ChainCommunicationError::from_other)?,
+        }
         if existing.is_some() {
             debug!(index = checkpoint.index, "Checkpoint already submitted");
             return Ok(());
@@ -245,6 +328,9 @@ impl ValidatorSubmitter {
             .write_checkpoint(&signed_checkpoint)
             .await?;
         debug!(index = checkpoint.index, "Signed and submitted checkpoint");
+        self.metrics
+            .last_signature_timestamp
+            .set(unix_timestamp_s() as i64);
 
         // TODO: move these into S3 implementations
         // small sleep before signing next checkpoint to avoid rate limiting
@@ -286,6 +372,13 @@ impl ValidatorSubmitter {
     }
 }
 
+fn unix_timestamp_s() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
 /// Returns whether the tree exceeds the checkpoint.
 fn tree_exceeds_checkpoint(checkpoint: &Checkpoint, tree: &IncrementalMerkle) -> bool {
     // tree.index() will panic if the tree is empty, so we use tree.count() instead
@@ -293,22 +386,42 @@ fn tree_exceeds_checkpoint(checkpoint: &Checkpoint, tree: &IncrementalMerkle) ->
     checkpoint.index + 1 < tree.count() as u32
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub(crate) struct ValidatorSubmitterMetrics {
     latest_checkpoint_observed: IntGauge,
     latest_checkpoint_processed: IntGauge,
+    latest_merkle_index: IntGauge,
+    last_signature_timestamp: IntGauge,
+    missing_insertions: IntGauge,
 }
 
 impl ValidatorSubmitterMetrics {
-    pub fn new(metrics: &CoreMetrics, mailbox_chain: &HyperlaneDomain) -> Self {
+    pub fn new(metrics: &CoreMetrics, mailbox_chain: &HyperlaneDomain) -> eyre::Result<Self> {
         let chain_name = mailbox_chain.name();
-        Self {
+        Ok(Self {
             latest_checkpoint_observed: metrics
                 .latest_checkpoint()
                 .with_label_values(&["validator_observed", chain_name]),
             latest_checkpoint_processed: metrics
                 .latest_checkpoint()
                 .with_label_values(&["validator_processed", chain_name]),
-        }
+            latest_merkle_index: metrics
+                .latest_checkpoint()
+                .with_label_values(&["validator_tip", chain_name]),
+            last_signature_timestamp: metrics
+                .new_int_gauge(
+                    "validator_last_signature_timestamp",
+                    "Unix timestamp in seconds of the last checkpoint the validator signed",
+                    &["chain"],
+                )?
+                .with_label_values(&[chain_name]),
+            missing_insertions: metrics
+                .new_int_gauge(
+                    "validator_missing_merkle_insertions",
+                    "Number of indices up to the latest checkpoint whose merkle tree insertion has not been indexed, blocking signing",
+                    &["chain"],
+                )?
+                .with_label_values(&[chain_name]),
+        })
     }
 }
@@ -1,32 +1,37 @@
-use std::{num::NonZeroU64, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use crate::server as validator_server;
 use async_trait::async_trait;
 use derive_more::AsRef;
-use eyre::Result;
+use eyre::{bail, Result};
 
 use futures_util::future::try_join_all;
 use tokio::{task::JoinHandle, time::sleep};
-use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument};
+use tracing::{error, info, info_span, instrument::Instrumented, Instrument};
 
 use hyperlane_base::{
-    db::{HyperlaneRocksDB, DB},
+    check_rocks_db,
+    db::{DbMaintenance, HyperlaneRocksDB, DB, HYPERLANE_DB_MIGRATIONS},
     metrics::AgentMetrics,
     settings::ChainConf,
     BaseAgent, ChainMetrics, CheckpointSyncer, ContractSyncMetrics, ContractSyncer, CoreMetrics,
-    HyperlaneAgentCore, MetricsUpdater, SequencedDataContractSync,
+    DoctorReport, HyperlaneAgentCore, MetricsUpdater, RedundantCheckpointSyncer,
+    SequencedDataContractSync,
 };
 
 use hyperlane_core::{
-    Announcement, ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSigner,
-    HyperlaneSignerExt, Mailbox, MerkleTreeHook, MerkleTreeInsertion, TxOutcome, ValidatorAnnounce,
-    H256, U256,
+    Announcement, FinalityTag, HyperlaneContract, HyperlaneDomain, HyperlaneSigner,
+    HyperlaneSignerExt, MerkleTreeHook, MerkleTreeInsertion, SignedAnnouncement,
 };
-use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
+use hyperlane_ethereum::{Signers, SingletonSigner, SingletonSignerHandle};
 
 use crate::{
+    announcer::{AnnouncementChain, ValidatorAnnouncer},
+    attester::CheckpointAttester,
     settings::ValidatorSettings,
-    submit::{ValidatorSubmitter, ValidatorSubmitterMetrics},
+    signing_record::SigningRecord,
+    simulation::{CheckpointComparer, SimulationMetrics},
+    submit::{CheckpointFinality, ValidatorSubmitter, ValidatorSubmitterMetrics},
 };
 
 /// A validator agent
@@ -37,17 +42,26 @@ pub struct Validator {
     #[as_ref]
     core: HyperlaneAgentCore,
     db: HyperlaneRocksDB,
+    signing_record: SigningRecord,
+    /// Taken when `run` is called
+    db_maintenance: Option<DbMaintenance>,
     merkle_tree_hook_sync: Arc<SequencedDataContractSync<MerkleTreeInsertion>>,
-    mailbox: Arc<dyn Mailbox>,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
-    validator_announce: Arc<dyn ValidatorAnnounce>,
+    announcer: ValidatorAnnouncer,
+    /// Taken when `run` is called
+    attester: Option<CheckpointAttester>,
+    /// Set when simulating, in which case the validator isn't announced.
+    /// Taken when `run` is called.
+    simulation: Option<CheckpointComparer>,
     signer: SingletonSignerHandle,
     // temporary holder until `run` is called
     signer_instance: Option<Box<SingletonSigner>>,
     reorg_period: u64,
+    finality_tag: Option<FinalityTag>,
     interval: Duration,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
     core_metrics: Arc<CoreMetrics>,
+    submitter_metrics: ValidatorSubmitterMetrics,
     agent_metrics: AgentMetrics,
     chain_metrics: ChainMetrics,
 }
@@ -69,26 +83,38 @@ impl BaseAgent for Validator {
         Self: Sized,
     {
         let db = DB::from_path(&settings.db)?;
-        let msg_db = HyperlaneRocksDB::new(&settings.origin_chain, db);
+        db.migrate(HYPERLANE_DB_MIGRATIONS)?;
+        let msg_db = HyperlaneRocksDB::new(&settings.origin_chain, db.clone());
+        // Opening takes the db lock, so only one validator can use the record
+        let signing_db = DB::from_path(&settings.signing_db)?;
+        let signing_record = SigningRecord::new(&settings.origin_chain, signing_db.clone())
+            .with_legacy_roots(msg_db.clone());
+        let db_maintenance = DbMaintenance::new(settings.db_maintenance.clone(), &metrics)?
+            .with_db(Self::AGENT_NAME, db)
+            .with_db("signing_record", signing_db);
 
         // Intentionally using hyperlane_ethereum for the validator's signer
         let (signer_instance, signer) = SingletonSigner::new(settings.validator.build().await?);
 
         let core = settings.build_hyperlane_core(metrics.clone());
-        let checkpoint_syncer = settings.checkpoint_syncer.build(None).await?.into();
-
-        let mailbox = settings
-            .build_mailbox(&settings.origin_chain, &metrics)
-            .await?;
+        let checkpoint_syncer: Arc<dyn CheckpointSyncer> =
+            if settings.checkpoint_syncer_mirrors.is_empty() {
+                settings.checkpoint_syncer.build(None).await?.into()
+            } else {
+                let mut mirrors = Vec::with_capacity(settings.checkpoint_syncer_mirrors.len());
+                for mirror in &settings.checkpoint_syncer_mirrors {
+                    mirrors.push(mirror.build(None).await?);
+                }
+                Arc::new(RedundantCheckpointSyncer::new(
+                    settings.checkpoint_syncer.build(None).await?,
+                    mirrors,
+                ))
+            };
 
         let merkle_tree_hook = settings
             .build_merkle_tree_hook(&settings.origin_chain, &metrics)
             .await?;
 
-        let validator_announce = settings
-            .build_validator_announce(&settings.origin_chain, &metrics)
-            .await?;
-
         let origin_chain_conf = core
             .settings
             .chain_setup(&settings.origin_chain)
@@ -106,22 +132,79 @@ impl BaseAgent for Validator {
             )
             .await?;
 
+        let submitter_metrics = ValidatorSubmitterMetrics::new(&metrics, &settings.origin_chain)?;
+
+        let mut announcement_chains = Vec::with_capacity(settings.announce_chains.len() + 1);
+        for domain in std::iter::once(&settings.origin_chain).chain(&settings.announce_chains) {
+            announcement_chains.push(AnnouncementChain {
+                domain: domain.clone(),
+                conf: core.settings.chain_setup(domain)?.clone(),
+                validator_announce: settings
+                    .build_validator_announce(domain, &metrics)
+                    .await?
+                    .into(),
+            });
+        }
+        let origin_announcement_chain = announcement_chains.remove(0);
+        let simulation = match &settings.simulation {
+            Some(simulation) => Some(CheckpointComparer::new(
+                settings.origin_chain.clone(),
+                checkpoint_syncer.clone(),
+                origin_announcement_chain.validator_announce.clone(),
+                simulation.compare_validators.clone(),
+                simulation.compare_interval,
+                SimulationMetrics::new(&metrics)?,
+            )),
+            None => None,
+        };
+        let announcer = ValidatorAnnouncer::new(
+            origin_announcement_chain,
+            announcement_chains,
+            signer.clone(),
+            checkpoint_syncer.clone(),
+            settings.interval,
+            settings.announce_interval,
+        );
+
+        // A simulated validator isn't live, so doesn't attest its liveness
+        let attester = match &settings.checkpoint_attestation {
+            Some(attestation) if simulation.is_none() => Some(CheckpointAttester::new(
+                core.settings
+                    .chain_setup(&attestation.chain)?
+                    .build_checkpoint_attestation(attestation.address, &metrics)
+                    .await?
+                    .into(),
+                checkpoint_syncer.clone(),
+                signer.eth_address().into(),
+                &settings.origin_chain,
+                merkle_tree_hook.address(),
+                attestation.interval,
+                &metrics,
+            )),
+            _ => None,
+        };
+
         Ok(Self {
             origin_chain: settings.origin_chain,
             origin_chain_conf,
             core,
             db: msg_db,
-            mailbox: mailbox.into(),
+            signing_record,
+            db_maintenance: Some(db_maintenance),
             merkle_tree_hook: merkle_tree_hook.into(),
             merkle_tree_hook_sync,
-            validator_announce: validator_announce.into(),
+            announcer,
+            attester,
+            simulation,
             signer,
             signer_instance: Some(Box::new(signer_instance)),
             reorg_period: settings.reorg_period,
+            finality_tag: settings.finality_tag,
             interval: settings.interval,
             checkpoint_syncer,
             agent_metrics,
             chain_metrics,
+            submitter_metrics,
             core_metrics: metrics,
         })
     }
@@ -144,6 +227,10 @@ impl BaseAgent for Validator {
         .instrument(info_span!("Validator server"));
         tasks.push(server_task);
 
+        if let Some(db_maintenance) = self.db_maintenance.take() {
+            tasks.push(db_maintenance.spawn());
+        }
+
         if let Some(signer_instance) = self.signer_instance.take() {
             tasks.push(
                 tokio::spawn(async move {
@@ -169,15 +256,47 @@ impl BaseAgent for Validator {
             .instrument(info_span!("MetricsUpdater")),
         );
 
-        // announce the validator after spawning the signer task
-        self.announce().await.expect("Failed to announce validator");
+        if let Some(comparer) = self.simulation.take() {
+            info!("Simulating: checkpoints are only written locally and the validator is never announced");
+            tasks.push(
+                tokio::spawn(async move { comparer.run().await })
+                    .instrument(info_span!("CheckpointComparer")),
+            );
+        } else {
+            // announce the validator after spawning the signer task
+            self.announcer
+                .announce_origin()
+                .await
+                .expect("Failed to announce validator");
+
+            let announcer = self.announcer.clone();
+            tasks.push(
+                tokio::spawn(async move { announcer.run().await })
+                    .instrument(info_span!("ValidatorAnnouncer")),
+            );
+        }
 
-        let reorg_period = NonZeroU64::new(self.reorg_period);
+        if let Some(attester) = self.attester.take() {
+            tasks.push(
+                tokio::spawn(async move { attester.run().await })
+                    .instrument(info_span!("CheckpointAttester")),
+            );
+        }
+
+        let finality = CheckpointFinality::new(self.reorg_period, self.finality_tag);
 
         // Ensure that the merkle tree hook has count > 0 before we begin indexing
         // messages or submitting checkpoints.
         loop {
-            match self.merkle_tree_hook.count(reorg_period).await {
+            let count = match finality {
+                CheckpointFinality::ReorgPeriod(lag) => self.merkle_tree_hook.count(lag).await,
+                CheckpointFinality::Tag(tag) => self
+                    .merkle_tree_hook
+                    .finalized_tree(tag)
+                    .await
+                    .map(|tree| tree.count() as u32),
+            };
+            match count {
                 Ok(0) => {
                     info!("Waiting for first message in merkle tree hook");
                     sleep(self.interval).await;
@@ -201,6 +320,46 @@ impl BaseAgent for Validator {
             error!(?err, "One of the validator tasks returned an error");
         }
     }
+
+    async fn doctor(
+        settings: &Self::Settings,
+        _metrics: Arc<CoreMetrics>,
+        report: &mut DoctorReport,
+    ) {
+        report.record("db", "rocksdb", check_rocks_db(&settings.db));
+        report.record(
+            "signing record",
+            "rocksdb",
+            check_rocks_db(&settings.signing_db),
+        );
+
+        let checkpoint_syncer = match settings.checkpoint_syncer.build(None).await {
+            Ok(checkpoint_syncer) => checkpoint_syncer,
+            Err(err) => {
+                report.record("checkpoint syncer", "read", Err(err));
+                return;
+            }
+        };
+        let signer_check = sign_announcement(settings, checkpoint_syncer.announcement_location())
+            .await
+            .map(|signed_announcement| {
+                format!("{:?} can sign", signed_announcement.value.validator)
+            });
+        report.record("validator", "signer", signer_check);
+
+        report.record(
+            "checkpoint syncer",
+            "read",
+            check_checkpoint_syncer(&*checkpoint_syncer).await,
+        );
+        for (i, mirror) in settings.checkpoint_syncer_mirrors.iter().enumerate() {
+            let result = match mirror.build(None).await {
+                Ok(mirror) => check_checkpoint_syncer(&*mirror).await,
+                Err(err) => Err(err),
+            };
+            report.record(format!("checkpoint mirror {}", i + 1), "read", result);
+        }
+    }
 }
 
 impl Validator {
@@ -219,20 +378,20 @@ impl Validator {
     }
 
     async fn run_checkpoint_submitters(&self) -> Vec<Instrumented<JoinHandle<()>>> {
+        let finality = CheckpointFinality::new(self.reorg_period, self.finality_tag);
         let submitter = ValidatorSubmitter::new(
             self.interval,
-            self.reorg_period,
+            finality,
             self.merkle_tree_hook.clone(),
             self.signer.clone(),
             self.checkpoint_syncer.clone(),
             self.db.clone(),
-            ValidatorSubmitterMetrics::new(&self.core.metrics, &self.origin_chain),
+            self.signing_record.clone(),
+            self.submitter_metrics.clone(),
         );
 
-        let reorg_period = NonZeroU64::new(self.reorg_period);
-        let tip_tree = self
-            .merkle_tree_hook
-            .tree(reorg_period)
+        let tip_tree = finality
+            .tree(self.merkle_tree_hook.as_ref())
             .await
             .expect("failed to get merkle tree");
         // This function is only called after we have already checked that the
@@ -260,110 +419,41 @@ impl Validator {
 
         tasks
     }
+}
 
-    fn log_on_announce_failure(result: ChainResult<TxOutcome>, chain_signer: &String) {
-        match result {
-            Ok(outcome) => {
-                if outcome.executed {
-                    info!(
-                        tx_outcome=?outcome,
-                        ?chain_signer,
-                        "Successfully announced validator",
-                    );
-                } else {
-                    error!(
-                        txid=?outcome.transaction_id,
-                        gas_used=?outcome.gas_used,
-                        gas_price=?outcome.gas_price,
-                        ?chain_signer,
-                        "Transaction attempting to announce validator reverted. Make sure you have enough funds in your account to pay for transaction fees."
-                    );
-                }
-            }
-            Err(err) => {
-                error!(
-                    ?err,
-                    ?chain_signer,
-                    "Failed to announce validator. Make sure you have enough funds in your account to pay for gas."
-                );
-            }
-        }
-    }
-
-    async fn announce(&self) -> Result<()> {
-        let address = self.signer.eth_address();
-        let announcement_location = self.checkpoint_syncer.announcement_location();
-
-        // Sign and post the validator announcement
-        let announcement = Announcement {
-            validator: address,
-            mailbox_address: self.mailbox.address(),
-            mailbox_domain: self.mailbox.domain().id(),
-            storage_location: announcement_location.clone(),
-        };
-        let signed_announcement = self.signer.sign(announcement.clone()).await?;
-        self.checkpoint_syncer
-            .write_announcement(&signed_announcement)
-            .await?;
-
-        // Ensure that the validator has announced themselves before we enter
-        // the main validator submit loop. This is to avoid a situation in
-        // which the validator is signing checkpoints but has not announced
-        // their locations, which makes them functionally unusable.
-        let validators: [H256; 1] = [address.into()];
-        loop {
-            info!("Checking for validator announcement");
-            if let Some(locations) = self
-                .validator_announce
-                .get_announced_storage_locations(&validators)
-                .await?
-                .first()
-            {
-                if locations.contains(&announcement_location) {
-                    info!(
-                        ?locations,
-                        ?announcement_location,
-                        "Validator has announced signature storage location"
-                    );
-                    break;
-                }
-                info!(
-                    announced_locations=?locations,
-                    "Validator has not announced signature storage location"
-                );
-
-                if let Some(chain_signer) = self.core.settings.chains[self.origin_chain.name()]
-                    .chain_signer()
-                    .await?
-                {
-                    let chain_signer = chain_signer.address_string();
-                    info!(eth_validator_address=?announcement.validator, ?chain_signer, "Attempting self announce");
-                    let balance_delta = self
-                        .validator_announce
-                        .announce_tokens_needed(signed_announcement.clone())
-                        .await
-                        .unwrap_or_default();
-                    if balance_delta > U256::zero() {
-                        warn!(
-                            tokens_needed=%balance_delta,
-                            eth_validator_address=?announcement.validator,
-                            ?chain_signer,
-                            "Please send tokens to your chain signer address to announce",
-                        );
-                    } else {
-                        let result = self
-                            .validator_announce
-                            .announce(signed_announcement.clone())
-                            .await;
-                        Self::log_on_announce_failure(result, &chain_signer);
-                    }
-                } else {
-                    warn!(origin_chain=%self.origin_chain, "Cannot announce validator without a signer; make sure a signer is set for the origin chain");
-                }
+/// The announcement of the storage location on the origin, signed with the
+/// validator key. Signing needs permission to use the key if it is held
+/// remotely.
+async fn sign_announcement(
+    settings: &ValidatorSettings,
+    storage_location: String,
+) -> Result<SignedAnnouncement> {
+    let signer: Signers = settings.validator.build().await?;
+    let origin = settings.chain_setup(&settings.origin_chain)?;
+    let announcement = Announcement {
+        validator: signer.eth_address(),
+        mailbox_address: origin.addresses.mailbox,
+        mailbox_domain: settings.origin_chain.id(),
+        storage_location,
+    };
+    Ok(signer.sign(announcement).await?)
+}
 
-                sleep(self.interval).await;
-            }
-        }
-        Ok(())
+/// Check that the checkpoints of a syncer can be read. Nothing is written,
+/// as the storage is the one the validator serves its checkpoints from.
+async fn check_checkpoint_syncer(checkpoint_syncer: &dyn CheckpointSyncer) -> Result<String> {
+    let location = checkpoint_syncer.announcement_location();
+    let Some(latest_index) = checkpoint_syncer.latest_index().await? else {
+        return Ok(format!("{location} has no checkpoints yet"));
+    };
+    if checkpoint_syncer
+        .fetch_checkpoint(latest_index)
+        .await?
+        .is_none()
+    {
+        bail!("{location} is missing checkpoint {latest_index}, its latest index");
     }
+    Ok(format!(
+        "{location} has checkpoints up to index {latest_index}"
+    ))
 }
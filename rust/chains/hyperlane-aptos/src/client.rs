@@ -0,0 +1,219 @@
+use reqwest::{Client, Method, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    types::{
+        Account, ApiError, Block, EntryFunctionPayload, Event, GasEstimate, LedgerInfo, Transaction,
+    },
+    HyperlaneAptosError,
+};
+
+type Result<T> = std::result::Result<T, HyperlaneAptosError>;
+
+/// Client of the REST API of an Aptos fullnode
+#[derive(Debug, Clone)]
+pub(crate) struct AptosClient {
+    http: Client,
+    url: Url,
+}
+
+impl AptosClient {
+    pub fn new(url: Url) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}/{path}", self.url.as_str().trim_end_matches('/'));
+        self.http.request(method, url)
+    }
+
+    async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T> {
+        let response = request.send().await?;
+        Ok(Self::check(response).await?.json().await?)
+    }
+
+    async fn check(response: Response) -> Result<Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await?;
+        let (message, error_code) = match serde_json::from_str::<ApiError>(&body) {
+            Ok(err) => (err.message, err.error_code),
+            Err(_) => (body, None),
+        };
+        Err(HyperlaneAptosError::Api {
+            status: status.as_u16(),
+            message,
+            error_code,
+        })
+    }
+
+    pub async fn ledger_info(&self) -> Result<LedgerInfo> {
+        Self::send(self.request(Method::GET, "")).await
+    }
+
+    pub async fn block_by_height(&self, height: u64) -> Result<Block> {
+        Self::send(self.request(Method::GET, &format!("blocks/by_height/{height}"))).await
+    }
+
+    pub async fn block_by_version(&self, version: u64) -> Result<Block> {
+        Self::send(self.request(Method::GET, &format!("blocks/by_version/{version}"))).await
+    }
+
+    pub async fn account(&self, address: &str) -> Result<Account> {
+        Self::send(self.request(Method::GET, &format!("accounts/{address}"))).await
+    }
+
+    /// The data of a resource of an account, if it exists
+    pub async fn account_resource<T: DeserializeOwned>(
+        &self,
+        address: &str,
+        resource_type: &str,
+    ) -> Result<Option<T>> {
+        let path = format!("accounts/{address}/resource/{resource_type}");
+        match Self::send::<Value>(self.request(Method::GET, &path)).await {
+            Ok(resource) => {
+                let data = resource.get("data").cloned().unwrap_or_default();
+                Ok(Some(serde_json::from_value(data)?))
+            }
+            Err(HyperlaneAptosError::Api { status: 404, .. }) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Whether any module is published under an account
+    pub async fn has_modules(&self, address: &str) -> Result<bool> {
+        let path = format!("accounts/{address}/modules");
+        match Self::send::<Vec<Value>>(self.request(Method::GET, &path).query(&[("limit", 1)]))
+            .await
+        {
+            Ok(modules) => Ok(!modules.is_empty()),
+            Err(HyperlaneAptosError::Api { status: 404, .. }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Up to `limit` events of an event handle, starting at the event with
+    /// sequence number `start`
+    pub async fn events_by_handle(
+        &self,
+        address: &str,
+        handle_struct: &str,
+        field_name: &str,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<Event>> {
+        let path = format!("accounts/{address}/events/{handle_struct}/{field_name}");
+        Self::send(
+            self.request(Method::GET, &path)
+                .query(&[("start", start), ("limit", limit)]),
+        )
+        .await
+    }
+
+    pub async fn transaction_by_hash(&self, hash: &str) -> Result<Transaction> {
+        Self::send(self.request(Method::GET, &format!("transactions/by_hash/{hash}"))).await
+    }
+
+    pub async fn transaction_by_version(&self, version: u64) -> Result<Transaction> {
+        Self::send(self.request(Method::GET, &format!("transactions/by_version/{version}"))).await
+    }
+
+    /// Call a view function, at `ledger_version` if set
+    pub async fn view(
+        &self,
+        function: String,
+        arguments: Vec<Value>,
+        ledger_version: Option<u64>,
+    ) -> Result<Vec<Value>> {
+        let mut request = self.request(Method::POST, "view").json(&json!({
+            "function": function,
+            "type_arguments": Vec::<String>::new(),
+            "arguments": arguments,
+        }));
+        if let Some(version) = ledger_version {
+            request = request.query(&[("ledger_version", version)]);
+        }
+        Self::send(request).await
+    }
+
+    pub async fn estimate_gas_price(&self) -> Result<GasEstimate> {
+        Self::send(self.request(Method::GET, "estimate_gas_price")).await
+    }
+
+    /// The bytes to sign for a transaction
+    pub async fn encode_submission<T: Serialize>(&self, request: &T) -> Result<Vec<u8>> {
+        let encoded: String = Self::send(
+            self.request(Method::POST, "transactions/encode_submission")
+                .json(request),
+        )
+        .await?;
+        Ok(hex::decode(encoded.trim_start_matches("0x"))?)
+    }
+
+    /// Submit a signed transaction, returning it while pending
+    pub async fn submit<T: Serialize>(&self, signed: &T) -> Result<Transaction> {
+        Self::send(self.request(Method::POST, "transactions").json(signed)).await
+    }
+
+    /// Simulate a transaction, letting the fullnode estimate its gas price
+    /// and amount
+    pub async fn simulate<T: Serialize>(&self, signed: &T) -> Result<Transaction> {
+        let transactions: Vec<Transaction> = Self::send(
+            self.request(Method::POST, "transactions/simulate")
+                .query(&[
+                    ("estimate_gas_unit_price", true),
+                    ("estimate_max_gas_amount", true),
+                ])
+                .json(signed),
+        )
+        .await?;
+        transactions.into_iter().next().ok_or_else(|| {
+            HyperlaneAptosError::UnexpectedResponse("Empty simulation result".into())
+        })
+    }
+}
+
+/// A transaction request, as signed and submitted
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct TransactionRequest {
+    pub sender: String,
+    #[serde(serialize_with = "serialize_u64_str")]
+    pub sequence_number: u64,
+    #[serde(serialize_with = "serialize_u64_str")]
+    pub max_gas_amount: u64,
+    #[serde(serialize_with = "serialize_u64_str")]
+    pub gas_unit_price: u64,
+    #[serde(serialize_with = "serialize_u64_str")]
+    pub expiration_timestamp_secs: u64,
+    pub payload: EntryFunctionPayload,
+}
+
+fn serialize_u64_str<S: serde::Serializer>(
+    value: &u64,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+/// A transaction request with its signature
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SignedTransactionRequest {
+    #[serde(flatten)]
+    pub request: TransactionRequest,
+    pub signature: Ed25519Signature,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Ed25519Signature {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub public_key: String,
+    pub signature: String,
+}
@@ -0,0 +1,43 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-aptos
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneAptosError {
+    /// Http client error
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// Error returned by the fullnode API
+    #[error("Aptos API error {status}: {message} ({error_code:?})")]
+    Api {
+        /// Http status of the response
+        status: u16,
+        /// Message of the error
+        message: String,
+        /// Aptos error code, e.g. `account_not_found`
+        error_code: Option<String>,
+    },
+    /// Json (de)serialization error
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// Hex decoding error
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+    /// Invalid signing key
+    #[error(transparent)]
+    Signature(#[from] ed25519_dalek::SignatureError),
+    /// A response didn't have the expected shape
+    #[error("Unexpected response from the fullnode: {0}")]
+    UnexpectedResponse(String),
+    /// A submitted transaction wasn't committed in time
+    #[error("Transaction {0} wasn't committed in time")]
+    TransactionTimeout(String),
+}
+
+impl From<HyperlaneAptosError> for ChainCommunicationError {
+    fn from(value: HyperlaneAptosError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
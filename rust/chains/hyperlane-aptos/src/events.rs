@@ -0,0 +1,89 @@
+use std::ops::RangeInclusive;
+
+use hyperlane_core::{ChainResult, LogMeta, H256};
+
+use crate::{
+    types::{Event, EventHandle},
+    utils::{encode_address, field, parse_address},
+    AptosProvider, HyperlaneAptosError,
+};
+
+/// The events of an event handle stored in a resource of an account. Events
+/// are numbered in the order they were emitted, so a handle emitting one
+/// event per message can be indexed by sequence.
+#[derive(Debug, Clone)]
+pub(crate) struct EventStream {
+    provider: AptosProvider,
+    account: H256,
+    /// Struct of the resource holding the handle
+    handle_struct: String,
+    /// Field of the resource holding the handle
+    field: &'static str,
+}
+
+impl EventStream {
+    pub fn new(
+        provider: AptosProvider,
+        account: H256,
+        handle_struct: String,
+        field: &'static str,
+    ) -> Self {
+        Self {
+            provider,
+            account,
+            handle_struct,
+            field,
+        }
+    }
+
+    /// The number of events emitted so far
+    pub async fn count(&self) -> ChainResult<u64> {
+        let resource: Option<serde_json::Value> = self
+            .provider
+            .client()
+            .account_resource(&encode_address(self.account), &self.handle_struct)
+            .await?;
+        let Some(resource) = resource else {
+            return Ok(0);
+        };
+        let handle: EventHandle = serde_json::from_value(field(&resource, self.field)?.clone())
+            .map_err(HyperlaneAptosError::from)?;
+        Ok(handle.counter)
+    }
+
+    /// The events with sequence numbers in `range`
+    pub async fn events(&self, range: RangeInclusive<u32>) -> ChainResult<Vec<Event>> {
+        let start = u64::from(*range.start());
+        let limit = u64::from(range.end().saturating_sub(*range.start())) + 1;
+        Ok(self
+            .provider
+            .client()
+            .events_by_handle(
+                &encode_address(self.account),
+                &self.handle_struct,
+                self.field,
+                start,
+                limit,
+            )
+            .await?)
+    }
+
+    /// Where an event was emitted. Aptos numbers transactions globally by
+    /// version, which is used as the transaction index.
+    pub async fn log_meta(&self, event: &Event) -> ChainResult<LogMeta> {
+        let block = self.provider.block_by_version(event.version).await?;
+        let transaction = self
+            .provider
+            .client()
+            .transaction_by_version(event.version)
+            .await?;
+        Ok(LogMeta {
+            address: self.account,
+            block_number: block.number,
+            block_hash: block.hash,
+            transaction_id: parse_address(&transaction.hash)?.into(),
+            transaction_index: event.version,
+            log_index: event.sequence_number.into(),
+        })
+    }
+}
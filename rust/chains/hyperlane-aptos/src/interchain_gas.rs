@@ -0,0 +1,130 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, InterchainGasPaymaster,
+    InterchainGasPayment, LogMeta, SequenceAwareIndexer, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    events::EventStream,
+    types::Event,
+    utils::{encode_address, field, parse_h256, parse_u64},
+    AptosProvider, ConnectionConf,
+};
+
+/// Module of the IGP
+const IGP_MODULE: &str = "igps";
+
+/// A reference to an IGP contract on some Aptos chain
+#[derive(Debug)]
+pub struct AptosInterchainGasPaymaster {
+    provider: AptosProvider,
+    address: H256,
+}
+
+impl AptosInterchainGasPaymaster {
+    /// Create a new Aptos IGP.
+    pub fn new(conf: &ConnectionConf, locator: &ContractLocator) -> Self {
+        Self {
+            provider: AptosProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for AptosInterchainGasPaymaster {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosInterchainGasPaymaster {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+impl InterchainGasPaymaster for AptosInterchainGasPaymaster {}
+
+/// Struct that retrieves event data for an Aptos IGP contract
+#[derive(Debug)]
+pub struct AptosInterchainGasPaymasterIndexer {
+    provider: AptosProvider,
+    payments: EventStream,
+}
+
+impl AptosInterchainGasPaymasterIndexer {
+    /// Create a new Aptos IGP indexer.
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let provider = AptosProvider::new(locator.domain.clone(), conf, None);
+        let payments = EventStream::new(
+            provider.clone(),
+            locator.address,
+            format!(
+                "{}::{IGP_MODULE}::IgpState",
+                encode_address(locator.address)
+            ),
+            "gas_payment_events",
+        );
+        Self { provider, payments }
+    }
+
+    fn payment(event: &Event) -> ChainResult<InterchainGasPayment> {
+        let data = &event.data;
+        Ok(InterchainGasPayment {
+            message_id: parse_h256(field(data, "message_id")?)?,
+            destination: parse_u64(field(data, "dest_domain")?)?
+                .try_into()
+                .map_err(ChainCommunicationError::from_other)?,
+            payment: parse_u64(field(data, "required_amount")?)?.into(),
+            gas_amount: parse_u64(field(data, "gas_amount")?)?.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<InterchainGasPayment> for AptosInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        let mut payments = vec![];
+        for event in self.payments.events(range).await? {
+            let payment = Self::payment(&event)?;
+            let meta = self.payments.log_meta(&event).await?;
+            let sequence = event
+                .sequence_number
+                .try_into()
+                .map_err(ChainCommunicationError::from_other)?;
+            payments.push((Indexed::new(payment).with_sequence(sequence), meta));
+        }
+        Ok(payments)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.block_height().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for AptosInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.provider.block_height().await?;
+        let count = self
+            .payments
+            .count()
+            .await?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok((Some(count), tip))
+    }
+}
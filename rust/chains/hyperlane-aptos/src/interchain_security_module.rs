@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType, RawHyperlaneMessage,
+    H256, U256,
+};
+use num_traits::cast::FromPrimitive;
+use tracing::{instrument, warn};
+
+use crate::{
+    utils::{bytes_arg, function_id, parse_u64},
+    AptosProvider, ConnectionConf,
+};
+
+/// A reference to an InterchainSecurityModule contract on some Aptos chain
+#[derive(Debug)]
+pub struct AptosInterchainSecurityModule {
+    provider: AptosProvider,
+    address: H256,
+}
+
+impl AptosInterchainSecurityModule {
+    /// Create a new Aptos InterchainSecurityModule
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: AptosProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for AptosInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for AptosInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let module = self
+            .provider
+            .view_one(
+                function_id(self.address, "ism", "module_type"),
+                vec![],
+                None,
+            )
+            .await?;
+        let module = parse_u64(&module)?;
+
+        if let Some(module_type) = ModuleType::from_u64(module) {
+            Ok(module_type)
+        } else {
+            warn!(%module, "Unknown module type");
+            Ok(ModuleType::Unused)
+        }
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        let verified = self
+            .provider
+            .view_one(
+                function_id(self.address, "ism", "verify"),
+                vec![
+                    bytes_arg(metadata),
+                    bytes_arg(&RawHyperlaneMessage::from(message)),
+                ],
+                None,
+            )
+            .await?;
+        // Views don't meter gas, so only whether the metadata verifies is known
+        Ok(verified.as_bool().unwrap_or_default().then(U256::zero))
+    }
+}
@@ -0,0 +1,36 @@
+//! Implementation of hyperlane for Aptos.
+//!
+//! Talks to the REST API of an Aptos fullnode. Each core contract address is
+//! the account the hyperlane Move package is published under, which holds
+//! the modules and resources below:
+//! - `mailbox`: the outbox merkle tree, `inbox_process` and the
+//!   `MailboxState` resource with the dispatch and process event handles
+//! - `igps`: the `IgpState` resource with the gas payment event handle
+//! - `ism`, `multisig_ism` and `routing_ism`: the ISM views
+//! - `validator_announce`: the storage locations views and `announce`
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+#![deny(warnings)]
+
+pub use self::{
+    error::*, interchain_gas::*, interchain_security_module::*, mailbox::*, merkle_tree_hook::*,
+    multisig_ism::*, provider::*, routing_ism::*, signers::*, trait_builder::*,
+    validator_announce::*,
+};
+
+mod client;
+mod error;
+mod events;
+mod interchain_gas;
+mod interchain_security_module;
+mod mailbox;
+mod merkle_tree_hook;
+mod multisig_ism;
+mod provider;
+mod routing_ism;
+mod signers;
+mod trait_builder;
+mod types;
+mod utils;
+mod validator_announce;
@@ -0,0 +1,270 @@
+use std::{num::NonZeroU64, ops::RangeInclusive};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
+    RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+use serde_json::Value;
+use tracing::instrument;
+
+use crate::{
+    events::EventStream,
+    types::EntryFunctionPayload,
+    utils::{
+        address_arg, bytes_arg, encode_address, field, function_id, parse_address_value,
+        parse_bytes, parse_h256, parse_u64,
+    },
+    AptosProvider, ConnectionConf, Signer,
+};
+
+/// Module of the mailbox
+pub(crate) const MAILBOX_MODULE: &str = "mailbox";
+
+/// A reference to a Mailbox contract on some Aptos chain
+#[derive(Debug)]
+pub struct AptosMailbox {
+    pub(crate) provider: AptosProvider,
+    pub(crate) address: H256,
+}
+
+impl AptosMailbox {
+    /// Create a new Aptos mailbox
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, signer: Option<Signer>) -> Self {
+        Self {
+            provider: AptosProvider::new(locator.domain.clone(), conf, signer),
+            address: locator.address,
+        }
+    }
+
+    pub(crate) fn function(&self, function: &str) -> String {
+        function_id(self.address, MAILBOX_MODULE, function)
+    }
+
+    fn process_arguments(message: &HyperlaneMessage, metadata: &[u8]) -> Vec<Value> {
+        vec![
+            bytes_arg(&RawHyperlaneMessage::from(message)),
+            bytes_arg(metadata),
+        ]
+    }
+}
+
+impl HyperlaneContract for AptosMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for AptosMailbox {
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        let version = self.provider.ledger_version_for_lag(lag).await?;
+        let count = self
+            .provider
+            .view_one(self.function("outbox_get_count"), vec![], version)
+            .await?;
+        parse_u64(&count)?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let delivered = self
+            .provider
+            .view_one(
+                self.function("delivered"),
+                vec![bytes_arg(id.as_bytes())],
+                None,
+            )
+            .await?;
+        delivered
+            .as_bool()
+            .ok_or_else(|| ChainCommunicationError::from_other_str("Expected a boolean"))
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .view_one(self.function("get_default_ism"), vec![], None)
+            .await?;
+        parse_address_value(&ism)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .view_one(
+                self.function("get_recipient_ism"),
+                vec![address_arg(recipient)],
+                None,
+            )
+            .await?;
+        parse_address_value(&ism)
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let max_gas_amount = tx_gas_limit.map(|limit| limit.min(u64::MAX.into()).as_u64());
+        self.provider
+            .submit_entry_function(
+                self.function("inbox_process"),
+                Self::process_arguments(message, metadata),
+                max_gas_amount,
+            )
+            .await
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        self.provider
+            .estimate_entry_function(
+                self.function("inbox_process"),
+                Self::process_arguments(message, metadata),
+            )
+            .await
+    }
+
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        let payload = EntryFunctionPayload::new(
+            self.function("inbox_process"),
+            Self::process_arguments(message, metadata),
+        );
+        serde_json::to_vec(&payload).unwrap_or_default()
+    }
+}
+
+/// Struct that retrieves event data for an Aptos Mailbox contract
+#[derive(Debug)]
+pub struct AptosMailboxIndexer {
+    mailbox: AptosMailbox,
+    dispatches: EventStream,
+    processes: EventStream,
+}
+
+impl AptosMailboxIndexer {
+    /// Create a new Aptos mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let mailbox = AptosMailbox::new(conf, locator, None);
+        let state = format!(
+            "{}::{MAILBOX_MODULE}::MailboxState",
+            encode_address(mailbox.address)
+        );
+        let stream = |field| {
+            EventStream::new(
+                mailbox.provider.clone(),
+                mailbox.address,
+                state.clone(),
+                field,
+            )
+        };
+        Self {
+            dispatches: stream("dispatch_events"),
+            processes: stream("process_events"),
+            mailbox,
+        }
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.block_height().await
+    }
+
+    async fn sequence_count_and_tip(
+        &self,
+        stream: &EventStream,
+    ) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        let count = stream
+            .count()
+            .await?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for AptosMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let mut messages = vec![];
+        for event in self.dispatches.events(range).await? {
+            let message = HyperlaneMessage::from(parse_bytes(field(&event.data, "message")?)?);
+            let meta = self.dispatches.log_meta(&event).await?;
+            let nonce = message.nonce;
+            messages.push((Indexed::new(message).with_sequence(nonce), meta));
+        }
+        Ok(messages)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for AptosMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        self.sequence_count_and_tip(&self.dispatches).await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for AptosMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let mut deliveries = vec![];
+        for event in self.processes.events(range).await? {
+            let message_id = parse_h256(field(&event.data, "message_id")?)?;
+            let meta = self.processes.log_meta(&event).await?;
+            let sequence = event
+                .sequence_number
+                .try_into()
+                .map_err(ChainCommunicationError::from_other)?;
+            deliveries.push((Indexed::new(message_id).with_sequence(sequence), meta));
+        }
+        Ok(deliveries)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for AptosMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        self.sequence_count_and_tip(&self.processes).await
+    }
+}
@@ -0,0 +1,105 @@
+use std::{num::NonZeroU64, ops::RangeInclusive};
+
+use async_trait::async_trait;
+use derive_new::new;
+use hyperlane_core::{
+    accumulator::{incremental::IncrementalMerkle, TREE_DEPTH},
+    ChainCommunicationError, ChainResult, Checkpoint, HyperlaneChain, HyperlaneMessage, Indexed,
+    Indexer, LogMeta, MerkleTreeHook, MerkleTreeInsertion, SequenceAwareIndexer, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    utils::{field, parse_h256, parse_u64},
+    AptosMailbox, AptosMailboxIndexer,
+};
+
+/// The merkle tree of the mailbox, which is its own merkle tree hook on
+/// Aptos
+#[async_trait]
+impl MerkleTreeHook for AptosMailbox {
+    #[instrument(err, ret, skip(self))]
+    async fn tree(&self, lag: Option<NonZeroU64>) -> ChainResult<IncrementalMerkle> {
+        let version = self.provider.ledger_version_for_lag(lag).await?;
+        let tree = self
+            .provider
+            .view_one(self.function("outbox_get_tree"), vec![], version)
+            .await?;
+
+        let branch = field(&tree, "branch")?
+            .as_array()
+            .ok_or_else(|| ChainCommunicationError::from_other_str("Expected a branch array"))?
+            .iter()
+            .map(parse_h256)
+            .collect::<ChainResult<Vec<H256>>>()?;
+        let branch: [H256; TREE_DEPTH] = branch.try_into().map_err(|_| {
+            ChainCommunicationError::from_other_str("Unexpected merkle tree branch length")
+        })?;
+        let count = parse_u64(field(&tree, "count")?)?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+
+        Ok(IncrementalMerkle::new(branch, count))
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        hyperlane_core::Mailbox::count(self, lag).await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn latest_checkpoint(&self, lag: Option<NonZeroU64>) -> ChainResult<Checkpoint> {
+        let tree = self.tree(lag).await?;
+
+        let count: u32 = tree
+            .count()
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        let index = count.checked_sub(1).ok_or_else(|| {
+            ChainCommunicationError::from_contract_error_str(
+                "Outbox is empty, cannot compute checkpoint",
+            )
+        })?;
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address,
+            mailbox_domain: self.domain().id(),
+            root: tree.root(),
+            index,
+        })
+    }
+}
+
+/// Struct that retrieves event data for an Aptos merkle tree hook contract
+/// For now it's just a wrapper around the AptosMailboxIndexer
+#[derive(Debug, new)]
+pub struct AptosMerkleTreeHookIndexer(AptosMailboxIndexer);
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for AptosMerkleTreeHookIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let messages = Indexer::<HyperlaneMessage>::fetch_logs_in_range(&self.0, range).await?;
+        let merkle_tree_insertions = messages
+            .into_iter()
+            .map(|(message, meta)| {
+                let message = message.inner();
+                let insertion = MerkleTreeInsertion::new(message.nonce, message.id());
+                (Indexed::new(insertion).with_sequence(message.nonce), meta)
+            })
+            .collect();
+        Ok(merkle_tree_insertions)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        Indexer::<HyperlaneMessage>::get_finalized_block_number(&self.0).await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for AptosMerkleTreeHookIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        SequenceAwareIndexer::<HyperlaneMessage>::latest_sequence_count_and_tip(&self.0).await
+    }
+}
@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    utils::{function_id, parse_address_value, parse_u64},
+    AptosProvider, ConnectionConf,
+};
+
+/// A reference to a MultisigIsm contract on some Aptos chain
+#[derive(Debug)]
+pub struct AptosMultisigIsm {
+    provider: AptosProvider,
+    address: H256,
+}
+
+impl AptosMultisigIsm {
+    /// Create a new Aptos MultisigIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: AptosProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for AptosMultisigIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosMultisigIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for AptosMultisigIsm {
+    /// Returns the validator and threshold needed to verify message
+    #[instrument(err, ret, skip(self))]
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let values = self
+            .provider
+            .view(
+                function_id(self.address, "multisig_ism", "validators_and_threshold"),
+                vec![message.origin.into()],
+                None,
+            )
+            .await?;
+        let [validators, threshold] = values.as_slice() else {
+            return Err(ChainCommunicationError::from_other_str(
+                "Expected validators and a threshold",
+            ));
+        };
+
+        let validators = validators
+            .as_array()
+            .ok_or_else(|| ChainCommunicationError::from_other_str("Expected a validator array"))?
+            .iter()
+            .map(parse_address_value)
+            .collect::<ChainResult<Vec<_>>>()?;
+        let threshold = parse_u64(threshold)?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok((validators, threshold))
+    }
+}
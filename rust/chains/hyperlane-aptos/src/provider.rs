@@ -0,0 +1,426 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    num::NonZeroU64,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, TxCostEstimate, TxOutcome, TxnInfo, TxnReceiptInfo, H256, U256,
+};
+use serde_json::Value;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    client::{AptosClient, Ed25519Signature, SignedTransactionRequest, TransactionRequest},
+    types::{EntryFunctionPayload, Transaction},
+    utils::{encode_address, field, parse_address, parse_u64, unexpected},
+    ConnectionConf, HyperlaneAptosError, Signer,
+};
+
+/// How long a transaction may wait in the mempool before it expires
+const TRANSACTION_EXPIRATION: Duration = Duration::from_secs(60);
+
+/// How often a submitted transaction is polled until it is committed
+const TRANSACTION_POLLING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Coin whose balance is reported for an account
+const APTOS_COIN: &str = "0x1::aptos_coin::AptosCoin";
+
+/// Most blocks remembered by hash
+const MAX_CACHED_BLOCKS: usize = 10_000;
+
+/// Blocks events were emitted in, by hash. Fullnodes can only look blocks up
+/// by height or version, so the blocks of indexed events are remembered for
+/// `get_block_by_hash`, which is asked for the blocks of events indexed by
+/// the same agent.
+static BLOCKS_BY_HASH: OnceLock<Mutex<BlockCache>> = OnceLock::new();
+
+#[derive(Debug, Default)]
+struct BlockCache {
+    blocks: HashMap<H256, BlockInfo>,
+    /// Hashes of the blocks, oldest first, to forget the oldest block once
+    /// the cache is full
+    hashes: VecDeque<H256>,
+}
+
+impl BlockCache {
+    fn insert(&mut self, block: BlockInfo) {
+        if self.blocks.insert(block.hash, block.clone()).is_some() {
+            return;
+        }
+        self.hashes.push_back(block.hash);
+        if self.hashes.len() > MAX_CACHED_BLOCKS {
+            if let Some(oldest) = self.hashes.pop_front() {
+                self.blocks.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Abstraction over a connection to an Aptos fullnode
+#[derive(Debug, Clone)]
+pub struct AptosProvider {
+    domain: HyperlaneDomain,
+    conf: ConnectionConf,
+    client: AptosClient,
+    signer: Option<Signer>,
+}
+
+impl AptosProvider {
+    /// Create a provider connected to the fullnode of `conf`, signing
+    /// transactions with `signer` if set
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            conf: conf.clone(),
+            client: AptosClient::new(conf.url.clone()),
+            signer,
+        }
+    }
+
+    pub(crate) fn client(&self) -> &AptosClient {
+        &self.client
+    }
+
+    /// Height of the latest committed block. Blocks are final once committed.
+    pub(crate) async fn block_height(&self) -> ChainResult<u32> {
+        let ledger = self.client.ledger_info().await?;
+        ledger
+            .block_height
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// The last ledger version of the block `lag` blocks behind the tip, to
+    /// read state as of that block. The latest version if there's no lag.
+    pub(crate) async fn ledger_version_for_lag(
+        &self,
+        lag: Option<NonZeroU64>,
+    ) -> ChainResult<Option<u64>> {
+        let Some(lag) = lag else {
+            return Ok(None);
+        };
+        let ledger = self.client.ledger_info().await?;
+        let block = self
+            .client
+            .block_by_height(ledger.block_height.saturating_sub(lag.get()))
+            .await?;
+        Ok(Some(block.last_version))
+    }
+
+    /// The block containing the transaction of `version`, remembering it for
+    /// `get_block_by_hash`
+    pub(crate) async fn block_by_version(&self, version: u64) -> ChainResult<BlockInfo> {
+        let block = self.client.block_by_version(version).await?;
+        let block = BlockInfo {
+            hash: parse_address(&block.block_hash)?,
+            timestamp: block.block_timestamp / 1_000_000,
+            number: block.block_height,
+        };
+        BLOCKS_BY_HASH
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap()
+            .insert(block.clone());
+        Ok(block)
+    }
+
+    /// Call a view function
+    pub(crate) async fn view(
+        &self,
+        function: String,
+        arguments: Vec<Value>,
+        ledger_version: Option<u64>,
+    ) -> ChainResult<Vec<Value>> {
+        Ok(self
+            .client
+            .view(function, arguments, ledger_version)
+            .await?)
+    }
+
+    /// Call a view function returning a single value
+    pub(crate) async fn view_one(
+        &self,
+        function: String,
+        arguments: Vec<Value>,
+        ledger_version: Option<u64>,
+    ) -> ChainResult<Value> {
+        self.view(function.clone(), arguments, ledger_version)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| unexpected(format!("`{function}` returned nothing")).into())
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The address of the account signing transactions, if any
+    pub(crate) fn signer_address(&self) -> Option<H256> {
+        self.signer.as_ref().map(Signer::address)
+    }
+
+    /// The most a transaction with the default max gas amount can cost, in
+    /// octas
+    pub(crate) async fn max_transaction_cost(&self) -> ChainResult<U256> {
+        let gas_unit_price = self.gas_unit_price().await?;
+        Ok(U256::from(self.conf.max_gas_amount) * U256::from(gas_unit_price))
+    }
+
+    /// The configured gas unit price, or the fullnode's estimate if unset
+    async fn gas_unit_price(&self) -> ChainResult<u64> {
+        match self.conf.gas_unit_price {
+            Some(price) => Ok(price),
+            None => Ok(self.client.estimate_gas_price().await?.gas_estimate),
+        }
+    }
+
+    async fn transaction_request(
+        &self,
+        payload: EntryFunctionPayload,
+        max_gas_amount: u64,
+    ) -> ChainResult<TransactionRequest> {
+        let signer = self.signer()?;
+        let account = self.client.account(&signer.address_string()).await?;
+        let ledger = self.client.ledger_info().await?;
+        let gas_unit_price = self.gas_unit_price().await?;
+        Ok(TransactionRequest {
+            sender: signer.address_string(),
+            sequence_number: account.sequence_number,
+            max_gas_amount,
+            gas_unit_price,
+            expiration_timestamp_secs: ledger.ledger_timestamp / 1_000_000
+                + TRANSACTION_EXPIRATION.as_secs(),
+            payload,
+        })
+    }
+
+    /// Submit a transaction calling an entry function and wait for it to be
+    /// committed
+    #[instrument(err, skip(self, arguments))]
+    pub(crate) async fn submit_entry_function(
+        &self,
+        function: String,
+        arguments: Vec<Value>,
+        max_gas_amount: Option<u64>,
+    ) -> ChainResult<TxOutcome> {
+        let signer = self.signer()?;
+        let request = self
+            .transaction_request(
+                EntryFunctionPayload::new(function, arguments),
+                max_gas_amount.unwrap_or(self.conf.max_gas_amount),
+            )
+            .await?;
+        let signing_message = self.client.encode_submission(&request).await?;
+        let signed = SignedTransactionRequest {
+            request,
+            signature: Ed25519Signature {
+                kind: "ed25519_signature",
+                public_key: signer.public_key_hex(),
+                signature: signer.sign(&signing_message),
+            },
+        };
+        let pending = self.client.submit(&signed).await?;
+        info!(hash = pending.hash, "Submitted transaction");
+
+        let transaction = self.wait_for_transaction(&pending.hash).await?;
+        if !transaction.success.unwrap_or_default() {
+            warn!(
+                hash = transaction.hash,
+                vm_status = ?transaction.vm_status,
+                "Transaction failed"
+            );
+        }
+        tx_outcome(&transaction)
+    }
+
+    async fn wait_for_transaction(&self, hash: &str) -> ChainResult<Transaction> {
+        let start = tokio::time::Instant::now();
+        // Give up shortly after the transaction expired
+        while start.elapsed() < TRANSACTION_EXPIRATION * 2 {
+            match self.client.transaction_by_hash(hash).await {
+                Ok(transaction) if transaction.is_committed() => return Ok(transaction),
+                Ok(_) | Err(HyperlaneAptosError::Api { status: 404, .. }) => {}
+                Err(err) => return Err(err.into()),
+            }
+            tokio::time::sleep(TRANSACTION_POLLING_INTERVAL).await;
+        }
+        Err(HyperlaneAptosError::TransactionTimeout(hash.into()).into())
+    }
+
+    /// Estimate the gas of a transaction calling an entry function by
+    /// simulating it
+    #[instrument(err, skip(self, arguments))]
+    pub(crate) async fn estimate_entry_function(
+        &self,
+        function: String,
+        arguments: Vec<Value>,
+    ) -> ChainResult<TxCostEstimate> {
+        let signer = self.signer()?;
+        let request = self
+            .transaction_request(
+                EntryFunctionPayload::new(function, arguments),
+                self.conf.max_gas_amount,
+            )
+            .await?;
+        // Simulations must not be validly signed
+        let signed = SignedTransactionRequest {
+            request,
+            signature: Ed25519Signature {
+                kind: "ed25519_signature",
+                public_key: signer.public_key_hex(),
+                signature: format!("0x{}", hex::encode([0; 64])),
+            },
+        };
+        let simulation = self.client.simulate(&signed).await?;
+        if !simulation.success.unwrap_or_default() {
+            return Err(ChainCommunicationError::CustomError(format!(
+                "Simulation failed: {}",
+                simulation.vm_status.unwrap_or_default()
+            )));
+        }
+        Ok(TxCostEstimate {
+            gas_limit: simulation.gas_used.unwrap_or_default().into(),
+            gas_price: U256::from(simulation.gas_unit_price.unwrap_or_default()).try_into()?,
+            l2_gas_limit: None,
+        })
+    }
+
+    /// Balance of an account in the native coin, in octas
+    pub(crate) async fn balance(&self, address: H256) -> ChainResult<U256> {
+        let store: Option<Value> = self
+            .client
+            .account_resource(
+                &encode_address(address),
+                &format!("0x1::coin::CoinStore<{APTOS_COIN}>"),
+            )
+            .await?;
+        // Accounts that never held the coin have no coin store
+        let Some(store) = store else {
+            return Ok(U256::zero());
+        };
+        Ok(parse_u64(field(field(&store, "coin")?, "value")?)?.into())
+    }
+}
+
+impl HyperlaneChain for AptosProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for AptosProvider {
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        BLOCKS_BY_HASH
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap()
+            .blocks
+            .get(hash)
+            .cloned()
+            .ok_or(ChainCommunicationError::BlockNotFound(*hash))
+    }
+
+    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
+        let transaction = self
+            .client
+            .transaction_by_hash(&format!("{hash:?}"))
+            .await?;
+        let gas_price = transaction.gas_unit_price.map(U256::from);
+        let receipt = transaction.gas_used.map(|gas_used| TxnReceiptInfo {
+            gas_used: gas_used.into(),
+            cumulative_gas_used: gas_used.into(),
+            effective_gas_price: gas_price,
+        });
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit: transaction.max_gas_amount.unwrap_or_default().into(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price,
+            nonce: transaction.sequence_number.unwrap_or_default(),
+            sender: transaction
+                .sender
+                .as_deref()
+                .map(parse_address)
+                .transpose()?
+                .unwrap_or_default(),
+            recipient: transaction
+                .payload
+                .as_ref()
+                .and_then(|payload| payload.function.split("::").next())
+                .map(parse_address)
+                .transpose()?,
+            receipt,
+        })
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        Ok(self.client.has_modules(&encode_address(*address)).await?)
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        self.balance(parse_address(&address)?).await
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        let ledger = self.client.ledger_info().await?;
+        let block = self.client.block_by_height(ledger.block_height).await?;
+        let gas_price = self.client.estimate_gas_price().await?;
+        Ok(Some(ChainInfo::new(
+            BlockInfo {
+                hash: parse_address(&block.block_hash)?,
+                timestamp: block.block_timestamp / 1_000_000,
+                number: block.block_height,
+            },
+            Some(gas_price.gas_estimate.into()),
+        )))
+    }
+}
+
+/// The outcome of a committed transaction
+pub(crate) fn tx_outcome(transaction: &Transaction) -> ChainResult<TxOutcome> {
+    Ok(TxOutcome {
+        transaction_id: parse_address(&transaction.hash)?.into(),
+        executed: transaction.success.unwrap_or_default(),
+        gas_used: transaction.gas_used.unwrap_or_default().into(),
+        gas_price: U256::from(transaction.gas_unit_price.unwrap_or_default()).try_into()?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_block_cache_forgets_oldest_blocks() {
+        let block = |number: u64| BlockInfo {
+            hash: H256::from_low_u64_be(number),
+            timestamp: number,
+            number,
+        };
+        let mut cache = BlockCache::default();
+        for number in 0..=MAX_CACHED_BLOCKS as u64 {
+            cache.insert(block(number));
+        }
+        // Seen again, which doesn't make it the newest
+        cache.insert(block(1));
+        assert_eq!(cache.blocks.len(), MAX_CACHED_BLOCKS);
+        assert!(!cache.blocks.contains_key(&H256::from_low_u64_be(0)));
+        assert_eq!(
+            cache.blocks[&H256::from_low_u64_be(1)].number,
+            block(1).number
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, RawHyperlaneMessage, RoutingIsm, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    utils::{bytes_arg, function_id, parse_address_value},
+    AptosProvider, ConnectionConf,
+};
+
+/// A reference to a RoutingIsm contract on some Aptos chain
+#[derive(Debug)]
+pub struct AptosRoutingIsm {
+    provider: AptosProvider,
+    address: H256,
+}
+
+impl AptosRoutingIsm {
+    /// Create a new Aptos RoutingIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: AptosProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for AptosRoutingIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosRoutingIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for AptosRoutingIsm {
+    #[instrument(err, ret, skip(self))]
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .view_one(
+                function_id(self.address, "routing_ism", "route"),
+                vec![bytes_arg(&RawHyperlaneMessage::from(message))],
+                None,
+            )
+            .await?;
+        parse_address_value(&ism)
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt::{Debug, Formatter};
+
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+use hyperlane_core::{ChainResult, H256};
+use sha3::{Digest, Sha3_256};
+
+use crate::{utils::encode_address, HyperlaneAptosError};
+
+/// Scheme byte of single Ed25519 key accounts, appended to the public key to
+/// derive the address
+const ED25519_SCHEME: u8 = 0;
+
+/// Signer of the transactions of an Aptos account with a single Ed25519 key
+#[derive(Clone)]
+pub struct Signer {
+    secret_key: [u8; 32],
+    public_key: PublicKey,
+    address: H256,
+}
+
+impl Signer {
+    /// Create a signer from the 32 byte private key of an account
+    pub fn new(private_key: &[u8]) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key).map_err(HyperlaneAptosError::from)?;
+        let public_key = PublicKey::from(&secret);
+        let address = H256::from_slice(
+            Sha3_256::new()
+                .chain_update(public_key.as_bytes())
+                .chain_update([ED25519_SCHEME])
+                .finalize()
+                .as_slice(),
+        );
+        Ok(Self {
+            secret_key: secret.to_bytes(),
+            public_key,
+            address,
+        })
+    }
+
+    /// The address of the account
+    pub fn address(&self) -> H256 {
+        self.address
+    }
+
+    /// The address of the account, formatted the way Aptos does
+    pub fn address_string(&self) -> String {
+        encode_address(self.address)
+    }
+
+    /// The hex encoded public key
+    pub(crate) fn public_key_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.public_key.as_bytes()))
+    }
+
+    /// Sign a message, returning the hex encoded signature
+    pub(crate) fn sign(&self, message: &[u8]) -> String {
+        // The key was validated on creation
+        let secret = SecretKey::from_bytes(&self.secret_key).expect("Valid secret key");
+        let signature = ExpandedSecretKey::from(&secret).sign(message, &self.public_key);
+        format!("0x{}", hex::encode(signature.to_bytes()))
+    }
+}
+
+impl Debug for Signer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signer")
+            .field("address", &self.address_string())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signature, Verifier};
+
+    use super::*;
+
+    #[test]
+    fn test_signature_verifies_with_public_key() {
+        let signer = Signer::new(&[1; 32]).unwrap();
+        let signature = hex::decode(signer.sign(b"message").trim_start_matches("0x")).unwrap();
+        let signature = Signature::from_bytes(&signature).unwrap();
+        assert!(signer.public_key.verify(b"message", &signature).is_ok());
+        assert_eq!(signer.address_string().len(), 66);
+    }
+}
@@ -0,0 +1,31 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// Maximum gas units of a submitted transaction if not configured
+pub const DEFAULT_MAX_GAS_AMOUNT: u64 = 200_000;
+
+/// Aptos connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Url of the REST API of a fullnode, e.g.
+    /// `https://fullnode.mainnet.aptoslabs.com/v1`
+    pub url: Url,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+    /// Maximum gas units a submitted transaction may use
+    pub max_gas_amount: u64,
+    /// Price of a gas unit of submitted transactions, in octas. Estimated by
+    /// the fullnode if not specified.
+    pub gas_unit_price: Option<u64>,
+}
+
+/// An error type when parsing a connection configuration.
+#[derive(thiserror::Error, Debug)]
+pub enum ConnectionConfError {
+    /// Missing `url` for connection configuration
+    #[error("Missing `url` for connection configuration")]
+    MissingConnectionUrl,
+    /// Invalid `url` for connection configuration
+    #[error("Invalid `url` for connection configuration: `{0}` ({1})")]
+    InvalidConnectionUrl(String, url::ParseError),
+}
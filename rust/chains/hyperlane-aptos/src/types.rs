@@ -0,0 +1,131 @@
+//! Responses of the fullnode REST API. The API writes 64 bit and larger
+//! integers as strings.
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Deserialize a u64 written as a string
+pub(crate) fn u64_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+/// Deserialize an optional u64 written as a string
+pub(crate) fn opt_u64_str<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Error body of a failed request
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiError {
+    pub message: String,
+    pub error_code: Option<String>,
+}
+
+/// The state of the chain as seen by the fullnode
+#[derive(Debug, Deserialize)]
+pub(crate) struct LedgerInfo {
+    #[serde(deserialize_with = "u64_str")]
+    pub block_height: u64,
+    /// In microseconds
+    #[serde(deserialize_with = "u64_str")]
+    pub ledger_timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Block {
+    #[serde(deserialize_with = "u64_str")]
+    pub block_height: u64,
+    pub block_hash: String,
+    /// In microseconds
+    #[serde(deserialize_with = "u64_str")]
+    pub block_timestamp: u64,
+    #[serde(deserialize_with = "u64_str")]
+    pub last_version: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Account {
+    #[serde(deserialize_with = "u64_str")]
+    pub sequence_number: u64,
+}
+
+/// An event emitted to an event handle
+#[derive(Debug, Deserialize)]
+pub(crate) struct Event {
+    /// Version of the transaction that emitted the event
+    #[serde(deserialize_with = "u64_str")]
+    pub version: u64,
+    /// Position of the event in its event handle
+    #[serde(deserialize_with = "u64_str")]
+    pub sequence_number: u64,
+    pub data: Value,
+}
+
+/// Resource data of an `0x1::event::EventHandle`
+#[derive(Debug, Deserialize)]
+pub(crate) struct EventHandle {
+    /// Number of events emitted to the handle
+    #[serde(deserialize_with = "u64_str")]
+    pub counter: u64,
+}
+
+/// A pending, user or system transaction. Only user transactions have a
+/// sender and gas settings.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Transaction {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub hash: String,
+    pub success: Option<bool>,
+    pub vm_status: Option<String>,
+    #[serde(default, deserialize_with = "opt_u64_str")]
+    pub gas_used: Option<u64>,
+    #[serde(default, deserialize_with = "opt_u64_str")]
+    pub gas_unit_price: Option<u64>,
+    #[serde(default, deserialize_with = "opt_u64_str")]
+    pub max_gas_amount: Option<u64>,
+    #[serde(default, deserialize_with = "opt_u64_str")]
+    pub sequence_number: Option<u64>,
+    pub sender: Option<String>,
+    pub payload: Option<EntryFunctionPayload>,
+}
+
+impl Transaction {
+    /// Whether the transaction was committed to the chain
+    pub fn is_committed(&self) -> bool {
+        self.kind != "pending_transaction"
+    }
+}
+
+/// Payload of a transaction calling an entry function
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub(crate) struct EntryFunctionPayload {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// `<address>::<module>::<function>`
+    pub function: String,
+    pub type_arguments: Vec<String>,
+    pub arguments: Vec<Value>,
+}
+
+impl EntryFunctionPayload {
+    pub fn new(function: String, arguments: Vec<Value>) -> Self {
+        Self {
+            kind: "entry_function_payload".into(),
+            function,
+            type_arguments: vec![],
+            arguments,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GasEstimate {
+    pub gas_estimate: u64,
+}
@@ -0,0 +1,107 @@
+use hyperlane_core::{ChainResult, H256};
+use serde_json::Value;
+
+use crate::HyperlaneAptosError;
+
+/// Write an address the way the API expects it
+pub(crate) fn encode_address(address: H256) -> String {
+    format!("0x{}", hex::encode(address.as_bytes()))
+}
+
+/// Parse an address returned by the API, which drops the leading zeros of
+/// special addresses such as `0x1`
+pub(crate) fn parse_address(address: &str) -> ChainResult<H256> {
+    let hex = address.trim_start_matches("0x");
+    if hex.len() > 64 {
+        return Err(unexpected(format!("`{address}` is not an address")).into());
+    }
+    let bytes = hex::decode(format!("{hex:0>64}")).map_err(HyperlaneAptosError::from)?;
+    Ok(H256::from_slice(&bytes))
+}
+
+/// The id of a function of a module published at `address`
+pub(crate) fn function_id(address: H256, module: &str, function: &str) -> String {
+    format!("{}::{module}::{function}", encode_address(address))
+}
+
+/// A `vector<u8>` argument
+pub(crate) fn bytes_arg(bytes: &[u8]) -> Value {
+    Value::String(format!("0x{}", hex::encode(bytes)))
+}
+
+/// An `address` argument
+pub(crate) fn address_arg(address: H256) -> Value {
+    Value::String(encode_address(address))
+}
+
+/// Parse a `vector<u8>` value
+pub(crate) fn parse_bytes(value: &Value) -> ChainResult<Vec<u8>> {
+    let hex = value
+        .as_str()
+        .ok_or_else(|| unexpected(format!("`{value}` is not a byte vector")))?;
+    Ok(hex::decode(hex.trim_start_matches("0x")).map_err(HyperlaneAptosError::from)?)
+}
+
+/// Parse a 32 byte `vector<u8>` value
+pub(crate) fn parse_h256(value: &Value) -> ChainResult<H256> {
+    let bytes = parse_bytes(value)?;
+    if bytes.len() != 32 {
+        return Err(unexpected(format!("`{value}` is not 32 bytes long")).into());
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// Parse an `address` value
+pub(crate) fn parse_address_value(value: &Value) -> ChainResult<H256> {
+    let address = value
+        .as_str()
+        .ok_or_else(|| unexpected(format!("`{value}` is not an address")))?;
+    parse_address(address)
+}
+
+/// Parse an integer value, which the API writes as a number up to `u32` and
+/// as a string above
+pub(crate) fn parse_u64(value: &Value) -> ChainResult<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| unexpected(format!("`{value}` is not an integer")).into())
+}
+
+/// The field `name` of a Move struct value
+pub(crate) fn field<'a>(value: &'a Value, name: &str) -> ChainResult<&'a Value> {
+    value
+        .get(name)
+        .ok_or_else(|| unexpected(format!("Missing field `{name}` in `{value}`")).into())
+}
+
+pub(crate) fn unexpected(message: String) -> HyperlaneAptosError {
+    HyperlaneAptosError::UnexpectedResponse(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_short_address() {
+        let address = parse_address("0x1").unwrap();
+        assert_eq!(address, H256::from_low_u64_be(1));
+        assert_eq!(
+            encode_address(address),
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn test_parse_u64_numbers_and_strings() {
+        assert_eq!(parse_u64(&Value::from(7)).unwrap(), 7);
+        assert_eq!(
+            parse_u64(&Value::from("18446744073709551615")).unwrap(),
+            u64::MAX
+        );
+        assert!(parse_u64(&Value::from("0x1")).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    Announcement, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, SignedType, TxOutcome,
+    ValidatorAnnounce, H256, U256,
+};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use crate::{
+    utils::{bytes_arg, function_id},
+    AptosProvider, ConnectionConf, Signer,
+};
+
+/// Module of the validator announce contract
+const VALIDATOR_ANNOUNCE_MODULE: &str = "validator_announce";
+
+/// A reference to a ValidatorAnnounce contract on some Aptos chain
+#[derive(Debug)]
+pub struct AptosValidatorAnnounce {
+    provider: AptosProvider,
+    address: H256,
+}
+
+impl AptosValidatorAnnounce {
+    /// Create a new Aptos ValidatorAnnounce
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, signer: Option<Signer>) -> Self {
+        Self {
+            provider: AptosProvider::new(locator.domain.clone(), conf, signer),
+            address: locator.address,
+        }
+    }
+
+    fn announce_arguments(announcement: &SignedType<Announcement>) -> Vec<Value> {
+        vec![
+            bytes_arg(announcement.value.validator.as_bytes()),
+            bytes_arg(&announcement.signature.to_vec()),
+            Value::String(announcement.value.storage_location.clone()),
+        ]
+    }
+}
+
+impl HyperlaneContract for AptosValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for AptosValidatorAnnounce {
+    #[instrument(err, ret, skip(self))]
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        // Validators are announced by their 20 byte Ethereum address
+        let validators = validators
+            .iter()
+            .map(|validator| bytes_arg(&validator.as_bytes()[12..]))
+            .collect::<Vec<_>>();
+        let locations = self
+            .provider
+            .view_one(
+                function_id(
+                    self.address,
+                    VALIDATOR_ANNOUNCE_MODULE,
+                    "get_announced_storage_locations",
+                ),
+                vec![Value::Array(validators)],
+                None,
+            )
+            .await?;
+        serde_json::from_value(locations).map_err(ChainCommunicationError::from_other)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        self.provider
+            .submit_entry_function(
+                function_id(self.address, VALIDATOR_ANNOUNCE_MODULE, "announce"),
+                Self::announce_arguments(&announcement),
+                None,
+            )
+            .await
+    }
+
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        let Some(signer) = self.provider.signer_address() else {
+            warn!(?announcement, "Cannot announce without a signer");
+            return None;
+        };
+        let max_cost = self.provider.max_transaction_cost().await.ok()?;
+        let balance = self.provider.balance(signer).await.ok()?;
+        Some(max_cost.saturating_sub(balance))
+    }
+}
@@ -44,6 +44,12 @@ pub enum HyperlaneCosmosError {
     /// Protobuf error
     #[error("{0}")]
     Protobuf(#[from] protobuf::ProtobufError),
+    /// Websocket error
+    #[error("{0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+    /// Error response to an event subscription
+    #[error("Subscription error: {0}")]
+    SubscriptionError(String),
     /// Fallback providers failed
     #[error("Fallback providers failed. (Errors: {0:?})")]
     FallbackProvidersFailed(Vec<HyperlaneCosmosError>),
@@ -231,6 +231,10 @@ impl Indexer<InterchainGasPayment> for CosmosInterchainGasPaymasterIndexer {
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
         self.indexer.get_finalized_block_number().await
     }
+
+    async fn new_logs_notified(&self) {
+        self.indexer.new_logs_notified().await
+    }
 }
 
 #[async_trait]
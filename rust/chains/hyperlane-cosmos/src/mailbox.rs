@@ -377,6 +377,10 @@ impl Indexer<HyperlaneMessage> for CosmosMailboxIndexer {
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
         self.indexer.get_finalized_block_number().await
     }
+
+    async fn new_logs_notified(&self) {
+        self.indexer.new_logs_notified().await
+    }
 }
 
 #[async_trait]
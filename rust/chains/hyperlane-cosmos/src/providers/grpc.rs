@@ -22,7 +22,7 @@ use cosmrs::{
         traits::Message,
     },
     tx::{self, Fee, MessageExt, SignDoc, SignerInfo},
-    Any, Coin,
+    AccountId, Any, Coin,
 };
 use derive_new::new;
 use hyperlane_core::{
@@ -135,6 +135,8 @@ pub struct WasmGrpcProvider {
     /// See `<https://docs.rs/tonic/latest/tonic/transport/struct.Channel.html#multiplexing-requests>`
     provider: CosmosFallbackProvider<CosmosChannel>,
     gas_price: CosmosAmount,
+    /// Account paying the transaction fees through a fee grant, if any.
+    fee_granter: Option<AccountId>,
 }
 
 impl WasmGrpcProvider {
@@ -158,8 +160,12 @@ impl WasmGrpcProvider {
             .collect();
         let mut builder = FallbackProvider::builder();
         builder = builder.add_providers(channels?);
+        if let Some(conf) = conf.get_health_scoring() {
+            builder = builder.with_health_scoring(conf.clone());
+        }
         let fallback_provider = builder.build();
         let provider = CosmosFallbackProvider::new(fallback_provider);
+        provider.spawn_block_height_sampler();
 
         let contract_address = locator
             .map(|l| {
@@ -171,6 +177,12 @@ impl WasmGrpcProvider {
             })
             .transpose()?;
 
+        let fee_granter = conf
+            .get_fee_granter()
+            .map(|granter| granter.parse::<AccountId>())
+            .transpose()
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+
         Ok(Self {
             domain,
             conf,
@@ -178,6 +190,7 @@ impl WasmGrpcProvider {
             signer,
             provider,
             gas_price,
+            fee_granter,
         })
     }
 
@@ -224,8 +237,10 @@ impl WasmGrpcProvider {
             self.conf.get_canonical_asset().as_str(),
         )
         .map_err(Into::<HyperlaneCosmosError>::into)?;
-        let auth_info =
-            signer_info.auth_info(Fee::from_amount_and_gas(fee_coin.clone(), gas_limit));
+        let mut fee = Fee::from_amount_and_gas(fee_coin.clone(), gas_limit);
+        // The granter pays the fee out of the allowance it granted the signer
+        fee.granter = self.fee_granter.clone();
+        let auth_info = signer_info.auth_info(fee);
 
         let chain_id = self
             .conf
@@ -563,16 +578,19 @@ impl WasmProvider for WasmGrpcProvider {
         });
         let (tx_bytes, fee) = self.generate_raw_signed_tx_and_fee(msgs, gas_limit).await?;
 
-        // Check if the signer has enough funds to pay for the fee so we can get
-        // a more informative error.
-        let signer_balance = self
-            .get_balance(signer.address.clone(), fee.denom.to_string())
-            .await?;
+        // Check if the account paying the fee has enough funds so we can get
+        // a more informative error. The allowance of a fee grant isn't
+        // checked, only the balance of the granter.
+        let payer = match &self.fee_granter {
+            Some(granter) => granter.to_string(),
+            None => signer.address.clone(),
+        };
+        let payer_balance = self.get_balance(payer, fee.denom.to_string()).await?;
         let fee_amount: U256 = fee.amount.into();
-        if signer_balance < fee_amount {
+        if payer_balance < fee_amount {
             return Err(ChainCommunicationError::InsufficientFunds {
                 required: fee_amount,
-                available: signer_balance,
+                available: payer_balance,
             });
         }
 
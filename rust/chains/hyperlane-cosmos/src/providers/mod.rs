@@ -1,13 +1,23 @@
+use std::str::FromStr;
+
 use async_trait::async_trait;
+use cosmrs::{
+    proto::{cosmwasm::wasm::v1::MsgExecuteContract, traits::Message},
+    tx::{SignerPublicKey, Tx},
+};
 use hyperlane_core::{
-    BlockInfo, ChainInfo, ChainResult, ContractLocator, HyperlaneChain, HyperlaneDomain,
-    HyperlaneProvider, TxnInfo, H256, U256,
+    rpc_clients::FallbackProvider, BlockInfo, ChainCommunicationError, ChainInfo, ChainResult,
+    ContractLocator, HyperlaneChain, HyperlaneDomain, HyperlaneProvider, TxnInfo, TxnReceiptInfo,
+    H256, U256,
 };
-use tendermint_rpc::{client::CompatMode, HttpClient};
+use tendermint::{hash::Algorithm, Hash};
 
-use crate::{ConnectionConf, CosmosAmount, HyperlaneCosmosError, Signer};
+use crate::{
+    address::CosmosAddress, rpc_clients::CosmosFallbackProvider, ConnectionConf, CosmosAmount,
+    HyperlaneCosmosError, Signer,
+};
 
-use self::grpc::WasmGrpcProvider;
+use self::{grpc::WasmGrpcProvider, rpc::CosmosRpcClient};
 
 /// cosmos grpc provider
 pub mod grpc;
@@ -19,8 +29,9 @@ pub mod rpc;
 pub struct CosmosProvider {
     domain: HyperlaneDomain,
     canonical_asset: String,
+    bech32_prefix: String,
     grpc_client: WasmGrpcProvider,
-    rpc_client: HttpClient,
+    rpc_client: CosmosFallbackProvider<CosmosRpcClient>,
 }
 
 impl CosmosProvider {
@@ -39,21 +50,25 @@ impl CosmosProvider {
             locator,
             signer,
         )?;
-        let rpc_client = HttpClient::builder(
-            conf.get_rpc_url()
-                .parse()
-                .map_err(Into::<HyperlaneCosmosError>::into)?,
-        )
-        // Consider supporting different compatibility modes.
-        .compat_mode(CompatMode::latest())
-        .build()
-        .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let clients = conf
+            .get_rpc_urls()
+            .into_iter()
+            .map(CosmosRpcClient::new)
+            .collect::<Result<Vec<_>, HyperlaneCosmosError>>()?;
+        let mut builder = FallbackProvider::builder();
+        builder = builder.add_providers(clients);
+        if let Some(conf) = conf.get_health_scoring() {
+            builder = builder.with_health_scoring(conf.clone());
+        }
+        let rpc_client = CosmosFallbackProvider::new(builder.build());
+        rpc_client.spawn_block_height_sampler();
 
         Ok(Self {
             domain,
             rpc_client,
             grpc_client,
             canonical_asset: conf.get_canonical_asset(),
+            bech32_prefix: conf.get_bech32_prefix(),
         })
     }
 
@@ -62,8 +77,8 @@ impl CosmosProvider {
         &self.grpc_client
     }
 
-    /// Get an rpc client
-    pub fn rpc(&self) -> &HttpClient {
+    /// Get an rpc client, falling back between the configured endpoints
+    pub fn rpc(&self) -> &CosmosFallbackProvider<CosmosRpcClient> {
         &self.rpc_client
     }
 }
@@ -80,12 +95,99 @@ impl HyperlaneChain for CosmosProvider {
 
 #[async_trait]
 impl HyperlaneProvider for CosmosProvider {
-    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
-        todo!() // FIXME
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        let tendermint_hash = Hash::from_bytes(Algorithm::Sha256, hash.as_bytes())
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let response = self
+            .rpc_client
+            .call(move |provider| {
+                let future = async move {
+                    Ok(provider
+                        .client()
+                        .block_by_hash(tendermint_hash)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?)
+                };
+                Box::pin(future)
+            })
+            .await?;
+        let header = response
+            .block
+            .ok_or_else(|| ChainCommunicationError::from_other_str("block not found"))?
+            .header;
+        Ok(BlockInfo {
+            hash: *hash,
+            timestamp: header.time.unix_timestamp() as u64,
+            number: header.height.value(),
+        })
     }
 
-    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
-        todo!() // FIXME
+    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
+        let tendermint_hash = Hash::from_bytes(Algorithm::Sha256, hash.as_bytes())
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let response = self
+            .rpc_client
+            .call(move |provider| {
+                let future = async move {
+                    Ok(provider
+                        .client()
+                        .tx(tendermint_hash, false)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?)
+                };
+                Box::pin(future)
+            })
+            .await?;
+        let tx = Tx::from_bytes(&response.tx).map_err(Into::<HyperlaneCosmosError>::into)?;
+
+        let signer =
+            tx.auth_info.signer_infos.first().ok_or_else(|| {
+                ChainCommunicationError::from_other_str("transaction has no signer")
+            })?;
+        let Some(SignerPublicKey::Single(public_key)) = signer.public_key.clone() else {
+            return Err(ChainCommunicationError::from_other_str(
+                "transaction signer has no single public key",
+            ));
+        };
+        let sender = CosmosAddress::from_pubkey(public_key, &self.bech32_prefix)?.digest();
+
+        // The contract of the first message, the one e.g. a dispatch was sent to
+        let recipient = tx
+            .body
+            .messages
+            .first()
+            .and_then(|msg| MsgExecuteContract::decode(msg.value.as_slice()).ok())
+            .map(|msg| CosmosAddress::from_str(&msg.contract))
+            .transpose()?
+            .map(|address| address.digest());
+
+        // Fees are paid in full rather than for the gas used, and their price
+        // can be fractional, so it's rounded down
+        let gas_limit = U256::from(tx.auth_info.fee.gas_limit);
+        let fee = tx
+            .auth_info
+            .fee
+            .amount
+            .iter()
+            .find(|coin| coin.denom.as_ref() == self.canonical_asset)
+            .map_or(U256::zero(), |coin| U256::from(coin.amount));
+        let gas_price = (!gas_limit.is_zero()).then(|| fee / gas_limit);
+
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit,
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price,
+            nonce: signer.sequence,
+            sender,
+            recipient,
+            receipt: Some(TxnReceiptInfo {
+                gas_used: U256::from(response.tx_result.gas_used),
+                cumulative_gas_used: U256::from(response.tx_result.gas_used),
+                effective_gas_price: gas_price,
+            }),
+        })
     }
 
     async fn is_contract(&self, _address: &H256) -> ChainResult<bool> {
@@ -1,19 +1,67 @@
 use async_trait::async_trait;
 use cosmrs::rpc::client::Client;
-use hyperlane_core::{ChainCommunicationError, ChainResult, ContractLocator, LogMeta, H256, U256};
+use hyperlane_core::{
+    rpc_clients::BlockNumberGetter, ChainCommunicationError, ChainResult, ContractLocator, LogMeta,
+    H256, U256,
+};
 use sha256::digest;
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
 use tendermint::abci::{Event, EventAttribute};
 use tendermint::hash::Algorithm;
 use tendermint::Hash;
+use tendermint_rpc::client::CompatMode;
 use tendermint_rpc::endpoint::block::Response as BlockResponse;
 use tendermint_rpc::endpoint::block_results::Response as BlockResultsResponse;
-use tendermint_rpc::HttpClient;
-use tracing::{debug, instrument, trace};
+use tendermint_rpc::{HttpClient, Order, Query};
+use tracing::{debug, instrument, trace, warn};
+use url::Url;
 
 use crate::address::CosmosAddress;
+use crate::rpc_clients::EventSubscription;
 use crate::{ConnectionConf, CosmosProvider, HyperlaneCosmosError};
 
+/// Most transactions fetched per `tx_search` request
+const TX_SEARCH_PAGE_SIZE: u8 = 100;
+
+/// A Tendermint RPC client of one of the endpoints of a chain
+#[derive(Debug, Clone)]
+pub struct CosmosRpcClient {
+    client: HttpClient,
+    /// The url that this client is connected to.
+    /// Not explicitly used, but useful for debugging.
+    _url: Url,
+}
+
+impl CosmosRpcClient {
+    /// Create a client of the endpoint at `url`
+    pub fn new(url: Url) -> Result<Self, HyperlaneCosmosError> {
+        let client = HttpClient::builder(url.as_str().parse()?)
+            // Consider supporting different compatibility modes.
+            .compat_mode(CompatMode::latest())
+            .build()?;
+        Ok(Self { client, _url: url })
+    }
+
+    /// Get the underlying client
+    pub fn client(&self) -> &HttpClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl BlockNumberGetter for CosmosRpcClient {
+    async fn get_block_number(&self) -> Result<u64, ChainCommunicationError> {
+        let response = self
+            .client
+            .latest_block()
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        Ok(response.block.header.height.value())
+    }
+}
+
 #[async_trait]
 /// Trait for wasm indexer. Use rpc provider
 pub trait WasmIndexer: Send + Sync {
@@ -29,6 +77,10 @@ pub trait WasmIndexer: Send + Sync {
     ) -> ChainResult<Vec<(T, LogMeta)>>
     where
         T: Send + Sync + PartialEq + Debug + 'static;
+
+    /// Wait until new events may have been emitted. Never completes if the
+    /// indexer isn't subscribed to new events.
+    async fn new_logs_notified(&self);
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -60,6 +112,9 @@ pub struct CosmosWasmIndexer {
     contract_address: CosmosAddress,
     target_event_kind: String,
     reorg_period: u32,
+    /// Subscription to new events of the contract, if the connection has a
+    /// websocket url to subscribe with
+    subscription: Option<Arc<EventSubscription>>,
 }
 
 impl CosmosWasmIndexer {
@@ -78,40 +133,118 @@ impl CosmosWasmIndexer {
             Some(locator.clone()),
             None,
         )?;
+        let contract_address = CosmosAddress::from_h256(
+            locator.address,
+            conf.get_bech32_prefix().as_str(),
+            conf.get_contract_address_bytes(),
+        )?;
+        let target_event_kind = format!("{}-{}", Self::WASM_TYPE, event_type);
+        let subscription = conf.get_websocket_url().map(|url| {
+            Arc::new(EventSubscription::new(
+                url,
+                &target_event_kind,
+                contract_address.address().as_str(),
+            ))
+        });
         Ok(Self {
             provider,
-            contract_address: CosmosAddress::from_h256(
-                locator.address,
-                conf.get_bech32_prefix().as_str(),
-                conf.get_contract_address_bytes(),
-            )?,
-            target_event_kind: format!("{}-{}", Self::WASM_TYPE, event_type),
+            contract_address,
+            target_event_kind,
             reorg_period,
+            subscription,
         })
     }
 
-    async fn get_block(client: HttpClient, block_number: u32) -> ChainResult<BlockResponse> {
-        Ok(client
-            .block(block_number)
+    async fn get_block(&self, block_number: u32) -> ChainResult<BlockResponse> {
+        self.provider
+            .rpc()
+            .call(move |provider| {
+                let future = async move {
+                    Ok(provider
+                        .client()
+                        .block(block_number)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?)
+                };
+                Box::pin(future)
+            })
             .await
-            .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
 
-    async fn get_block_results(
-        client: HttpClient,
-        block_number: u32,
-    ) -> ChainResult<BlockResultsResponse> {
-        Ok(client
-            .block_results(block_number)
+    async fn get_block_results(&self, block_number: u32) -> ChainResult<BlockResultsResponse> {
+        self.provider
+            .rpc()
+            .call(move |provider| {
+                let future = async move {
+                    Ok(provider
+                        .client()
+                        .block_results(block_number)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?)
+                };
+                Box::pin(future)
+            })
             .await
-            .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
 
-    async fn get_latest_block(client: HttpClient) -> ChainResult<BlockResponse> {
-        Ok(client
-            .latest_block()
+    /// Heights of the blocks of `range` with events of the contract, as
+    /// indexed by the node
+    async fn heights_with_events(&self, range: RangeInclusive<u32>) -> ChainResult<Vec<u32>> {
+        let query = Query::default()
+            .and_gte("tx.height", *range.start() as u64)
+            .and_lte("tx.height", *range.end() as u64)
+            .and_eq(
+                format!("{}._contract_address", self.target_event_kind),
+                self.contract_address.address(),
+            );
+        let mut heights = vec![];
+        for page in 1.. {
+            let query = query.clone();
+            let response = self
+                .provider
+                .rpc()
+                .call(move |provider| {
+                    let query = query.clone();
+                    let future = async move {
+                        Ok(provider
+                            .client()
+                            .tx_search(query, false, page, TX_SEARCH_PAGE_SIZE, Order::Ascending)
+                            .await
+                            .map_err(Into::<HyperlaneCosmosError>::into)?)
+                    };
+                    Box::pin(future)
+                })
+                .await?;
+            for tx in &response.txs {
+                heights.push(
+                    tx.height
+                        .value()
+                        .try_into()
+                        .map_err(ChainCommunicationError::from_other)?,
+                );
+            }
+            let fetched = (page - 1) * TX_SEARCH_PAGE_SIZE as u32 + response.txs.len() as u32;
+            if response.txs.is_empty() || fetched >= response.total_count {
+                break;
+            }
+        }
+        Ok(heights)
+    }
+
+    async fn get_latest_block(&self) -> ChainResult<BlockResponse> {
+        self.provider
+            .rpc()
+            .call(move |provider| {
+                let future = async move {
+                    Ok(provider
+                        .client()
+                        .latest_block()
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?)
+                };
+                Box::pin(future)
+            })
             .await
-            .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
 }
 
@@ -215,7 +348,7 @@ impl CosmosWasmIndexer {
 impl WasmIndexer for CosmosWasmIndexer {
     #[instrument(err, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        let latest_block = Self::get_latest_block(self.provider.rpc().clone()).await?;
+        let latest_block = self.get_latest_block().await?;
         let latest_height: u32 = latest_block
             .block
             .header
@@ -236,14 +369,38 @@ impl WasmIndexer for CosmosWasmIndexer {
     where
         T: Send + Sync + PartialEq + Debug + 'static,
     {
-        let client = self.provider.rpc().clone();
+        if let Some(subscription) = &self.subscription {
+            // Blocks are only skipped once RPC agrees they have no events
+            if let Some(range) = subscription.unconfirmed(block_number) {
+                match self.heights_with_events(range.clone()).await {
+                    Ok(heights) => subscription.on_confirmed(range, heights),
+                    Err(err) => warn!(
+                        ?err,
+                        ?range,
+                        cursor_label,
+                        "Failed to confirm the blocks with events"
+                    ),
+                }
+            }
+            if subscription.has_no_events(block_number) {
+                trace!(?block_number, cursor_label, "Skipping block without events");
+                return Ok(vec![]);
+            }
+        }
         debug!(?block_number, cursor_label, domain=?self.provider.domain, "Getting logs in block");
 
         // The two calls below could be made in parallel, but on cosmos rate limiting is a bigger problem
         // than indexing latency, so we do them sequentially.
-        let block = Self::get_block(client.clone(), block_number).await?;
-        let block_results = Self::get_block_results(client.clone(), block_number).await?;
+        let block = self.get_block(block_number).await?;
+        let block_results = self.get_block_results(block_number).await?;
 
         Ok(self.handle_txs(block, block_results, parser, cursor_label))
     }
+
+    async fn new_logs_notified(&self) {
+        match &self.subscription {
+            Some(subscription) => subscription.new_event().await,
+            None => std::future::pending().await,
+        }
+    }
 }
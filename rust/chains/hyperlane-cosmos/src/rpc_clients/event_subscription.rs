@@ -0,0 +1,355 @@
+use std::{
+    collections::BTreeSet,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{SinkExt, StreamExt};
+use hyperlane_core::ChainResult;
+use serde_json::{json, Value};
+use tokio::{sync::Notify, task::JoinHandle, time::sleep};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{info, trace, warn};
+use url::Url;
+
+use crate::HyperlaneCosmosError;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+/// Most recent blocks whose events are remembered, beyond which blocks are
+/// fetched as if the subscription didn't cover them
+const RETAINED_BLOCKS: u32 = 100_000;
+/// Events of a block are sent after its header, but by another subscription
+/// that may lag a little, so a block is only confirmed over RPC once the
+/// header this many blocks later was received
+const COMPLETION_LAG: u32 = 2;
+
+const TX_SUBSCRIPTION_ID: u64 = 1;
+const HEADER_SUBSCRIPTION_ID: u64 = 2;
+const TX_EVENT_TYPE: &str = "tendermint/event/Tx";
+const HEADER_EVENT_TYPE: &str = "tendermint/event/NewBlockHeader";
+
+/// Notifies of new events of a contract as soon as they are emitted, using
+/// a Tendermint `subscribe` subscription over websocket.
+///
+/// Events are still fetched block by block over http by the cursors, which
+/// keep polling on their own while the websocket is disconnected. While it
+/// is connected, the subscription also tells which blocks have no events of
+/// the contract, so they don't need fetching, once the blocks with events
+/// are confirmed over RPC. Blocks from before it was
+/// (re)connected are fetched as usual, which backfills any gap. The
+/// subscription is reconnected in the background.
+#[derive(Debug)]
+pub struct EventSubscription {
+    notify: Arc<Notify>,
+    coverage: Arc<Mutex<Coverage>>,
+    task: JoinHandle<()>,
+}
+
+/// The blocks whose events the subscription received
+#[derive(Debug, Default)]
+struct Coverage {
+    /// First block whose events were all received
+    from: Option<u32>,
+    /// Last block whose header was received
+    tip: Option<u32>,
+    /// Last block up to which the blocks with events were confirmed over
+    /// RPC
+    confirmed_to: Option<u32>,
+    /// Blocks from `from` with events of the contract, as received by the
+    /// subscription or found when confirming
+    blocks_with_events: BTreeSet<u32>,
+}
+
+impl Coverage {
+    fn on_header(&mut self, height: u32) {
+        let contiguous = self.tip.map_or(false, |tip| tip + 1 == height);
+        if !contiguous {
+            // Missed headers, so missed events may have been too
+            *self = Self {
+                from: Some(height),
+                ..Default::default()
+            };
+        }
+        self.tip = Some(height);
+        let oldest = height.saturating_sub(RETAINED_BLOCKS);
+        if self.from.map_or(false, |from| from < oldest) {
+            self.from = Some(oldest);
+            self.blocks_with_events = self.blocks_with_events.split_off(&oldest);
+        }
+    }
+
+    fn on_event(&mut self, height: u32) {
+        if self.from.map_or(false, |from| height >= from) {
+            self.blocks_with_events.insert(height);
+        }
+    }
+
+    /// The blocks to confirm over RPC before telling whether the block has
+    /// events, if it's covered but not confirmed yet
+    fn unconfirmed(&self, height: u32) -> Option<RangeInclusive<u32>> {
+        let (from, tip) = (self.from?, self.tip?);
+        let confirmable_to = tip.checked_sub(COMPLETION_LAG)?;
+        let confirm_from = self.confirmed_to.map_or(from, |confirmed| confirmed + 1);
+        (confirm_from <= height && height <= confirmable_to)
+            .then_some(confirm_from..=confirmable_to)
+    }
+
+    /// Record that the blocks of `range` with events of the contract are
+    /// `heights`, unless the coverage restarted since
+    fn on_confirmed(&mut self, range: RangeInclusive<u32>, heights: impl IntoIterator<Item = u32>) {
+        let Some(from) = self.from else {
+            return;
+        };
+        if *range.start() < from || self.tip.map_or(true, |tip| *range.end() > tip) {
+            return;
+        }
+        self.blocks_with_events
+            .extend(heights.into_iter().filter(|height| range.contains(height)));
+        if self
+            .confirmed_to
+            .map_or(true, |confirmed| confirmed < *range.end())
+        {
+            self.confirmed_to = Some(*range.end());
+        }
+    }
+
+    fn has_no_events(&self, height: u32) -> bool {
+        match (self.from, self.confirmed_to) {
+            (Some(from), Some(confirmed_to)) => {
+                from <= height
+                    && height <= confirmed_to
+                    && !self.blocks_with_events.contains(&height)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventSubscription {
+    /// Subscribe to the `event_kind` events of the contract with the bech32
+    /// `contract_address` over the websocket at `url`
+    pub fn new(url: Url, event_kind: &str, contract_address: &str) -> Self {
+        let query =
+            format!("tm.event='Tx' AND {event_kind}._contract_address='{contract_address}'");
+        let notify = Arc::new(Notify::new());
+        let coverage = Arc::new(Mutex::new(Coverage::default()));
+        let task = tokio::spawn(run_subscription(
+            url,
+            query,
+            notify.clone(),
+            coverage.clone(),
+        ));
+        Self {
+            notify,
+            coverage,
+            task,
+        }
+    }
+
+    /// Wait for a new event. An event emitted while nobody was waiting isn't
+    /// missed, the next call returns immediately.
+    pub async fn new_event(&self) {
+        self.notify.notified().await
+    }
+
+    /// The blocks whose events to confirm over RPC before telling whether
+    /// the block at `height` has events, if any
+    pub fn unconfirmed(&self, height: u32) -> Option<RangeInclusive<u32>> {
+        self.coverage.lock().unwrap().unconfirmed(height)
+    }
+
+    /// Record that the blocks of `range` with events of the contract are
+    /// `heights`, as found over RPC
+    pub fn on_confirmed(&self, range: RangeInclusive<u32>, heights: impl IntoIterator<Item = u32>) {
+        self.coverage.lock().unwrap().on_confirmed(range, heights)
+    }
+
+    /// Whether the block is known to have no events of the contract, by both
+    /// the subscription and RPC, so it doesn't need fetching
+    pub fn has_no_events(&self, height: u32) -> bool {
+        self.coverage.lock().unwrap().has_no_events(height)
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run_subscription(
+    url: Url,
+    query: String,
+    notify: Arc<Notify>,
+    coverage: Arc<Mutex<Coverage>>,
+) {
+    loop {
+        match subscribe(&url, &query, &notify, &coverage).await {
+            Ok(()) => warn!("Event subscription ended, polling until it is reconnected"),
+            Err(err) => warn!(
+                ?err,
+                "Event subscription failed, polling until it is reconnected"
+            ),
+        }
+        *coverage.lock().unwrap() = Coverage::default();
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn subscribe(
+    url: &Url,
+    query: &str,
+    notify: &Notify,
+    coverage: &Mutex<Coverage>,
+) -> ChainResult<()> {
+    let (mut ws, _) = connect_async(url.as_str())
+        .await
+        .map_err(Into::<HyperlaneCosmosError>::into)?;
+    // Headers are only subscribed to once events are, so that the events of
+    // every block whose header is received are too
+    for (id, query) in [
+        (TX_SUBSCRIPTION_ID, query),
+        (HEADER_SUBSCRIPTION_ID, "tm.event='NewBlockHeader'"),
+    ] {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "subscribe",
+            "params": { "query": query },
+        });
+        ws.send(Message::Text(request.to_string()))
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        loop {
+            let Some(response) = ws.next().await else {
+                return Ok(());
+            };
+            let response = response.map_err(Into::<HyperlaneCosmosError>::into)?;
+            if let Some(response) = parse_response(response)? {
+                if response.get("id").and_then(Value::as_u64) == Some(id) {
+                    break;
+                }
+            }
+        }
+    }
+    info!(query, "Subscribed to events");
+
+    while let Some(message) = ws.next().await {
+        let message = message.map_err(Into::<HyperlaneCosmosError>::into)?;
+        let Some(response) = parse_response(message)? else {
+            continue;
+        };
+        let result = &response["result"];
+        match result["data"]["type"].as_str() {
+            Some(TX_EVENT_TYPE) => {
+                let height = result["events"]["tx.height"][0]
+                    .as_str()
+                    .and_then(|h| h.parse().ok());
+                trace!(?height, "Notified of new event");
+                match height {
+                    Some(height) => coverage.lock().unwrap().on_event(height),
+                    // Without its height, any block may have the event
+                    None => *coverage.lock().unwrap() = Coverage::default(),
+                }
+                notify.notify_one();
+            }
+            Some(HEADER_EVENT_TYPE) => {
+                let height = result["data"]["value"]["header"]["height"]
+                    .as_str()
+                    .and_then(|h| h.parse().ok());
+                if let Some(height) = height {
+                    coverage.lock().unwrap().on_header(height);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parse a JSON-RPC response from the websocket, or None if the message
+/// isn't one
+fn parse_response(message: Message) -> ChainResult<Option<Value>> {
+    let Message::Text(text) = message else {
+        return Ok(None);
+    };
+    let response: Value = serde_json::from_str(&text)?;
+    if let Some(error) = response.get("error") {
+        return Err(HyperlaneCosmosError::SubscriptionError(error.to_string()).into());
+    }
+    Ok(Some(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage_of_contiguous_headers() {
+        let mut coverage = Coverage::default();
+        assert!(!coverage.has_no_events(10));
+        assert_eq!(coverage.unconfirmed(10), None);
+
+        coverage.on_header(10);
+        coverage.on_event(10);
+        coverage.on_header(11);
+        coverage.on_header(12);
+        coverage.on_header(13);
+        // Nothing is skipped until confirmed over RPC
+        assert!(!coverage.has_no_events(11));
+        // Blocks before the subscription, and those close to the tip, aren't
+        // confirmed
+        assert_eq!(coverage.unconfirmed(9), None);
+        assert_eq!(coverage.unconfirmed(12), None);
+        assert_eq!(coverage.unconfirmed(10), Some(10..=11));
+
+        coverage.on_confirmed(10..=11, []);
+        assert_eq!(coverage.unconfirmed(11), None);
+        assert!(!coverage.has_no_events(9));
+        assert!(!coverage.has_no_events(10));
+        assert!(coverage.has_no_events(11));
+        assert!(!coverage.has_no_events(12));
+
+        // A gap in the headers restarts the coverage
+        coverage.on_header(15);
+        assert!(!coverage.has_no_events(11));
+        coverage.on_header(16);
+        coverage.on_header(17);
+        assert!(!coverage.has_no_events(15));
+        assert_eq!(coverage.unconfirmed(15), Some(15..=15));
+        coverage.on_confirmed(15..=15, []);
+        assert!(coverage.has_no_events(15));
+    }
+
+    #[test]
+    fn test_coverage_confirms_events_missed_by_subscription() {
+        let mut coverage = Coverage::default();
+        for height in 10..=20 {
+            coverage.on_header(height);
+        }
+        coverage.on_event(12);
+        // Events of blocks 14 and 15 weren't received by the subscription
+        coverage.on_confirmed(10..=18, [12, 14, 15, 30]);
+        assert!(coverage.has_no_events(13));
+        assert!(!coverage.has_no_events(12));
+        assert!(!coverage.has_no_events(14));
+        assert!(!coverage.has_no_events(15));
+        assert_eq!(coverage.unconfirmed(18), None);
+        assert!(!coverage.blocks_with_events.contains(&30));
+    }
+
+    #[test]
+    fn test_coverage_ignores_confirmations_from_before_restart() {
+        let mut coverage = Coverage::default();
+        for height in 10..=20 {
+            coverage.on_header(height);
+        }
+        let range = coverage.unconfirmed(12).unwrap();
+        // The subscription restarted while confirming
+        coverage.on_header(30);
+        coverage.on_confirmed(range, []);
+        assert!(!coverage.has_no_events(12));
+        assert_eq!(coverage.confirmed_to, None);
+    }
+}
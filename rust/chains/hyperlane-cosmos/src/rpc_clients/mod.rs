@@ -1,3 +1,4 @@
-pub use self::fallback::*;
+pub use self::{event_subscription::*, fallback::*};
 
+mod event_subscription;
 mod fallback;
@@ -1,7 +1,10 @@
 use std::str::FromStr;
 
 use derive_new::new;
-use hyperlane_core::{config::OperationBatchConfig, ChainCommunicationError, FixedPointNumber};
+use hyperlane_core::{
+    config::OperationBatchConfig, rpc_clients::HealthScoringConf, ChainCommunicationError,
+    FixedPointNumber,
+};
 use url::Url;
 
 /// Cosmos connection configuration
@@ -9,8 +12,8 @@ use url::Url;
 pub struct ConnectionConf {
     /// The GRPC url to connect to
     grpc_urls: Vec<Url>,
-    /// The RPC url to connect to
-    rpc_url: String,
+    /// The RPC urls to connect to
+    rpc_urls: Vec<Url>,
     /// The chain ID
     chain_id: String,
     /// The human readable address prefix for the chains using bech32.
@@ -27,6 +30,16 @@ pub struct ConnectionConf {
     contract_address_bytes: usize,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// Health scoring of the gRPC and RPC endpoints, if set. Otherwise
+    /// endpoints are only deprioritized when they stall.
+    health_scoring: Option<HealthScoringConf>,
+    /// The bech32 address of an account that granted the signer an
+    /// allowance to pay its transaction fees, if any. Fees are then paid by
+    /// this account instead of the signer.
+    fee_granter: Option<String>,
+    /// Tendermint websocket url to subscribe to new events with, so they are
+    /// indexed without waiting for the next poll
+    websocket_url: Option<Url>,
 }
 
 /// Untyped cosmos amount
@@ -83,9 +96,9 @@ impl ConnectionConf {
         self.grpc_urls.clone()
     }
 
-    /// Get the RPC url
-    pub fn get_rpc_url(&self) -> String {
-        self.rpc_url.clone()
+    /// Get the RPC urls
+    pub fn get_rpc_urls(&self) -> Vec<Url> {
+        self.rpc_urls.clone()
     }
 
     /// Get the chain ID
@@ -113,27 +126,48 @@ impl ConnectionConf {
         self.contract_address_bytes
     }
 
+    /// Get the health scoring configuration of the endpoints
+    pub fn get_health_scoring(&self) -> Option<&HealthScoringConf> {
+        self.health_scoring.as_ref()
+    }
+
+    /// Get the account paying the transaction fees of the signer, if any
+    pub fn get_fee_granter(&self) -> Option<String> {
+        self.fee_granter.clone()
+    }
+
+    /// Get the websocket url to subscribe to new events with, if any
+    pub fn get_websocket_url(&self) -> Option<Url> {
+        self.websocket_url.clone()
+    }
+
     /// Create a new connection configuration
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         grpc_urls: Vec<Url>,
-        rpc_url: String,
+        rpc_urls: Vec<Url>,
         chain_id: String,
         bech32_prefix: String,
         canonical_asset: String,
         minimum_gas_price: RawCosmosAmount,
         contract_address_bytes: usize,
         operation_batch: OperationBatchConfig,
+        health_scoring: Option<HealthScoringConf>,
+        fee_granter: Option<String>,
+        websocket_url: Option<Url>,
     ) -> Self {
         Self {
             grpc_urls,
-            rpc_url,
+            rpc_urls,
             chain_id,
             bech32_prefix,
             canonical_asset,
             gas_price: minimum_gas_price,
             contract_address_bytes,
             operation_batch,
+            health_scoring,
+            fee_granter,
+            websocket_url,
         }
     }
 }
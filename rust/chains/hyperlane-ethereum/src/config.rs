@@ -1,4 +1,11 @@
-use hyperlane_core::{config::OperationBatchConfig, U256};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use hyperlane_core::{
+    config::OperationBatchConfig,
+    rpc_clients::{CircuitBreaker, HealthScoringConf, RateLimitConf},
+    FinalityTag, HyperlaneDomain, U256,
+};
+use serde::Deserialize;
 use url::Url;
 
 /// Ethereum RPC connection configuration
@@ -13,6 +20,9 @@ pub enum RpcConnectionConf {
     HttpFallback {
         /// List of urls to connect to in order of priority
         urls: Vec<Url>,
+        /// Demote providers by their error rate, latency and block lag. If
+        /// not set, providers are only demoted once they stall.
+        health_scoring: Option<HealthScoringConf>,
     },
     /// HTTP connection details
     Http {
@@ -35,6 +45,97 @@ pub struct ConnectionConf {
     pub transaction_overrides: TransactionOverrides,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// Stuck transaction replacement configuration. If not specified,
+    /// submitted transactions are never replaced.
+    pub transaction_replacement: Option<TransactionReplacementConf>,
+    /// Websocket url to subscribe to new logs with, so that indexing doesn't
+    /// wait for the next poll. If not specified, indexing only polls.
+    pub log_subscription_url: Option<Url>,
+    /// Client-side rate limits of the rpc urls
+    pub rpc_rate_limits: RpcRateLimits,
+    /// Circuit breaker of the chain, shared by every client built with this
+    /// connection. If not specified, requests never fail fast.
+    pub circuit_breaker: Option<Arc<CircuitBreaker>>,
+    /// Expected time between blocks, pacing how often pending transactions
+    /// are polled and how long they are waited on. Assumes a few seconds if
+    /// not specified.
+    pub block_time: Option<Duration>,
+    /// Block tag treated as final by the indexers and the validator, instead
+    /// of lagging the tip by the reorg period
+    pub finality_tag: Option<FinalityTag>,
+    /// Number of blocks a submitted transaction has to be included for before
+    /// it is considered confirmed
+    pub transaction_confirmations: usize,
+    /// Method tracing a delivery simulation that reverted, to find its revert
+    /// data when the node doesn't return it. If not specified, reverts are
+    /// reported as returned by the node.
+    pub revert_trace_method: Option<RevertTraceMethod>,
+    /// Adapter for the fees of the chain that generic estimation doesn't
+    /// account for. If not specified, Arbitrum Nitro based chains use the
+    /// Arbitrum adapter and other chains none.
+    pub fee_adapter: Option<FeeAdapterKind>,
+    /// Block that reads and gas estimates are made at instead of the latest
+    /// one, to simulate against the past state of an archive node. Never
+    /// read from the agent config, only set by tools.
+    pub historical_block: Option<u64>,
+}
+
+impl ConnectionConf {
+    /// The fee adapter of a chain, the configured one or the default of its
+    /// technical stack
+    pub fn fee_adapter_kind(&self, domain: &HyperlaneDomain) -> Option<FeeAdapterKind> {
+        self.fee_adapter.or_else(|| {
+            domain
+                .is_arbitrum_nitro()
+                .then_some(FeeAdapterKind::Arbitrum)
+        })
+    }
+}
+
+/// Kind of fee adapter, for the way an L2 charges fees
+#[derive(Copy, Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeAdapterKind {
+    /// OP stack chains, charging an L1 data fee quoted by the
+    /// `GasPriceOracle` predeploy
+    OpStack,
+    /// Arbitrum Nitro based chains, charging L1 costs as L2 gas
+    Arbitrum,
+    /// Scroll, charging an L1 data fee quoted by the `L1GasPriceOracle`
+    /// predeploy
+    Scroll,
+    /// Linea, requiring priority fees that grow with the size of the
+    /// transaction data
+    Linea,
+}
+
+/// RPC method tracing a call, which not every node supports
+#[derive(Copy, Debug, Deserialize, Clone, PartialEq, Eq)]
+pub enum RevertTraceMethod {
+    /// `debug_traceCall` with the `callTracer`, supported by geth and most
+    /// of its forks
+    #[serde(rename = "debug_traceCall")]
+    DebugTraceCall,
+    /// `trace_call`, supported by erigon, nethermind and reth
+    #[serde(rename = "trace_call")]
+    TraceCall,
+}
+
+/// Client-side rate limits of the rpc urls of a chain
+#[derive(Debug, Clone, Default)]
+pub struct RpcRateLimits {
+    /// Limit of the urls without a limit of their own. If not specified,
+    /// they aren't limited.
+    pub default: Option<RateLimitConf>,
+    /// Limits of specific urls
+    pub urls: HashMap<Url, RateLimitConf>,
+}
+
+impl RpcRateLimits {
+    /// Get the limit of requests to a url, if any
+    pub fn for_url(&self, url: &Url) -> Option<&RateLimitConf> {
+        self.urls.get(url).or(self.default.as_ref())
+    }
 }
 
 /// Ethereum transaction overrides.
@@ -51,3 +152,31 @@ pub struct TransactionOverrides {
     /// Max priority fee per gas to use for EIP-1559 transactions.
     pub max_priority_fee_per_gas: Option<U256>,
 }
+
+/// Configuration for detecting and replacing transactions that are not mined
+/// in a timely manner.
+#[derive(Debug, Clone)]
+pub struct TransactionReplacementConf {
+    /// Number of blocks a transaction can remain unmined before it is
+    /// considered stuck.
+    pub stuck_after_blocks: u64,
+    /// Percentage by which the fees of a stuck transaction are bumped when
+    /// replacing it. Most nodes require at least a 10% increase.
+    pub fee_bump_percent: u64,
+    /// Maximum number of fee-bumped replacements to attempt.
+    pub max_replacements: u32,
+    /// If true, once `max_replacements` is reached the nonce is freed up by
+    /// sending a zero-value transfer to self with the same nonce.
+    pub cancel_when_exhausted: bool,
+}
+
+impl Default for TransactionReplacementConf {
+    fn default() -> Self {
+        Self {
+            stuck_after_blocks: 10,
+            fee_bump_percent: 15,
+            max_replacements: 3,
+            cancel_when_exhausted: true,
+        }
+    }
+}
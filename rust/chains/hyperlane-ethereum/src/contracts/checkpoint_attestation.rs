@@ -0,0 +1,160 @@
+#![allow(clippy::enum_variant_names)]
+#![allow(missing_docs)]
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use hyperlane_core::{
+    ChainResult, CheckpointAttestation, ContractLocator, HyperlaneAbi, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, SignedCheckpointWithMessageId,
+    TxOutcome, H160, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    interfaces::i_checkpoint_attestation::{
+        ICheckpointAttestation as EthereumCheckpointAttestationInternal, ICHECKPOINTATTESTATION_ABI,
+    },
+    tx::{fill_tx_gas_params, report_tx},
+    BuildableWithProvider, ConnectionConf, EthereumProvider,
+};
+
+impl<M> std::fmt::Display for EthereumCheckpointAttestationInternal<M>
+where
+    M: Middleware,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+pub struct CheckpointAttestationBuilder {}
+
+#[async_trait]
+impl BuildableWithProvider for CheckpointAttestationBuilder {
+    type Output = Box<dyn CheckpointAttestation>;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumCheckpointAttestation::new(
+            Arc::new(provider),
+            conn,
+            locator,
+        ))
+    }
+}
+
+/// A reference to a CheckpointAttestation contract on some Ethereum chain
+#[derive(Debug)]
+pub struct EthereumCheckpointAttestation<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumCheckpointAttestationInternal<M>>,
+    domain: HyperlaneDomain,
+    provider: Arc<M>,
+    conn: ConnectionConf,
+}
+
+impl<M> EthereumCheckpointAttestation<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a reference to a CheckpointAttestation contract at a specific
+    /// Ethereum address on some chain
+    pub fn new(provider: Arc<M>, conn: &ConnectionConf, locator: &ContractLocator) -> Self {
+        Self {
+            contract: Arc::new(EthereumCheckpointAttestationInternal::new(
+                locator.address,
+                provider.clone(),
+            )),
+            domain: locator.domain.clone(),
+            provider,
+            conn: conn.clone(),
+        }
+    }
+}
+
+impl<M> HyperlaneChain for EthereumCheckpointAttestation<M>
+where
+    M: Middleware + 'static,
+{
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(EthereumProvider::new(
+            self.contract.client(),
+            self.domain.clone(),
+        ))
+    }
+}
+
+impl<M> HyperlaneContract for EthereumCheckpointAttestation<M>
+where
+    M: Middleware + 'static,
+{
+    fn address(&self) -> H256 {
+        self.contract.address().into()
+    }
+}
+
+#[async_trait]
+impl<M> CheckpointAttestation for EthereumCheckpointAttestation<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err, skip(self))]
+    async fn latest_attested_index(
+        &self,
+        validator: H256,
+        origin: u32,
+        merkle_tree_hook: H256,
+    ) -> ChainResult<Option<u32>> {
+        let (_, index, _, timestamp) = self
+            .contract
+            .latest_checkpoint(
+                H160::from(validator).into(),
+                origin,
+                merkle_tree_hook.into(),
+            )
+            .call()
+            .await?;
+        // The contract returns zeroes for validators that never attested
+        Ok((!timestamp.is_zero()).then_some(index))
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn attest(&self, checkpoint: &SignedCheckpointWithMessageId) -> ChainResult<TxOutcome> {
+        let serialized_signature: [u8; 65] = checkpoint.signature.into();
+        let value = &checkpoint.value;
+        let tx = self.contract.attest(
+            value.mailbox_domain,
+            value.merkle_tree_hook_address.into(),
+            value.root.into(),
+            value.index,
+            value.message_id.into(),
+            serialized_signature.into(),
+        );
+        let contract_call =
+            fill_tx_gas_params(tx, self.provider.clone(), &self.conn.transaction_overrides).await?;
+        let receipt = report_tx(contract_call, &self.conn).await?;
+        Ok(receipt.into())
+    }
+}
+
+pub struct EthereumCheckpointAttestationAbi;
+
+impl HyperlaneAbi for EthereumCheckpointAttestationAbi {
+    const SELECTOR_SIZE_BYTES: usize = 4;
+
+    fn fn_map() -> HashMap<Vec<u8>, &'static str> {
+        crate::extract_fn_map(&ICHECKPOINTATTESTATION_ABI)
+    }
+}
@@ -8,17 +8,20 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use ethers::prelude::Middleware;
 use hyperlane_core::{
-    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi, HyperlaneChain,
-    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer,
-    InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer, H160, H256, H512,
+    ChainCommunicationError, ChainResult, ContractLocator, FinalityTag, HyperlaneAbi,
+    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer,
+    InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer, TxOutcome, H160,
+    H256, H512, U256,
 };
 use tracing::instrument;
 
-use super::utils::fetch_raw_logs_and_log_meta;
+use super::utils::{fetch_raw_logs_and_log_meta, finalized_block_number};
 use crate::interfaces::i_interchain_gas_paymaster::{
     GasPaymentFilter, IInterchainGasPaymaster as EthereumInterchainGasPaymasterInternal,
     IINTERCHAINGASPAYMASTER_ABI,
 };
+use crate::interfaces::interchain_gas_paymaster::InterchainGasPaymaster as EthereumInterchainGasPaymasterClaimInternal;
+use crate::tx::{fill_tx_gas_params, report_tx};
 use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
 
 impl<M> Display for EthereumInterchainGasPaymasterInternal<M>
@@ -42,14 +45,16 @@ impl BuildableWithProvider for InterchainGasPaymasterIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumInterchainGasPaymasterIndexer::new(
+        let mut indexer = EthereumInterchainGasPaymasterIndexer::new(
             Arc::new(provider),
             locator,
             self.reorg_period,
-        ))
+        );
+        indexer.finality_tag = conn.finality_tag;
+        Box::new(indexer)
     }
 }
 
@@ -62,6 +67,8 @@ where
     contract: Arc<EthereumInterchainGasPaymasterInternal<M>>,
     provider: Arc<M>,
     reorg_period: u32,
+    /// Indexes up to the block with this tag instead of lagging the tip
+    finality_tag: Option<FinalityTag>,
 }
 
 impl<M> EthereumInterchainGasPaymasterIndexer<M>
@@ -77,6 +84,7 @@ where
             )),
             provider,
             reorg_period,
+            finality_tag: None,
         }
     }
 }
@@ -118,13 +126,7 @@ where
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        Ok(self
-            .provider
-            .get_block_number()
-            .await
-            .map_err(ChainCommunicationError::from_other)?
-            .as_u32()
-            .saturating_sub(self.reorg_period))
+        finalized_block_number(self.provider.as_ref(), self.reorg_period, self.finality_tag).await
     }
 
     async fn fetch_logs_by_tx_hash(
@@ -180,11 +182,12 @@ impl BuildableWithProvider for InterchainGasPaymasterBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumInterchainGasPaymaster::new(
             Arc::new(provider),
+            conn,
             locator,
         ))
     }
@@ -197,6 +200,10 @@ where
     M: Middleware,
 {
     contract: Arc<EthereumInterchainGasPaymasterInternal<M>>,
+    /// The concrete paymaster, which fees can be claimed from
+    claim_contract: Arc<EthereumInterchainGasPaymasterClaimInternal<M>>,
+    provider: Arc<M>,
+    conn: ConnectionConf,
     domain: HyperlaneDomain,
 }
 
@@ -206,12 +213,18 @@ where
 {
     /// Create a reference to a mailbox at a specific Ethereum address on some
     /// chain
-    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+    pub fn new(provider: Arc<M>, conn: &ConnectionConf, locator: &ContractLocator) -> Self {
         Self {
             contract: Arc::new(EthereumInterchainGasPaymasterInternal::new(
                 locator.address,
-                provider,
+                provider.clone(),
+            )),
+            claim_contract: Arc::new(EthereumInterchainGasPaymasterClaimInternal::new(
+                locator.address,
+                provider.clone(),
             )),
+            provider,
+            conn: conn.clone(),
             domain: locator.domain.clone(),
         }
     }
@@ -243,7 +256,63 @@ where
 }
 
 #[async_trait]
-impl<M> InterchainGasPaymaster for EthereumInterchainGasPaymaster<M> where M: Middleware + 'static {}
+impl<M> InterchainGasPaymaster for EthereumInterchainGasPaymaster<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err, ret, skip(self))]
+    async fn beneficiary(&self) -> ChainResult<H256> {
+        let beneficiary = self.claim_contract.beneficiary().call().await?;
+        Ok(beneficiary.into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn claim(&self) -> ChainResult<TxOutcome> {
+        let tx = fill_tx_gas_params(
+            self.claim_contract.claim(),
+            self.provider.clone(),
+            &self.conn.transaction_overrides,
+        )
+        .await?;
+        let receipt = report_tx(tx, &self.conn).await?;
+        Ok(receipt.into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn pay_for_gas(
+        &self,
+        message_id: H256,
+        destination: u32,
+        gas_amount: U256,
+    ) -> ChainResult<TxOutcome> {
+        let payment = self
+            .contract
+            .quote_gas_payment(destination, gas_amount.into())
+            .call()
+            .await?;
+        let refund_address = self
+            .provider
+            .default_sender()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        let call = self
+            .contract
+            .pay_for_gas(
+                message_id.into(),
+                destination,
+                gas_amount.into(),
+                refund_address,
+            )
+            .value(payment);
+        let tx = fill_tx_gas_params(
+            call,
+            self.provider.clone(),
+            &self.conn.transaction_overrides,
+        )
+        .await?;
+        let receipt = report_tx(tx, &self.conn).await?;
+        Ok(receipt.into())
+    }
+}
 
 pub struct EthereumInterchainGasPaymasterAbi;
 
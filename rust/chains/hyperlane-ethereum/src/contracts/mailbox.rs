@@ -4,34 +4,45 @@
 use std::collections::HashMap;
 use std::num::NonZeroU64;
 use std::ops::RangeInclusive;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use async_trait::async_trait;
-use ethers::abi::{AbiEncode, Detokenize};
-use ethers::prelude::Middleware;
-use ethers_contract::builders::ContractCall;
+use ethers::abi::{decode, AbiEncode, Detokenize, ParamType, Token};
+use ethers::prelude::{Middleware, TransactionReceipt};
+use ethers::providers::MiddlewareError;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers_contract::{builders::ContractCall, EthEvent, MulticallResult};
 use futures_util::future::join_all;
 use hyperlane_core::H512;
 use tracing::instrument;
 
 use hyperlane_core::{
     utils::bytes_to_hex, BatchItem, ChainCommunicationError, ChainResult, ContractLocator,
-    HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProtocolError, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
-    RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H160, H256, U256,
+    FinalityTag, HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProtocolError, HyperlaneProvider, Indexed, Indexer, LogMeta,
+    Mailbox, ProcessSimulation, RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate,
+    TxOutcome, H256, U256,
 };
 
 use crate::error::HyperlaneEthereumError;
-use crate::interfaces::arbitrum_node_interface::ArbitrumNodeInterface;
+use crate::interfaces::i_interchain_security_module::IInterchainSecurityModule;
 use crate::interfaces::i_mailbox::{
-    IMailbox as EthereumMailboxInternal, ProcessCall, IMAILBOX_ABI,
+    DispatchIdFilter, IMailbox as EthereumMailboxInternal, ProcessCall, IMAILBOX_ABI,
+};
+use crate::interfaces::mailbox::{DefaultIsmSetFilter, DispatchFilter};
+use crate::revert::{
+    classify_call_failure, classify_process_revert, decode_revert, is_ism_verification_failure,
+    trace_revert, CallFailure,
+};
+use crate::tx::{bump_tx_fees, call_with_lag, fill_tx_gas_params, report_tx_with_replacement};
+use crate::zksync;
+use crate::{
+    build_fee_adapter, BuildableWithProvider, ConnectionConf, EthereumProvider, FeeAdapter,
+    LogSubscription, TransactionOverrides,
 };
-use crate::interfaces::mailbox::DispatchFilter;
-use crate::tx::{call_with_lag, fill_tx_gas_params, report_tx};
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, TransactionOverrides};
 
 use super::multicall::{self, build_multicall};
-use super::utils::fetch_raw_logs_and_log_meta;
+use super::utils::{fetch_raw_logs_and_log_meta, finalized_block_number};
 
 impl<M> std::fmt::Display for EthereumMailboxInternal<M>
 where
@@ -53,14 +64,18 @@ impl BuildableWithProvider for SequenceIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMailboxIndexer::new(
-            Arc::new(provider),
-            locator,
+        let mut indexer =
+            EthereumMailboxIndexer::new(Arc::new(provider), locator, self.reorg_period);
+        indexer.finality_tag = conn.finality_tag;
+        indexer.log_subscription = LogSubscription::for_event::<DispatchFilter>(
+            conn,
+            indexer.contract.address(),
             self.reorg_period,
-        ))
+        );
+        Box::new(indexer)
     }
 }
 
@@ -75,14 +90,13 @@ impl BuildableWithProvider for DeliveryIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMailboxIndexer::new(
-            Arc::new(provider),
-            locator,
-            self.reorg_period,
-        ))
+        let mut indexer =
+            EthereumMailboxIndexer::new(Arc::new(provider), locator, self.reorg_period);
+        indexer.finality_tag = conn.finality_tag;
+        Box::new(indexer)
     }
 }
 
@@ -95,6 +109,10 @@ where
     contract: Arc<EthereumMailboxInternal<M>>,
     provider: Arc<M>,
     reorg_period: u32,
+    /// Indexes up to the block with this tag instead of lagging the tip
+    finality_tag: Option<FinalityTag>,
+    /// Notifies of new dispatches
+    log_subscription: Option<Arc<LogSubscription>>,
 }
 
 impl<M> EthereumMailboxIndexer<M>
@@ -111,18 +129,14 @@ where
             contract,
             provider,
             reorg_period,
+            finality_tag: None,
+            log_subscription: None,
         }
     }
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        Ok(self
-            .provider
-            .get_block_number()
-            .await
-            .map_err(ChainCommunicationError::from_other)?
-            .as_u32()
-            .saturating_sub(self.reorg_period))
+        finalized_block_number(self.provider.as_ref(), self.reorg_period, self.finality_tag).await
     }
 }
 
@@ -161,6 +175,13 @@ where
         Ok(events)
     }
 
+    async fn new_logs_notified(&self) {
+        match &self.log_subscription {
+            Some(subscription) => subscription.new_log().await,
+            None => std::future::pending().await,
+        }
+    }
+
     async fn fetch_logs_by_tx_hash(
         &self,
         tx_hash: H512,
@@ -262,8 +283,10 @@ where
     contract: Arc<EthereumMailboxInternal<M>>,
     domain: HyperlaneDomain,
     provider: Arc<M>,
-    arbitrum_node_interface: Option<Arc<ArbitrumNodeInterface<M>>>,
+    fee_adapter: Option<Box<dyn FeeAdapter>>,
     conn: ConnectionConf,
+    /// Notifies of new default ISMs, subscribed to once they are waited for
+    default_ism_subscription: OnceLock<Option<Arc<LogSubscription>>>,
 }
 
 impl<M> EthereumMailbox<M>
@@ -273,31 +296,39 @@ where
     /// Create a reference to a mailbox at a specific Ethereum address on some
     /// chain
     pub fn new(provider: Arc<M>, conn: &ConnectionConf, locator: &ContractLocator) -> Self {
-        // Arbitrum Nitro based chains are a special case for transaction cost estimation.
-        // The gas amount that eth_estimateGas returns considers both L1 and L2 gas costs.
-        // We use the NodeInterface, found at address(0xC8), to isolate the L2 gas costs.
-        // See https://developer.arbitrum.io/arbos/gas#nodeinterfacesol or https://github.com/OffchainLabs/nitro/blob/master/contracts/src/node-interface/NodeInterface.sol#L25
-        let arbitrum_node_interface = locator.domain.is_arbitrum_nitro().then(|| {
-            Arc::new(ArbitrumNodeInterface::new(
-                H160::from_low_u64_be(0xC8),
-                provider.clone(),
-            ))
-        });
-
+        // L2s that charge fees generic estimation doesn't account for are
+        // adjusted for by their fee adapter
+        let fee_adapter = conn
+            .fee_adapter_kind(&locator.domain)
+            .map(|kind| build_fee_adapter(kind, provider.clone()));
+        let contract = Arc::new(EthereumMailboxInternal::new(
+            locator.address,
+            provider.clone(),
+        ));
         Self {
-            contract: Arc::new(EthereumMailboxInternal::new(
-                locator.address,
-                provider.clone(),
-            )),
+            contract,
             domain: locator.domain.clone(),
             provider,
-            arbitrum_node_interface,
+            fee_adapter,
             conn: conn.clone(),
+            default_ism_subscription: OnceLock::new(),
         }
     }
 
+    /// The unsigned transaction processing the provided message, without gas
+    /// parameters
+    fn process_tx(&self, message: &HyperlaneMessage, metadata: &[u8]) -> TypedTransaction {
+        self.contract
+            .process(
+                metadata.to_vec().into(),
+                RawHyperlaneMessage::from(message).to_vec().into(),
+            )
+            .tx
+    }
+
     /// Returns a ContractCall that processes the provided message.
-    /// If the provided tx_gas_limit is None, gas estimation occurs.
+    /// If the provided tx_gas_limit is None, gas estimation occurs, and a
+    /// reverting estimation is traced for its revert data if configured.
     async fn process_contract_call(
         &self,
         message: &HyperlaneMessage,
@@ -308,7 +339,17 @@ where
             metadata.to_vec().into(),
             RawHyperlaneMessage::from(message).to_vec().into(),
         );
-        self.add_gas_overrides(tx, tx_gas_estimate).await
+        let unfilled_tx = tx.tx.clone();
+        match self.add_gas_overrides(tx, tx_gas_estimate).await {
+            Err(err) => Err(trace_revert(
+                self.provider.as_ref(),
+                unfilled_tx,
+                self.conn.revert_trace_method,
+                err,
+            )
+            .await),
+            call => call,
+        }
     }
 
     async fn add_gas_overrides<D: Detokenize>(
@@ -326,7 +367,44 @@ where
                 .or(tx_gas_estimate),
             ..self.conn.transaction_overrides.clone()
         };
-        fill_tx_gas_params(tx, self.provider.clone(), &tx_overrides).await
+        let mut call = fill_tx_gas_params(tx, self.provider.clone(), &tx_overrides).await?;
+        if let Some(fee_adapter) = &self.fee_adapter {
+            fee_adapter.adjust_tx(&mut call.tx).await?;
+        }
+        Ok(call)
+    }
+
+    /// Whether the ISM of the recipient accepts the metadata when called by
+    /// the mailbox
+    async fn ism_accepts(&self, message: &HyperlaneMessage, metadata: &[u8]) -> ChainResult<bool> {
+        let ism_address = self
+            .contract
+            .recipient_ism(message.recipient.into())
+            .call()
+            .await?;
+        let ism = IInterchainSecurityModule::new(ism_address, self.provider.clone());
+        let verify = ism
+            .verify(
+                metadata.to_vec().into(),
+                RawHyperlaneMessage::from(message).to_vec().into(),
+            )
+            .from(self.contract.address());
+        match verify.call().await {
+            Ok(verifies) => Ok(verifies),
+            Err(err) => match classify_call_failure(&err.to_string(), err.as_revert().is_some()) {
+                CallFailure::Reverted => Ok(false),
+                CallFailure::OutOfGas | CallFailure::Other => Err(err.into()),
+            },
+        }
+    }
+
+    /// The outcome of a submitted transaction, adjusted for the fees it paid
+    /// apart from its gas
+    fn outcome(&self, receipt: TransactionReceipt) -> TxOutcome {
+        match &self.fee_adapter {
+            Some(fee_adapter) => fee_adapter.adjust_outcome(&receipt, receipt.clone().into()),
+            None => receipt.into(),
+        }
     }
 }
 
@@ -372,11 +450,47 @@ where
         Ok(self.contract.delivered(id.into()).call().await?)
     }
 
+    #[instrument(skip(self, ids), fields(size=%ids.len()))]
+    async fn delivered_batch(&self, ids: &[H256]) -> ChainResult<Vec<bool>> {
+        let mut multicall = build_multicall(self.provider.clone(), &self.conn, self.domain.clone())
+            .await
+            .map_err(|e| HyperlaneEthereumError::MulticallError(e.to_string()))?;
+        let calls = ids
+            .iter()
+            .map(|id| self.contract.delivered((*id).into()))
+            .collect();
+        let results = multicall::batch(&mut multicall, calls).call().await?;
+        decode_delivered_batch(results)
+    }
+
+    #[instrument(skip(self))]
+    async fn processed_at(&self, id: H256) -> ChainResult<Option<u64>> {
+        // Messages that weren't delivered were processed at block 0
+        let block_number = self.contract.processed_at(id.into()).call().await?;
+        Ok((block_number != 0).then_some(block_number))
+    }
+
     #[instrument(skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
         Ok(self.contract.default_ism().call().await?.into())
     }
 
+    async fn wait_for_default_ism_set(&self) -> bool {
+        let subscription = self.default_ism_subscription.get_or_init(|| {
+            // The default ISM is read at the head, so it's refreshed straight away
+            LogSubscription::for_event::<DefaultIsmSetFilter>(
+                &self.conn,
+                self.contract.address(),
+                0,
+            )
+        });
+        let Some(subscription) = subscription else {
+            return false;
+        };
+        subscription.new_log().await;
+        true
+    }
+
     #[instrument(skip(self))]
     async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
         Ok(self
@@ -394,11 +508,43 @@ where
         metadata: &[u8],
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
+        if self.domain.is_zksync() {
+            let receipt = zksync::send_eip712_tx(
+                self.provider.as_ref(),
+                self.process_tx(message, metadata),
+                self.conn.transaction_overrides.gas_limit.or(tx_gas_limit),
+                &self.conn,
+            )
+            .await?;
+            return Ok(receipt.into());
+        }
+
         let contract_call = self
             .process_contract_call(message, metadata, tx_gas_limit)
             .await?;
-        let receipt = report_tx(contract_call).await?;
-        Ok(receipt.into())
+        let receipt = report_tx_with_replacement(contract_call, &self.conn).await?;
+        Ok(self.outcome(receipt))
+    }
+
+    #[instrument(skip(self, message, metadata))]
+    async fn process_with_fee_bump(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+        fee_bump_percent: u64,
+    ) -> ChainResult<TxOutcome> {
+        // zkSync transactions are built with their own fees
+        if fee_bump_percent == 0 || self.domain.is_zksync() {
+            return self.process(message, metadata, tx_gas_limit).await;
+        }
+
+        let mut contract_call = self
+            .process_contract_call(message, metadata, tx_gas_limit)
+            .await?;
+        bump_tx_fees(&mut contract_call.tx, fee_bump_percent);
+        let receipt = report_tx_with_replacement(contract_call, &self.conn).await?;
+        Ok(self.outcome(receipt))
     }
 
     #[instrument(skip(self, messages), fields(size=%messages.len()))]
@@ -426,10 +572,22 @@ where
             .collect::<ChainResult<Vec<_>>>()?;
 
         let batch_call = multicall::batch::<_, ()>(&mut multicall, contract_calls);
+        // Like single deliveries, batches are sent as EIP-712 transactions on
+        // zkSync, with the gas limit estimated by the node
+        if self.domain.is_zksync() {
+            let receipt = zksync::send_eip712_tx(
+                self.provider.as_ref(),
+                batch_call.tx,
+                self.conn.transaction_overrides.gas_limit,
+                &self.conn,
+            )
+            .await?;
+            return Ok(receipt.into());
+        }
         let call = self.add_gas_overrides(batch_call, None).await?;
 
-        let receipt = report_tx(call).await?;
-        Ok(receipt.into())
+        let receipt = report_tx_with_replacement(call, &self.conn).await?;
+        Ok(self.outcome(receipt))
     }
 
     #[instrument(skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
@@ -438,6 +596,17 @@ where
         message: &HyperlaneMessage,
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
+        if self.domain.is_zksync() {
+            let fee =
+                zksync::estimate_fee(self.provider.as_ref(), &self.process_tx(message, metadata))
+                    .await?;
+            return Ok(TxCostEstimate {
+                gas_limit: fee.gas_limit.into(),
+                gas_price: U256::from(fee.max_fee_per_gas).try_into()?,
+                l2_gas_limit: None,
+            });
+        }
+
         let contract_call = self.process_contract_call(message, metadata, None).await?;
         let gas_limit = contract_call
             .tx
@@ -445,27 +614,6 @@ where
             .copied()
             .ok_or(HyperlaneProtocolError::ProcessGasLimitRequired)?;
 
-        // If we have a ArbitrumNodeInterface, we need to set the l2_gas_limit.
-        let l2_gas_limit = if let Some(arbitrum_node_interface) = &self.arbitrum_node_interface {
-            Some(
-                arbitrum_node_interface
-                    .estimate_retryable_ticket(
-                        H160::zero().into(),
-                        // Give the sender a deposit, otherwise it reverts
-                        U256::MAX.into(),
-                        self.contract.address(),
-                        U256::zero().into(),
-                        H160::zero().into(),
-                        H160::zero().into(),
-                        contract_call.calldata().unwrap_or_default(),
-                    )
-                    .estimate_gas()
-                    .await?,
-            )
-        } else {
-            None
-        };
-
         let gas_price: U256 = self
             .provider
             .get_gas_price()
@@ -473,11 +621,58 @@ where
             .map_err(ChainCommunicationError::from_other)?
             .into();
 
-        Ok(TxCostEstimate {
+        let estimate = TxCostEstimate {
             gas_limit: gas_limit.into(),
             gas_price: gas_price.try_into()?,
-            l2_gas_limit: l2_gas_limit.map(|v| v.into()),
-        })
+            l2_gas_limit: None,
+        };
+        match &self.fee_adapter {
+            Some(fee_adapter) => {
+                fee_adapter
+                    .adjust_estimate(&contract_call.tx, estimate)
+                    .await
+            }
+            None => Ok(estimate),
+        }
+    }
+
+    #[instrument(skip(self), fields(metadata=%bytes_to_hex(metadata)))]
+    async fn simulate_process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: U256,
+    ) -> ChainResult<Option<ProcessSimulation>> {
+        // zkSync deliveries are only simulated by estimating their fee
+        if self.domain.is_zksync() {
+            return Ok(None);
+        }
+        let mut tx = self.process_tx(message, metadata);
+        tx.set_gas(ethers::types::U256::from(tx_gas_limit));
+        if let Some(sender) = self.provider.default_sender() {
+            tx.set_from(sender);
+        }
+        let err = match self.provider.call(&tx, None).await {
+            Ok(_) => return Ok(Some(ProcessSimulation::WouldSucceed)),
+            Err(err) => err,
+        };
+
+        let revert_data = err
+            .as_error_response()
+            .and_then(|response| response.as_revert_data());
+        match classify_call_failure(&err.to_string(), revert_data.is_some()) {
+            CallFailure::OutOfGas => return Ok(Some(ProcessSimulation::OutOfGas)),
+            // e.g. a connection error, the delivery may well succeed
+            CallFailure::Other => return Err(ChainCommunicationError::from_other(err)),
+            CallFailure::Reverted => {}
+        }
+        let reason =
+            revert_data.map_or_else(|| "unknown".to_owned(), |data| decode_revert(&data).1);
+        // The ISM isn't asked again if the mailbox reported that it rejected
+        // the metadata
+        let ism_accepts =
+            !is_ism_verification_failure(&reason) && self.ism_accepts(message, metadata).await?;
+        Ok(Some(classify_process_revert(reason, ism_accepts)))
     }
 
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
@@ -488,6 +683,33 @@ where
 
         AbiEncode::encode(process_call)
     }
+
+    #[instrument(skip(self), fields(body=%bytes_to_hex(body)))]
+    async fn dispatch(
+        &self,
+        destination: u32,
+        recipient: H256,
+        body: &[u8],
+    ) -> ChainResult<(H256, TxOutcome)> {
+        let fee = self
+            .contract
+            .quote_dispatch(destination, recipient.into(), body.to_vec().into())
+            .call()
+            .await?;
+        let call = self
+            .contract
+            .dispatch(destination, recipient.into(), body.to_vec().into());
+        let call = self.add_gas_overrides(call.value(fee), None).await?;
+        let receipt = report_tx_with_replacement(call, &self.conn).await?;
+        let message_id = receipt
+            .logs
+            .iter()
+            .find(|log| log.topics.first() == Some(&DispatchIdFilter::signature()))
+            .and_then(|log| log.topics.get(1))
+            .copied()
+            .ok_or_else(|| ChainCommunicationError::from_other_str("No message was dispatched"))?;
+        Ok((message_id.into(), self.outcome(receipt)))
+    }
 }
 
 pub struct EthereumMailboxAbi;
@@ -500,9 +722,30 @@ impl HyperlaneAbi for EthereumMailboxAbi {
     }
 }
 
+/// Decode the results of a multicall of `delivered` for each message
+fn decode_delivered_batch(results: Vec<MulticallResult>) -> ChainResult<Vec<bool>> {
+    results
+        .into_iter()
+        .map(|result| {
+            let delivered = result
+                .success
+                .then(|| decode(&[ParamType::Bool], &result.return_data).ok())
+                .flatten()
+                .and_then(|tokens| tokens.into_iter().next())
+                .and_then(Token::into_bool);
+            delivered.ok_or_else(|| {
+                HyperlaneEthereumError::MulticallError(
+                    "Failed to check the delivery of a message".to_owned(),
+                )
+                .into()
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
-    use std::{str::FromStr, sync::Arc};
+    use std::sync::Arc;
 
     use ethers::{
         providers::{MockProvider, Provider},
@@ -511,11 +754,13 @@ mod test {
 
     use hyperlane_core::{
         ContractLocator, HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain, Mailbox,
-        TxCostEstimate, H160, H256, U256,
+        TxCostEstimate, H256, U256,
     };
 
     use crate::{contracts::EthereumMailbox, ConnectionConf, RpcConnectionConf};
 
+    use super::*;
+
     /// An amount of gas to add to the estimated gas
     const GAS_ESTIMATE_BUFFER: u32 = 75_000;
 
@@ -529,6 +774,16 @@ mod test {
             },
             transaction_overrides: Default::default(),
             operation_batch: Default::default(),
+            transaction_replacement: None,
+            log_subscription_url: None,
+            rpc_rate_limits: Default::default(),
+            circuit_breaker: None,
+            block_time: None,
+            finality_tag: None,
+            transaction_confirmations: 1,
+            revert_trace_method: None,
+            fee_adapter: None,
+            historical_block: None,
         };
 
         let mailbox = EthereumMailbox::new(
@@ -545,27 +800,23 @@ mod test {
         let message = HyperlaneMessage::default();
         let metadata: Vec<u8> = vec![];
 
-        assert!(mailbox.arbitrum_node_interface.is_some());
-        // Confirm `H160::from_low_u64_ne(0xC8)` does what's expected
-        assert_eq!(
-            H160::from(mailbox.arbitrum_node_interface.as_ref().unwrap().address()),
-            H160::from_str("0x00000000000000000000000000000000000000C8").unwrap(),
-        );
+        // Arbitrum Nitro chains default to the Arbitrum fee adapter
+        assert!(mailbox.fee_adapter.is_some());
 
         // The MockProvider responses we push are processed in LIFO
         // order, so we start with the final RPCs and work toward the first
         // RPCs
 
-        // RPC 4: eth_gasPrice by process_estimate_costs
+        // RPC 4: eth_estimateGas to the ArbitrumNodeInterface's estimateRetryableTicket function by the fee adapter
+        let l2_gas_limit = U256::from(200000); // 200k gas
+        mock_provider.push(l2_gas_limit).unwrap();
+
+        // RPC 3: eth_gasPrice by process_estimate_costs
         // Return 15 gwei
         let gas_price: U256 =
             EthersU256::from(ethers::utils::parse_units("15", "gwei").unwrap()).into();
         mock_provider.push(gas_price).unwrap();
 
-        // RPC 3: eth_estimateGas to the ArbitrumNodeInterface's estimateRetryableTicket function by process_estimate_costs
-        let l2_gas_limit = U256::from(200000); // 200k gas
-        mock_provider.push(l2_gas_limit).unwrap();
-
         // RPC 2: eth_getBlockByNumber from the estimate_eip1559_fees call in process_contract_call
         mock_provider.push(Block::<Transaction>::default()).unwrap();
 
@@ -591,4 +842,30 @@ mod test {
             },
         );
     }
+
+    #[test]
+    fn test_decode_delivered_batch() {
+        let result = |success: bool, return_data: Vec<u8>| MulticallResult {
+            success,
+            return_data: return_data.into(),
+        };
+        let encoded = |delivered: bool| ethers::abi::encode(&[Token::Bool(delivered)]);
+
+        assert_eq!(
+            decode_delivered_batch(vec![
+                result(true, encoded(true)),
+                result(true, encoded(false))
+            ])
+            .unwrap(),
+            vec![true, false]
+        );
+        assert_eq!(decode_delivered_batch(vec![]).unwrap(), Vec::<bool>::new());
+        // A failed call or undecodable result fails the whole batch
+        assert!(decode_delivered_batch(vec![
+            result(true, encoded(true)),
+            result(false, encoded(true))
+        ])
+        .is_err());
+        assert!(decode_delivered_batch(vec![result(true, vec![])]).is_err());
+    }
 }
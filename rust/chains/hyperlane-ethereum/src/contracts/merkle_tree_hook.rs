@@ -9,18 +9,18 @@ use hyperlane_core::accumulator::incremental::IncrementalMerkle;
 use tracing::instrument;
 
 use hyperlane_core::{
-    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
-    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta,
-    MerkleTreeHook, MerkleTreeInsertion, SequenceAwareIndexer, H256, H512,
+    ChainResult, Checkpoint, ContractLocator, FinalityTag, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta, MerkleTreeHook,
+    MerkleTreeInsertion, SequenceAwareIndexer, H256, H512,
 };
 
 use crate::interfaces::merkle_tree_hook::{
     InsertedIntoTreeFilter, MerkleTreeHook as MerkleTreeHookContract, Tree,
 };
 use crate::tx::call_with_lag;
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, LogSubscription};
 
-use super::utils::fetch_raw_logs_and_log_meta;
+use super::utils::{block_tag, fetch_raw_logs_and_log_meta, finalized_block_number};
 
 // We don't need the reverse of this impl, so it's ok to disable the clippy lint
 #[allow(clippy::from_over_into)]
@@ -66,14 +66,18 @@ impl BuildableWithProvider for MerkleTreeHookIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMerkleTreeHookIndexer::new(
-            Arc::new(provider),
-            locator,
+        let mut indexer =
+            EthereumMerkleTreeHookIndexer::new(Arc::new(provider), locator, self.reorg_period);
+        indexer.finality_tag = conn.finality_tag;
+        indexer.log_subscription = LogSubscription::for_event::<InsertedIntoTreeFilter>(
+            conn,
+            indexer.contract.address(),
             self.reorg_period,
-        ))
+        );
+        Box::new(indexer)
     }
 }
 
@@ -86,6 +90,10 @@ where
     contract: Arc<MerkleTreeHookContract<M>>,
     provider: Arc<M>,
     reorg_period: u32,
+    /// Indexes up to the block with this tag instead of lagging the tip
+    finality_tag: Option<FinalityTag>,
+    /// Notifies of new insertions
+    log_subscription: Option<Arc<LogSubscription>>,
 }
 
 impl<M> EthereumMerkleTreeHookIndexer<M>
@@ -101,6 +109,8 @@ where
             )),
             provider,
             reorg_period,
+            finality_tag: None,
+            log_subscription: None,
         }
     }
 }
@@ -138,13 +148,7 @@ where
 
     #[instrument(level = "debug", err, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        Ok(self
-            .provider
-            .get_block_number()
-            .await
-            .map_err(ChainCommunicationError::from_other)?
-            .as_u32()
-            .saturating_sub(self.reorg_period))
+        finalized_block_number(self.provider.as_ref(), self.reorg_period, self.finality_tag).await
     }
 
     async fn fetch_logs_by_tx_hash(
@@ -167,6 +171,13 @@ where
         .collect();
         Ok(logs)
     }
+
+    async fn new_logs_notified(&self) {
+        match &self.log_subscription {
+            Some(subscription) => subscription.new_log().await,
+            None => std::future::pending().await,
+        }
+    }
 }
 
 #[async_trait]
@@ -271,4 +282,23 @@ where
         let count = call.call().await?;
         Ok(count)
     }
+
+    #[instrument(skip(self))]
+    async fn finalized_tree(&self, tag: FinalityTag) -> ChainResult<IncrementalMerkle> {
+        let call = self.contract.tree().block(block_tag(tag));
+        Ok(call.call().await?.into())
+    }
+
+    #[instrument(skip(self))]
+    async fn latest_finalized_checkpoint(&self, tag: FinalityTag) -> ChainResult<Checkpoint> {
+        let call = self.contract.latest_checkpoint().block(block_tag(tag));
+
+        let (root, index) = call.call().await?;
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address(),
+            mailbox_domain: self.domain.id(),
+            root: root.into(),
+            index,
+        })
+    }
 }
@@ -1,8 +1,13 @@
-pub use {interchain_gas::*, mailbox::*, merkle_tree_hook::*, validator_announce::*};
+pub use {
+    checkpoint_attestation::*, interchain_gas::*, mailbox::*, merkle_tree_hook::*,
+    storage_gas_oracle::*, validator_announce::*,
+};
 
+mod checkpoint_attestation;
 mod interchain_gas;
 mod mailbox;
 mod merkle_tree_hook;
 mod multicall;
+mod storage_gas_oracle;
 mod utils;
 mod validator_announce;
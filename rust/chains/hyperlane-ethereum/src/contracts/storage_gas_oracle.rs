@@ -0,0 +1,140 @@
+#![allow(missing_docs)]
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::prelude::Middleware;
+use hyperlane_core::{
+    ChainResult, ContractLocator, DestinationGasUpdate, FinalityTag, Indexed, Indexer, LogMeta,
+    SequenceAwareIndexer, H512, U256,
+};
+use tracing::instrument;
+
+use super::utils::{fetch_raw_logs_and_log_meta, finalized_block_number};
+use crate::interfaces::i_storage_gas_oracle::{
+    IStorageGasOracle as EthereumStorageGasOracleInternal, RemoteGasDataSetFilter,
+};
+use crate::{BuildableWithProvider, ConnectionConf};
+
+pub struct StorageGasOracleIndexerBuilder {
+    pub reorg_period: u32,
+}
+
+#[async_trait]
+impl BuildableWithProvider for StorageGasOracleIndexerBuilder {
+    type Output = Box<dyn SequenceAwareIndexer<DestinationGasUpdate>>;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        let mut indexer =
+            EthereumStorageGasOracleIndexer::new(Arc::new(provider), locator, self.reorg_period);
+        indexer.finality_tag = conn.finality_tag;
+        Box::new(indexer)
+    }
+}
+
+#[derive(Debug)]
+/// Struct that retrieves the destination gas data set in an Ethereum
+/// StorageGasOracle
+pub struct EthereumStorageGasOracleIndexer<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumStorageGasOracleInternal<M>>,
+    provider: Arc<M>,
+    reorg_period: u32,
+    /// Indexes up to the block with this tag instead of lagging the tip
+    finality_tag: Option<FinalityTag>,
+}
+
+impl<M> EthereumStorageGasOracleIndexer<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create new EthereumStorageGasOracleIndexer
+    pub fn new(provider: Arc<M>, locator: &ContractLocator, reorg_period: u32) -> Self {
+        Self {
+            contract: Arc::new(EthereumStorageGasOracleInternal::new(
+                locator.address,
+                provider.clone(),
+            )),
+            provider,
+            reorg_period,
+            finality_tag: None,
+        }
+    }
+}
+
+impl From<RemoteGasDataSetFilter> for DestinationGasUpdate {
+    fn from(log: RemoteGasDataSetFilter) -> Self {
+        Self {
+            destination: log.remote_domain,
+            token_exchange_rate: U256::from(log.token_exchange_rate),
+            gas_price: U256::from(log.gas_price),
+        }
+    }
+}
+
+#[async_trait]
+impl<M> Indexer<DestinationGasUpdate> for EthereumStorageGasOracleIndexer<M>
+where
+    M: Middleware + 'static,
+{
+    /// Note: This call may return duplicates depending on the provider used
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<DestinationGasUpdate>, LogMeta)>> {
+        let events = self
+            .contract
+            .remote_gas_data_set_filter()
+            .from_block(*range.start())
+            .to_block(*range.end())
+            .query_with_meta()
+            .await?;
+
+        Ok(events
+            .into_iter()
+            .map(|(log, log_meta)| (Indexed::new(log.into()), log_meta.into()))
+            .collect())
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        finalized_block_number(self.provider.as_ref(), self.reorg_period, self.finality_tag).await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<DestinationGasUpdate>, LogMeta)>> {
+        let logs = fetch_raw_logs_and_log_meta::<RemoteGasDataSetFilter, M>(
+            tx_hash,
+            self.provider.clone(),
+            self.contract.address(),
+        )
+        .await?
+        .into_iter()
+        .map(|(log, log_meta)| (Indexed::new(log.into()), log_meta))
+        .collect();
+        Ok(logs)
+    }
+}
+
+#[async_trait]
+impl<M> SequenceAwareIndexer<DestinationGasUpdate> for EthereumStorageGasOracleIndexer<M>
+where
+    M: Middleware + 'static,
+{
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // Gas data updates have no sequence, so are only indexed by block range
+        let tip = self.get_finalized_block_number().await?;
+        Ok((None, tip))
+    }
+}
@@ -3,10 +3,10 @@ use std::sync::Arc;
 use ethers::{
     abi::RawLog,
     providers::Middleware,
-    types::{H160 as EthersH160, H256 as EthersH256},
+    types::{BlockNumber, H160 as EthersH160, H256 as EthersH256},
 };
 use ethers_contract::{ContractError, EthEvent, LogMeta as EthersLogMeta};
-use hyperlane_core::{ChainResult, LogMeta, H512};
+use hyperlane_core::{ChainCommunicationError, ChainResult, FinalityTag, LogMeta, H512};
 use tracing::warn;
 
 pub async fn fetch_raw_logs_and_log_meta<T: EthEvent, M>(
@@ -46,3 +46,38 @@ where
         .collect();
     Ok(logs)
 }
+
+/// The block tag of ethers matching a finality tag
+pub(crate) fn block_tag(tag: FinalityTag) -> BlockNumber {
+    match tag {
+        FinalityTag::Safe => BlockNumber::Safe,
+        FinalityTag::Finalized => BlockNumber::Finalized,
+    }
+}
+
+/// The latest block that is final enough to index. That is the block with
+/// the finality tag if the chain is configured with one, or the tip lagged by
+/// the reorg period otherwise.
+pub(crate) async fn finalized_block_number<M: Middleware>(
+    provider: &M,
+    reorg_period: u32,
+    finality_tag: Option<FinalityTag>,
+) -> ChainResult<u32> {
+    let Some(tag) = finality_tag else {
+        return Ok(provider
+            .get_block_number()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .as_u32()
+            .saturating_sub(reorg_period));
+    };
+    provider
+        .get_block(block_tag(tag))
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .and_then(|block| block.number)
+        .map(|number| number.as_u32())
+        .ok_or_else(|| {
+            ChainCommunicationError::CustomError(format!("The chain has no {tag} block yet"))
+        })
+}
@@ -164,7 +164,7 @@ where
     #[instrument(err, ret, skip(self))]
     async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
         let contract_call = self.announce_contract_call(announcement).await?;
-        let receipt = report_tx(contract_call).await?;
+        let receipt = report_tx(contract_call, &self.conn).await?;
         Ok(receipt.into())
     }
 }
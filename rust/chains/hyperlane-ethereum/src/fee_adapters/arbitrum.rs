@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::{prelude::Middleware, types::transaction::eip2718::TypedTransaction};
+use hyperlane_core::{ChainResult, TxCostEstimate, H160, U256};
+
+use crate::interfaces::arbitrum_node_interface::ArbitrumNodeInterface;
+
+use super::FeeAdapter;
+
+/// Arbitrum Nitro based chains charge L1 costs as gas, which
+/// `eth_estimateGas` includes. The NodeInterface, found at address(0xC8),
+/// is used to isolate the L2 gas costs, which gas enforcement is applied to.
+/// See https://developer.arbitrum.io/arbos/gas#nodeinterfacesol or https://github.com/OffchainLabs/nitro/blob/master/contracts/src/node-interface/NodeInterface.sol#L25
+#[derive(Debug)]
+pub struct ArbitrumFeeAdapter<M> {
+    node_interface: ArbitrumNodeInterface<M>,
+}
+
+impl<M: Middleware> ArbitrumFeeAdapter<M> {
+    /// Create an adapter using the NodeInterface of the chain of `provider`
+    pub fn new(provider: Arc<M>) -> Self {
+        Self {
+            node_interface: ArbitrumNodeInterface::new(H160::from_low_u64_be(0xC8), provider),
+        }
+    }
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> FeeAdapter for ArbitrumFeeAdapter<M> {
+    async fn adjust_estimate(
+        &self,
+        tx: &TypedTransaction,
+        estimate: TxCostEstimate,
+    ) -> ChainResult<TxCostEstimate> {
+        let l2_gas_limit = self
+            .node_interface
+            .estimate_retryable_ticket(
+                H160::zero().into(),
+                // Give the sender a deposit, otherwise it reverts
+                U256::MAX.into(),
+                tx.to_addr().copied().unwrap_or_default(),
+                U256::zero().into(),
+                H160::zero().into(),
+                H160::zero().into(),
+                tx.data().cloned().unwrap_or_default(),
+            )
+            .estimate_gas()
+            .await?;
+        Ok(TxCostEstimate {
+            l2_gas_limit: Some(l2_gas_limit.into()),
+            ..estimate
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use ethers::providers::{MockProvider, Provider};
+
+    use super::*;
+
+    #[test]
+    fn uses_node_interface_at_0xc8() {
+        let provider = Arc::new(Provider::new(MockProvider::new()));
+        let adapter = ArbitrumFeeAdapter::new(provider);
+        assert_eq!(
+            H160::from(adapter.node_interface.address()),
+            H160::from_str("0x00000000000000000000000000000000000000C8").unwrap(),
+        );
+    }
+}
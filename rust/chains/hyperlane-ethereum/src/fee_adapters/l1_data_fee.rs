@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, ParamType, Token},
+    prelude::{Middleware, TransactionReceipt},
+    types::{transaction::eip2718::TypedTransaction, Address, TransactionRequest},
+    utils::id,
+};
+use hyperlane_core::{ChainCommunicationError, ChainResult, TxCostEstimate, TxOutcome, U256};
+use tracing::warn;
+
+use super::FeeAdapter;
+
+/// Address of the `GasPriceOracle` predeploy of OP stack chains
+const OP_STACK_GAS_PRICE_ORACLE: &str = "0x420000000000000000000000000000000000000F";
+
+/// Address of the `L1GasPriceOracle` predeploy of Scroll
+const SCROLL_L1_GAS_PRICE_ORACLE: &str = "0x5300000000000000000000000000000000000002";
+
+/// OP stack chains and Scroll charge a fee for posting the data of a
+/// transaction to L1 on top of its gas, quoted by an oracle predeploy and
+/// reported by receipts as `l1Fee`. The fee isn't paid with gas, so it's
+/// spread over the gas of the transaction as part of its gas price, which
+/// leaves the gas limit and the gas enforcement on it to the L2 gas alone.
+#[derive(Debug)]
+pub struct L1DataFeeAdapter<M> {
+    provider: Arc<M>,
+    oracle: Address,
+}
+
+impl<M: Middleware> L1DataFeeAdapter<M> {
+    /// Create an adapter for an OP stack chain
+    pub fn op_stack(provider: Arc<M>) -> Self {
+        Self {
+            provider,
+            oracle: OP_STACK_GAS_PRICE_ORACLE.parse().expect("valid address"),
+        }
+    }
+
+    /// Create an adapter for Scroll
+    pub fn scroll(provider: Arc<M>) -> Self {
+        Self {
+            provider,
+            oracle: SCROLL_L1_GAS_PRICE_ORACLE.parse().expect("valid address"),
+        }
+    }
+
+    /// The L1 data fee of a transaction in wei, quoted by `getL1Fee(bytes)`
+    /// of the oracle
+    async fn l1_fee(&self, tx: &TypedTransaction) -> ChainResult<U256> {
+        let data = [
+            id("getL1Fee(bytes)").to_vec(),
+            abi::encode(&[Token::Bytes(tx.rlp().to_vec())]),
+        ]
+        .concat();
+        let call: TypedTransaction = TransactionRequest::new().to(self.oracle).data(data).into();
+        let result = self
+            .provider
+            .call(&call, None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        match abi::decode(&[ParamType::Uint(256)], &result)
+            .ok()
+            .and_then(|tokens| tokens.into_iter().next()?.into_uint())
+        {
+            Some(fee) => Ok(fee.into()),
+            None => Err(ChainCommunicationError::from_other_str(
+                "Invalid L1 fee returned by the gas price oracle",
+            )),
+        }
+    }
+}
+
+/// The price per gas paying for a fee spread over an amount of gas, rounded up
+fn fee_per_gas(fee: U256, gas: U256) -> U256 {
+    if gas.is_zero() {
+        return U256::zero();
+    }
+    (fee + gas - 1) / gas
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> FeeAdapter for L1DataFeeAdapter<M> {
+    async fn adjust_estimate(
+        &self,
+        tx: &TypedTransaction,
+        estimate: TxCostEstimate,
+    ) -> ChainResult<TxCostEstimate> {
+        let l1_fee = self.l1_fee(tx).await?;
+        let gas_price: U256 = estimate.gas_price.clone().try_into()?;
+        let gas_price = gas_price + fee_per_gas(l1_fee, estimate.gas_limit);
+        Ok(TxCostEstimate {
+            gas_price: gas_price.try_into()?,
+            ..estimate
+        })
+    }
+
+    fn adjust_outcome(&self, receipt: &TransactionReceipt, outcome: TxOutcome) -> TxOutcome {
+        let Some(l1_fee) = receipt.other.get("l1Fee") else {
+            return outcome;
+        };
+        let Ok(l1_fee) = serde_json::from_value::<ethers::types::U256>(l1_fee.clone()) else {
+            warn!(?l1_fee, "Invalid L1 fee in receipt");
+            return outcome;
+        };
+        let gas_price = U256::from(receipt.effective_gas_price.unwrap_or_default())
+            + fee_per_gas(l1_fee.into(), outcome.gas_used);
+        match gas_price.try_into() {
+            Ok(gas_price) => TxOutcome {
+                gas_price,
+                ..outcome
+            },
+            Err(err) => {
+                warn!(?err, "Invalid gas price including the L1 fee");
+                outcome
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rounds_fees_per_gas_up() {
+        assert_eq!(fee_per_gas(1000.into(), 10.into()), 100.into());
+        assert_eq!(fee_per_gas(1001.into(), 10.into()), 101.into());
+        assert_eq!(fee_per_gas(1000.into(), U256::zero()), U256::zero());
+    }
+
+    #[test]
+    fn adds_l1_fee_to_gas_price_of_outcome() {
+        let provider = Arc::new(ethers::providers::Provider::new(
+            ethers::providers::MockProvider::new(),
+        ));
+        let adapter = L1DataFeeAdapter::op_stack(provider);
+        let mut receipt = TransactionReceipt {
+            effective_gas_price: Some(10.into()),
+            ..Default::default()
+        };
+        receipt
+            .other
+            .insert("l1Fee".into(), serde_json::json!("0x3e8"));
+        let outcome = TxOutcome {
+            transaction_id: Default::default(),
+            executed: true,
+            gas_used: 100.into(),
+            gas_price: U256::from(10).try_into().unwrap(),
+        };
+
+        let adjusted = adapter.adjust_outcome(&receipt, outcome.clone());
+        // The gas used stays the L2 gas
+        assert_eq!(adjusted.gas_used, 100.into());
+        assert_eq!(adjusted.gas_price, U256::from(20).try_into().unwrap());
+
+        receipt.other = Default::default();
+        let unadjusted = adapter.adjust_outcome(&receipt, outcome);
+        assert_eq!(unadjusted.gas_price, U256::from(10).try_into().unwrap());
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    types::{transaction::eip2718::TypedTransaction, U256 as EthersU256},
+};
+use hyperlane_core::{ChainCommunicationError, ChainResult, TxCostEstimate, U256};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::FeeAdapter;
+
+/// Estimate of `linea_estimateGas`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LineaGasEstimate {
+    gas_limit: EthersU256,
+    base_fee_per_gas: EthersU256,
+    priority_fee_per_gas: EthersU256,
+}
+
+/// Linea's sequencer requires a priority fee that grows with the size of a
+/// transaction's data, which `eth_gasPrice` and `eth_feeHistory` don't
+/// reflect. Gas and fees are estimated with `linea_estimateGas` instead.
+#[derive(Debug)]
+pub struct LineaFeeAdapter<M> {
+    provider: Arc<M>,
+}
+
+impl<M: Middleware> LineaFeeAdapter<M> {
+    /// Create an adapter estimating with the node of `provider`
+    pub fn new(provider: Arc<M>) -> Self {
+        Self { provider }
+    }
+
+    async fn estimate(&self, tx: &TypedTransaction) -> ChainResult<LineaGasEstimate> {
+        let request = json!({
+            "from": tx.from().copied().or_else(|| self.provider.default_sender()),
+            "to": tx.to_addr(),
+            "data": tx.data(),
+            "value": tx.value(),
+        });
+        self.provider
+            .provider()
+            .request("linea_estimateGas", [request])
+            .await
+            .map_err(ChainCommunicationError::from_other)
+    }
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> FeeAdapter for LineaFeeAdapter<M> {
+    async fn adjust_estimate(
+        &self,
+        tx: &TypedTransaction,
+        estimate: TxCostEstimate,
+    ) -> ChainResult<TxCostEstimate> {
+        let linea_estimate = self.estimate(tx).await?;
+        let gas_price = linea_estimate.base_fee_per_gas + linea_estimate.priority_fee_per_gas;
+        Ok(TxCostEstimate {
+            // The generic estimate includes a buffer
+            gas_limit: estimate.gas_limit.max(linea_estimate.gas_limit.into()),
+            gas_price: U256::from(gas_price).try_into()?,
+            ..estimate
+        })
+    }
+
+    async fn adjust_tx(&self, tx: &mut TypedTransaction) -> ChainResult<()> {
+        let linea_estimate = self.estimate(tx).await?;
+        let priority_fee = linea_estimate.priority_fee_per_gas;
+        let min_fee = linea_estimate.base_fee_per_gas + priority_fee;
+        match tx {
+            TypedTransaction::Eip1559(inner) => {
+                inner.max_priority_fee_per_gas = Some(
+                    inner
+                        .max_priority_fee_per_gas
+                        .map_or(priority_fee, |fee| fee.max(priority_fee)),
+                );
+                inner.max_fee_per_gas = Some(
+                    inner
+                        .max_fee_per_gas
+                        .map_or(min_fee, |fee| fee.max(min_fee)),
+                );
+            }
+            _ => {
+                let gas_price = tx.gas_price().map_or(min_fee, |price| price.max(min_fee));
+                tx.set_gas_price(gas_price);
+            }
+        }
+        Ok(())
+    }
+}
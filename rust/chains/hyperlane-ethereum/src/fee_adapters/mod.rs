@@ -0,0 +1,56 @@
+//! Adapters for the fees of L2s that generic EVM gas estimation doesn't
+//! account for, adjusting both the estimates gas enforcement quotes against
+//! and the transactions the submitter sends.
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use ethers::{
+    prelude::{Middleware, TransactionReceipt},
+    types::transaction::eip2718::TypedTransaction,
+};
+use hyperlane_core::{ChainResult, TxCostEstimate, TxOutcome};
+
+pub use self::{arbitrum::*, l1_data_fee::*, linea::*};
+
+mod arbitrum;
+mod l1_data_fee;
+mod linea;
+
+use crate::FeeAdapterKind;
+
+/// Adjusts the costs of transactions to a chain for the way it charges fees
+#[async_trait]
+pub trait FeeAdapter: Debug + Send + Sync {
+    /// Adjust the cost estimate of a transaction, made by `eth_estimateGas`
+    /// and `eth_gasPrice`
+    async fn adjust_estimate(
+        &self,
+        tx: &TypedTransaction,
+        estimate: TxCostEstimate,
+    ) -> ChainResult<TxCostEstimate>;
+
+    /// Adjust the fees of a transaction before it's submitted
+    async fn adjust_tx(&self, _tx: &mut TypedTransaction) -> ChainResult<()> {
+        Ok(())
+    }
+
+    /// Adjust the outcome of a submitted transaction for the fees its
+    /// receipt reports apart from its gas
+    fn adjust_outcome(&self, _receipt: &TransactionReceipt, outcome: TxOutcome) -> TxOutcome {
+        outcome
+    }
+}
+
+/// Build the fee adapter of a kind
+pub fn build_fee_adapter<M>(kind: FeeAdapterKind, provider: Arc<M>) -> Box<dyn FeeAdapter>
+where
+    M: Middleware + 'static,
+{
+    match kind {
+        FeeAdapterKind::Arbitrum => Box::new(ArbitrumFeeAdapter::new(provider)),
+        FeeAdapterKind::OpStack => Box::new(L1DataFeeAdapter::op_stack(provider)),
+        FeeAdapterKind::Scroll => Box::new(L1DataFeeAdapter::scroll(provider)),
+        FeeAdapterKind::Linea => Box::new(LineaFeeAdapter::new(provider)),
+    }
+}
@@ -109,6 +109,11 @@ where
         }
     }
 
+    #[instrument]
+    async fn raw_module_type(&self) -> ChainResult<Option<u8>> {
+        Ok(Some(self.contract.module_type().call().await?))
+    }
+
     #[instrument]
     async fn dry_run_verify(
         &self,
@@ -8,10 +8,19 @@ use std::collections::HashMap;
 use ethers::abi::FunctionExt;
 use ethers::prelude::{abi, Lazy, Middleware};
 
-pub use self::{config::*, contracts::*, ism::*, rpc_clients::*, signer::*};
+pub use self::{
+    config::*, contracts::*, fee_adapters::*, ism::*, rpc_clients::*, signer::*,
+};
 
 mod tx;
 
+mod revert;
+
+mod zksync;
+
+/// Adapters for the fees of L2s
+mod fee_adapters;
+
 mod contracts;
 
 mod ism;
@@ -0,0 +1,372 @@
+//! Decoding of revert data, traced when the node doesn't return it with a
+//! reverted simulation
+
+use ethers::{
+    abi::{self, Abi, ParamType, Token},
+    prelude::{ContractError, Lazy, Middleware},
+    types::transaction::eip2718::TypedTransaction,
+};
+use hyperlane_core::{ChainCommunicationError, HyperlaneCustomError, ProcessSimulation};
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use crate::interfaces::{
+    i_aggregation_ism::IAGGREGATIONISM_ABI, i_ccip_read_ism::ICCIPREADISM_ABI,
+    i_interchain_security_module::IINTERCHAINSECURITYMODULE_ABI, i_mailbox::IMAILBOX_ABI,
+    i_multisig_ism::IMULTISIGISM_ABI, i_routing_ism::IROUTINGISM_ABI, mailbox::MAILBOX_ABI,
+};
+use crate::RevertTraceMethod;
+
+/// Selector of `Error(string)`, the error of revert strings
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`, the error of failed assertions and
+/// arithmetic errors
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Revert string of the mailbox when the ISM rejects the metadata
+const ISM_VERIFICATION_FAILED: &str = "Mailbox: ISM verification failed";
+
+/// Decoded prefix of the revert strings of the mailbox's own checks
+const MAILBOX_REVERT_PREFIX: &str = "Error(\"Mailbox: ";
+
+/// ABIs whose custom errors a delivery can revert with
+static KNOWN_ABIS: [&Lazy<Abi>; 7] = [
+    &MAILBOX_ABI,
+    &IMAILBOX_ABI,
+    &IINTERCHAINSECURITYMODULE_ABI,
+    &IMULTISIGISM_ABI,
+    &IROUTINGISM_ABI,
+    &IAGGREGATIONISM_ABI,
+    &ICCIPREADISM_ABI,
+];
+
+/// Trace a simulation that failed with `err` to find its revert data,
+/// returning the decoded revert if it's found and `err` otherwise. Errors
+/// that aren't reverts, e.g. of the connection, aren't traced.
+pub(crate) async fn trace_revert<M: Middleware + 'static>(
+    provider: &M,
+    mut tx: TypedTransaction,
+    method: Option<RevertTraceMethod>,
+    err: ChainCommunicationError,
+) -> ChainCommunicationError {
+    let Some(method) = method else {
+        return err;
+    };
+    if matches!(err, ChainCommunicationError::SimulationReverted { .. }) {
+        return err;
+    }
+    // The node returned the revert data with the error, so there's nothing
+    // to trace
+    if let Some(data) = revert_data::<M>(&err) {
+        let (error, reason) = decode_revert(&data);
+        return ChainCommunicationError::SimulationReverted { error, reason };
+    }
+    if classify_call_failure(&err.to_string(), false) != CallFailure::Reverted {
+        return err;
+    }
+    if tx.from().is_none() {
+        if let Some(sender) = provider.default_sender() {
+            tx.set_from(sender);
+        }
+    }
+
+    let data = match method {
+        RevertTraceMethod::DebugTraceCall => provider
+            .provider()
+            .request::<_, Value>(
+                "debug_traceCall",
+                (&tx, "latest", json!({ "tracer": "callTracer" })),
+            )
+            .await
+            .map(|frame| call_frame_revert_data(&frame)),
+        RevertTraceMethod::TraceCall => provider
+            .provider()
+            .request::<_, Value>("trace_call", (&tx, ["trace"], "latest"))
+            .await
+            .map(|trace| trace_revert_data(&trace)),
+    };
+    match data {
+        Ok(Some(data)) => {
+            let (error, reason) = decode_revert(&data);
+            debug!(?method, %reason, "Traced revert of simulation");
+            ChainCommunicationError::SimulationReverted { error, reason }
+        }
+        Ok(None) => err,
+        Err(trace_err) => {
+            warn!(?method, error = ?trace_err, "Failed to trace reverted simulation");
+            err
+        }
+    }
+}
+
+/// The revert data returned with a failed call of a contract, if any
+fn revert_data<M: Middleware + 'static>(err: &ChainCommunicationError) -> Option<Vec<u8>> {
+    let ChainCommunicationError::ContractError(err) = err else {
+        return None;
+    };
+    let err: &dyn HyperlaneCustomError = err.as_ref();
+    let revert = err
+        .as_any()
+        .downcast_ref::<ContractError<M>>()?
+        .as_revert()?;
+    (!revert.is_empty()).then(|| revert.to_vec())
+}
+
+/// The revert data of a `callTracer` frame. Calls bubbling up the revert of
+/// an inner call return its data, otherwise the data is looked for in the
+/// last reverted inner call.
+fn call_frame_revert_data(frame: &Value) -> Option<Vec<u8>> {
+    frame.get("error")?;
+    if let Some(output) = frame.get("output").and_then(parse_hex) {
+        return Some(output);
+    }
+    frame
+        .get("calls")
+        .and_then(Value::as_array)?
+        .iter()
+        .rev()
+        .find_map(call_frame_revert_data)
+}
+
+/// The revert data of a `trace_call` trace, that of the call itself or of the
+/// last reverted inner call
+fn trace_revert_data(trace: &Value) -> Option<Vec<u8>> {
+    if let Some(output) = trace.get("output").and_then(parse_hex) {
+        return Some(output);
+    }
+    trace
+        .get("trace")
+        .and_then(Value::as_array)?
+        .iter()
+        .rev()
+        .filter(|call| call.get("error").is_some())
+        .find_map(|call| call.get("result")?.get("output").and_then(parse_hex))
+}
+
+/// Non-empty hex data
+fn parse_hex(value: &Value) -> Option<Vec<u8>> {
+    let data = hex::decode(value.as_str()?.trim_start_matches("0x")).ok()?;
+    (!data.is_empty()).then_some(data)
+}
+
+/// The name of the error of revert data and the error with its decoded
+/// arguments
+pub(crate) fn decode_revert(data: &[u8]) -> (String, String) {
+    if data.len() < 4 {
+        return ("unknown".into(), format!("0x{}", hex::encode(data)));
+    }
+    let (selector, args) = data.split_at(4);
+
+    if selector == ERROR_SELECTOR {
+        if let Ok(tokens) = abi::decode(&[ParamType::String], args) {
+            return ("Error".into(), format!("Error({})", format_tokens(&tokens)));
+        }
+    } else if selector == PANIC_SELECTOR {
+        if let Ok(tokens) = abi::decode(&[ParamType::Uint(256)], args) {
+            return ("Panic".into(), format!("Panic({})", format_tokens(&tokens)));
+        }
+    } else if let Some(error) = KNOWN_ABIS
+        .iter()
+        .flat_map(|abi| abi.errors())
+        .find(|error| error.signature().as_bytes()[..4] == *selector)
+    {
+        if let Ok(tokens) = error.decode(args) {
+            return (
+                error.name.clone(),
+                format!("{}({})", error.name, format_tokens(&tokens)),
+            );
+        }
+    }
+
+    let selector = format!("0x{}", hex::encode(selector));
+    let reason = format!("{selector}(0x{})", hex::encode(args));
+    (selector, reason)
+}
+
+/// How a call of a contract failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CallFailure {
+    /// The call ran out of gas
+    OutOfGas,
+    /// The call reverted
+    Reverted,
+    /// The call failed for another reason, e.g. of the connection
+    Other,
+}
+
+/// Classify the failure of a call from its error, given whether the node
+/// returned revert data with it. Nodes that don't return revert data only
+/// report reverts in their error messages.
+pub(crate) fn classify_call_failure(err: &str, has_revert_data: bool) -> CallFailure {
+    if has_revert_data {
+        return CallFailure::Reverted;
+    }
+    let err = err.to_lowercase();
+    if err.contains("out of gas") || err.contains("gas required exceeds") {
+        CallFailure::OutOfGas
+    } else if err.contains("revert") {
+        CallFailure::Reverted
+    } else {
+        CallFailure::Other
+    }
+}
+
+/// Whether a decoded revert of a delivery is the mailbox reporting that the
+/// ISM rejected the metadata
+pub(crate) fn is_ism_verification_failure(reason: &str) -> bool {
+    reason.contains(ISM_VERIFICATION_FAILED)
+}
+
+/// Classify a delivery simulation that reverted with the decoded `reason`,
+/// given whether the recipient's ISM accepts the metadata. Reverts of the ISM
+/// bubble up through the mailbox, so they can't be told apart from reverts of
+/// the recipient by their data alone.
+pub(crate) fn classify_process_revert(reason: String, ism_accepts: bool) -> ProcessSimulation {
+    if !ism_accepts || is_ism_verification_failure(&reason) {
+        ProcessSimulation::IsmVerifyFailed { reason }
+    } else if reason.starts_with(MAILBOX_REVERT_PREFIX) {
+        ProcessSimulation::Reverted { reason }
+    } else {
+        ProcessSimulation::RecipientReverted { reason }
+    }
+}
+
+fn format_tokens(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::String(string) => format!("{string:?}"),
+            Token::Uint(uint) => format!("{uint:#x}"),
+            token => token.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::{
+        abi::encode,
+        providers::{MockProvider, Provider},
+        types::Bytes,
+    };
+
+    use super::*;
+
+    #[test]
+    fn decodes_revert_strings() {
+        let data = [
+            ERROR_SELECTOR.to_vec(),
+            encode(&[Token::String("!threshold".into())]),
+        ]
+        .concat();
+        assert_eq!(
+            decode_revert(&data),
+            ("Error".into(), "Error(\"!threshold\")".into())
+        );
+    }
+
+    #[test]
+    fn decodes_panics() {
+        let data = [PANIC_SELECTOR.to_vec(), encode(&[Token::Uint(0x11.into())])].concat();
+        assert_eq!(decode_revert(&data), ("Panic".into(), "Panic(0x11)".into()));
+    }
+
+    #[test]
+    fn reports_unknown_selectors() {
+        assert_eq!(
+            decode_revert(&[0xde, 0xad, 0xbe, 0xef, 0x01]),
+            ("0xdeadbeef".into(), "0xdeadbeef(0x01)".into())
+        );
+    }
+
+    #[test]
+    fn classifies_process_reverts() {
+        let classify = |reason: &str, ism_accepts| {
+            classify_process_revert(reason.to_owned(), ism_accepts).as_str()
+        };
+        assert_eq!(
+            classify("Error(\"Mailbox: ISM verification failed\")", true),
+            "ism_verify_failed"
+        );
+        assert_eq!(
+            classify("Error(\"!threshold\")", false),
+            "ism_verify_failed"
+        );
+        assert_eq!(
+            classify("Error(\"Mailbox: already delivered\")", true),
+            "reverted"
+        );
+        assert_eq!(classify("Error(\"!amount\")", true), "recipient_reverted");
+    }
+
+    #[test]
+    fn classifies_call_failures() {
+        assert_eq!(
+            classify_call_failure("Out of gas", false),
+            CallFailure::OutOfGas
+        );
+        assert_eq!(
+            classify_call_failure("gas required exceeds allowance (100000)", false),
+            CallFailure::OutOfGas
+        );
+        assert_eq!(
+            classify_call_failure("execution reverted", false),
+            CallFailure::Reverted
+        );
+        // Revert data is a revert whatever the message says
+        assert_eq!(
+            classify_call_failure("out of gas", true),
+            CallFailure::Reverted
+        );
+        assert_eq!(
+            classify_call_failure("connection refused", false),
+            CallFailure::Other
+        );
+    }
+
+    #[test]
+    fn uses_returned_revert_data() {
+        type M = Provider<MockProvider>;
+        let data = vec![0xde, 0xad, 0xbe, 0xef];
+        let err = ChainCommunicationError::from(ContractError::<M>::Revert(data.clone().into()));
+        assert_eq!(revert_data::<M>(&err), Some(data));
+
+        let empty = ChainCommunicationError::from(ContractError::<M>::Revert(Bytes::default()));
+        assert_eq!(revert_data::<M>(&empty), None);
+        assert_eq!(
+            revert_data::<M>(&ChainCommunicationError::from_other_str("reverted")),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_revert_data_of_inner_calls() {
+        let frame = json!({
+            "error": "execution reverted",
+            "output": "0x",
+            "calls": [
+                { "output": "0x01" },
+                {
+                    "error": "execution reverted",
+                    "output": "0xdeadbeef",
+                },
+            ],
+        });
+        assert_eq!(
+            call_frame_revert_data(&frame),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+    }
+
+    #[test]
+    fn prefers_bubbled_revert_data() {
+        let frame = json!({
+            "error": "execution reverted",
+            "output": "0x01",
+            "calls": [{ "error": "execution reverted", "output": "0x02" }],
+        });
+        assert_eq!(call_frame_revert_data(&frame), Some(vec![0x01]));
+    }
+}
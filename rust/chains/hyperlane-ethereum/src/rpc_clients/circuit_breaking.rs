@@ -0,0 +1,121 @@
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use derive_new::new;
+use ethers::providers::{JsonRpcClient, ProviderError, RpcError};
+use hyperlane_core::{rpc_clients::CircuitBreaker, ChainCommunicationError};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A client whose requests go through the circuit breaker of its chain, if
+/// it has one. It wraps the client every contract of the chain is built
+/// with, so they all share the breaker.
+///
+/// Error responses of the node, e.g. reverts, are answers and don't count as
+/// failures, only requests the node didn't answer do.
+#[derive(Debug, Clone, new)]
+pub struct CircuitBreakingClient<C> {
+    inner: C,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<C> JsonRpcClient for CircuitBreakingClient<C>
+where
+    C: JsonRpcClient,
+{
+    type Error = ProviderError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let Some(breaker) = &self.breaker else {
+            return self.inner.request(method, params).await.map_err(Into::into);
+        };
+        breaker.check().map_err(|retry_in| {
+            ProviderError::CustomError(ChainCommunicationError::CircuitOpen(retry_in).to_string())
+        })?;
+        let result = self.inner.request(method, params).await;
+        breaker.record(match &result {
+            Ok(_) => true,
+            Err(err) => err.is_error_response(),
+        });
+        result.map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ethers::providers::{HttpClientError, JsonRpcError};
+    use hyperlane_core::rpc_clients::CircuitBreakerConf;
+
+    use super::*;
+
+    /// Answers every request with an error response if `responding`, fails
+    /// them otherwise
+    #[derive(Debug)]
+    struct FailingClient {
+        responding: bool,
+    }
+
+    #[async_trait]
+    impl JsonRpcClient for FailingClient {
+        type Error = HttpClientError;
+
+        async fn request<T, R>(&self, _method: &str, _params: T) -> Result<R, Self::Error>
+        where
+            T: Debug + Serialize + Send + Sync,
+            R: DeserializeOwned + Send,
+        {
+            Err(if self.responding {
+                HttpClientError::JsonRpcError(JsonRpcError {
+                    code: 3,
+                    message: "execution reverted".to_owned(),
+                    data: None,
+                })
+            } else {
+                HttpClientError::SerdeJson {
+                    err: serde_json::from_str::<u64>("").unwrap_err(),
+                    text: "connection refused".to_owned(),
+                }
+            })
+        }
+    }
+
+    fn client(responding: bool) -> CircuitBreakingClient<FailingClient> {
+        CircuitBreakingClient::new(
+            FailingClient { responding },
+            Some(Arc::new(CircuitBreaker::new(CircuitBreakerConf {
+                failure_threshold: 2,
+                cooldown: Duration::from_secs(60),
+            }))),
+        )
+    }
+
+    fn is_circuit_open(result: Result<u64, ProviderError>) -> bool {
+        matches!(result, Err(ProviderError::CustomError(msg)) if msg.starts_with("Circuit open"))
+    }
+
+    #[tokio::test]
+    async fn test_fails_fast_once_open() {
+        let client = client(false);
+        for _ in 0..2 {
+            assert!(!is_circuit_open(
+                client.request("eth_blockNumber", ()).await
+            ));
+        }
+        assert!(is_circuit_open(client.request("eth_call", ()).await));
+    }
+
+    #[tokio::test]
+    async fn test_error_responses_keep_the_circuit_closed() {
+        let client = client(true);
+        for _ in 0..3 {
+            assert!(!is_circuit_open(client.request("eth_call", ()).await));
+        }
+    }
+}
@@ -2,7 +2,7 @@ use derive_new::new;
 use hyperlane_core::rpc_clients::{BlockNumberGetter, FallbackProvider};
 use std::fmt::{Debug, Formatter};
 use std::ops::Deref;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 use async_trait::async_trait;
@@ -106,12 +106,18 @@ where
                     Value::Null => provider.request(method, ()),
                     _ => provider.request(method, &params),
                 };
+                let started = Instant::now();
                 let resp = fut.await;
+                let latency = started.elapsed();
                 self.handle_stalled_provider(priority, provider).await;
                 let _span =
                     warn_span!("request", fallback_count=%idx, provider_index=%priority.index, ?provider).entered();
 
-                match categorize_client_response(method, resp) {
+                let resp = categorize_client_response(method, resp);
+                // Non-retryable errors are a response of the node, e.g. a revert
+                let success = !matches!(resp, RetryableErr(_) | RateLimitErr(_));
+                self.record_response(priority, success, latency);
+                match resp {
                     IsOk(v) => return Ok(serde_json::from_value(v)?),
                     RetryableErr(e) | RateLimitErr(e) => errors.push(e.into()),
                     NonRetryableErr(e) => return Err(e.into()),
@@ -0,0 +1,93 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use derive_new::new;
+use ethers::providers::JsonRpcClient;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+/// Methods reading state at a block, with the position of their block
+/// parameter
+const BLOCK_PARAM_POSITIONS: &[(&str, usize)] = &[
+    ("eth_call", 1),
+    ("eth_estimateGas", 1),
+    ("eth_createAccessList", 1),
+    ("eth_getBalance", 1),
+    ("eth_getCode", 1),
+    ("eth_getTransactionCount", 1),
+    ("eth_getStorageAt", 2),
+];
+
+/// A provider that reads state and estimates gas at a past block rather than
+/// at the latest one, which requires an archive node. Requests at an explicit
+/// block number or hash are left as they are.
+#[derive(Debug, Clone, new)]
+pub struct HistoricalBlockProvider<C> {
+    inner: C,
+    block: u64,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<C> JsonRpcClient for HistoricalBlockProvider<C>
+where
+    C: JsonRpcClient,
+{
+    type Error = C::Error;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let position = BLOCK_PARAM_POSITIONS
+            .iter()
+            .find(|(m, _)| *m == method)
+            .map(|(_, position)| *position);
+        match (position, serde_json::to_value(&params)) {
+            (Some(position), Ok(Value::Array(mut params))) => {
+                pin_block(&mut params, position, self.block);
+                self.inner.request(method, params).await
+            }
+            _ => self.inner.request(method, params).await,
+        }
+    }
+}
+
+/// Replace the block parameter with the pinned block if it is missing or
+/// refers to the tip of the chain
+fn pin_block(params: &mut Vec<Value>, position: usize, block: u64) {
+    let pinned = json!(format!("{block:#x}"));
+    match params.get_mut(position) {
+        Some(param) => {
+            if matches!(param.as_str(), Some("latest" | "pending")) || param.is_null() {
+                *param = pinned;
+            }
+        }
+        None if params.len() == position => params.push(pinned),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_block() {
+        let tx = json!({ "to": "0x0000000000000000000000000000000000000001" });
+
+        let mut params = vec![tx.clone()];
+        pin_block(&mut params, 1, 100);
+        assert_eq!(params, vec![tx.clone(), json!("0x64")]);
+
+        let mut params = vec![tx.clone(), json!("latest")];
+        pin_block(&mut params, 1, 100);
+        assert_eq!(params, vec![tx.clone(), json!("0x64")]);
+
+        // An explicit block is kept
+        let mut params = vec![tx.clone(), json!("0x10")];
+        pin_block(&mut params, 1, 100);
+        assert_eq!(params, vec![tx, json!("0x10")]);
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock, Weak},
+    time::Duration,
+};
+
+use ethers::prelude::{Filter, Log, Middleware, Provider, Ws, H160, H256};
+use ethers_contract::EthEvent;
+use futures_util::StreamExt;
+use hyperlane_core::{ChainCommunicationError, ChainResult};
+use tokio::{
+    sync::{watch, Notify},
+    task::JoinHandle,
+    time::sleep,
+};
+use tracing::{info, trace, warn};
+use url::Url;
+
+use crate::ConnectionConf;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// The subscriptions of the chains in use, by websocket url. Every watcher of
+/// a chain shares its websocket, so it is only connected to once however
+/// many contract instances are built.
+static CHAINS: OnceLock<Mutex<HashMap<Url, Weak<ChainSubscription>>>> = OnceLock::new();
+
+/// Notifies of new logs matching a contract and event, using `eth_subscribe`
+/// subscriptions over the websocket of the chain.
+///
+/// Only the notification comes from the subscription: logs are still fetched
+/// by block range over http by the cursors, which keeps polling on their own
+/// while the websocket is disconnected. As the cursors only index up to
+/// `reorg_period` blocks behind the head, a log is only notified of once the
+/// head is that far past its block. The subscription is reconnected in the
+/// background.
+#[derive(Debug)]
+pub struct LogSubscription {
+    blocks: tokio::sync::Mutex<WatchedBlocks>,
+    reorg_period: u32,
+    /// Kept for the subscription to last as long as the watcher
+    _logs: Arc<watch::Sender<u64>>,
+    _chain: Arc<ChainSubscription>,
+}
+
+#[derive(Debug)]
+struct WatchedBlocks {
+    /// Block of the latest log
+    logs: watch::Receiver<u64>,
+    /// Number of the latest head
+    heads: watch::Receiver<u64>,
+    /// Block of a log waiting to be deep enough, kept if waiting for it is
+    /// cancelled
+    pending: Option<u64>,
+}
+
+/// The websocket subscriptions to the heads and watched logs of a chain,
+/// which unsubscribe once no watcher is left
+#[derive(Debug)]
+struct ChainSubscription {
+    state: Arc<ChainState>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Debug)]
+struct ChainState {
+    /// Block of the latest log of each contract and event watched
+    watched: Mutex<HashMap<(H160, H256), Weak<watch::Sender<u64>>>>,
+    /// Changes of `watched`, to subscribe to the new set of logs
+    watched_changed: Notify,
+    /// Number of the latest head
+    head: watch::Sender<u64>,
+}
+
+impl LogSubscription {
+    /// Subscribe to the `E` events of the contract at `address`, if the
+    /// connection has a websocket url to subscribe with, notifying of them
+    /// once `reorg_period` blocks deep.
+    pub fn for_event<E: EthEvent>(
+        conn: &ConnectionConf,
+        address: H160,
+        reorg_period: u32,
+    ) -> Option<Arc<Self>> {
+        let url = conn.log_subscription_url.clone()?;
+        let chain = {
+            let mut chains = CHAINS.get_or_init(Default::default).lock().unwrap();
+            chains.retain(|_, chain| chain.strong_count() > 0);
+            match chains.get(&url).and_then(Weak::upgrade) {
+                Some(chain) => chain,
+                None => {
+                    let state = Arc::new(ChainState::new());
+                    let task = tokio::spawn(run_subscriptions(url.clone(), state.clone()));
+                    let chain = Arc::new(ChainSubscription { state, task });
+                    chains.insert(url, Arc::downgrade(&chain));
+                    chain
+                }
+            }
+        };
+        Some(Arc::new(Self::watch(
+            chain,
+            (address, E::signature()),
+            reorg_period,
+        )))
+    }
+
+    fn watch(chain: Arc<ChainSubscription>, key: (H160, H256), reorg_period: u32) -> Self {
+        let logs = {
+            let mut watched = chain.state.watched.lock().unwrap();
+            match watched.get(&key).and_then(Weak::upgrade) {
+                Some(logs) => logs,
+                None => {
+                    watched.retain(|_, logs| logs.strong_count() > 0);
+                    let logs = Arc::new(watch::channel(0).0);
+                    watched.insert(key, Arc::downgrade(&logs));
+                    chain.state.watched_changed.notify_one();
+                    logs
+                }
+            }
+        };
+        Self {
+            blocks: tokio::sync::Mutex::new(WatchedBlocks {
+                logs: logs.subscribe(),
+                heads: chain.state.head.subscribe(),
+                pending: None,
+            }),
+            reorg_period,
+            _logs: logs,
+            _chain: chain,
+        }
+    }
+
+    /// Wait for a new log to be `reorg_period` blocks deep. A log emitted
+    /// while nobody was waiting isn't missed, the next call returns as soon
+    /// as it is deep enough.
+    pub async fn new_log(&self) {
+        let mut blocks = self.blocks.lock().await;
+        // The senders live as long as the subscription, so these can't fail
+        let block = match blocks.pending {
+            Some(block) => block,
+            None => {
+                let _ = blocks.logs.changed().await;
+                let block = *blocks.logs.borrow_and_update();
+                blocks.pending = Some(block);
+                block
+            }
+        };
+        let _ = blocks
+            .heads
+            .wait_for(|head| *head >= block + self.reorg_period as u64)
+            .await;
+        blocks.pending = None;
+    }
+}
+
+impl Drop for ChainSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl ChainState {
+    fn new() -> Self {
+        Self {
+            watched: Default::default(),
+            watched_changed: Notify::new(),
+            head: watch::channel(0).0,
+        }
+    }
+
+    /// A filter matching every watched log, if any is watched
+    fn filter(&self) -> Option<Filter> {
+        let watched = self.watched.lock().unwrap();
+        let (addresses, topics): (Vec<_>, Vec<_>) = watched
+            .iter()
+            .filter(|(_, logs)| logs.strong_count() > 0)
+            .map(|(key, _)| *key)
+            .unzip();
+        (!addresses.is_empty()).then(|| Filter::new().address(addresses).topic0(topics))
+    }
+
+    fn notify(&self, log: &Log) {
+        // Logs removed by a reorg are cleaned up by the cursors
+        if log.removed == Some(true) {
+            return;
+        }
+        let (Some(topic0), Some(block)) = (log.topics.first(), log.block_number) else {
+            return;
+        };
+        // The filter may also match other events of the watched contracts
+        let logs = self
+            .watched
+            .lock()
+            .unwrap()
+            .get(&(log.address, *topic0))
+            .and_then(Weak::upgrade);
+        if let Some(logs) = logs {
+            trace!(block_number = ?log.block_number, "Notified of new log");
+            logs.send_modify(|latest| *latest = block.as_u64().max(*latest));
+        }
+    }
+}
+
+async fn run_subscriptions(url: Url, state: Arc<ChainState>) {
+    loop {
+        match subscribe(&url, &state).await {
+            Ok(()) => warn!("Log subscription ended, polling until it is reconnected"),
+            Err(err) => warn!(
+                ?err,
+                "Log subscription failed, polling until it is reconnected"
+            ),
+        }
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn subscribe(url: &Url, state: &ChainState) -> ChainResult<()> {
+    let ws = Ws::connect(url)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let provider = Provider::new(ws);
+    let mut heads = provider
+        .subscribe_blocks()
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    loop {
+        // Resubscribed over the same websocket whenever the watched logs change
+        let mut logs = match state.filter() {
+            Some(filter) => Some(
+                provider
+                    .subscribe_logs(&filter)
+                    .await
+                    .map_err(ChainCommunicationError::from_other)?,
+            ),
+            None => None,
+        };
+        info!(filter = ?state.filter(), "Subscribed to logs");
+        loop {
+            let next_log = async {
+                match &mut logs {
+                    Some(logs) => logs.next().await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                head = heads.next() => {
+                    let Some(head) = head else {
+                        return Ok(());
+                    };
+                    if let Some(number) = head.number {
+                        state.head.send_replace(number.as_u64());
+                    }
+                }
+                log = next_log => {
+                    let Some(log) = log else {
+                        return Ok(());
+                    };
+                    state.notify(&log);
+                }
+                _ = state.watched_changed.notified() => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::types::U64;
+    use tokio::time::timeout;
+
+    use super::*;
+
+    async fn notified(subscription: &LogSubscription) -> bool {
+        timeout(Duration::from_millis(50), subscription.new_log())
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_notifies_of_logs_past_the_reorg_window() {
+        let state = Arc::new(ChainState::new());
+        let chain = Arc::new(ChainSubscription {
+            state: state.clone(),
+            task: tokio::spawn(std::future::pending::<()>()),
+        });
+        let key = (H160::repeat_byte(1), H256::repeat_byte(2));
+        assert!(state.filter().is_none());
+        let subscription = LogSubscription::watch(chain.clone(), key, 2);
+        let other_event = LogSubscription::watch(chain, (key.0, H256::repeat_byte(3)), 0);
+        assert!(state.filter().is_some());
+
+        let log = |block: u64| Log {
+            address: key.0,
+            topics: vec![key.1],
+            block_number: Some(U64::from(block)),
+            ..Default::default()
+        };
+
+        state.notify(&log(10));
+        state.head.send_replace(11);
+        assert!(!notified(&subscription).await);
+        // The log isn't forgotten by giving up waiting for it
+        state.head.send_replace(12);
+        assert!(notified(&subscription).await);
+        // Only notified of each log once
+        assert!(!notified(&subscription).await);
+        // Logs of other events aren't notified of
+        assert!(!notified(&other_event).await);
+    }
+}
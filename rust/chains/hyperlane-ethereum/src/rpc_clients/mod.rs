@@ -1,9 +1,15 @@
 use ethers::providers::HttpClientError;
 use tracing::{info, trace, warn};
 
-pub use self::{fallback::*, provider::*, retrying::*, trait_builder::*};
+pub use self::{
+    circuit_breaking::*, fallback::*, historical::*, log_subscription::*, provider::*, retrying::*,
+    trait_builder::*,
+};
 
+mod circuit_breaking;
 mod fallback;
+mod historical;
+mod log_subscription;
 mod provider;
 mod retrying;
 mod trait_builder;
@@ -143,6 +143,16 @@ where
         );
         Ok(Some(chain_metrics))
     }
+
+    #[instrument(err, skip(self))]
+    async fn get_chain_id(&self) -> ChainResult<Option<String>> {
+        let chain_id = self
+            .provider
+            .get_chainid()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(Some(chain_id.to_string()))
+    }
 }
 
 impl<M> EthereumProvider<M>
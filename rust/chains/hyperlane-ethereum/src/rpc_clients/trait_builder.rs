@@ -24,7 +24,10 @@ use hyperlane_core::{
 };
 
 use crate::signer::Signers;
-use crate::{ConnectionConf, EthereumFallbackProvider, RetryingProvider, RpcConnectionConf};
+use crate::{
+    CircuitBreakingClient, ConnectionConf, EthereumFallbackProvider, HistoricalBlockProvider,
+    RetryingProvider, RpcConnectionConf,
+};
 
 // This should be whatever the prometheus scrape interval is
 const HTTP_CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
@@ -84,6 +87,7 @@ pub trait BuildableWithProvider {
                     let metrics_provider = self.wrap_rpc_with_metrics(
                         http_provider,
                         url.clone(),
+                        conn,
                         &rpc_metrics,
                         &middleware_metrics,
                     );
@@ -95,8 +99,14 @@ pub trait BuildableWithProvider {
                 let quorum_provider = builder.build();
                 self.build(quorum_provider, conn, locator, signer).await?
             }
-            RpcConnectionConf::HttpFallback { urls } => {
+            RpcConnectionConf::HttpFallback {
+                urls,
+                health_scoring,
+            } => {
                 let mut builder = FallbackProvider::builder();
+                if let Some(conf) = health_scoring {
+                    builder = builder.with_health_scoring(conf.clone());
+                }
                 let http_client = Client::builder()
                     .timeout(HTTP_CLIENT_TIMEOUT)
                     .build()
@@ -106,6 +116,7 @@ pub trait BuildableWithProvider {
                     let metrics_provider = self.wrap_rpc_with_metrics(
                         http_provider,
                         url.clone(),
+                        conn,
                         &rpc_metrics,
                         &middleware_metrics,
                     );
@@ -116,6 +127,7 @@ pub trait BuildableWithProvider {
                     _,
                     JsonRpcBlockGetter<PrometheusJsonRpcClient<Http>>,
                 >::new(fallback_provider);
+                ethereum_fallback_provider.spawn_block_height_sampler();
                 self.build(ethereum_fallback_provider, conn, locator, signer)
                     .await?
             }
@@ -128,6 +140,7 @@ pub trait BuildableWithProvider {
                 let metrics_provider = self.wrap_rpc_with_metrics(
                     http_provider,
                     url.clone(),
+                    conn,
                     &rpc_metrics,
                     &middleware_metrics,
                 );
@@ -144,15 +157,18 @@ pub trait BuildableWithProvider {
         })
     }
 
-    /// Wrap a JsonRpcClient with metrics for use with a quorum provider.
+    /// Wrap a JsonRpcClient with metrics and the rate limit of its url for
+    /// use with a quorum provider.
     fn wrap_rpc_with_metrics<C>(
         &self,
         client: C,
         url: Url,
+        conn: &ConnectionConf,
         rpc_metrics: &Option<JsonRpcClientMetrics>,
         middleware_metrics: &Option<(MiddlewareMetrics, PrometheusMiddlewareConf)>,
     ) -> PrometheusJsonRpcClient<C> {
-        PrometheusJsonRpcClient::new(
+        let rate_limit = conn.rpc_rate_limits.for_url(&url).cloned();
+        let client = PrometheusJsonRpcClient::new(
             client,
             rpc_metrics
                 .clone()
@@ -177,7 +193,11 @@ pub trait BuildableWithProvider {
                     .as_ref()
                     .and_then(|(_, v)| v.chain.clone()),
             },
-        )
+        );
+        match rate_limit {
+            Some(conf) => client.with_rate_limit(url.as_str(), conf),
+            None => client,
+        }
     }
 
     /// Create the provider, applying any middlewares (e.g. gas oracle, signer) as needed,
@@ -192,6 +212,14 @@ pub trait BuildableWithProvider {
     where
         P: JsonRpcClient + 'static,
     {
+        let client = CircuitBreakingClient::new(client, conn.circuit_breaker.clone());
+        if let Some(block) = conn.historical_block {
+            let client = HistoricalBlockProvider::new(client, block);
+            let provider = wrap_with_gas_oracle(Provider::new(client), locator.domain)?;
+            return self
+                .build_with_signer(provider, conn, locator, signer)
+                .await;
+        }
         let provider = wrap_with_gas_oracle(Provider::new(client), locator.domain)?;
         self.build_with_signer(provider, conn, locator, signer)
             .await
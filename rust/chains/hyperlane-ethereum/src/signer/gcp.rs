@@ -0,0 +1,317 @@
+use std::fmt;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ethers::core::k256::ecdsa::{Error as K256Error, RecoveryId, Signature as KSig, VerifyingKey};
+use ethers::prelude::{Address, Signature, H256, U256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::{hash_message, public_key_to_address};
+use ethers_signers::{to_eip155_v, Signer};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+use yup_oauth2::{
+    authenticator::DefaultAuthenticator, ApplicationDefaultCredentialsAuthenticator,
+    ApplicationDefaultCredentialsFlowOpts,
+};
+
+const CLOUD_KMS_URL: &str = "https://cloudkms.googleapis.com/v1";
+const CLOUD_KMS_SCOPE: &str = "https://www.googleapis.com/auth/cloudkms";
+const SECP256K1_ALGORITHM: &str = "EC_SIGN_SECP256K1_SHA256";
+/// Length of an uncompressed SEC1 encoded secp256k1 public key
+const UNCOMPRESSED_PUBLIC_KEY_LEN: usize = 65;
+
+/// A signer backed by a secp256k1 key version in Google Cloud KMS.
+///
+/// Credentials are looked up the same way as for other Google Cloud clients:
+/// from the service account key file in `GOOGLE_APPLICATION_CREDENTIALS` or
+/// from the metadata server when running on GCP.
+#[derive(Clone)]
+pub struct GcpSigner {
+    client: Client,
+    authenticator: DefaultAuthenticator,
+    /// Full resource name of the key version, i.e.
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    key_name: String,
+    pubkey: VerifyingKey,
+    address: Address,
+    chain_id: u64,
+}
+
+impl fmt::Debug for GcpSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GcpSigner")
+            .field("key_name", &self.key_name)
+            .field("address", &self.address)
+            .field("chain_id", &self.chain_id)
+            .finish()
+    }
+}
+
+/// Errors produced by the GcpSigner
+#[derive(Debug, thiserror::Error)]
+pub enum GcpSignerError {
+    /// Error authenticating with Google Cloud
+    #[error("{0}")]
+    Auth(#[from] yup_oauth2::Error),
+    /// Error calling Cloud KMS
+    #[error("{0}")]
+    Http(#[from] reqwest::Error),
+    /// Error decoding a response of Cloud KMS
+    #[error("{0}")]
+    Base64(#[from] base64::DecodeError),
+    /// Error parsing a key or signature
+    #[error("{0}")]
+    K256(#[from] K256Error),
+    /// Error encoding EIP-712 typed data
+    #[error("{0}")]
+    Eip712(String),
+    /// The key can not be used to sign ethereum transactions
+    #[error("{0}")]
+    InvalidKey(String),
+    /// Error setting up the authenticator
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Deserialize)]
+struct PublicKeyResponse {
+    pem: String,
+    algorithm: String,
+}
+
+#[derive(Serialize)]
+struct AsymmetricSignRequest {
+    digest: Digest,
+}
+
+#[derive(Serialize)]
+struct Digest {
+    sha256: String,
+}
+
+#[derive(Deserialize)]
+struct AsymmetricSignResponse {
+    signature: String,
+}
+
+impl GcpSigner {
+    /// Instantiate a new signer from the resource name of a Cloud KMS key
+    /// version and a chain id. Fetches the public key to derive the address.
+    pub async fn new(key_name: impl Into<String>, chain_id: u64) -> Result<Self, GcpSignerError> {
+        let authenticator = match ApplicationDefaultCredentialsAuthenticator::builder(
+            ApplicationDefaultCredentialsFlowOpts::default(),
+        )
+        .await
+        {
+            yup_oauth2::authenticator::ApplicationDefaultCredentialsTypes::ServiceAccount(
+                builder,
+            ) => builder.build().await?,
+            yup_oauth2::authenticator::ApplicationDefaultCredentialsTypes::InstanceMetadata(
+                builder,
+            ) => builder.build().await?,
+        };
+        let client = Client::new();
+        let key_name = key_name.into();
+        let pubkey = Self::fetch_pubkey(&client, &authenticator, &key_name).await?;
+        let address = public_key_to_address(&pubkey);
+        trace!(?address, key_name, "Instantiated GCP KMS signer");
+        Ok(Self {
+            client,
+            authenticator,
+            key_name,
+            pubkey,
+            address,
+            chain_id,
+        })
+    }
+
+    async fn access_token(authenticator: &DefaultAuthenticator) -> Result<String, GcpSignerError> {
+        let token = authenticator.token(&[CLOUD_KMS_SCOPE]).await?;
+        token
+            .token()
+            .map(str::to_owned)
+            .ok_or_else(|| GcpSignerError::InvalidKey("No access token for Cloud KMS".to_owned()))
+    }
+
+    async fn fetch_pubkey(
+        client: &Client,
+        authenticator: &DefaultAuthenticator,
+        key_name: &str,
+    ) -> Result<VerifyingKey, GcpSignerError> {
+        let response: PublicKeyResponse = client
+            .get(format!("{CLOUD_KMS_URL}/{key_name}/publicKey"))
+            .bearer_auth(Self::access_token(authenticator).await?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if response.algorithm != SECP256K1_ALGORITHM {
+            return Err(GcpSignerError::InvalidKey(format!(
+                "Expected a {SECP256K1_ALGORITHM} key, got {}",
+                response.algorithm
+            )));
+        }
+        decode_pem_public_key(&response.pem)
+    }
+
+    /// Sign a digest with the key, returning the normalized signature
+    #[instrument(err, skip(self))]
+    async fn sign_digest(&self, digest: H256) -> Result<KSig, GcpSignerError> {
+        // Cloud KMS signs whatever 32 bytes it is given, the digest does not
+        // need to be a sha256 hash.
+        let request = AsymmetricSignRequest {
+            digest: Digest {
+                sha256: BASE64.encode(digest.as_bytes()),
+            },
+        };
+        let response: AsymmetricSignResponse = self
+            .client
+            .post(format!("{CLOUD_KMS_URL}/{}:asymmetricSign", self.key_name))
+            .bearer_auth(Self::access_token(&self.authenticator).await?)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let signature = KSig::from_der(&BASE64.decode(response.signature)?)?;
+        Ok(signature.normalize_s().unwrap_or(signature))
+    }
+
+    /// Sign a digest, with `v` set to the recovery id
+    async fn sign_digest_with_recovery_id(
+        &self,
+        digest: H256,
+    ) -> Result<Signature, GcpSignerError> {
+        let signature = self.sign_digest(digest).await?;
+        let recovery_id = recovery_id(&signature, digest, &self.pubkey)?;
+        Ok(Signature {
+            r: U256::from_big_endian(signature.r().to_bytes().as_slice()),
+            s: U256::from_big_endian(signature.s().to_bytes().as_slice()),
+            v: recovery_id.to_byte() as u64,
+        })
+    }
+
+    /// Sign a digest with an EIP-155 `v` for `chain_id`
+    async fn sign_digest_with_eip155(
+        &self,
+        digest: H256,
+        chain_id: u64,
+    ) -> Result<Signature, GcpSignerError> {
+        let mut signature = self.sign_digest_with_recovery_id(digest).await?;
+        signature.v = to_eip155_v(signature.v as u8, chain_id);
+        Ok(signature)
+    }
+}
+
+/// Extract the public key from a PEM encoded SubjectPublicKeyInfo. The SEC1
+/// encoded key is at the end of the structure.
+fn decode_pem_public_key(pem: &str) -> Result<VerifyingKey, GcpSignerError> {
+    let der = BASE64.decode(
+        pem.lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect::<String>(),
+    )?;
+    let sec1 = der
+        .len()
+        .checked_sub(UNCOMPRESSED_PUBLIC_KEY_LEN)
+        .map(|start| &der[start..])
+        .ok_or_else(|| GcpSignerError::InvalidKey("Public key is too short".to_owned()))?;
+    Ok(VerifyingKey::from_sec1_bytes(sec1)?)
+}
+
+/// Find the recovery id that recovers `pubkey` from the signature
+fn recovery_id(
+    signature: &KSig,
+    digest: H256,
+    pubkey: &VerifyingKey,
+) -> Result<RecoveryId, GcpSignerError> {
+    [0, 1]
+        .into_iter()
+        .filter_map(RecoveryId::from_byte)
+        .find(|recovery_id| {
+            VerifyingKey::recover_from_prehash(digest.as_bytes(), signature, *recovery_id)
+                .map_or(false, |recovered| &recovered == pubkey)
+        })
+        .ok_or_else(|| GcpSignerError::K256(K256Error::new()))
+}
+
+#[async_trait::async_trait]
+impl Signer for GcpSigner {
+    type Error = GcpSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        let mut signature = self
+            .sign_digest_with_recovery_id(hash_message(message))
+            .await?;
+        signature.v += 27;
+        Ok(signature)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        tx.set_chain_id(chain_id);
+        self.sign_digest_with_eip155(tx.sighash(), chain_id).await
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let digest = payload
+            .encode_eip712()
+            .map_err(|e| GcpSignerError::Eip712(e.to_string()))?;
+        let mut signature = self
+            .sign_digest_with_recovery_id(H256::from(digest))
+            .await?;
+        signature.v += 27;
+        Ok(signature)
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::core::k256::ecdsa::SigningKey;
+
+    use super::*;
+
+    #[test]
+    fn test_recovery_id_and_pem_decoding() {
+        let signing_key = SigningKey::from_bytes(&[0x11; 32].into()).unwrap();
+        let pubkey = *signing_key.verifying_key();
+
+        // SubjectPublicKeyInfo prefix of a secp256k1 public key
+        let mut der = hex::decode("3056301006072a8648ce3d020106052b8104000a034200").unwrap();
+        der.extend_from_slice(pubkey.to_encoded_point(false).as_bytes());
+        let pem = format!(
+            "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+            BASE64.encode(der)
+        );
+        assert_eq!(decode_pem_public_key(&pem).unwrap(), pubkey);
+
+        let digest = H256::repeat_byte(0x42);
+        let (signature, expected) = signing_key
+            .sign_prehash_recoverable(digest.as_bytes())
+            .unwrap();
+        assert_eq!(recovery_id(&signature, digest, &pubkey).unwrap(), expected);
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+use async_trait::async_trait;
+use ethers::prelude::{Address, Signature};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers_signers::{HDPath, Ledger, LedgerError, Signer};
+use tokio::sync::Mutex;
+use tracing::instrument;
+
+/// Ledgers connected to, by derivation path. Every signer built for a key
+/// shares its connection, as opening one takes a while and the device only
+/// handles a single request at a time anyway.
+static LEDGERS: OnceLock<Mutex<HashMap<String, Arc<Ledger>>>> = OnceLock::new();
+
+/// A signer using a key held by a Ledger hardware wallet connected over USB,
+/// which must be unlocked with the Ethereum app open.
+///
+/// Every signature has to be approved on the device, so the key never leaves
+/// it. Requests to the device are made one at a time.
+#[derive(Debug, Clone)]
+pub struct LedgerSigner {
+    ledger: Arc<Ledger>,
+    chain_id: u64,
+}
+
+impl LedgerSigner {
+    /// Connect to the Ledger and load the address of the key at
+    /// `derivation_path`, e.g. `m/44'/60'/0'/0/0`. Reuses the connection of
+    /// any signer built for the same key before.
+    #[instrument(err)]
+    pub async fn new(derivation_path: &str, chain_id: u64) -> Result<Self, LedgerError> {
+        let mut ledgers = LEDGERS.get_or_init(Default::default).lock().await;
+        let ledger = match ledgers.get(derivation_path) {
+            Some(ledger) => ledger.clone(),
+            None => {
+                let ledger = Arc::new(
+                    Ledger::new(HDPath::Other(derivation_path.to_owned()), chain_id).await?,
+                );
+                ledgers.insert(derivation_path.to_owned(), ledger.clone());
+                ledger
+            }
+        };
+        Ok(Self { ledger, chain_id })
+    }
+}
+
+#[async_trait]
+impl Signer for LedgerSigner {
+    type Error = LedgerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.ledger.sign_message(message).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        // The device signs for the chain id it was connected with unless the
+        // transaction has its own
+        let mut tx = tx.clone();
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+        self.ledger.sign_transaction(&tx).await
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        self.ledger.sign_typed_data(payload).await
+    }
+
+    fn address(&self) -> Address {
+        self.ledger.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
@@ -2,14 +2,20 @@ use async_trait::async_trait;
 use ethers::prelude::{Address, Signature};
 use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::types::transaction::eip712::Eip712;
-use ethers_signers::{AwsSigner, AwsSignerError, LocalWallet, Signer, WalletError};
+use ethers_signers::{AwsSigner, AwsSignerError, LedgerError, LocalWallet, Signer, WalletError};
 
 use hyperlane_core::{
     HyperlaneSigner, HyperlaneSignerError, Signature as HyperlaneSignature, H160, H256,
 };
 
+mod gcp;
+mod ledger;
 mod singleton;
+mod web3signer;
+pub use gcp::*;
+pub use ledger::*;
 pub use singleton::*;
+pub use web3signer::*;
 
 /// Ethereum-supported signer types
 #[derive(Debug, Clone)]
@@ -18,6 +24,12 @@ pub enum Signers {
     Local(LocalWallet),
     /// A signer using a key stored in aws kms
     Aws(AwsSigner),
+    /// A signer using a key stored in gcp cloud kms
+    Gcp(GcpSigner),
+    /// A signer delegating to a remote Web3Signer-compatible service
+    Web3Signer(Web3Signer),
+    /// A signer using a key held by a Ledger hardware wallet
+    Ledger(LedgerSigner),
 }
 
 impl From<LocalWallet> for Signers {
@@ -32,6 +44,24 @@ impl From<AwsSigner> for Signers {
     }
 }
 
+impl From<GcpSigner> for Signers {
+    fn from(s: GcpSigner) -> Self {
+        Signers::Gcp(s)
+    }
+}
+
+impl From<Web3Signer> for Signers {
+    fn from(s: Web3Signer) -> Self {
+        Signers::Web3Signer(s)
+    }
+}
+
+impl From<LedgerSigner> for Signers {
+    fn from(s: LedgerSigner) -> Self {
+        Signers::Ledger(s)
+    }
+}
+
 #[async_trait]
 impl Signer for Signers {
     type Error = SignersError;
@@ -43,6 +73,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_message(message).await?),
             Signers::Aws(signer) => Ok(signer.sign_message(message).await?),
+            Signers::Gcp(signer) => Ok(signer.sign_message(message).await?),
+            Signers::Web3Signer(signer) => Ok(signer.sign_message(message).await?),
+            Signers::Ledger(signer) => Ok(signer.sign_message(message).await?),
         }
     }
 
@@ -50,6 +83,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_transaction(message).await?),
             Signers::Aws(signer) => Ok(signer.sign_transaction(message).await?),
+            Signers::Gcp(signer) => Ok(signer.sign_transaction(message).await?),
+            Signers::Web3Signer(signer) => Ok(signer.sign_transaction(message).await?),
+            Signers::Ledger(signer) => Ok(signer.sign_transaction(message).await?),
         }
     }
 
@@ -60,6 +96,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_typed_data(payload).await?),
             Signers::Aws(signer) => Ok(signer.sign_typed_data(payload).await?),
+            Signers::Gcp(signer) => Ok(signer.sign_typed_data(payload).await?),
+            Signers::Web3Signer(signer) => Ok(signer.sign_typed_data(payload).await?),
+            Signers::Ledger(signer) => Ok(signer.sign_typed_data(payload).await?),
         }
     }
 
@@ -67,6 +106,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.address(),
             Signers::Aws(signer) => signer.address(),
+            Signers::Gcp(signer) => signer.address(),
+            Signers::Web3Signer(signer) => signer.address(),
+            Signers::Ledger(signer) => signer.address(),
         }
     }
 
@@ -74,6 +116,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.chain_id(),
             Signers::Aws(signer) => signer.chain_id(),
+            Signers::Gcp(signer) => signer.chain_id(),
+            Signers::Web3Signer(signer) => signer.chain_id(),
+            Signers::Ledger(signer) => signer.chain_id(),
         }
     }
 
@@ -81,6 +126,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.with_chain_id(chain_id).into(),
             Signers::Aws(signer) => signer.with_chain_id(chain_id).into(),
+            Signers::Gcp(signer) => signer.with_chain_id(chain_id).into(),
+            Signers::Web3Signer(signer) => signer.with_chain_id(chain_id).into(),
+            Signers::Ledger(signer) => signer.with_chain_id(chain_id).into(),
         }
     }
 }
@@ -106,6 +154,15 @@ pub enum SignersError {
     /// AWS Signer Error
     #[error("{0}")]
     AwsSignerError(#[from] AwsSignerError),
+    /// GCP Signer Error
+    #[error("{0}")]
+    GcpSignerError(#[from] GcpSignerError),
+    /// Web3Signer Error
+    #[error("{0}")]
+    Web3SignerError(#[from] Web3SignerError),
+    /// Ledger Error
+    #[error("{0}")]
+    LedgerError(#[from] LedgerError),
     /// Wallet Signer Error
     #[error("{0}")]
     WalletError(#[from] WalletError),
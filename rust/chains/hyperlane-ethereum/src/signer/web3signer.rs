@@ -0,0 +1,281 @@
+use std::{fmt, path::Path, time::Duration};
+
+use ethers::prelude::{Address, Signature};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::{hex, keccak256};
+use ethers_signers::{to_eip155_v, Signer};
+use reqwest::{Certificate, Client, Identity};
+use serde::Serialize;
+use tracing::{instrument, trace};
+use url::Url;
+
+const WEB3SIGNER_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A signer that delegates signing to a remote
+/// [Web3Signer](https://docs.web3signer.consensys.io/)-compatible service, so
+/// that the agent never holds the key.
+///
+/// Uses the eth1 REST api, which signs the keccak256 hash of the data it is
+/// given. Requests can be authenticated with a TLS client certificate.
+#[derive(Clone)]
+pub struct Web3Signer {
+    client: Client,
+    url: Url,
+    /// The public key the service signs with, as it identifies keys by it
+    public_key: String,
+    address: Address,
+    chain_id: u64,
+}
+
+impl fmt::Debug for Web3Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Web3Signer")
+            .field("url", &self.url)
+            .field("address", &self.address)
+            .field("chain_id", &self.chain_id)
+            .finish()
+    }
+}
+
+/// Errors produced by the Web3Signer
+#[derive(Debug, thiserror::Error)]
+pub enum Web3SignerError {
+    /// Error calling the remote signer
+    #[error("{0}")]
+    Http(#[from] reqwest::Error),
+    /// Error reading a certificate
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    /// Error decoding a response of the remote signer
+    #[error("{0}")]
+    Hex(#[from] hex::FromHexError),
+    /// The remote signer returned an invalid signature
+    #[error("{0}")]
+    Signature(#[from] ethers::types::SignatureError),
+    /// Error encoding EIP-712 typed data
+    #[error("{0}")]
+    Eip712(String),
+    /// The remote signer doesn't hold the key of the configured address
+    #[error("Remote signer has no key for address {0:?}")]
+    UnknownAddress(Address),
+    /// Invalid url
+    #[error("{0}")]
+    Url(#[from] url::ParseError),
+}
+
+#[derive(Serialize)]
+struct SignRequest {
+    data: String,
+}
+
+impl Web3Signer {
+    /// Instantiate a new signer for the key of `address` held by the remote
+    /// signer at `url`.
+    ///
+    /// - `client_identity` is a PKCS#12 archive with the client certificate
+    ///   and key, and its password, for TLS client auth.
+    /// - `ca_cert` is a PEM encoded certificate to trust in addition to the
+    ///   system roots, for remote signers with a private CA.
+    pub async fn new(
+        url: Url,
+        address: Address,
+        client_identity: Option<(&Path, &str)>,
+        ca_cert: Option<&Path>,
+        chain_id: u64,
+    ) -> Result<Self, Web3SignerError> {
+        let mut builder = Client::builder().timeout(WEB3SIGNER_REQUEST_TIMEOUT);
+        if let Some((path, password)) = client_identity {
+            let identity = Identity::from_pkcs12_der(&std::fs::read(path)?, password)?;
+            builder = builder.identity(identity);
+        }
+        if let Some(path) = ca_cert {
+            builder = builder.add_root_certificate(Certificate::from_pem(&std::fs::read(path)?)?);
+        }
+        let client = builder.build()?;
+        let url = with_trailing_slash(url);
+        let public_key = Self::find_public_key(&client, &url, address).await?;
+        trace!(?address, %url, "Instantiated remote signer");
+        Ok(Self {
+            client,
+            url,
+            public_key,
+            address,
+            chain_id,
+        })
+    }
+
+    /// Find the public key of `address` among the keys of the remote signer
+    async fn find_public_key(
+        client: &Client,
+        url: &Url,
+        address: Address,
+    ) -> Result<String, Web3SignerError> {
+        let public_keys: Vec<String> = client
+            .get(url.join("api/v1/eth1/publicKeys")?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        for public_key in public_keys {
+            if public_key_to_address(&public_key)? == address {
+                return Ok(public_key);
+            }
+        }
+        Err(Web3SignerError::UnknownAddress(address))
+    }
+
+    /// Sign the keccak256 hash of `data`, returning a signature with a `v` of
+    /// 27 or 28.
+    #[instrument(err, skip(self, data))]
+    async fn sign_data(&self, data: &[u8]) -> Result<Signature, Web3SignerError> {
+        let response = self
+            .client
+            .post(
+                self.url
+                    .join(&format!("api/v1/eth1/sign/{}", self.public_key))?,
+            )
+            .json(&SignRequest {
+                data: format!("0x{}", hex::encode(data)),
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let mut signature = Signature::try_from(decode_hex(response.trim())?.as_slice())?;
+        if signature.v < 27 {
+            signature.v += 27;
+        }
+        Ok(signature)
+    }
+}
+
+/// Make `url` a directory, so that the api paths are joined under its path
+/// instead of replacing its last segment
+fn with_trailing_slash(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    url
+}
+
+fn decode_hex(data: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    hex::decode(data.trim_matches('"').trim_start_matches("0x"))
+}
+
+/// Derive the address of a hex encoded secp256k1 public key, with or without
+/// the SEC1 `0x04` prefix.
+fn public_key_to_address(public_key: &str) -> Result<Address, Web3SignerError> {
+    let bytes = decode_hex(public_key)?;
+    let bytes = match bytes.as_slice() {
+        [0x04, rest @ ..] if rest.len() == 64 => rest,
+        rest => rest,
+    };
+    Ok(Address::from_slice(&keccak256(bytes)[12..]))
+}
+
+/// The data an EIP-191 personal message signature is over
+fn eip191_data(message: &[u8]) -> Vec<u8> {
+    let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    data.extend_from_slice(message);
+    data
+}
+
+#[async_trait::async_trait]
+impl Signer for Web3Signer {
+    type Error = Web3SignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_data(&eip191_data(message.as_ref())).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        tx.set_chain_id(chain_id);
+        // The hash of the rlp encoding is the sighash
+        let mut signature = self.sign_data(&tx.rlp()).await?;
+        signature.v = to_eip155_v((signature.v - 27) as u8, chain_id);
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let domain_separator = payload
+            .domain_separator()
+            .map_err(|e| Web3SignerError::Eip712(e.to_string()))?;
+        let struct_hash = payload
+            .struct_hash()
+            .map_err(|e| Web3SignerError::Eip712(e.to_string()))?;
+        let data = [&[0x19, 0x01][..], &domain_separator, &struct_hash].concat();
+        self.sign_data(&data).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::core::k256::ecdsa::SigningKey;
+    use ethers::utils::hash_message;
+
+    use super::*;
+
+    #[test]
+    fn test_public_key_to_address() {
+        let signing_key = SigningKey::from_bytes(&[0x11; 32].into()).unwrap();
+        let encoded = signing_key.verifying_key().to_encoded_point(false);
+        let expected = ethers::utils::public_key_to_address(signing_key.verifying_key());
+
+        let with_prefix = format!("0x{}", hex::encode(encoded.as_bytes()));
+        let without_prefix = format!("0x{}", hex::encode(&encoded.as_bytes()[1..]));
+        assert_eq!(public_key_to_address(&with_prefix).unwrap(), expected);
+        assert_eq!(public_key_to_address(&without_prefix).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_api_paths_are_joined_under_the_base_path() {
+        for base in [
+            "https://signer.example/web3signer",
+            "https://signer.example/web3signer/",
+        ] {
+            let url = with_trailing_slash(Url::parse(base).unwrap());
+            assert_eq!(
+                url.join("api/v1/eth1/publicKeys").unwrap().as_str(),
+                "https://signer.example/web3signer/api/v1/eth1/publicKeys"
+            );
+        }
+        let url = with_trailing_slash(Url::parse("https://signer.example").unwrap());
+        assert_eq!(
+            url.join("api/v1/eth1/publicKeys").unwrap().as_str(),
+            "https://signer.example/api/v1/eth1/publicKeys"
+        );
+    }
+
+    #[test]
+    fn test_eip191_data_hashes_to_message_hash() {
+        let message = b"hello world";
+        assert_eq!(
+            keccak256(eip191_data(message)),
+            hash_message(message).to_fixed_bytes()
+        );
+    }
+}
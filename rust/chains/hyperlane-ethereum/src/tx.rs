@@ -6,7 +6,7 @@ use ethers::{
     abi::Detokenize,
     prelude::{NameOrAddress, TransactionReceipt},
     providers::{JsonRpcClient, PendingTransaction, ProviderError},
-    types::Eip1559TransactionRequest,
+    types::{transaction::eip2718::TypedTransaction, Address, Eip1559TransactionRequest},
 };
 use ethers_contract::builders::ContractCall;
 use ethers_core::{
@@ -17,17 +17,46 @@ use ethers_core::{
     },
 };
 use hyperlane_core::{utils::bytes_to_hex, ChainCommunicationError, ChainResult, H256, U256};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use crate::{Middleware, TransactionOverrides};
+use crate::{ConnectionConf, Middleware, TransactionOverrides, TransactionReplacementConf};
 
 /// An amount of gas to add to the estimated gas
 pub const GAS_ESTIMATE_BUFFER: u32 = 75_000;
 
 const PENDING_TRANSACTION_POLLING_INTERVAL: Duration = Duration::from_secs(2);
 
-/// Dispatches a transaction, logs the tx id, and returns the result
-pub(crate) async fn report_tx<M, D>(tx: ContractCall<M, D>) -> ChainResult<TransactionReceipt>
+/// Minimum time a pending transaction is waited on before timing out
+const MIN_PENDING_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(150);
+
+/// Number of block times a pending transaction is waited on on chains with
+/// slow blocks, where `MIN_PENDING_TRANSACTION_TIMEOUT` is too short
+const PENDING_TRANSACTION_TIMEOUT_BLOCKS: u32 = 10;
+
+/// How often pending transactions are polled. Chains with fast blocks are
+/// polled once per block.
+pub(crate) fn polling_interval(conn: &ConnectionConf) -> Duration {
+    conn.block_time
+        .map_or(PENDING_TRANSACTION_POLLING_INTERVAL, |block_time| {
+            block_time.min(PENDING_TRANSACTION_POLLING_INTERVAL)
+        })
+}
+
+/// How long a pending transaction is waited on, including its confirmations
+pub(crate) fn pending_tx_timeout(conn: &ConnectionConf) -> Duration {
+    let blocks = PENDING_TRANSACTION_TIMEOUT_BLOCKS + conn.transaction_confirmations as u32;
+    conn.block_time
+        .map_or(MIN_PENDING_TRANSACTION_TIMEOUT, |block_time| {
+            (block_time * blocks).max(MIN_PENDING_TRANSACTION_TIMEOUT)
+        })
+}
+
+/// Dispatches a transaction, logs the tx id, and returns the result once it
+/// has the confirmation depth of the chain
+pub(crate) async fn report_tx<M, D>(
+    tx: ContractCall<M, D>,
+    conn: &ConnectionConf,
+) -> ChainResult<TransactionReceipt>
 where
     M: Middleware + 'static,
     D: Detokenize,
@@ -49,18 +78,20 @@ where
     let dispatch_fut = tx.send();
     let dispatched = dispatch_fut
         .await?
-        .interval(PENDING_TRANSACTION_POLLING_INTERVAL);
-    track_pending_tx(dispatched).await
+        .interval(polling_interval(conn))
+        .confirmations(conn.transaction_confirmations);
+    track_pending_tx(dispatched, pending_tx_timeout(conn)).await
 }
 
 pub(crate) async fn track_pending_tx<P: JsonRpcClient>(
     pending_tx: PendingTransaction<'_, P>,
+    timeout: Duration,
 ) -> ChainResult<TransactionReceipt> {
     let tx_hash: H256 = (*pending_tx).into();
 
     info!(?tx_hash, "Dispatched tx");
 
-    match tokio::time::timeout(Duration::from_secs(150), pending_tx).await {
+    match tokio::time::timeout(timeout, pending_tx).await {
         // all good
         Ok(Ok(Some(receipt))) => {
             info!(?tx_hash, "confirmed transaction");
@@ -82,6 +113,250 @@ pub(crate) async fn track_pending_tx<P: JsonRpcClient>(
     }
 }
 
+/// Dispatches a transaction and waits for it to be included. If the
+/// transaction remains unmined for `stuck_after_blocks` blocks, it is replaced
+/// by a copy using the same nonce and bumped fees. Once `max_replacements` is
+/// reached, the nonce can optionally be freed up by a cancellation transaction.
+///
+/// Falls back to `report_tx` if no replacement config is provided.
+pub(crate) async fn report_tx_with_replacement<M, D>(
+    tx: ContractCall<M, D>,
+    conn: &ConnectionConf,
+) -> ChainResult<TransactionReceipt>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    let Some(conf) = conn.transaction_replacement.as_ref() else {
+        return report_tx(tx, conn).await;
+    };
+    let provider = tx.client.clone();
+    let receipt = replace_until_included(tx, conf, polling_interval(conn)).await?;
+    wait_for_confirmations(
+        provider.as_ref(),
+        &receipt,
+        conn.transaction_confirmations,
+        polling_interval(conn),
+    )
+    .await?;
+    Ok(receipt)
+}
+
+async fn replace_until_included<M, D>(
+    mut tx: ContractCall<M, D>,
+    conf: &TransactionReplacementConf,
+    interval: Duration,
+) -> ChainResult<TransactionReceipt>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    let provider = tx.client.clone();
+
+    // Fill in the nonce up front so that every replacement reuses it
+    provider
+        .fill_transaction(&mut tx.tx, None)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let nonce = tx.tx.nonce().cloned();
+    let mut typed_tx = tx.tx;
+    let mut sent_tx_hashes = vec![];
+    let mut replacements = 0;
+
+    loop {
+        let tx_hash = match send_typed_tx(provider.as_ref(), typed_tx.clone()).await {
+            Ok(tx_hash) => tx_hash,
+            // A replacement can be rejected because a previously sent transaction
+            // with the same nonce got included in the meantime
+            Err(err) => {
+                return find_receipt(provider.as_ref(), &sent_tx_hashes)
+                    .await?
+                    .ok_or(err)
+            }
+        };
+        sent_tx_hashes.push(tx_hash);
+
+        if let Some(receipt) = wait_for_receipt(
+            provider.as_ref(),
+            &sent_tx_hashes,
+            conf.stuck_after_blocks,
+            interval,
+        )
+        .await?
+        {
+            return Ok(receipt);
+        }
+        if replacements >= conf.max_replacements {
+            break;
+        }
+        replacements += 1;
+        bump_tx_fees(&mut typed_tx, conf.fee_bump_percent);
+        warn!(
+            ?tx_hash,
+            ?nonce,
+            replacements,
+            stuck_after_blocks = conf.stuck_after_blocks,
+            "Transaction is stuck, replacing it with higher fees"
+        );
+    }
+
+    let stuck_tx_hash = *sent_tx_hashes
+        .last()
+        .expect("At least one transaction was sent");
+    if !conf.cancel_when_exhausted {
+        error!(
+            ?stuck_tx_hash,
+            ?nonce,
+            "Transaction is stuck and replacements are exhausted"
+        );
+        return Err(ChainCommunicationError::TransactionTimeout());
+    }
+
+    warn!(?stuck_tx_hash, ?nonce, "Cancelling stuck transaction");
+    let mut cancellation = cancellation_tx(&typed_tx, provider.default_sender())?;
+    bump_tx_fees(&mut cancellation, conf.fee_bump_percent);
+    let cancellation_hash = match send_typed_tx(provider.as_ref(), cancellation).await {
+        Ok(tx_hash) => tx_hash,
+        Err(err) => {
+            return find_receipt(provider.as_ref(), &sent_tx_hashes)
+                .await?
+                .ok_or(err)
+        }
+    };
+    sent_tx_hashes.push(cancellation_hash);
+
+    match wait_for_receipt(
+        provider.as_ref(),
+        &sent_tx_hashes,
+        conf.stuck_after_blocks,
+        interval,
+    )
+    .await?
+    {
+        // One of the original transactions made it in after all
+        Some(receipt) if H256::from(receipt.transaction_hash) != cancellation_hash => Ok(receipt),
+        Some(_) => Err(ChainCommunicationError::TransactionCancelled(stuck_tx_hash)),
+        None => Err(ChainCommunicationError::TransactionTimeout()),
+    }
+}
+
+async fn send_typed_tx<M: Middleware>(provider: &M, tx: TypedTransaction) -> ChainResult<H256> {
+    let pending_tx = provider
+        .send_transaction(tx, None)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let tx_hash: H256 = (*pending_tx).into();
+    info!(?tx_hash, "Dispatched tx");
+    Ok(tx_hash)
+}
+
+/// Polls for a receipt of any of the given transactions until one is found,
+/// or until `stuck_after_blocks` blocks have passed without any of them being
+/// included.
+async fn wait_for_receipt<M: Middleware>(
+    provider: &M,
+    tx_hashes: &[H256],
+    stuck_after_blocks: u64,
+    interval: Duration,
+) -> ChainResult<Option<TransactionReceipt>> {
+    let sent_at_block = current_block_number(provider).await?;
+    loop {
+        if let Some(receipt) = find_receipt(provider, tx_hashes).await? {
+            return Ok(Some(receipt));
+        }
+        let block = current_block_number(provider).await?;
+        if block.saturating_sub(sent_at_block) >= stuck_after_blocks {
+            return Ok(None);
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Waits until the block of `receipt` is `confirmations` blocks deep, the
+/// block of the receipt counting as the first confirmation
+async fn wait_for_confirmations<M: Middleware>(
+    provider: &M,
+    receipt: &TransactionReceipt,
+    confirmations: usize,
+    interval: Duration,
+) -> ChainResult<()> {
+    let Some(included_at) = receipt.block_number.map(|number| number.as_u64()) else {
+        return Ok(());
+    };
+    let target = included_at + (confirmations as u64).saturating_sub(1);
+    while current_block_number(provider).await? < target {
+        tokio::time::sleep(interval).await;
+    }
+    Ok(())
+}
+
+async fn find_receipt<M: Middleware>(
+    provider: &M,
+    tx_hashes: &[H256],
+) -> ChainResult<Option<TransactionReceipt>> {
+    for tx_hash in tx_hashes {
+        let receipt = provider
+            .get_transaction_receipt::<ethers::types::H256>((*tx_hash).into())
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        if let Some(receipt) = receipt {
+            info!(?tx_hash, "confirmed transaction");
+            return Ok(Some(receipt));
+        }
+    }
+    Ok(None)
+}
+
+async fn current_block_number<M: Middleware>(provider: &M) -> ChainResult<u64> {
+    Ok(provider
+        .get_block_number()
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .as_u64())
+}
+
+/// Bumps the fees of a transaction by `bump_percent`, making sure they
+/// increase by at least one wei.
+pub(crate) fn bump_tx_fees(tx: &mut TypedTransaction, bump_percent: u64) {
+    let bump = |fee: EthersU256| {
+        let bumped = fee.saturating_mul((100 + bump_percent).into()) / 100;
+        bumped.max(fee.saturating_add(1.into()))
+    };
+    match tx {
+        TypedTransaction::Eip1559(inner) => {
+            inner.max_fee_per_gas = inner.max_fee_per_gas.map(bump);
+            inner.max_priority_fee_per_gas = inner.max_priority_fee_per_gas.map(bump);
+        }
+        _ => {
+            if let Some(gas_price) = tx.gas_price() {
+                tx.set_gas_price(bump(gas_price));
+            }
+        }
+    }
+}
+
+/// Builds a zero-value transfer to self that reuses the nonce and fees of
+/// `tx`, which frees up the nonce once included. The sender is the signer of
+/// the provider if `tx` doesn't set one, and must be known: a cancellation
+/// sent to the original recipient would call it again.
+fn cancellation_tx(
+    tx: &TypedTransaction,
+    signer: Option<Address>,
+) -> ChainResult<TypedTransaction> {
+    let from = tx
+        .from()
+        .copied()
+        .or(signer)
+        .ok_or(ChainCommunicationError::SignerUnavailable)?;
+    let mut cancellation = tx.clone();
+    cancellation.set_from(from);
+    cancellation.set_to(from);
+    cancellation.set_value(EthersU256::zero());
+    cancellation.set_data(Default::default());
+    cancellation.set_gas(21_000);
+    Ok(cancellation)
+}
+
 /// Populates the gas limit and price for a transaction
 pub(crate) async fn fill_tx_gas_params<M, D>(
     tx: ContractCall<M, D>,
@@ -148,7 +423,7 @@ where
     request = request.max_fee_per_gas(max_fee);
     request = request.max_priority_fee_per_gas(max_priority_fee);
     let mut eip_1559_tx = tx;
-    eip_1559_tx.tx = ethers::types::transaction::eip2718::TypedTransaction::Eip1559(request);
+    eip_1559_tx.tx = TypedTransaction::Eip1559(request);
     Ok(eip_1559_tx.gas(gas_limit))
 }
 
@@ -213,3 +488,52 @@ where
         Ok(call)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ethers::types::{Eip1559TransactionRequest, TransactionRequest};
+
+    use super::*;
+
+    #[test]
+    fn bump_tx_fees_increases_legacy_and_eip1559_fees() {
+        let mut legacy: TypedTransaction = TransactionRequest::new().gas_price(100).into();
+        bump_tx_fees(&mut legacy, 15);
+        assert_eq!(legacy.gas_price(), Some(115.into()));
+
+        let mut eip1559: TypedTransaction = Eip1559TransactionRequest::new()
+            .max_fee_per_gas(200)
+            .max_priority_fee_per_gas(1)
+            .into();
+        bump_tx_fees(&mut eip1559, 15);
+        let TypedTransaction::Eip1559(inner) = eip1559 else {
+            panic!("Expected an EIP-1559 transaction");
+        };
+        assert_eq!(inner.max_fee_per_gas, Some(230.into()));
+        // Always bumped by at least one wei
+        assert_eq!(inner.max_priority_fee_per_gas, Some(2.into()));
+    }
+
+    #[test]
+    fn cancellation_tx_is_a_transfer_to_the_sender() {
+        let signer = Address::repeat_byte(1);
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(Address::repeat_byte(2))
+            .data(vec![1, 2, 3])
+            .value(10)
+            .nonce(7)
+            .into();
+
+        let cancellation = cancellation_tx(&tx, Some(signer)).unwrap();
+        assert_eq!(cancellation.from(), Some(&signer));
+        assert_eq!(cancellation.to_addr(), Some(&signer));
+        assert_eq!(cancellation.value(), Some(&EthersU256::zero()));
+        assert_eq!(cancellation.nonce(), Some(&7.into()));
+
+        // Never sent to the original recipient when the sender is unknown
+        assert!(matches!(
+            cancellation_tx(&tx, None),
+            Err(ChainCommunicationError::SignerUnavailable)
+        ));
+    }
+}
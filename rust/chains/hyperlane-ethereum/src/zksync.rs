@@ -0,0 +1,294 @@
+//! Transactions of zkSync Era and its hyperchains. Generic EVM transactions
+//! are accepted, but their gas is estimated without the pubdata the
+//! bootloader charges for, so they're sent as EIP-712 transactions with the
+//! fees estimated by the node instead.
+
+use ethers::{
+    prelude::{Middleware, TransactionReceipt},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::TypedData},
+        Address, Bytes, Signature, U256 as EthersU256,
+    },
+    utils::rlp::RlpStream,
+};
+use hyperlane_core::{ChainCommunicationError, ChainResult, U256};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use crate::{
+    tx::{pending_tx_timeout, polling_interval, track_pending_tx},
+    ConnectionConf,
+};
+
+/// Type of EIP-712 transactions
+const EIP712_TX_TYPE: u8 = 0x71;
+
+/// Gas per byte of pubdata the fees are estimated for, the default of the
+/// zkSync SDKs
+const DEFAULT_GAS_PER_PUBDATA: u64 = 50_000;
+
+/// Fees estimated by `zks_estimateFee`, accounting for the pubdata of the
+/// transaction
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ZkSyncFee {
+    pub gas_limit: EthersU256,
+    pub gas_per_pubdata_limit: EthersU256,
+    pub max_fee_per_gas: EthersU256,
+    pub max_priority_fee_per_gas: EthersU256,
+}
+
+/// The sender of a transaction, that of the signer if it isn't set
+fn sender<M: Middleware>(provider: &M, tx: &TypedTransaction) -> ChainResult<Address> {
+    tx.from()
+        .copied()
+        .or_else(|| provider.default_sender())
+        .ok_or(ChainCommunicationError::SignerUnavailable)
+}
+
+/// Estimate the fees of a transaction with the node, which simulates it in
+/// the bootloader
+pub(crate) async fn estimate_fee<M: Middleware>(
+    provider: &M,
+    tx: &TypedTransaction,
+) -> ChainResult<ZkSyncFee> {
+    let request = json!({
+        "from": sender(provider, tx)?,
+        "to": tx.to_addr(),
+        "data": tx.data(),
+        "value": tx.value(),
+        "eip712Meta": {
+            "gasPerPubdata": EthersU256::from(DEFAULT_GAS_PER_PUBDATA),
+        },
+    });
+    provider
+        .provider()
+        .request("zks_estimateFee", [request])
+        .await
+        .map_err(ChainCommunicationError::from_other)
+}
+
+/// The fields of an EIP-712 transaction without a paymaster or factory
+/// deps, as signed and encoded
+#[derive(Debug, Clone)]
+struct Eip712Tx {
+    nonce: EthersU256,
+    max_priority_fee_per_gas: EthersU256,
+    max_fee_per_gas: EthersU256,
+    gas_limit: EthersU256,
+    to: Address,
+    value: EthersU256,
+    data: Bytes,
+    chain_id: EthersU256,
+    from: Address,
+    gas_per_pubdata_limit: EthersU256,
+}
+
+impl Eip712Tx {
+    /// The typed data the sender signs
+    fn typed_data(&self) -> ChainResult<TypedData> {
+        Ok(serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                ],
+                "Transaction": [
+                    { "name": "txType", "type": "uint256" },
+                    { "name": "from", "type": "uint256" },
+                    { "name": "to", "type": "uint256" },
+                    { "name": "gasLimit", "type": "uint256" },
+                    { "name": "gasPerPubdataByteLimit", "type": "uint256" },
+                    { "name": "maxFeePerGas", "type": "uint256" },
+                    { "name": "maxPriorityFeePerGas", "type": "uint256" },
+                    { "name": "paymaster", "type": "uint256" },
+                    { "name": "nonce", "type": "uint256" },
+                    { "name": "value", "type": "uint256" },
+                    { "name": "data", "type": "bytes" },
+                    { "name": "factoryDeps", "type": "bytes32[]" },
+                    { "name": "paymasterInput", "type": "bytes" },
+                ],
+            },
+            "primaryType": "Transaction",
+            "domain": { "name": "zkSync", "version": "2", "chainId": self.chain_id },
+            "message": {
+                "txType": EIP712_TX_TYPE,
+                "from": EthersU256::from_big_endian(self.from.as_bytes()),
+                "to": EthersU256::from_big_endian(self.to.as_bytes()),
+                "gasLimit": self.gas_limit,
+                "gasPerPubdataByteLimit": self.gas_per_pubdata_limit,
+                "maxFeePerGas": self.max_fee_per_gas,
+                "maxPriorityFeePerGas": self.max_priority_fee_per_gas,
+                "paymaster": 0,
+                "nonce": self.nonce,
+                "value": self.value,
+                "data": self.data,
+                "factoryDeps": [],
+                "paymasterInput": "0x",
+            },
+        }))?)
+    }
+
+    /// The raw transaction, with the sender's signature of its typed data
+    fn rlp_signed(&self, signature: &Signature) -> Bytes {
+        let mut rlp = RlpStream::new_list(16);
+        rlp.append(&self.nonce);
+        rlp.append(&self.max_priority_fee_per_gas);
+        rlp.append(&self.max_fee_per_gas);
+        rlp.append(&self.gas_limit);
+        rlp.append(&self.to);
+        rlp.append(&self.value);
+        rlp.append(&self.data);
+        rlp.append(&signature.v.saturating_sub(27));
+        rlp.append(&signature.r);
+        rlp.append(&signature.s);
+        rlp.append(&self.chain_id);
+        rlp.append(&self.from);
+        rlp.append(&self.gas_per_pubdata_limit);
+        // No factory deps, the signature as the custom signature, and no
+        // paymaster
+        rlp.begin_list(0);
+        rlp.append(&signature.to_vec());
+        rlp.begin_list(0);
+        [&[EIP712_TX_TYPE], rlp.as_raw()].concat().into()
+    }
+}
+
+/// Send a transaction as an EIP-712 transaction with the estimated fees and
+/// `gas_limit` if set, returning its receipt once it has the confirmation
+/// depth of the chain. Its nonce is taken from the nonce manager of the
+/// provider, like those of the signer's other transactions.
+pub(crate) async fn send_eip712_tx<M: Middleware>(
+    provider: &M,
+    mut tx: TypedTransaction,
+    gas_limit: Option<U256>,
+    conn: &ConnectionConf,
+) -> ChainResult<TransactionReceipt> {
+    let from = sender(provider, &tx)?;
+    let fee = estimate_fee(provider, &tx).await?;
+    let gas_limit = gas_limit.map_or(fee.gas_limit, Into::into);
+    // With the gas and its price set, filling the transaction only takes a
+    // nonce from the nonce manager
+    tx.set_from(from);
+    tx.set_gas(gas_limit);
+    tx.set_gas_price(fee.max_fee_per_gas);
+    provider
+        .fill_transaction(&mut tx, None)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let nonce = tx
+        .nonce()
+        .copied()
+        .ok_or_else(|| ChainCommunicationError::from_other_str("Transaction has no nonce"))?;
+    let chain_id = provider
+        .get_chainid()
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+
+    let eip712_tx = Eip712Tx {
+        nonce,
+        max_priority_fee_per_gas: fee.max_priority_fee_per_gas,
+        max_fee_per_gas: fee.max_fee_per_gas,
+        gas_limit,
+        to: tx.to_addr().copied().unwrap_or_default(),
+        value: tx.value().copied().unwrap_or_default(),
+        data: tx.data().cloned().unwrap_or_default(),
+        chain_id,
+        from,
+        gas_per_pubdata_limit: fee.gas_per_pubdata_limit,
+    };
+    let signature = provider
+        .sign_typed_data(&eip712_tx.typed_data()?, from)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+
+    info!(to = ?eip712_tx.to, data = %eip712_tx.data, ?nonce, ?gas_limit, "Dispatching EIP-712 transaction");
+    let pending_tx = provider
+        .send_raw_transaction(eip712_tx.rlp_signed(&signature))
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .interval(polling_interval(conn))
+        .confirmations(conn.transaction_confirmations);
+    track_pending_tx(pending_tx, pending_tx_timeout(conn)).await
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::{
+        types::transaction::eip712::Eip712,
+        utils::rlp::{Prototype, Rlp},
+    };
+
+    use super::*;
+
+    fn eip712_tx() -> Eip712Tx {
+        Eip712Tx {
+            nonce: 7.into(),
+            max_priority_fee_per_gas: 1_000.into(),
+            max_fee_per_gas: 250_000_000.into(),
+            gas_limit: 1_500_000.into(),
+            to: Address::repeat_byte(0x22),
+            value: 0.into(),
+            data: vec![0xab, 0xcd].into(),
+            chain_id: 324.into(),
+            from: Address::repeat_byte(0x11),
+            gas_per_pubdata_limit: DEFAULT_GAS_PER_PUBDATA.into(),
+        }
+    }
+
+    #[test]
+    fn test_rlp_signed() {
+        let tx = eip712_tx();
+        let signature = Signature {
+            r: EthersU256::from(3),
+            s: EthersU256::from(4),
+            v: 28,
+        };
+        let raw = tx.rlp_signed(&signature);
+        assert_eq!(raw[0], EIP712_TX_TYPE);
+
+        let rlp = Rlp::new(&raw[1..]);
+        assert_eq!(rlp.prototype().unwrap(), Prototype::List(16));
+        assert_eq!(rlp.val_at::<EthersU256>(0).unwrap(), tx.nonce);
+        assert_eq!(
+            rlp.val_at::<EthersU256>(1).unwrap(),
+            tx.max_priority_fee_per_gas
+        );
+        assert_eq!(rlp.val_at::<EthersU256>(2).unwrap(), tx.max_fee_per_gas);
+        assert_eq!(rlp.val_at::<EthersU256>(3).unwrap(), tx.gas_limit);
+        assert_eq!(rlp.val_at::<Address>(4).unwrap(), tx.to);
+        assert_eq!(rlp.val_at::<EthersU256>(5).unwrap(), tx.value);
+        assert_eq!(rlp.val_at::<Vec<u8>>(6).unwrap(), tx.data.to_vec());
+        // The parity of the signature, not its legacy `v`
+        assert_eq!(rlp.val_at::<u64>(7).unwrap(), 1);
+        assert_eq!(rlp.val_at::<EthersU256>(8).unwrap(), signature.r);
+        assert_eq!(rlp.val_at::<EthersU256>(9).unwrap(), signature.s);
+        assert_eq!(rlp.val_at::<EthersU256>(10).unwrap(), tx.chain_id);
+        assert_eq!(rlp.val_at::<Address>(11).unwrap(), tx.from);
+        assert_eq!(
+            rlp.val_at::<EthersU256>(12).unwrap(),
+            tx.gas_per_pubdata_limit
+        );
+        assert_eq!(rlp.at(13).unwrap().prototype().unwrap(), Prototype::List(0));
+        assert_eq!(rlp.val_at::<Vec<u8>>(14).unwrap(), signature.to_vec());
+        assert_eq!(rlp.at(15).unwrap().prototype().unwrap(), Prototype::List(0));
+    }
+
+    #[test]
+    fn test_typed_data() {
+        let tx = eip712_tx();
+        let typed_data = tx.typed_data().unwrap();
+        assert_eq!(typed_data.domain.chain_id, Some(tx.chain_id));
+        assert_eq!(typed_data.domain.name.as_deref(), Some("zkSync"));
+        assert_eq!(typed_data.primary_type, "Transaction");
+
+        // The signed hash commits to every field
+        let hash = typed_data.encode_eip712().unwrap();
+        let other = Eip712Tx {
+            nonce: 8.into(),
+            ..tx
+        };
+        assert_ne!(other.typed_data().unwrap().encode_eip712().unwrap(), hash);
+    }
+}
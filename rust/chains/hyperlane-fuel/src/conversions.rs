@@ -43,6 +43,12 @@ impl_h256!(
     |v| H256::from(*v.hash)
 );
 
+impl_h256!(
+    fuels::prelude::Bech32Address,
+    |v| fuels::prelude::Bech32Address::from(fuels::prelude::Address::new(v.0)),
+    |v| H256::from(*v.hash)
+);
+
 impl_h256!(fuels::types::Bits256, |v| fuels::types::Bits256(v.0), |v| {
     H256::from(v.0)
 });
@@ -0,0 +1,33 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-fuel
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneFuelError {
+    /// Error of the GraphQL client
+    #[error(transparent)]
+    Client(#[from] std::io::Error),
+    /// Error of the fuels SDK
+    #[error(transparent)]
+    Fuels(#[from] fuels::prelude::Error),
+    /// Error of the fuels provider
+    #[error(transparent)]
+    Provider(#[from] fuels::prelude::ProviderError),
+    /// A transaction wasn't found
+    #[error("Transaction {0} not found")]
+    TransactionNotFound(String),
+    /// A transaction has an unexpected kind
+    #[error("Transaction {0} is not a script")]
+    NotScript(String),
+    /// A value of the GraphQL API couldn't be parsed
+    #[error("Unexpected value {0}")]
+    UnexpectedValue(String),
+}
+
+impl From<HyperlaneFuelError> for ChainCommunicationError {
+    fn from(value: HyperlaneFuelError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -6,13 +6,15 @@
 #![allow(unused_variables)]
 
 pub use self::{
-    interchain_gas::*, mailbox::*, multisig_ism::*, provider::*, routing_ism::*, trait_builder::*,
-    validator_announce::*,
+    error::*, interchain_gas::*, mailbox::*, multisig_ism::*, provider::*, routing_ism::*,
+    trait_builder::*, validator_announce::*,
 };
 
 mod contracts;
 mod conversions;
+mod error;
 mod interchain_gas;
+mod logs;
 mod mailbox;
 mod multisig_ism;
 mod provider;
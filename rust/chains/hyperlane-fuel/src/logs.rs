@@ -0,0 +1,103 @@
+use std::ops::RangeInclusive;
+
+use fuels::{
+    client::{PageDirection, PaginationRequest},
+    tx::Receipt,
+};
+use hyperlane_core::{ChainResult, LogMeta, H256};
+
+use crate::{
+    conversions::*,
+    provider::{parse_hash, script_succeeded},
+    FuelProvider, HyperlaneFuelError,
+};
+
+/// Number of blocks to query at once
+const PAGE_SIZE: i32 = 100;
+
+/// Fetches the data logged by a contract with a log id. Typed logs have the
+/// id the compiler assigns to their type, raw logs the id the contract sets
+/// as `rB` of the `LOGD` instruction.
+#[derive(Debug, Clone)]
+pub(crate) struct LogFetcher {
+    provider: FuelProvider,
+    contract: H256,
+    log_id: u64,
+}
+
+impl LogFetcher {
+    pub fn new(provider: FuelProvider, contract: H256, log_id: u64) -> Self {
+        Self {
+            provider,
+            contract,
+            log_id,
+        }
+    }
+
+    /// The latest block, which is final
+    pub async fn finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.block_number().await
+    }
+
+    /// The data logged in successful transactions of the blocks of `range`,
+    /// in order, with where it was logged. Blocks are queried a page at a
+    /// time, the receipts of each of their transactions one at a time.
+    pub async fn fetch(&self, range: RangeInclusive<u32>) -> ChainResult<Vec<(Vec<u8>, LogMeta)>> {
+        let client = self.provider.client();
+        let mut logs = vec![];
+        // The cursor is the height of the block to start after
+        let mut cursor = range
+            .start()
+            .checked_sub(1)
+            .map(|height| height.to_string());
+        loop {
+            let page = client
+                .blocks(PaginationRequest {
+                    cursor: cursor.clone(),
+                    results: PAGE_SIZE,
+                    direction: PageDirection::Forward,
+                })
+                .await
+                .map_err(HyperlaneFuelError::from)?;
+
+            for block in &page.results {
+                let height = block.header.height.0;
+                if height > u64::from(*range.end()) {
+                    return Ok(logs);
+                }
+                let block_hash = parse_hash(&block.id.to_string())?;
+                for (transaction_index, transaction) in block.transactions.iter().enumerate() {
+                    let transaction_id = parse_hash(&transaction.id.to_string())?;
+                    let receipts = self.provider.receipts(transaction_id).await?;
+                    // Logs of reverted transactions are still in their
+                    // receipts
+                    if !script_succeeded(&receipts) {
+                        continue;
+                    }
+                    for (log_index, receipt) in receipts.into_iter().enumerate() {
+                        let Receipt::LogData { id, rb, data, .. } = receipt else {
+                            continue;
+                        };
+                        if id.into_h256() != self.contract || rb != self.log_id {
+                            continue;
+                        }
+                        let meta = LogMeta {
+                            address: self.contract,
+                            block_number: height,
+                            block_hash,
+                            transaction_id: transaction_id.into(),
+                            transaction_index: transaction_index as u64,
+                            log_index: (log_index as u64).into(),
+                        };
+                        logs.push((data, meta));
+                    }
+                }
+                cursor = Some(height.to_string());
+            }
+
+            if !page.has_next_page || page.results.is_empty() {
+                return Ok(logs);
+            }
+        }
+    }
+}
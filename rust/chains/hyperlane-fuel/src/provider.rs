@@ -1,43 +1,219 @@
-use async_trait::async_trait;
+use std::str::FromStr;
 
+use async_trait::async_trait;
+use fuels::{
+    client::{
+        schema::block::Block,
+        types::{TransactionResponse, TransactionStatus},
+        FuelClient,
+    },
+    prelude::{Bech32Address, Provider, BASE_ASSET_ID},
+    tx::{field, Input, Receipt, ScriptExecutionResult, Transaction},
+};
 use hyperlane_core::{
-    BlockInfo, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider, TxnInfo,
-    H256, U256,
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, TxnInfo, TxnReceiptInfo, H256, U256,
 };
 
+use crate::{conversions::*, make_provider, ConnectionConf, HyperlaneFuelError};
+
 /// A wrapper around a fuel provider to get generic blockchain information.
-#[derive(Debug)]
-pub struct FuelProvider {}
+#[derive(Debug, Clone)]
+pub struct FuelProvider {
+    domain: HyperlaneDomain,
+    provider: Provider,
+}
+
+impl FuelProvider {
+    /// Create a provider connected to the GraphQL API of `conf`
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf) -> ChainResult<Self> {
+        Ok(Self {
+            domain,
+            provider: make_provider(conf)?,
+        })
+    }
+
+    /// The underlying fuels provider
+    pub(crate) fn fuels_provider(&self) -> &Provider {
+        &self.provider
+    }
+
+    pub(crate) fn client(&self) -> &FuelClient {
+        &self.provider.client
+    }
+
+    /// Height of the latest block. Blocks are produced by a single node, so
+    /// they're final as soon as they're produced.
+    pub(crate) async fn block_number(&self) -> ChainResult<u32> {
+        let info = self
+            .client()
+            .chain_info()
+            .await
+            .map_err(HyperlaneFuelError::from)?;
+        Ok(info.latest_block.header.height.0 as u32)
+    }
+
+    /// The lowest gas price the node accepts
+    pub(crate) async fn min_gas_price(&self) -> ChainResult<u64> {
+        let info = self
+            .client()
+            .node_info()
+            .await
+            .map_err(HyperlaneFuelError::from)?;
+        Ok(info.min_gas_price.0)
+    }
+
+    /// The receipts of a transaction, empty if it isn't executed yet
+    pub(crate) async fn receipts(&self, id: H256) -> ChainResult<Vec<Receipt>> {
+        self.client()
+            .receipts(&format!("{id:?}"))
+            .await
+            .map_err(|err| HyperlaneFuelError::from(err).into())
+    }
+
+    async fn transaction(&self, id: H256) -> ChainResult<Option<TransactionResponse>> {
+        self.client()
+            .transaction(&format!("{id:?}"))
+            .await
+            .map_err(|err| HyperlaneFuelError::from(err).into())
+    }
+}
 
 impl HyperlaneChain for FuelProvider {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        &self.domain
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.clone())
     }
 }
 
 #[async_trait]
 impl HyperlaneProvider for FuelProvider {
     async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
-        todo!()
+        let block = self
+            .client()
+            .block(&format!("{hash:?}"))
+            .await
+            .map_err(HyperlaneFuelError::from)?
+            .ok_or(ChainCommunicationError::BlockNotFound(*hash))?;
+        block_info(&block)
     }
 
     async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
-        todo!()
+        let response = self
+            .transaction(*hash)
+            .await?
+            .ok_or_else(|| HyperlaneFuelError::TransactionNotFound(format!("{hash:?}")))?;
+        let Transaction::Script(script) = response.transaction else {
+            return Err(HyperlaneFuelError::NotScript(format!("{hash:?}")).into());
+        };
+        let gas_price = U256::from(*field::GasPrice::gas_price(&script));
+        let gas_limit = U256::from(*field::GasLimit::gas_limit(&script));
+        // Transactions spend coins rather than coming from an account, the
+        // owner of the first coin is taken as the sender
+        let sender = field::Inputs::inputs(&script)
+            .iter()
+            .find_map(|input| match input {
+                Input::CoinSigned { owner, .. } | Input::CoinPredicate { owner, .. } => {
+                    Some(H256::from(**owner))
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+        let recipient = field::Inputs::inputs(&script)
+            .iter()
+            .find_map(|input| match input {
+                Input::Contract { contract_id, .. } => Some(contract_id.into_h256()),
+                _ => None,
+            });
+        let receipt = match response.status {
+            TransactionStatus::Success { .. } | TransactionStatus::Failure { .. } => {
+                let gas_used = self
+                    .receipts(*hash)
+                    .await?
+                    .iter()
+                    .find_map(|receipt| match receipt {
+                        Receipt::ScriptResult { gas_used, .. } => Some(U256::from(*gas_used)),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                Some(TxnReceiptInfo {
+                    gas_used,
+                    cumulative_gas_used: gas_used,
+                    effective_gas_price: Some(gas_price),
+                })
+            }
+            _ => None,
+        };
+
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit,
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: Some(gas_price),
+            nonce: 0,
+            sender,
+            recipient,
+            receipt,
+        })
     }
 
     async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
-        todo!()
+        let contract = self
+            .client()
+            .contract(&format!("{address:?}"))
+            .await
+            .map_err(HyperlaneFuelError::from)?;
+        Ok(contract.is_some())
     }
 
     async fn get_balance(&self, address: String) -> ChainResult<U256> {
-        todo!()
+        let address = Bech32Address::from_str(&address)
+            .or_else(|_| H256::from_str(&address).map(|address| Bech32Address::from_h256(&address)))
+            .map_err(|_| HyperlaneFuelError::UnexpectedValue(address.clone()))?;
+        let balance = self
+            .provider
+            .get_asset_balance(&address, BASE_ASSET_ID)
+            .await
+            .map_err(HyperlaneFuelError::from)?;
+        Ok(balance.into())
     }
 
     async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
-        Ok(None)
+        let info = self
+            .client()
+            .chain_info()
+            .await
+            .map_err(HyperlaneFuelError::from)?;
+        Ok(Some(ChainInfo::new(block_info(&info.latest_block)?, None)))
     }
 }
+
+pub(crate) fn block_info(block: &Block) -> ChainResult<BlockInfo> {
+    Ok(BlockInfo {
+        hash: parse_hash(&block.id.to_string())?,
+        timestamp: block.header.time.0.to_unix() as u64,
+        number: block.header.height.0,
+    })
+}
+
+/// Whether a script executed successfully, going by its receipts
+pub(crate) fn script_succeeded(receipts: &[Receipt]) -> bool {
+    receipts.iter().any(|receipt| {
+        matches!(
+            receipt,
+            Receipt::ScriptResult {
+                result: ScriptExecutionResult::Success,
+                ..
+            }
+        )
+    })
+}
+
+/// A hash the GraphQL API writes as a hex string
+pub(crate) fn parse_hash(value: &str) -> ChainResult<H256> {
+    H256::from_str(value).map_err(|_| HyperlaneFuelError::UnexpectedValue(value.to_owned()).into())
+}
@@ -6,29 +6,32 @@ use hyperlane_core::{
 };
 
 /// A reference to a RoutingIsm contract on some Fuel chain
+///
+/// Fuel doesn't support it yet, so it can't be built and the type has no
+/// values.
 #[derive(Debug)]
-pub struct FuelRoutingIsm {}
+pub enum FuelRoutingIsm {}
 
 impl HyperlaneContract for FuelRoutingIsm {
     fn address(&self) -> H256 {
-        todo!()
+        match *self {}
     }
 }
 
 impl HyperlaneChain for FuelRoutingIsm {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        match *self {}
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        match *self {}
     }
 }
 
 #[async_trait]
 impl RoutingIsm for FuelRoutingIsm {
     /// Returns the ism needed to verify message
-    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
-        todo!()
+    async fn route(&self, _message: &HyperlaneMessage) -> ChainResult<H256> {
+        match *self {}
     }
 }
@@ -1,6 +1,6 @@
 use hyperlane_core::ChainCommunicationError;
 use solana_client::client_error::ClientError;
-use solana_sdk::pubkey::ParsePubkeyError;
+use solana_sdk::{pubkey::ParsePubkeyError, transaction::TransactionError};
 
 /// Errors from the crates specific to the hyperlane-sealevel
 /// implementation.
@@ -14,6 +14,21 @@ pub enum HyperlaneSealevelError {
     /// ClientError error
     #[error("{0}")]
     ClientError(#[from] ClientError),
+    /// Request to the Jito block engine failed
+    #[error("{0}")]
+    JitoRequest(#[from] reqwest::Error),
+    /// Jito block engine returned an error
+    #[error("Jito block engine error: {0}")]
+    Jito(String),
+    /// Jito bundle didn't land in time
+    #[error("Jito bundle {0} didn't land")]
+    BundleNotLanded(String),
+    /// The transaction of a Jito bundle landed but failed
+    #[error("Jito bundle {0} landed but its transaction failed: {1}")]
+    BundleFailed(String, TransactionError),
+    /// Bincode error
+    #[error("{0}")]
+    Bincode(#[from] bincode::Error),
 }
 
 impl From<HyperlaneSealevelError> for ChainCommunicationError {
@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use hyperlane_core::ChainResult;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    system_instruction,
+    transaction::{self, Transaction},
+};
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+use crate::{error::HyperlaneSealevelError, JitoConf};
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    message: String,
+}
+
+/// Client of the JSON-RPC API of a Jito block engine
+#[derive(Debug)]
+pub(crate) struct JitoClient {
+    http: reqwest::Client,
+    conf: JitoConf,
+    /// The accounts tips can be paid to, fetched once
+    tip_accounts: OnceCell<Vec<Pubkey>>,
+}
+
+impl JitoClient {
+    pub fn new(conf: JitoConf) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            conf,
+            tip_accounts: OnceCell::new(),
+        }
+    }
+
+    /// How long to wait for a bundle to land
+    pub fn bundle_timeout(&self) -> Duration {
+        self.conf.bundle_timeout
+    }
+
+    async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> ChainResult<T> {
+        let response: JsonRpcResponse<T> = self
+            .http
+            .post(self.conf.url.clone())
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(HyperlaneSealevelError::from)?
+            .json()
+            .await
+            .map_err(HyperlaneSealevelError::from)?;
+        into_result(method, response)
+    }
+
+    async fn tip_accounts(&self) -> ChainResult<&[Pubkey]> {
+        let accounts = self
+            .tip_accounts
+            .get_or_try_init(|| async {
+                let accounts: Vec<String> = self.request("getTipAccounts", json!([])).await?;
+                accounts
+                    .iter()
+                    .map(|account| {
+                        account
+                            .parse()
+                            .map_err(|err| HyperlaneSealevelError::from(err).into())
+                    })
+                    .collect::<ChainResult<Vec<Pubkey>>>()
+            })
+            .await?;
+        Ok(accounts)
+    }
+
+    /// An instruction paying the configured tip from `payer`. The tip
+    /// account is picked by the blockhash of the transaction, spreading tips
+    /// over the accounts to reduce write contention on them.
+    pub async fn tip_instruction(
+        &self,
+        payer: &Pubkey,
+        blockhash: &Hash,
+    ) -> ChainResult<Instruction> {
+        let tip_account = tip_account(self.tip_accounts().await?, blockhash)
+            .ok_or_else(|| HyperlaneSealevelError::Jito("No tip accounts".into()))?;
+        Ok(system_instruction::transfer(
+            payer,
+            tip_account,
+            self.conf.tip_lamports,
+        ))
+    }
+
+    /// Send the transactions as a bundle, returning its id
+    pub async fn send_bundle(&self, transactions: &[Transaction]) -> ChainResult<String> {
+        let encoded = transactions
+            .iter()
+            .map(|transaction| {
+                bincode::serialize(transaction)
+                    .map(|bytes| bs58::encode(bytes).into_string())
+                    .map_err(|err| HyperlaneSealevelError::from(err).into())
+            })
+            .collect::<ChainResult<Vec<_>>>()?;
+        let bundle_id: String = self.request("sendBundle", json!([encoded])).await?;
+        debug!(bundle_id, "Sent Jito bundle");
+        Ok(bundle_id)
+    }
+}
+
+/// The result of a JSON-RPC response, or the error it carries instead
+fn into_result<T>(method: &str, response: JsonRpcResponse<T>) -> ChainResult<T> {
+    match response {
+        JsonRpcResponse {
+            result: Some(result),
+            ..
+        } => Ok(result),
+        JsonRpcResponse {
+            error: Some(error), ..
+        } => Err(HyperlaneSealevelError::Jito(error.message).into()),
+        _ => Err(HyperlaneSealevelError::Jito(format!("Empty response to {method}")).into()),
+    }
+}
+
+/// The tip account picked for a transaction with `blockhash`, if any
+fn tip_account<'a>(accounts: &'a [Pubkey], blockhash: &Hash) -> Option<&'a Pubkey> {
+    if accounts.is_empty() {
+        return None;
+    }
+    accounts.get(blockhash.as_ref()[0] as usize % accounts.len())
+}
+
+/// Whether the transaction of a bundle landed, given its signature status.
+/// A transaction that landed but failed is an error, so the message it
+/// processes is resubmitted.
+pub(crate) fn bundle_landed(
+    bundle_id: &str,
+    status: Option<transaction::Result<()>>,
+) -> ChainResult<bool> {
+    match status {
+        Some(Ok(())) => Ok(true),
+        Some(Err(err)) => {
+            Err(HyperlaneSealevelError::BundleFailed(bundle_id.to_owned(), err).into())
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use solana_sdk::transaction::TransactionError;
+
+    use super::*;
+
+    #[test]
+    fn test_bundle_landed() {
+        assert!(bundle_landed("bundle", Some(Ok(()))).unwrap());
+        assert!(!bundle_landed("bundle", None).unwrap());
+        let err = bundle_landed("bundle", Some(Err(TransactionError::AccountInUse))).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Jito bundle bundle landed but its transaction failed"));
+    }
+
+    #[test]
+    fn test_into_result() {
+        let response: JsonRpcResponse<String> =
+            serde_json::from_value(json!({ "jsonrpc": "2.0", "id": 1, "result": "id" })).unwrap();
+        assert_eq!(into_result("sendBundle", response).unwrap(), "id");
+
+        let response: JsonRpcResponse<String> = serde_json::from_value(
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32602, "message": "bad bundle" } }),
+        )
+        .unwrap();
+        assert!(into_result("sendBundle", response)
+            .unwrap_err()
+            .to_string()
+            .contains("bad bundle"));
+
+        let response: JsonRpcResponse<String> =
+            serde_json::from_value(json!({ "jsonrpc": "2.0", "id": 1 })).unwrap();
+        assert!(into_result("sendBundle", response)
+            .unwrap_err()
+            .to_string()
+            .contains("Empty response to sendBundle"));
+    }
+
+    #[test]
+    fn test_tip_account() {
+        let accounts = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        assert_eq!(tip_account(&[], &Hash::new_unique()), None);
+        assert_eq!(
+            tip_account(&accounts, &Hash::new(&[4; 32])),
+            Some(&accounts[1])
+        );
+        assert_eq!(
+            tip_account(&accounts, &Hash::new(&[0; 32])),
+            Some(&accounts[0])
+        );
+    }
+}
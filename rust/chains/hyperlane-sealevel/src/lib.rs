@@ -18,6 +18,7 @@ pub use validator_announce::*;
 mod error;
 mod interchain_gas;
 mod interchain_security_module;
+mod jito;
 mod mailbox;
 mod merkle_tree_hook;
 mod multisig_ism;
@@ -1,6 +1,12 @@
 #![allow(warnings)] // FIXME remove
 
-use std::{collections::HashMap, num::NonZeroU64, ops::RangeInclusive, str::FromStr as _};
+use std::{
+    collections::HashMap,
+    num::NonZeroU64,
+    ops::RangeInclusive,
+    str::FromStr as _,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -26,6 +32,7 @@ use hyperlane_sealevel_mailbox::{
 use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
+use prometheus::IntCounterVec;
 use serializable_account_meta::SimulationReturnData;
 use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
 use solana_client::{
@@ -54,8 +61,12 @@ use solana_transaction_status::{
 
 use crate::RpcClientWithDebug;
 use crate::{
-    utils::{get_account_metas, get_finalized_block_number, simulate_instruction},
-    ConnectionConf, SealevelProvider,
+    error::HyperlaneSealevelError,
+    jito::{bundle_landed, JitoClient},
+    utils::{
+        get_account_metas, get_finalized_block_number, get_priority_fee, simulate_instruction,
+    },
+    ConnectionConf, PriorityFeeConf, SealevelProvider,
 };
 
 const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
@@ -65,6 +76,9 @@ const SPL_NOOP: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
 // TODO: consider a more sane value and/or use IGP gas payments instead.
 const PROCESS_COMPUTE_UNITS: u32 = 1_400_000;
 
+// How often to check whether a Jito bundle landed.
+const BUNDLE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 /// A reference to a Mailbox contract on some Sealevel chain
 pub struct SealevelMailbox {
     pub(crate) program_id: Pubkey,
@@ -72,6 +86,9 @@ pub struct SealevelMailbox {
     pub(crate) outbox: (Pubkey, u8),
     pub(crate) provider: SealevelProvider,
     payer: Option<Keypair>,
+    priority_fee: Option<PriorityFeeConf>,
+    jito: Option<JitoClient>,
+    submission_metrics: Option<IntCounterVec>,
 }
 
 impl SealevelMailbox {
@@ -98,9 +115,108 @@ impl SealevelMailbox {
             outbox,
             provider,
             payer,
+            priority_fee: conf.priority_fee.clone(),
+            jito: conf.jito.clone().map(JitoClient::new),
+            submission_metrics: None,
         })
     }
 
+    /// Count the process transactions submitted by this mailbox, labeled by
+    /// chain, path (`jito` or `rpc`) and status (`landed` or `failed`).
+    pub fn with_submission_metrics(mut self, metrics: IntCounterVec) -> Self {
+        self.submission_metrics = Some(metrics);
+        self
+    }
+
+    fn record_submission(&self, path: &str, status: &str) {
+        if let Some(metrics) = &self.submission_metrics {
+            metrics
+                .with_label_values(&[self.provider.domain().name(), path, status])
+                .inc();
+        }
+    }
+
+    /// Sends a transaction with `instructions` through the RPC, waiting for
+    /// it to be confirmed.
+    async fn send_with_rpc(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        commitment: CommitmentConfig,
+    ) -> ChainResult<Signature> {
+        let (recent_blockhash, _) = self
+            .rpc()
+            .get_latest_blockhash_with_commitment(commitment)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        let txn = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            recent_blockhash,
+        );
+
+        tracing::info!(?txn, "Created sealevel transaction to process message");
+
+        let result = self
+            .rpc()
+            .send_and_confirm_transaction(&txn)
+            .await
+            .map_err(ChainCommunicationError::from_other);
+        self.record_submission("rpc", if result.is_ok() { "landed" } else { "failed" });
+        let signature = result?;
+
+        tracing::info!(?txn, ?signature, "Sealevel transaction sent");
+        Ok(signature)
+    }
+
+    /// Sends a transaction with `instructions` and a tip as a Jito bundle,
+    /// waiting for it to land until the bundle timeout.
+    async fn send_with_jito(
+        &self,
+        jito: &JitoClient,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        commitment: CommitmentConfig,
+    ) -> ChainResult<Signature> {
+        let (recent_blockhash, _) = self
+            .rpc()
+            .get_latest_blockhash_with_commitment(commitment)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        let mut instructions = instructions.to_vec();
+        instructions.push(
+            jito.tip_instruction(&payer.pubkey(), &recent_blockhash)
+                .await?,
+        );
+        let txn = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            recent_blockhash,
+        );
+        let signature = txn.signatures[0];
+
+        let bundle_id = jito.send_bundle(&[txn]).await?;
+        tracing::info!(bundle_id, ?signature, "Sent Jito bundle to process message");
+
+        let start = Instant::now();
+        while start.elapsed() < jito.bundle_timeout() {
+            let status = self
+                .rpc()
+                .get_signature_status_with_commitment(&signature, commitment)
+                .await
+                .map_err(ChainCommunicationError::from_other)?;
+            if bundle_landed(&bundle_id, status)? {
+                return Ok(signature);
+            }
+            tokio::time::sleep(BUNDLE_POLL_INTERVAL).await;
+        }
+        Err(HyperlaneSealevelError::BundleNotLanded(bundle_id).into())
+    }
+
     pub fn inbox(&self) -> (Pubkey, u8) {
         self.inbox
     }
@@ -353,7 +469,7 @@ impl Mailbox for SealevelMailbox {
             .as_ref()
             .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
 
-        let mut instructions = Vec::with_capacity(2);
+        let mut instructions = Vec::with_capacity(3);
         // Set the compute unit limit.
         instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
             PROCESS_COMPUTE_UNITS,
@@ -435,28 +551,46 @@ impl Mailbox for SealevelMailbox {
             accounts,
         };
         instructions.push(inbox_instruction);
-        let (recent_blockhash, _) = self
-            .rpc()
-            .get_latest_blockhash_with_commitment(commitment)
-            .await
-            .map_err(ChainCommunicationError::from_other)?;
 
-        let txn = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&payer.pubkey()),
-            &[payer],
-            recent_blockhash,
-        );
-
-        tracing::info!(?txn, "Created sealevel transaction to process message");
-
-        let signature = self
-            .rpc()
-            .send_and_confirm_transaction(&txn)
-            .await
-            .map_err(ChainCommunicationError::from_other)?;
+        // Set the compute unit price, so that the transaction lands during congestion.
+        if let Some(priority_fee_conf) = &self.priority_fee {
+            let writable_accounts = [self.inbox.0, processed_message_account_key];
+            let micro_lamports =
+                get_priority_fee(&self.rpc(), &writable_accounts, priority_fee_conf).await?;
+            debug!(
+                micro_lamports,
+                "Setting priority fee for process transaction"
+            );
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                micro_lamports,
+            ));
+        }
 
-        tracing::info!(?txn, ?signature, "Sealevel transaction sent");
+        // Bundles that fail or don't land in time are resubmitted through the
+        // RPC. Should the bundle still land, the resubmission fails as the
+        // message was already processed.
+        let signature = match &self.jito {
+            Some(jito) => {
+                match self
+                    .send_with_jito(jito, &instructions, payer, commitment)
+                    .await
+                {
+                    Ok(signature) => {
+                        self.record_submission("jito", "landed");
+                        signature
+                    }
+                    Err(err) => {
+                        self.record_submission("jito", "failed");
+                        warn!(
+                            ?err,
+                            "Failed to land Jito bundle, falling back to RPC submission"
+                        );
+                        self.send_with_rpc(&instructions, payer, commitment).await?
+                    }
+                }
+            }
+            None => self.send_with_rpc(&instructions, payer, commitment).await?,
+        };
 
         let executed = self
             .rpc()
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use hyperlane_core::{config::OperationBatchConfig, ChainCommunicationError};
 use url::Url;
 
@@ -8,6 +10,57 @@ pub struct ConnectionConf {
     pub url: Url,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// Priority fee configuration for submitted transactions. If not
+    /// specified, transactions are sent without a priority fee.
+    pub priority_fee: Option<PriorityFeeConf>,
+    /// Jito configuration for submitting process transactions as bundles. If
+    /// not specified, transactions are only sent through the RPC.
+    pub jito: Option<JitoConf>,
+}
+
+/// Configuration for the compute unit price (priority fee) attached to
+/// transactions, determined from recently paid prioritization fees.
+#[derive(Debug, Clone)]
+pub struct PriorityFeeConf {
+    /// Percentile of the recent prioritization fees to pay, between 0 and 100.
+    pub percentile: u8,
+    /// Minimum compute unit price, in micro-lamports.
+    pub min_micro_lamports: u64,
+    /// Maximum compute unit price, in micro-lamports.
+    pub max_micro_lamports: u64,
+}
+
+impl Default for PriorityFeeConf {
+    fn default() -> Self {
+        Self {
+            percentile: 75,
+            min_micro_lamports: 0,
+            max_micro_lamports: 1_000_000,
+        }
+    }
+}
+
+/// Configuration for submitting transactions as Jito bundles, paying a tip
+/// to the block engine so they land during congestion. Bundles that fail or
+/// don't land in time are resubmitted through the RPC without a tip.
+#[derive(Debug, Clone)]
+pub struct JitoConf {
+    /// Url of the bundles endpoint of a Jito block engine, e.g.
+    /// `https://mainnet.block-engine.jito.wtf/api/v1/bundles`
+    pub url: Url,
+    /// Tip paid to Jito for each bundle, in lamports.
+    pub tip_lamports: u64,
+    /// How long to wait for a bundle to land before falling back to the RPC.
+    pub bundle_timeout: Duration,
+}
+
+impl JitoConf {
+    /// The smallest tip Jito accepts, in lamports.
+    pub const MIN_TIP_LAMPORTS: u64 = 1_000;
+    /// The tip paid if not specified, in lamports.
+    pub const DEFAULT_TIP_LAMPORTS: u64 = 10_000;
+    /// How long to wait for a bundle to land if not specified.
+    pub const DEFAULT_BUNDLE_TIMEOUT: Duration = Duration::from_secs(30);
 }
 
 /// An error type when parsing a connection configuration.
@@ -8,12 +8,13 @@ use solana_sdk::{
     commitment_config::CommitmentConfig,
     instruction::{AccountMeta, Instruction},
     message::Message,
+    pubkey::Pubkey,
     signature::{Keypair, Signer},
     transaction::Transaction,
 };
 use solana_transaction_status::UiReturnDataEncoding;
 
-use crate::client::RpcClientWithDebug;
+use crate::{client::RpcClientWithDebug, PriorityFeeConf};
 
 /// Simulates an instruction, and attempts to deserialize it into a T.
 /// If no return data at all was returned, returns Ok(None).
@@ -91,3 +92,49 @@ pub async fn get_finalized_block_number(rpc_client: &RpcClientWithDebug) -> Chai
         .expect("sealevel block height exceeds u32::MAX");
     Ok(height)
 }
+
+/// Determines the compute unit price (in micro-lamports) to attach to a transaction
+/// writing to `writable_accounts`, based on the fees recently paid to write to them.
+pub async fn get_priority_fee(
+    rpc_client: &RpcClient,
+    writable_accounts: &[Pubkey],
+    conf: &PriorityFeeConf,
+) -> ChainResult<u64> {
+    let mut recent_fees = rpc_client
+        .get_recent_prioritization_fees(writable_accounts)
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect::<Vec<_>>();
+    Ok(fee_percentile(&mut recent_fees, conf.percentile)
+        .max(conf.min_micro_lamports)
+        .min(conf.max_micro_lamports))
+}
+
+/// Returns the fee at `percentile` of `fees`, or zero if there are none.
+fn fee_percentile(fees: &mut [u64], percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    let percentile = percentile.min(100) as usize;
+    let index = (fees.len() - 1) * percentile / 100;
+    fees[index]
+}
+
+#[cfg(test)]
+mod test {
+    use super::fee_percentile;
+
+    #[test]
+    fn test_fee_percentile() {
+        assert_eq!(fee_percentile(&mut [], 75), 0);
+        let mut fees = [50, 0, 10, 40, 20, 30];
+        assert_eq!(fee_percentile(&mut fees, 0), 0);
+        assert_eq!(fee_percentile(&mut fees, 50), 20);
+        assert_eq!(fee_percentile(&mut fees, 100), 50);
+        // Percentiles above 100 are capped
+        assert_eq!(fee_percentile(&mut fees, 200), 50);
+    }
+}
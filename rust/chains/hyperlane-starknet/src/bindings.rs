@@ -0,0 +1,235 @@
+//! Bindings of the Cairo contracts: the selectors of their functions and
+//! events, and the serialization of their arguments, results and event data.
+//! Event fields are all in the data of the event, the first key being the
+//! selector of the event's name.
+
+use hyperlane_core::{
+    Announcement, ChainResult, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion,
+    ModuleType, SignedType, H256,
+};
+use num_traits::FromPrimitive;
+use starknet::{core::types::FieldElement, macros::selector};
+
+use crate::cairo::{
+    array_felts, byte_array_felts, bytes_felts, felt_from_h256, message_felts, u256_felts,
+    unexpected, FeltReader,
+};
+
+/// Read a single value out of the result of a call, rejecting leftovers
+fn decode<T>(
+    felts: &[FieldElement],
+    read: impl FnOnce(&mut FeltReader) -> ChainResult<T>,
+) -> ChainResult<T> {
+    let mut reader = FeltReader::new(felts);
+    let value = read(&mut reader)?;
+    if reader.felt().is_ok() {
+        return Err(unexpected(format!("Unexpected values in {felts:?}")).into());
+    }
+    Ok(value)
+}
+
+pub(crate) mod mailbox {
+    use super::*;
+
+    pub const NONCE: FieldElement = selector!("nonce");
+    pub const DELIVERED: FieldElement = selector!("delivered");
+    pub const GET_DEFAULT_ISM: FieldElement = selector!("get_default_ism");
+    pub const RECIPIENT_ISM: FieldElement = selector!("recipient_ism");
+    pub const PROCESS: FieldElement = selector!("process");
+
+    /// `Dispatch { sender: u256, destination_domain: u32, recipient_address:
+    /// u256, message: Message }`
+    pub const DISPATCH_EVENT: FieldElement = selector!("Dispatch");
+    /// `ProcessId { id: u256 }`
+    pub const PROCESS_ID_EVENT: FieldElement = selector!("ProcessId");
+
+    pub fn delivered_calldata(id: H256) -> Vec<FieldElement> {
+        u256_felts(id).to_vec()
+    }
+
+    pub fn recipient_ism_calldata(recipient: H256) -> Vec<FieldElement> {
+        u256_felts(recipient).to_vec()
+    }
+
+    pub fn process_calldata(message: &HyperlaneMessage, metadata: &[u8]) -> Vec<FieldElement> {
+        let mut calldata = bytes_felts(metadata);
+        calldata.extend(message_felts(message));
+        calldata
+    }
+
+    pub fn decode_nonce(felts: &[FieldElement]) -> ChainResult<u32> {
+        decode(felts, FeltReader::u32)
+    }
+
+    pub fn decode_delivered(felts: &[FieldElement]) -> ChainResult<bool> {
+        decode(felts, FeltReader::bool)
+    }
+
+    pub fn decode_ism(felts: &[FieldElement]) -> ChainResult<H256> {
+        decode(felts, FeltReader::h256)
+    }
+
+    pub fn decode_dispatch(data: &[FieldElement]) -> ChainResult<HyperlaneMessage> {
+        decode(data, |reader| {
+            reader.u256_h256()?;
+            reader.u32()?;
+            reader.u256_h256()?;
+            reader.message()
+        })
+    }
+
+    pub fn decode_process_id(data: &[FieldElement]) -> ChainResult<H256> {
+        decode(data, FeltReader::u256_h256)
+    }
+}
+
+pub(crate) mod merkle_tree_hook {
+    use super::*;
+
+    pub const COUNT: FieldElement = selector!("count");
+    pub const TREE: FieldElement = selector!("tree");
+
+    /// `InsertedIntoTree { id: u256, index: u32 }`
+    pub const INSERTED_INTO_TREE_EVENT: FieldElement = selector!("InsertedIntoTree");
+
+    pub fn decode_count(felts: &[FieldElement]) -> ChainResult<u32> {
+        decode(felts, FeltReader::u32)
+    }
+
+    /// `Tree { branch: Array<u256>, count: u256 }`
+    pub fn decode_tree(felts: &[FieldElement]) -> ChainResult<(Vec<H256>, usize)> {
+        decode(felts, |reader| {
+            let branch = reader.array(FeltReader::u256_h256)?;
+            let count = reader.u256()?;
+            if count > usize::MAX.into() {
+                return Err(unexpected(format!("Tree count {count} is too large")).into());
+            }
+            Ok((branch, count.as_usize()))
+        })
+    }
+
+    pub fn decode_inserted_into_tree(data: &[FieldElement]) -> ChainResult<MerkleTreeInsertion> {
+        decode(data, |reader| {
+            let id = reader.u256_h256()?;
+            let index = reader.u32()?;
+            Ok(MerkleTreeInsertion::new(index, id))
+        })
+    }
+}
+
+pub(crate) mod igp {
+    use super::*;
+
+    /// `GasPayment { message_id: u256, destination_domain: u32, gas_amount:
+    /// u256, payment: u256 }`
+    pub const GAS_PAYMENT_EVENT: FieldElement = selector!("GasPayment");
+
+    pub fn decode_gas_payment(data: &[FieldElement]) -> ChainResult<InterchainGasPayment> {
+        decode(data, |reader| {
+            Ok(InterchainGasPayment {
+                message_id: reader.u256_h256()?,
+                destination: reader.u32()?,
+                gas_amount: reader.u256()?,
+                payment: reader.u256()?,
+            })
+        })
+    }
+}
+
+pub(crate) mod ism {
+    use super::*;
+
+    pub const MODULE_TYPE: FieldElement = selector!("module_type");
+    pub const VERIFY: FieldElement = selector!("verify");
+    pub const VALIDATORS_AND_THRESHOLD: FieldElement = selector!("validators_and_threshold");
+    pub const ROUTE: FieldElement = selector!("route");
+
+    pub fn verify_calldata(message: &HyperlaneMessage, metadata: &[u8]) -> Vec<FieldElement> {
+        let mut calldata = bytes_felts(metadata);
+        calldata.extend(message_felts(message));
+        calldata
+    }
+
+    pub fn message_calldata(message: &HyperlaneMessage) -> Vec<FieldElement> {
+        message_felts(message)
+    }
+
+    /// The `ModuleType` enum, whose variants hold the address of the ISM
+    /// and are in the order of [`ModuleType`]
+    pub fn decode_module_type(felts: &[FieldElement]) -> ChainResult<Option<ModuleType>> {
+        decode(felts, |reader| {
+            let variant = reader.u64()?;
+            reader.felt()?;
+            Ok(ModuleType::from_u64(variant))
+        })
+    }
+
+    pub fn decode_verify(felts: &[FieldElement]) -> ChainResult<bool> {
+        decode(felts, FeltReader::bool)
+    }
+
+    /// `(Span<EthAddress>, u32)`
+    pub fn decode_validators_and_threshold(felts: &[FieldElement]) -> ChainResult<(Vec<H256>, u8)> {
+        decode(felts, |reader| {
+            let validators = reader.array(FeltReader::h256)?;
+            let threshold = reader.u32()?;
+            let threshold = threshold
+                .try_into()
+                .map_err(|_| unexpected(format!("Threshold {threshold} is too large")))?;
+            Ok((validators, threshold))
+        })
+    }
+
+    pub fn decode_route(felts: &[FieldElement]) -> ChainResult<H256> {
+        decode(felts, FeltReader::h256)
+    }
+}
+
+pub(crate) mod validator_announce {
+    use super::*;
+
+    pub const GET_ANNOUNCED_STORAGE_LOCATIONS: FieldElement =
+        selector!("get_announced_storage_locations");
+    pub const ANNOUNCE: FieldElement = selector!("announce");
+
+    pub fn get_announced_storage_locations_calldata(
+        validators: &[H256],
+    ) -> ChainResult<Vec<FieldElement>> {
+        let validators = validators
+            .iter()
+            .map(|validator| felt_from_h256(*validator))
+            .collect::<ChainResult<Vec<_>>>()?;
+        Ok(array_felts(validators.into_iter()))
+    }
+
+    /// `announce(validator: EthAddress, storage_location: ByteArray,
+    /// signature: Bytes)`
+    pub fn announce_calldata(
+        announcement: &SignedType<Announcement>,
+    ) -> ChainResult<Vec<FieldElement>> {
+        let mut validator = H256::zero();
+        validator.as_bytes_mut()[12..].copy_from_slice(announcement.value.validator.as_bytes());
+        let mut calldata = vec![felt_from_h256(validator)?];
+        calldata.extend(byte_array_felts(&announcement.value.storage_location));
+        calldata.extend(bytes_felts(&announcement.signature.to_vec()));
+        Ok(calldata)
+    }
+
+    /// `Span<Span<ByteArray>>`
+    pub fn decode_storage_locations(felts: &[FieldElement]) -> ChainResult<Vec<Vec<String>>> {
+        decode(felts, |reader| {
+            reader.array(|reader| reader.array(FeltReader::byte_array))
+        })
+    }
+}
+
+/// Balance of an account in an ERC20 token
+pub(crate) mod erc20 {
+    use super::*;
+
+    pub const BALANCE_OF: FieldElement = selector!("balanceOf");
+
+    pub fn decode_balance(felts: &[FieldElement]) -> ChainResult<hyperlane_core::U256> {
+        decode(felts, FeltReader::u256)
+    }
+}
@@ -0,0 +1,266 @@
+//! Serialization of Cairo values to and from field elements, the way
+//! contract calls take their arguments and return their results and events
+//! hold their data.
+
+use hyperlane_core::{ChainResult, HyperlaneMessage, H256, U256};
+use starknet::core::types::FieldElement;
+
+use crate::HyperlaneStarknetError;
+
+/// Bytes per word of a `Bytes` value
+const BYTES_WORD_SIZE: usize = 16;
+
+/// Bytes per full word of a `ByteArray` value
+const BYTE_ARRAY_WORD_SIZE: usize = 31;
+
+/// A 32 byte value that is a field element, such as an address
+pub(crate) fn felt_from_h256(value: H256) -> ChainResult<FieldElement> {
+    FieldElement::from_bytes_be(value.as_fixed_bytes())
+        .map_err(|_| HyperlaneStarknetError::FeltOverflow(format!("{value:?}")).into())
+}
+
+pub(crate) fn h256_from_felt(felt: FieldElement) -> H256 {
+    H256(felt.to_bytes_be())
+}
+
+/// A `u256`, which is serialized as its low and high 128 bits
+pub(crate) fn u256_felts(value: H256) -> [FieldElement; 2] {
+    let bytes = value.as_fixed_bytes();
+    let half = |range: std::ops::Range<usize>| {
+        let mut word = [0; 16];
+        word.copy_from_slice(&bytes[range]);
+        FieldElement::from(u128::from_be_bytes(word))
+    };
+    [half(16..32), half(0..16)]
+}
+
+/// A `Bytes`, which is serialized as its length and an array of 16 byte
+/// words. The last word holds the remaining bytes, right aligned.
+pub(crate) fn bytes_felts(bytes: &[u8]) -> Vec<FieldElement> {
+    let words = bytes.chunks(BYTES_WORD_SIZE).map(|chunk| {
+        let mut word = [0; 16];
+        word[BYTES_WORD_SIZE - chunk.len()..].copy_from_slice(chunk);
+        FieldElement::from(u128::from_be_bytes(word))
+    });
+    let mut felts = vec![(bytes.len() as u64).into()];
+    felts.push((((bytes.len() + BYTES_WORD_SIZE - 1) / BYTES_WORD_SIZE) as u64).into());
+    felts.extend(words);
+    felts
+}
+
+/// A `ByteArray`, Cairo's string, which is serialized as an array of 31
+/// byte words, a pending word with the remaining bytes and its length
+pub(crate) fn byte_array_felts(string: &str) -> Vec<FieldElement> {
+    let word = |chunk: &[u8]| {
+        let mut padded = [0; 32];
+        padded[32 - chunk.len()..].copy_from_slice(chunk);
+        // Fewer than 32 bytes always fit
+        FieldElement::from_bytes_be(&padded).expect("Valid field element")
+    };
+    let bytes = string.as_bytes();
+    let pending_len = bytes.len() % BYTE_ARRAY_WORD_SIZE;
+    let (full, pending) = bytes.split_at(bytes.len() - pending_len);
+    let mut felts = array_felts(full.chunks(BYTE_ARRAY_WORD_SIZE).map(word));
+    felts.push(word(pending));
+    felts.push((pending_len as u64).into());
+    felts
+}
+
+/// A `Message` struct
+pub(crate) fn message_felts(message: &HyperlaneMessage) -> Vec<FieldElement> {
+    let mut felts = vec![
+        message.version.into(),
+        message.nonce.into(),
+        message.origin.into(),
+    ];
+    felts.extend(u256_felts(message.sender));
+    felts.push(message.destination.into());
+    felts.extend(u256_felts(message.recipient));
+    felts.extend(bytes_felts(&message.body));
+    felts
+}
+
+/// An array of field elements, prefixed by its length
+pub(crate) fn array_felts(
+    elements: impl ExactSizeIterator<Item = FieldElement>,
+) -> Vec<FieldElement> {
+    let mut felts = vec![(elements.len() as u64).into()];
+    felts.extend(elements);
+    felts
+}
+
+/// Reads Cairo values from a sequence of field elements
+#[derive(Debug)]
+pub(crate) struct FeltReader<'a> {
+    felts: std::slice::Iter<'a, FieldElement>,
+}
+
+impl<'a> FeltReader<'a> {
+    pub fn new(felts: &'a [FieldElement]) -> Self {
+        Self {
+            felts: felts.iter(),
+        }
+    }
+
+    pub fn felt(&mut self) -> ChainResult<FieldElement> {
+        self.felts
+            .next()
+            .copied()
+            .ok_or_else(|| unexpected("Not enough values".into()).into())
+    }
+
+    pub fn u64(&mut self) -> ChainResult<u64> {
+        let felt = self.felt()?;
+        u64::try_from(felt).map_err(|_| unexpected(format!("{felt} is not a u64")).into())
+    }
+
+    pub fn u32(&mut self) -> ChainResult<u32> {
+        let value = self.u64()?;
+        u32::try_from(value).map_err(|_| unexpected(format!("{value} is not a u32")).into())
+    }
+
+    pub fn u8(&mut self) -> ChainResult<u8> {
+        let value = self.u64()?;
+        u8::try_from(value).map_err(|_| unexpected(format!("{value} is not a u8")).into())
+    }
+
+    pub fn u128(&mut self) -> ChainResult<u128> {
+        let felt = self.felt()?;
+        u128::try_from(felt).map_err(|_| unexpected(format!("{felt} is not a u128")).into())
+    }
+
+    pub fn bool(&mut self) -> ChainResult<bool> {
+        Ok(self.felt()? != FieldElement::ZERO)
+    }
+
+    /// An address or other value that fits in a field element
+    pub fn h256(&mut self) -> ChainResult<H256> {
+        Ok(h256_from_felt(self.felt()?))
+    }
+
+    /// A `u256` as 32 bytes, such as a message id
+    pub fn u256_h256(&mut self) -> ChainResult<H256> {
+        let low = self.u128()?;
+        let high = self.u128()?;
+        let mut bytes = [0; 32];
+        bytes[..16].copy_from_slice(&high.to_be_bytes());
+        bytes[16..].copy_from_slice(&low.to_be_bytes());
+        Ok(H256(bytes))
+    }
+
+    pub fn u256(&mut self) -> ChainResult<U256> {
+        Ok(U256::from_big_endian(self.u256_h256()?.as_bytes()))
+    }
+
+    /// An `Array` or `Span` of values read by `element`
+    pub fn array<T>(
+        &mut self,
+        mut element: impl FnMut(&mut Self) -> ChainResult<T>,
+    ) -> ChainResult<Vec<T>> {
+        let len = self.u64()?;
+        (0..len).map(|_| element(self)).collect()
+    }
+
+    /// A `Bytes`
+    pub fn bytes(&mut self) -> ChainResult<Vec<u8>> {
+        let size = self.u64()? as usize;
+        let words = self.array(Self::u128)?;
+        let mut bytes = Vec::with_capacity(size);
+        for (i, word) in words.iter().enumerate() {
+            let word_len = (size - (i * BYTES_WORD_SIZE).min(size)).min(BYTES_WORD_SIZE);
+            bytes.extend_from_slice(&word.to_be_bytes()[BYTES_WORD_SIZE - word_len..]);
+        }
+        if bytes.len() != size {
+            return Err(
+                unexpected(format!("Bytes of size {size} have {} words", words.len())).into(),
+            );
+        }
+        Ok(bytes)
+    }
+
+    /// A `ByteArray`, Cairo's string, which is serialized as an array of 31
+    /// byte words, a pending word and the length of the pending word
+    pub fn byte_array(&mut self) -> ChainResult<String> {
+        let words = self.array(Self::felt)?;
+        let pending = self.felt()?;
+        let pending_len = self.u64()? as usize;
+        if pending_len >= BYTE_ARRAY_WORD_SIZE {
+            return Err(unexpected(format!("Pending word of {pending_len} bytes")).into());
+        }
+
+        let mut bytes = vec![];
+        for word in words {
+            bytes.extend_from_slice(&word.to_bytes_be()[32 - BYTE_ARRAY_WORD_SIZE..]);
+        }
+        bytes.extend_from_slice(&pending.to_bytes_be()[32 - pending_len..]);
+        String::from_utf8(bytes).map_err(|err| unexpected(err.to_string()).into())
+    }
+
+    /// A `Message` struct
+    pub fn message(&mut self) -> ChainResult<HyperlaneMessage> {
+        Ok(HyperlaneMessage {
+            version: self.u8()?,
+            nonce: self.u32()?,
+            origin: self.u32()?,
+            sender: self.u256_h256()?,
+            destination: self.u32()?,
+            recipient: self.u256_h256()?,
+            body: self.bytes()?,
+        })
+    }
+}
+
+pub(crate) fn unexpected(message: String) -> HyperlaneStarknetError {
+    HyperlaneStarknetError::UnexpectedResponse(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_roundtrip() {
+        let message = HyperlaneMessage {
+            version: 3,
+            nonce: 7,
+            origin: 23448593,
+            sender: H256::repeat_byte(0xab),
+            destination: 1,
+            recipient: H256::from_low_u64_be(42),
+            body: (0..=40).collect(),
+        };
+        let felts = message_felts(&message);
+        let mut reader = FeltReader::new(&felts);
+        assert_eq!(reader.message().unwrap(), message);
+        assert!(reader.felt().is_err());
+    }
+
+    #[test]
+    fn test_bytes_pad_last_word_on_the_left() {
+        let felts = bytes_felts(&[1, 2, 3]);
+        assert_eq!(
+            felts,
+            vec![3u64.into(), 1u64.into(), FieldElement::from(0x010203u64)]
+        );
+        assert_eq!(FeltReader::new(&felts).bytes().unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_byte_array() {
+        let location = "s3://hyperlane-validator-signatures-starknet/us-east-1";
+        let bytes = location.as_bytes();
+        let word = |bytes: &[u8]| {
+            let mut padded = [0; 32];
+            padded[32 - bytes.len()..].copy_from_slice(bytes);
+            FieldElement::from_bytes_be(&padded).unwrap()
+        };
+        let felts = vec![
+            1u64.into(),
+            word(&bytes[..31]),
+            word(&bytes[31..]),
+            ((bytes.len() - 31) as u64).into(),
+        ];
+        assert_eq!(byte_array_felts(location), felts);
+        assert_eq!(FeltReader::new(&felts).byte_array().unwrap(), location);
+    }
+}
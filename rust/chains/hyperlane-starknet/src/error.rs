@@ -0,0 +1,31 @@
+use hyperlane_core::ChainCommunicationError;
+use starknet::providers::ProviderError;
+
+/// Errors from the crates specific to the hyperlane-starknet
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneStarknetError {
+    /// Error of a call to the node
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Error building, signing or submitting a transaction
+    #[error("Account error: {0}")]
+    Account(String),
+    /// A value doesn't fit in a field element
+    #[error("{0} doesn't fit in a field element")]
+    FeltOverflow(String),
+    /// A response didn't have the expected shape
+    #[error("Unexpected response from the node: {0}")]
+    UnexpectedResponse(String),
+    /// A transaction wasn't accepted in time
+    #[error("Transaction {0} wasn't accepted in time")]
+    TransactionTimeout(String),
+}
+
+impl From<HyperlaneStarknetError> for ChainCommunicationError {
+    fn from(value: HyperlaneStarknetError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use hyperlane_core::{ChainResult, LogMeta, H256};
+use starknet::{
+    core::types::{BlockId, EmittedEvent, EventFilter, FieldElement},
+    providers::Provider,
+};
+
+use crate::{
+    cairo::{felt_from_h256, h256_from_felt},
+    HyperlaneStarknetError, StarknetProvider,
+};
+
+/// Number of events to query at once
+const PAGE_SIZE: u64 = 100;
+
+/// Fetches the events of a type emitted by a contract
+#[derive(Debug, Clone)]
+pub(crate) struct EventFetcher {
+    provider: StarknetProvider,
+    contract: H256,
+    /// Selector of the name of the event
+    event: FieldElement,
+    /// Number of blocks behind the latest block to index up to
+    reorg_period: u32,
+}
+
+impl EventFetcher {
+    pub fn new(
+        provider: StarknetProvider,
+        contract: H256,
+        event: FieldElement,
+        reorg_period: u32,
+    ) -> Self {
+        Self {
+            provider,
+            contract,
+            event,
+            reorg_period,
+        }
+    }
+
+    /// The latest block considered final
+    pub async fn finalized_block_number(&self) -> ChainResult<u32> {
+        let latest = self.provider.block_number().await?;
+        Ok(latest.saturating_sub(self.reorg_period))
+    }
+
+    /// The data of the events emitted in the blocks of `range`, in order,
+    /// with where they were emitted. The node returns them in pages, each
+    /// with a continuation token to fetch the next.
+    pub async fn fetch(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Vec<FieldElement>, LogMeta)>> {
+        let client = self.provider.client();
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number((*range.start()).into())),
+            to_block: Some(BlockId::Number((*range.end()).into())),
+            address: Some(felt_from_h256(self.contract)?),
+            keys: Some(vec![vec![self.event]]),
+        };
+
+        let mut events = vec![];
+        let mut continuation_token = None;
+        loop {
+            let page = client
+                .get_events(filter.clone(), continuation_token, PAGE_SIZE)
+                .await
+                .map_err(HyperlaneStarknetError::from)?;
+            events.extend(page.events);
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        let mut transaction_indices = HashMap::new();
+        let mut log_indices = HashMap::<FieldElement, u64>::new();
+        let mut fetched = Vec::with_capacity(events.len());
+        for event in events {
+            let transaction_index = match transaction_indices.get(&event.transaction_hash) {
+                Some(index) => *index,
+                None => {
+                    let index = self
+                        .provider
+                        .transaction_index(event.block_hash, event.transaction_hash)
+                        .await?;
+                    transaction_indices.insert(event.transaction_hash, index);
+                    index
+                }
+            };
+            // Only the events of this type are known, so they're numbered
+            // among themselves within their transaction
+            let log_index = log_indices.entry(event.transaction_hash).or_default();
+            let meta = self.log_meta(&event, transaction_index, *log_index);
+            *log_index += 1;
+            fetched.push((event.data, meta));
+        }
+        Ok(fetched)
+    }
+
+    fn log_meta(&self, event: &EmittedEvent, transaction_index: u64, log_index: u64) -> LogMeta {
+        LogMeta {
+            address: self.contract,
+            block_number: event.block_number,
+            block_hash: h256_from_felt(event.block_hash),
+            transaction_id: h256_from_felt(event.transaction_hash).into(),
+            transaction_index,
+            log_index: log_index.into(),
+        }
+    }
+}
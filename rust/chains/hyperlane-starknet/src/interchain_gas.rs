@@ -0,0 +1,98 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, Indexed, Indexer, InterchainGasPaymaster, InterchainGasPayment, LogMeta,
+    SequenceAwareIndexer, H256,
+};
+use tracing::instrument;
+
+use crate::{bindings::igp, events::EventFetcher, ConnectionConf, StarknetProvider};
+
+/// A reference to an IGP contract on some StarkNet chain
+#[derive(Debug)]
+pub struct StarknetInterchainGasPaymaster {
+    provider: StarknetProvider,
+    address: H256,
+}
+
+impl StarknetInterchainGasPaymaster {
+    /// Create a new StarkNet IGP.
+    pub fn new(conf: &ConnectionConf, locator: &ContractLocator) -> Self {
+        Self {
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for StarknetInterchainGasPaymaster {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetInterchainGasPaymaster {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+impl InterchainGasPaymaster for StarknetInterchainGasPaymaster {}
+
+/// Struct that retrieves event data for a StarkNet IGP contract
+#[derive(Debug)]
+pub struct StarknetInterchainGasPaymasterIndexer {
+    payments: EventFetcher,
+}
+
+impl StarknetInterchainGasPaymasterIndexer {
+    /// Create a new StarkNet IGP indexer.
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, reorg_period: u32) -> Self {
+        let provider = StarknetProvider::new(locator.domain.clone(), conf, None);
+        Self {
+            payments: EventFetcher::new(
+                provider,
+                locator.address,
+                igp::GAS_PAYMENT_EVENT,
+                reorg_period,
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl Indexer<InterchainGasPayment> for StarknetInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        self.payments
+            .fetch(range)
+            .await?
+            .into_iter()
+            .map(|(data, meta)| Ok((igp::decode_gas_payment(&data)?.into(), meta)))
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.payments.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for StarknetInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.payments.finalized_block_number().await?;
+
+        // No sequence for gas payments.
+        Ok((None, tip))
+    }
+}
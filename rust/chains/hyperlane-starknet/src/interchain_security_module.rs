@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType, H256, U256,
+};
+use tracing::{instrument, warn};
+
+use crate::{bindings::ism, ConnectionConf, StarknetProvider};
+
+/// A reference to an InterchainSecurityModule contract on some StarkNet chain
+#[derive(Debug)]
+pub struct StarknetInterchainSecurityModule {
+    provider: StarknetProvider,
+    address: H256,
+}
+
+impl StarknetInterchainSecurityModule {
+    /// Create a new StarkNet InterchainSecurityModule
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for StarknetInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for StarknetInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let module = self
+            .provider
+            .call(self.address, ism::MODULE_TYPE, vec![])
+            .await?;
+
+        if let Some(module_type) = ism::decode_module_type(&module)? {
+            Ok(module_type)
+        } else {
+            warn!(?module, "Unknown module type");
+            Ok(ModuleType::Unused)
+        }
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        let verified = self
+            .provider
+            .call(
+                self.address,
+                ism::VERIFY,
+                ism::verify_calldata(message, metadata),
+            )
+            .await?;
+        // Calls aren't metered, so there's no gas to report
+        Ok(ism::decode_verify(&verified)?.then(U256::zero))
+    }
+}
@@ -0,0 +1,31 @@
+//! Implementation of hyperlane for StarkNet.
+//!
+//! Talks to the JSON-RPC API of a StarkNet node through starknet-rs. The
+//! Cairo contracts are called through the hand written bindings of
+//! [`bindings`], and their events are indexed by block range, paging
+//! through the results with the node's continuation tokens.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+#![deny(warnings)]
+
+pub use self::{
+    error::*, interchain_gas::*, interchain_security_module::*, mailbox::*, merkle_tree_hook::*,
+    multisig_ism::*, provider::*, routing_ism::*, signers::*, trait_builder::*,
+    validator_announce::*,
+};
+
+mod bindings;
+mod cairo;
+mod error;
+mod events;
+mod interchain_gas;
+mod interchain_security_module;
+mod mailbox;
+mod merkle_tree_hook;
+mod multisig_ism;
+mod provider;
+mod routing_ism;
+mod signers;
+mod trait_builder;
+mod validator_announce;
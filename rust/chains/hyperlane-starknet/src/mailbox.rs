@@ -0,0 +1,231 @@
+use std::{num::NonZeroU64, ops::RangeInclusive};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, SequenceAwareIndexer,
+    TxCostEstimate, TxOutcome, H256, U256,
+};
+use starknet::core::types::{BlockId, BlockTag};
+use tracing::instrument;
+
+use crate::{bindings::mailbox, events::EventFetcher, ConnectionConf, Signer, StarknetProvider};
+
+/// A reference to a Mailbox contract on some StarkNet chain
+#[derive(Debug)]
+pub struct StarknetMailbox {
+    provider: StarknetProvider,
+    address: H256,
+}
+
+impl StarknetMailbox {
+    /// Create a new StarkNet mailbox
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, signer: Option<Signer>) -> Self {
+        Self {
+            provider: StarknetProvider::new(locator.domain.clone(), conf, signer),
+            address: locator.address,
+        }
+    }
+
+    async fn nonce_at(&self, block: BlockId) -> ChainResult<u32> {
+        let nonce = self
+            .provider
+            .call_at(self.address, mailbox::NONCE, vec![], block)
+            .await?;
+        mailbox::decode_nonce(&nonce)
+    }
+}
+
+impl HyperlaneContract for StarknetMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for StarknetMailbox {
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        let block = match lag {
+            Some(lag) => {
+                let latest = self.provider.block_number().await?;
+                BlockId::Number(u64::from(latest).saturating_sub(lag.get()))
+            }
+            None => BlockId::Tag(BlockTag::Latest),
+        };
+        self.nonce_at(block).await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let delivered = self
+            .provider
+            .call(
+                self.address,
+                mailbox::DELIVERED,
+                mailbox::delivered_calldata(id),
+            )
+            .await?;
+        mailbox::decode_delivered(&delivered)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .call(self.address, mailbox::GET_DEFAULT_ISM, vec![])
+            .await?;
+        mailbox::decode_ism(&ism)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .call(
+                self.address,
+                mailbox::RECIPIENT_ISM,
+                mailbox::recipient_ism_calldata(recipient),
+            )
+            .await?;
+        mailbox::decode_ism(&ism)
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        self.provider
+            .invoke(
+                self.address,
+                mailbox::PROCESS,
+                mailbox::process_calldata(message, metadata),
+                tx_gas_limit,
+            )
+            .await
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        self.provider
+            .estimate_invoke(
+                self.address,
+                mailbox::PROCESS,
+                mailbox::process_calldata(message, metadata),
+            )
+            .await
+    }
+
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        mailbox::process_calldata(message, metadata)
+            .iter()
+            .flat_map(|felt| felt.to_bytes_be())
+            .collect()
+    }
+}
+
+/// Struct that retrieves event data for a StarkNet Mailbox contract
+#[derive(Debug)]
+pub struct StarknetMailboxIndexer {
+    mailbox: StarknetMailbox,
+    dispatches: EventFetcher,
+    processes: EventFetcher,
+}
+
+impl StarknetMailboxIndexer {
+    /// Create a new StarkNet mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, reorg_period: u32) -> Self {
+        let mailbox = StarknetMailbox::new(conf, locator, None);
+        let fetcher = |event| {
+            EventFetcher::new(
+                mailbox.provider.clone(),
+                mailbox.address,
+                event,
+                reorg_period,
+            )
+        };
+        Self {
+            dispatches: fetcher(mailbox::DISPATCH_EVENT),
+            processes: fetcher(mailbox::PROCESS_ID_EVENT),
+            mailbox,
+        }
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for StarknetMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        self.dispatches
+            .fetch(range)
+            .await?
+            .into_iter()
+            .map(|(data, meta)| Ok((mailbox::decode_dispatch(&data)?.into(), meta)))
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.dispatches.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for StarknetMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.dispatches.finalized_block_number().await?;
+        let count = self.mailbox.nonce_at(BlockId::Number(tip.into())).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for StarknetMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        self.processes
+            .fetch(range)
+            .await?
+            .into_iter()
+            .map(|(data, meta)| Ok((Indexed::new(mailbox::decode_process_id(&data)?), meta)))
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.processes.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for StarknetMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.processes.finalized_block_number().await?;
+
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
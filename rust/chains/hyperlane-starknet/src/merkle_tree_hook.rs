@@ -0,0 +1,164 @@
+use std::{num::NonZeroU64, ops::RangeInclusive};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    accumulator::{incremental::IncrementalMerkle, TREE_DEPTH},
+    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta,
+    MerkleTreeHook, MerkleTreeInsertion, SequenceAwareIndexer, H256,
+};
+use starknet::core::types::{BlockId, BlockTag};
+use tracing::instrument;
+
+use crate::{bindings::merkle_tree_hook, events::EventFetcher, ConnectionConf, StarknetProvider};
+
+/// A reference to a MerkleTreeHook contract on some StarkNet chain
+#[derive(Debug)]
+pub struct StarknetMerkleTreeHook {
+    provider: StarknetProvider,
+    address: H256,
+}
+
+impl StarknetMerkleTreeHook {
+    /// Create a new StarkNet merkle tree hook
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+
+    async fn block_for_lag(&self, lag: Option<NonZeroU64>) -> ChainResult<BlockId> {
+        Ok(match lag {
+            Some(lag) => {
+                let latest = self.provider.block_number().await?;
+                BlockId::Number(u64::from(latest).saturating_sub(lag.get()))
+            }
+            None => BlockId::Tag(BlockTag::Latest),
+        })
+    }
+
+    async fn count_at(&self, block: BlockId) -> ChainResult<u32> {
+        let count = self
+            .provider
+            .call_at(self.address, merkle_tree_hook::COUNT, vec![], block)
+            .await?;
+        merkle_tree_hook::decode_count(&count)
+    }
+}
+
+impl HyperlaneContract for StarknetMerkleTreeHook {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetMerkleTreeHook {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl MerkleTreeHook for StarknetMerkleTreeHook {
+    #[instrument(err, ret, skip(self))]
+    async fn tree(&self, lag: Option<NonZeroU64>) -> ChainResult<IncrementalMerkle> {
+        let block = self.block_for_lag(lag).await?;
+        let tree = self
+            .provider
+            .call_at(self.address, merkle_tree_hook::TREE, vec![], block)
+            .await?;
+        let (branch, count) = merkle_tree_hook::decode_tree(&tree)?;
+        let branch: [H256; TREE_DEPTH] = branch.try_into().map_err(|_| {
+            ChainCommunicationError::from_other_str("Unexpected merkle tree branch length")
+        })?;
+        Ok(IncrementalMerkle::new(branch, count))
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        let block = self.block_for_lag(lag).await?;
+        self.count_at(block).await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn latest_checkpoint(&self, lag: Option<NonZeroU64>) -> ChainResult<Checkpoint> {
+        let tree = self.tree(lag).await?;
+
+        let count: u32 = tree
+            .count()
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        let index = count.checked_sub(1).ok_or_else(|| {
+            ChainCommunicationError::from_contract_error_str(
+                "Outbox is empty, cannot compute checkpoint",
+            )
+        })?;
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address,
+            mailbox_domain: self.domain().id(),
+            root: tree.root(),
+            index,
+        })
+    }
+}
+
+/// Struct that retrieves event data for a StarkNet merkle tree hook contract
+#[derive(Debug)]
+pub struct StarknetMerkleTreeHookIndexer {
+    hook: StarknetMerkleTreeHook,
+    insertions: EventFetcher,
+}
+
+impl StarknetMerkleTreeHookIndexer {
+    /// Create a new StarkNet merkle tree hook indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, reorg_period: u32) -> Self {
+        let hook = StarknetMerkleTreeHook::new(conf, locator);
+        let insertions = EventFetcher::new(
+            hook.provider.clone(),
+            hook.address,
+            merkle_tree_hook::INSERTED_INTO_TREE_EVENT,
+            reorg_period,
+        );
+        Self { hook, insertions }
+    }
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for StarknetMerkleTreeHookIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        self.insertions
+            .fetch(range)
+            .await?
+            .into_iter()
+            .map(|(data, meta)| {
+                Ok((
+                    merkle_tree_hook::decode_inserted_into_tree(&data)?.into(),
+                    meta,
+                ))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.insertions.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for StarknetMerkleTreeHookIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.insertions.finalized_block_number().await?;
+        let count = self.hook.count_at(BlockId::Number(tip.into())).await?;
+        Ok((Some(count), tip))
+    }
+}
@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+use tracing::instrument;
+
+use crate::{bindings::ism, ConnectionConf, StarknetProvider};
+
+/// A reference to a MultisigIsm contract on some StarkNet chain
+#[derive(Debug)]
+pub struct StarknetMultisigIsm {
+    provider: StarknetProvider,
+    address: H256,
+}
+
+impl StarknetMultisigIsm {
+    /// Create a new StarkNet MultisigIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for StarknetMultisigIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetMultisigIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for StarknetMultisigIsm {
+    /// Returns the validator and threshold needed to verify message
+    #[instrument(err, ret, skip(self))]
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let result = self
+            .provider
+            .call(
+                self.address,
+                ism::VALIDATORS_AND_THRESHOLD,
+                ism::message_calldata(message),
+            )
+            .await?;
+        ism::decode_validators_and_threshold(&result)
+    }
+}
@@ -0,0 +1,388 @@
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, TxCostEstimate, TxOutcome, TxnInfo, TxnReceiptInfo, H256, U256,
+};
+use starknet::{
+    accounts::{Account, Call, ExecutionEncoding, SingleOwnerAccount},
+    core::types::{
+        BlockId, BlockTag, BlockWithTxHashes, ExecutionResult, FieldElement, FunctionCall,
+        InvokeTransaction, MaybePendingBlockWithTxHashes, MaybePendingTransactionReceipt,
+        StarknetError, Transaction, TransactionReceipt,
+    },
+    macros::felt,
+    providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError},
+    signers::LocalWallet,
+};
+use tracing::{debug, info, instrument, warn};
+
+use crate::{
+    bindings::erc20,
+    cairo::{felt_from_h256, h256_from_felt, unexpected},
+    ConnectionConf, HyperlaneStarknetError, Signer,
+};
+
+/// The ETH token, which fees are paid in
+const FEE_TOKEN: FieldElement =
+    felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
+
+/// Margin on the estimated fee of a transaction, to absorb changes of the
+/// gas price until it's included
+const FEE_ESTIMATE_MULTIPLIER: f64 = 1.5;
+
+/// How often to poll for the receipt of a submitted transaction
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long to wait for a submitted transaction to be accepted
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+type StarknetAccount = SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>;
+
+/// Abstraction over a connection to a StarkNet node
+#[derive(Debug, Clone)]
+pub struct StarknetProvider {
+    domain: HyperlaneDomain,
+    conf: ConnectionConf,
+    signer: Option<Signer>,
+}
+
+impl StarknetProvider {
+    /// Create a provider connected to the node of `conf`, signing
+    /// transactions with `signer` if set
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            conf: conf.clone(),
+            signer,
+        }
+    }
+
+    /// A client of the node. Clients are cheap, and accounts need to own
+    /// theirs.
+    pub(crate) fn client(&self) -> JsonRpcClient<HttpTransport> {
+        JsonRpcClient::new(HttpTransport::new(self.conf.url.clone()))
+    }
+
+    /// Number of the latest block
+    pub(crate) async fn block_number(&self) -> ChainResult<u32> {
+        let number = self
+            .client()
+            .block_number()
+            .await
+            .map_err(HyperlaneStarknetError::from)?;
+        number
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// Call a view function of a contract at the latest block
+    pub(crate) async fn call(
+        &self,
+        contract: H256,
+        selector: FieldElement,
+        calldata: Vec<FieldElement>,
+    ) -> ChainResult<Vec<FieldElement>> {
+        self.call_at(contract, selector, calldata, BlockId::Tag(BlockTag::Latest))
+            .await
+    }
+
+    /// Call a view function of a contract at a block
+    pub(crate) async fn call_at(
+        &self,
+        contract: H256,
+        selector: FieldElement,
+        calldata: Vec<FieldElement>,
+        block: BlockId,
+    ) -> ChainResult<Vec<FieldElement>> {
+        let call = FunctionCall {
+            contract_address: felt_from_h256(contract)?,
+            entry_point_selector: selector,
+            calldata,
+        };
+        Ok(self
+            .client()
+            .call(call, block)
+            .await
+            .map_err(HyperlaneStarknetError::from)?)
+    }
+
+    /// The address of the account signing transactions, if any
+    pub(crate) fn signer_address(&self) -> Option<H256> {
+        self.signer.as_ref().map(Signer::address)
+    }
+
+    async fn account(&self) -> ChainResult<StarknetAccount> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        let chain_id = self
+            .client()
+            .chain_id()
+            .await
+            .map_err(HyperlaneStarknetError::from)?;
+        Ok(SingleOwnerAccount::new(
+            self.client(),
+            signer.wallet(),
+            signer.account_address(),
+            chain_id,
+            ExecutionEncoding::New,
+        ))
+    }
+
+    /// Invoke a function of a contract in a transaction and wait for it to
+    /// be accepted. Spends at most `gas_limit` gas if set, else a margin
+    /// over the estimated fee.
+    #[instrument(err, skip(self, calldata))]
+    pub(crate) async fn invoke(
+        &self,
+        contract: H256,
+        selector: FieldElement,
+        calldata: Vec<FieldElement>,
+        gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let account = self.account().await?;
+        let calls = vec![Call {
+            to: felt_from_h256(contract)?,
+            selector,
+            calldata,
+        }];
+        let estimate = account
+            .execute(calls.clone())
+            .estimate_fee()
+            .await
+            .map_err(|err| HyperlaneStarknetError::Account(err.to_string()))?;
+        let max_fee = match gas_limit {
+            Some(limit) => limit.saturating_mul(estimate.gas_price.into()),
+            None => U256::from((estimate.overall_fee as f64 * FEE_ESTIMATE_MULTIPLIER) as u64),
+        };
+        let max_fee = felt_from_h256(u256_to_h256(max_fee))?;
+
+        let result = account
+            .execute(calls)
+            .max_fee(max_fee)
+            .send()
+            .await
+            .map_err(|err| HyperlaneStarknetError::Account(err.to_string()))?;
+        let hash = result.transaction_hash;
+        info!(transaction_hash = %format!("{hash:#x}"), "Submitted transaction");
+
+        let (executed, actual_fee) = self.wait_for_receipt(hash).await?;
+        let gas_price = estimate.gas_price.max(1);
+        Ok(TxOutcome {
+            transaction_id: h256_from_felt(hash).into(),
+            executed,
+            gas_used: actual_fee / gas_price,
+            gas_price: U256::from(gas_price).try_into()?,
+        })
+    }
+
+    /// Poll for the receipt of a transaction until it's accepted, returning
+    /// whether it succeeded and the fee it paid
+    async fn wait_for_receipt(&self, hash: FieldElement) -> ChainResult<(bool, U256)> {
+        let client = self.client();
+        let start = Instant::now();
+        loop {
+            match client.get_transaction_receipt(hash).await {
+                Ok(MaybePendingTransactionReceipt::Receipt(TransactionReceipt::Invoke(
+                    receipt,
+                ))) => {
+                    let executed = match &receipt.execution_result {
+                        ExecutionResult::Succeeded => true,
+                        ExecutionResult::Reverted { reason } => {
+                            warn!(transaction_hash = %format!("{hash:#x}"), %reason, "Transaction reverted");
+                            false
+                        }
+                    };
+                    let fee = U256::from_big_endian(&receipt.actual_fee.to_bytes_be());
+                    return Ok((executed, fee));
+                }
+                Ok(MaybePendingTransactionReceipt::Receipt(receipt)) => {
+                    return Err(unexpected(format!("Not an invoke receipt: {receipt:?}")).into());
+                }
+                Ok(MaybePendingTransactionReceipt::PendingReceipt(_)) => {
+                    debug!("Transaction is pending");
+                }
+                Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {
+                    debug!("Transaction isn't known yet");
+                }
+                Err(err) => return Err(HyperlaneStarknetError::from(err).into()),
+            }
+            if start.elapsed() > RECEIPT_TIMEOUT {
+                return Err(
+                    HyperlaneStarknetError::TransactionTimeout(format!("{hash:#x}")).into(),
+                );
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Estimate the gas of a transaction invoking a function of a contract
+    #[instrument(err, skip(self, calldata))]
+    pub(crate) async fn estimate_invoke(
+        &self,
+        contract: H256,
+        selector: FieldElement,
+        calldata: Vec<FieldElement>,
+    ) -> ChainResult<TxCostEstimate> {
+        let account = self.account().await?;
+        let estimate = account
+            .execute(vec![Call {
+                to: felt_from_h256(contract)?,
+                selector,
+                calldata,
+            }])
+            .estimate_fee()
+            .await
+            .map_err(|err| HyperlaneStarknetError::Account(err.to_string()))?;
+        Ok(TxCostEstimate {
+            gas_limit: estimate.gas_consumed.into(),
+            gas_price: U256::from(estimate.gas_price).try_into()?,
+            l2_gas_limit: None,
+        })
+    }
+
+    /// Balance of an account in the fee token, in wei
+    pub(crate) async fn balance(&self, address: H256) -> ChainResult<U256> {
+        let balance = self
+            .call(
+                h256_from_felt(FEE_TOKEN),
+                erc20::BALANCE_OF,
+                vec![felt_from_h256(address)?],
+            )
+            .await?;
+        erc20::decode_balance(&balance)
+    }
+
+    async fn block(&self, id: BlockId) -> ChainResult<BlockWithTxHashes> {
+        let block = self
+            .client()
+            .get_block_with_tx_hashes(id)
+            .await
+            .map_err(HyperlaneStarknetError::from)?;
+        match block {
+            MaybePendingBlockWithTxHashes::Block(block) => Ok(block),
+            MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+                Err(unexpected(format!("Block {id:?} is pending")).into())
+            }
+        }
+    }
+
+    /// Index of a transaction in its block
+    pub(crate) async fn transaction_index(
+        &self,
+        block_hash: FieldElement,
+        transaction_hash: FieldElement,
+    ) -> ChainResult<u64> {
+        let block = self.block(BlockId::Hash(block_hash)).await?;
+        let index = block
+            .transactions
+            .iter()
+            .position(|hash| *hash == transaction_hash)
+            .ok_or_else(|| {
+                unexpected(format!(
+                    "Transaction {transaction_hash:#x} isn't in block {block_hash:#x}"
+                ))
+            })?;
+        Ok(index as u64)
+    }
+}
+
+impl HyperlaneChain for StarknetProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for StarknetProvider {
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        let block = self.block(BlockId::Hash(felt_from_h256(*hash)?)).await?;
+        Ok(block_info(&block))
+    }
+
+    /// Transactions bound the fee they pay rather than their gas, so fees are
+    /// reported as gas at a price of one wei
+    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
+        let client = self.client();
+        let felt = felt_from_h256(*hash)?;
+        let transaction = client
+            .get_transaction_by_hash(felt)
+            .await
+            .map_err(HyperlaneStarknetError::from)?;
+        let (sender, max_fee, nonce) = match transaction {
+            Transaction::Invoke(InvokeTransaction::V1(tx)) => {
+                (tx.sender_address, tx.max_fee, tx.nonce)
+            }
+            other => {
+                return Err(unexpected(format!("Not an invoke v1 transaction: {other:?}")).into())
+            }
+        };
+        let receipt = match client.get_transaction_receipt(felt).await {
+            Ok(MaybePendingTransactionReceipt::Receipt(TransactionReceipt::Invoke(receipt))) => {
+                let fee = U256::from_big_endian(&receipt.actual_fee.to_bytes_be());
+                Some(TxnReceiptInfo {
+                    gas_used: fee,
+                    cumulative_gas_used: fee,
+                    effective_gas_price: Some(U256::one()),
+                })
+            }
+            _ => None,
+        };
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit: U256::from_big_endian(&max_fee.to_bytes_be()),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: Some(U256::one()),
+            nonce: u64::try_from(nonce).map_err(ChainCommunicationError::from_other)?,
+            sender: h256_from_felt(sender),
+            recipient: None,
+            receipt,
+        })
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        let class_hash = self
+            .client()
+            .get_class_hash_at(BlockId::Tag(BlockTag::Latest), felt_from_h256(*address)?)
+            .await;
+        match class_hash {
+            Ok(_) => Ok(true),
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(false),
+            Err(err) => Err(HyperlaneStarknetError::from(err).into()),
+        }
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let address =
+            FieldElement::from_hex_be(&address).map_err(ChainCommunicationError::from_other)?;
+        self.balance(h256_from_felt(address)).await
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        let block = self.block(BlockId::Tag(BlockTag::Latest)).await?;
+        let gas_price = U256::from_big_endian(&block.l1_gas_price.price_in_wei.to_bytes_be());
+        Ok(Some(ChainInfo::new(block_info(&block), Some(gas_price))))
+    }
+}
+
+fn block_info(block: &BlockWithTxHashes) -> BlockInfo {
+    BlockInfo {
+        hash: h256_from_felt(block.block_hash),
+        timestamp: block.timestamp,
+        number: block.block_number,
+    }
+}
+
+fn u256_to_h256(value: U256) -> H256 {
+    let mut bytes = [0; 32];
+    value.to_big_endian(&mut bytes);
+    H256(bytes)
+}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, RoutingIsm, H256,
+};
+use tracing::instrument;
+
+use crate::{bindings::ism, ConnectionConf, StarknetProvider};
+
+/// A reference to a RoutingIsm contract on some StarkNet chain
+#[derive(Debug)]
+pub struct StarknetRoutingIsm {
+    provider: StarknetProvider,
+    address: H256,
+}
+
+impl StarknetRoutingIsm {
+    /// Create a new StarkNet RoutingIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for StarknetRoutingIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetRoutingIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for StarknetRoutingIsm {
+    #[instrument(err, ret, skip(self))]
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .call(self.address, ism::ROUTE, ism::message_calldata(message))
+            .await?;
+        ism::decode_route(&ism)
+    }
+}
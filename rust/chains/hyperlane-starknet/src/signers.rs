@@ -0,0 +1,54 @@
+use std::fmt::{Debug, Formatter};
+
+use hyperlane_core::{ChainResult, H256};
+use starknet::{
+    core::types::FieldElement,
+    signers::{LocalWallet, SigningKey},
+};
+
+use crate::cairo::{felt_from_h256, h256_from_felt};
+
+/// Signer of the transactions of a StarkNet account. Accounts are contracts,
+/// so the signer is made of the account's address and the Stark key the
+/// account validates signatures with.
+#[derive(Clone)]
+pub struct Signer {
+    key: FieldElement,
+    address: FieldElement,
+}
+
+impl Signer {
+    /// Create a signer of the account at `address` from its private key
+    pub fn new(private_key: H256, address: H256) -> ChainResult<Self> {
+        Ok(Self {
+            key: felt_from_h256(private_key)?,
+            address: felt_from_h256(address)?,
+        })
+    }
+
+    /// The address of the account
+    pub fn address(&self) -> H256 {
+        h256_from_felt(self.address)
+    }
+
+    /// The address of the account, formatted the way StarkNet does
+    pub fn address_string(&self) -> String {
+        format!("{:#066x}", self.address)
+    }
+
+    pub(crate) fn account_address(&self) -> FieldElement {
+        self.address
+    }
+
+    pub(crate) fn wallet(&self) -> LocalWallet {
+        LocalWallet::from(SigningKey::from_secret_scalar(self.key))
+    }
+}
+
+impl Debug for Signer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signer")
+            .field("address", &self.address_string())
+            .finish_non_exhaustive()
+    }
+}
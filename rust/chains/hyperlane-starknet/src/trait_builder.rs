@@ -0,0 +1,12 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// StarkNet connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Url of the JSON-RPC API of a node, e.g.
+    /// `https://starknet-mainnet.public.blastapi.io`
+    pub url: Url,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    Announcement, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, SignedType, TxOutcome, ValidatorAnnounce, H256, U256,
+};
+use tracing::{instrument, warn};
+
+use crate::{bindings::validator_announce, ConnectionConf, Signer, StarknetProvider};
+
+/// A reference to a ValidatorAnnounce contract on some StarkNet chain
+#[derive(Debug)]
+pub struct StarknetValidatorAnnounce {
+    provider: StarknetProvider,
+    address: H256,
+}
+
+impl StarknetValidatorAnnounce {
+    /// Create a new StarkNet ValidatorAnnounce
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, signer: Option<Signer>) -> Self {
+        Self {
+            provider: StarknetProvider::new(locator.domain.clone(), conf, signer),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for StarknetValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for StarknetValidatorAnnounce {
+    #[instrument(err, ret, skip(self))]
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        let locations = self
+            .provider
+            .call(
+                self.address,
+                validator_announce::GET_ANNOUNCED_STORAGE_LOCATIONS,
+                validator_announce::get_announced_storage_locations_calldata(validators)?,
+            )
+            .await?;
+        validator_announce::decode_storage_locations(&locations)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        self.provider
+            .invoke(
+                self.address,
+                validator_announce::ANNOUNCE,
+                validator_announce::announce_calldata(&announcement)?,
+                None,
+            )
+            .await
+    }
+
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        let Some(signer) = self.provider.signer_address() else {
+            warn!(?announcement, "Cannot announce without a signer");
+            return None;
+        };
+        let estimate = self
+            .provider
+            .estimate_invoke(
+                self.address,
+                validator_announce::ANNOUNCE,
+                validator_announce::announce_calldata(&announcement).ok()?,
+            )
+            .await
+            .ok()?;
+        let gas_price: U256 = estimate.gas_price.try_into().ok()?;
+        let fee = estimate.gas_limit.saturating_mul(gas_price);
+        let balance = self.provider.balance(signer).await.ok()?;
+        Some(fee.saturating_sub(balance))
+    }
+}
@@ -0,0 +1,190 @@
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    types::{
+        Balance, Checkpoint, DryRun, EventId, EventPage, ObjectResponse, RpcError,
+        TransactionBlock, TransactionBytes,
+    },
+    HyperlaneSuiError,
+};
+
+type Result<T> = std::result::Result<T, HyperlaneSuiError>;
+
+/// Coin type of the native token
+const SUI_COIN: &str = "0x2::sui::SUI";
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+/// Client of the JSON-RPC API of a Sui fullnode
+#[derive(Debug, Clone)]
+pub(crate) struct SuiClient {
+    http: Client,
+    url: Url,
+}
+
+impl SuiClient {
+    pub fn new(url: Url) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let response: RpcResponse<T> = self
+            .http
+            .post(self.url.clone())
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match (response.result, response.error) {
+            (_, Some(err)) => Err(HyperlaneSuiError::Rpc {
+                code: err.code,
+                message: err.message,
+            }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(HyperlaneSuiError::UnexpectedResponse(format!(
+                "`{method}` returned no result"
+            ))),
+        }
+    }
+
+    pub async fn latest_checkpoint(&self) -> Result<u64> {
+        let sequence: String = self
+            .call("sui_getLatestCheckpointSequenceNumber", json!([]))
+            .await?;
+        sequence
+            .parse()
+            .map_err(|_| HyperlaneSuiError::UnexpectedResponse(sequence))
+    }
+
+    /// A checkpoint by sequence number or base58 digest
+    pub async fn checkpoint(&self, id: String) -> Result<Checkpoint> {
+        self.call("sui_getCheckpoint", json!([id])).await
+    }
+
+    pub async fn object(&self, id: &str) -> Result<ObjectResponse> {
+        self.call(
+            "sui_getObject",
+            json!([id, { "showType": true, "showContent": true }]),
+        )
+        .await
+    }
+
+    /// The dynamic field `name` of type `name_type` of an object, such as an
+    /// entry of a `0x2::table::Table`
+    pub async fn dynamic_field(
+        &self,
+        parent: &str,
+        name_type: &str,
+        name: Value,
+    ) -> Result<ObjectResponse> {
+        self.call(
+            "suix_getDynamicFieldObject",
+            json!([parent, { "type": name_type, "value": name }]),
+        )
+        .await
+    }
+
+    /// Up to `limit` events of a Move event type, after `cursor` in the order
+    /// they were emitted
+    pub async fn query_events(
+        &self,
+        event_type: &str,
+        cursor: Option<&EventId>,
+        limit: u32,
+    ) -> Result<EventPage> {
+        self.call(
+            "suix_queryEvents",
+            json!([{ "MoveEventType": event_type }, cursor, limit, false]),
+        )
+        .await
+    }
+
+    pub async fn transaction(&self, digest: &str) -> Result<TransactionBlock> {
+        self.call(
+            "sui_getTransactionBlock",
+            json!([digest, { "showInput": true, "showEffects": true }]),
+        )
+        .await
+    }
+
+    pub async fn reference_gas_price(&self) -> Result<u64> {
+        let price: String = self.call("suix_getReferenceGasPrice", json!([])).await?;
+        price
+            .parse()
+            .map_err(|_| HyperlaneSuiError::UnexpectedResponse(price))
+    }
+
+    /// Total balance of the native token of an account, in MIST
+    pub async fn balance(&self, owner: &str) -> Result<u128> {
+        let balance: Balance = self
+            .call("suix_getBalance", json!([owner, SUI_COIN]))
+            .await?;
+        balance
+            .total_balance
+            .parse()
+            .map_err(|_| HyperlaneSuiError::UnexpectedResponse(balance.total_balance))
+    }
+
+    /// Build a transaction calling a Move function, paying for gas with a
+    /// coin of `signer` picked by the fullnode
+    pub async fn move_call(
+        &self,
+        signer: &str,
+        package: &str,
+        module: &str,
+        function: &str,
+        arguments: Vec<Value>,
+        gas_budget: u64,
+    ) -> Result<TransactionBytes> {
+        self.call(
+            "unsafe_moveCall",
+            json!([
+                signer,
+                package,
+                module,
+                function,
+                Vec::<String>::new(),
+                arguments,
+                Value::Null,
+                gas_budget.to_string(),
+            ]),
+        )
+        .await
+    }
+
+    pub async fn dry_run(&self, tx_bytes: &str) -> Result<DryRun> {
+        self.call("sui_dryRunTransactionBlock", json!([tx_bytes]))
+            .await
+    }
+
+    /// Execute a signed transaction and wait for it to be executed
+    pub async fn execute(&self, tx_bytes: &str, signature: &str) -> Result<TransactionBlock> {
+        self.call(
+            "sui_executeTransactionBlock",
+            json!([
+                tx_bytes,
+                [signature],
+                { "showInput": true, "showEffects": true },
+                "WaitForLocalExecution",
+            ]),
+        )
+        .await
+    }
+}
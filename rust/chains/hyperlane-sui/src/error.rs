@@ -0,0 +1,44 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-sui
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneSuiError {
+    /// Http client error
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// Error returned by the JSON-RPC API
+    #[error("Sui RPC error {code}: {message}")]
+    Rpc {
+        /// JSON-RPC error code
+        code: i64,
+        /// Message of the error
+        message: String,
+    },
+    /// Json (de)serialization error
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// Hex decoding error
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+    /// Base58 decoding error
+    #[error(transparent)]
+    Base58(#[from] bs58::decode::Error),
+    /// Base64 decoding error
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+    /// Invalid signing key
+    #[error(transparent)]
+    Signature(#[from] ed25519_dalek::SignatureError),
+    /// A response didn't have the expected shape
+    #[error("Unexpected response from the fullnode: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl From<HyperlaneSuiError> for ChainCommunicationError {
+    fn from(value: HyperlaneSuiError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    collections::BTreeMap,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+};
+
+use hyperlane_core::{ChainResult, LogMeta};
+
+use crate::{
+    object::ContractObject,
+    types::{Event, EventId},
+    utils::{parse_digest, unexpected},
+};
+
+/// Number of events to query at once
+const PAGE_SIZE: u32 = 50;
+
+/// The events of a type emitted by a module of a contract's package. Sui
+/// doesn't number events, so they are numbered by their position in the
+/// stream, which the indexer uses as their sequence.
+#[derive(Debug)]
+pub(crate) struct EventStream {
+    object: Arc<ContractObject>,
+    module: &'static str,
+    event: &'static str,
+    /// The cursors to query the events from a position onwards, cached to
+    /// avoid walking the stream from the start each time
+    cursors: Mutex<BTreeMap<u32, Option<EventId>>>,
+}
+
+impl EventStream {
+    pub fn new(object: Arc<ContractObject>, module: &'static str, event: &'static str) -> Self {
+        Self {
+            object,
+            module,
+            event,
+            cursors: Mutex::new(BTreeMap::from([(0, None)])),
+        }
+    }
+
+    /// The number of events emitted so far
+    pub async fn count(&self) -> ChainResult<u32> {
+        let (count, _) = self.walk(u32::MAX, None).await?;
+        Ok(count)
+    }
+
+    /// The events at positions in `range`, with their positions
+    pub async fn events(&self, range: RangeInclusive<u32>) -> ChainResult<Vec<(u32, Event)>> {
+        let (_, events) = self.walk(*range.start(), Some(*range.end())).await?;
+        Ok(events)
+    }
+
+    /// Walk the stream from the closest cached cursor before `start`,
+    /// collecting the events from `start` up to `end`, or to the end of the
+    /// stream if unset. Returns the position the walk stopped at.
+    async fn walk(&self, start: u32, end: Option<u32>) -> ChainResult<(u32, Vec<(u32, Event)>)> {
+        let (mut position, mut cursor) = {
+            let cursors = self.cursors.lock().unwrap();
+            cursors
+                .range(..=start)
+                .next_back()
+                .map(|(position, cursor)| (*position, cursor.clone()))
+                .unwrap_or_default()
+        };
+        let event_type = self.object.event_type(self.module, self.event).await?;
+        let client = self.object.provider().client();
+
+        let mut events = vec![];
+        loop {
+            let page = client
+                .query_events(&event_type, cursor.as_ref(), PAGE_SIZE)
+                .await?;
+            for event in page.data {
+                if end.map_or(false, |end| position > end) {
+                    return Ok((position, events));
+                }
+                if position >= start {
+                    events.push((position, event));
+                }
+                position += 1;
+            }
+            if page.next_cursor.is_some() {
+                cursor = page.next_cursor;
+                self.cursors
+                    .lock()
+                    .unwrap()
+                    .insert(position, cursor.clone());
+            }
+            if !page.has_next_page || end.map_or(false, |end| position > end) {
+                return Ok((position, events));
+            }
+        }
+    }
+
+    /// Where an event was emitted. Checkpoints are used as blocks.
+    pub async fn log_meta(&self, event: &Event) -> ChainResult<LogMeta> {
+        let client = self.object.provider().client();
+        let transaction = client.transaction(&event.id.tx_digest).await?;
+        let sequence = transaction.checkpoint.ok_or_else(|| {
+            unexpected(format!(
+                "Transaction {} isn't in a checkpoint",
+                transaction.digest
+            ))
+        })?;
+        let checkpoint = client.checkpoint(sequence.to_string()).await?;
+        let transaction_index = checkpoint
+            .transactions
+            .iter()
+            .position(|digest| *digest == transaction.digest)
+            .unwrap_or_default() as u64;
+        Ok(LogMeta {
+            address: self.object.id(),
+            block_number: checkpoint.sequence_number,
+            block_hash: parse_digest(&checkpoint.digest)?,
+            transaction_id: parse_digest(&transaction.digest)?.into(),
+            transaction_index,
+            log_index: event
+                .id
+                .event_seq
+                .parse::<u64>()
+                .map_err(|_| unexpected(format!("`{}` is not an event seq", event.id.event_seq)))?
+                .into(),
+        })
+    }
+}
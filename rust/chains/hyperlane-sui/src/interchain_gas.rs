@@ -0,0 +1,117 @@
+use std::{ops::RangeInclusive, sync::Arc};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, InterchainGasPaymaster,
+    InterchainGasPayment, LogMeta, SequenceAwareIndexer, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    events::EventStream,
+    object::ContractObject,
+    types::Event,
+    utils::{field, parse_h256, parse_u64},
+    ConnectionConf, SuiProvider,
+};
+
+/// Module of the IGP
+const IGP_MODULE: &str = "igps";
+
+/// A reference to an IGP contract on some Sui chain
+#[derive(Debug)]
+pub struct SuiInterchainGasPaymaster {
+    provider: SuiProvider,
+    address: H256,
+}
+
+impl SuiInterchainGasPaymaster {
+    /// Create a new Sui IGP.
+    pub fn new(conf: &ConnectionConf, locator: &ContractLocator) -> Self {
+        Self {
+            provider: SuiProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for SuiInterchainGasPaymaster {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for SuiInterchainGasPaymaster {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+impl InterchainGasPaymaster for SuiInterchainGasPaymaster {}
+
+/// Struct that retrieves event data for a Sui IGP contract
+#[derive(Debug)]
+pub struct SuiInterchainGasPaymasterIndexer {
+    provider: SuiProvider,
+    payments: EventStream,
+}
+
+impl SuiInterchainGasPaymasterIndexer {
+    /// Create a new Sui IGP indexer.
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let provider = SuiProvider::new(locator.domain.clone(), conf, None);
+        let object = Arc::new(ContractObject::new(provider.clone(), locator.address));
+        Self {
+            provider,
+            payments: EventStream::new(object, IGP_MODULE, "GasPaymentEvent"),
+        }
+    }
+
+    fn payment(event: &Event) -> ChainResult<InterchainGasPayment> {
+        let data = &event.parsed_json;
+        Ok(InterchainGasPayment {
+            message_id: parse_h256(field(data, "message_id")?)?,
+            destination: parse_u64(field(data, "destination_domain")?)?
+                .try_into()
+                .map_err(ChainCommunicationError::from_other)?,
+            payment: parse_u64(field(data, "payment")?)?.into(),
+            gas_amount: parse_u64(field(data, "gas_amount")?)?.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<InterchainGasPayment> for SuiInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        let mut payments = vec![];
+        for (position, event) in self.payments.events(range).await? {
+            let payment = Self::payment(&event)?;
+            let meta = self.payments.log_meta(&event).await?;
+            payments.push((Indexed::new(payment).with_sequence(position), meta));
+        }
+        Ok(payments)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.checkpoint_height().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for SuiInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.provider.checkpoint_height().await?;
+        let count = self.payments.count().await?;
+        Ok((Some(count), tip))
+    }
+}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType, H256, U256,
+};
+use num_traits::cast::FromPrimitive;
+use tracing::{instrument, warn};
+
+use crate::{
+    object::ContractObject,
+    utils::{field, parse_u64},
+    ConnectionConf, SuiProvider,
+};
+
+/// A reference to an InterchainSecurityModule contract on some Sui chain
+#[derive(Debug)]
+pub struct SuiInterchainSecurityModule {
+    object: ContractObject,
+}
+
+impl SuiInterchainSecurityModule {
+    /// Create a new Sui InterchainSecurityModule
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let provider = SuiProvider::new(locator.domain.clone(), conf, None);
+        Self {
+            object: ContractObject::new(provider, locator.address),
+        }
+    }
+}
+
+impl HyperlaneContract for SuiInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.object.id()
+    }
+}
+
+impl HyperlaneChain for SuiInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.object.provider().domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.object.provider().provider()
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for SuiInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let fields = self.object.fields().await?;
+        let module = parse_u64(field(&fields, "module_type")?)?;
+
+        if let Some(module_type) = ModuleType::from_u64(module) {
+            Ok(module_type)
+        } else {
+            warn!(%module, "Unknown module type");
+            Ok(ModuleType::Unused)
+        }
+    }
+
+    /// ISMs verify in the transaction processing the message, which can't be
+    /// dry run without a signer. Assume the metadata verifies and let the
+    /// estimate of the process transaction catch invalid metadata.
+    #[instrument(err, ret, skip(self, _metadata))]
+    async fn dry_run_verify(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        Ok(Some(U256::zero()))
+    }
+}
@@ -0,0 +1,39 @@
+//! Implementation of hyperlane for Sui.
+//!
+//! Talks to the JSON-RPC API of a Sui fullnode. Each contract address is the
+//! id of the shared object holding the contract's state, whose type names the
+//! package of its modules:
+//! - `mailbox::Mailbox`: the outbox merkle tree, the default ISM and tables
+//!   of the delivered messages and recipient ISMs. Emits `DispatchEvent` and
+//!   `ProcessEvent`.
+//! - `igps::Igp`: emits `GasPaymentEvent`
+//! - `ism::Ism`, `multisig_ism::MultisigIsm` and `routing_ism::RoutingIsm`
+//! - `validator_announce::ValidatorAnnounce`: a table of the announced
+//!   storage locations
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+#![deny(warnings)]
+
+pub use self::{
+    error::*, interchain_gas::*, interchain_security_module::*, mailbox::*, merkle_tree_hook::*,
+    multisig_ism::*, provider::*, routing_ism::*, signers::*, trait_builder::*,
+    validator_announce::*,
+};
+
+mod client;
+mod error;
+mod events;
+mod interchain_gas;
+mod interchain_security_module;
+mod mailbox;
+mod merkle_tree_hook;
+mod multisig_ism;
+mod object;
+mod provider;
+mod routing_ism;
+mod signers;
+mod trait_builder;
+mod types;
+mod utils;
+mod validator_announce;
@@ -0,0 +1,238 @@
+use std::{num::NonZeroU64, ops::RangeInclusive, sync::Arc};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
+    RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+use serde_json::{json, Value};
+use tracing::instrument;
+
+use crate::{
+    events::EventStream,
+    object::ContractObject,
+    utils::{
+        bytes_arg, encode_address, field, parse_address_value, parse_bytes, parse_h256, parse_u64,
+    },
+    ConnectionConf, Signer, SuiProvider,
+};
+
+/// Module of the mailbox
+const MAILBOX_MODULE: &str = "mailbox";
+
+/// A reference to a Mailbox contract on some Sui chain
+#[derive(Debug)]
+pub struct SuiMailbox {
+    pub(crate) object: Arc<ContractObject>,
+}
+
+impl SuiMailbox {
+    /// Create a new Sui mailbox
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, signer: Option<Signer>) -> Self {
+        let provider = SuiProvider::new(locator.domain.clone(), conf, signer);
+        Self {
+            object: Arc::new(ContractObject::new(provider, locator.address)),
+        }
+    }
+
+    fn process_arguments(message: &HyperlaneMessage, metadata: &[u8]) -> Vec<Value> {
+        vec![
+            bytes_arg(&RawHyperlaneMessage::from(message)),
+            bytes_arg(metadata),
+        ]
+    }
+}
+
+impl HyperlaneContract for SuiMailbox {
+    fn address(&self) -> H256 {
+        self.object.id()
+    }
+}
+
+impl HyperlaneChain for SuiMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.object.provider().domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.object.provider().provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for SuiMailbox {
+    /// Checkpoints are final, so the lag is ignored
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, _lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        let fields = self.object.fields().await?;
+        parse_u64(field(field(&fields, "outbox_tree")?, "count")?)?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let entry = self
+            .object
+            .table_entry("delivered", "vector<u8>", bytes_arg(id.as_bytes()))
+            .await?;
+        Ok(entry.is_some())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let fields = self.object.fields().await?;
+        parse_address_value(field(&fields, "default_ism")?)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let ism = self
+            .object
+            .table_entry(
+                "recipient_isms",
+                "address",
+                Value::String(encode_address(recipient)),
+            )
+            .await?;
+        match ism {
+            Some(ism) => parse_address_value(&ism),
+            None => self.default_ism().await,
+        }
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        self.object
+            .call(
+                MAILBOX_MODULE,
+                "process",
+                Self::process_arguments(message, metadata),
+                tx_gas_limit,
+            )
+            .await
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        self.object
+            .estimate_call(
+                MAILBOX_MODULE,
+                "process",
+                Self::process_arguments(message, metadata),
+            )
+            .await
+    }
+
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        let call = json!({
+            "module": MAILBOX_MODULE,
+            "function": "process",
+            "arguments": Self::process_arguments(message, metadata),
+        });
+        serde_json::to_vec(&call).unwrap_or_default()
+    }
+}
+
+/// Struct that retrieves event data for a Sui Mailbox contract
+#[derive(Debug)]
+pub struct SuiMailboxIndexer {
+    mailbox: SuiMailbox,
+    dispatches: EventStream,
+    processes: EventStream,
+}
+
+impl SuiMailboxIndexer {
+    /// Create a new Sui mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let mailbox = SuiMailbox::new(conf, locator, None);
+        Self {
+            dispatches: EventStream::new(mailbox.object.clone(), MAILBOX_MODULE, "DispatchEvent"),
+            processes: EventStream::new(mailbox.object.clone(), MAILBOX_MODULE, "ProcessEvent"),
+            mailbox,
+        }
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.object.provider().checkpoint_height().await
+    }
+
+    async fn sequence_count_and_tip(
+        &self,
+        stream: &EventStream,
+    ) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        let count = stream.count().await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for SuiMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let mut messages = vec![];
+        for (_, event) in self.dispatches.events(range).await? {
+            let message =
+                HyperlaneMessage::from(parse_bytes(field(&event.parsed_json, "message")?)?);
+            let meta = self.dispatches.log_meta(&event).await?;
+            let nonce = message.nonce;
+            messages.push((Indexed::new(message).with_sequence(nonce), meta));
+        }
+        Ok(messages)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for SuiMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        self.sequence_count_and_tip(&self.dispatches).await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for SuiMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let mut deliveries = vec![];
+        for (position, event) in self.processes.events(range).await? {
+            let message_id = parse_h256(field(&event.parsed_json, "message_id")?)?;
+            let meta = self.processes.log_meta(&event).await?;
+            deliveries.push((Indexed::new(message_id).with_sequence(position), meta));
+        }
+        Ok(deliveries)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for SuiMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        self.sequence_count_and_tip(&self.processes).await
+    }
+}
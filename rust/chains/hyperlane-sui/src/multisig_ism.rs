@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    object::ContractObject,
+    utils::{field, parse_bytes, parse_u64},
+    ConnectionConf, SuiProvider,
+};
+
+/// A reference to a MultisigIsm contract on some Sui chain
+#[derive(Debug)]
+pub struct SuiMultisigIsm {
+    object: ContractObject,
+}
+
+impl SuiMultisigIsm {
+    /// Create a new Sui MultisigIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let provider = SuiProvider::new(locator.domain.clone(), conf, None);
+        Self {
+            object: ContractObject::new(provider, locator.address),
+        }
+    }
+}
+
+impl HyperlaneContract for SuiMultisigIsm {
+    fn address(&self) -> H256 {
+        self.object.id()
+    }
+}
+
+impl HyperlaneChain for SuiMultisigIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.object.provider().domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.object.provider().provider()
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for SuiMultisigIsm {
+    /// Returns the validator and threshold needed to verify message
+    #[instrument(err, ret, skip(self))]
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let Some(set) = self
+            .object
+            .table_entry("validator_sets", "u32", message.origin.into())
+            .await?
+        else {
+            return Ok((vec![], 0));
+        };
+
+        // Validators are stored by their 20 byte Ethereum address
+        let validators = field(&set, "validators")?
+            .as_array()
+            .ok_or_else(|| ChainCommunicationError::from_other_str("Expected a validator array"))?
+            .iter()
+            .map(|validator| {
+                let validator = parse_bytes(validator)?;
+                if validator.len() != 20 {
+                    return Err(ChainCommunicationError::from_other_str(
+                        "Expected a 20 byte validator address",
+                    ));
+                }
+                let mut address = H256::zero();
+                address.as_bytes_mut()[12..].copy_from_slice(&validator);
+                Ok(address)
+            })
+            .collect::<ChainResult<Vec<_>>>()?;
+        let threshold = parse_u64(field(&set, "threshold")?)?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok((validators, threshold))
+    }
+}
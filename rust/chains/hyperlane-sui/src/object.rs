@@ -0,0 +1,142 @@
+use hyperlane_core::{ChainResult, TxCostEstimate, TxOutcome, H256, U256};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+
+use crate::{
+    types::ObjectResponse,
+    utils::{encode_address, field, parse_address_value, type_package, unexpected},
+    SuiProvider,
+};
+
+/// The shared object holding the state of a contract. Its type names the
+/// package the contract's modules are published in.
+#[derive(Debug)]
+pub(crate) struct ContractObject {
+    provider: SuiProvider,
+    id: H256,
+    package: OnceCell<H256>,
+}
+
+impl ContractObject {
+    pub fn new(provider: SuiProvider, id: H256) -> Self {
+        Self {
+            provider,
+            id,
+            package: OnceCell::new(),
+        }
+    }
+
+    pub fn id(&self) -> H256 {
+        self.id
+    }
+
+    pub fn provider(&self) -> &SuiProvider {
+        &self.provider
+    }
+
+    /// The package of the contract's modules and events
+    pub async fn package(&self) -> ChainResult<H256> {
+        self.package
+            .get_or_try_init(|| async {
+                let object = self.fetch().await?;
+                let kind = object
+                    .data
+                    .and_then(|data| data.kind)
+                    .ok_or_else(|| unexpected(format!("Object {:?} has no type", self.id)))?;
+                type_package(&kind)
+            })
+            .await
+            .copied()
+    }
+
+    /// The fields of the object's struct
+    pub async fn fields(&self) -> ChainResult<Value> {
+        let object = self.fetch().await?;
+        object
+            .data
+            .and_then(|data| data.content)
+            .map(|content| content.fields)
+            .ok_or_else(|| unexpected(format!("Object {:?} doesn't exist", self.id)).into())
+    }
+
+    /// The value at `key` of the `0x2::table::Table` in the field `table` of
+    /// the object, if any
+    pub async fn table_entry(
+        &self,
+        table: &str,
+        key_type: &str,
+        key: Value,
+    ) -> ChainResult<Option<Value>> {
+        let fields = self.fields().await?;
+        let table_id = parse_address_value(field(field(field(&fields, table)?, "id")?, "id")?)?;
+        // Missing entries are returned without data
+        let entry = self
+            .provider
+            .client()
+            .dynamic_field(&encode_address(table_id), key_type, key)
+            .await?;
+        entry
+            .data
+            .and_then(|data| data.content)
+            .map(|content| field(&content.fields, "value").cloned())
+            .transpose()
+    }
+
+    /// The full name of an event of a module of the contract's package
+    pub async fn event_type(&self, module: &str, event: &str) -> ChainResult<String> {
+        Ok(format!(
+            "{}::{module}::{event}",
+            encode_address(self.package().await?)
+        ))
+    }
+
+    /// Call a function of the contract's package, passing the object first
+    pub async fn call(
+        &self,
+        module: &str,
+        function: &str,
+        arguments: Vec<Value>,
+        gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        self.provider
+            .move_call(
+                self.package().await?,
+                module,
+                function,
+                self.with_object(arguments),
+                gas_limit,
+            )
+            .await
+    }
+
+    /// Estimate the cost of calling a function of the contract's package
+    pub async fn estimate_call(
+        &self,
+        module: &str,
+        function: &str,
+        arguments: Vec<Value>,
+    ) -> ChainResult<TxCostEstimate> {
+        self.provider
+            .estimate_move_call(
+                self.package().await?,
+                module,
+                function,
+                self.with_object(arguments),
+            )
+            .await
+    }
+
+    fn with_object(&self, arguments: Vec<Value>) -> Vec<Value> {
+        std::iter::once(Value::String(encode_address(self.id)))
+            .chain(arguments)
+            .collect()
+    }
+
+    async fn fetch(&self) -> ChainResult<ObjectResponse> {
+        Ok(self
+            .provider
+            .client()
+            .object(&encode_address(self.id))
+            .await?)
+    }
+}
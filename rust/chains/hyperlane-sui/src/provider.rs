@@ -0,0 +1,262 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, TxCostEstimate, TxOutcome, TxnInfo, TxnReceiptInfo, H256, U256,
+};
+use serde_json::Value;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    client::SuiClient,
+    types::{Checkpoint, TransactionBlock},
+    utils::{encode_address, encode_digest, parse_address, parse_digest, unexpected},
+    ConnectionConf, HyperlaneSuiError, Signer,
+};
+
+/// Abstraction over a connection to a Sui fullnode
+#[derive(Debug, Clone)]
+pub struct SuiProvider {
+    domain: HyperlaneDomain,
+    conf: ConnectionConf,
+    client: SuiClient,
+    signer: Option<Signer>,
+}
+
+impl SuiProvider {
+    /// Create a provider connected to the fullnode of `conf`, signing
+    /// transactions with `signer` if set
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            conf: conf.clone(),
+            client: SuiClient::new(conf.url.clone()),
+            signer,
+        }
+    }
+
+    pub(crate) fn client(&self) -> &SuiClient {
+        &self.client
+    }
+
+    /// Sequence number of the latest checkpoint. Checkpoints are final.
+    pub(crate) async fn checkpoint_height(&self) -> ChainResult<u32> {
+        self.client
+            .latest_checkpoint()
+            .await?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The address of the account signing transactions, if any
+    pub(crate) fn signer_address(&self) -> Option<H256> {
+        self.signer.as_ref().map(Signer::address)
+    }
+
+    /// The configured gas budget of a transaction, in MIST
+    pub(crate) fn gas_budget(&self) -> u64 {
+        self.conf.gas_budget
+    }
+
+    /// Call a Move function in a transaction and wait for it to be executed.
+    /// Spends at most `gas_limit` gas units if set, else the configured gas
+    /// budget.
+    #[instrument(err, skip(self, arguments))]
+    pub(crate) async fn move_call(
+        &self,
+        package: H256,
+        module: &str,
+        function: &str,
+        arguments: Vec<Value>,
+        gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let signer = self.signer()?;
+        let gas_budget = match gas_limit {
+            Some(limit) => {
+                let price = self.client.reference_gas_price().await?;
+                limit
+                    .saturating_mul(price.into())
+                    .min(self.conf.gas_budget.into())
+                    .as_u64()
+            }
+            None => self.conf.gas_budget,
+        };
+        let transaction = self
+            .client
+            .move_call(
+                &signer.address_string(),
+                &encode_address(package),
+                module,
+                function,
+                arguments,
+                gas_budget,
+            )
+            .await?;
+        let tx_bytes = STANDARD
+            .decode(&transaction.tx_bytes)
+            .map_err(HyperlaneSuiError::from)?;
+        let signature = signer.sign_transaction(&tx_bytes);
+
+        let executed = self
+            .client
+            .execute(&transaction.tx_bytes, &signature)
+            .await?;
+        info!(digest = executed.digest, "Executed transaction");
+        let outcome = tx_outcome(&executed)?;
+        if !outcome.executed {
+            warn!(
+                digest = executed.digest,
+                error = ?executed
+                    .effects
+                    .as_ref()
+                    .and_then(|effects| effects.status.error.as_deref()),
+                "Transaction failed"
+            );
+        }
+        Ok(outcome)
+    }
+
+    /// Estimate the gas of a transaction calling a Move function by dry
+    /// running it
+    #[instrument(err, skip(self, arguments))]
+    pub(crate) async fn estimate_move_call(
+        &self,
+        package: H256,
+        module: &str,
+        function: &str,
+        arguments: Vec<Value>,
+    ) -> ChainResult<TxCostEstimate> {
+        let signer = self.signer()?;
+        let transaction = self
+            .client
+            .move_call(
+                &signer.address_string(),
+                &encode_address(package),
+                module,
+                function,
+                arguments,
+                self.conf.gas_budget,
+            )
+            .await?;
+        let dry_run = self.client.dry_run(&transaction.tx_bytes).await?;
+        if !dry_run.effects.success() {
+            return Err(ChainCommunicationError::CustomError(format!(
+                "Dry run failed: {}",
+                dry_run.effects.status.error.unwrap_or_default()
+            )));
+        }
+        let price = self.client.reference_gas_price().await?;
+        Ok(TxCostEstimate {
+            gas_limit: gas_units(dry_run.effects.gas_used.total(), price),
+            gas_price: U256::from(price).try_into()?,
+            l2_gas_limit: None,
+        })
+    }
+
+    /// Balance of an account in the native token, in MIST
+    pub(crate) async fn balance(&self, address: H256) -> ChainResult<U256> {
+        Ok(self.client.balance(&encode_address(address)).await?.into())
+    }
+}
+
+impl HyperlaneChain for SuiProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for SuiProvider {
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        let checkpoint = self.client.checkpoint(encode_digest(*hash)).await?;
+        block_info(checkpoint)
+    }
+
+    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
+        let transaction = self.client.transaction(&encode_digest(*hash)).await?;
+        let data = transaction
+            .transaction
+            .ok_or_else(|| unexpected(format!("Transaction {hash:?} has no input")))?
+            .data;
+        let receipt = transaction.effects.map(|effects| {
+            let gas_used = gas_units(effects.gas_used.total(), data.gas_data.price);
+            TxnReceiptInfo {
+                gas_used,
+                cumulative_gas_used: gas_used,
+                effective_gas_price: Some(data.gas_data.price.into()),
+            }
+        });
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit: gas_units(data.gas_data.budget, data.gas_data.price),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: Some(data.gas_data.price.into()),
+            nonce: 0,
+            sender: parse_address(&data.sender)?,
+            recipient: None,
+            receipt,
+        })
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        let object = self.client.object(&encode_address(*address)).await?;
+        Ok(object.data.is_some())
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        self.balance(parse_address(&address)?).await
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        let sequence = self.client.latest_checkpoint().await?;
+        let checkpoint = self.client.checkpoint(sequence.to_string()).await?;
+        let gas_price = self.client.reference_gas_price().await?;
+        Ok(Some(ChainInfo::new(
+            block_info(checkpoint)?,
+            Some(gas_price.into()),
+        )))
+    }
+}
+
+fn block_info(checkpoint: Checkpoint) -> ChainResult<BlockInfo> {
+    Ok(BlockInfo {
+        hash: parse_digest(&checkpoint.digest)?,
+        timestamp: checkpoint.timestamp_ms / 1000,
+        number: checkpoint.sequence_number,
+    })
+}
+
+/// Gas units worth `cost` MIST at `price`
+fn gas_units(cost: u64, price: u64) -> U256 {
+    (cost / price.max(1)).into()
+}
+
+/// The outcome of an executed transaction
+pub(crate) fn tx_outcome(transaction: &TransactionBlock) -> ChainResult<TxOutcome> {
+    let effects = transaction
+        .effects
+        .as_ref()
+        .ok_or_else(|| unexpected(format!("Transaction {} has no effects", transaction.digest)))?;
+    let price = transaction
+        .transaction
+        .as_ref()
+        .map(|signed| signed.data.gas_data.price)
+        .unwrap_or_default();
+    Ok(TxOutcome {
+        transaction_id: parse_digest(&transaction.digest)?.into(),
+        executed: effects.success(),
+        gas_used: gas_units(effects.gas_used.total(), price),
+        gas_price: U256::from(price).try_into()?,
+    })
+}
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RoutingIsm, H256,
+};
+use tracing::instrument;
+
+use crate::{object::ContractObject, utils::parse_address_value, ConnectionConf, SuiProvider};
+
+/// A reference to a RoutingIsm contract on some Sui chain
+#[derive(Debug)]
+pub struct SuiRoutingIsm {
+    object: ContractObject,
+}
+
+impl SuiRoutingIsm {
+    /// Create a new Sui RoutingIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let provider = SuiProvider::new(locator.domain.clone(), conf, None);
+        Self {
+            object: ContractObject::new(provider, locator.address),
+        }
+    }
+}
+
+impl HyperlaneContract for SuiRoutingIsm {
+    fn address(&self) -> H256 {
+        self.object.id()
+    }
+}
+
+impl HyperlaneChain for SuiRoutingIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.object.provider().domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.object.provider().provider()
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for SuiRoutingIsm {
+    #[instrument(err, ret, skip(self))]
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        let ism = self
+            .object
+            .table_entry("routes", "u32", message.origin.into())
+            .await?
+            .ok_or_else(|| {
+                ChainCommunicationError::CustomError(format!(
+                    "No ISM routed for origin {}",
+                    message.origin
+                ))
+            })?;
+        parse_address_value(&ism)
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt::{Debug, Formatter};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+use hyperlane_core::{ChainResult, H256};
+
+use crate::{utils::encode_address, HyperlaneSuiError};
+
+type Blake2b256 = Blake2b<U32>;
+
+/// Flag of the Ed25519 signature scheme, prepended to the public key to
+/// derive the address and to serialized signatures
+const ED25519_FLAG: u8 = 0;
+
+/// Intent of signing transaction data: a transaction, intent version 0, for
+/// the Sui app
+const TRANSACTION_INTENT: [u8; 3] = [0, 0, 0];
+
+/// Signer of the transactions of a Sui account with an Ed25519 key
+#[derive(Clone)]
+pub struct Signer {
+    secret_key: [u8; 32],
+    public_key: PublicKey,
+    address: H256,
+}
+
+impl Signer {
+    /// Create a signer from the 32 byte private key of an account
+    pub fn new(private_key: &[u8]) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key).map_err(HyperlaneSuiError::from)?;
+        let public_key = PublicKey::from(&secret);
+        let address = H256::from_slice(
+            Blake2b256::new()
+                .chain_update([ED25519_FLAG])
+                .chain_update(public_key.as_bytes())
+                .finalize()
+                .as_slice(),
+        );
+        Ok(Self {
+            secret_key: secret.to_bytes(),
+            public_key,
+            address,
+        })
+    }
+
+    /// The address of the account
+    pub fn address(&self) -> H256 {
+        self.address
+    }
+
+    /// The address of the account, formatted the way Sui does
+    pub fn address_string(&self) -> String {
+        encode_address(self.address)
+    }
+
+    /// Sign transaction data, returning the serialized signature
+    pub(crate) fn sign_transaction(&self, tx_bytes: &[u8]) -> String {
+        let digest = Blake2b256::new()
+            .chain_update(TRANSACTION_INTENT)
+            .chain_update(tx_bytes)
+            .finalize();
+        // The key was validated on creation
+        let secret = SecretKey::from_bytes(&self.secret_key).expect("Valid secret key");
+        let signature = ExpandedSecretKey::from(&secret).sign(&digest, &self.public_key);
+
+        let mut serialized = vec![ED25519_FLAG];
+        serialized.extend_from_slice(&signature.to_bytes());
+        serialized.extend_from_slice(self.public_key.as_bytes());
+        STANDARD.encode(serialized)
+    }
+}
+
+impl Debug for Signer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signer")
+            .field("address", &self.address_string())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signature, Verifier};
+
+    use super::*;
+
+    #[test]
+    fn test_transaction_signature_verifies_with_public_key() {
+        let signer = Signer::new(&[1; 32]).unwrap();
+        let serialized = STANDARD.decode(signer.sign_transaction(b"tx")).unwrap();
+        assert_eq!(serialized.len(), 1 + 64 + 32);
+        assert_eq!(serialized[0], ED25519_FLAG);
+        assert_eq!(&serialized[65..], signer.public_key.as_bytes());
+
+        let digest = Blake2b256::new()
+            .chain_update(TRANSACTION_INTENT)
+            .chain_update(b"tx")
+            .finalize();
+        let signature = Signature::from_bytes(&serialized[1..65]).unwrap();
+        assert!(signer.public_key.verify(&digest, &signature).is_ok());
+    }
+}
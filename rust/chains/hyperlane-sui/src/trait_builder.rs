@@ -0,0 +1,17 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// Gas budget of a submitted transaction if not configured, in MIST
+pub const DEFAULT_GAS_BUDGET: u64 = 50_000_000;
+
+/// Sui connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Url of the JSON-RPC API of a fullnode, e.g.
+    /// `https://fullnode.mainnet.sui.io:443`
+    pub url: Url,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+    /// Most a submitted transaction may spend on gas, in MIST
+    pub gas_budget: u64,
+}
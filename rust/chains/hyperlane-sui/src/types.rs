@@ -0,0 +1,173 @@
+//! Responses of the fullnode JSON-RPC API. The API writes 64 bit and larger
+//! integers as strings.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// Deserialize a u64 written as a string
+pub(crate) fn u64_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+/// Deserialize an optional u64 written as a string
+pub(crate) fn opt_u64_str<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Error of a failed call
+#[derive(Debug, Deserialize)]
+pub(crate) struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// Identifies an event, and is the cursor to query the events after it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EventId {
+    pub tx_digest: String,
+    pub event_seq: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Event {
+    pub id: EventId,
+    /// The fields of the Move event struct
+    pub parsed_json: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EventPage {
+    pub data: Vec<Event>,
+    pub next_cursor: Option<EventId>,
+    pub has_next_page: bool,
+}
+
+/// A checkpoint, which Sui finalizes transactions in. Used as blocks.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Checkpoint {
+    #[serde(deserialize_with = "u64_str")]
+    pub sequence_number: u64,
+    /// Base58
+    pub digest: String,
+    #[serde(deserialize_with = "u64_str")]
+    pub timestamp_ms: u64,
+    /// Digests of the transactions of the checkpoint, in order
+    pub transactions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ObjectResponse {
+    pub data: Option<ObjectData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ObjectData {
+    /// `<package>::<module>::<struct>`
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub content: Option<ObjectContent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ObjectContent {
+    pub fields: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionBlock {
+    /// Base58
+    pub digest: String,
+    #[serde(default, deserialize_with = "opt_u64_str")]
+    pub checkpoint: Option<u64>,
+    pub transaction: Option<SignedTransactionData>,
+    pub effects: Option<Effects>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SignedTransactionData {
+    pub data: TransactionData,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionData {
+    pub sender: String,
+    pub gas_data: GasData,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GasData {
+    #[serde(deserialize_with = "u64_str")]
+    pub price: u64,
+    #[serde(deserialize_with = "u64_str")]
+    pub budget: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Effects {
+    pub status: ExecutionStatus,
+    pub gas_used: GasCostSummary,
+}
+
+impl Effects {
+    pub fn success(&self) -> bool {
+        self.status.status == "success"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExecutionStatus {
+    /// `success` or `failure`
+    pub status: String,
+    pub error: Option<String>,
+}
+
+/// Gas charged to a transaction, in MIST
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GasCostSummary {
+    #[serde(deserialize_with = "u64_str")]
+    pub computation_cost: u64,
+    #[serde(deserialize_with = "u64_str")]
+    pub storage_cost: u64,
+    #[serde(deserialize_with = "u64_str")]
+    pub storage_rebate: u64,
+}
+
+impl GasCostSummary {
+    /// The net cost, storage rebates deducted
+    pub fn total(&self) -> u64 {
+        (self.computation_cost + self.storage_cost).saturating_sub(self.storage_rebate)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DryRun {
+    pub effects: Effects,
+}
+
+/// An unsigned transaction built by the fullnode
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionBytes {
+    /// Base64 BCS of the transaction data
+    pub tx_bytes: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Balance {
+    pub total_balance: String,
+}
@@ -0,0 +1,133 @@
+use hyperlane_core::{ChainResult, H256};
+use serde_json::Value;
+
+use crate::HyperlaneSuiError;
+
+/// Write an address or object id the way the API expects it
+pub(crate) fn encode_address(address: H256) -> String {
+    format!("0x{}", hex::encode(address.as_bytes()))
+}
+
+/// Parse an address or object id returned by the API, which drops the
+/// leading zeros of system addresses such as `0x2`
+pub(crate) fn parse_address(address: &str) -> ChainResult<H256> {
+    let hex = address.trim_start_matches("0x");
+    if hex.len() > 64 {
+        return Err(unexpected(format!("`{address}` is not an address")).into());
+    }
+    let bytes = hex::decode(format!("{hex:0>64}")).map_err(HyperlaneSuiError::from)?;
+    Ok(H256::from_slice(&bytes))
+}
+
+/// Parse a base58 transaction or checkpoint digest
+pub(crate) fn parse_digest(digest: &str) -> ChainResult<H256> {
+    let bytes = bs58::decode(digest)
+        .into_vec()
+        .map_err(HyperlaneSuiError::from)?;
+    if bytes.len() != 32 {
+        return Err(unexpected(format!("`{digest}` is not a digest")).into());
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// Write a transaction or checkpoint digest the way the API expects it
+pub(crate) fn encode_digest(digest: H256) -> String {
+    bs58::encode(digest.as_bytes()).into_string()
+}
+
+/// A `vector<u8>` argument or dynamic field name
+pub(crate) fn bytes_arg(bytes: &[u8]) -> Value {
+    Value::Array(bytes.iter().copied().map(Value::from).collect())
+}
+
+/// Parse a `vector<u8>` value
+pub(crate) fn parse_bytes(value: &Value) -> ChainResult<Vec<u8>> {
+    value
+        .as_array()
+        .and_then(|bytes| {
+            bytes
+                .iter()
+                .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                .collect::<Option<Vec<u8>>>()
+        })
+        .ok_or_else(|| unexpected(format!("`{value}` is not a byte vector")).into())
+}
+
+/// Parse a 32 byte `vector<u8>` value
+pub(crate) fn parse_h256(value: &Value) -> ChainResult<H256> {
+    let bytes = parse_bytes(value)?;
+    if bytes.len() != 32 {
+        return Err(unexpected(format!("`{value}` is not 32 bytes long")).into());
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// Parse an `address` or `ID` value
+pub(crate) fn parse_address_value(value: &Value) -> ChainResult<H256> {
+    let address = value
+        .as_str()
+        .ok_or_else(|| unexpected(format!("`{value}` is not an address")))?;
+    parse_address(address)
+}
+
+/// Parse an integer value, which the API writes as a number up to `u32` and
+/// as a string above
+pub(crate) fn parse_u64(value: &Value) -> ChainResult<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| unexpected(format!("`{value}` is not an integer")).into())
+}
+
+/// The field `name` of a Move struct value. Nested structs are written as
+/// their type and fields.
+pub(crate) fn field<'a>(value: &'a Value, name: &str) -> ChainResult<&'a Value> {
+    value
+        .get("fields")
+        .unwrap_or(value)
+        .get(name)
+        .ok_or_else(|| unexpected(format!("Missing field `{name}` in `{value}`")).into())
+}
+
+/// The package of a Move type, i.e. `0x2` of `0x2::coin::Coin<..>`
+pub(crate) fn type_package(move_type: &str) -> ChainResult<H256> {
+    let package = move_type
+        .split("::")
+        .next()
+        .ok_or_else(|| unexpected(format!("`{move_type}` is not a Move type")))?;
+    parse_address(package)
+}
+
+pub(crate) fn unexpected(message: String) -> HyperlaneSuiError {
+    HyperlaneSuiError::UnexpectedResponse(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested_struct_field() {
+        let tree = serde_json::json!({
+            "type": "0x1::merkle::Tree",
+            "fields": { "branch": [], "count": "3" }
+        });
+        assert_eq!(parse_u64(field(&tree, "count").unwrap()).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_type_package() {
+        assert_eq!(
+            type_package("0x2::table::Table<vector<u8>, bool>").unwrap(),
+            H256::from_low_u64_be(2)
+        );
+    }
+
+    #[test]
+    fn test_digest_roundtrip() {
+        let digest = H256::repeat_byte(7);
+        assert_eq!(parse_digest(&encode_digest(digest)).unwrap(), digest);
+    }
+}
@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    Announcement, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, SignedType, TxOutcome,
+    ValidatorAnnounce, H256, U256,
+};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use crate::{object::ContractObject, utils::bytes_arg, ConnectionConf, Signer, SuiProvider};
+
+/// Module of the validator announce contract
+const VALIDATOR_ANNOUNCE_MODULE: &str = "validator_announce";
+
+/// A reference to a ValidatorAnnounce contract on some Sui chain
+#[derive(Debug)]
+pub struct SuiValidatorAnnounce {
+    object: ContractObject,
+}
+
+impl SuiValidatorAnnounce {
+    /// Create a new Sui ValidatorAnnounce
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, signer: Option<Signer>) -> Self {
+        let provider = SuiProvider::new(locator.domain.clone(), conf, signer);
+        Self {
+            object: ContractObject::new(provider, locator.address),
+        }
+    }
+
+    fn announce_arguments(announcement: &SignedType<Announcement>) -> Vec<Value> {
+        vec![
+            bytes_arg(announcement.value.validator.as_bytes()),
+            bytes_arg(&announcement.signature.to_vec()),
+            Value::String(announcement.value.storage_location.clone()),
+        ]
+    }
+}
+
+impl HyperlaneContract for SuiValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.object.id()
+    }
+}
+
+impl HyperlaneChain for SuiValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.object.provider().domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.object.provider().provider()
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for SuiValidatorAnnounce {
+    #[instrument(err, ret, skip(self))]
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        let mut locations = Vec::with_capacity(validators.len());
+        for validator in validators {
+            // Validators are announced by their 20 byte Ethereum address
+            let entry = self
+                .object
+                .table_entry(
+                    "storage_locations",
+                    "vector<u8>",
+                    bytes_arg(&validator.as_bytes()[12..]),
+                )
+                .await?;
+            let validator_locations = match entry {
+                Some(entry) => {
+                    serde_json::from_value(entry).map_err(ChainCommunicationError::from_other)?
+                }
+                None => vec![],
+            };
+            locations.push(validator_locations);
+        }
+        Ok(locations)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        self.object
+            .call(
+                VALIDATOR_ANNOUNCE_MODULE,
+                "announce",
+                Self::announce_arguments(&announcement),
+                None,
+            )
+            .await
+    }
+
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        let provider = self.object.provider();
+        let Some(signer) = provider.signer_address() else {
+            warn!(?announcement, "Cannot announce without a signer");
+            return None;
+        };
+        let balance = provider.balance(signer).await.ok()?;
+        Some(U256::from(provider.gas_budget()).saturating_sub(balance))
+    }
+}
@@ -0,0 +1,359 @@
+//! Serialization of values to and from cells, the way contracts take the
+//! bodies of their messages and emit events, and to and from the stack of
+//! get methods.
+
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hyperlane_core::{ChainResult, HyperlaneMessage, H256, U256};
+use num_bigint::BigUint;
+use tonlib_core::{
+    cell::{ArcCell, BagOfCells, Cell, CellBuilder, CellParser},
+    TonAddress,
+};
+
+use crate::{types::StackEntry, HyperlaneTonError};
+
+/// Workchain of the contracts and wallets. Addresses are stored as the
+/// 32 byte hash of the account, which is in the basechain.
+pub(crate) const BASECHAIN: i32 = 0;
+
+/// Bytes of a snake value held by each cell of the chain
+const SNAKE_CHUNK_SIZE: usize = 127;
+
+pub(crate) fn ton_address(address: H256) -> TonAddress {
+    TonAddress::new(BASECHAIN, &address.0)
+}
+
+/// The raw form of an address, e.g. `0:ABCD...`
+pub(crate) fn raw_address(address: H256) -> String {
+    ton_address(address).to_hex()
+}
+
+/// The hash of an account in the basechain
+pub(crate) fn h256_from_address(address: &TonAddress) -> ChainResult<H256> {
+    if address.workchain != BASECHAIN {
+        return Err(unexpected(format!("{address} is not in the basechain")).into());
+    }
+    Ok(H256(address.hash_part))
+}
+
+/// Parse an address in any of its forms
+pub(crate) fn parse_address(address: &str) -> ChainResult<H256> {
+    let address = address
+        .parse::<TonAddress>()
+        .map_err(|_| unexpected(format!("Invalid address {address}")))?;
+    h256_from_address(&address)
+}
+
+/// A base64 hash of a transaction, message or block
+pub(crate) fn parse_hash(hash: &str) -> ChainResult<H256> {
+    let bytes = STANDARD.decode(hash).map_err(HyperlaneTonError::from)?;
+    if bytes.len() != 32 {
+        return Err(unexpected(format!("Invalid hash {hash}")).into());
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+pub(crate) fn encode_hash(hash: H256) -> String {
+    STANDARD.encode(hash.as_bytes())
+}
+
+/// The root cell of a base64 bag of cells
+pub(crate) fn parse_boc(boc: &str) -> ChainResult<ArcCell> {
+    let cell = BagOfCells::parse_base64(boc)
+        .and_then(|boc| boc.single_root())
+        .map_err(HyperlaneTonError::from)?;
+    Ok(cell)
+}
+
+/// A cell as a bag of cells
+pub(crate) fn boc_bytes(cell: Cell) -> ChainResult<Vec<u8>> {
+    let boc = BagOfCells::from_root(cell)
+        .serialize(true)
+        .map_err(HyperlaneTonError::from)?;
+    Ok(boc)
+}
+
+/// A cell as a base64 bag of cells
+pub(crate) fn encode_boc(cell: Cell) -> ChainResult<String> {
+    Ok(STANDARD.encode(boc_bytes(cell)?))
+}
+
+/// Bytes that may not fit in a cell, stored in a chain of cells each holding
+/// a chunk of the bytes and a reference to the next
+pub(crate) fn snake_cell(bytes: &[u8]) -> ChainResult<Cell> {
+    let mut next: Option<Cell> = None;
+    for chunk in bytes.chunks(SNAKE_CHUNK_SIZE).rev() {
+        let mut builder = CellBuilder::new();
+        builder
+            .store_slice(chunk)
+            .map_err(HyperlaneTonError::from)?;
+        if let Some(next) = next.take() {
+            builder.store_child(next).map_err(HyperlaneTonError::from)?;
+        }
+        next = Some(builder.build().map_err(HyperlaneTonError::from)?);
+    }
+    match next {
+        Some(cell) => Ok(cell),
+        None => Ok(CellBuilder::new()
+            .build()
+            .map_err(HyperlaneTonError::from)?),
+    }
+}
+
+pub(crate) fn read_snake(cell: &Cell) -> ChainResult<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut cell = cell;
+    loop {
+        if cell.bit_len() % 8 != 0 {
+            return Err(unexpected("Snake cell of partial bytes".into()).into());
+        }
+        bytes.extend(
+            cell.parser()
+                .load_bytes(cell.bit_len() / 8)
+                .map_err(HyperlaneTonError::from)?,
+        );
+        match cell.references().first() {
+            Some(next) => cell = next,
+            None => return Ok(bytes),
+        }
+    }
+}
+
+/// A `Message` cell, the body of the message being in a snake cell
+/// referenced by it
+pub(crate) fn message_cell(message: &HyperlaneMessage) -> ChainResult<Cell> {
+    let body = snake_cell(&message.body)?;
+    let mut builder = CellBuilder::new();
+    builder
+        .store_u8(8, message.version)
+        .and_then(|b| b.store_u32(32, message.nonce))
+        .and_then(|b| b.store_u32(32, message.origin))
+        .and_then(|b| b.store_slice(message.sender.as_bytes()))
+        .and_then(|b| b.store_u32(32, message.destination))
+        .and_then(|b| b.store_slice(message.recipient.as_bytes()))
+        .and_then(|b| b.store_child(body))
+        .map_err(HyperlaneTonError::from)?;
+    Ok(builder.build().map_err(HyperlaneTonError::from)?)
+}
+
+pub(crate) fn read_message(cell: &Cell) -> ChainResult<HyperlaneMessage> {
+    let mut parser = cell.parser();
+    let message = HyperlaneMessage {
+        version: parser.load_u8(8).map_err(HyperlaneTonError::from)?,
+        nonce: parser.load_u32(32).map_err(HyperlaneTonError::from)?,
+        origin: parser.load_u32(32).map_err(HyperlaneTonError::from)?,
+        sender: read_h256(&mut parser)?,
+        destination: parser.load_u32(32).map_err(HyperlaneTonError::from)?,
+        recipient: read_h256(&mut parser)?,
+        body: read_snake(first_reference(cell)?)?,
+    };
+    Ok(message)
+}
+
+pub(crate) fn read_h256(parser: &mut CellParser) -> ChainResult<H256> {
+    let bytes = parser.load_bytes(32).map_err(HyperlaneTonError::from)?;
+    Ok(H256::from_slice(&bytes))
+}
+
+pub(crate) fn read_coins(parser: &mut CellParser) -> ChainResult<U256> {
+    let coins = parser.load_coins().map_err(HyperlaneTonError::from)?;
+    Ok(U256::from_big_endian(&coins.to_bytes_be()))
+}
+
+pub(crate) fn first_reference(cell: &Cell) -> ChainResult<&ArcCell> {
+    cell.references()
+        .first()
+        .ok_or_else(|| unexpected("Missing cell reference".into()).into())
+}
+
+/// The body of a message to a contract: its op code, a query id and the
+/// given cells as references
+pub(crate) fn op_body(op: u32, fields: &[u8], references: Vec<Cell>) -> ChainResult<Cell> {
+    let mut builder = CellBuilder::new();
+    builder
+        .store_u32(32, op)
+        .and_then(|b| b.store_u64(64, 0))
+        .and_then(|b| b.store_slice(fields))
+        .map_err(HyperlaneTonError::from)?;
+    for reference in references {
+        builder
+            .store_reference(&Arc::new(reference))
+            .map_err(HyperlaneTonError::from)?;
+    }
+    Ok(builder.build().map_err(HyperlaneTonError::from)?)
+}
+
+/// A parser of the fields of an event, after its op code
+pub(crate) fn event_fields(cell: &Cell) -> ChainResult<CellParser> {
+    let mut parser = cell.parser();
+    parser.load_u32(32).map_err(HyperlaneTonError::from)?;
+    Ok(parser)
+}
+
+/// The op code of an event
+pub(crate) fn event_op(cell: &Cell) -> Option<u32> {
+    cell.parser().load_u32(32).ok()
+}
+
+pub(crate) fn num_arg(value: u64) -> StackEntry {
+    StackEntry::Num(format!("{value:#x}"))
+}
+
+pub(crate) fn h256_arg(value: H256) -> StackEntry {
+    StackEntry::Num(format!("{value:#x}"))
+}
+
+/// Reads values from the stack returned by a get method
+#[derive(Debug)]
+pub(crate) struct StackReader {
+    entries: std::vec::IntoIter<StackEntry>,
+}
+
+impl StackReader {
+    pub fn new(entries: Vec<StackEntry>) -> Self {
+        Self {
+            entries: entries.into_iter(),
+        }
+    }
+
+    fn entry(&mut self) -> ChainResult<StackEntry> {
+        self.entries
+            .next()
+            .ok_or_else(|| unexpected("Not enough stack entries".into()).into())
+    }
+
+    /// An integer, which is negative if the hex is prefixed with `-`
+    fn num(&mut self) -> ChainResult<(bool, BigUint)> {
+        let StackEntry::Num(num) = self.entry()? else {
+            return Err(unexpected("Expected a number".into()).into());
+        };
+        let (negative, hex) = match num.strip_prefix('-') {
+            Some(hex) => (true, hex),
+            None => (false, num.as_str()),
+        };
+        let value = hex
+            .strip_prefix("0x")
+            .and_then(|hex| BigUint::parse_bytes(hex.as_bytes(), 16))
+            .ok_or_else(|| unexpected(format!("Invalid number {num}")))?;
+        Ok((negative, value))
+    }
+
+    pub fn u64(&mut self) -> ChainResult<u64> {
+        match self.num()? {
+            (false, value) => u64::try_from(&value)
+                .map_err(|_| unexpected(format!("{value} is not a u64")).into()),
+            (true, value) => Err(unexpected(format!("-{value} is not a u64")).into()),
+        }
+    }
+
+    pub fn u32(&mut self) -> ChainResult<u32> {
+        let value = self.u64()?;
+        u32::try_from(value).map_err(|_| unexpected(format!("{value} is not a u32")).into())
+    }
+
+    /// A boolean, true being -1
+    pub fn bool(&mut self) -> ChainResult<bool> {
+        let (_, value) = self.num()?;
+        Ok(value != BigUint::default())
+    }
+
+    /// A 256 bit integer as 32 bytes, such as a hash
+    pub fn h256(&mut self) -> ChainResult<H256> {
+        match self.num()? {
+            (false, value) if value.bits() <= 256 => {
+                let bytes = value.to_bytes_be();
+                let mut padded = [0; 32];
+                padded[32 - bytes.len()..].copy_from_slice(&bytes);
+                Ok(H256(padded))
+            }
+            (_, value) => Err(unexpected(format!("{value} is not a u256")).into()),
+        }
+    }
+
+    pub fn cell(&mut self) -> ChainResult<ArcCell> {
+        match self.entry()? {
+            StackEntry::Cell(boc) | StackEntry::Slice(boc) => parse_boc(&boc),
+            entry => Err(unexpected(format!("Expected a cell, got {entry:?}")).into()),
+        }
+    }
+
+    /// An address stored in a slice, unset if it's `addr_none`
+    pub fn address(&mut self) -> ChainResult<Option<H256>> {
+        let cell = self.cell()?;
+        let address = cell
+            .parser()
+            .load_address()
+            .map_err(HyperlaneTonError::from)?;
+        if address == TonAddress::NULL {
+            return Ok(None);
+        }
+        h256_from_address(&address).map(Some)
+    }
+
+    /// A tuple or list of values read by `element`
+    pub fn tuple<T>(
+        &mut self,
+        mut element: impl FnMut(&mut Self) -> ChainResult<T>,
+    ) -> ChainResult<Vec<T>> {
+        match self.entry()? {
+            StackEntry::Tuple(entries) | StackEntry::List(entries) => {
+                let mut reader = Self::new(entries);
+                let len = reader.entries.len();
+                (0..len).map(|_| element(&mut reader)).collect()
+            }
+            entry => Err(unexpected(format!("Expected a tuple, got {entry:?}")).into()),
+        }
+    }
+}
+
+pub(crate) fn unexpected(message: String) -> HyperlaneTonError {
+    HyperlaneTonError::UnexpectedResponse(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_roundtrip() {
+        let message = HyperlaneMessage {
+            version: 3,
+            nonce: 7,
+            origin: 1,
+            sender: H256::repeat_byte(0xab),
+            destination: 777001,
+            recipient: H256::from_low_u64_be(42),
+            body: (0..=200).collect(),
+        };
+        let cell = message_cell(&message).unwrap();
+        assert_eq!(read_message(&cell).unwrap(), message);
+    }
+
+    #[test]
+    fn test_snake_chunks() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let cell = snake_cell(&bytes).unwrap();
+        assert_eq!(cell.bit_len(), SNAKE_CHUNK_SIZE * 8);
+        assert_eq!(cell.references().len(), 1);
+        assert_eq!(read_snake(&cell).unwrap(), bytes);
+        assert!(read_snake(&snake_cell(&[]).unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stack_reader() {
+        let mut reader = StackReader::new(vec![
+            StackEntry::Num("-0x1".into()),
+            StackEntry::Num("0x2a".into()),
+            StackEntry::Tuple(vec![StackEntry::Num("0xff".into())]),
+        ]);
+        assert!(reader.bool().unwrap());
+        assert_eq!(reader.u32().unwrap(), 42);
+        assert_eq!(
+            reader.tuple(StackReader::h256).unwrap(),
+            vec![H256::from_low_u64_be(0xff)]
+        );
+        assert!(reader.u64().is_err());
+    }
+}
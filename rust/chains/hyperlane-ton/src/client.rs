@@ -0,0 +1,198 @@
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    types::{
+        Account, ApiError, Block, Blocks, FeeEstimate, GetMethodResult, MasterchainInfo,
+        SentMessage, StackEntry, Transaction, Transactions, WalletInformation,
+    },
+    HyperlaneTonError,
+};
+
+type Result<T> = std::result::Result<T, HyperlaneTonError>;
+
+/// Workchain of the masterchain
+const MASTERCHAIN: i32 = -1;
+
+/// Client of a toncenter v3 API
+#[derive(Debug, Clone)]
+pub(crate) struct TonClient {
+    http: Client,
+    url: Url,
+    api_key: Option<String>,
+}
+
+impl TonClient {
+    pub fn new(url: Url, api_key: Option<String>) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+            api_key,
+        }
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .expect("Http urls have a path")
+            .pop_if_empty()
+            .push(path);
+        url
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let request = match &self.api_key {
+            Some(key) => request.header("X-API-Key", key),
+            None => request,
+        };
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = match response.json::<ApiError>().await {
+                Ok(err) => err.error,
+                Err(_) => status.to_string(),
+            };
+            return Err(HyperlaneTonError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, String)]) -> Result<T> {
+        self.send(self.http.get(self.endpoint(path)).query(query))
+            .await
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: serde_json::Value) -> Result<T> {
+        self.send(self.http.post(self.endpoint(path)).json(&body))
+            .await
+    }
+
+    /// The latest masterchain block
+    pub async fn latest_block(&self) -> Result<Block> {
+        let info: MasterchainInfo = self.get("masterchainInfo", &[]).await?;
+        Ok(info.last)
+    }
+
+    /// A masterchain block by sequence number
+    pub async fn block(&self, seqno: u32) -> Result<Block> {
+        let blocks: Blocks = self
+            .get(
+                "blocks",
+                &[
+                    ("workchain", MASTERCHAIN.to_string()),
+                    ("seqno", seqno.to_string()),
+                ],
+            )
+            .await?;
+        blocks.blocks.into_iter().next().ok_or_else(|| {
+            HyperlaneTonError::UnexpectedResponse(format!("Masterchain block {seqno} not found"))
+        })
+    }
+
+    /// Up to `limit` transactions of an account between two unix times, in
+    /// the order they were executed, skipping the first `offset`
+    pub async fn transactions(
+        &self,
+        account: &str,
+        start_utime: u64,
+        end_utime: u64,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<Transaction>> {
+        let transactions: Transactions = self
+            .get(
+                "transactions",
+                &[
+                    ("account", account.to_owned()),
+                    ("start_utime", start_utime.to_string()),
+                    ("end_utime", end_utime.to_string()),
+                    ("limit", limit.to_string()),
+                    ("offset", offset.to_string()),
+                    ("sort", "asc".to_owned()),
+                ],
+            )
+            .await?;
+        Ok(transactions.transactions)
+    }
+
+    /// A transaction by its base64 hash
+    pub async fn transaction(&self, hash: &str) -> Result<Option<Transaction>> {
+        let transactions: Transactions = self
+            .get("transactions", &[("hash", hash.to_owned())])
+            .await?;
+        Ok(transactions.transactions.into_iter().next())
+    }
+
+    /// The transaction that received a message, once it's executed
+    pub async fn transaction_by_message(&self, message_hash: &str) -> Result<Option<Transaction>> {
+        let transactions: Transactions = self
+            .get(
+                "transactionsByMessage",
+                &[
+                    ("msg_hash", message_hash.to_owned()),
+                    ("direction", "in".to_owned()),
+                ],
+            )
+            .await?;
+        Ok(transactions.transactions.into_iter().next())
+    }
+
+    /// Run a get method of a contract against its latest state
+    pub async fn run_get_method(
+        &self,
+        address: &str,
+        method: &str,
+        stack: Vec<StackEntry>,
+    ) -> Result<Vec<StackEntry>> {
+        let result: GetMethodResult = self
+            .post(
+                "runGetMethod",
+                json!({ "address": address, "method": method, "stack": stack }),
+            )
+            .await?;
+        if result.exit_code != 0 {
+            return Err(HyperlaneTonError::GetMethod {
+                method: method.to_owned(),
+                exit_code: result.exit_code,
+            });
+        }
+        Ok(result.stack)
+    }
+
+    /// Send an external message, given as a base64 bag of cells, returning
+    /// its base64 hash
+    pub async fn send_message(&self, boc: &str) -> Result<String> {
+        let sent: SentMessage = self.post("message", json!({ "boc": boc })).await?;
+        Ok(sent.message_hash)
+    }
+
+    /// Fees the recipient of an external message pays to execute it
+    pub async fn estimate_fee(&self, address: &str, body: &str) -> Result<u64> {
+        let estimate: FeeEstimate = self
+            .post(
+                "estimateFee",
+                json!({ "address": address, "body": body, "ignore_chksig": true }),
+            )
+            .await?;
+        Ok(estimate.source_fees.total())
+    }
+
+    pub async fn account(&self, address: &str) -> Result<Account> {
+        self.get("account", &[("address", address.to_owned())])
+            .await
+    }
+
+    /// Sequence number of the next message of a wallet, unset if the wallet
+    /// isn't deployed
+    pub async fn wallet_seqno(&self, address: &str) -> Result<Option<u32>> {
+        let wallet: WalletInformation = self
+            .get("wallet", &[("address", address.to_owned())])
+            .await?;
+        Ok(wallet.seqno)
+    }
+}
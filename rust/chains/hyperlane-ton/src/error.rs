@@ -0,0 +1,53 @@
+use hyperlane_core::ChainCommunicationError;
+use tonlib_core::{cell::TonCellError, wallet::TonWalletError};
+
+/// Errors from the crates specific to the hyperlane-ton
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneTonError {
+    /// Http client error
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// Error returned by the toncenter API
+    #[error("TON API error {status}: {message}")]
+    Api {
+        /// Http status of the response
+        status: u16,
+        /// Message of the error
+        message: String,
+    },
+    /// A get method exited with an error
+    #[error("Get method `{method}` exited with code {exit_code}")]
+    GetMethod {
+        /// Name of the get method
+        method: String,
+        /// Exit code of the TVM
+        exit_code: i32,
+    },
+    /// Base64 decoding error
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+    /// Cell (de)serialization error
+    #[error(transparent)]
+    Cell(#[from] TonCellError),
+    /// Wallet error
+    #[error(transparent)]
+    Wallet(#[from] TonWalletError),
+    /// Invalid signing key
+    #[error(transparent)]
+    Signature(#[from] ed25519_dalek::SignatureError),
+    /// A response didn't have the expected shape
+    #[error("Unexpected response from the API: {0}")]
+    UnexpectedResponse(String),
+    /// A sent message wasn't executed in time
+    #[error("Message {0} wasn't executed in time")]
+    MessageTimeout(String),
+}
+
+impl From<HyperlaneTonError> for ChainCommunicationError {
+    fn from(value: HyperlaneTonError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use hyperlane_core::{ChainResult, LogMeta, H256};
+use tonlib_core::cell::ArcCell;
+
+use crate::{
+    cells::{event_op, parse_boc, parse_hash, raw_address},
+    types::parse_int,
+    TonProvider,
+};
+
+/// Number of transactions to query at once
+const PAGE_SIZE: u32 = 100;
+
+/// Most seconds a shard block is created before the masterchain block it's
+/// committed in
+const COMMIT_DELAY: u64 = 60;
+
+/// Fetches the events of a type emitted by a contract. Events are external
+/// out messages, whose body starts with the op code of the event.
+#[derive(Debug, Clone)]
+pub(crate) struct EventFetcher {
+    provider: TonProvider,
+    contract: H256,
+    op: u32,
+    /// Number of blocks behind the latest block to index up to
+    reorg_period: u32,
+}
+
+impl EventFetcher {
+    pub fn new(provider: TonProvider, contract: H256, op: u32, reorg_period: u32) -> Self {
+        Self {
+            provider,
+            contract,
+            op,
+            reorg_period,
+        }
+    }
+
+    /// The latest masterchain block considered final
+    pub async fn finalized_block_number(&self) -> ChainResult<u32> {
+        let latest = self.provider.block_number().await?;
+        Ok(latest.saturating_sub(self.reorg_period))
+    }
+
+    /// The bodies of the events emitted in the transactions committed in the
+    /// masterchain blocks of `range`, in order, with where they were emitted.
+    /// Transactions are queried by time, from a bit before the first block
+    /// to include those of shard blocks committed late.
+    pub async fn fetch(&self, range: RangeInclusive<u32>) -> ChainResult<Vec<(ArcCell, LogMeta)>> {
+        let client = self.provider.client();
+        let first = client.block(*range.start()).await?;
+        let last = client.block(*range.end()).await?;
+        let start_utime = parse_int::<u64>(&first.gen_utime)?.saturating_sub(COMMIT_DELAY);
+        let end_utime = parse_int::<u64>(&last.gen_utime)?;
+        let mut block_hashes = HashMap::from([
+            (first.seqno, parse_hash(&first.root_hash)?),
+            (last.seqno, parse_hash(&last.root_hash)?),
+        ]);
+
+        let account = raw_address(self.contract);
+        let mut events = vec![];
+        let mut offset = 0;
+        loop {
+            let transactions = client
+                .transactions(&account, start_utime, end_utime, PAGE_SIZE, offset)
+                .await?;
+            let page_len = transactions.len() as u32;
+
+            for transaction in transactions {
+                let Some(seqno) = transaction
+                    .mc_block_seqno
+                    .filter(|seqno| range.contains(seqno))
+                else {
+                    continue;
+                };
+                for (log_index, message) in transaction.out_msgs.iter().enumerate() {
+                    let (None, Some(content)) = (&message.destination, &message.message_content)
+                    else {
+                        continue;
+                    };
+                    let body = parse_boc(&content.body)?;
+                    if event_op(&body) != Some(self.op) {
+                        continue;
+                    }
+                    let block_hash = match block_hashes.get(&seqno) {
+                        Some(hash) => *hash,
+                        None => {
+                            let hash = parse_hash(&client.block(seqno).await?.root_hash)?;
+                            block_hashes.insert(seqno, hash);
+                            hash
+                        }
+                    };
+                    let meta = LogMeta {
+                        address: self.contract,
+                        block_number: seqno.into(),
+                        block_hash,
+                        transaction_id: parse_hash(&transaction.hash)?.into(),
+                        // Logical times order the transactions of an account
+                        transaction_index: parse_int(&transaction.lt)?,
+                        log_index: (log_index as u64).into(),
+                    };
+                    events.push((body, meta));
+                }
+            }
+
+            if page_len < PAGE_SIZE {
+                return Ok(events);
+            }
+            offset += page_len;
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, Indexed, Indexer, InterchainGasPaymaster, InterchainGasPayment, LogMeta,
+    SequenceAwareIndexer, H256, U256,
+};
+use tracing::instrument;
+
+use crate::{
+    cells::{event_fields, read_coins, read_h256},
+    events::EventFetcher,
+    ConnectionConf, HyperlaneTonError, TonProvider,
+};
+
+/// Op code of the event of a gas payment: `gas_payment#... message_id:uint256
+/// destination:uint32 gas_amount:uint256 payment:Coins`
+const EVENT_GAS_PAYMENT: u32 = 0x3c8d_52e6;
+
+/// A reference to an IGP contract on some TON chain
+#[derive(Debug)]
+pub struct TonInterchainGasPaymaster {
+    provider: TonProvider,
+    address: H256,
+}
+
+impl TonInterchainGasPaymaster {
+    /// Create a new TON IGP.
+    pub fn new(conf: &ConnectionConf, locator: &ContractLocator) -> Self {
+        Self {
+            provider: TonProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for TonInterchainGasPaymaster {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonInterchainGasPaymaster {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+impl InterchainGasPaymaster for TonInterchainGasPaymaster {}
+
+/// Struct that retrieves event data for a TON IGP contract
+#[derive(Debug)]
+pub struct TonInterchainGasPaymasterIndexer {
+    payments: EventFetcher,
+}
+
+impl TonInterchainGasPaymasterIndexer {
+    /// Create a new TON IGP indexer.
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, reorg_period: u32) -> Self {
+        let provider = TonProvider::new(locator.domain.clone(), conf, None);
+        Self {
+            payments: EventFetcher::new(provider, locator.address, EVENT_GAS_PAYMENT, reorg_period),
+        }
+    }
+}
+
+#[async_trait]
+impl Indexer<InterchainGasPayment> for TonInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        self.payments
+            .fetch(range)
+            .await?
+            .into_iter()
+            .map(|(body, meta)| {
+                let mut fields = event_fields(&body)?;
+                let payment = InterchainGasPayment {
+                    message_id: read_h256(&mut fields)?,
+                    destination: fields.load_u32(32).map_err(HyperlaneTonError::from)?,
+                    gas_amount: U256::from_big_endian(read_h256(&mut fields)?.as_bytes()),
+                    payment: read_coins(&mut fields)?,
+                };
+                Ok((payment.into(), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.payments.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for TonInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.payments.finalized_block_number().await?;
+
+        // No sequence for gas payments.
+        Ok((None, tip))
+    }
+}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType, H256, U256,
+};
+use num_traits::cast::FromPrimitive;
+use tracing::{instrument, warn};
+
+use crate::{ConnectionConf, TonProvider};
+
+/// A reference to an InterchainSecurityModule contract on some TON chain
+#[derive(Debug)]
+pub struct TonInterchainSecurityModule {
+    provider: TonProvider,
+    address: H256,
+}
+
+impl TonInterchainSecurityModule {
+    /// Create a new TON InterchainSecurityModule
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: TonProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for TonInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for TonInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let module = self
+            .provider
+            .run_get_method(self.address, "get_module_type", vec![])
+            .await?
+            .u64()?;
+
+        if let Some(module_type) = ModuleType::from_u64(module) {
+            Ok(module_type)
+        } else {
+            warn!(%module, "Unknown module type");
+            Ok(ModuleType::Unused)
+        }
+    }
+
+    /// ISMs verify in the transaction processing the message, as contracts
+    /// can't call each other synchronously. Assume the metadata verifies and
+    /// let the processing catch invalid metadata.
+    #[instrument(err, ret, skip(self, _metadata))]
+    async fn dry_run_verify(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        Ok(Some(U256::zero()))
+    }
+}
@@ -0,0 +1,43 @@
+//! Implementation of hyperlane for TON.
+//!
+//! Talks to a toncenter v3 API. Contracts are read with get methods and sent
+//! messages from the signer's wallet, with external messages to the wallet.
+//! Messages to contracts start with an op code and a query id, and carry
+//! values that don't fit in a cell, such as message bodies, as snake cells.
+//! Contracts emit events as external out messages starting with the op code
+//! of the event, which are indexed by masterchain block.
+//! - Mailbox: `get_nonce`, `get_delivered` and `get_default_ism`; processes
+//!   messages and emits `dispatch` and `process_id`. Recipients name their
+//!   ISM with `get_ism`.
+//! - Merkle tree hook: `get_count` and `get_tree`; emits
+//!   `inserted_into_tree`
+//! - IGP: emits `gas_payment`
+//! - ISMs: `get_module_type`, `get_validators_and_threshold` and
+//!   `get_route`
+//! - Validator announce: `get_storage_locations`; announces validators
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+#![deny(warnings)]
+
+pub use self::{
+    error::*, interchain_gas::*, interchain_security_module::*, mailbox::*, merkle_tree_hook::*,
+    multisig_ism::*, provider::*, routing_ism::*, signers::*, trait_builder::*,
+    validator_announce::*,
+};
+
+mod cells;
+mod client;
+mod error;
+mod events;
+mod interchain_gas;
+mod interchain_security_module;
+mod mailbox;
+mod merkle_tree_hook;
+mod multisig_ism;
+mod provider;
+mod routing_ism;
+mod signers;
+mod trait_builder;
+mod types;
+mod validator_announce;
@@ -0,0 +1,242 @@
+use std::{num::NonZeroU64, ops::RangeInclusive};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+use tonlib_core::cell::Cell;
+use tracing::instrument;
+
+use crate::{
+    cells::{
+        boc_bytes, event_fields, first_reference, h256_arg, message_cell, op_body, read_h256,
+        read_message, snake_cell,
+    },
+    events::EventFetcher,
+    ConnectionConf, Signer, TonProvider,
+};
+
+/// Op code of the message processing a message: `process#... query_id:uint64
+/// message:^Message metadata:^Snake`
+const OP_PROCESS: u32 = 0xa4e2_1f3d;
+
+/// Op code of the event of a dispatched message: `dispatch#...
+/// message:^Message`
+const EVENT_DISPATCH: u32 = 0x5d1a_0c73;
+
+/// Op code of the event of a processed message: `process_id#... id:uint256`
+const EVENT_PROCESS_ID: u32 = 0x6b2c_8e91;
+
+/// A reference to a Mailbox contract on some TON chain
+#[derive(Debug)]
+pub struct TonMailbox {
+    provider: TonProvider,
+    address: H256,
+}
+
+impl TonMailbox {
+    /// Create a new TON mailbox
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, signer: Option<Signer>) -> Self {
+        Self {
+            provider: TonProvider::new(locator.domain.clone(), conf, signer),
+            address: locator.address,
+        }
+    }
+
+    fn process_body(message: &HyperlaneMessage, metadata: &[u8]) -> ChainResult<Cell> {
+        op_body(
+            OP_PROCESS,
+            &[],
+            vec![message_cell(message)?, snake_cell(metadata)?],
+        )
+    }
+
+    async fn nonce(&self) -> ChainResult<u32> {
+        self.provider
+            .run_get_method(self.address, "get_nonce", vec![])
+            .await?
+            .u32()
+    }
+}
+
+impl HyperlaneContract for TonMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for TonMailbox {
+    /// Get methods only run against the latest state, and masterchain
+    /// blocks are final, so the lag is ignored
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, _lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        self.nonce().await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        self.provider
+            .run_get_method(self.address, "get_delivered", vec![h256_arg(id)])
+            .await?
+            .bool()
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        self.provider
+            .run_get_method(self.address, "get_default_ism", vec![])
+            .await?
+            .address()?
+            .ok_or_else(|| ChainCommunicationError::from_contract_error_str("No default ISM"))
+    }
+
+    /// Recipients set their ISM with the `get_ism` get method, falling back
+    /// to the default ISM if they don't have it or it returns `addr_none`
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let ism = match self
+            .provider
+            .try_run_get_method(recipient, "get_ism", vec![])
+            .await?
+        {
+            Some(mut stack) => stack.address()?,
+            None => None,
+        };
+        match ism {
+            Some(ism) => Ok(ism),
+            None => self.default_ism().await,
+        }
+    }
+
+    /// Messages carry the configured value to pay for their execution, so
+    /// there's no gas limit to set
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        _tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        self.provider
+            .send(self.address, Self::process_body(message, metadata)?)
+            .await
+    }
+
+    #[instrument(err, ret, skip(self, metadata))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        self.provider
+            .estimate_send(self.address, Self::process_body(message, metadata)?)
+            .await
+    }
+
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        Self::process_body(message, metadata)
+            .and_then(boc_bytes)
+            .unwrap_or_default()
+    }
+}
+
+/// Struct that retrieves event data for a TON Mailbox contract
+#[derive(Debug)]
+pub struct TonMailboxIndexer {
+    mailbox: TonMailbox,
+    dispatches: EventFetcher,
+    processes: EventFetcher,
+}
+
+impl TonMailboxIndexer {
+    /// Create a new TON mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, reorg_period: u32) -> Self {
+        let mailbox = TonMailbox::new(conf, locator, None);
+        let fetcher =
+            |op| EventFetcher::new(mailbox.provider.clone(), mailbox.address, op, reorg_period);
+        Self {
+            dispatches: fetcher(EVENT_DISPATCH),
+            processes: fetcher(EVENT_PROCESS_ID),
+            mailbox,
+        }
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for TonMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        self.dispatches
+            .fetch(range)
+            .await?
+            .into_iter()
+            .map(|(body, meta)| Ok((read_message(first_reference(&body)?)?.into(), meta)))
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.dispatches.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for TonMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.dispatches.finalized_block_number().await?;
+        // The nonce is read from the latest state, which may be ahead of the
+        // tip
+        let count = self.mailbox.nonce().await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for TonMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        self.processes
+            .fetch(range)
+            .await?
+            .into_iter()
+            .map(|(body, meta)| {
+                let id = read_h256(&mut event_fields(&body)?)?;
+                Ok((Indexed::new(id), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.processes.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for TonMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.processes.finalized_block_number().await?;
+
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
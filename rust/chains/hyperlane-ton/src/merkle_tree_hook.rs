@@ -0,0 +1,170 @@
+use std::{num::NonZeroU64, ops::RangeInclusive};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    accumulator::{incremental::IncrementalMerkle, TREE_DEPTH},
+    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta,
+    MerkleTreeHook, MerkleTreeInsertion, SequenceAwareIndexer, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    cells::{event_fields, read_h256, StackReader},
+    events::EventFetcher,
+    ConnectionConf, HyperlaneTonError, TonProvider,
+};
+
+/// Op code of the event of a message id inserted into the tree:
+/// `inserted_into_tree#... id:uint256 index:uint32`
+const EVENT_INSERTED_INTO_TREE: u32 = 0x1f7e_93b4;
+
+/// A reference to a MerkleTreeHook contract on some TON chain
+#[derive(Debug)]
+pub struct TonMerkleTreeHook {
+    provider: TonProvider,
+    address: H256,
+}
+
+impl TonMerkleTreeHook {
+    /// Create a new TON merkle tree hook
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: TonProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+
+    async fn get_count(&self) -> ChainResult<u32> {
+        self.provider
+            .run_get_method(self.address, "get_count", vec![])
+            .await?
+            .u32()
+    }
+}
+
+impl HyperlaneContract for TonMerkleTreeHook {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonMerkleTreeHook {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+/// Get methods only run against the latest state, and masterchain blocks are
+/// final, so lags are ignored
+#[async_trait]
+impl MerkleTreeHook for TonMerkleTreeHook {
+    /// The tree is returned as the tuple of its branch and its count
+    #[instrument(err, ret, skip(self))]
+    async fn tree(&self, _lag: Option<NonZeroU64>) -> ChainResult<IncrementalMerkle> {
+        let mut stack = self
+            .provider
+            .run_get_method(self.address, "get_tree", vec![])
+            .await?;
+        let branch: [H256; TREE_DEPTH] =
+            stack
+                .tuple(StackReader::h256)?
+                .try_into()
+                .map_err(|branch: Vec<H256>| {
+                    HyperlaneTonError::UnexpectedResponse(format!(
+                        "Merkle tree branch of length {}",
+                        branch.len()
+                    ))
+                })?;
+        let count = stack.u32()?;
+        Ok(IncrementalMerkle::new(branch, count as usize))
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, _lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        self.get_count().await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn latest_checkpoint(&self, lag: Option<NonZeroU64>) -> ChainResult<Checkpoint> {
+        let tree = self.tree(lag).await?;
+
+        let count: u32 = tree
+            .count()
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        let index = count.checked_sub(1).ok_or_else(|| {
+            ChainCommunicationError::from_contract_error_str(
+                "Outbox is empty, cannot compute checkpoint",
+            )
+        })?;
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address,
+            mailbox_domain: self.domain().id(),
+            root: tree.root(),
+            index,
+        })
+    }
+}
+
+/// Struct that retrieves event data for a TON merkle tree hook contract
+#[derive(Debug)]
+pub struct TonMerkleTreeHookIndexer {
+    hook: TonMerkleTreeHook,
+    insertions: EventFetcher,
+}
+
+impl TonMerkleTreeHookIndexer {
+    /// Create a new TON merkle tree hook indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, reorg_period: u32) -> Self {
+        let hook = TonMerkleTreeHook::new(conf, locator);
+        let insertions = EventFetcher::new(
+            hook.provider.clone(),
+            hook.address,
+            EVENT_INSERTED_INTO_TREE,
+            reorg_period,
+        );
+        Self { hook, insertions }
+    }
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for TonMerkleTreeHookIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        self.insertions
+            .fetch(range)
+            .await?
+            .into_iter()
+            .map(|(body, meta)| {
+                let mut fields = event_fields(&body)?;
+                let id = read_h256(&mut fields)?;
+                let index = fields.load_u32(32).map_err(HyperlaneTonError::from)?;
+                Ok((MerkleTreeInsertion::new(index, id).into(), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.insertions.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for TonMerkleTreeHookIndexer {
+    #[instrument(err, skip(self))]
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.insertions.finalized_block_number().await?;
+        // The count is read from the latest state, which may be ahead of the
+        // tip
+        let count = self.hook.get_count().await?;
+        Ok((Some(count), tip))
+    }
+}
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+use tracing::instrument;
+
+use crate::{
+    cells::{num_arg, unexpected, StackReader},
+    ConnectionConf, TonProvider,
+};
+
+/// A reference to a MultisigIsm contract on some TON chain
+#[derive(Debug)]
+pub struct TonMultisigIsm {
+    provider: TonProvider,
+    address: H256,
+}
+
+impl TonMultisigIsm {
+    /// Create a new TON MultisigIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: TonProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for TonMultisigIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonMultisigIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for TonMultisigIsm {
+    /// Returns the validator and threshold needed to verify message. The
+    /// validators are the integers of their 20 byte Ethereum addresses.
+    #[instrument(err, ret, skip(self))]
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let mut stack = self
+            .provider
+            .run_get_method(
+                self.address,
+                "get_validators_and_threshold",
+                vec![num_arg(message.origin.into())],
+            )
+            .await?;
+        let validators = stack.tuple(StackReader::h256)?;
+        let threshold = stack.u32()?;
+        let threshold = threshold
+            .try_into()
+            .map_err(|_| unexpected(format!("Threshold {threshold} is too large")))?;
+        Ok((validators, threshold))
+    }
+}
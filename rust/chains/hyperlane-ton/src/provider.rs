@@ -0,0 +1,339 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, TxCostEstimate, TxOutcome, TxnInfo, TxnReceiptInfo, H256, U256,
+};
+use num_bigint::BigUint;
+use tokio::time::{sleep, Instant};
+use tonlib_core::cell::{Cell, CellBuilder};
+use tracing::{info, instrument, warn};
+
+use crate::{
+    cells::{
+        encode_boc, encode_hash, parse_address, parse_hash, raw_address, ton_address, unexpected,
+        StackReader,
+    },
+    client::TonClient,
+    types::{parse_int, Account, Block, StackEntry, Transaction},
+    ConnectionConf, HyperlaneTonError, Signer,
+};
+
+/// How long external messages are valid for after they're signed
+const MESSAGE_TTL: Duration = Duration::from_secs(60);
+
+/// How often to check whether a sent message was executed
+const MESSAGE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long to wait for a sent message to be executed
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Abstraction over a connection to a toncenter API
+#[derive(Debug, Clone)]
+pub struct TonProvider {
+    domain: HyperlaneDomain,
+    conf: ConnectionConf,
+    client: TonClient,
+    signer: Option<Signer>,
+}
+
+impl TonProvider {
+    /// Create a provider connected to the API of `conf`, sending messages
+    /// from the wallet of `signer` if set
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            conf: conf.clone(),
+            client: TonClient::new(conf.url.clone(), conf.api_key.clone()),
+            signer,
+        }
+    }
+
+    pub(crate) fn client(&self) -> &TonClient {
+        &self.client
+    }
+
+    /// Sequence number of the latest masterchain block. Blocks are final
+    /// once in the masterchain.
+    pub(crate) async fn block_number(&self) -> ChainResult<u32> {
+        Ok(self.client.latest_block().await?.seqno)
+    }
+
+    /// Run a get method of a contract, reading its results off the stack
+    pub(crate) async fn run_get_method(
+        &self,
+        contract: H256,
+        method: &str,
+        stack: Vec<StackEntry>,
+    ) -> ChainResult<StackReader> {
+        let stack = self
+            .client
+            .run_get_method(&raw_address(contract), method, stack)
+            .await?;
+        Ok(StackReader::new(stack))
+    }
+
+    /// Run a get method of a contract, unset if it exits with an error, such
+    /// as when the contract doesn't have it
+    pub(crate) async fn try_run_get_method(
+        &self,
+        contract: H256,
+        method: &str,
+        stack: Vec<StackEntry>,
+    ) -> ChainResult<Option<StackReader>> {
+        match self
+            .client
+            .run_get_method(&raw_address(contract), method, stack)
+            .await
+        {
+            Ok(stack) => Ok(Some(StackReader::new(stack))),
+            Err(HyperlaneTonError::GetMethod { .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The address of the wallet sending messages, if any
+    pub(crate) fn signer_address(&self) -> Option<H256> {
+        self.signer.as_ref().map(Signer::address)
+    }
+
+    /// The signed body of an external message to the wallet of the signer,
+    /// having it send `body` to a contract, and the external message itself
+    async fn external_message(&self, contract: H256, body: Cell) -> ChainResult<(Cell, Cell)> {
+        let signer = self.signer()?;
+        let wallet = signer.wallet();
+        let seqno = self.client.wallet_seqno(&signer.address_string()).await?;
+        let internal = internal_message(contract, self.conf.message_value, body)?;
+        let expire_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .saturating_add(MESSAGE_TTL)
+            .as_secs() as u32;
+
+        let unsigned = wallet
+            .create_external_body(
+                expire_at,
+                seqno.unwrap_or_default(),
+                vec![Arc::new(internal)],
+            )
+            .map_err(HyperlaneTonError::from)?;
+        let signed = wallet
+            .sign_external_body(&unsigned)
+            .map_err(HyperlaneTonError::from)?;
+        // The first message of a wallet deploys it
+        let message = wallet
+            .wrap_signed_body(signed.clone(), seqno.is_none())
+            .map_err(HyperlaneTonError::from)?;
+        Ok((signed, message))
+    }
+
+    /// Have the wallet of the signer send a message to a contract, attaching
+    /// the configured value, and wait for the contract to execute it
+    #[instrument(err, skip(self, body))]
+    pub(crate) async fn send(&self, contract: H256, body: Cell) -> ChainResult<TxOutcome> {
+        let (_, message) = self.external_message(contract, body).await?;
+        let hash = self.client.send_message(&encode_boc(message)?).await?;
+        info!(hash, "Sent external message");
+
+        let wallet_transaction = self.wait_for_transaction(&hash).await?;
+        let mut fees: u64 = parse_int(&wallet_transaction.total_fees)?;
+        let mut executed = wallet_transaction.success();
+        if executed {
+            let sent = wallet_transaction.out_msgs.first().ok_or_else(|| {
+                unexpected(format!(
+                    "Wallet transaction {} sent no message",
+                    wallet_transaction.hash
+                ))
+            })?;
+            let contract_transaction = self.wait_for_transaction(&sent.hash).await?;
+            fees += parse_int::<u64>(&contract_transaction.total_fees)?;
+            executed = contract_transaction.success();
+            if !executed {
+                warn!(
+                    hash = contract_transaction.hash,
+                    exit_code = ?contract_transaction
+                        .description
+                        .compute_ph
+                        .as_ref()
+                        .and_then(|compute| compute.exit_code),
+                    "Contract failed to execute message"
+                );
+            }
+        }
+
+        // Fees are reported as gas at a price of one nanoton
+        Ok(TxOutcome {
+            transaction_id: parse_hash(&wallet_transaction.hash)?.into(),
+            executed,
+            gas_used: fees.into(),
+            gas_price: U256::one().try_into()?,
+        })
+    }
+
+    /// The transaction executing a message, waiting for it to be executed
+    async fn wait_for_transaction(&self, message_hash: &str) -> ChainResult<Transaction> {
+        let start = Instant::now();
+        loop {
+            if let Some(transaction) = self.client.transaction_by_message(message_hash).await? {
+                return Ok(transaction);
+            }
+            if start.elapsed() > MESSAGE_TIMEOUT {
+                return Err(HyperlaneTonError::MessageTimeout(message_hash.to_owned()).into());
+            }
+            sleep(MESSAGE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Estimate the fees of having the wallet of the signer send a message
+    /// to a contract. The contract's own fees are paid out of the attached
+    /// value, so they're assumed to be all of it.
+    #[instrument(err, skip(self, body))]
+    pub(crate) async fn estimate_send(
+        &self,
+        contract: H256,
+        body: Cell,
+    ) -> ChainResult<TxCostEstimate> {
+        let signer = self.signer()?;
+        let (signed, _) = self.external_message(contract, body).await?;
+        let fee = self
+            .client
+            .estimate_fee(&signer.address_string(), &encode_boc(signed)?)
+            .await?;
+        Ok(TxCostEstimate {
+            gas_limit: U256::from(fee) + U256::from(self.conf.message_value),
+            gas_price: U256::one().try_into()?,
+            l2_gas_limit: None,
+        })
+    }
+
+    /// The account at an address, unset if it doesn't exist
+    async fn account(&self, address: H256) -> ChainResult<Option<Account>> {
+        match self.client.account(&raw_address(address)).await {
+            Ok(account) => Ok(Some(account)),
+            Err(HyperlaneTonError::Api { status: 404, .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Balance of an account, in nanotons
+    pub(crate) async fn balance(&self, address: H256) -> ChainResult<U256> {
+        match self.account(address).await? {
+            Some(account) => Ok(U256::from(parse_int::<u128>(&account.balance)?)),
+            None => Ok(U256::zero()),
+        }
+    }
+}
+
+impl HyperlaneChain for TonProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for TonProvider {
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        // The API only looks blocks up by sequence number
+        Err(ChainCommunicationError::BlockNotFound(*hash))
+    }
+
+    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
+        let transaction = self
+            .client
+            .transaction(&encode_hash(*hash))
+            .await?
+            .ok_or_else(|| unexpected(format!("Transaction {hash:?} not found")))?;
+        let account = parse_address(&transaction.account)?;
+        // External messages have no source, the account sent them itself
+        let sender = match transaction
+            .in_msg
+            .as_ref()
+            .and_then(|message| message.source.as_deref())
+        {
+            Some(source) => parse_address(source)?,
+            None => account,
+        };
+        let fees = U256::from(parse_int::<u64>(&transaction.total_fees)?);
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit: fees,
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: Some(U256::one()),
+            nonce: 0,
+            sender,
+            recipient: Some(account),
+            receipt: Some(TxnReceiptInfo {
+                gas_used: fees,
+                cumulative_gas_used: fees,
+                effective_gas_price: Some(U256::one()),
+            }),
+        })
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        Ok(self.account(*address).await?.map_or(false, |account| {
+            account.status == "active" && account.code.is_some()
+        }))
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        self.balance(parse_address(&address)?).await
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        let block = self.client.latest_block().await?;
+        Ok(Some(ChainInfo::new(block_info(&block)?, None)))
+    }
+}
+
+pub(crate) fn block_info(block: &Block) -> ChainResult<BlockInfo> {
+    Ok(BlockInfo {
+        hash: parse_hash(&block.root_hash)?,
+        timestamp: parse_int(&block.gen_utime)?,
+        number: block.seqno.into(),
+    })
+}
+
+/// An internal message from the wallet to a contract carrying `value`
+/// nanotons, bouncing back if the contract fails to execute it
+fn internal_message(contract: H256, value: u64, body: Cell) -> ChainResult<Cell> {
+    let mut builder = CellBuilder::new();
+    builder
+        // int_msg_info$0 ihr_disabled:Bool bounce:Bool bounced:Bool
+        .store_bit(false)
+        .and_then(|b| b.store_bit(true))
+        .and_then(|b| b.store_bit(true))
+        .and_then(|b| b.store_bit(false))
+        // The source is filled in by the wallet
+        .and_then(|b| b.store_u8(2, 0))
+        .and_then(|b| b.store_address(&ton_address(contract)))
+        .and_then(|b| b.store_coins(&BigUint::from(value)))
+        // No extra currencies, forwarding fees, logical time, creation time
+        // or state init
+        .and_then(|b| b.store_bit(false))
+        .and_then(|b| b.store_coins(&BigUint::default()))
+        .and_then(|b| b.store_coins(&BigUint::default()))
+        .and_then(|b| b.store_u64(64, 0))
+        .and_then(|b| b.store_u32(32, 0))
+        .and_then(|b| b.store_bit(false))
+        // The body is in a reference
+        .and_then(|b| b.store_bit(true))
+        .and_then(|b| b.store_child(body))
+        .map_err(HyperlaneTonError::from)?;
+    Ok(builder.build().map_err(HyperlaneTonError::from)?)
+}
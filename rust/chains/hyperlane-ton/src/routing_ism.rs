@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RoutingIsm, H256,
+};
+use tracing::instrument;
+
+use crate::{cells::num_arg, ConnectionConf, TonProvider};
+
+/// A reference to a RoutingIsm contract on some TON chain
+#[derive(Debug)]
+pub struct TonRoutingIsm {
+    provider: TonProvider,
+    address: H256,
+}
+
+impl TonRoutingIsm {
+    /// Create a new TON RoutingIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            provider: TonProvider::new(locator.domain.clone(), conf, None),
+            address: locator.address,
+        }
+    }
+}
+
+impl HyperlaneContract for TonRoutingIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonRoutingIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for TonRoutingIsm {
+    #[instrument(err, ret, skip(self))]
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        self.provider
+            .run_get_method(
+                self.address,
+                "get_route",
+                vec![num_arg(message.origin.into())],
+            )
+            .await?
+            .address()?
+            .ok_or_else(|| {
+                ChainCommunicationError::CustomError(format!(
+                    "No ISM routed for origin {}",
+                    message.origin
+                ))
+            })
+    }
+}
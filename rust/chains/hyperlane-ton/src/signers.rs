@@ -0,0 +1,59 @@
+use std::fmt::{Debug, Formatter};
+
+use ed25519_dalek::{PublicKey, SecretKey};
+use hyperlane_core::{ChainResult, H256};
+use tonlib_core::{
+    mnemonic::KeyPair,
+    wallet::{TonWallet, WalletVersion},
+};
+
+use crate::{cells::h256_from_address, HyperlaneTonError};
+
+/// Signer of the external messages of a v4r2 wallet with an Ed25519 key.
+/// The wallet sends the messages to contracts on its behalf.
+#[derive(Clone)]
+pub struct Signer {
+    wallet: TonWallet,
+    address: H256,
+}
+
+impl Signer {
+    /// Create a signer of the wallet of a 32 byte private key
+    pub fn new(private_key: &[u8]) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key).map_err(HyperlaneTonError::from)?;
+        let public_key = PublicKey::from(&secret);
+        // The secret key of a key pair is the private key followed by the
+        // public key, the way NaCl expects it
+        let key_pair = KeyPair {
+            public_key: public_key.to_bytes().to_vec(),
+            secret_key: [secret.to_bytes(), public_key.to_bytes()].concat(),
+        };
+        let wallet = TonWallet::derive_default(WalletVersion::V4R2, &key_pair)
+            .map_err(HyperlaneTonError::from)?;
+        let address = h256_from_address(&wallet.address)?;
+        Ok(Self { wallet, address })
+    }
+
+    /// The address of the wallet
+    pub fn address(&self) -> H256 {
+        self.address
+    }
+
+    /// The address of the wallet, in the non bounceable user friendly form
+    /// wallets are shared in
+    pub fn address_string(&self) -> String {
+        self.wallet.address.to_base64_url_flags(true, false)
+    }
+
+    pub(crate) fn wallet(&self) -> &TonWallet {
+        &self.wallet
+    }
+}
+
+impl Debug for Signer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signer")
+            .field("address", &self.address_string())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,19 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// Value attached to messages to contracts if not configured, in nanotons
+pub const DEFAULT_MESSAGE_VALUE: u64 = 100_000_000;
+
+/// TON connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Url of a toncenter v3 API, e.g. `https://toncenter.com/api/v3/`
+    pub url: Url,
+    /// Key of the API, raising its rate limits
+    pub api_key: Option<String>,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+    /// Value attached to the messages sent to contracts, in nanotons.
+    /// Contracts pay for their execution out of it and return the excess.
+    pub message_value: u64,
+}
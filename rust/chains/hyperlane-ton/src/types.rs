@@ -0,0 +1,167 @@
+//! Types of the toncenter v3 API. Integers that may not fit in a JSON number,
+//! such as logical times and amounts, are strings.
+
+use std::str::FromStr;
+
+use hyperlane_core::ChainResult;
+use serde::{Deserialize, Serialize};
+
+use crate::HyperlaneTonError;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiError {
+    pub error: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MasterchainInfo {
+    pub last: Block,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Blocks {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Block {
+    pub seqno: u32,
+    /// Base64 hash of the block
+    pub root_hash: String,
+    pub gen_utime: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Transactions {
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Transaction {
+    /// Raw address of the account, e.g. `0:ABCD...`
+    pub account: String,
+    /// Base64 hash of the transaction
+    pub hash: String,
+    /// Logical time of the transaction, ordering the transactions of an
+    /// account
+    pub lt: String,
+    /// Masterchain block the transaction was committed in, if it was
+    pub mc_block_seqno: Option<u32>,
+    /// Fees paid by the account for the transaction, in nanotons
+    pub total_fees: String,
+    pub description: TransactionDescription,
+    pub in_msg: Option<Message>,
+    pub out_msgs: Vec<Message>,
+}
+
+impl Transaction {
+    /// Whether the transaction's computation succeeded and it wasn't
+    /// rolled back
+    pub fn success(&self) -> bool {
+        !self.description.aborted
+            && self
+                .description
+                .compute_ph
+                .as_ref()
+                .map_or(false, |compute| compute.success.unwrap_or(false))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TransactionDescription {
+    pub aborted: bool,
+    pub compute_ph: Option<ComputePhase>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ComputePhase {
+    /// Unset if the computation was skipped
+    pub success: Option<bool>,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Message {
+    /// Base64 hash of the message
+    pub hash: String,
+    /// Raw address of the sender, unset for external messages
+    pub source: Option<String>,
+    /// Raw address of the recipient, unset for external out messages, which
+    /// contracts emit as events
+    pub destination: Option<String>,
+    pub message_content: Option<MessageContent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct MessageContent {
+    /// Base64 bag of cells of the body
+    pub body: String,
+}
+
+/// An entry of the stack of a get method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub(crate) enum StackEntry {
+    /// A hex integer, e.g. `0x2a`
+    Num(String),
+    /// A base64 bag of cells
+    Cell(String),
+    /// A base64 bag of cells, read from its start
+    Slice(String),
+    Tuple(Vec<StackEntry>),
+    List(Vec<StackEntry>),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GetMethodResult {
+    pub exit_code: i32,
+    pub stack: Vec<StackEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SentMessage {
+    /// Base64 hash of the message
+    pub message_hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FeeEstimate {
+    pub source_fees: Fees,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Fees {
+    pub in_fwd_fee: u64,
+    pub storage_fee: u64,
+    pub gas_fee: u64,
+    pub fwd_fee: u64,
+}
+
+impl Fees {
+    pub fn total(&self) -> u64 {
+        self.in_fwd_fee + self.storage_fee + self.gas_fee + self.fwd_fee
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Account {
+    /// Balance in nanotons
+    pub balance: String,
+    /// Base64 bag of cells of the code, unset if the account has none
+    pub code: Option<String>,
+    /// `active`, `uninit`, `frozen` or `nonexist`
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct WalletInformation {
+    /// Unset if the wallet isn't deployed
+    pub seqno: Option<u32>,
+}
+
+/// An integer the API writes as a string
+pub(crate) fn parse_int<T: FromStr>(value: &str) -> ChainResult<T> {
+    value
+        .parse()
+        .map_err(|_| HyperlaneTonError::UnexpectedResponse(value.to_owned()).into())
+}
@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    Announcement, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, SignedType, TxOutcome, ValidatorAnnounce, H256, U256,
+};
+use tonlib_core::cell::Cell;
+use tracing::{instrument, warn};
+
+use crate::{
+    cells::{h256_arg, op_body, read_snake, snake_cell, unexpected, StackReader},
+    ConnectionConf, Signer, TonProvider,
+};
+
+/// Op code of the message announcing a validator: `announce#...
+/// query_id:uint64 validator:uint160 storage_location:^Snake
+/// signature:^Snake`
+const OP_ANNOUNCE: u32 = 0x980b_3d44;
+
+/// A reference to a ValidatorAnnounce contract on some TON chain
+#[derive(Debug)]
+pub struct TonValidatorAnnounce {
+    provider: TonProvider,
+    address: H256,
+}
+
+impl TonValidatorAnnounce {
+    /// Create a new TON ValidatorAnnounce
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, signer: Option<Signer>) -> Self {
+        Self {
+            provider: TonProvider::new(locator.domain.clone(), conf, signer),
+            address: locator.address,
+        }
+    }
+
+    fn announce_body(announcement: &SignedType<Announcement>) -> ChainResult<Cell> {
+        op_body(
+            OP_ANNOUNCE,
+            announcement.value.validator.as_bytes(),
+            vec![
+                snake_cell(announcement.value.storage_location.as_bytes())?,
+                snake_cell(&announcement.signature.to_vec())?,
+            ],
+        )
+    }
+}
+
+impl HyperlaneContract for TonValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for TonValidatorAnnounce {
+    /// Storage locations are returned per validator, as a tuple of snake
+    /// strings
+    #[instrument(err, ret, skip(self))]
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        let mut locations = Vec::with_capacity(validators.len());
+        for validator in validators {
+            let validator_locations = self
+                .provider
+                .run_get_method(
+                    self.address,
+                    "get_storage_locations",
+                    vec![h256_arg(*validator)],
+                )
+                .await?
+                .tuple(|stack: &mut StackReader| {
+                    let location = read_snake(&stack.cell()?)?;
+                    String::from_utf8(location).map_err(|err| unexpected(err.to_string()).into())
+                })?;
+            locations.push(validator_locations);
+        }
+        Ok(locations)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        self.provider
+            .send(self.address, Self::announce_body(&announcement)?)
+            .await
+    }
+
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        let Some(signer) = self.provider.signer_address() else {
+            warn!(?announcement, "Cannot announce without a signer");
+            return None;
+        };
+        let body = Self::announce_body(&announcement).ok()?;
+        let estimate = self.provider.estimate_send(self.address, body).await.ok()?;
+        let balance = self.provider.balance(signer).await.ok()?;
+        Some(estimate.gas_limit.saturating_sub(balance))
+    }
+}
@@ -2,17 +2,19 @@
 //! was designed specifically for use with the quorum provider.
 
 use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
 use derive_builder::Builder;
 use derive_new::new;
 use ethers::prelude::JsonRpcClient;
+use ethers::providers::RpcError;
 use ethers_core::types::U64;
-use hyperlane_core::rpc_clients::BlockNumberGetter;
+use hyperlane_core::rpc_clients::{BlockNumberGetter, RateLimitConf, TokenBucket};
 use hyperlane_core::ChainCommunicationError;
 use maplit::hashmap;
-use prometheus::{CounterVec, IntCounterVec};
+use prometheus::{CounterVec, HistogramVec, IntCounterVec};
 use serde::{de::DeserializeOwned, Serialize};
 
 pub use crate::ChainInfo;
@@ -51,6 +53,35 @@ pub struct JsonRpcClientMetrics {
     ///   might still be an "error" but not one with the transport layer.
     #[builder(setter(into, strip_option), default)]
     request_duration_seconds: Option<CounterVec>,
+
+    /// Total number of requests delayed by the client-side rate limit.
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the request was made on.
+    /// - `method`: request method string.
+    #[builder(setter(into, strip_option), default)]
+    throttled_request_count: Option<IntCounterVec>,
+
+    /// Latency of requests, including failed ones.
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the request was made on.
+    /// - `method`: request method string.
+    #[builder(setter(into, strip_option), default)]
+    request_latency_seconds: Option<HistogramVec>,
+
+    /// Total number of failed requests.
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the request was made on.
+    /// - `method`: request method string.
+    /// - `error`: `rate_limited` or `json_rpc` if the node responded with an
+    ///   error, `transport` if there was no valid response.
+    #[builder(setter(into, strip_option), default)]
+    request_error_count: Option<IntCounterVec>,
 }
 
 /// Expected label names for the metric.
@@ -64,6 +95,25 @@ pub const REQUEST_DURATION_SECONDS_LABELS: &[&str] =
 /// Help string for the metric.
 pub const REQUEST_DURATION_SECONDS_HELP: &str = "Total number of seconds spent making requests";
 
+/// Expected label names for the metric.
+pub const THROTTLED_REQUEST_COUNT_LABELS: &[&str] = &["provider_node", "chain", "method"];
+/// Help string for the metric.
+pub const THROTTLED_REQUEST_COUNT_HELP: &str =
+    "Total number of requests delayed by the client-side rate limit";
+
+/// Expected label names for the metric.
+pub const REQUEST_LATENCY_SECONDS_LABELS: &[&str] = &["provider_node", "chain", "method"];
+/// Help string for the metric.
+pub const REQUEST_LATENCY_SECONDS_HELP: &str = "Latency of requests, including failed ones";
+/// Buckets of the metric, from fast reads up to slow `eth_getLogs` queries.
+pub const REQUEST_LATENCY_SECONDS_BUCKETS: &[f64] =
+    &[0.025, 0.05, 0.1, 0.25, 0.5, 1., 2.5, 5., 10., 30.];
+
+/// Expected label names for the metric.
+pub const REQUEST_ERROR_COUNT_LABELS: &[&str] = &["provider_node", "chain", "method", "error"];
+/// Help string for the metric.
+pub const REQUEST_ERROR_COUNT_HELP: &str = "Total number of failed requests";
+
 /// Configuration for the prometheus JsonRpcClioent. This can be loaded via
 /// serde.
 #[derive(Default, Clone, Debug)]
@@ -112,6 +162,8 @@ pub struct PrometheusJsonRpcClient<C> {
     inner: C,
     metrics: JsonRpcClientMetrics,
     config: PrometheusJsonRpcClientConfig,
+    #[new(default)]
+    rate_limit: Option<Arc<TokenBucket>>,
 }
 
 impl<C: Clone> Clone for PrometheusJsonRpcClient<C> {
@@ -120,6 +172,7 @@ impl<C: Clone> Clone for PrometheusJsonRpcClient<C> {
             inner: self.inner.clone(),
             metrics: self.metrics.clone(),
             config: self.config.clone(),
+            rate_limit: self.rate_limit.clone(),
         }
     }
 }
@@ -138,6 +191,14 @@ impl<C> PrometheusJsonRpcClient<C> {
     pub fn inner(&self) -> &C {
         &self.inner
     }
+
+    /// Delay requests to stay within the rate limit of `url`, so that the
+    /// node isn't made to reject them instead. Every client of the url in the
+    /// process shares the same limit.
+    pub fn with_rate_limit(mut self, url: &str, conf: RateLimitConf) -> Self {
+        self.rate_limit = Some(TokenBucket::shared(url, conf));
+        self
+    }
 }
 
 impl<C> PrometheusJsonRpcClientConfigExt for PrometheusJsonRpcClient<C> {
@@ -166,6 +227,21 @@ where
         T: Debug + Serialize + Send + Sync,
         R: DeserializeOwned,
     {
+        if let Some(rate_limit) = &self.rate_limit {
+            let delay = rate_limit.reserve();
+            if !delay.is_zero() {
+                if let Some(counter) = &self.metrics.throttled_request_count {
+                    counter
+                        .with(&hashmap! {
+                            "provider_node" => self.config.node_host(),
+                            "chain" => self.config.chain_name(),
+                            "method" => method,
+                        })
+                        .inc()
+                }
+                tokio::time::sleep(delay).await;
+            }
+        }
         let start = Instant::now();
         let res = self.inner.request(method, params).await;
         let labels = hashmap! {
@@ -177,15 +253,49 @@ where
         if let Some(counter) = &self.metrics.request_count {
             counter.with(&labels).inc()
         }
+        let elapsed = (Instant::now() - start).as_secs_f64();
         if let Some(counter) = &self.metrics.request_duration_seconds {
-            counter
-                .with(&labels)
-                .inc_by((Instant::now() - start).as_secs_f64())
+            counter.with(&labels).inc_by(elapsed)
         };
+        if let Some(histogram) = &self.metrics.request_latency_seconds {
+            histogram
+                .with(&hashmap! {
+                    "provider_node" => self.config.node_host(),
+                    "chain" => self.config.chain_name(),
+                    "method" => method,
+                })
+                .observe(elapsed)
+        }
+        if let (Some(counter), Err(err)) = (&self.metrics.request_error_count, &res) {
+            counter
+                .with(&hashmap! {
+                    "provider_node" => self.config.node_host(),
+                    "chain" => self.config.chain_name(),
+                    "method" => method,
+                    "error" => error_kind(err),
+                })
+                .inc()
+        }
         res
     }
 }
 
+/// The `error` label of a failed request
+fn error_kind(err: &impl RpcError) -> &'static str {
+    let Some(response) = err.as_error_response() else {
+        return "transport";
+    };
+    let message = response.message.to_ascii_lowercase();
+    if response.code == 429
+        || message.contains("rate limit")
+        || message.contains("too many requests")
+    {
+        "rate_limited"
+    } else {
+        "json_rpc"
+    }
+}
+
 impl<C: JsonRpcClient + 'static> From<PrometheusJsonRpcClient<C>>
     for JsonRpcBlockGetter<PrometheusJsonRpcClient<C>>
 {
@@ -1,15 +1,15 @@
 use std::{env, fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
-use eyre::Result;
+use eyre::{bail, Result};
 use hyperlane_core::config::*;
 use tracing::info;
 
 use crate::{
-    create_chain_metrics,
+    create_chain_metrics, diagnose_chains, doctor_requested,
     metrics::{create_agent_metrics, AgentMetrics, CoreMetrics},
-    settings::Settings,
-    ChainMetrics,
+    settings::{shutdown_otlp, Settings},
+    ChainMetrics, DoctorReport,
 };
 
 /// Properties shared across all hyperlane agents
@@ -26,6 +26,13 @@ pub trait LoadableFromSettings: AsRef<Settings> + Sized {
     /// Create a new instance of these settings by reading the configs and env
     /// vars.
     fn load() -> ConfigResult<Self>;
+
+    /// Check the loaded settings before any task is started, reporting every
+    /// problem with its config path. Agents with settings of their own should
+    /// extend the checks of the base settings.
+    fn validate(&self) -> ConfigResult<()> {
+        self.as_ref().validate(&ConfigPath::default())
+    }
 }
 
 /// A fundamental agent which does not make any assumptions about the tools
@@ -36,7 +43,7 @@ pub trait BaseAgent: Send + Sync + Debug {
     const AGENT_NAME: &'static str;
 
     /// The settings object for this agent
-    type Settings: LoadableFromSettings;
+    type Settings: LoadableFromSettings + Sync;
 
     /// Instantiate the agent from the standard settings object
     async fn from_settings(
@@ -52,6 +59,36 @@ pub trait BaseAgent: Send + Sync + Debug {
     /// Start running this agent.
     #[allow(clippy::async_yields_async)]
     async fn run(self);
+
+    /// Check what only this agent depends on for the `doctor` command, e.g.
+    /// its database. The chains of the settings are checked for every agent.
+    async fn doctor(
+        _settings: &Self::Settings,
+        _metrics: Arc<CoreMetrics>,
+        _report: &mut DoctorReport,
+    ) where
+        Self: Sized,
+    {
+    }
+}
+
+/// Resolves once the agent is asked to stop, by SIGTERM or ctrl-c. Agents
+/// with work in flight can wait on this to wind it down before exiting.
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = sigterm.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
 }
 
 /// Call this from `main` to fully initialize and run the agent for its entire
@@ -73,7 +110,12 @@ pub async fn agent_main<A: BaseAgent>() -> Result<()> {
     }
 
     let settings = A::Settings::load()?;
+    if doctor_requested() {
+        return doctor::<A>(settings).await;
+    }
+    settings.validate()?;
     let core_settings: &Settings = settings.as_ref();
+    core_settings.address_book().install();
 
     let metrics = settings.as_ref().metrics(A::AGENT_NAME)?;
     let tokio_server = core_settings.tracing.start_tracing(&metrics)?;
@@ -91,5 +133,34 @@ pub async fn agent_main<A: BaseAgent>() -> Result<()> {
     // This await will only end if a panic happens. We won't crash, but instead gracefully shut down
     agent.run().await;
     info!(agent = A::AGENT_NAME, "Shutting down agent...");
+    shutdown_otlp();
+    Ok(())
+}
+
+/// Diagnose the setup of the agent instead of running it, printing a
+/// pass/fail report of every check. Fails if any check did.
+async fn doctor<A: BaseAgent>(settings: A::Settings) -> Result<()> {
+    let mut report = DoctorReport::default();
+    report.record(
+        "config",
+        "settings",
+        settings
+            .validate()
+            .map(|_| "valid".to_owned())
+            .map_err(Into::into),
+    );
+    let core_settings: &Settings = settings.as_ref();
+    core_settings.address_book().install();
+
+    // Metrics aren't served, but building the chain clients records them
+    let metrics = core_settings.metrics(A::AGENT_NAME)?;
+    report.extend(diagnose_chains(core_settings, &metrics).await);
+    A::doctor(&settings, metrics, &mut report).await;
+
+    println!("{report}");
+    let failed = report.failures().count();
+    if failed > 0 {
+        bail!("{failed} {} doctor checks failed", A::AGENT_NAME);
+    }
     Ok(())
 }
@@ -0,0 +1,165 @@
+use std::{fmt::Debug, future::Future, ops::RangeInclusive, sync::Arc};
+
+use async_trait::async_trait;
+use derive_new::new;
+use hyperlane_core::{
+    rpc_clients::CircuitBreaker, BlockInfo, ChainCommunicationError, ChainInfo, ChainResult,
+    HyperlaneChain, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta,
+    SequenceAwareIndexer, TxnInfo, H256, H512, U256,
+};
+
+/// Make a call to a chain, failing fast with
+/// `ChainCommunicationError::CircuitOpen` while its circuit is open
+async fn call<T>(
+    breaker: &CircuitBreaker,
+    fut: impl Future<Output = ChainResult<T>>,
+) -> ChainResult<T> {
+    breaker
+        .check()
+        .map_err(ChainCommunicationError::CircuitOpen)?;
+    let result = fut.await;
+    breaker.record(result.is_ok());
+    result
+}
+
+/// An indexer whose calls go through the circuit breaker of its chain
+#[derive(Debug, new)]
+pub struct CircuitBreakingIndexer<I> {
+    inner: I,
+    breaker: Arc<CircuitBreaker>,
+}
+
+#[async_trait]
+impl<T, I> Indexer<T> for CircuitBreakingIndexer<I>
+where
+    T: Send + Sync + Debug + 'static,
+    I: Indexer<T>,
+{
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<T>, LogMeta)>> {
+        call(&self.breaker, self.inner.fetch_logs_in_range(range)).await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        call(&self.breaker, self.inner.get_finalized_block_number()).await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<T>, LogMeta)>> {
+        call(&self.breaker, self.inner.fetch_logs_by_tx_hash(tx_hash)).await
+    }
+
+    async fn new_logs_notified(&self) {
+        self.inner.new_logs_notified().await
+    }
+}
+
+#[async_trait]
+impl<T, I> SequenceAwareIndexer<T> for CircuitBreakingIndexer<I>
+where
+    T: Send + Sync + Debug + 'static,
+    I: SequenceAwareIndexer<T>,
+{
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        call(&self.breaker, self.inner.latest_sequence_count_and_tip()).await
+    }
+}
+
+/// A provider whose calls go through the circuit breaker of its chain
+#[derive(Debug, Clone, new)]
+pub struct CircuitBreakingProvider {
+    inner: Arc<dyn HyperlaneProvider>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl HyperlaneChain for CircuitBreakingProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.inner.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for CircuitBreakingProvider {
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        call(&self.breaker, self.inner.get_block_by_hash(hash)).await
+    }
+
+    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
+        call(&self.breaker, self.inner.get_txn_by_hash(hash)).await
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        call(&self.breaker, self.inner.is_contract(address)).await
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        call(&self.breaker, self.inner.get_balance(address)).await
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        call(&self.breaker, self.inner.get_chain_metrics()).await
+    }
+
+    async fn get_chain_id(&self) -> ChainResult<Option<String>> {
+        call(&self.breaker, self.inner.get_chain_id()).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use hyperlane_core::rpc_clients::CircuitBreakerConf;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct FailingIndexer;
+
+    #[async_trait]
+    impl Indexer<H256> for FailingIndexer {
+        async fn fetch_logs_in_range(
+            &self,
+            _range: RangeInclusive<u32>,
+        ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+            Err(ChainCommunicationError::from_other_str(
+                "connection refused",
+            ))
+        }
+
+        async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+            Err(ChainCommunicationError::from_other_str(
+                "connection refused",
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fails_fast_once_open() {
+        let indexer = CircuitBreakingIndexer::new(
+            FailingIndexer,
+            Arc::new(CircuitBreaker::new(CircuitBreakerConf {
+                failure_threshold: 2,
+                cooldown: Duration::from_secs(60),
+            })),
+        );
+        for _ in 0..2 {
+            assert!(!matches!(
+                indexer.get_finalized_block_number().await,
+                Err(ChainCommunicationError::CircuitOpen(_))
+            ));
+        }
+        assert!(matches!(
+            indexer.fetch_logs_in_range(0..=10).await,
+            Err(ChainCommunicationError::CircuitOpen(_))
+        ));
+    }
+}
@@ -1,7 +1,8 @@
 pub(crate) mod sequence_aware;
 
 use hyperlane_core::{
-    Delivery, HyperlaneDomainProtocol, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion,
+    Delivery, DestinationGasUpdate, HyperlaneDomainProtocol, HyperlaneMessage,
+    InterchainGasPayment, MerkleTreeInsertion,
 };
 pub(crate) use sequence_aware::ForwardBackwardSequenceAwareSyncCursor;
 
@@ -31,9 +32,13 @@ impl Indexable for HyperlaneMessage {
     fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
         match domain {
             HyperlaneDomainProtocol::Ethereum => CursorType::SequenceAware,
-            HyperlaneDomainProtocol::Fuel => todo!(),
+            HyperlaneDomainProtocol::Fuel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Sui => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Starknet => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Ton => CursorType::SequenceAware,
         }
     }
 
@@ -47,20 +52,37 @@ impl Indexable for InterchainGasPayment {
     fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
         match domain {
             HyperlaneDomainProtocol::Ethereum => CursorType::RateLimited,
-            HyperlaneDomainProtocol::Fuel => todo!(),
+            // Fuel has no gas payment indexer yet, building one fails
+            HyperlaneDomainProtocol::Fuel => CursorType::RateLimited,
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Sui => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Starknet => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Ton => CursorType::RateLimited,
         }
     }
 }
 
+impl Indexable for DestinationGasUpdate {
+    fn indexing_cursor(_domain: HyperlaneDomainProtocol) -> CursorType {
+        // Gas oracle updates have no sequence to index them by
+        CursorType::RateLimited
+    }
+}
+
 impl Indexable for MerkleTreeInsertion {
     fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
         match domain {
             HyperlaneDomainProtocol::Ethereum => CursorType::SequenceAware,
-            HyperlaneDomainProtocol::Fuel => todo!(),
+            // Fuel has no merkle tree hook indexer yet, building one fails
+            HyperlaneDomainProtocol::Fuel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Sui => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Starknet => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Ton => CursorType::SequenceAware,
         }
     }
 }
@@ -69,9 +91,13 @@ impl Indexable for Delivery {
     fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
         match domain {
             HyperlaneDomainProtocol::Ethereum => CursorType::RateLimited,
-            HyperlaneDomainProtocol::Fuel => todo!(),
+            HyperlaneDomainProtocol::Fuel => CursorType::RateLimited,
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Sui => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Starknet => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Ton => CursorType::RateLimited,
         }
     }
 }
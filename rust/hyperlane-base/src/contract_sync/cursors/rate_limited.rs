@@ -20,8 +20,6 @@ const ETA_TIME_WINDOW: f64 = 2. * 60.;
 #[derive(Debug, new)]
 pub(crate) struct SyncState {
     chunk_size: u32,
-    /// The starting block for the cursor
-    start_block: u32,
     /// The next block that should be indexed.
     next_block: u32,
     direction: SyncDirection,
@@ -76,6 +74,12 @@ pub enum SyncDirection {
 /// Tool for handling the logic of what the next block range that should be
 /// queried is and also handling rate limiting. Rate limiting is automatically
 /// performed by `next_action`.
+///
+/// Used for the events without a sequence, e.g. deliveries and gas payments,
+/// which can't be checked for gaps. The block after the last window whose
+/// logs were all stored is persisted as the watermark, and syncing resumes
+/// `chunk_size` blocks before it after a restart. Logs of the overlapping
+/// blocks are indexed again and deduplicated by the log store.
 pub(crate) struct RateLimitedContractSyncCursor<T> {
     indexer: Arc<dyn Indexer<T>>,
     db: Arc<dyn HyperlaneWatermarkedLogStore<T>>,
@@ -83,6 +87,7 @@ pub(crate) struct RateLimitedContractSyncCursor<T> {
     last_tip_update: Instant,
     eta_calculator: SyncerEtaCalculator,
     sync_state: SyncState,
+    poll_interval: Duration,
 }
 
 impl<T> RateLimitedContractSyncCursor<T> {
@@ -92,6 +97,7 @@ impl<T> RateLimitedContractSyncCursor<T> {
         db: Arc<dyn HyperlaneWatermarkedLogStore<T>>,
         chunk_size: u32,
         initial_height: u32,
+        poll_interval: Duration,
     ) -> Result<Self> {
         let tip = indexer.get_finalized_block_number().await?;
         Ok(Self {
@@ -103,10 +109,10 @@ impl<T> RateLimitedContractSyncCursor<T> {
             sync_state: SyncState::new(
                 chunk_size,
                 initial_height,
-                initial_height,
                 // The rate limited cursor currently only syncs in the forward direction.
                 SyncDirection::Forward,
             ),
+            poll_interval,
         })
     }
 
@@ -173,8 +179,7 @@ where
         if let Some(range) = self.get_next_range().await? {
             return Ok((CursorAction::Query(range), eta));
         } else {
-            // TODO: Define the sleep time from interval flag
-            return Ok((CursorAction::Sleep(Duration::from_secs(5)), eta));
+            return Ok((CursorAction::Sleep(self.poll_interval), eta));
         }
     }
 
@@ -187,17 +192,12 @@ where
         _: Vec<(Indexed<T>, LogMeta)>,
         range: RangeInclusive<u32>,
     ) -> Result<()> {
-        // Store a relatively conservative view of the high watermark, which should allow a single watermark to be
-        // safely shared across multiple cursors, so long as they are running sufficiently in sync
+        // Only called once the logs of the range are stored, so everything
+        // before the next block is
+        self.sync_state.update_range(range);
         self.db
-            .store_high_watermark(u32::max(
-                self.sync_state.start_block,
-                self.sync_state
-                    .next_block
-                    .saturating_sub(self.sync_state.chunk_size),
-            ))
+            .store_high_watermark(self.sync_state.next_block)
             .await?;
-        self.sync_state.update_range(range);
 
         match self.indexer.get_finalized_block_number().await {
             Ok(tip) => {
@@ -302,6 +302,7 @@ pub(crate) mod test {
             Arc::new(db),
             chunk_size,
             initial_height,
+            Duration::from_secs(5),
         )
         .await
         .unwrap()
@@ -333,6 +334,35 @@ pub(crate) mod test {
         assert!(matches!(action_3, CursorAction::Query(_expected_range)));
     }
 
+    #[tokio::test]
+    async fn test_update_stores_the_next_block_as_watermark() {
+        let mut indexer = MockIndexer::new();
+        indexer
+            .expect_get_finalized_block_number()
+            .returning(|| Ok(100));
+        let mut db = MockDb::new();
+        db.expect_store_high_watermark()
+            .withf(|block| *block == INITIAL_HEIGHT + CHUNK_SIZE + 1)
+            .times(1)
+            .returning(|_| Ok(()));
+        let mut cursor = RateLimitedContractSyncCursor::new(
+            Arc::new(indexer),
+            Arc::new(db),
+            CHUNK_SIZE,
+            INITIAL_HEIGHT,
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+
+        let (action, _) = cursor.next_action().await.unwrap();
+        let CursorAction::Query(range) = action else {
+            panic!("Expected Query action");
+        };
+        assert_eq!(range, INITIAL_HEIGHT..=INITIAL_HEIGHT + CHUNK_SIZE);
+        cursor.update(vec![], range).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_next_action_sleeps_if_tip_is_not_updated() {
         let chain_tips = vec![10];
@@ -67,6 +67,7 @@ pub(crate) struct ForwardBackwardSequenceAwareSyncCursor<T> {
     forward: ForwardSequenceAwareSyncCursor<T>,
     backward: BackwardSequenceAwareSyncCursor<T>,
     last_direction: SyncDirection,
+    poll_interval: Duration,
 }
 
 impl<T: Debug> ForwardBackwardSequenceAwareSyncCursor<T> {
@@ -76,6 +77,7 @@ impl<T: Debug> ForwardBackwardSequenceAwareSyncCursor<T> {
         db: Arc<dyn HyperlaneSequenceAwareIndexerStoreReader<T>>,
         chunk_size: u32,
         mode: IndexMode,
+        poll_interval: Duration,
     ) -> Result<Self> {
         let (sequence_count, tip) = latest_sequence_querier
             .latest_sequence_count_and_tip()
@@ -97,6 +99,7 @@ impl<T: Debug> ForwardBackwardSequenceAwareSyncCursor<T> {
             forward: forward_cursor,
             backward: backward_cursor,
             last_direction: SyncDirection::Forward,
+            poll_interval,
         })
     }
 }
@@ -118,8 +121,7 @@ impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
             self.last_direction = SyncDirection::Backward;
             return Ok((CursorAction::Query(backward_range), eta));
         }
-        // TODO: Define the sleep time from interval flag
-        return Ok((CursorAction::Sleep(Duration::from_secs(5)), eta));
+        return Ok((CursorAction::Sleep(self.poll_interval), eta));
     }
 
     fn latest_queried_block(&self) -> u32 {
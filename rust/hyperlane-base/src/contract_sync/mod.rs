@@ -6,9 +6,9 @@ use axum::async_trait;
 use cursors::*;
 use derive_new::new;
 use hyperlane_core::{
-    utils::fmt_sync_time, ContractSyncCursor, CursorAction, HyperlaneDomain, HyperlaneLogStore,
-    HyperlaneSequenceAwareIndexerStore, HyperlaneWatermarkedLogStore, Indexer,
-    SequenceAwareIndexer,
+    utils::fmt_sync_time, ChainCommunicationError, ContractSyncCursor, CursorAction,
+    HyperlaneDomain, HyperlaneLogStore, HyperlaneSequenceAwareIndexerStore,
+    HyperlaneWatermarkedLogStore, Indexer, SequenceAwareIndexer,
 };
 use hyperlane_core::{Indexed, LogMeta, H512};
 pub use metrics::ContractSyncMetrics;
@@ -28,6 +28,24 @@ use cursors::ForwardBackwardSequenceAwareSyncCursor;
 
 const SLEEP_DURATION: Duration = Duration::from_secs(5);
 
+/// How long to wait after an error. Waits until the circuit of the chain is
+/// probed again if it is open, rather than retrying while it fails fast.
+fn backoff(err: Option<&ChainCommunicationError>) -> Duration {
+    match err {
+        Some(ChainCommunicationError::CircuitOpen(retry_in)) => *retry_in,
+        _ => SLEEP_DURATION,
+    }
+}
+
+/// How long to wait after an error of a cursor, which may have wrapped the
+/// error of the chain in context
+fn report_backoff(err: &eyre::Report) -> Duration {
+    backoff(
+        err.chain()
+            .find_map(|cause| cause.downcast_ref::<ChainCommunicationError>()),
+    )
+}
+
 /// Entity that drives the syncing of an agent's db with on-chain data.
 /// Extracts chain-specific data (emitted checkpoints, messages, etc) from an
 /// `indexer` and fills the agent's db with this data.
@@ -110,7 +128,13 @@ where
                             continue;
                         }
                     };
-                    let logs = self.dedupe_and_store_logs(logs, stored_logs_metric).await;
+                    let logs = match self.dedupe_and_store_logs(logs, stored_logs_metric).await {
+                        Ok(logs) => logs,
+                        Err(err) => {
+                            warn!(?err, ?tx_id, "Error storing logs in db");
+                            continue;
+                        }
+                    };
                     let num_logs = logs.len() as u64;
                     info!(
                         num_logs,
@@ -143,7 +167,7 @@ where
             Ok((action, eta)) => (action, eta),
             Err(err) => {
                 warn!(?err, "Error getting next action");
-                sleep(SLEEP_DURATION).await;
+                sleep(report_backoff(&err)).await;
                 return;
             }
         };
@@ -158,11 +182,23 @@ where
                     Ok(logs) => logs,
                     Err(err) => {
                         warn!(?err, ?range, "Error fetching logs in range");
-                        break SLEEP_DURATION;
+                        break backoff(Some(&err));
                     }
                 };
 
-                let logs = self.dedupe_and_store_logs(logs, stored_logs_metric).await;
+                // The cursor isn't updated if storing fails, so that the range
+                // is queried again rather than skipped
+                let logs = match self.dedupe_and_store_logs(logs, stored_logs_metric).await {
+                    Ok(logs) => logs,
+                    Err(err) => {
+                        warn!(?err, ?range, "Error storing logs in db");
+                        break SLEEP_DURATION;
+                    }
+                };
+                if let Err(err) = self.db.store_indexed_range(range.clone()).await {
+                    // At worst, the range is taken for a hole and indexed again
+                    warn!(?err, ?range, "Error recording indexed range");
+                }
                 let logs_found = logs.len() as u64;
                 info!(
                     ?range,
@@ -188,7 +224,14 @@ where
                 };
                 break Default::default();
             },
-            CursorAction::Sleep(duration) => duration,
+            CursorAction::Sleep(duration) => {
+                // Stop waiting as soon as the indexer is notified of new logs
+                tokio::select! {
+                    _ = sleep(duration) => {}
+                    _ = self.indexer.new_logs_notified() => debug!("Notified of new logs"),
+                }
+                Default::default()
+            }
         };
         sleep(sleep_duration).await
     }
@@ -197,18 +240,12 @@ where
         &self,
         logs: Vec<(Indexed<T>, LogMeta)>,
         stored_logs_metric: &GenericCounter<AtomicU64>,
-    ) -> Vec<(Indexed<T>, LogMeta)> {
+    ) -> eyre::Result<Vec<(Indexed<T>, LogMeta)>> {
         let deduped_logs = HashSet::<_>::from_iter(logs);
         let logs = Vec::from_iter(deduped_logs);
 
         // Store deliveries
-        let stored = match self.db.store_logs(&logs).await {
-            Ok(stored) => stored,
-            Err(err) => {
-                warn!(?err, "Error storing logs in db");
-                Default::default()
-            }
-        };
+        let stored = self.db.store_logs(&logs).await?;
         if stored > 0 {
             debug!(
                 domain = self.domain.as_ref(),
@@ -219,7 +256,7 @@ where
         }
         // Report amount of deliveries stored into db
         stored_logs_metric.inc_by(stored as u64);
-        logs
+        Ok(logs)
     }
 }
 
@@ -277,9 +314,15 @@ where
     async fn cursor(&self, index_settings: IndexSettings) -> Box<dyn ContractSyncCursor<T>> {
         let watermark = self.db.retrieve_high_watermark().await.unwrap();
         let index_settings = IndexSettings {
-            from: watermark.unwrap_or(index_settings.from),
-            chunk_size: index_settings.chunk_size,
-            mode: index_settings.mode,
+            // Overlap the blocks indexed before the restart by a chunk
+            from: watermark
+                .map(|watermark| {
+                    watermark
+                        .saturating_sub(index_settings.chunk_size)
+                        .max(index_settings.from)
+                })
+                .unwrap_or(index_settings.from),
+            ..index_settings
         };
         Box::new(
             RateLimitedContractSyncCursor::new(
@@ -287,6 +330,7 @@ where
                 self.db.clone(),
                 index_settings.chunk_size,
                 index_settings.from,
+                index_settings.poll_interval(),
             )
             .await
             .unwrap(),
@@ -326,6 +370,7 @@ where
                 Arc::new(self.db.clone()),
                 index_settings.chunk_size,
                 index_settings.mode,
+                index_settings.poll_interval(),
             )
             .await
             .unwrap(),
@@ -344,3 +389,26 @@ where
         ContractSync::get_broadcaster(self)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use eyre::WrapErr;
+
+    use super::*;
+
+    #[test]
+    fn test_report_backoff_finds_wrapped_circuit_errors() {
+        let retry_in = Duration::from_secs(42);
+        let err: eyre::Result<()> = Err(ChainCommunicationError::CircuitOpen(retry_in).into());
+        assert_eq!(report_backoff(&err.unwrap_err()), retry_in);
+
+        let wrapped: eyre::Result<()> = Err(ChainCommunicationError::CircuitOpen(retry_in))
+            .wrap_err("Failed to get the tip of the chain");
+        assert_eq!(report_backoff(&wrapped.unwrap_err()), retry_in);
+
+        assert_eq!(
+            report_backoff(&eyre::eyre!("Some other error")),
+            SLEEP_DURATION
+        );
+    }
+}
@@ -1,13 +1,15 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use async_trait::async_trait;
 use eyre::{bail, Result};
 use paste::paste;
 use tracing::{debug, instrument, trace};
 
 use hyperlane_core::{
-    GasPaymentKey, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage,
-    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, Indexed,
-    InterchainGasExpenditure, InterchainGasPayment, InterchainGasPaymentMeta, LogMeta,
-    MerkleTreeInsertion, H256,
+    DeadLetter, DestinationGasUpdate, GasPaymentKey, HyperlaneDomain, HyperlaneLogStore,
+    HyperlaneMessage, HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore,
+    Indexed, InterchainGasExpenditure, InterchainGasPayment, InterchainGasPaymentMeta, LogMeta,
+    MerkleTreeInsertion, OperationId, H256, U256,
 };
 
 use super::{
@@ -20,6 +22,7 @@ use super::{
 
 const MESSAGE_ID: &str = "message_id_";
 const MESSAGE_DISPATCHED_BLOCK_NUMBER: &str = "message_dispatched_block_number_";
+const MESSAGE_DISPATCHED_BLOCK_HASH: &str = "message_dispatched_block_hash_";
 const MESSAGE: &str = "message_";
 const NONCE_PROCESSED: &str = "nonce_processed_";
 const GAS_PAYMENT_BY_SEQUENCE: &str = "gas_payment_by_sequence_";
@@ -34,6 +37,22 @@ const MERKLE_LEAF_INDEX_BY_MESSAGE_ID: &str = "merkle_leaf_index_by_message_id_"
 const MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX: &str =
     "merkle_tree_insertion_block_number_by_leaf_index_";
 const LATEST_INDEXED_GAS_PAYMENT_BLOCK: &str = "latest_indexed_gas_payment_block";
+const DEAD_LETTER_BY_NONCE: &str = "dead_letter_by_nonce_";
+/// Roots validators recorded as signed before they had a signing record db.
+/// Only read, to carry them over to it.
+const SIGNED_CHECKPOINT_ROOT_BY_INDEX: &str = "signed_checkpoint_root_by_index_";
+const DEAD_LETTER_REPLAYED_AT_BY_NONCE: &str = "dead_letter_replayed_at_by_nonce_";
+const OPERATION_ID_BY_MESSAGE_ID: &str = "operation_id_by_message_id_";
+const MESSAGE_INDEXED_AT_BY_NONCE: &str = "message_indexed_at_by_nonce_";
+const DESTINATION_GAS_UPDATE_BY_DOMAIN: &str = "destination_gas_update_by_domain_";
+const DESTINATION_GAS_UPDATE_BLOCK_BY_DOMAIN: &str = "destination_gas_update_block_by_domain_";
+const DESTINATION_GAS_UPDATE_LOG_INDEX_BY_DOMAIN: &str =
+    "destination_gas_update_log_index_by_domain_";
+const LATEST_INDEXED_DESTINATION_GAS_BLOCK: &str = "latest_indexed_destination_gas_block";
+const NEXT_NONCE_TO_PRUNE: &str = "next_nonce_to_prune_";
+const DEADLINE_MISSED_BY_MESSAGE_ID: &str = "deadline_missed_by_message_id_";
+const TENANT_BUDGET_PERIOD_START_BY_TENANT: &str = "tenant_budget_period_start_by_tenant_";
+const TENANT_BUDGET_SPEND_BY_TENANT: &str = "tenant_budget_spend_by_tenant_";
 
 /// Rocks DB result type
 pub type DbResult<T> = std::result::Result<T, DbError>;
@@ -100,6 +119,15 @@ impl HyperlaneRocksDB {
         self.try_update_max_seen_message_nonce(message.nonce)?;
         // - `nonce` --> `dispatched block number`
         self.store_dispatched_block_number_by_nonce(&message.nonce, &dispatched_block_number)?;
+        // - `id` --> `operation id`, which the relayer logs the message with
+        self.store_operation_id_by_message_id(&id, &OperationId::random())?;
+        // - `nonce` --> when the message was indexed, from which the relayer
+        //   measures how long deliveries take
+        let indexed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.store_message_indexed_at_by_nonce(&message.nonce, &indexed_at)?;
         Ok(true)
     }
 
@@ -128,6 +156,50 @@ impl HyperlaneRocksDB {
         self.retrieve_highest_seen_message_nonce_number(&Default::default())
     }
 
+    /// Retrieve the dead letter of a message, unless the message was replayed
+    /// after it was dead-lettered
+    pub fn retrieve_unreplayed_dead_letter_by_nonce(
+        &self,
+        nonce: u32,
+    ) -> DbResult<Option<DeadLetter>> {
+        let Some(dead_letter) = self.retrieve_dead_letter_by_nonce(&nonce)? else {
+            return Ok(None);
+        };
+        let replayed_at = self.retrieve_dead_letter_replayed_at_by_nonce(&nonce)?;
+        if replayed_at.map_or(false, |replayed_at| {
+            replayed_at >= dead_letter.dead_lettered_at
+        }) {
+            return Ok(None);
+        }
+        Ok(Some(dead_letter))
+    }
+
+    /// Stores the gas data of a destination unless a more recent update of it
+    /// is stored already, updates being ordered by their block and log index.
+    /// Returns whether it was stored.
+    pub fn process_destination_gas_update(
+        &self,
+        update: Indexed<DestinationGasUpdate>,
+        log_meta: &LogMeta,
+    ) -> DbResult<bool> {
+        let update = *update.inner();
+        let destination = update.destination;
+        if let Some(block) = self.retrieve_destination_gas_update_block_by_domain(&destination)? {
+            // Updates stored before their log index was aren't replaced by
+            // updates of their block
+            let log_index = self
+                .retrieve_destination_gas_update_log_index_by_domain(&destination)?
+                .unwrap_or(U256::MAX);
+            if (block, log_index) >= (log_meta.block_number, log_meta.log_index) {
+                return Ok(false);
+            }
+        }
+        self.store_destination_gas_update_by_domain(&destination, &update)?;
+        self.store_destination_gas_update_block_by_domain(&destination, &log_meta.block_number)?;
+        self.store_destination_gas_update_log_index_by_domain(&destination, &log_meta.log_index)?;
+        Ok(true)
+    }
+
     /// If the provided gas payment, identified by its metadata, has not been
     /// processed, processes the gas payment and records it as processed.
     /// Returns whether the gas payment was processed for the first time.
@@ -267,6 +339,75 @@ impl HyperlaneRocksDB {
             .complete(gas_payment_key.message_id, gas_payment_key.destination))
     }
 
+    /// Delete the bookkeeping of delivered messages that is only read while
+    /// a message is pending: its retry count, gas payment and expenditure
+    /// totals and dead letter. Messages and merkle tree insertions are kept,
+    /// cursors and the merkle tree are built from them.
+    ///
+    /// Goes through at most `limit` nonces in order from where the last call
+    /// stopped, and stops at the first message that isn't delivered yet or
+    /// was indexed after `cutoff` (unix seconds). Returns how many messages
+    /// were pruned.
+    pub fn prune_delivered_messages(&self, cutoff: u64, limit: u32) -> DbResult<u32> {
+        let start = self
+            .retrieve_next_nonce_to_prune_number(&Default::default())?
+            .unwrap_or_default();
+        let mut nonce = start;
+        while nonce - start < limit {
+            let Some(message) = self.retrieve_message_by_nonce(nonce)? else {
+                break;
+            };
+            if !self.retrieve_processed_by_nonce(&nonce)?.unwrap_or(false) {
+                break;
+            }
+            // Messages indexed before the time was recorded are old enough
+            if self
+                .retrieve_message_indexed_at_by_nonce(&nonce)?
+                .map_or(false, |indexed_at| indexed_at >= cutoff)
+            {
+                break;
+            }
+            let id = message.id();
+            self.delete_keyed(PENDING_MESSAGE_RETRY_COUNT_FOR_MESSAGE_ID, &id)?;
+            self.delete_keyed(DEADLINE_MISSED_BY_MESSAGE_ID, &id)?;
+            self.delete_keyed(GAS_EXPENDITURE_FOR_MESSAGE_ID, &id)?;
+            self.delete_keyed(
+                GAS_PAYMENT_FOR_MESSAGE_ID,
+                &GasPaymentKey {
+                    message_id: id,
+                    destination: message.destination,
+                },
+            )?;
+            self.delete_keyed(DEAD_LETTER_BY_NONCE, &nonce)?;
+            self.delete_keyed(DEAD_LETTER_REPLAYED_AT_BY_NONCE, &nonce)?;
+            nonce += 1;
+        }
+        if nonce != start {
+            self.store_next_nonce_to_prune_number(&Default::default(), &nonce)?;
+        }
+        Ok(nonce - start)
+    }
+
+    /// Store the current budget period of a tenant on this domain: when it
+    /// started, in unix seconds, and what the tenant spent on gas since
+    pub fn store_tenant_budget_period(
+        &self,
+        tenant: &str,
+        started_at: u64,
+        spent: U256,
+    ) -> DbResult<()> {
+        self.store_encodable(TENANT_BUDGET_PERIOD_START_BY_TENANT, tenant, &started_at)?;
+        self.store_encodable(TENANT_BUDGET_SPEND_BY_TENANT, tenant, &spent)
+    }
+
+    /// Retrieve the current budget period of a tenant on this domain, if one
+    /// was stored
+    pub fn retrieve_tenant_budget_period(&self, tenant: &str) -> DbResult<Option<(u64, U256)>> {
+        let started_at = self.retrieve_decodable(TENANT_BUDGET_PERIOD_START_BY_TENANT, tenant)?;
+        let spent = self.retrieve_decodable(TENANT_BUDGET_SPEND_BY_TENANT, tenant)?;
+        Ok(started_at.zip(spent))
+    }
+
     /// Retrieve the total gas payment for a message
     pub fn retrieve_gas_expenditure_by_message_id(
         &self,
@@ -288,6 +429,11 @@ impl HyperlaneLogStore<HyperlaneMessage> for HyperlaneRocksDB {
         for (message, meta) in messages {
             let stored_message = self.store_message(message.inner(), meta.block_number)?;
             if stored_message {
+                // - `nonce` --> `dispatched block hash`, used to determine the age of the message
+                self.store_dispatched_block_hash_by_nonce(
+                    &message.inner().nonce,
+                    &meta.block_hash,
+                )?;
                 stored += 1;
             }
         }
@@ -334,6 +480,24 @@ impl HyperlaneLogStore<InterchainGasPayment> for HyperlaneRocksDB {
     }
 }
 
+#[async_trait]
+impl HyperlaneLogStore<DestinationGasUpdate> for HyperlaneRocksDB {
+    /// Store the latest gas data of each destination
+    #[instrument(skip_all)]
+    async fn store_logs(
+        &self,
+        updates: &[(Indexed<DestinationGasUpdate>, LogMeta)],
+    ) -> Result<u32> {
+        store_and_count_new(
+            self,
+            updates,
+            "destination gas updates",
+            HyperlaneRocksDB::process_destination_gas_update,
+        )
+        .await
+    }
+}
+
 #[async_trait]
 impl HyperlaneLogStore<MerkleTreeInsertion> for HyperlaneRocksDB {
     /// Store every tree insertion event
@@ -408,6 +572,22 @@ impl HyperlaneWatermarkedLogStore<InterchainGasPayment> for HyperlaneRocksDB {
     }
 }
 
+#[async_trait]
+impl HyperlaneWatermarkedLogStore<DestinationGasUpdate> for HyperlaneRocksDB {
+    /// Gets the block number high watermark
+    async fn retrieve_high_watermark(&self) -> Result<Option<u32>> {
+        let watermark = self.retrieve_decodable("", LATEST_INDEXED_DESTINATION_GAS_BLOCK)?;
+        Ok(watermark)
+    }
+
+    /// Stores the block number high watermark
+    async fn store_high_watermark(&self, block_number: u32) -> Result<()> {
+        let result =
+            self.store_encodable("", LATEST_INDEXED_DESTINATION_GAS_BLOCK, &block_number)?;
+        Ok(result)
+    }
+}
+
 // Keep this implementation for type compatibility with the `contract_syncs` sync builder
 #[async_trait]
 impl HyperlaneWatermarkedLogStore<HyperlaneMessage> for HyperlaneRocksDB {
@@ -447,6 +627,10 @@ pub trait ProcessMessage: Send + Sync {
     /// Retrieve whether a message has been processed
     fn retrieve_processed_by_nonce(&self, nonce: u32) -> DbResult<Option<bool>>;
 
+    /// Retrieve the dead letter of a message the relayer gave up on, unless
+    /// the message was replayed since
+    fn retrieve_dead_letter_by_nonce(&self, nonce: u32) -> DbResult<Option<DeadLetter>>;
+
     /// Get the origin domain of the database
     fn domain(&self) -> &HyperlaneDomain;
 }
@@ -464,6 +648,10 @@ impl ProcessMessage for HyperlaneRocksDB {
         self.retrieve_processed_by_nonce(&nonce)
     }
 
+    fn retrieve_dead_letter_by_nonce(&self, nonce: u32) -> DbResult<Option<DeadLetter>> {
+        self.retrieve_unreplayed_dead_letter_by_nonce(nonce)
+    }
+
     fn domain(&self) -> &HyperlaneDomain {
         self.domain()
     }
@@ -496,9 +684,78 @@ macro_rules! make_store_and_retrieve {
 }
 
 make_store_and_retrieve!(pub, message_id_by_nonce, MESSAGE_ID, u32, H256);
-make_store_and_retrieve!(pub(self), message_by_id, MESSAGE, H256, HyperlaneMessage);
-make_store_and_retrieve!(pub(self), dispatched_block_number_by_nonce, MESSAGE_DISPATCHED_BLOCK_NUMBER, u32, u64);
+make_store_and_retrieve!(pub, message_by_id, MESSAGE, H256, HyperlaneMessage);
+make_store_and_retrieve!(
+    pub,
+    dispatched_block_number_by_nonce,
+    MESSAGE_DISPATCHED_BLOCK_NUMBER,
+    u32,
+    u64
+);
+make_store_and_retrieve!(
+    pub,
+    dispatched_block_hash_by_nonce,
+    MESSAGE_DISPATCHED_BLOCK_HASH,
+    u32,
+    H256
+);
 make_store_and_retrieve!(pub, processed_by_nonce, NONCE_PROCESSED, u32, bool);
+make_store_and_retrieve!(
+    pub,
+    signed_checkpoint_root_by_index,
+    SIGNED_CHECKPOINT_ROOT_BY_INDEX,
+    u32,
+    H256
+);
+make_store_and_retrieve!(
+    pub,
+    dead_letter_by_nonce,
+    DEAD_LETTER_BY_NONCE,
+    u32,
+    DeadLetter
+);
+make_store_and_retrieve!(
+    pub,
+    dead_letter_replayed_at_by_nonce,
+    DEAD_LETTER_REPLAYED_AT_BY_NONCE,
+    u32,
+    u64
+);
+make_store_and_retrieve!(
+    pub,
+    operation_id_by_message_id,
+    OPERATION_ID_BY_MESSAGE_ID,
+    H256,
+    OperationId
+);
+make_store_and_retrieve!(
+    pub,
+    message_indexed_at_by_nonce,
+    MESSAGE_INDEXED_AT_BY_NONCE,
+    u32,
+    u64
+);
+make_store_and_retrieve!(
+    pub,
+    destination_gas_update_by_domain,
+    DESTINATION_GAS_UPDATE_BY_DOMAIN,
+    u32,
+    DestinationGasUpdate
+);
+make_store_and_retrieve!(
+    pub(self),
+    destination_gas_update_block_by_domain,
+    DESTINATION_GAS_UPDATE_BLOCK_BY_DOMAIN,
+    u32,
+    u64
+);
+make_store_and_retrieve!(
+    pub(self),
+    destination_gas_update_log_index_by_domain,
+    DESTINATION_GAS_UPDATE_LOG_INDEX_BY_DOMAIN,
+    u32,
+    U256
+);
 make_store_and_retrieve!(pub(self), processed_by_gas_payment_meta, GAS_PAYMENT_META_PROCESSED, InterchainGasPaymentMeta, bool);
 make_store_and_retrieve!(pub(self), interchain_gas_expenditure_data_by_message_id, GAS_EXPENDITURE_FOR_MESSAGE_ID, H256, InterchainGasExpenditureData);
 make_store_and_retrieve!(pub(self), interchain_gas_payment_data_by_gas_payment_key, GAS_PAYMENT_FOR_MESSAGE_ID, GasPaymentKey, InterchainGasPaymentData);
@@ -511,6 +768,13 @@ make_store_and_retrieve!(
     H256,
     u32
 );
+make_store_and_retrieve!(
+    pub,
+    deadline_missed_by_message_id,
+    DEADLINE_MISSED_BY_MESSAGE_ID,
+    H256,
+    bool
+);
 make_store_and_retrieve!(
     pub,
     merkle_tree_insertion_by_leaf_index,
@@ -535,3 +799,65 @@ make_store_and_retrieve!(
 // There's no unit struct Encode/Decode impl, so just use `bool`, have visibility be private (by omitting the first argument), and wrap
 // with a function that always uses the `Default::default()` key
 make_store_and_retrieve!(, highest_seen_message_nonce_number, HIGHEST_SEEN_MESSAGE_NONCE, bool, u32);
+make_store_and_retrieve!(, next_nonce_to_prune_number, NEXT_NONCE_TO_PRUNE, bool, u32);
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+    use crate::db::test_utils;
+
+    fn meta(block_number: u64, log_index: u64) -> LogMeta {
+        LogMeta {
+            block_number,
+            log_index: log_index.into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_destination_gas_updates_are_rejected() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Test1);
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let update = |gas_price: u64| {
+                Indexed::new(DestinationGasUpdate {
+                    destination: 2,
+                    token_exchange_rate: 1.into(),
+                    gas_price: gas_price.into(),
+                })
+            };
+            let stored = || {
+                db.retrieve_destination_gas_update_by_domain(&2)
+                    .unwrap()
+                    .map(|update| update.gas_price.as_u64())
+            };
+
+            assert!(db
+                .process_destination_gas_update(update(10), &meta(100, 5))
+                .unwrap());
+            // The same log, and older logs, are rejected
+            assert!(!db
+                .process_destination_gas_update(update(10), &meta(100, 5))
+                .unwrap());
+            assert!(!db
+                .process_destination_gas_update(update(9), &meta(100, 4))
+                .unwrap());
+            assert!(!db
+                .process_destination_gas_update(update(8), &meta(99, 7))
+                .unwrap());
+            assert_eq!(stored(), Some(10));
+
+            // Later logs of the same block replace it, whatever their data
+            assert!(db
+                .process_destination_gas_update(update(10), &meta(100, 6))
+                .unwrap());
+            assert!(db
+                .process_destination_gas_update(update(11), &meta(101, 0))
+                .unwrap());
+            assert_eq!(stored(), Some(11));
+        })
+        .await;
+    }
+}
@@ -0,0 +1,326 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use eyre::Result;
+use prometheus::IntGaugeVec;
+use tokio::{task::JoinHandle, time::sleep};
+use tracing::{info, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::{
+    db::{HyperlaneRocksDB, DB},
+    CoreMetrics,
+};
+
+/// How often the size of the dbs is reported
+const METRICS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Minimum time between two compactions of a db larger than its maximum, so
+/// that a db that stays too large isn't compacted over and over
+const MIN_OVER_CAP_COMPACTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum number of delivered messages of an origin pruned at a time, so that
+/// a backlog of them is pruned over several rounds
+const MAX_PRUNED_MESSAGES: u32 = 10_000;
+
+/// Scheduled maintenance of an agent's local dbs
+#[derive(Debug, Clone, Default)]
+pub struct DbMaintenanceConf {
+    /// How often the dbs are fully compacted, reclaiming the space of
+    /// overwritten and deleted entries. Never if not set.
+    pub compaction_interval: Option<Duration>,
+    /// Size of the sst files of a db above which it is compacted straight
+    /// away and a warning is logged, rather than waiting for the next
+    /// scheduled compaction
+    pub max_size_bytes: Option<u64>,
+    /// How long the bookkeeping of a delivered message is kept in the
+    /// relayer's db after the message was indexed, see
+    /// `HyperlaneRocksDB::prune_delivered_messages`. Kept forever if not set.
+    pub message_retention: Option<Duration>,
+}
+
+/// Sizes reported by rocksdb for a db
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DbSizeStats {
+    /// Total size of the sst files
+    pub sst_files_bytes: u64,
+    /// Estimated size of the live data, excluding overwritten and deleted
+    /// entries waiting to be compacted away
+    pub live_data_bytes: u64,
+    /// Size of the memtables not yet flushed to sst files
+    pub memtables_bytes: u64,
+    /// Estimated number of keys
+    pub estimated_keys: u64,
+}
+
+impl DB {
+    /// Compact the whole key range of the db
+    pub fn compact(&self) {
+        self.0.compact_range::<&[u8], &[u8]>(None, None);
+    }
+
+    /// The sizes of the db as estimated by rocksdb
+    pub fn size_stats(&self) -> super::Result<DbSizeStats> {
+        let property = |name: &str| -> super::Result<u64> {
+            Ok(self.0.property_int_value(name)?.unwrap_or_default())
+        };
+        Ok(DbSizeStats {
+            sst_files_bytes: property("rocksdb.total-sst-files-size")?,
+            live_data_bytes: property("rocksdb.estimate-live-data-size")?,
+            memtables_bytes: property("rocksdb.cur-size-all-mem-tables")?,
+            estimated_keys: property("rocksdb.estimate-num-keys")?,
+        })
+    }
+}
+
+/// Compacts an agent's dbs on a schedule and reports their size
+#[derive(Debug)]
+pub struct DbMaintenance {
+    conf: DbMaintenanceConf,
+    dbs: Vec<(String, DB)>,
+    message_dbs: Vec<HyperlaneRocksDB>,
+    size_bytes: IntGaugeVec,
+    estimated_keys: IntGaugeVec,
+}
+
+impl DbMaintenance {
+    /// Create the maintenance of no db yet
+    pub fn new(conf: DbMaintenanceConf, metrics: &CoreMetrics) -> Result<Self> {
+        Ok(Self {
+            conf,
+            dbs: vec![],
+            message_dbs: vec![],
+            size_bytes: metrics.new_int_gauge(
+                "db_size_bytes",
+                "Size of an agent's local db, by `kind` of storage: `sst_files`, `live_data` or `memtables`",
+                &["db", "kind"],
+            )?,
+            estimated_keys: metrics.new_int_gauge(
+                "db_estimated_keys",
+                "Estimated number of keys in an agent's local db",
+                &["db"],
+            )?,
+        })
+    }
+
+    /// Maintain `db` too, reported under `name`
+    pub fn with_db(mut self, name: impl Into<String>, db: DB) -> Self {
+        self.dbs.push((name.into(), db));
+        self
+    }
+
+    /// Prune the delivered messages of an origin stored in `db` too, if a
+    /// message retention is configured
+    pub fn with_message_db(mut self, db: HyperlaneRocksDB) -> Self {
+        self.message_dbs.push(db);
+        self
+    }
+
+    /// Run the maintenance in the background
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("DbMaintenance");
+        tokio::spawn(async move { self.run().await }).instrument(span)
+    }
+
+    async fn run(self) {
+        let mut compacted_at = vec![Instant::now(); self.dbs.len()];
+        loop {
+            if let Some(retention) = self.conf.message_retention {
+                self.prune_messages(retention).await;
+            }
+            for ((name, db), compacted_at) in self.dbs.iter().zip(compacted_at.iter_mut()) {
+                let stats = match db.size_stats() {
+                    Ok(stats) => stats,
+                    Err(err) => {
+                        warn!(?err, db = %name, "Failed to get the size of the db");
+                        continue;
+                    }
+                };
+                self.report(name, &stats);
+
+                let since_compaction = compacted_at.elapsed();
+                let compaction_due = self
+                    .conf
+                    .compaction_interval
+                    .map_or(false, |interval| since_compaction >= interval);
+                let over_cap = self
+                    .conf
+                    .max_size_bytes
+                    .map_or(false, |max| stats.sst_files_bytes > max);
+                if over_cap && since_compaction >= MIN_OVER_CAP_COMPACTION_INTERVAL {
+                    warn!(
+                        db = %name,
+                        size_bytes = stats.sst_files_bytes,
+                        max_size_bytes = ?self.conf.max_size_bytes,
+                        "Db is larger than its configured maximum, compacting it"
+                    );
+                } else if !compaction_due {
+                    continue;
+                }
+                self.compact(name, db).await;
+                *compacted_at = Instant::now();
+            }
+            sleep(METRICS_INTERVAL).await;
+        }
+    }
+
+    async fn compact(&self, name: &str, db: &DB) {
+        let start = Instant::now();
+        let compacting = db.clone();
+        // Compaction blocks until it is done, which can take minutes on a
+        // large db
+        if let Err(err) = tokio::task::spawn_blocking(move || compacting.compact()).await {
+            warn!(?err, db = name, "Failed to compact the db");
+            return;
+        }
+        info!(db = name, elapsed = ?start.elapsed(), "Compacted db");
+        if let Ok(stats) = db.size_stats() {
+            self.report(name, &stats);
+        }
+    }
+
+    async fn prune_messages(&self, retention: Duration) {
+        let cutoff = SystemTime::now()
+            .checked_sub(retention)
+            .and_then(|cutoff| cutoff.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |cutoff| cutoff.as_secs());
+        for db in &self.message_dbs {
+            let pruning = db.clone();
+            let pruned = tokio::task::spawn_blocking(move || {
+                pruning.prune_delivered_messages(cutoff, MAX_PRUNED_MESSAGES)
+            })
+            .await;
+            match pruned {
+                Ok(Ok(0)) => {}
+                Ok(Ok(pruned)) => {
+                    info!(origin = %db.domain(), pruned, "Pruned delivered messages from the db")
+                }
+                Ok(Err(err)) => {
+                    warn!(?err, origin = %db.domain(), "Failed to prune delivered messages")
+                }
+                Err(err) => {
+                    warn!(?err, origin = %db.domain(), "Failed to prune delivered messages")
+                }
+            }
+        }
+    }
+
+    fn report(&self, name: &str, stats: &DbSizeStats) {
+        for (kind, bytes) in [
+            ("sst_files", stats.sst_files_bytes),
+            ("live_data", stats.live_data_bytes),
+            ("memtables", stats.memtables_bytes),
+        ] {
+            self.size_bytes
+                .with_label_values(&[name, kind])
+                .set(bytes as i64);
+        }
+        self.estimated_keys
+            .with_label_values(&[name])
+            .set(stats.estimated_keys as i64);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{
+        GasPaymentKey, HyperlaneDomain, HyperlaneMessage, InterchainGasPayment,
+        KnownHyperlaneDomain, LogMeta, U256,
+    };
+
+    use crate::db::{test_utils, HyperlaneRocksDB};
+
+    #[tokio::test]
+    async fn test_size_stats_after_compaction() {
+        test_utils::run_test_db(|db| async move {
+            for i in 0u32..100 {
+                db.store(&i.to_be_bytes(), &[0; 64]).unwrap();
+            }
+            db.compact();
+            let stats = db.size_stats().unwrap();
+            assert!(stats.sst_files_bytes > 0);
+            assert!(stats.estimated_keys > 0);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_prune_delivered_messages() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Test1);
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let messages: Vec<_> = (0..3)
+                .map(|nonce| HyperlaneMessage {
+                    nonce,
+                    destination: 13372,
+                    ..Default::default()
+                })
+                .collect();
+            for message in &messages {
+                db.store_message(message, 1).unwrap();
+                db.store_pending_message_retry_count_by_message_id(&message.id(), &3)
+                    .unwrap();
+                db.store_deadline_missed_by_message_id(&message.id(), &true)
+                    .unwrap();
+                db.process_gas_payment(
+                    InterchainGasPayment {
+                        message_id: message.id(),
+                        destination: message.destination,
+                        payment: U256::from(100),
+                        gas_amount: U256::from(10),
+                    },
+                    &LogMeta {
+                        log_index: message.nonce.into(),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            }
+            // The second message isn't delivered yet
+            db.store_processed_by_nonce(&0, &true).unwrap();
+            db.store_processed_by_nonce(&2, &true).unwrap();
+
+            // All messages were indexed after the cutoff
+            assert_eq!(db.prune_delivered_messages(0, 10).unwrap(), 0);
+
+            assert_eq!(db.prune_delivered_messages(u64::MAX, 10).unwrap(), 1);
+            let pruned = messages[0].id();
+            assert_eq!(
+                db.retrieve_pending_message_retry_count_by_message_id(&pruned)
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                db.retrieve_deadline_missed_by_message_id(&pruned).unwrap(),
+                None
+            );
+            let payment = db
+                .retrieve_gas_payment_by_gas_payment_key(GasPaymentKey {
+                    message_id: pruned,
+                    destination: 13372,
+                })
+                .unwrap();
+            assert_eq!(payment.payment, U256::zero());
+            // The message itself is kept
+            assert_eq!(
+                db.retrieve_message_by_nonce(0).unwrap(),
+                Some(messages[0].clone())
+            );
+            // Pruning stops at the pending message
+            assert_eq!(
+                db.retrieve_pending_message_retry_count_by_message_id(&messages[2].id())
+                    .unwrap(),
+                Some(3)
+            );
+
+            db.store_processed_by_nonce(&1, &true).unwrap();
+            assert_eq!(db.prune_delivered_messages(u64::MAX, 1).unwrap(), 1);
+            assert_eq!(db.prune_delivered_messages(u64::MAX, 10).unwrap(), 1);
+            assert_eq!(
+                db.retrieve_pending_message_retry_count_by_message_id(&messages[2].id())
+                    .unwrap(),
+                None
+            );
+            assert_eq!(db.prune_delivered_messages(u64::MAX, 10).unwrap(), 0);
+        })
+        .await;
+    }
+}
@@ -0,0 +1,248 @@
+use rocksdb::IteratorMode;
+use tracing::{info, instrument};
+
+use super::{DbError, Result, DB};
+
+/// Key of the schema version of a db. Every other key starts with the name
+/// of a domain followed by `_`, so this can't collide with them.
+const SCHEMA_VERSION_KEY: &[u8] = b"__schema_version";
+
+/// A step changing the format of the data stored in a db
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Schema version of the db once the migration has run. Versions start at
+    /// 1 and each migration bumps it by one.
+    pub version: u32,
+    /// What the migration changes, for the logs
+    pub description: &'static str,
+    /// Rewrite the entries of the db. A migration interrupted midway is run
+    /// again from the start on the next startup, so it must be idempotent.
+    pub migrate: fn(&DB) -> Result<()>,
+}
+
+/// The migrations of the relayer and validator message dbs, in order.
+///
+/// To change the format of stored data, append a migration rewriting the
+/// existing entries rather than versioning the key prefix, which leaves
+/// operators to re-index from scratch.
+pub const HYPERLANE_DB_MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "Delete gas payments and expenditures stored under retired key prefixes",
+    migrate: delete_retired_gas_entries,
+}];
+
+/// Key prefixes gas payments and expenditures were stored under before their
+/// format changed and the prefix was versioned. Nothing reads them anymore.
+const RETIRED_GAS_PREFIXES: &[&[u8]] = &[
+    b"gas_payment_for_message_id_",
+    b"gas_payment_meta_processed_",
+    b"gas_expenditure_for_message_id_",
+];
+
+/// The prefixes that replaced the retired ones, some of which start with a
+/// retired prefix
+const CURRENT_GAS_PREFIXES: &[&[u8]] = &[
+    b"gas_payment_sequence_for_message_id_v2_",
+    b"gas_payment_meta_processed_v3_",
+    b"gas_expenditure_for_message_id_v2_",
+];
+
+fn delete_retired_gas_entries(db: &DB) -> Result<()> {
+    for entry in db.entries() {
+        let (key, _) = entry?;
+        if has_prefix(&key, RETIRED_GAS_PREFIXES) && !has_prefix(&key, CURRENT_GAS_PREFIXES) {
+            db.delete(&key)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `key` has one of `prefixes` after the name of its domain, i.e.
+/// after one of the underscores of its leading ascii characters
+fn has_prefix(key: &[u8], prefixes: &[&[u8]]) -> bool {
+    key.iter()
+        .enumerate()
+        .take_while(|(_, b)| b.is_ascii_graphic())
+        .filter(|(_, b)| **b == b'_')
+        .any(|(i, _)| {
+            prefixes
+                .iter()
+                .any(|prefix| key[i + 1..].starts_with(prefix))
+        })
+}
+
+impl DB {
+    /// The schema version stored in the db, if any
+    pub fn schema_version(&self) -> Result<Option<u32>> {
+        self.retrieve(SCHEMA_VERSION_KEY)?
+            .map(|bytes| {
+                bytes
+                    .try_into()
+                    .map(u32::from_be_bytes)
+                    .map_err(DbError::InvalidSchemaVersion)
+            })
+            .transpose()
+    }
+
+    fn store_schema_version(&self, version: u32) -> Result<()> {
+        self.store(SCHEMA_VERSION_KEY, &version.to_be_bytes())
+    }
+
+    /// Bring the db up to the latest version of `migrations`, running the
+    /// ones it is missing in order.
+    ///
+    /// A new db is created at the latest version. A db created before schema
+    /// versions were stored is at version 0, and every migration runs on it.
+    #[instrument(skip_all, err)]
+    pub fn migrate(&self, migrations: &[Migration]) -> Result<()> {
+        let latest = migrations.last().map_or(0, |migration| migration.version);
+        let stored = self.schema_version()?;
+        let current = match stored {
+            Some(version) => version,
+            None if self.is_empty()? => {
+                info!(
+                    version = latest,
+                    "Stamping new db with the latest schema version"
+                );
+                return self.store_schema_version(latest);
+            }
+            None => 0,
+        };
+        if current > latest {
+            return Err(DbError::UnsupportedSchemaVersion { current, latest });
+        }
+
+        for migration in migrations.iter().filter(|m| m.version > current) {
+            info!(
+                version = migration.version,
+                description = migration.description,
+                "Running db migration"
+            );
+            (migration.migrate)(self)?;
+            // Stored after each step so that an interrupted migration resumes
+            // where it stopped
+            self.store_schema_version(migration.version)?;
+        }
+        if current < latest {
+            info!(from = current, to = latest, "Migrated db");
+        } else if stored.is_none() {
+            self.store_schema_version(latest)?;
+        }
+        Ok(())
+    }
+
+    /// Iterate over all the entries of the DB, for migrations to rewrite them
+    pub fn entries(&self) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + '_ {
+        self.0
+            .iterator(IteratorMode::Start)
+            .map(|entry| entry.map_err(Into::into))
+    }
+
+    fn is_empty(&self) -> Result<bool> {
+        Ok(self.entries().next().transpose()?.is_none())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::db::test_utils;
+
+    use super::*;
+
+    const FOO: &[u8] = b"test_foo_";
+
+    /// Moves the values of the `test_foo_` keys to `test_bar_` keys
+    fn rename_foo(db: &DB) -> Result<()> {
+        for entry in db.entries() {
+            let (key, value) = entry?;
+            if let Some(suffix) = key.strip_prefix(FOO) {
+                db.store(&[&b"test_bar_"[..], suffix].concat(), &value)?;
+                db.delete(&key)?;
+            }
+        }
+        Ok(())
+    }
+
+    const MIGRATIONS: &[Migration] = &[Migration {
+        version: 1,
+        description: "Rename foo to bar",
+        migrate: rename_foo,
+    }];
+
+    #[tokio::test]
+    async fn test_migrates_existing_db() {
+        test_utils::run_test_db(|db| async move {
+            db.store(b"test_foo_1", b"value").unwrap();
+
+            db.migrate(MIGRATIONS).unwrap();
+            assert_eq!(db.schema_version().unwrap(), Some(1));
+            assert_eq!(db.retrieve(b"test_foo_1").unwrap(), None);
+            assert_eq!(db.retrieve(b"test_bar_1").unwrap(), Some(b"value".to_vec()));
+
+            // Migrating again is a no-op
+            db.store(b"test_foo_2", b"value").unwrap();
+            db.migrate(MIGRATIONS).unwrap();
+            assert!(db.retrieve(b"test_foo_2").unwrap().is_some());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_stamps_new_db() {
+        test_utils::run_test_db(|db| async move {
+            db.migrate(MIGRATIONS).unwrap();
+            assert_eq!(db.schema_version().unwrap(), Some(1));
+            // A db from a newer agent is refused
+            assert!(matches!(
+                db.migrate(&[]),
+                Err(DbError::UnsupportedSchemaVersion {
+                    current: 1,
+                    latest: 0
+                })
+            ));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_deletes_retired_gas_entries() {
+        test_utils::run_test_db(|db| async move {
+            // A db of an agent from before schema versions, with gas
+            // payments stored under both retired and current prefixes
+            let retired: &[&[u8]] = &[
+                b"ethereum_gas_payment_for_message_id_v2_\x01\x02",
+                b"ethereum_gas_payment_meta_processed_v2_\x03",
+                b"arbitrum_nova_gas_expenditure_for_message_id_\x04",
+            ];
+            let current: &[&[u8]] = &[
+                b"ethereum_gas_payment_sequence_for_message_id_v2_\x01\x02",
+                b"ethereum_gas_payment_meta_processed_v3_\x03",
+                b"arbitrum_nova_gas_expenditure_for_message_id_v2_\x04",
+                b"ethereum_message_\x05",
+            ];
+            for key in retired.iter().chain(current) {
+                db.store(key, b"value").unwrap();
+            }
+
+            db.migrate(HYPERLANE_DB_MIGRATIONS).unwrap();
+            assert_eq!(
+                db.schema_version().unwrap(),
+                HYPERLANE_DB_MIGRATIONS.last().map(|m| m.version)
+            );
+            for key in retired {
+                assert_eq!(db.retrieve(key).unwrap(), None);
+            }
+            for key in current {
+                assert!(db.retrieve(key).unwrap().is_some());
+            }
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_migration_versions_are_sequential() {
+        for (i, migration) in HYPERLANE_DB_MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, i + 1);
+        }
+    }
+}
@@ -6,6 +6,8 @@ use rocksdb::{Options, DB as Rocks};
 use tracing::info;
 
 pub use hyperlane_db::*;
+pub use maintenance::*;
+pub use migration::*;
 pub use typed_db::*;
 
 /// Shared functionality surrounding use of rocksdb
@@ -13,6 +15,10 @@ pub mod iterator;
 
 /// DB operations tied to specific Mailbox
 mod hyperlane_db;
+/// Scheduled compaction and size metrics
+mod maintenance;
+/// Schema versions and migrations
+mod migration;
 /// Type-specific db operations
 mod typed_db;
 
@@ -56,6 +62,17 @@ pub enum DbError {
     /// Hyperlane Error
     #[error("{0}")]
     HyperlaneError(#[from] HyperlaneProtocolError),
+    /// The stored schema version isn't a big endian u32
+    #[error("Invalid schema version {0:?}")]
+    InvalidSchemaVersion(Vec<u8>),
+    /// The db was migrated by a newer version of the agent
+    #[error("Db is at schema version {current}, newer than the latest known version {latest}")]
+    UnsupportedSchemaVersion {
+        /// Schema version of the db
+        current: u32,
+        /// Latest schema version known to this agent
+        latest: u32,
+    },
 }
 
 impl From<DbError> for ChainCommunicationError {
@@ -70,17 +87,7 @@ impl DB {
     /// Opens db at `db_path` and creates if missing
     #[tracing::instrument(err)]
     pub fn from_path(db_path: &Path) -> Result<DB> {
-        let path = {
-            let mut path = db_path
-                .parent()
-                .unwrap_or(Path::new("."))
-                .canonicalize()
-                .map_err(|e| DbError::InvalidDbPath(e, db_path.to_string_lossy().into()))?;
-            if let Some(file_name) = db_path.file_name() {
-                path.push(file_name);
-            }
-            path
-        };
+        let path = Self::canonicalize_path(db_path)?;
 
         if path.is_dir() {
             info!(path=%path.to_string_lossy(), "Opening existing db")
@@ -100,6 +107,32 @@ impl DB {
             .map(Into::into)
     }
 
+    /// Opens an existing db at `db_path` in read-only mode. This does not take
+    /// the db lock, so it can be used to inspect the db of a running agent.
+    #[tracing::instrument(err)]
+    pub fn from_path_read_only(db_path: &Path) -> Result<DB> {
+        let path = Self::canonicalize_path(db_path)?;
+        Rocks::open_for_read_only(&Options::default(), &path, false)
+            .map_err(|e| DbError::OpeningError {
+                source: e,
+                path: db_path.into(),
+                canonicalized: path,
+            })
+            .map(Into::into)
+    }
+
+    fn canonicalize_path(db_path: &Path) -> Result<PathBuf> {
+        let mut path = db_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .canonicalize()
+            .map_err(|e| DbError::InvalidDbPath(e, db_path.to_string_lossy().into()))?;
+        if let Some(file_name) = db_path.file_name() {
+            path.push(file_name);
+        }
+        Ok(path)
+    }
+
     /// Store a value in the DB
     pub fn store(&self, key: &[u8], value: &[u8]) -> Result<()> {
         Ok(self.0.put(key, value)?)
@@ -109,4 +142,9 @@ impl DB {
     pub fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
         Ok(self.0.get(key)?)
     }
+
+    /// Delete a value from the DB
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        Ok(self.0.delete(key)?)
+    }
 }
@@ -0,0 +1,255 @@
+use std::{
+    env,
+    fmt::{self, Display},
+    path::Path,
+};
+
+use eyre::{bail, eyre, Result};
+use futures_util::future::join_all;
+use hyperlane_core::{HyperlaneProvider, U256};
+use itertools::Itertools;
+
+use crate::{
+    db::{DB, HYPERLANE_DB_MIGRATIONS},
+    settings::{ChainConf, Settings},
+    CoreMetrics,
+};
+
+/// The first argument an agent is started with to diagnose its setup
+/// instead of running, e.g. `relayer doctor`. Any config arguments follow.
+pub const DOCTOR_COMMAND: &str = "doctor";
+
+/// Whether the agent was started with the `doctor` command
+pub fn doctor_requested() -> bool {
+    env::args_os()
+        .nth(1)
+        .map_or(false, |arg| arg == DOCTOR_COMMAND)
+}
+
+/// The outcome of one check of the `doctor` command
+#[derive(Debug, Clone)]
+pub struct DoctorCheck {
+    /// What was checked, e.g. the name of a chain
+    pub subject: String,
+    /// The kind of check, e.g. `rpc`
+    pub check: &'static str,
+    /// Whether the check passed
+    pub passed: bool,
+    /// What was found, or why the check failed
+    pub detail: String,
+}
+
+/// The checks of the `doctor` command, printed as a pass/fail report
+#[derive(Debug, Default)]
+pub struct DoctorReport {
+    checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// Record a check, which passed if `result` is Ok
+    pub fn record(
+        &mut self,
+        subject: impl Into<String>,
+        check: &'static str,
+        result: Result<String>,
+    ) {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(err) => (false, format!("{err:#}")),
+        };
+        self.checks.push(DoctorCheck {
+            subject: subject.into(),
+            check,
+            passed,
+            detail,
+        });
+    }
+
+    /// Add the checks of another report
+    pub fn extend(&mut self, other: DoctorReport) {
+        self.checks.extend(other.checks);
+    }
+
+    /// The checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &DoctorCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+}
+
+impl Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let subject_width = self
+            .checks
+            .iter()
+            .map(|check| check.subject.len())
+            .max()
+            .unwrap_or_default();
+        let check_width = self
+            .checks
+            .iter()
+            .map(|check| check.check.len())
+            .max()
+            .unwrap_or_default();
+        for check in &self.checks {
+            writeln!(
+                f,
+                "[{}] {:subject_width$}  {:check_width$}  {}",
+                if check.passed { "PASS" } else { "FAIL" },
+                check.subject,
+                check.check,
+                check.detail,
+            )?;
+        }
+        write!(
+            f,
+            "{} checks, {} failed",
+            self.checks.len(),
+            self.failures().count()
+        )
+    }
+}
+
+/// Check the rpc, chain id and signer of every chain of the settings. Chains
+/// are checked concurrently and reported in order of their name.
+pub async fn diagnose_chains(settings: &Settings, metrics: &CoreMetrics) -> DoctorReport {
+    let chains = settings
+        .chains
+        .iter()
+        .sorted_by_key(|(name, _)| name.as_str())
+        .map(|(name, conf)| diagnose_chain(name, conf, metrics));
+    let mut report = DoctorReport::default();
+    for chain_report in join_all(chains).await {
+        report.extend(chain_report);
+    }
+    report
+}
+
+async fn diagnose_chain(name: &str, conf: &ChainConf, metrics: &CoreMetrics) -> DoctorReport {
+    let mut report = DoctorReport::default();
+    let provider = match conf.build_provider(metrics).await {
+        Ok(provider) => provider,
+        Err(err) => {
+            report.record(name, "rpc", Err(err));
+            return report;
+        }
+    };
+
+    let rpc = provider.get_chain_metrics().await;
+    let reachable = rpc.is_ok();
+    report.record(
+        name,
+        "rpc",
+        match rpc {
+            Ok(Some(info)) => Ok(format!("latest block {}", info.latest_block.number)),
+            Ok(None) => Ok("connected".to_owned()),
+            Err(err) => Err(err.into()),
+        },
+    );
+    // Everything else is read over the rpc
+    if !reachable {
+        return report;
+    }
+
+    if let Some(expected) = &conf.chain_id {
+        match provider.get_chain_id().await {
+            Ok(Some(actual)) if actual == *expected => {
+                report.record(name, "chain id", Ok(actual));
+            }
+            Ok(Some(actual)) => report.record(
+                name,
+                "chain id",
+                Err(eyre!("Rpc is for chain {actual}, expected {expected}")),
+            ),
+            // The provider of the protocol can't tell
+            Ok(None) => {}
+            Err(err) => report.record(name, "chain id", Err(err.into())),
+        }
+    }
+
+    if conf.signer.is_some() {
+        report.record(name, "signer", check_signer(conf, &*provider).await);
+    }
+    report
+}
+
+/// Check that the signer can be loaded, which needs permission to use its
+/// key when it is held remotely, and that it can pay for transactions
+async fn check_signer(conf: &ChainConf, provider: &dyn HyperlaneProvider) -> Result<String> {
+    let Some(signer) = conf.chain_signer().await? else {
+        bail!("Signer is not supported for the protocol of the chain");
+    };
+    let address = signer.address_string();
+    let balance = provider.get_balance(address.clone()).await?;
+    if balance == U256::zero() {
+        bail!("Signer {address} has no funds to pay for transactions");
+    }
+    Ok(format!("{address} has a balance of {balance}"))
+}
+
+/// Check a message db of a relayer or validator, and that it is at a schema
+/// version this agent can migrate from. Opened read-only, so it can be
+/// checked while the agent runs.
+pub fn check_rocks_db(path: &Path) -> Result<String> {
+    if !path.exists() {
+        let ancestor = path
+            .ancestors()
+            .skip(1)
+            .find(|ancestor| ancestor.exists())
+            .unwrap_or_else(|| Path::new("."));
+        if ancestor.metadata()?.permissions().readonly() {
+            bail!("{} is not writable to create the db in", ancestor.display());
+        }
+        return Ok(format!("{} will be created on startup", path.display()));
+    }
+
+    let db = DB::from_path_read_only(path)?;
+    let latest = HYPERLANE_DB_MIGRATIONS
+        .last()
+        .map_or(0, |migration| migration.version);
+    match db.schema_version()? {
+        Some(current) if current > latest => bail!(
+            "Db is at schema version {current}, newer than the latest this agent supports ({latest})"
+        ),
+        Some(current) if current < latest => Ok(format!(
+            "at schema version {current}, migrated to {latest} on startup"
+        )),
+        Some(current) => Ok(format!("at schema version {current}")),
+        None => Ok(format!(
+            "not versioned yet, migrated to {latest} on startup"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_failures() {
+        let mut report = DoctorReport::default();
+        report.record("ethereum", "rpc", Ok("latest block 1".to_owned()));
+        report.record("db", "rocksdb", Err(eyre!("Permission denied")));
+
+        assert_eq!(report.failures().count(), 1);
+        assert_eq!(
+            report.to_string(),
+            "[PASS] ethereum  rpc      latest block 1\n\
+             [FAIL] db        rocksdb  Permission denied\n\
+             2 checks, 1 failed"
+        );
+    }
+
+    #[test]
+    fn test_rocks_db_is_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        assert!(check_rocks_db(&path)
+            .unwrap()
+            .ends_with("will be created on startup"));
+
+        let db = DB::from_path(&path).unwrap();
+        db.migrate(HYPERLANE_DB_MIGRATIONS).unwrap();
+        drop(db);
+        assert_eq!(check_rocks_db(&path).unwrap(), "at schema version 1");
+    }
+}
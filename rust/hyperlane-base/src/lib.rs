@@ -12,6 +12,10 @@ pub mod settings;
 mod agent;
 pub use agent::*;
 
+/// Diagnosing the setup of an agent
+mod doctor;
+pub use doctor::*;
+
 pub mod metrics;
 pub use metrics::*;
 
@@ -19,6 +23,10 @@ pub use metrics::*;
 pub mod server;
 pub use server::*;
 
+/// Failing fast on chains whose calls keep failing
+mod circuit_breaker;
+pub use circuit_breaker::*;
+
 mod contract_sync;
 pub use contract_sync::*;
 
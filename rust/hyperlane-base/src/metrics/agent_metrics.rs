@@ -1,7 +1,7 @@
 //! Metrics either related to the agents, or observed by them
 
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use derive_builder::Builder;
 use eyre::Result;
@@ -44,6 +44,12 @@ pub const GAS_PRICE_LABELS: &[&str] = &["chain"];
 pub const GAS_PRICE_HELP: &str =
     "Tracks the current gas price of the chain, in the lowest denomination (e.g. wei)";
 
+/// Expected label names for the `last_successful_rpc_call_timestamp` metric.
+pub const LAST_SUCCESSFUL_RPC_CALL_LABELS: &[&str] = &["chain"];
+/// Help string for the metric.
+pub const LAST_SUCCESSFUL_RPC_CALL_HELP: &str =
+    "Unix timestamp in seconds of the last successful RPC call made to the chain";
+
 /// Agent-specific metrics
 #[derive(Clone, Builder, Debug)]
 pub struct AgentMetrics {
@@ -86,6 +92,13 @@ pub struct ChainMetrics {
     ///   chain the gas price refers to.
     #[builder(setter(into, strip_option), default)]
     pub gas_price: Option<GaugeVec>,
+
+    /// Unix timestamp in seconds of the last successful RPC call made to the
+    /// chain, used to report the health of the agent.
+    /// - `chain`: the chain name (or chain ID if the name is unknown) of the
+    ///   chain the call was made to.
+    #[builder(setter(into, strip_option), default)]
+    pub last_successful_rpc_call: Option<IntGaugeVec>,
 }
 
 pub(crate) fn create_chain_metrics(metrics: &CoreMetrics) -> Result<ChainMetrics> {
@@ -96,6 +109,11 @@ pub(crate) fn create_chain_metrics(metrics: &CoreMetrics) -> Result<ChainMetrics
             BLOCK_HEIGHT_LABELS,
         )?)
         .gas_price(metrics.new_gauge("gas_price", GAS_PRICE_HELP, GAS_PRICE_LABELS)?)
+        .last_successful_rpc_call(metrics.new_int_gauge(
+            "last_successful_rpc_call_timestamp",
+            LAST_SUCCESSFUL_RPC_CALL_HELP,
+            LAST_SUCCESSFUL_RPC_CALL_LABELS,
+        )?)
         .build()?)
 }
 
@@ -155,6 +173,7 @@ impl MetricsUpdater {
 
         match self.provider.get_balance(wallet_addr.clone()).await {
             Ok(balance) => {
+                self.record_rpc_success();
                 let balance = u256_as_scaled_f64(balance, self.conf.domain.domain_protocol());
                 trace!("Wallet {wallet_name} ({wallet_addr}) on chain {chain} balance is {balance} of the native currency");
                 wallet_balance_metric
@@ -190,6 +209,7 @@ impl MetricsUpdater {
             _ => return,
         };
 
+        self.record_rpc_success();
         let height = chain_metrics.latest_block.number as i64;
         trace!("Block height for chain {chain} is {height}");
         block_height
@@ -209,6 +229,19 @@ impl MetricsUpdater {
         }
     }
 
+    fn record_rpc_success(&self) {
+        let Some(last_successful_rpc_call) = &self.chain_metrics.last_successful_rpc_call else {
+            return;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        last_successful_rpc_call
+            .with(&hashmap! { "chain" => self.conf.domain.name() })
+            .set(now);
+    }
+
     /// Periodically updates the metrics
     pub async fn start_updating_on_interval(self, period: Duration) {
         let mut interval = tokio::time::interval(period);
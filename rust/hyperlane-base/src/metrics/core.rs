@@ -51,6 +51,30 @@ pub struct CoreMetrics {
     /// Set of provider-specific metrics. These only need to get created once.
     provider_metrics: OnceLock<MiddlewareMetrics>,
 
+    /// Submissions of Sealevel process transactions, only created for agents
+    /// delivering to Sealevel chains.
+    sealevel_submissions_count: OnceLock<IntCounterVec>,
+
+    /// Reverted delivery simulations by their decoded error, only created
+    /// for the relayer.
+    simulation_reverts_count: OnceLock<IntCounterVec>,
+
+    /// Fixed gas limits of messages by how they compare to the estimate they
+    /// replaced, only created for the relayer.
+    fixed_gas_limit_comparisons_count: OnceLock<IntCounterVec>,
+
+    /// Delivery simulations run before submitting messages by their outcome,
+    /// only created for the relayer.
+    process_simulations_count: OnceLock<IntCounterVec>,
+
+    /// Messages awaiting a quorum of validator signatures, only created for
+    /// the relayer.
+    messages_awaiting_quorum: OnceLock<IntGaugeVec>,
+
+    /// Messages not delivered by their delivery deadline, only created for
+    /// the relayer.
+    delivery_deadline_misses_count: OnceLock<IntCounterVec>,
+
     /// Metrics that are used to observe validator sets.
     pub validator_metrics: ValidatorObservabilityMetricManager,
 }
@@ -186,6 +210,12 @@ impl CoreMetrics {
 
             json_rpc_client_metrics: OnceLock::new(),
             provider_metrics: OnceLock::new(),
+            sealevel_submissions_count: OnceLock::new(),
+            simulation_reverts_count: OnceLock::new(),
+            fixed_gas_limit_comparisons_count: OnceLock::new(),
+            process_simulations_count: OnceLock::new(),
+            messages_awaiting_quorum: OnceLock::new(),
+            delivery_deadline_misses_count: OnceLock::new(),
 
             validator_metrics: ValidatorObservabilityMetricManager::new(
                 observed_validator_latest_index.clone(),
@@ -212,6 +242,130 @@ impl CoreMetrics {
             .clone()
     }
 
+    /// The number of Sealevel transactions submitted to process messages.
+    ///
+    /// Labels:
+    /// - `chain`: Chain the transaction was submitted to.
+    /// - `path`: `jito` if submitted as a Jito bundle, `rpc` if submitted
+    ///   through the RPC.
+    /// - `status`: `landed` or `failed`. A failed bundle is followed by a
+    ///   submission through the RPC.
+    pub fn sealevel_submissions_count(&self) -> IntCounterVec {
+        self.sealevel_submissions_count
+            .get_or_init(|| {
+                self.new_int_counter(
+                    "sealevel_submissions_count",
+                    "Number of Sealevel transactions submitted to process messages",
+                    &["chain", "path", "status"],
+                )
+                .expect("Failed to create sealevel submission metrics!")
+            })
+            .clone()
+    }
+
+    /// The number of message deliveries whose simulation reverted, by the error
+    /// they reverted with. Only reverts whose data could be decoded are
+    /// counted.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain the message comes from.
+    /// - `remote`: Destination chain the message is delivered to.
+    /// - `error`: Name of the error, e.g. `Error` for revert strings, or its
+    ///   selector if it isn't known.
+    pub fn simulation_reverts_count(&self) -> IntCounterVec {
+        self.simulation_reverts_count
+            .get_or_init(|| {
+                self.new_int_counter(
+                    "simulation_reverts_count",
+                    "Number of message delivery simulations that reverted",
+                    &["origin", "remote", "error"],
+                )
+                .expect("Failed to create simulation revert metrics!")
+            })
+            .clone()
+    }
+
+    /// Messages delivered with a fixed gas limit instead of their estimate,
+    /// by how the fixed limit compares to the estimate.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain the message comes from.
+    /// - `remote`: Destination chain the message is delivered to.
+    /// - `comparison`: `equal`, `higher` or `lower` than the estimate, or
+    ///   `estimation_failed`.
+    pub fn fixed_gas_limit_comparisons_count(&self) -> IntCounterVec {
+        self.fixed_gas_limit_comparisons_count
+            .get_or_init(|| {
+                self.new_int_counter(
+                    "fixed_gas_limit_comparisons_count",
+                    "Number of fixed gas limits used instead of an estimate, by how they compare to it",
+                    &["origin", "remote", "comparison"],
+                )
+                .expect("Failed to create fixed gas limit comparison metrics!")
+            })
+            .clone()
+    }
+
+    /// The number of delivery simulations run right before submitting a
+    /// message, by their outcome.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain the message comes from.
+    /// - `remote`: Destination chain the message is delivered to.
+    /// - `outcome`: `would_succeed`, `ism_verify_failed`,
+    ///   `recipient_reverted`, `out_of_gas` or `reverted`.
+    pub fn process_simulations_count(&self) -> IntCounterVec {
+        self.process_simulations_count
+            .get_or_init(|| {
+                self.new_int_counter(
+                    "process_simulations_count",
+                    "Number of delivery simulations run before submitting a message, by outcome",
+                    &["origin", "remote", "outcome"],
+                )
+                .expect("Failed to create process simulation metrics!")
+            })
+            .clone()
+    }
+
+    /// The number of messages whose metadata can't be built until a quorum
+    /// of validators signed a checkpoint including them, which are checked
+    /// for one without building their metadata again.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain the messages come from.
+    /// - `remote`: Destination chain the messages are delivered to.
+    pub fn messages_awaiting_quorum(&self) -> IntGaugeVec {
+        self.messages_awaiting_quorum
+            .get_or_init(|| {
+                self.new_int_gauge(
+                    "messages_awaiting_quorum",
+                    "Number of messages awaiting a quorum of validator signatures before their metadata is built",
+                    &["origin", "remote"],
+                )
+                .expect("Failed to create messages awaiting quorum metrics!")
+            })
+            .clone()
+    }
+
+    /// The number of messages that weren't delivered by the deadline of
+    /// their app.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain the messages come from.
+    /// - `remote`: Destination chain the messages are delivered to.
+    pub fn delivery_deadline_misses_count(&self) -> IntCounterVec {
+        self.delivery_deadline_misses_count
+            .get_or_init(|| {
+                self.new_int_counter(
+                    "delivery_deadline_misses_count",
+                    "Number of messages not delivered by the deadline of their app",
+                    &["origin", "remote"],
+                )
+                .expect("Failed to create delivery deadline miss metrics!")
+            })
+            .clone()
+    }
+
     /// Create and register a new int gauge.
     pub fn new_int_gauge(
         &self,
@@ -314,6 +468,8 @@ impl CoreMetrics {
     /// - `validator_observed`: When the validator has observed the checkpoint
     ///   on the mailbox contract.
     /// - `validator_processed`: When the validator has written this checkpoint.
+    /// - `validator_tip`: The latest merkle tree index at the chain tip,
+    ///   ignoring the reorg period.
     pub fn latest_checkpoint(&self) -> IntGaugeVec {
         self.latest_checkpoint.clone()
     }
@@ -413,6 +569,11 @@ impl CoreMetrics {
         Ok(out_buf)
     }
 
+    /// Gather the current value of all registered metrics.
+    pub(crate) fn gather_families(&self) -> Vec<prometheus::proto::MetricFamily> {
+        self.registry.gather()
+    }
+
     /// Get the name of this agent, e.g. "relayer"
     pub fn agent_name(&self) -> &str {
         &self.agent_name
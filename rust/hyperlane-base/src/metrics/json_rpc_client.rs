@@ -17,5 +17,21 @@ pub(crate) fn create_json_rpc_client_metrics(
             REQUEST_DURATION_SECONDS_HELP,
             REQUEST_DURATION_SECONDS_LABELS,
         )?)
+        .throttled_request_count(metrics.new_int_counter(
+            "throttled_request_count",
+            THROTTLED_REQUEST_COUNT_HELP,
+            THROTTLED_REQUEST_COUNT_LABELS,
+        )?)
+        .request_latency_seconds(metrics.new_histogram(
+            "request_latency_seconds",
+            REQUEST_LATENCY_SECONDS_HELP,
+            REQUEST_LATENCY_SECONDS_LABELS,
+            REQUEST_LATENCY_SECONDS_BUCKETS.to_vec(),
+        )?)
+        .request_error_count(metrics.new_int_counter(
+            "request_error_count",
+            REQUEST_ERROR_COUNT_HELP,
+            REQUEST_ERROR_COUNT_LABELS,
+        )?)
         .build()?)
 }
@@ -10,5 +10,7 @@ mod core;
 mod agent_metrics;
 mod json_rpc_client;
 mod provider;
+mod push;
 
 pub use self::agent_metrics::*;
+pub use self::push::*;
@@ -0,0 +1,365 @@
+//! Pushing the agent's metrics, for environments where the metrics port can't
+//! be scraped. Metrics are still served on `/metrics` as well.
+
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use eyre::{eyre, Context, Result};
+use prometheus::proto::{Metric, MetricFamily, MetricType};
+use prost::Message;
+use reqwest::{header, Client, RequestBuilder};
+use tokio::{task::JoinHandle, time::sleep};
+use tracing::{debug, info_span, instrument::Instrumented, warn, Instrument};
+use url::Url;
+
+use crate::CoreMetrics;
+
+/// How often metrics are pushed if no interval is configured
+pub const DEFAULT_PUSH_INTERVAL: Duration = Duration::from_secs(15);
+
+const TEXT_FORMAT: &str = "text/plain; version=0.0.4";
+const REMOTE_WRITE_VERSION: &str = "0.1.0";
+
+/// Where the metrics are pushed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetricsPushTarget {
+    /// A Prometheus Pushgateway. Every push replaces the metrics of the `job`
+    /// group, which defaults to the name of the agent.
+    Pushgateway {
+        /// Base url of the gateway, e.g. `http://pushgateway:9091`
+        url: Url,
+        /// Name of the group the metrics are pushed to
+        job: Option<String>,
+    },
+    /// A Prometheus remote-write endpoint, e.g. of Prometheus, Mimir or
+    /// Grafana Cloud
+    RemoteWrite {
+        /// Full url of the endpoint, e.g. `http://prometheus:9090/api/v1/write`
+        url: Url,
+    },
+}
+
+/// Credentials sent with every push
+#[derive(Clone, PartialEq, Eq)]
+pub enum MetricsPushAuth {
+    /// HTTP basic auth
+    Basic {
+        /// The username
+        username: String,
+        /// The password
+        password: String,
+    },
+    /// A bearer token
+    Bearer(String),
+}
+
+impl Debug for MetricsPushAuth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .finish_non_exhaustive(),
+            Self::Bearer(_) => f.write_str("Bearer(..)"),
+        }
+    }
+}
+
+/// Pushing the agent's metrics to a target on an interval
+#[derive(Debug, Clone)]
+pub struct MetricsPushConf {
+    /// Where the metrics are pushed to
+    pub target: MetricsPushTarget,
+    /// Time between two pushes
+    pub interval: Duration,
+    /// Credentials sent with every push
+    pub auth: Option<MetricsPushAuth>,
+    /// Additional headers sent with every push, e.g. `X-Scope-OrgID`
+    pub headers: HashMap<String, String>,
+}
+
+/// Pushes the agent's metrics to a target
+#[derive(Debug)]
+pub struct MetricsPusher {
+    conf: MetricsPushConf,
+    metrics: Arc<CoreMetrics>,
+    client: Client,
+}
+
+impl MetricsPusher {
+    /// Create a pusher of the metrics of `metrics`
+    pub fn new(conf: MetricsPushConf, metrics: Arc<CoreMetrics>) -> Self {
+        Self {
+            conf,
+            metrics,
+            client: Client::new(),
+        }
+    }
+
+    /// Push the metrics in the background
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("MetricsPusher", target = ?self.conf.target);
+        tokio::spawn(async move { self.run().await }).instrument(span)
+    }
+
+    async fn run(self) {
+        loop {
+            match self.push().await {
+                Ok(()) => debug!("Pushed metrics"),
+                Err(err) => warn!(?err, "Failed to push metrics"),
+            }
+            sleep(self.conf.interval).await;
+        }
+    }
+
+    async fn push(&self) -> Result<()> {
+        let request = match &self.conf.target {
+            MetricsPushTarget::Pushgateway { url, job } => {
+                let job = job.as_deref().unwrap_or(self.metrics.agent_name());
+                self.client
+                    .put(pushgateway_url(url, job)?)
+                    .header(header::CONTENT_TYPE, TEXT_FORMAT)
+                    .body(self.metrics.gather()?)
+            }
+            MetricsPushTarget::RemoteWrite { url } => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+                let body =
+                    remote_write_request(&self.metrics.gather_families(), now).encode_to_vec();
+                let body = snap::raw::Encoder::new()
+                    .compress_vec(&body)
+                    .context("Failed to compress the remote-write request")?;
+                self.client
+                    .post(url.clone())
+                    .header(header::CONTENT_TYPE, "application/x-protobuf")
+                    .header(header::CONTENT_ENCODING, "snappy")
+                    .header("X-Prometheus-Remote-Write-Version", REMOTE_WRITE_VERSION)
+                    .body(body)
+            }
+        };
+
+        let response = self.with_auth(request).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(eyre!("Push was rejected with status {status}: {body}"));
+        }
+        Ok(())
+    }
+
+    fn with_auth(&self, mut request: RequestBuilder) -> RequestBuilder {
+        request = match &self.conf.auth {
+            Some(MetricsPushAuth::Basic { username, password }) => {
+                request.basic_auth(username, Some(password))
+            }
+            Some(MetricsPushAuth::Bearer(token)) => request.bearer_auth(token),
+            None => request,
+        };
+        for (name, value) in &self.conf.headers {
+            request = request.header(name, value);
+        }
+        request
+    }
+}
+
+/// The url the metrics of `job` are pushed to on a Pushgateway
+fn pushgateway_url(base: &Url, job: &str) -> Result<Url> {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|_| eyre!("Pushgateway url `{base}` can't be a base"))?
+        .pop_if_empty()
+        .extend(["metrics", "job", job]);
+    Ok(url)
+}
+
+/// The remote-write protobuf messages, see
+/// https://prometheus.io/docs/concepts/remote_write_spec/
+mod remote_write {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct WriteRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub timeseries: Vec<TimeSeries>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TimeSeries {
+        /// Sorted by name, starting with `__name__`
+        #[prost(message, repeated, tag = "1")]
+        pub labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        pub samples: Vec<Sample>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Label {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub value: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sample {
+        #[prost(double, tag = "1")]
+        pub value: f64,
+        /// Milliseconds since the unix epoch
+        #[prost(int64, tag = "2")]
+        pub timestamp: i64,
+    }
+}
+
+/// A remote-write request of the current value of every metric, with the
+/// series histograms and summaries are exposed as in the text format
+fn remote_write_request(families: &[MetricFamily], now: Duration) -> remote_write::WriteRequest {
+    let timestamp = now.as_millis() as i64;
+    let mut timeseries = vec![];
+    for family in families {
+        let name = family.get_name();
+        for metric in family.get_metric() {
+            let mut series = |suffix: &str, extra_label: Option<(&str, String)>, value: f64| {
+                timeseries.push(time_series(
+                    &format!("{name}{suffix}"),
+                    metric,
+                    extra_label,
+                    value,
+                    timestamp,
+                ))
+            };
+            match family.get_field_type() {
+                MetricType::COUNTER => series("", None, metric.get_counter().get_value()),
+                MetricType::GAUGE => series("", None, metric.get_gauge().get_value()),
+                MetricType::UNTYPED => series("", None, metric.get_untyped().get_value()),
+                MetricType::HISTOGRAM => {
+                    let histogram = metric.get_histogram();
+                    for bucket in histogram.get_bucket() {
+                        series(
+                            "_bucket",
+                            Some(("le", bucket.get_upper_bound().to_string())),
+                            bucket.get_cumulative_count() as f64,
+                        );
+                    }
+                    let count = histogram.get_sample_count() as f64;
+                    series("_bucket", Some(("le", "+Inf".to_owned())), count);
+                    series("_sum", None, histogram.get_sample_sum());
+                    series("_count", None, count);
+                }
+                MetricType::SUMMARY => {
+                    let summary = metric.get_summary();
+                    for quantile in summary.get_quantile() {
+                        series(
+                            "",
+                            Some(("quantile", quantile.get_quantile().to_string())),
+                            quantile.get_value(),
+                        );
+                    }
+                    series("_sum", None, summary.get_sample_sum());
+                    series("_count", None, summary.get_sample_count() as f64);
+                }
+            }
+        }
+    }
+    remote_write::WriteRequest { timeseries }
+}
+
+fn time_series(
+    name: &str,
+    metric: &Metric,
+    extra_label: Option<(&str, String)>,
+    value: f64,
+    timestamp: i64,
+) -> remote_write::TimeSeries {
+    let label = |name: &str, value: &str| remote_write::Label {
+        name: name.to_owned(),
+        value: value.to_owned(),
+    };
+    let mut labels: Vec<_> = std::iter::once(label("__name__", name))
+        .chain(
+            metric
+                .get_label()
+                .iter()
+                .map(|pair| label(pair.get_name(), pair.get_value())),
+        )
+        .chain(extra_label.map(|(name, value)| label(name, &value)))
+        .collect();
+    labels.sort_by(|a, b| a.name.cmp(&b.name));
+    remote_write::TimeSeries {
+        labels,
+        samples: vec![remote_write::Sample { value, timestamp }],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use prometheus::{Histogram, HistogramOpts, IntCounterVec, Opts, Registry};
+
+    use super::*;
+
+    #[test]
+    fn test_pushgateway_url() {
+        let url = |base: &str| {
+            pushgateway_url(&base.parse().unwrap(), "relayer")
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            url("http://pushgateway:9091"),
+            "http://pushgateway:9091/metrics/job/relayer"
+        );
+        assert_eq!(
+            url("https://example.com/gateway/"),
+            "https://example.com/gateway/metrics/job/relayer"
+        );
+    }
+
+    #[test]
+    fn test_remote_write_request() {
+        let registry = Registry::new();
+        let counter =
+            IntCounterVec::new(Opts::new("messages", "help"), &["remote", "origin"]).unwrap();
+        counter.with_label_values(&["test2", "test1"]).inc_by(3);
+        let histogram =
+            Histogram::with_opts(HistogramOpts::new("latency", "help").buckets(vec![1., 5.]))
+                .unwrap();
+        histogram.observe(2.);
+        registry.register(Box::new(counter)).unwrap();
+        registry.register(Box::new(histogram)).unwrap();
+
+        let request = remote_write_request(&registry.gather(), Duration::from_millis(1234));
+        let series: Vec<(Vec<(&str, &str)>, f64)> = request
+            .timeseries
+            .iter()
+            .map(|series| {
+                assert_eq!(series.samples.len(), 1);
+                assert_eq!(series.samples[0].timestamp, 1234);
+                let labels = series
+                    .labels
+                    .iter()
+                    .map(|l| (l.name.as_str(), l.value.as_str()))
+                    .collect();
+                (labels, series.samples[0].value)
+            })
+            .collect();
+
+        assert_eq!(
+            series,
+            vec![
+                (vec![("__name__", "latency_bucket"), ("le", "1")], 0.),
+                (vec![("__name__", "latency_bucket"), ("le", "5")], 1.),
+                (vec![("__name__", "latency_bucket"), ("le", "+Inf")], 1.),
+                (vec![("__name__", "latency_sum")], 2.),
+                (vec![("__name__", "latency_count")], 1.),
+                (
+                    vec![
+                        ("__name__", "messages"),
+                        ("origin", "test1"),
+                        ("remote", "test2")
+                    ],
+                    3.
+                ),
+            ]
+        );
+    }
+}
@@ -1,4 +1,8 @@
-use crate::CoreMetrics;
+use crate::{
+    server::{HealthApi, LogFilterApi},
+    settings::LogFilter,
+    CoreMetrics, MetricsPushConf, MetricsPusher,
+};
 use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
 use derive_new::new;
 use std::{net::SocketAddr, sync::Arc};
@@ -8,6 +12,7 @@ use tokio::task::JoinHandle;
 #[derive(new, Debug)]
 pub struct Server {
     listen_port: u16,
+    metrics_push: Vec<MetricsPushConf>,
     core_metrics: Arc<CoreMetrics>,
 }
 
@@ -21,7 +26,10 @@ impl Server {
     ///
     /// routes:
     ///  - metrics - serving OpenMetrics format reports on `/metrics`
-    ///     (this is compatible with Prometheus, which ought to be configured to scrape this endpoint)
+    ///     (this is compatible with Prometheus, which ought to be configured to scrape this endpoint),
+    ///     and pushed to each configured Pushgateway or remote-write endpoint
+    ///  - health - per-chain status as JSON on `/healthz` and `/readyz`
+    ///  - log_filter - changing the log filter at runtime on `/log_filter`
     ///  - custom_routes - additional routes to be served by the server as per the specific agent
     pub fn run_with_custom_routes(
         self: Arc<Self>,
@@ -32,15 +40,24 @@ impl Server {
 
         let core_metrics_clone = self.core_metrics.clone();
 
-        let mut app = Router::new().route(
-            "/metrics",
-            get(move || Self::gather_metrics(core_metrics_clone)),
-        );
+        let mut app = Router::new()
+            .route(
+                "/metrics",
+                get(move || Self::gather_metrics(core_metrics_clone)),
+            )
+            .merge(HealthApi::new(self.core_metrics.clone()).router());
+        if let Some(log_filter) = LogFilter::global() {
+            app = app.merge(LogFilterApi::new(log_filter).router());
+        }
 
         for (route, router) in custom_routes {
             app = app.nest(route, router);
         }
 
+        for conf in &self.metrics_push {
+            MetricsPusher::new(conf.clone(), self.core_metrics.clone()).spawn();
+        }
+
         tokio::spawn(async move {
             let addr = SocketAddr::from(([0, 0, 0, 0], port));
             axum::Server::bind(&addr)
@@ -91,6 +108,7 @@ mod tests {
 
         let server = Server::new(
             8080,
+            vec![],
             Arc::new(CoreMetrics::new("test", 8080, mock_registry).unwrap()),
         );
         let server = Arc::new(server);
@@ -0,0 +1,270 @@
+//! Machine-readable liveness and readiness of an agent, derived from the
+//! metrics it already reports.
+//!
+//! Routes
+//! - /healthz - Always 200 while the agent can report on itself, with the
+//!   per-chain status as JSON
+//! - /readyz - 200 if every chain is ready, 503 otherwise, with the same body
+
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use prometheus::proto::MetricFamily;
+use serde::Serialize;
+
+use crate::metrics::{CoreMetrics, NAMESPACE};
+
+const BLOCK_HEIGHT_METRIC: &str = "block_height";
+const CONTRACT_SYNC_BLOCK_HEIGHT_METRIC: &str = "contract_sync_block_height";
+const LAST_SUCCESSFUL_RPC_CALL_METRIC: &str = "last_successful_rpc_call_timestamp";
+const WALLET_BALANCE_METRIC: &str = "wallet_balance";
+const SUBMITTER_QUEUE_LENGTH_METRIC: &str = "submitter_queue_length";
+
+/// Limits beyond which a chain is reported as not ready.
+#[derive(Clone, Debug)]
+pub struct HealthThresholds {
+    /// Maximum number of blocks an indexer may be behind the chain tip
+    pub max_indexing_lag_blocks: i64,
+    /// Maximum time since the last successful RPC call to the chain
+    pub max_rpc_staleness: Duration,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            max_indexing_lag_blocks: 1_000,
+            max_rpc_staleness: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// Status of a single chain
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ChainHealth {
+    /// Latest block height observed on the chain
+    pub block_height: Option<i64>,
+    /// Latest indexed block height per indexed data type
+    pub indexed_heights: BTreeMap<String, i64>,
+    /// Number of blocks the slowest indexer is behind `block_height`
+    pub indexing_lag: Option<i64>,
+    /// Unix timestamp in seconds of the last successful RPC call
+    pub last_successful_rpc_call: Option<i64>,
+    /// Whether the signer holds a non-zero balance, if its balance is tracked
+    pub signer_balance_ok: Option<bool>,
+    /// Number of operations queued for submission to the chain, per queue
+    pub queue_depth: BTreeMap<String, i64>,
+    /// Whether the chain is within all thresholds
+    pub ready: bool,
+}
+
+/// Status of all chains the agent interacts with
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct HealthReport {
+    /// Whether every chain is ready
+    pub ready: bool,
+    /// Status per chain name
+    pub chains: BTreeMap<String, ChainHealth>,
+}
+
+impl HealthReport {
+    /// Build the report from the gathered metric families.
+    pub fn from_metric_families(
+        families: &[MetricFamily],
+        thresholds: &HealthThresholds,
+        now: i64,
+    ) -> Self {
+        let mut chains: BTreeMap<String, ChainHealth> = BTreeMap::new();
+        let name = |metric: &str| format!("{NAMESPACE}_{metric}");
+
+        for family in families {
+            let family_name = family.get_name();
+            for metric in family.get_metric() {
+                let label = |key: &str| {
+                    metric
+                        .get_label()
+                        .iter()
+                        .find(|l| l.get_name() == key)
+                        .map(|l| l.get_value().to_owned())
+                };
+                let value = metric.get_gauge().get_value();
+
+                if family_name == name(BLOCK_HEIGHT_METRIC) {
+                    if let Some(chain) = label("chain") {
+                        chains.entry(chain).or_default().block_height = Some(value as i64);
+                    }
+                } else if family_name == name(CONTRACT_SYNC_BLOCK_HEIGHT_METRIC) {
+                    if let (Some(chain), Some(data_type)) = (label("chain"), label("data_type")) {
+                        chains
+                            .entry(chain)
+                            .or_default()
+                            .indexed_heights
+                            .insert(data_type, value as i64);
+                    }
+                } else if family_name == name(LAST_SUCCESSFUL_RPC_CALL_METRIC) {
+                    if let Some(chain) = label("chain") {
+                        chains.entry(chain).or_default().last_successful_rpc_call =
+                            Some(value as i64);
+                    }
+                } else if family_name == name(WALLET_BALANCE_METRIC) {
+                    if let Some(chain) = label("chain") {
+                        let health = chains.entry(chain).or_default();
+                        // A chain may track several wallets, all of them need funds
+                        health.signer_balance_ok =
+                            Some(health.signer_balance_ok.unwrap_or(true) && value > 0.);
+                    }
+                } else if family_name == name(SUBMITTER_QUEUE_LENGTH_METRIC) {
+                    if let (Some(chain), Some(queue)) = (label("remote"), label("queue_name")) {
+                        *chains
+                            .entry(chain)
+                            .or_default()
+                            .queue_depth
+                            .entry(queue)
+                            .or_default() += value as i64;
+                    }
+                }
+            }
+        }
+
+        for health in chains.values_mut() {
+            health.indexing_lag = health.block_height.and_then(|tip| {
+                health
+                    .indexed_heights
+                    .values()
+                    .map(|indexed| (tip - indexed).max(0))
+                    .max()
+            });
+            let rpc_fresh = health.last_successful_rpc_call.map_or(false, |at| {
+                now.saturating_sub(at) <= thresholds.max_rpc_staleness.as_secs() as i64
+            });
+            let indexing_caught_up = health
+                .indexing_lag
+                .map_or(true, |lag| lag <= thresholds.max_indexing_lag_blocks);
+            health.ready =
+                rpc_fresh && indexing_caught_up && health.signer_balance_ok.unwrap_or(true);
+        }
+
+        Self {
+            ready: chains.values().all(|health| health.ready),
+            chains,
+        }
+    }
+}
+
+/// Serves `/healthz` and `/readyz` from the agent's metrics
+#[derive(Clone, Debug)]
+pub struct HealthApi {
+    core_metrics: Arc<CoreMetrics>,
+    thresholds: HealthThresholds,
+}
+
+impl HealthApi {
+    /// Create a new health api using the default thresholds
+    pub fn new(core_metrics: Arc<CoreMetrics>) -> Self {
+        Self {
+            core_metrics,
+            thresholds: HealthThresholds::default(),
+        }
+    }
+
+    /// Routes to be merged into the root of the server
+    pub fn router(&self) -> Router {
+        let healthz = self.clone();
+        let readyz = self.clone();
+        Router::new()
+            .route("/healthz", get(move || healthz.clone().healthz_handler()))
+            .route("/readyz", get(move || readyz.clone().readyz_handler()))
+    }
+
+    fn report(&self) -> HealthReport {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        HealthReport::from_metric_families(
+            &self.core_metrics.gather_families(),
+            &self.thresholds,
+            now,
+        )
+    }
+
+    async fn healthz_handler(self) -> impl IntoResponse {
+        (StatusCode::OK, Json(self.report()))
+    }
+
+    async fn readyz_handler(self) -> impl IntoResponse {
+        let report = self.report();
+        let status = if report.ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        (status, Json(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+
+    use super::*;
+
+    #[test]
+    fn test_report_from_metrics() {
+        let metrics = CoreMetrics::new("test", 0, Registry::new()).unwrap();
+        let block_height = metrics
+            .new_int_gauge(BLOCK_HEIGHT_METRIC, "help", &["chain"])
+            .unwrap();
+        let indexed_height = metrics
+            .new_int_gauge(
+                CONTRACT_SYNC_BLOCK_HEIGHT_METRIC,
+                "help",
+                &["data_type", "chain"],
+            )
+            .unwrap();
+        let last_rpc = metrics
+            .new_int_gauge(LAST_SUCCESSFUL_RPC_CALL_METRIC, "help", &["chain"])
+            .unwrap();
+
+        block_height.with_label_values(&["ethereum"]).set(1_500);
+        indexed_height
+            .with_label_values(&["messages", "ethereum"])
+            .set(1_490);
+        indexed_height
+            .with_label_values(&["gas_payments", "ethereum"])
+            .set(100);
+        last_rpc.with_label_values(&["ethereum"]).set(1_000);
+        block_height.with_label_values(&["polygon"]).set(10);
+        last_rpc.with_label_values(&["polygon"]).set(1_000);
+        metrics
+            .submitter_queue_length()
+            .with_label_values(&["polygon", "submit_queue", "default"])
+            .set(3);
+
+        let report = HealthReport::from_metric_families(
+            &metrics.gather_families(),
+            &HealthThresholds::default(),
+            1_060,
+        );
+
+        let ethereum = &report.chains["ethereum"];
+        assert_eq!(ethereum.indexing_lag, Some(1_400));
+        assert!(!ethereum.ready);
+        let polygon = &report.chains["polygon"];
+        assert_eq!(polygon.indexing_lag, None);
+        assert_eq!(polygon.queue_depth["submit_queue"], 3);
+        assert!(polygon.ready);
+        assert!(!report.ready);
+
+        // Stale RPC
+        let report = HealthReport::from_metric_families(
+            &metrics.gather_families(),
+            &HealthThresholds::default(),
+            1_000 + 10 * 60,
+        );
+        assert!(!report.chains["polygon"].ready);
+    }
+}
@@ -0,0 +1,80 @@
+//! Changing the log filter of the agent at runtime.
+//!
+//! Routes
+//! - GET /log_filter - The change to the configured filter in effect
+//! - PUT /log_filter - Apply directives on top of the configured filter for a
+//!   while, e.g. `{"filter": "hyperlane_ethereum=trace", "durationSecs": 600}`
+//! - DELETE /log_filter - Restore the configured filter
+
+use std::time::Duration;
+
+use axum::{http::StatusCode, routing::get, Json, Router};
+use serde::Deserialize;
+
+use crate::settings::{LogFilter, LogFilterStatus, DEFAULT_OVERRIDE_DURATION};
+
+const LOG_FILTER_API_BASE: &str = "/log_filter";
+
+/// A change to the configured log filter
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilterRequest {
+    /// Directives in the `RUST_LOG` syntax, e.g. `hyperlane_ethereum=trace`
+    pub filter: String,
+    /// How long the change lasts. Defaults to 10 minutes.
+    pub duration_secs: Option<u64>,
+}
+
+/// Serves the log filter of the agent
+#[derive(Clone, Debug)]
+pub struct LogFilterApi {
+    log_filter: &'static LogFilter,
+}
+
+type LogFilterResponse = Result<Json<LogFilterStatus>, (StatusCode, String)>;
+
+impl LogFilterApi {
+    /// Create a new log filter api
+    pub fn new(log_filter: &'static LogFilter) -> Self {
+        Self { log_filter }
+    }
+
+    /// The routes of the api
+    pub fn router(&self) -> Router {
+        let (status, set, reset) = (self.clone(), self.clone(), self.clone());
+        Router::new().route(
+            LOG_FILTER_API_BASE,
+            get(move || status.clone().status_handler())
+                .put(move |Json(request): Json<LogFilterRequest>| set.clone().set_handler(request))
+                .delete(move || reset.clone().reset_handler()),
+        )
+    }
+
+    async fn status_handler(self) -> Json<LogFilterStatus> {
+        Json(self.log_filter.status())
+    }
+
+    async fn set_handler(self, request: LogFilterRequest) -> LogFilterResponse {
+        let duration = match request.duration_secs {
+            Some(0) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "`durationSecs` must be positive".to_owned(),
+                ))
+            }
+            Some(secs) => Duration::from_secs(secs),
+            None => DEFAULT_OVERRIDE_DURATION,
+        };
+        self.log_filter
+            .set(&request.filter, duration)
+            .map(Json)
+            .map_err(|err| (StatusCode::BAD_REQUEST, format!("{err:#}")))
+    }
+
+    async fn reset_handler(self) -> LogFilterResponse {
+        self.log_filter
+            .reset()
+            .map(Json)
+            .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")))
+    }
+}
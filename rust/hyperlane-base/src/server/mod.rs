@@ -1,2 +1,7 @@
 mod base_server;
+mod health;
+mod log_filter;
+
 pub use base_server::Server;
+pub use health::{ChainHealth, HealthApi, HealthReport, HealthThresholds};
+pub use log_filter::{LogFilterApi, LogFilterRequest};
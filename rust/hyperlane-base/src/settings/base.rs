@@ -3,17 +3,22 @@ use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc};
 use eyre::{eyre, Context, Result};
 use futures_util::future::try_join_all;
 use hyperlane_core::{
-    HyperlaneChain, HyperlaneDomain, HyperlaneLogStore, HyperlaneProvider,
+    AddressBook, HyperlaneChain, HyperlaneDomain, HyperlaneLogStore, HyperlaneProvider,
     HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, InterchainGasPaymaster,
     Mailbox, MerkleTreeHook, MultisigIsm, SequenceAwareIndexer, ValidatorAnnounce, H256,
 };
+use tracing::warn;
 
 use crate::{
     cursors::{CursorType, Indexable},
-    settings::{chains::ChainConf, trace::TracingConfig},
+    db::DbMaintenanceConf,
+    settings::{
+        chains::{ChainConf, UnsupportedByProtocol},
+        trace::TracingConfig,
+    },
     ContractSync, ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore,
-    SequenceAwareLogStore, SequencedDataContractSync, Server, WatermarkContractSync,
-    WatermarkLogStore,
+    MetricsPushConf, SequenceAwareLogStore, SequencedDataContractSync, Server,
+    WatermarkContractSync, WatermarkLogStore,
 };
 
 use super::TryFromWithMetrics;
@@ -48,8 +53,13 @@ pub struct Settings {
     pub chains: HashMap<String, ChainConf>,
     /// Port to listen for prometheus scrape requests
     pub metrics_port: u16,
+    /// Targets the metrics are pushed to as well, for when the metrics port
+    /// can't be scraped
+    pub metrics_push: Vec<MetricsPushConf>,
     /// The tracing configuration
     pub tracing: TracingConfig,
+    /// Maintenance of the agent's local dbs
+    pub db_maintenance: DbMaintenanceConf,
 }
 
 impl Settings {
@@ -61,6 +71,29 @@ impl Settings {
         }
     }
 
+    /// The address book of the configured chains: how each writes its
+    /// addresses, and the names of its core and known contracts
+    pub fn address_book(&self) -> AddressBook {
+        let mut book = AddressBook::default();
+        for chain in self.chains.values() {
+            let domain = chain.domain.id();
+            book.set_format(domain, chain.connection.address_format());
+            let addresses = &chain.addresses;
+            for (address, name) in [
+                (addresses.mailbox, "mailbox"),
+                (addresses.interchain_gas_paymaster, "igp"),
+                (addresses.validator_announce, "validator_announce"),
+                (addresses.merkle_tree_hook, "merkle_tree_hook"),
+            ] {
+                book.set_name(domain, address, name);
+            }
+            for (address, name) in &chain.known_contracts {
+                book.set_name(domain, *address, name.clone());
+            }
+        }
+        book
+    }
+
     /// Try to get a MultisigIsm
     pub async fn build_multisig_ism(
         &self,
@@ -100,7 +133,11 @@ impl Settings {
 
     /// Create the server from the settings given the name of the agent.
     pub fn server(&self, core_metrics: Arc<CoreMetrics>) -> Result<Arc<Server>> {
-        Ok(Arc::new(Server::new(self.metrics_port, core_metrics)))
+        Ok(Arc::new(Server::new(
+            self.metrics_port,
+            self.metrics_push.clone(),
+            core_metrics,
+        )))
     }
 
     /// Private to preserve linearity of AgentCore::from_settings -- creating an
@@ -109,7 +146,9 @@ impl Settings {
         Self {
             chains: self.chains.clone(),
             metrics_port: self.metrics_port,
+            metrics_push: self.metrics_push.clone(),
             tracing: self.tracing.clone(),
+            db_maintenance: self.db_maintenance.clone(),
         }
     }
 }
@@ -229,7 +268,7 @@ impl Settings {
                         dbs.get(domain).unwrap().clone(),
                     )
                     .await
-                    .map(|r| r as Arc<dyn ContractSyncer<T>>)?,
+                    .map(|r| r as Arc<dyn ContractSyncer<T>>),
                 CursorType::RateLimited => self
                     .watermark_contract_sync(
                         domain,
@@ -238,9 +277,16 @@ impl Settings {
                         dbs.get(domain).unwrap().clone(),
                     )
                     .await
-                    .map(|r| r as Arc<dyn ContractSyncer<T>>)?,
+                    .map(|r| r as Arc<dyn ContractSyncer<T>>),
             };
-            syncs.push(sync);
+            match sync {
+                Ok(sync) => syncs.push(sync),
+                // The chain's other indexers still run
+                Err(err) if UnsupportedByProtocol::caused(&err) => {
+                    warn!(domain = domain.name(), ?err, "Skipping unsupported indexer");
+                }
+                Err(err) => return Err(err),
+            }
         }
 
         syncs
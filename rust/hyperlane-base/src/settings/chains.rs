@@ -1,15 +1,17 @@
 use axum::async_trait;
 use ethers::prelude::Selector;
 use h_cosmos::CosmosProvider;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 
 use eyre::{eyre, Context, Result};
 
 use ethers_prometheus::middleware::{ChainInfo, ContractInfo, PrometheusMiddlewareConf};
+use hyperlane_aptos as h_aptos;
 use hyperlane_core::{
-    config::OperationBatchConfig, AggregationIsm, CcipReadIsm, ContractLocator, HyperlaneAbi,
-    HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider, IndexMode,
-    InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, Mailbox,
+    config::OperationBatchConfig, rpc_clients::CircuitBreaker, AddressFormat, AggregationIsm,
+    CcipReadIsm, CheckpointAttestation, ContractLocator, DestinationGasUpdate, FinalityTag,
+    HyperlaneAbi, HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider,
+    IndexMode, InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, Mailbox,
     MerkleTreeHook, MerkleTreeInsertion, MultisigIsm, RoutingIsm, SequenceAwareIndexer,
     ValidatorAnnounce, H256,
 };
@@ -20,15 +22,22 @@ use hyperlane_ethereum::{
 };
 use hyperlane_fuel as h_fuel;
 use hyperlane_sealevel as h_sealevel;
+use hyperlane_starknet as h_starknet;
+use hyperlane_sui as h_sui;
+use hyperlane_ton as h_ton;
 
 use crate::{
     metrics::AgentMetricsConf,
     settings::signers::{BuildableWithSignerConf, SignerConf},
-    CoreMetrics,
+    CircuitBreakingIndexer, CircuitBreakingProvider, CoreMetrics,
 };
 
 use super::ChainSigner;
 
+/// Bounds of how often the indexers poll for new blocks once synced
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
 /// A trait for converting to a type from a chain configuration with metrics
 #[async_trait]
 pub trait TryFromWithMetrics<T>: Sized {
@@ -56,6 +65,24 @@ pub struct ChainConf {
     pub metrics_conf: PrometheusMiddlewareConf,
     /// Settings for event indexing
     pub index: IndexSettings,
+    /// Fails the calls to the chain fast after repeated failures. Shared by
+    /// everything built from this config: every contract of EVM chains, the
+    /// indexers and provider of the others.
+    pub circuit_breaker: Option<Arc<CircuitBreaker>>,
+    /// Names of known contracts of the chain, e.g. warp routes, printed next
+    /// to their address in logs and used to label their metrics
+    pub known_contracts: HashMap<H256, String>,
+    /// Most transactions the signer of the chain may have submitted but not
+    /// confirmed yet, so that one failure doesn't strand a long chain of
+    /// dependent nonces. Not limited if not set.
+    pub max_inflight_transactions: Option<u32>,
+    /// Most deliveries checked in a single call when confirming submitted
+    /// operations, e.g. through a multicall. Deliveries are checked one by
+    /// one if not set.
+    pub confirmation_batch_size: Option<u32>,
+    /// Id the chain is expected to report over rpc, checked by the `doctor`
+    /// command. Not checked if not set.
+    pub chain_id: Option<String>,
 }
 
 /// A sequence-aware indexer for messages
@@ -70,6 +97,9 @@ pub type IgpIndexer = Arc<dyn SequenceAwareIndexer<InterchainGasPayment>>;
 /// A sequence-aware indexer for merkle tree hooks
 pub type MerkleTreeHookIndexer = Arc<dyn SequenceAwareIndexer<MerkleTreeInsertion>>;
 
+/// An indexer for the gas data of destinations set in a gas oracle
+pub type DestinationGasIndexer = Arc<dyn SequenceAwareIndexer<DestinationGasUpdate>>;
+
 #[async_trait]
 impl TryFromWithMetrics<ChainConf> for MessageIndexer {
     async fn try_from_with_metrics(conf: &ChainConf, metrics: &CoreMetrics) -> Result<Self> {
@@ -102,6 +132,15 @@ impl TryFromWithMetrics<ChainConf> for MerkleTreeHookIndexer {
     }
 }
 
+#[async_trait]
+impl TryFromWithMetrics<ChainConf> for DestinationGasIndexer {
+    async fn try_from_with_metrics(conf: &ChainConf, metrics: &CoreMetrics) -> Result<Self> {
+        conf.build_destination_gas_indexer(metrics)
+            .await
+            .map(Into::into)
+    }
+}
+
 /// A connection to _some_ blockchain.
 #[derive(Clone, Debug)]
 pub enum ChainConnectionConf {
@@ -113,6 +152,14 @@ pub enum ChainConnectionConf {
     Sealevel(h_sealevel::ConnectionConf),
     /// Cosmos configuration.
     Cosmos(h_cosmos::ConnectionConf),
+    /// Aptos configuration.
+    Aptos(h_aptos::ConnectionConf),
+    /// Sui configuration.
+    Sui(h_sui::ConnectionConf),
+    /// StarkNet configuration.
+    Starknet(h_starknet::ConnectionConf),
+    /// TON configuration.
+    Ton(h_ton::ConnectionConf),
 }
 
 impl ChainConnectionConf {
@@ -123,15 +170,41 @@ impl ChainConnectionConf {
             Self::Fuel(_) => HyperlaneDomainProtocol::Fuel,
             Self::Sealevel(_) => HyperlaneDomainProtocol::Sealevel,
             Self::Cosmos(_) => HyperlaneDomainProtocol::Cosmos,
+            Self::Aptos(_) => HyperlaneDomainProtocol::Aptos,
+            Self::Sui(_) => HyperlaneDomainProtocol::Sui,
+            Self::Starknet(_) => HyperlaneDomainProtocol::Starknet,
+            Self::Ton(_) => HyperlaneDomainProtocol::Ton,
         }
     }
 
+    /// Get how the addresses of this chain are written and stored.
+    pub fn address_format(&self) -> AddressFormat {
+        let prefix = match self {
+            Self::Cosmos(conf) => Some(conf.get_bech32_prefix()),
+            _ => None,
+        };
+        AddressFormat::for_protocol(self.protocol(), prefix.as_deref())
+    }
+
     /// Get the message batch configuration for this chain.
     pub fn operation_batch_config(&self) -> Option<&OperationBatchConfig> {
         match self {
             Self::Ethereum(conf) => Some(&conf.operation_batch),
             Self::Cosmos(conf) => Some(&conf.operation_batch),
             Self::Sealevel(conf) => Some(&conf.operation_batch),
+            Self::Aptos(conf) => Some(&conf.operation_batch),
+            Self::Sui(conf) => Some(&conf.operation_batch),
+            Self::Starknet(conf) => Some(&conf.operation_batch),
+            Self::Ton(conf) => Some(&conf.operation_batch),
+            _ => None,
+        }
+    }
+
+    /// Get the block tag used to read finalized state on this chain, if it
+    /// overrides waiting for the reorg period.
+    pub fn finality_tag(&self) -> Option<FinalityTag> {
+        match self {
+            Self::Ethereum(conf) => conf.finality_tag,
             _ => None,
         }
     }
@@ -148,6 +221,9 @@ pub struct CoreContractAddresses {
     pub validator_announce: H256,
     /// Address of the MerkleTreeHook contract
     pub merkle_tree_hook: H256,
+    /// Address of the StorageGasOracle the InterchainGasPaymaster quotes
+    /// destination gas with, if it has one
+    pub storage_gas_oracle: Option<H256>,
 }
 
 /// Indexing settings
@@ -159,6 +235,19 @@ pub struct IndexSettings {
     pub chunk_size: u32,
     /// The indexing mode.
     pub mode: IndexMode,
+    /// The assumed block time of the chain, which paces how often the
+    /// indexers poll for new blocks once synced
+    pub block_time: Option<Duration>,
+}
+
+impl IndexSettings {
+    /// How long the indexers wait before polling again once they have
+    /// caught up with the tip
+    pub fn poll_interval(&self) -> Duration {
+        self.block_time.map_or(MAX_POLL_INTERVAL, |block_time| {
+            block_time.clamp(MIN_POLL_INTERVAL, MAX_POLL_INTERVAL)
+        })
+    }
 }
 
 impl ChainConf {
@@ -167,6 +256,31 @@ impl ChainConf {
         self.index.clone()
     }
 
+    /// The circuit breaker to route the calls of the indexers and provider
+    /// through. EVM chains have none as their rpc client, shared by every
+    /// contract, already goes through the breaker.
+    fn client_circuit_breaker(&self) -> Option<&Arc<CircuitBreaker>> {
+        match &self.connection {
+            ChainConnectionConf::Ethereum(_) => None,
+            _ => self.circuit_breaker.as_ref(),
+        }
+    }
+
+    /// Route the calls of an indexer through the circuit breaker of the
+    /// chain, if it has one
+    fn with_circuit_breaker<T>(
+        &self,
+        indexer: Box<dyn SequenceAwareIndexer<T>>,
+    ) -> Box<dyn SequenceAwareIndexer<T>>
+    where
+        T: Send + Sync + Debug + 'static,
+    {
+        match self.client_circuit_breaker() {
+            Some(breaker) => Box::new(CircuitBreakingIndexer::new(indexer, breaker.clone())),
+            None => indexer,
+        }
+    }
+
     /// Try to convert the chain settings into an HyperlaneProvider.
     pub async fn build_provider(
         &self,
@@ -179,7 +293,10 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::HyperlaneProviderBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => Ok(Box::new(h_fuel::FuelProvider::new(
+                locator.domain.clone(),
+                conf,
+            )?) as Box<dyn HyperlaneProvider>),
             ChainConnectionConf::Sealevel(conf) => Ok(Box::new(h_sealevel::SealevelProvider::new(
                 locator.domain.clone(),
                 conf,
@@ -193,8 +310,35 @@ impl ChainConf {
                 )?;
                 Ok(Box::new(provider) as Box<dyn HyperlaneProvider>)
             }
+            ChainConnectionConf::Aptos(conf) => Ok(Box::new(h_aptos::AptosProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            ChainConnectionConf::Sui(conf) => Ok(Box::new(h_sui::SuiProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            ChainConnectionConf::Starknet(conf) => Ok(Box::new(h_starknet::StarknetProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            ChainConnectionConf::Ton(conf) => Ok(Box::new(h_ton::TonProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
         }
         .context(ctx)
+        .map(|provider| match self.client_circuit_breaker() {
+            Some(breaker) => Box::new(CircuitBreakingProvider::new(
+                provider.into(),
+                breaker.clone(),
+            )) as Box<dyn HyperlaneProvider>,
+            None => provider,
+        })
     }
 
     /// Try to convert the chain setting into a Mailbox contract
@@ -216,6 +360,7 @@ impl ChainConf {
             ChainConnectionConf::Sealevel(conf) => {
                 let keypair = self.sealevel_signer().await.context(ctx)?;
                 h_sealevel::SealevelMailbox::new(conf, locator, keypair)
+                    .map(|m| m.with_submission_metrics(metrics.sealevel_submissions_count()))
                     .map(|m| Box::new(m) as Box<dyn Mailbox>)
                     .map_err(Into::into)
             }
@@ -225,6 +370,26 @@ impl ChainConf {
                     .map(|m| Box::new(m) as Box<dyn Mailbox>)
                     .map_err(Into::into)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let signer = self.aptos_signer().await.context(ctx)?;
+                let mailbox = h_aptos::AptosMailbox::new(conf, locator, signer);
+                Ok(Box::new(mailbox) as Box<dyn Mailbox>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let signer = self.sui_signer().await.context(ctx)?;
+                let mailbox = h_sui::SuiMailbox::new(conf, locator, signer);
+                Ok(Box::new(mailbox) as Box<dyn Mailbox>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let signer = self.starknet_signer().await.context(ctx)?;
+                let mailbox = h_starknet::StarknetMailbox::new(conf, locator, signer);
+                Ok(Box::new(mailbox) as Box<dyn Mailbox>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let signer = self.ton_signer().await.context(ctx)?;
+                let mailbox = h_ton::TonMailbox::new(conf, locator, signer);
+                Ok(Box::new(mailbox) as Box<dyn Mailbox>)
+            }
         }
         .context(ctx)
     }
@@ -242,9 +407,10 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::MerkleTreeHookBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_conf) => {
-                todo!("Fuel does not support merkle tree hooks yet")
-            }
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "merkle tree hooks",
+            )),
             ChainConnectionConf::Sealevel(conf) => {
                 h_sealevel::SealevelMailbox::new(conf, locator, None)
                     .map(|m| Box::new(m) as Box<dyn MerkleTreeHook>)
@@ -257,6 +423,22 @@ impl ChainConf {
 
                 Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let hook = h_aptos::AptosMailbox::new(conf, locator, None);
+                Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let hook = h_sui::SuiMailbox::new(conf, locator, None);
+                Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let hook = h_starknet::StarknetMerkleTreeHook::new(conf, locator);
+                Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let hook = h_ton::TonMerkleTreeHook::new(conf, locator);
+                Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
+            }
         }
         .context(ctx)
     }
@@ -281,7 +463,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.signer().await.context(ctx)?;
+                let indexer = Box::new(h_fuel::FuelMailboxIndexer::new(conf, locator, wallet)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
@@ -296,8 +482,33 @@ impl ChainConf {
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let indexer = Box::new(h_aptos::AptosMailboxIndexer::new(conf, locator));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let indexer = Box::new(h_sui::SuiMailboxIndexer::new(conf, locator));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let indexer = Box::new(h_starknet::StarknetMailboxIndexer::new(
+                    conf,
+                    locator,
+                    self.reorg_period,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonMailboxIndexer::new(
+                    conf,
+                    locator,
+                    self.reorg_period,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
         }
         .context(ctx)
+        .map(|indexer| self.with_circuit_breaker(indexer))
     }
 
     /// Try to convert the chain settings into a delivery indexer
@@ -320,7 +531,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.signer().await.context(ctx)?;
+                let indexer = Box::new(h_fuel::FuelMailboxIndexer::new(conf, locator, wallet)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
@@ -335,8 +550,33 @@ impl ChainConf {
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let indexer = Box::new(h_aptos::AptosMailboxIndexer::new(conf, locator));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let indexer = Box::new(h_sui::SuiMailboxIndexer::new(conf, locator));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let indexer = Box::new(h_starknet::StarknetMailboxIndexer::new(
+                    conf,
+                    locator,
+                    self.reorg_period,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonMailboxIndexer::new(
+                    conf,
+                    locator,
+                    self.reorg_period,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
         }
         .context(ctx)
+        .map(|indexer| self.with_circuit_breaker(indexer))
     }
 
     /// Try to convert the chain setting into an interchain gas paymaster
@@ -358,7 +598,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "interchain gas paymaster",
+            ))
+            .context(ctx),
             ChainConnectionConf::Sealevel(conf) => {
                 let paymaster = Box::new(
                     h_sealevel::SealevelInterchainGasPaymaster::new(conf, &locator).await?,
@@ -374,6 +618,24 @@ impl ChainConf {
                 )?);
                 Ok(paymaster as Box<dyn InterchainGasPaymaster>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let paymaster = Box::new(h_aptos::AptosInterchainGasPaymaster::new(conf, &locator));
+                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let paymaster = Box::new(h_sui::SuiInterchainGasPaymaster::new(conf, &locator));
+                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let paymaster = Box::new(h_starknet::StarknetInterchainGasPaymaster::new(
+                    conf, &locator,
+                ));
+                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let paymaster = Box::new(h_ton::TonInterchainGasPaymaster::new(conf, &locator));
+                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+            }
         }
         .context(ctx)
     }
@@ -399,7 +661,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "interchain gas payment indexing",
+            ))
+            .context(ctx),
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(
                     h_sealevel::SealevelInterchainGasPaymasterIndexer::new(conf, locator).await?,
@@ -414,8 +680,70 @@ impl ChainConf {
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let indexer = Box::new(h_aptos::AptosInterchainGasPaymasterIndexer::new(
+                    conf, locator,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let indexer = Box::new(h_sui::SuiInterchainGasPaymasterIndexer::new(conf, locator));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let indexer = Box::new(h_starknet::StarknetInterchainGasPaymasterIndexer::new(
+                    conf,
+                    locator,
+                    self.reorg_period,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonInterchainGasPaymasterIndexer::new(
+                    conf,
+                    locator,
+                    self.reorg_period,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+            }
+        }
+        .context(ctx)
+        .map(|indexer| self.with_circuit_breaker(indexer))
+    }
+
+    /// Try to convert the chain settings into an indexer of the destination
+    /// gas data set in the storage gas oracle. Only EVM chains are supported.
+    pub async fn build_destination_gas_indexer(
+        &self,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn SequenceAwareIndexer<DestinationGasUpdate>>> {
+        let ctx = "Building destination gas indexer";
+        let address = self
+            .addresses
+            .storage_gas_oracle
+            .ok_or_else(|| eyre!("No storage gas oracle configured"))
+            .context(ctx)?;
+        let locator = self.locator(address);
+
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(
+                    conf,
+                    &locator,
+                    metrics,
+                    h_eth::StorageGasOracleIndexerBuilder {
+                        reorg_period: self.reorg_period,
+                    },
+                )
+                .await
+            }
+            _ => Err(eyre!(
+                "Indexing destination gas updates is not supported on {}",
+                self.domain.domain_protocol()
+            )),
         }
         .context(ctx)
+        .map(|indexer| self.with_circuit_breaker(indexer))
     }
 
     /// Try to convert the chain settings into a merkle tree hook indexer
@@ -438,7 +766,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "merkle tree hook indexing",
+            ))
+            .context(ctx),
             ChainConnectionConf::Sealevel(conf) => {
                 let mailbox_indexer =
                     Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
@@ -458,8 +790,35 @@ impl ChainConf {
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let mailbox_indexer = h_aptos::AptosMailboxIndexer::new(conf, locator);
+                let indexer = Box::new(h_aptos::AptosMerkleTreeHookIndexer::new(mailbox_indexer));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let mailbox_indexer = h_sui::SuiMailboxIndexer::new(conf, locator);
+                let indexer = Box::new(h_sui::SuiMerkleTreeHookIndexer::new(mailbox_indexer));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let indexer = Box::new(h_starknet::StarknetMerkleTreeHookIndexer::new(
+                    conf,
+                    locator,
+                    self.reorg_period,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonMerkleTreeHookIndexer::new(
+                    conf,
+                    locator,
+                    self.reorg_period,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+            }
         }
         .context(ctx)
+        .map(|indexer| self.with_circuit_breaker(indexer))
     }
 
     /// Try to convert the chain settings into a ValidatorAnnounce
@@ -474,7 +833,11 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::ValidatorAnnounceBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "validator announce",
+            ))
+            .context(ctx),
             ChainConnectionConf::Sealevel(conf) => {
                 let va = Box::new(h_sealevel::SealevelValidatorAnnounce::new(conf, locator));
                 Ok(va as Box<dyn ValidatorAnnounce>)
@@ -489,6 +852,28 @@ impl ChainConf {
 
                 Ok(va as Box<dyn ValidatorAnnounce>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let signer = self.aptos_signer().await.context(ctx)?;
+                let va = Box::new(h_aptos::AptosValidatorAnnounce::new(conf, locator, signer));
+                Ok(va as Box<dyn ValidatorAnnounce>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let signer = self.sui_signer().await.context(ctx)?;
+                let va = Box::new(h_sui::SuiValidatorAnnounce::new(conf, locator, signer));
+                Ok(va as Box<dyn ValidatorAnnounce>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let signer = self.starknet_signer().await.context(ctx)?;
+                let va = Box::new(h_starknet::StarknetValidatorAnnounce::new(
+                    conf, locator, signer,
+                ));
+                Ok(va as Box<dyn ValidatorAnnounce>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let signer = self.ton_signer().await.context(ctx)?;
+                let va = Box::new(h_ton::TonValidatorAnnounce::new(conf, locator, signer));
+                Ok(va as Box<dyn ValidatorAnnounce>)
+            }
         }
         .context("Building ValidatorAnnounce")
     }
@@ -513,7 +898,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "ISMs",
+            ))
+            .context(ctx),
             ChainConnectionConf::Sealevel(conf) => {
                 let keypair = self.sealevel_signer().await.context(ctx)?;
                 let ism = Box::new(h_sealevel::SealevelInterchainSecurityModule::new(
@@ -528,6 +917,24 @@ impl ChainConf {
                 )?);
                 Ok(ism as Box<dyn InterchainSecurityModule>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let ism = Box::new(h_aptos::AptosInterchainSecurityModule::new(conf, locator));
+                Ok(ism as Box<dyn InterchainSecurityModule>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let ism = Box::new(h_sui::SuiInterchainSecurityModule::new(conf, locator));
+                Ok(ism as Box<dyn InterchainSecurityModule>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let ism = Box::new(h_starknet::StarknetInterchainSecurityModule::new(
+                    conf, locator,
+                ));
+                Ok(ism as Box<dyn InterchainSecurityModule>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let ism = Box::new(h_ton::TonInterchainSecurityModule::new(conf, locator));
+                Ok(ism as Box<dyn InterchainSecurityModule>)
+            }
         }
         .context(ctx)
     }
@@ -547,7 +954,11 @@ impl ChainConf {
                     .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "multisig ISM",
+            ))
+            .context(ctx),
             ChainConnectionConf::Sealevel(conf) => {
                 let keypair = self.sealevel_signer().await.context(ctx)?;
                 let ism = Box::new(h_sealevel::SealevelMultisigIsm::new(conf, locator, keypair));
@@ -562,6 +973,22 @@ impl ChainConf {
                 )?);
                 Ok(ism as Box<dyn MultisigIsm>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let ism = Box::new(h_aptos::AptosMultisigIsm::new(conf, locator));
+                Ok(ism as Box<dyn MultisigIsm>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let ism = Box::new(h_sui::SuiMultisigIsm::new(conf, locator));
+                Ok(ism as Box<dyn MultisigIsm>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let ism = Box::new(h_starknet::StarknetMultisigIsm::new(conf, locator));
+                Ok(ism as Box<dyn MultisigIsm>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let ism = Box::new(h_ton::TonMultisigIsm::new(conf, locator));
+                Ok(ism as Box<dyn MultisigIsm>)
+            }
         }
         .context(ctx)
     }
@@ -583,10 +1010,16 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::RoutingIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
-            ChainConnectionConf::Sealevel(_) => {
-                Err(eyre!("Sealevel does not support routing ISM yet")).context(ctx)
-            }
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "routing ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Sealevel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Sealevel,
+                "routing ISM",
+            ))
+            .context(ctx),
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let ism = Box::new(h_cosmos::CosmosRoutingIsm::new(
@@ -596,6 +1029,22 @@ impl ChainConf {
                 )?);
                 Ok(ism as Box<dyn RoutingIsm>)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let ism = Box::new(h_aptos::AptosRoutingIsm::new(conf, locator));
+                Ok(ism as Box<dyn RoutingIsm>)
+            }
+            ChainConnectionConf::Sui(conf) => {
+                let ism = Box::new(h_sui::SuiRoutingIsm::new(conf, locator));
+                Ok(ism as Box<dyn RoutingIsm>)
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                let ism = Box::new(h_starknet::StarknetRoutingIsm::new(conf, locator));
+                Ok(ism as Box<dyn RoutingIsm>)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let ism = Box::new(h_ton::TonRoutingIsm::new(conf, locator));
+                Ok(ism as Box<dyn RoutingIsm>)
+            }
         }
         .context(ctx)
     }
@@ -617,10 +1066,16 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::AggregationIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
-            ChainConnectionConf::Sealevel(_) => {
-                Err(eyre!("Sealevel does not support aggregation ISM yet")).context(ctx)
-            }
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "aggregation ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Sealevel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Sealevel,
+                "aggregation ISM",
+            ))
+            .context(ctx),
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let ism = Box::new(h_cosmos::CosmosAggregationIsm::new(
@@ -631,6 +1086,26 @@ impl ChainConf {
 
                 Ok(ism as Box<dyn AggregationIsm>)
             }
+            ChainConnectionConf::Aptos(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Aptos,
+                "aggregation ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Sui(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Sui,
+                "aggregation ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Starknet(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Starknet,
+                "aggregation ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Ton(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Ton,
+                "aggregation ISM",
+            ))
+            .context(ctx),
         }
         .context(ctx)
     }
@@ -652,13 +1127,99 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::CcipReadIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
-            ChainConnectionConf::Sealevel(_) => {
-                Err(eyre!("Sealevel does not support CCIP read ISM yet")).context(ctx)
-            }
-            ChainConnectionConf::Cosmos(_) => {
-                Err(eyre!("Cosmos does not support CCIP read ISM yet")).context(ctx)
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "CCIP read ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Sealevel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Sealevel,
+                "CCIP read ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Cosmos(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Cosmos,
+                "CCIP read ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Aptos(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Aptos,
+                "CCIP read ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Sui(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Sui,
+                "CCIP read ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Starknet(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Starknet,
+                "CCIP read ISM",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Ton(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Ton,
+                "CCIP read ISM",
+            ))
+            .context(ctx),
+        }
+        .context(ctx)
+    }
+
+    /// Try to convert the chain setting into a CheckpointAttestation contract
+    pub async fn build_checkpoint_attestation(
+        &self,
+        address: H256,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn CheckpointAttestation>> {
+        let ctx = "Building checkpoint attestation";
+        let locator = self.locator(address);
+
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(
+                    conf,
+                    &locator,
+                    metrics,
+                    h_eth::CheckpointAttestationBuilder {},
+                )
+                .await
             }
+            ChainConnectionConf::Fuel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Fuel,
+                "checkpoint attestation",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Sealevel(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Sealevel,
+                "checkpoint attestation",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Cosmos(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Cosmos,
+                "checkpoint attestation",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Aptos(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Aptos,
+                "checkpoint attestation",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Sui(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Sui,
+                "checkpoint attestation",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Starknet(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Starknet,
+                "checkpoint attestation",
+            ))
+            .context(ctx),
+            ChainConnectionConf::Ton(_) => Err(UnsupportedByProtocol::report(
+                HyperlaneDomainProtocol::Ton,
+                "checkpoint attestation",
+            ))
+            .context(ctx),
         }
         .context(ctx)
     }
@@ -683,6 +1244,12 @@ impl ChainConf {
                     Box::new(conf.build::<h_sealevel::Keypair>().await?)
                 }
                 ChainConnectionConf::Cosmos(_) => Box::new(conf.build::<h_cosmos::Signer>().await?),
+                ChainConnectionConf::Aptos(_) => Box::new(conf.build::<h_aptos::Signer>().await?),
+                ChainConnectionConf::Sui(_) => Box::new(conf.build::<h_sui::Signer>().await?),
+                ChainConnectionConf::Starknet(_) => {
+                    Box::new(conf.build::<h_starknet::Signer>().await?)
+                }
+                ChainConnectionConf::Ton(_) => Box::new(conf.build::<h_ton::Signer>().await?),
             };
             Ok(Some(chain_signer))
         } else {
@@ -708,6 +1275,22 @@ impl ChainConf {
         self.signer().await
     }
 
+    async fn aptos_signer(&self) -> Result<Option<h_aptos::Signer>> {
+        self.signer().await
+    }
+
+    async fn sui_signer(&self) -> Result<Option<h_sui::Signer>> {
+        self.signer().await
+    }
+
+    async fn starknet_signer(&self) -> Result<Option<h_starknet::Signer>> {
+        self.signer().await
+    }
+
+    async fn ton_signer(&self) -> Result<Option<h_ton::Signer>> {
+        self.signer().await
+    }
+
     /// Try to build an agent metrics configuration from the chain config
     pub async fn agent_metrics_conf(&self, agent_name: String) -> Result<AgentMetricsConf> {
         let chain_signer_address = self.chain_signer().await?.map(|s| s.address_string());
@@ -761,6 +1344,9 @@ impl ChainConf {
             self.addresses.merkle_tree_hook,
             EthereumInterchainGasPaymasterAbi::fn_map_owned(),
         );
+        for (address, name) in &self.known_contracts {
+            register_contract(name, *address, HashMap::new());
+        }
 
         cfg
     }
@@ -1,12 +1,14 @@
 use crate::{
-    CheckpointSyncer, GcsStorageClientBuilder, LocalStorage, S3Storage, GCS_SERVICE_ACCOUNT_KEY,
-    GCS_USER_SECRET,
+    CheckpointRetentionConf, CheckpointSyncer, GcsStorageClientBuilder, IpfsStorage, LocalStorage,
+    S3Storage, DEFAULT_IPFS_GATEWAY_URL, GCS_SERVICE_ACCOUNT_KEY, GCS_USER_SECRET,
+    IPFS_GATEWAY_URL,
 };
 use core::str::FromStr;
 use eyre::{eyre, Context, Report, Result};
 use prometheus::IntGauge;
 use rusoto_core::Region;
 use std::{env, path::PathBuf};
+use url::Url;
 use ya_gcp::{AuthFlow, ServiceAccountAuth};
 
 /// Checkpoint Syncer types
@@ -25,6 +27,10 @@ pub enum CheckpointSyncerConf {
         folder: Option<String>,
         /// S3 Region
         region: Region,
+        /// Whether to also write checkpoints in compressed batches
+        batch_checkpoints: bool,
+        /// How the checkpoints in batches are retained
+        retention: CheckpointRetentionConf,
     },
     /// A checkpoint syncer on Google Cloud Storage
     Gcs {
@@ -38,6 +44,17 @@ pub enum CheckpointSyncerConf {
         /// `gcloud auth application-default login`
         user_secrets: Option<String>,
     },
+    /// A checkpoint syncer on IPFS, published under an IPNS name
+    Ipfs {
+        /// Gateway used to read checkpoints
+        gateway_url: Url,
+        /// IPNS name of the checkpoints - derived from `key` if not set
+        name: Option<String>,
+        /// RPC API of the IPFS node to write checkpoints to
+        api_url: Option<Url>,
+        /// Name of the key in the IPFS node's keystore to publish with
+        key: Option<String>,
+    },
 }
 
 impl FromStr for CheckpointSyncerConf {
@@ -63,6 +80,8 @@ impl FromStr for CheckpointSyncerConf {
                     region: region
                         .parse()
                         .context("Invalid region when parsing storage location")?,
+                    batch_checkpoints: false,
+                    retention: Default::default(),
                 })
             }
             "file" => Ok(CheckpointSyncerConf::LocalStorage {
@@ -90,11 +109,28 @@ impl FromStr for CheckpointSyncerConf {
                     })
                 }
             }
+            // read-only access through a gateway
+            "ipns" => Ok(Self::Ipfs {
+                gateway_url: ipfs_gateway_url()?,
+                name: Some(suffix.into()),
+                api_url: None,
+                key: None,
+            }),
             _ => Err(eyre!("Unknown storage location prefix `{prefix}`")),
         }
     }
 }
 
+/// The gateway to read checkpoints from IPFS with, from `IPFS_GATEWAY_URL` or
+/// a public gateway.
+pub fn ipfs_gateway_url() -> Result<Url> {
+    env::var(IPFS_GATEWAY_URL)
+        .as_deref()
+        .unwrap_or(DEFAULT_IPFS_GATEWAY_URL)
+        .parse()
+        .context("Invalid IPFS gateway url")
+}
+
 impl CheckpointSyncerConf {
     /// Turn conf info a Checkpoint Syncer
     pub async fn build(
@@ -109,10 +145,14 @@ impl CheckpointSyncerConf {
                 bucket,
                 folder,
                 region,
+                batch_checkpoints,
+                retention,
             } => Box::new(S3Storage::new(
                 bucket.clone(),
                 folder.clone(),
                 region.clone(),
+                *batch_checkpoints,
+                retention.clone(),
                 latest_index_gauge,
             )),
             CheckpointSyncerConf::Gcs {
@@ -136,6 +176,21 @@ impl CheckpointSyncerConf {
                         .await?,
                 )
             }
+            CheckpointSyncerConf::Ipfs {
+                gateway_url,
+                name,
+                api_url,
+                key,
+            } => Box::new(
+                IpfsStorage::new(
+                    gateway_url.clone(),
+                    name.clone(),
+                    api_url.clone(),
+                    key.clone(),
+                    latest_index_gauge,
+                )
+                .await?,
+            ),
         })
     }
 }
@@ -4,6 +4,8 @@ use config::{ConfigError, Map, Source, Value, ValueKind};
 use hyperlane_core::unwrap_or_none_result;
 use itertools::Itertools;
 
+use crate::DOCTOR_COMMAND;
+
 /// A source for loading configuration from command line arguments.
 ///
 /// * `--key=value`
@@ -108,12 +110,16 @@ impl ArgumentParser {
 
     /// Creates a parser from [`env::args_os`].
     ///
-    /// The executable path will be removed.
+    /// The executable path will be removed, and so will the `doctor` command
+    /// if the agent was started with it.
     ///
     /// [`env::args_os`]: https://doc.rust-lang.org/stable/std/env/fn.args_os.html
     fn from_env() -> Self {
         let mut args: Vec<_> = std::env::args_os().collect();
         args.remove(0);
+        if args.first().map_or(false, |arg| arg == DOCTOR_COMMAND) {
+            args.remove(0);
+        }
         ArgumentParser(args)
     }
 
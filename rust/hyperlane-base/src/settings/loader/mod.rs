@@ -2,7 +2,7 @@
 
 use std::{env, error::Error, fmt::Debug, path::PathBuf};
 
-use config::{Config, File};
+use config::{Config, File, FileFormat};
 use convert_case::Case;
 use eyre::{eyre, Context, Result};
 use hyperlane_core::config::*;
@@ -21,6 +21,49 @@ pub fn load_settings<T, R>() -> ConfigResult<R>
 where
     T: DeserializeOwned + Debug,
     R: FromRawConf<T>,
+{
+    load_settings_from_sources(NoFilter::default(), true)
+}
+
+/// Deserialize a settings object from the config files and the environment,
+/// but not the command line arguments, for tools that parse arguments of
+/// their own. `filter` limits what config paths are parsed.
+pub fn load_settings_without_arguments<T, R, F>(filter: F) -> ConfigResult<R>
+where
+    T: DeserializeOwned + Debug,
+    R: FromRawConf<T, F>,
+    F: Default,
+{
+    load_settings_from_sources(filter, false)
+}
+
+/// Deserialize a settings object from a JSON config alone, ignoring the
+/// config files and the environment, for tools that check a config before
+/// writing it out. `filter` limits what config paths are parsed.
+pub fn load_settings_from_json<T, R, F>(json: &str, filter: F) -> ConfigResult<R>
+where
+    T: DeserializeOwned + Debug,
+    R: FromRawConf<T, F>,
+    F: Default,
+{
+    let root_path = ConfigPath::default();
+    let raw_config = Config::builder()
+        .add_source(CaseAdapter::new(
+            File::from_str(json, FileFormat::Json),
+            Case::Flat,
+        ))
+        .build()
+        .and_then(Config::try_deserialize::<T>)
+        .context("Config deserialization error")
+        .into_config_result(|| root_path.clone())?;
+    raw_config.parse_config_with_filter(&root_path, filter)
+}
+
+fn load_settings_from_sources<T, R, F>(filter: F, with_arguments: bool) -> ConfigResult<R>
+where
+    T: DeserializeOwned + Debug,
+    R: FromRawConf<T, F>,
+    F: Default,
 {
     let root_path = ConfigPath::default();
 
@@ -77,16 +120,18 @@ where
         }
     }
 
-    let config_deserializer = builder
-        // Use a base configuration env variable prefix
-        .add_source(CaseAdapter::new(
-            Environment::default().prefix("HYP_").separator("_"),
-            Case::Flat,
-        ))
-        .add_source(CaseAdapter::new(
+    // Use a base configuration env variable prefix
+    builder = builder.add_source(CaseAdapter::new(
+        Environment::default().prefix("HYP_").separator("_"),
+        Case::Flat,
+    ));
+    if with_arguments {
+        builder = builder.add_source(CaseAdapter::new(
             CommandLineArguments::default().separator("."),
             Case::Flat,
-        ))
+        ));
+    }
+    let config_deserializer = builder
         .build()
         .context("Failed to load config sources")
         .into_config_result(|| root_path.clone())?;
@@ -121,7 +166,7 @@ where
         })
         .into_config_result(|| root_path.clone())?;
 
-    let res = raw_config.parse_config(&root_path);
+    let res = raw_config.parse_config_with_filter(&root_path, filter);
     if res.is_err() {
         eprintln!("Loaded config for debugging: {formatted_config}");
     }
@@ -72,10 +72,14 @@ pub use signers::*;
 pub use trace::*;
 
 mod envs {
+    pub use hyperlane_aptos as h_aptos;
     pub use hyperlane_cosmos as h_cosmos;
     pub use hyperlane_ethereum as h_eth;
     pub use hyperlane_fuel as h_fuel;
     pub use hyperlane_sealevel as h_sealevel;
+    pub use hyperlane_starknet as h_starknet;
+    pub use hyperlane_sui as h_sui;
+    pub use hyperlane_ton as h_ton;
 }
 
 /// AWS Credentials provider.
@@ -88,6 +92,8 @@ pub mod loader;
 mod signers;
 /// Tracing subscriber management
 mod trace;
+/// Validation of the parsed settings
+mod validation;
 
 mod checkpoint_syncer;
 pub mod parser;
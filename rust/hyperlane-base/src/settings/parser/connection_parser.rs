@@ -1,13 +1,16 @@
+use std::time::Duration;
+
 use eyre::eyre;
-use h_eth::TransactionOverrides;
+use h_eth::{RpcRateLimits, TransactionOverrides, TransactionReplacementConf};
 use hyperlane_core::config::{ConfigErrResultExt, OperationBatchConfig};
+use hyperlane_core::rpc_clients::{HealthScoringConf, RateLimitConf};
 use hyperlane_core::{config::ConfigParsingError, HyperlaneDomainProtocol};
 use url::Url;
 
 use crate::settings::envs::*;
 use crate::settings::ChainConnectionConf;
 
-use super::{parse_base_and_override_urls, parse_cosmos_gas_price, ValueParser};
+use super::{parse_base_and_override_urls, parse_block_time, parse_cosmos_gas_price, ValueParser};
 
 pub fn build_ethereum_connection_conf(
     rpcs: &[Url],
@@ -29,6 +32,7 @@ pub fn build_ethereum_connection_conf(
         "single" => Some(h_eth::RpcConnectionConf::Http { url: first_url }),
         "fallback" => Some(h_eth::RpcConnectionConf::HttpFallback {
             urls: rpcs.to_owned().clone(),
+            health_scoring: parse_rpc_health_scoring(chain, err),
         }),
         "quorum" => Some(h_eth::RpcConnectionConf::HttpQuorum {
             urls: rpcs.to_owned().clone(),
@@ -65,13 +69,194 @@ pub fn build_ethereum_connection_conf(
         })
         .unwrap_or_default();
 
+    let transaction_replacement = chain
+        .get_opt_key("transactionReplacement")
+        .take_err(err, || &chain.cwp + "transaction_replacement")
+        .flatten()
+        .map(|value_parser| {
+            let default = TransactionReplacementConf::default();
+            TransactionReplacementConf {
+                stuck_after_blocks: value_parser
+                    .chain(err)
+                    .get_opt_key("stuckAfterBlocks")
+                    .parse_u64()
+                    .unwrap_or(default.stuck_after_blocks),
+                fee_bump_percent: value_parser
+                    .chain(err)
+                    .get_opt_key("feeBumpPercent")
+                    .parse_u64()
+                    .unwrap_or(default.fee_bump_percent),
+                max_replacements: value_parser
+                    .chain(err)
+                    .get_opt_key("maxReplacements")
+                    .parse_u32()
+                    .unwrap_or(default.max_replacements),
+                cancel_when_exhausted: value_parser
+                    .chain(err)
+                    .get_opt_key("cancelWhenExhausted")
+                    .parse_bool()
+                    .unwrap_or(default.cancel_when_exhausted),
+            }
+        });
+
+    let finality_tag = chain
+        .chain(err)
+        .get_opt_key("blocks")
+        .get_opt_key("finalityTag")
+        .parse_value("Invalid finality tag")
+        .end();
+    let transaction_confirmations = chain
+        .chain(err)
+        .get_opt_key("blocks")
+        .get_opt_key("confirmations")
+        .parse_u64()
+        .map(|confirmations| confirmations as usize)
+        .unwrap_or(1);
+
+    let revert_trace_method = chain
+        .chain(err)
+        .get_opt_key("revertTraceMethod")
+        .parse_value("Invalid revert trace method")
+        .end();
+
+    let fee_adapter = chain
+        .chain(err)
+        .get_opt_key("feeAdapter")
+        .parse_value("Invalid fee adapter")
+        .end();
+
+    let log_subscription_url = chain
+        .chain(err)
+        .get_opt_key("index")
+        .get_opt_key("wsUrl")
+        .parse_from_str("Invalid websocket url")
+        .end();
+
     Some(ChainConnectionConf::Ethereum(h_eth::ConnectionConf {
         rpc_connection: rpc_connection_conf?,
         transaction_overrides,
         operation_batch,
+        transaction_replacement,
+        log_subscription_url,
+        rpc_rate_limits: parse_rpc_rate_limits(chain, err),
+        // Set from the circuit breaker of the chain
+        circuit_breaker: None,
+        block_time: parse_block_time(chain, err),
+        finality_tag,
+        transaction_confirmations,
+        revert_trace_method,
+        fee_adapter,
+        historical_block: None,
     }))
 }
 
+/// Expects AgentConfig.chains[chain].rpcRateLimits, a list of limits where
+/// the one without a `url` applies to every other url
+fn parse_rpc_rate_limits(chain: &ValueParser, err: &mut ConfigParsingError) -> RpcRateLimits {
+    let mut rate_limits = RpcRateLimits::default();
+    let Some(limits) = chain
+        .chain(err)
+        .get_opt_key("rpcRateLimits")
+        .into_array_iter()
+    else {
+        return rate_limits;
+    };
+    for limit in limits {
+        let url: Option<Url> = limit
+            .chain(err)
+            .get_opt_key("url")
+            .parse_from_str("Invalid url")
+            .end();
+        let Some(requests_per_second) = limit
+            .chain(err)
+            .get_key("requestsPerSecond")
+            .parse_f64()
+            .end()
+        else {
+            continue;
+        };
+        if requests_per_second <= 0. {
+            err.push(
+                &limit.cwp + "requests_per_second",
+                eyre!("Must be larger than 0"),
+            );
+            continue;
+        }
+        let burst = limit
+            .chain(err)
+            .get_opt_key("burst")
+            .parse_u32()
+            .unwrap_or_else(|| requests_per_second.ceil() as u32);
+        let conf = RateLimitConf {
+            requests_per_second,
+            burst,
+        };
+        match url {
+            Some(url) => {
+                rate_limits.urls.insert(url, conf);
+            }
+            None => rate_limits.default = Some(conf),
+        }
+    }
+    rate_limits
+}
+
+/// Expects AgentConfig.chains[chain].rpcHealthScoring
+fn parse_rpc_health_scoring(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<HealthScoringConf> {
+    chain
+        .get_opt_key("rpcHealthScoring")
+        .take_err(err, || &chain.cwp + "rpc_health_scoring")
+        .flatten()
+        .map(|value_parser| {
+            let default = HealthScoringConf::default();
+            HealthScoringConf {
+                window: value_parser
+                    .chain(err)
+                    .get_opt_key("window")
+                    .parse_u64()
+                    .map(|v| v as usize)
+                    .unwrap_or(default.window),
+                min_samples: value_parser
+                    .chain(err)
+                    .get_opt_key("minSamples")
+                    .parse_u64()
+                    .map(|v| v as usize)
+                    .unwrap_or(default.min_samples),
+                max_error_rate: value_parser
+                    .chain(err)
+                    .get_opt_key("maxErrorRate")
+                    .parse_f64()
+                    .unwrap_or(default.max_error_rate),
+                max_latency: value_parser
+                    .chain(err)
+                    .get_opt_key("maxLatencyMs")
+                    .parse_u64()
+                    .map(Duration::from_millis)
+                    .unwrap_or(default.max_latency),
+                max_block_lag: value_parser
+                    .chain(err)
+                    .get_opt_key("maxBlockLag")
+                    .parse_u64()
+                    .unwrap_or(default.max_block_lag),
+                probation: value_parser
+                    .chain(err)
+                    .get_opt_key("probationSecs")
+                    .parse_u64()
+                    .map(Duration::from_secs)
+                    .unwrap_or(default.probation),
+                block_height_interval: value_parser
+                    .chain(err)
+                    .get_opt_key("blockHeightIntervalSecs")
+                    .parse_u64()
+                    .map(Duration::from_secs)
+                    .unwrap_or(default.block_height_interval),
+            }
+        })
+}
+
 pub fn build_cosmos_connection_conf(
     rpcs: &[Url],
     chain: &ValueParser,
@@ -134,23 +319,107 @@ pub fn build_cosmos_connection_conf(
         .parse_u64()
         .end();
 
+    let fee_granter = chain
+        .chain(err)
+        .get_opt_key("feeGranter")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+
+    let websocket_url = chain
+        .chain(err)
+        .get_opt_key("index")
+        .get_opt_key("wsUrl")
+        .parse_from_str("Invalid websocket url")
+        .end();
+
     if !local_err.is_ok() {
         err.merge(local_err);
         None
     } else {
         Some(ChainConnectionConf::Cosmos(h_cosmos::ConnectionConf::new(
             grpcs,
-            rpcs.first().unwrap().to_string(),
+            rpcs.to_vec(),
             chain_id.unwrap().to_string(),
             prefix.unwrap().to_string(),
             canonical_asset.unwrap(),
             gas_price.unwrap(),
             contract_address_bytes.unwrap().try_into().unwrap(),
             operation_batch,
+            parse_rpc_health_scoring(chain, err),
+            fee_granter,
+            websocket_url,
         )))
     }
 }
 
+fn build_sealevel_priority_fee_conf(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<h_sealevel::PriorityFeeConf> {
+    chain
+        .get_opt_key("priorityFee")
+        .take_err(err, || &chain.cwp + "priority_fee")
+        .flatten()
+        .map(|value_parser| {
+            let default = h_sealevel::PriorityFeeConf::default();
+            let percentile = value_parser
+                .chain(err)
+                .get_opt_key("percentile")
+                .parse_u32()
+                .unwrap_or(default.percentile as u32);
+            h_sealevel::PriorityFeeConf {
+                percentile: u8::try_from(percentile)
+                    .ok()
+                    .filter(|p| *p <= 100)
+                    .ok_or_else(|| eyre!("Priority fee percentile must be between 0 and 100"))
+                    .take_err(err, || &value_parser.cwp + "percentile")
+                    .unwrap_or(default.percentile),
+                min_micro_lamports: value_parser
+                    .chain(err)
+                    .get_opt_key("minMicroLamports")
+                    .parse_u64()
+                    .unwrap_or(default.min_micro_lamports),
+                max_micro_lamports: value_parser
+                    .chain(err)
+                    .get_opt_key("maxMicroLamports")
+                    .parse_u64()
+                    .unwrap_or(default.max_micro_lamports),
+            }
+        })
+}
+
+fn build_sealevel_jito_conf(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<h_sealevel::JitoConf> {
+    chain
+        .get_opt_key("jito")
+        .take_err(err, || &chain.cwp + "jito")
+        .flatten()
+        .and_then(|value_parser| {
+            let url = value_parser
+                .chain(err)
+                .get_key("url")
+                .parse_from_str("Invalid url")
+                .end()?;
+            Some(h_sealevel::JitoConf {
+                url,
+                tip_lamports: value_parser
+                    .chain(err)
+                    .get_opt_key("tipLamports")
+                    .parse_u64()
+                    .unwrap_or(h_sealevel::JitoConf::DEFAULT_TIP_LAMPORTS),
+                bundle_timeout: value_parser
+                    .chain(err)
+                    .get_opt_key("bundleTimeoutSecs")
+                    .parse_u64()
+                    .map(Duration::from_secs)
+                    .unwrap_or(h_sealevel::JitoConf::DEFAULT_BUNDLE_TIMEOUT),
+            })
+        })
+}
+
 pub fn build_connection_conf(
     domain_protocol: HyperlaneDomainProtocol,
     rpcs: &[Url],
@@ -175,10 +444,62 @@ pub fn build_connection_conf(
             ChainConnectionConf::Sealevel(h_sealevel::ConnectionConf {
                 url: url.clone(),
                 operation_batch,
+                priority_fee: build_sealevel_priority_fee_conf(chain, err),
+                jito: build_sealevel_jito_conf(chain, err),
             })
         }),
         HyperlaneDomainProtocol::Cosmos => {
             build_cosmos_connection_conf(rpcs, chain, err, operation_batch)
         }
+        HyperlaneDomainProtocol::Aptos => rpcs.iter().next().map(|url| {
+            ChainConnectionConf::Aptos(h_aptos::ConnectionConf {
+                url: url.clone(),
+                operation_batch,
+                max_gas_amount: chain
+                    .chain(err)
+                    .get_opt_key("maxGasAmount")
+                    .parse_u64()
+                    .unwrap_or(h_aptos::DEFAULT_MAX_GAS_AMOUNT),
+                gas_unit_price: chain
+                    .chain(err)
+                    .get_opt_key("gasUnitPrice")
+                    .parse_u64()
+                    .end(),
+            })
+        }),
+        HyperlaneDomainProtocol::Sui => rpcs.iter().next().map(|url| {
+            ChainConnectionConf::Sui(h_sui::ConnectionConf {
+                url: url.clone(),
+                operation_batch,
+                gas_budget: chain
+                    .chain(err)
+                    .get_opt_key("gasBudget")
+                    .parse_u64()
+                    .unwrap_or(h_sui::DEFAULT_GAS_BUDGET),
+            })
+        }),
+        HyperlaneDomainProtocol::Starknet => rpcs.iter().next().map(|url| {
+            ChainConnectionConf::Starknet(h_starknet::ConnectionConf {
+                url: url.clone(),
+                operation_batch,
+            })
+        }),
+        HyperlaneDomainProtocol::Ton => rpcs.iter().next().map(|url| {
+            ChainConnectionConf::Ton(h_ton::ConnectionConf {
+                url: url.clone(),
+                api_key: chain
+                    .chain(err)
+                    .get_opt_key("apiKey")
+                    .parse_string()
+                    .end()
+                    .map(str::to_owned),
+                operation_batch,
+                message_value: chain
+                    .chain(err)
+                    .get_opt_key("messageValue")
+                    .parse_u64()
+                    .unwrap_or(h_ton::DEFAULT_MESSAGE_VALUE),
+            })
+        }),
     }
 }
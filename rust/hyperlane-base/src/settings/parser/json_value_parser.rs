@@ -3,7 +3,10 @@ use std::{fmt::Debug, str::FromStr};
 use convert_case::{Case, Casing};
 use derive_new::new;
 use eyre::{eyre, Context};
-use hyperlane_core::{config::*, utils::hex_or_base58_to_h256, H256, U256};
+use hyperlane_core::{
+    config::*, utils::hex_or_base58_to_h256, validate_address_with_prefix, HyperlaneDomainProtocol,
+    H256, U256,
+};
 use itertools::Itertools;
 use serde::de::{DeserializeOwned, StdError};
 use serde_json::Value;
@@ -244,6 +247,22 @@ impl<'v> ValueParser<'v> {
         .into_config_result(|| self.cwp.clone())
     }
 
+    /// Parse an address of a chain of `protocol`, rejecting the strings that
+    /// can't be one of its addresses, e.g. an EVM address with a wrong
+    /// checksum or a bech32 address without `bech32_prefix`.
+    pub fn parse_chain_address(
+        &self,
+        protocol: HyperlaneDomainProtocol,
+        bech32_prefix: Option<&str>,
+    ) -> ConfigResult<H256> {
+        match self.val {
+            Value::String(s) => validate_address_with_prefix(protocol, bech32_prefix, s)
+                .with_context(|| format!("Expected a valid {protocol:?} address, got `{s}`")),
+            _ => Err(eyre!("Expected an address string, got `{:?}`", self.val)),
+        }
+        .into_config_result(|| self.cwp.clone())
+    }
+
     /// Parse a private key allowing for it to be represented as a hex or base58 string.
     pub fn parse_private_key(&self) -> ConfigResult<H256> {
         match self.val {
@@ -329,6 +348,14 @@ impl<'v, 'e> ParseChain<'e, ValueParser<'v>> {
         self.and_then(|v| v.parse_value::<T>(ctx))
     }
 
+    pub fn parse_chain_address(
+        self,
+        protocol: HyperlaneDomainProtocol,
+        bech32_prefix: Option<&str>,
+    ) -> ParseChain<'e, H256> {
+        self.and_then(|v| v.parse_chain_address(protocol, bech32_prefix))
+    }
+
     pub fn into_obj_iter(self) -> Option<impl Iterator<Item = (String, ValueParser<'v>)> + 'v> {
         self.and_then(|v| v.into_obj_iter()).end()
     }
@@ -7,14 +7,18 @@
 use std::{
     collections::{HashMap, HashSet},
     default::Default,
+    sync::Arc,
+    time::Duration,
 };
 
 use convert_case::{Case, Casing};
 use eyre::{eyre, Context};
 use h_cosmos::RawCosmosAmount;
 use hyperlane_core::{
-    cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol,
-    HyperlaneDomainTechnicalStack, IndexMode,
+    cfg_unwrap_all,
+    config::*,
+    rpc_clients::{CircuitBreaker, CircuitBreakerConf},
+    HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneDomainTechnicalStack, IndexMode,
 };
 use itertools::Itertools;
 use serde::Deserialize;
@@ -23,9 +27,16 @@ use url::Url;
 
 pub use self::json_value_parser::ValueParser;
 pub use super::envs::*;
-use crate::settings::{
-    chains::IndexSettings, parser::connection_parser::build_connection_conf, trace::TracingConfig,
-    ChainConf, CoreContractAddresses, Settings, SignerConf,
+use crate::{
+    db::DbMaintenanceConf,
+    metrics::{MetricsPushAuth, MetricsPushConf, MetricsPushTarget, DEFAULT_PUSH_INTERVAL},
+    settings::{
+        chains::IndexSettings,
+        parser::connection_parser::build_connection_conf,
+        trace::{OtlpConfig, TracingConfig},
+        ChainConf, ChainConnectionConf, CoreContractAddresses, KeystorePassword, Settings,
+        SignerConf,
+    },
 };
 
 mod connection_parser;
@@ -52,6 +63,16 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_u16()
             .unwrap_or(9090);
 
+        let metrics_push = p
+            .chain(&mut err)
+            .get_opt_key("metricsPush")
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|push| parse_metrics_push(push).take_config_err(&mut err))
+                    .collect()
+            })
+            .unwrap_or_default();
+
         let fmt = p
             .chain(&mut err)
             .get_opt_key("log")
@@ -66,6 +87,19 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_value("Invalid log level")
             .unwrap_or_default();
 
+        let otlp = p
+            .chain(&mut err)
+            .get_opt_key("log")
+            .get_opt_key("otlp")
+            .and_then(parse_otlp)
+            .end();
+
+        let db_maintenance = p
+            .chain(&mut err)
+            .get_opt_key("dbMaintenance")
+            .and_then(parse_db_maintenance)
+            .unwrap_or_default();
+
         let raw_chains: Vec<(String, ValueParser)> = if let Some(filter) = filter {
             p.chain(&mut err)
                 .get_opt_key("chains")
@@ -109,7 +143,9 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
         err.into_result(Self {
             chains,
             metrics_port,
-            tracing: TracingConfig { fmt, level },
+            metrics_push,
+            tracing: TracingConfig { fmt, level, otlp },
+            db_maintenance,
         })
     }
 }
@@ -135,6 +171,7 @@ fn parse_chain(
         .get_key("reorgPeriod")
         .parse_u32()
         .unwrap_or(1);
+    let block_time = parse_block_time(&chain, &mut err);
 
     let rpcs = parse_base_and_override_urls(&chain, "rpcUrls", "customRpcUrls", "http", &mut err);
 
@@ -161,36 +198,83 @@ fn parse_chain(
                 .and_then(|d| match d.domain_protocol() {
                     HyperlaneDomainProtocol::Ethereum => Some(IndexMode::Block),
                     HyperlaneDomainProtocol::Sealevel => Some(IndexMode::Sequence),
+                    HyperlaneDomainProtocol::Aptos => Some(IndexMode::Sequence),
+                    HyperlaneDomainProtocol::Sui => Some(IndexMode::Sequence),
+                    HyperlaneDomainProtocol::Starknet => Some(IndexMode::Block),
+                    HyperlaneDomainProtocol::Ton => Some(IndexMode::Block),
                     _ => None,
                 })
                 .unwrap_or_default()
         });
 
+    let circuit_breaker = chain
+        .chain(&mut err)
+        .get_opt_key("circuitBreaker")
+        .and_then(parse_circuit_breaker)
+        .end()
+        .map(|conf| Arc::new(CircuitBreaker::new(conf)));
+
+    // Addresses are validated for the protocol of the chain, if it is known
+    let protocol = domain.as_ref().map(|d| d.domain_protocol());
+    let bech32_prefix = chain
+        .chain(&mut err)
+        .get_opt_key("bech32Prefix")
+        .parse_string()
+        .end()
+        .or_else(|| match &domain {
+            Some(HyperlaneDomain::Known(known)) => known.bech32_prefix(),
+            _ => None,
+        });
+    let parse_address = |address: ValueParser| match protocol {
+        Some(protocol) => address.parse_chain_address(protocol, bech32_prefix),
+        None => address.parse_address_hash(),
+    };
+
     let mailbox = chain
         .chain(&mut err)
         .get_key("mailbox")
-        .parse_address_hash()
+        .and_then(&parse_address)
         .end();
     let interchain_gas_paymaster = chain
         .chain(&mut err)
         .get_key("interchainGasPaymaster")
-        .parse_address_hash()
+        .and_then(&parse_address)
         .end();
     let validator_announce = chain
         .chain(&mut err)
         .get_key("validatorAnnounce")
-        .parse_address_hash()
+        .and_then(&parse_address)
         .end();
     let merkle_tree_hook = chain
         .chain(&mut err)
         .get_key("merkleTreeHook")
-        .parse_address_hash()
+        .and_then(&parse_address)
         .end();
 
+    let storage_gas_oracle = chain
+        .chain(&mut err)
+        .get_opt_key("storageGasOracle")
+        .and_then(&parse_address)
+        .end();
+
+    let known_contracts = chain
+        .chain(&mut err)
+        .get_opt_key("knownContracts")
+        .into_obj_iter()
+        .map(|itr| {
+            itr.filter_map(|(name, address)| {
+                parse_address(address)
+                    .take_config_err(&mut err)
+                    .map(|address| (address, name))
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+
     let batch_contract_address = chain
         .chain(&mut err)
         .get_opt_key("batchContractAddress")
-        .parse_address_hash()
+        .and_then(&parse_address)
         .end();
 
     let max_batch_size = chain
@@ -199,8 +283,31 @@ fn parse_chain(
         .parse_u32()
         .unwrap_or(1);
 
+    let max_inflight_transactions = chain
+        .chain(&mut err)
+        .get_opt_key("maxInflightTransactions")
+        .parse_u32()
+        .end();
+
+    let confirmation_batch_size = chain
+        .chain(&mut err)
+        .get_opt_key("confirmationBatchSize")
+        .parse_u32()
+        .end();
+
+    // Numeric for most protocols, but e.g. a string for cosmos chains
+    let chain_id = chain
+        .chain(&mut err)
+        .get_opt_key("chainId")
+        .and_then(|id| {
+            id.parse_u64()
+                .map(|id| id.to_string())
+                .or_else(|_| id.parse_string().map(str::to_owned))
+        })
+        .end();
+
     cfg_unwrap_all!(&chain.cwp, err: [domain]);
-    let connection = build_connection_conf(
+    let mut connection = build_connection_conf(
         domain.domain_protocol(),
         &rpcs,
         &chain,
@@ -211,6 +318,10 @@ fn parse_chain(
             max_batch_size,
         },
     );
+    // EVM chains trip their breaker in the rpc client every contract shares
+    if let Some(ChainConnectionConf::Ethereum(conf)) = &mut connection {
+        conf.circuit_breaker = circuit_breaker.clone();
+    }
 
     cfg_unwrap_all!(&chain.cwp, err: [connection, mailbox, interchain_gas_paymaster, validator_announce, merkle_tree_hook]);
     err.into_result(ChainConf {
@@ -222,6 +333,7 @@ fn parse_chain(
             interchain_gas_paymaster,
             validator_announce,
             merkle_tree_hook,
+            storage_gas_oracle,
         },
         connection,
         metrics_conf: Default::default(),
@@ -229,7 +341,13 @@ fn parse_chain(
             from,
             chunk_size,
             mode,
+            block_time,
         },
+        circuit_breaker,
+        known_contracts,
+        max_inflight_transactions,
+        confirmation_batch_size,
+        chain_id,
     })
 }
 
@@ -281,8 +399,226 @@ fn parse_domain(chain: ValueParser, name: &str) -> ConfigResult<HyperlaneDomain>
     err.into_result(domain)
 }
 
-/// Expects AgentSigner.
-fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
+/// Expects AgentConfig.log.otlp
+fn parse_otlp(otlp: ValueParser) -> ConfigResult<OtlpConfig> {
+    let mut err = ConfigParsingError::default();
+
+    let endpoint = otlp
+        .chain(&mut err)
+        .get_key("endpoint")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let headers = otlp
+        .chain(&mut err)
+        .get_opt_key("headers")
+        .into_obj_iter()
+        .map(|itr| {
+            itr.filter_map(|(key, value)| {
+                value
+                    .parse_string()
+                    .take_config_err(&mut err)
+                    .map(|value| (key, value.to_owned()))
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+    let sampling_ratio = otlp
+        .chain(&mut err)
+        .get_opt_key("samplingRatio")
+        .parse_f64()
+        .unwrap_or(1.);
+    let service_name = otlp
+        .chain(&mut err)
+        .get_opt_key("serviceName")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+
+    cfg_unwrap_all!(&otlp.cwp, err: [endpoint]);
+    err.into_result(OtlpConfig {
+        endpoint,
+        headers,
+        sampling_ratio,
+        service_name,
+    })
+}
+
+/// Expects AgentConfig.metricsPush.*
+fn parse_metrics_push(push: ValueParser) -> ConfigResult<MetricsPushConf> {
+    let mut err = ConfigParsingError::default();
+
+    let url: Option<Url> = push
+        .chain(&mut err)
+        .get_key("url")
+        .parse_from_str("Invalid url")
+        .end();
+    let job = push
+        .chain(&mut err)
+        .get_opt_key("job")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let kind = push.chain(&mut err).get_key("type").parse_string().end();
+    let target = match (kind, url) {
+        (Some("pushgateway"), Some(url)) => Some(MetricsPushTarget::Pushgateway { url, job }),
+        (Some("remoteWrite"), Some(url)) => Some(MetricsPushTarget::RemoteWrite { url }),
+        (Some(kind), _) if kind != "pushgateway" && kind != "remoteWrite" => {
+            err.push(
+                &push.cwp + "type",
+                eyre!(
+                    "Unknown metrics push type `{kind}`, expected `pushgateway` or `remoteWrite`"
+                ),
+            );
+            None
+        }
+        _ => None,
+    };
+    let interval = push
+        .chain(&mut err)
+        .get_opt_key("intervalSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_PUSH_INTERVAL);
+
+    let username = push
+        .chain(&mut err)
+        .get_opt_key("basicAuth")
+        .get_key("username")
+        .parse_string()
+        .end();
+    let password = push
+        .chain(&mut err)
+        .get_opt_key("basicAuth")
+        .get_key("password")
+        .parse_string()
+        .end();
+    let bearer_token = push
+        .chain(&mut err)
+        .get_opt_key("bearerToken")
+        .parse_string()
+        .end();
+    let auth = match (username, password, bearer_token) {
+        (Some(_), Some(_), Some(_)) => {
+            err.push(
+                &push.cwp + "bearer_token",
+                eyre!("Only one of `basicAuth` and `bearerToken` can be set"),
+            );
+            None
+        }
+        (Some(username), Some(password), None) => Some(MetricsPushAuth::Basic {
+            username: username.to_owned(),
+            password: password.to_owned(),
+        }),
+        (_, _, Some(token)) => Some(MetricsPushAuth::Bearer(token.to_owned())),
+        _ => None,
+    };
+
+    let headers = push
+        .chain(&mut err)
+        .get_opt_key("headers")
+        .into_obj_iter()
+        .map(|itr| {
+            itr.filter_map(|(key, value)| {
+                value
+                    .parse_string()
+                    .take_config_err(&mut err)
+                    .map(|value| (key, value.to_owned()))
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+
+    cfg_unwrap_all!(&push.cwp, err: [target]);
+    err.into_result(MetricsPushConf {
+        target,
+        interval,
+        auth,
+        headers,
+    })
+}
+
+/// Expects AgentConfig.dbMaintenance
+fn parse_db_maintenance(maintenance: ValueParser) -> ConfigResult<DbMaintenanceConf> {
+    let mut err = ConfigParsingError::default();
+
+    let compaction_interval = maintenance
+        .chain(&mut err)
+        .get_opt_key("compactionIntervalSecs")
+        .parse_u64()
+        .end()
+        .map(Duration::from_secs);
+    let max_size_bytes = maintenance
+        .chain(&mut err)
+        .get_opt_key("maxSizeBytes")
+        .parse_u64()
+        .end();
+    let message_retention = maintenance
+        .chain(&mut err)
+        .get_opt_key("messageRetentionSecs")
+        .parse_u64()
+        .end()
+        .map(Duration::from_secs);
+
+    err.into_result(DbMaintenanceConf {
+        compaction_interval,
+        max_size_bytes,
+        message_retention,
+    })
+}
+
+/// Expects AgentConfig.chains[chain].circuitBreaker
+fn parse_circuit_breaker(breaker: ValueParser) -> ConfigResult<CircuitBreakerConf> {
+    let mut err = ConfigParsingError::default();
+
+    let failure_threshold = breaker
+        .chain(&mut err)
+        .get_key("failureThreshold")
+        .parse_u32()
+        .end();
+    if failure_threshold == Some(0) {
+        err.push(
+            &breaker.cwp + "failure_threshold",
+            eyre!("Must be larger than 0"),
+        );
+    }
+    let cooldown = breaker
+        .chain(&mut err)
+        .get_opt_key("cooldownSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(30));
+
+    cfg_unwrap_all!(&breaker.cwp, err: [failure_threshold]);
+    err.into_result(CircuitBreakerConf {
+        failure_threshold,
+        cooldown,
+    })
+}
+
+/// Expects AgentConfig.chains[chain].blocks.estimateBlockTime, in seconds
+fn parse_block_time(chain: &ValueParser, err: &mut ConfigParsingError) -> Option<Duration> {
+    let block_time = chain
+        .chain(err)
+        .get_opt_key("blocks")
+        .get_opt_key("estimateBlockTime")
+        .parse_f64()
+        .end()?;
+    if block_time <= 0. {
+        err.push(
+            &chain.cwp + "blocks" + "estimateBlockTime",
+            eyre!("Must be larger than 0"),
+        );
+        return None;
+    }
+    Some(Duration::from_secs_f64(block_time))
+}
+
+/// The first account of the Ethereum app of a Ledger
+const DEFAULT_LEDGER_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Expects AgentSigner, e.g. of a chain or of a relayer tenant.
+pub fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
     let mut err = ConfigParsingError::default();
 
     let signer_type = signer
@@ -318,6 +654,96 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 .unwrap_or_default();
             err.into_result(SignerConf::Aws { id, region })
         }};
+        (gcp) => {{
+            let id = signer
+                .chain(&mut err)
+                .get_key("id")
+                .parse_string()
+                .unwrap_or("")
+                .to_owned();
+            err.into_result(SignerConf::Gcp { id })
+        }};
+        (web3signer) => {{
+            let url = signer
+                .chain(&mut err)
+                .get_key("url")
+                .parse_from_str("Expected remote signer url")
+                .end();
+            let address = signer
+                .chain(&mut err)
+                .get_key("address")
+                .parse_from_str("Expected address of the signing key")
+                .end();
+            let client_cert = signer
+                .chain(&mut err)
+                .get_opt_key("clientCert")
+                .parse_from_str("Expected path to a PKCS#12 client certificate")
+                .end();
+            let client_cert_password = signer
+                .chain(&mut err)
+                .get_opt_key("clientCertPassword")
+                .parse_string()
+                .unwrap_or_default()
+                .to_owned();
+            let ca_cert = signer
+                .chain(&mut err)
+                .get_opt_key("caCert")
+                .parse_from_str("Expected path to a PEM CA certificate")
+                .end();
+            cfg_unwrap_all!(&signer.cwp, err: [url, address]);
+            err.into_result(SignerConf::Web3Signer {
+                url,
+                address,
+                client_cert,
+                client_cert_password,
+                ca_cert,
+            })
+        }};
+        (keystore) => {{
+            let path = signer
+                .chain(&mut err)
+                .get_key("path")
+                .parse_from_str("Expected path to a keystore file")
+                .end();
+            let password_env = signer
+                .chain(&mut err)
+                .get_opt_key("passwordEnv")
+                .parse_string()
+                .end();
+            let password_file = signer
+                .chain(&mut err)
+                .get_opt_key("passwordFile")
+                .parse_from_str("Expected path to a keystore password file")
+                .end();
+            let password = match (password_env, password_file) {
+                (Some(var), None) => Some(KeystorePassword::Env(var.to_owned())),
+                (None, Some(path)) => Some(KeystorePassword::File(path)),
+                _ => {
+                    err.push(
+                        &signer.cwp + "password_env",
+                        eyre!("Expected exactly one of `passwordEnv` and `passwordFile`"),
+                    );
+                    None
+                }
+            };
+            cfg_unwrap_all!(&signer.cwp, err: [path, password]);
+            err.into_result(SignerConf::Keystore { path, password })
+        }};
+        (ledger) => {{
+            let derivation_path = signer
+                .chain(&mut err)
+                .get_opt_key("derivationPath")
+                .parse_string()
+                .unwrap_or(DEFAULT_LEDGER_DERIVATION_PATH)
+                .to_owned();
+            if !derivation_path.starts_with("m/") {
+                err.push(
+                    &signer.cwp + "derivation_path",
+                    eyre!("Expected a derivation path starting with `m/`"),
+                );
+            }
+            err.into_result(SignerConf::Ledger { derivation_path })
+        }};
         (cosmosKey) => {{
             let key = signer
                 .chain(&mut err)
@@ -334,12 +760,30 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 prefix: prefix.to_string(),
             })
         }};
+        (starkKey) => {{
+            let key = signer
+                .chain(&mut err)
+                .get_key("key")
+                .parse_private_key()
+                .unwrap_or_default();
+            let address = signer
+                .chain(&mut err)
+                .get_key("address")
+                .parse_address_hash()
+                .unwrap_or_default();
+            err.into_result(SignerConf::StarkKey { key, address })
+        }};
     }
 
     match signer_type {
         Some("hexKey") => parse_signer!(hexKey),
         Some("aws") => parse_signer!(aws),
+        Some("gcp") => parse_signer!(gcp),
+        Some("web3signer") => parse_signer!(web3signer),
+        Some("keystore") => parse_signer!(keystore),
+        Some("ledger") => parse_signer!(ledger),
         Some("cosmosKey") => parse_signer!(cosmosKey),
+        Some("starkKey") => parse_signer!(starkKey),
         Some(t) => {
             Err(eyre!("Unknown signer type `{t}`")).into_config_result(|| &signer.cwp + "type")
         }
@@ -466,3 +910,32 @@ fn parse_base_and_override_urls(
     }
     combined
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn signer(raw: Value) -> ConfigResult<SignerConf> {
+        parse_signer(ValueParser::new(ConfigPath::default(), &raw))
+    }
+
+    #[test]
+    fn test_parse_ledger_signer() {
+        let conf = signer(json!({ "type": "ledger" })).unwrap();
+        assert!(matches!(
+            conf,
+            SignerConf::Ledger { derivation_path } if derivation_path == DEFAULT_LEDGER_DERIVATION_PATH
+        ));
+
+        let conf =
+            signer(json!({ "type": "ledger", "derivationpath": "m/44'/60'/1'/0/0" })).unwrap();
+        assert!(matches!(
+            conf,
+            SignerConf::Ledger { derivation_path } if derivation_path == "m/44'/60'/1'/0/0"
+        ));
+
+        assert!(signer(json!({ "type": "ledger", "derivationpath": "44'/60'/0'/0/0" })).is_err());
+    }
+}
@@ -1,13 +1,16 @@
+use std::path::PathBuf;
+
 use async_trait::async_trait;
 use ed25519_dalek::SecretKey;
 use ethers::prelude::{AwsSigner, LocalWallet};
 use ethers::utils::hex::ToHex;
 use eyre::{bail, Context, Report};
-use hyperlane_core::H256;
+use hyperlane_core::{H160, H256};
 use hyperlane_sealevel::Keypair;
 use rusoto_core::Region;
 use rusoto_kms::KmsClient;
 use tracing::instrument;
+use url::Url;
 
 use super::aws_credentials::AwsChainCredentialsProvider;
 use crate::types::utils;
@@ -28,6 +31,42 @@ pub enum SignerConf {
         /// The AWS region
         region: Region,
     },
+    /// A GCP Cloud KMS signer. Note that GCP credentials are looked up from
+    /// the env or metadata server separately.
+    Gcp {
+        /// The resource name of the Cloud KMS key version
+        id: String,
+    },
+    /// A remote Web3Signer-compatible signer, so the agent never holds the
+    /// key
+    Web3Signer {
+        /// Url of the remote signer
+        url: Url,
+        /// Address of the key to sign with
+        address: H160,
+        /// Path to a PKCS#12 archive with the TLS client certificate and key
+        client_cert: Option<PathBuf>,
+        /// Password of the client certificate archive
+        client_cert_password: String,
+        /// Path to a PEM certificate of a private CA the remote signer's
+        /// certificate is issued by
+        ca_cert: Option<PathBuf>,
+    },
+    /// A local key in an encrypted Web3 Secret Storage (v3) keystore, as
+    /// written by geth, clef or `cast wallet`. The key is decrypted when the
+    /// signer is built.
+    Keystore {
+        /// Path to the keystore JSON file
+        path: PathBuf,
+        /// Where the password of the keystore is read from
+        password: KeystorePassword,
+    },
+    /// A key held by a Ledger hardware wallet connected over USB. Every
+    /// transaction has to be approved on the device.
+    Ledger {
+        /// BIP-32 derivation path of the key, e.g. `m/44'/60'/0'/0/0`
+        derivation_path: String,
+    },
     /// Cosmos Specific key
     CosmosKey {
         /// Private key value
@@ -35,17 +74,63 @@ pub enum SignerConf {
         /// Prefix for cosmos address
         prefix: String,
     },
+    /// StarkNet specific key. StarkNet accounts are contracts, so the
+    /// address isn't derived from the key.
+    StarkKey {
+        /// Private key value
+        key: H256,
+        /// Address of the account contract
+        address: H256,
+    },
     /// Assume node will sign on RPC calls
     #[default]
     Node,
 }
 
+/// Source of the password of a keystore, so that it isn't in the config
+#[derive(Debug, Clone)]
+pub enum KeystorePassword {
+    /// An environment variable
+    Env(String),
+    /// A file, with trailing newlines ignored
+    File(PathBuf),
+}
+
+impl KeystorePassword {
+    fn read(&self) -> Result<String, Report> {
+        match self {
+            KeystorePassword::Env(var) => std::env::var(var)
+                .with_context(|| format!("Keystore password variable `{var}` is not set")),
+            KeystorePassword::File(path) => Ok(std::fs::read_to_string(path)
+                .with_context(|| {
+                    format!("Failed to read keystore password file {}", path.display())
+                })?
+                .trim_end_matches(&['\r', '\n'][..])
+                .to_owned()),
+        }
+    }
+}
+
 impl SignerConf {
     /// Try to convert the ethereum signer to a local wallet
     #[instrument(err)]
     pub async fn build<S: BuildableWithSignerConf>(&self) -> Result<S, Report> {
         S::build(self).await
     }
+
+    /// The local private key of a hex key or keystore signer, decrypting the
+    /// keystore
+    pub fn local_key(&self) -> Result<Option<H256>, Report> {
+        match self {
+            SignerConf::HexKey { key } => Ok(Some(*key)),
+            SignerConf::Keystore { path, password } => {
+                let wallet = LocalWallet::decrypt_keystore(path, password.read()?)
+                    .with_context(|| format!("Failed to decrypt keystore {}", path.display()))?;
+                Ok(Some(H256::from_slice(&wallet.signer().to_bytes())))
+            }
+            _ => Ok(None),
+        }
+    }
 }
 
 /// A signer for a chain.
@@ -65,12 +150,17 @@ pub trait BuildableWithSignerConf: Sized + ChainSigner {
 impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
         Ok(match conf {
-            SignerConf::HexKey { key } => hyperlane_ethereum::Signers::Local(LocalWallet::from(
-                ethers::core::k256::ecdsa::SigningKey::from(
-                    ethers::core::k256::SecretKey::from_be_bytes(key.as_bytes())
-                        .context("Invalid ethereum signer key")?,
-                ),
-            )),
+            SignerConf::HexKey { .. } | SignerConf::Keystore { .. } => {
+                let Some(key) = conf.local_key()? else {
+                    bail!("Signer has no local key");
+                };
+                hyperlane_ethereum::Signers::Local(LocalWallet::from(
+                    ethers::core::k256::ecdsa::SigningKey::from(
+                        ethers::core::k256::SecretKey::from_be_bytes(key.as_bytes())
+                            .context("Invalid ethereum signer key")?,
+                    ),
+                ))
+            }
             SignerConf::Aws { id, region } => {
                 let client = KmsClient::new_with_client(
                     rusoto_core::Client::new_with(
@@ -83,9 +173,39 @@ impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
                 let signer = AwsSigner::new(client, id, 0).await?;
                 hyperlane_ethereum::Signers::Aws(signer)
             }
+            SignerConf::Gcp { id } => {
+                let signer = hyperlane_ethereum::GcpSigner::new(id, 0).await?;
+                hyperlane_ethereum::Signers::Gcp(signer)
+            }
+            SignerConf::Web3Signer {
+                url,
+                address,
+                client_cert,
+                client_cert_password,
+                ca_cert,
+            } => {
+                let signer = hyperlane_ethereum::Web3Signer::new(
+                    url.clone(),
+                    *address,
+                    client_cert
+                        .as_deref()
+                        .map(|path| (path, client_cert_password.as_str())),
+                    ca_cert.as_deref(),
+                    0,
+                )
+                .await?;
+                hyperlane_ethereum::Signers::Web3Signer(signer)
+            }
+            SignerConf::Ledger { derivation_path } => {
+                let signer = hyperlane_ethereum::LedgerSigner::new(derivation_path, 0).await?;
+                hyperlane_ethereum::Signers::Ledger(signer)
+            }
             SignerConf::CosmosKey { .. } => {
                 bail!("cosmosKey signer is not supported by Ethereum")
             }
+            SignerConf::StarkKey { .. } => {
+                bail!("starkKey signer is not supported by Ethereum")
+            }
             SignerConf::Node => bail!("Node signer"),
         })
     }
@@ -100,7 +220,7 @@ impl ChainSigner for hyperlane_ethereum::Signers {
 #[async_trait]
 impl BuildableWithSignerConf for fuels::prelude::WalletUnlocked {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
-        if let SignerConf::HexKey { key } = conf {
+        if let Some(key) = conf.local_key()? {
             let key = fuels::signers::fuel_crypto::SecretKey::try_from(key.as_bytes())
                 .context("Invalid fuel signer key")?;
             Ok(fuels::prelude::WalletUnlocked::new_from_private_key(
@@ -121,7 +241,7 @@ impl ChainSigner for fuels::prelude::WalletUnlocked {
 #[async_trait]
 impl BuildableWithSignerConf for Keypair {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
-        if let SignerConf::HexKey { key } = conf {
+        if let Some(key) = conf.local_key()? {
             let secret = SecretKey::from_bytes(key.as_bytes())
                 .context("Invalid sealevel ed25519 secret key")?;
             Ok(
@@ -159,3 +279,110 @@ impl ChainSigner for hyperlane_cosmos::Signer {
         self.address.clone()
     }
 }
+
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_aptos::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let Some(key) = conf.local_key()? {
+            Ok(hyperlane_aptos::Signer::new(key.as_bytes())
+                .context("Invalid aptos ed25519 private key")?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by aptos"));
+        }
+    }
+}
+
+impl ChainSigner for hyperlane_aptos::Signer {
+    fn address_string(&self) -> String {
+        hyperlane_aptos::Signer::address_string(self)
+    }
+}
+
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_sui::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let Some(key) = conf.local_key()? {
+            Ok(hyperlane_sui::Signer::new(key.as_bytes())
+                .context("Invalid sui ed25519 private key")?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by sui"));
+        }
+    }
+}
+
+impl ChainSigner for hyperlane_sui::Signer {
+    fn address_string(&self) -> String {
+        hyperlane_sui::Signer::address_string(self)
+    }
+}
+
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_starknet::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::StarkKey { key, address } = conf {
+            Ok(hyperlane_starknet::Signer::new(*key, *address)
+                .context("Invalid starknet signer key")?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by starknet"));
+        }
+    }
+}
+
+impl ChainSigner for hyperlane_starknet::Signer {
+    fn address_string(&self) -> String {
+        hyperlane_starknet::Signer::address_string(self)
+    }
+}
+
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_ton::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let Some(key) = conf.local_key()? {
+            Ok(hyperlane_ton::Signer::new(key.as_bytes())
+                .context("Invalid ton ed25519 private key")?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by ton"));
+        }
+    }
+}
+
+impl ChainSigner for hyperlane_ton::Signer {
+    fn address_string(&self) -> String {
+        hyperlane_ton::Signer::address_string(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::types::Address;
+
+    use super::*;
+
+    /// Keystore of the key of `0xf39F…2266`, encrypted with the password
+    /// `keystore-password` and cheap scrypt parameters
+    const KEYSTORE: &str = r#"{"address":"f39fd6e51aad88f6f4ce6ab8827279cfffb92266","crypto":{"cipher":"aes-128-ctr","cipherparams":{"iv":"cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"},"ciphertext":"96b2e7d29a0e53fae1aba97cadbf95adaff4d7f0c94c072f520b52fe8f114da5","kdf":"scrypt","kdfparams":{"dklen":32,"n":16,"p":1,"r":8,"salt":"abababababababababababababababababababababababababababababababab"},"mac":"773b9468ba1e902da747e6e8e70bced2e1603cf3f0fe2e88e7c137c2d9c071f9"},"id":"3198bc9c-6672-5ab3-d995-4942343ae5b6","version":3}"#;
+
+    #[tokio::test]
+    async fn test_decrypts_keystores_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keystore.json");
+        let password_path = dir.path().join("password");
+        std::fs::write(&path, KEYSTORE).unwrap();
+        std::fs::write(&password_path, "keystore-password\n").unwrap();
+        let conf = SignerConf::Keystore {
+            path,
+            password: KeystorePassword::File(password_path.clone()),
+        };
+        let address: Address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+            .parse()
+            .unwrap();
+
+        let signer: hyperlane_ethereum::Signers = conf.build().await.unwrap();
+        assert_eq!(ethers::signers::Signer::address(&signer), address);
+
+        // Built again from the decrypted key, without reading the password
+        std::fs::remove_file(password_path).unwrap();
+        let signer: hyperlane_ethereum::Signers = conf.build().await.unwrap();
+        assert_eq!(ethers::signers::Signer::address(&signer), address);
+    }
+}
@@ -10,12 +10,16 @@ use tracing_subscriber::{
     Layer,
 };
 
+use super::StructuredJson;
+
 /// Basic tracing configuration
 #[derive(Default, Debug, Clone, Copy, serde::Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum Style {
     /// JSON
     Json,
+    /// JSON with a stable schema, see [`StructuredJson`]
+    Structured,
     /// Compact
     Compact,
     /// Shows everything
@@ -49,6 +53,8 @@ pub enum LogOutputLayer<S, N = DefaultFields, W = fn() -> Stdout> {
     Compact(fmt::Layer<S, N, Format<Compact>, W>),
     /// Json log output
     Json(fmt::Layer<S, JsonFields, Format<Json>, W>),
+    /// Json log output with a stable schema
+    Structured(fmt::Layer<S, JsonFields, StructuredJson, W>),
 }
 
 impl<S> Default for LogOutputLayer<S> {
@@ -64,6 +70,11 @@ impl<S> From<Style> for LogOutputLayer<S> {
             Style::Pretty => Self::Pretty(fmt::layer().pretty()),
             Style::Compact => Self::Compact(fmt::layer().compact()),
             Style::Json => Self::Json(fmt::layer().json()),
+            Style::Structured => Self::Structured(
+                fmt::layer()
+                    .fmt_fields(JsonFields::new())
+                    .event_format(StructuredJson),
+            ),
         }
     }
 }
@@ -81,6 +92,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.register_callsite(metadata),
             LogOutputLayer::Compact(inner) => inner.register_callsite(metadata),
             LogOutputLayer::Json(inner) => inner.register_callsite(metadata),
+            LogOutputLayer::Structured(inner) => inner.register_callsite(metadata),
         }
     }
 
@@ -94,6 +106,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.enabled(metadata, ctx),
             LogOutputLayer::Compact(inner) => inner.enabled(metadata, ctx),
             LogOutputLayer::Json(inner) => inner.enabled(metadata, ctx),
+            LogOutputLayer::Structured(inner) => inner.enabled(metadata, ctx),
         }
     }
 
@@ -108,6 +121,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.on_new_span(attrs, id, ctx),
             LogOutputLayer::Compact(inner) => inner.on_new_span(attrs, id, ctx),
             LogOutputLayer::Json(inner) => inner.on_new_span(attrs, id, ctx),
+            LogOutputLayer::Structured(inner) => inner.on_new_span(attrs, id, ctx),
         }
     }
 
@@ -117,6 +131,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.max_level_hint(),
             LogOutputLayer::Compact(inner) => inner.max_level_hint(),
             LogOutputLayer::Json(inner) => inner.max_level_hint(),
+            LogOutputLayer::Structured(inner) => inner.max_level_hint(),
         }
     }
 
@@ -131,6 +146,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.on_record(span, values, ctx),
             LogOutputLayer::Compact(inner) => inner.on_record(span, values, ctx),
             LogOutputLayer::Json(inner) => inner.on_record(span, values, ctx),
+            LogOutputLayer::Structured(inner) => inner.on_record(span, values, ctx),
         }
     }
 
@@ -145,6 +161,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.on_follows_from(span, follows, ctx),
             LogOutputLayer::Compact(inner) => inner.on_follows_from(span, follows, ctx),
             LogOutputLayer::Json(inner) => inner.on_follows_from(span, follows, ctx),
+            LogOutputLayer::Structured(inner) => inner.on_follows_from(span, follows, ctx),
         }
     }
 
@@ -154,6 +171,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.on_event(event, ctx),
             LogOutputLayer::Compact(inner) => inner.on_event(event, ctx),
             LogOutputLayer::Json(inner) => inner.on_event(event, ctx),
+            LogOutputLayer::Structured(inner) => inner.on_event(event, ctx),
         }
     }
 
@@ -163,6 +181,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.on_enter(id, ctx),
             LogOutputLayer::Compact(inner) => inner.on_enter(id, ctx),
             LogOutputLayer::Json(inner) => inner.on_enter(id, ctx),
+            LogOutputLayer::Structured(inner) => inner.on_enter(id, ctx),
         }
     }
 
@@ -172,6 +191,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.on_exit(id, ctx),
             LogOutputLayer::Compact(inner) => inner.on_exit(id, ctx),
             LogOutputLayer::Json(inner) => inner.on_exit(id, ctx),
+            LogOutputLayer::Structured(inner) => inner.on_exit(id, ctx),
         }
     }
 
@@ -181,6 +201,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.on_close(id, ctx),
             LogOutputLayer::Compact(inner) => inner.on_close(id, ctx),
             LogOutputLayer::Json(inner) => inner.on_close(id, ctx),
+            LogOutputLayer::Structured(inner) => inner.on_close(id, ctx),
         }
     }
 
@@ -195,6 +216,7 @@ where
             LogOutputLayer::Pretty(inner) => inner.on_id_change(old, new, ctx),
             LogOutputLayer::Compact(inner) => inner.on_id_change(old, new, ctx),
             LogOutputLayer::Json(inner) => inner.on_id_change(old, new, ctx),
+            LogOutputLayer::Structured(inner) => inner.on_id_change(old, new, ctx),
         }
     }
 }
@@ -235,6 +257,12 @@ mod test {
             Style::Json
         );
 
+        let case = r#"{"style": "structured"}"#;
+        assert_eq!(
+            serde_json::from_str::<TestStyle>(case).unwrap().style,
+            Style::Structured
+        );
+
         let case = r#"{"style": "toast"}"#;
         assert_eq!(
             serde_json::from_str::<TestStyle>(case).unwrap().style,
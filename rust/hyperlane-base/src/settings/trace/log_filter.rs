@@ -0,0 +1,162 @@
+//! Changing the log filter of a running agent, e.g. to trace a single crate
+//! while debugging it, without a restart that loses the state being debugged.
+//! Changes revert to the configured filter after a while, so that a
+//! forgotten one doesn't flood the logs.
+
+use std::{
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use eyre::{Context, Result};
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use tracing_subscriber::{filter::Targets, reload};
+
+/// How long a change lasts if no duration is given
+pub const DEFAULT_OVERRIDE_DURATION: Duration = Duration::from_secs(10 * 60);
+/// Longest a change can last
+pub const MAX_OVERRIDE_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+static LOG_FILTER: OnceLock<LogFilter> = OnceLock::new();
+
+type Reload = Box<dyn Fn(Targets) -> Result<(), reload::Error> + Send + Sync>;
+
+/// The log filter of the agent, set up when tracing starts
+pub struct LogFilter {
+    configured: Targets,
+    reload: Reload,
+    current: Mutex<Option<Override>>,
+}
+
+struct Override {
+    directives: String,
+    expires_at: Instant,
+    revert: JoinHandle<()>,
+}
+
+/// The change to the configured log filter in effect, if any
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilterStatus {
+    /// Directives applied on top of the configured filter
+    pub overrides: Option<String>,
+    /// Seconds until the configured filter is restored
+    pub expires_in_secs: Option<u64>,
+}
+
+impl std::fmt::Debug for LogFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogFilter")
+            .field("configured", &self.configured)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LogFilter {
+    pub(crate) fn install(configured: Targets, reload: Reload) {
+        let _ = LOG_FILTER.set(Self {
+            configured,
+            reload,
+            current: Mutex::new(None),
+        });
+    }
+
+    /// The log filter of the agent, if tracing was started
+    pub fn global() -> Option<&'static LogFilter> {
+        LOG_FILTER.get()
+    }
+
+    /// Apply `directives`, e.g. `hyperlane_ethereum=trace,info`, on top of
+    /// the configured filter for `duration`. Replaces any previous change.
+    pub fn set(&'static self, directives: &str, duration: Duration) -> Result<LogFilterStatus> {
+        let filter = apply_overrides(&self.configured, directives)?;
+        let duration = duration.min(MAX_OVERRIDE_DURATION);
+        let mut current = self.current.lock().expect("log filter lock poisoned");
+        (self.reload)(filter).context("Failed to reload the log filter")?;
+        info!(directives, ?duration, "Changed the log filter");
+
+        let revert = tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            if let Err(err) = self.reset() {
+                warn!(?err, "Failed to restore the configured log filter");
+            }
+        });
+        if let Some(previous) = current.replace(Override {
+            directives: directives.to_owned(),
+            expires_at: Instant::now() + duration,
+            revert,
+        }) {
+            previous.revert.abort();
+        }
+        Ok(status(&current))
+    }
+
+    /// Restore the configured filter
+    pub fn reset(&self) -> Result<LogFilterStatus> {
+        let mut current = self.current.lock().expect("log filter lock poisoned");
+        if let Some(previous) = current.take() {
+            (self.reload)(self.configured.clone()).context("Failed to reload the log filter")?;
+            previous.revert.abort();
+            info!("Restored the configured log filter");
+        }
+        Ok(status(&current))
+    }
+
+    /// The change in effect
+    pub fn status(&self) -> LogFilterStatus {
+        status(&self.current.lock().expect("log filter lock poisoned"))
+    }
+}
+
+fn status(current: &Option<Override>) -> LogFilterStatus {
+    LogFilterStatus {
+        overrides: current.as_ref().map(|o| o.directives.clone()),
+        expires_in_secs: current.as_ref().map(|o| {
+            o.expires_at
+                .saturating_duration_since(Instant::now())
+                .as_secs()
+        }),
+    }
+}
+
+/// The configured filter with the targets and default level of `directives`
+/// replacing its own
+fn apply_overrides(configured: &Targets, directives: &str) -> Result<Targets> {
+    let overrides: Targets = directives
+        .parse()
+        .with_context(|| format!("Invalid log filter `{directives}`"))?;
+    let mut filter = configured.clone();
+    if let Some(level) = overrides.default_level() {
+        filter = filter.with_default(level);
+    }
+    Ok(filter.with_targets(overrides))
+}
+
+#[cfg(test)]
+mod test {
+    use tracing::Level;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_overrides() {
+        let configured = Targets::new()
+            .with_default(Level::INFO)
+            .with_target("hyper", Level::INFO);
+
+        let filter = apply_overrides(&configured, "hyperlane_ethereum=trace").unwrap();
+        assert!(filter.would_enable("hyperlane_ethereum::mailbox", &Level::TRACE));
+        // The rest of the configured filter is kept
+        assert!(filter.would_enable("relayer", &Level::INFO));
+        assert!(!filter.would_enable("relayer", &Level::DEBUG));
+        assert!(!filter.would_enable("hyper", &Level::DEBUG));
+
+        let filter = apply_overrides(&configured, "debug,hyper=trace").unwrap();
+        assert!(filter.would_enable("relayer", &Level::DEBUG));
+        assert!(filter.would_enable("hyper", &Level::TRACE));
+
+        assert!(apply_overrides(&configured, "hyperlane_ethereum=loud").is_err());
+    }
+}
@@ -3,15 +3,22 @@ pub use span_metrics::TimeSpanLifetime;
 use tracing_subscriber::{
     filter::{LevelFilter, Targets},
     prelude::*,
+    reload,
 };
 
 use self::fmt::LogOutputLayer;
+pub use self::log_filter::{LogFilter, LogFilterStatus, DEFAULT_OVERRIDE_DURATION};
+pub use self::otlp::{shutdown_otlp, OtlpConfig};
+pub use self::structured::StructuredJson;
 use crate::{settings::trace::fmt::Style, CoreMetrics};
 
 /// Configure a `tracing_subscriber::fmt` Layer outputting to stdout
 pub mod fmt;
 
+mod log_filter;
+mod otlp;
 mod span_metrics;
+mod structured;
 
 /// Logging level. A "higher level" means more will be logged.
 #[derive(Default, Debug, Clone, Copy, serde::Deserialize, PartialOrd, Ord, PartialEq, Eq)]
@@ -55,6 +62,9 @@ pub struct TracingConfig {
     pub(crate) fmt: Style,
     #[serde(default)]
     pub(crate) level: Level,
+    /// Export spans to an OpenTelemetry collector too
+    #[serde(skip)]
+    pub(crate) otlp: Option<OtlpConfig>,
 }
 
 impl TracingConfig {
@@ -83,6 +93,8 @@ impl TracingConfig {
             // only show sqlx query logs at trace level
             target_layer = target_layer.with_target("sqlx::query", Level::Warn);
         }
+        let configured_filter = target_layer.clone();
+        let (target_layer, filter_handle) = reload::Layer::new(target_layer);
         let fmt_layer: LogOutputLayer<_> = self.fmt.into();
         let err_layer = tracing_error::ErrorLayer::default();
 
@@ -93,8 +105,18 @@ impl TracingConfig {
             .with(TimeSpanLifetime::new(metrics))
             .with(fmt_layer)
             .with(err_layer);
+        let otlp_layer = self
+            .otlp
+            .as_ref()
+            .map(|otlp| otlp.layer(metrics.agent_name()))
+            .transpose()?;
+        let subscriber = subscriber.with(otlp_layer);
 
         subscriber.try_init()?;
+        LogFilter::install(
+            configured_filter,
+            Box::new(move |filter| filter_handle.reload(filter)),
+        );
         Ok(tokio_server)
     }
 }
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use eyre::Result;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    runtime,
+    trace::{self, Sampler, Tracer},
+    Resource,
+};
+use tonic::metadata::{Ascii, MetadataKey, MetadataMap, MetadataValue};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Configuration for exporting spans to an OpenTelemetry collector over
+/// OTLP/gRPC, e.g. to view them in Tempo or Jaeger
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Endpoint of the collector, e.g. `http://localhost:4317`
+    pub endpoint: String,
+    /// Headers sent with every export, e.g. to authenticate with the
+    /// collector
+    pub headers: HashMap<String, String>,
+    /// Fraction of traces to export, between 0 and 1. Spans of a trace that
+    /// was sampled by a parent are always exported.
+    pub sampling_ratio: f64,
+    /// The `service.name` of the spans. Defaults to the name of the agent.
+    pub service_name: Option<String>,
+}
+
+impl OtlpConfig {
+    /// A layer exporting spans in batches. The batches are exported from a
+    /// thread of their own, as the agents run on a current thread runtime.
+    pub(crate) fn layer<S>(&self, agent_name: &str) -> Result<OpenTelemetryLayer<S, Tracer>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let mut metadata = MetadataMap::new();
+        for (key, value) in &self.headers {
+            let key: MetadataKey<Ascii> = key.parse()?;
+            let value: MetadataValue<Ascii> = value.parse()?;
+            metadata.insert(key, value);
+        }
+        let exporter = opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(&self.endpoint)
+            .with_metadata(metadata);
+        let service_name = self.service_name.as_deref().unwrap_or(agent_name);
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(
+                trace::config()
+                    .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                        self.sampling_ratio,
+                    ))))
+                    .with_resource(Resource::new([KeyValue::new(
+                        "service.name",
+                        service_name.to_owned(),
+                    )])),
+            )
+            .install_batch(runtime::TokioCurrentThread)?;
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+}
+
+/// Export the spans that are still buffered. Blocks until they are exported.
+pub fn shutdown_otlp() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
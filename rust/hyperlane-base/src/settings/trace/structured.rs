@@ -0,0 +1,235 @@
+//! JSON log output with a stable schema, so log pipelines can parse and alert
+//! on agent logs without depending on how each log site names its fields.
+//!
+//! Every line is one JSON object with the same top-level keys, `null` when a
+//! log has no value for them:
+//! - `timestamp`, `level`, `target` and `message`
+//! - `chain`: name of the chain the log is about, from the `chain`, `domain`,
+//!   `destination` or `origin` field
+//! - `domain`: id of that chain if it is a known one, or else from the
+//!   `domain_id` field or a numeric `domain` field
+//! - `message_id`: from the `message_id`, `msg_id` or `id` field
+//! - `operation_id`: from the `operation_id` or `op_id` field
+//! - `operation_status`: from the `operation_status` or `status` field
+//! - `fields`: every field of the log and its spans
+//! - `spans`: names of the spans the log is in, outermost first
+//!
+//! Fields of the log take precedence over those of its spans, and fields of
+//! inner spans over those of outer ones.
+
+use std::{fmt, str::FromStr};
+
+use hyperlane_core::KnownHyperlaneDomain;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::{field::Field, Event, Subscriber};
+use tracing_subscriber::{
+    field::Visit,
+    fmt::{
+        format::{JsonFields, Writer},
+        time::{FormatTime, SystemTime},
+        FmtContext, FormatEvent, FormattedFields,
+    },
+    registry::LookupSpan,
+};
+
+const CHAIN_FIELDS: &[&str] = &["chain", "domain", "destination", "origin"];
+const DOMAIN_FIELDS: &[&str] = &["domain_id", "domain"];
+const MESSAGE_ID_FIELDS: &[&str] = &["message_id", "msg_id", "id"];
+const OPERATION_ID_FIELDS: &[&str] = &["operation_id", "op_id"];
+const OPERATION_STATUS_FIELDS: &[&str] = &["operation_status", "status"];
+
+/// Formats events as JSON with a stable schema
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StructuredJson;
+
+#[derive(Serialize)]
+struct StructuredLog<'a> {
+    timestamp: String,
+    level: &'a str,
+    target: &'a str,
+    message: Option<Value>,
+    chain: Option<Value>,
+    domain: Option<u32>,
+    message_id: Option<Value>,
+    operation_id: Option<Value>,
+    operation_status: Option<Value>,
+    fields: Map<String, Value>,
+    spans: Vec<&'a str>,
+}
+
+impl<S> FormatEvent<S, JsonFields> for StructuredJson
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, JsonFields>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut timestamp = String::new();
+        SystemTime.format_time(&mut Writer::new(&mut timestamp))?;
+
+        let mut event_fields = FieldVisitor::default();
+        event.record(&mut event_fields);
+        let mut event_fields = event_fields.0;
+        let message = event_fields.remove("message");
+
+        // Fields of the log first, then those of its spans from the innermost
+        let mut field_sets = vec![event_fields];
+        let mut spans = vec![];
+        for span in ctx.event_scope().into_iter().flatten() {
+            spans.push(span.name());
+            let extensions = span.extensions();
+            let span_fields: Option<Map<String, Value>> = extensions
+                .get::<FormattedFields<JsonFields>>()
+                .and_then(|fields| serde_json::from_str(&fields.fields).ok());
+            if let Some(span_fields) = span_fields {
+                field_sets.push(span_fields);
+            }
+        }
+        spans.reverse();
+
+        let (chain, domain) = chain_and_domain(&field_sets);
+        let log = StructuredLog {
+            timestamp,
+            level: event.metadata().level().as_str(),
+            target: event.metadata().target(),
+            message,
+            chain,
+            domain,
+            message_id: find_field(&field_sets, MESSAGE_ID_FIELDS, |_| true),
+            operation_id: find_field(&field_sets, OPERATION_ID_FIELDS, |_| true),
+            operation_status: find_field(&field_sets, OPERATION_STATUS_FIELDS, |_| true),
+            fields: merge_fields(field_sets),
+            spans,
+        };
+        let line = serde_json::to_string(&log).map_err(|_| fmt::Error)?;
+        writeln!(writer, "{line}")
+    }
+}
+
+/// The value of the first of `names` set in the field sets, by precedence
+fn find_field(
+    field_sets: &[Map<String, Value>],
+    names: &[&str],
+    accept: impl Fn(&Value) -> bool,
+) -> Option<Value> {
+    field_sets.iter().find_map(|fields| {
+        names
+            .iter()
+            .filter_map(|name| fields.get(*name))
+            .find(|value| accept(value))
+            .cloned()
+    })
+}
+
+/// The chain a log is about, and its domain id
+fn chain_and_domain(field_sets: &[Map<String, Value>]) -> (Option<Value>, Option<u32>) {
+    let chain = find_field(field_sets, CHAIN_FIELDS, |v| !is_number(v));
+    let known_domain = chain
+        .as_ref()
+        .and_then(Value::as_str)
+        .and_then(|name| KnownHyperlaneDomain::from_str(name).ok())
+        .map(|domain| domain as u32);
+    let domain = known_domain.or_else(|| {
+        let id = find_field(field_sets, DOMAIN_FIELDS, is_number)?;
+        match id {
+            Value::Number(id) => id.as_u64(),
+            Value::String(id) => id.parse().ok(),
+            _ => None,
+        }
+        .and_then(|id| u32::try_from(id).ok())
+    });
+    (chain, domain)
+}
+
+/// Merge the field sets, keeping the value with the highest precedence
+fn merge_fields(field_sets: Vec<Map<String, Value>>) -> Map<String, Value> {
+    let mut merged = Map::new();
+    for fields in field_sets {
+        for (name, value) in fields {
+            merged.entry(name).or_insert(value);
+        }
+    }
+    merged
+}
+
+fn is_number(value: &Value) -> bool {
+    match value {
+        Value::Number(_) => true,
+        Value::String(s) => s.parse::<u64>().is_ok(),
+        _ => false,
+    }
+}
+
+/// Records the fields of an event as JSON values
+#[derive(Default)]
+struct FieldVisitor(Map<String, Value>);
+
+impl Visit for FieldVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{value:?}").into());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_find_field_by_precedence() {
+        let event = json!({ "destination": "arbitrum", "msg_id": "0x01" });
+        let inner_span = json!({ "domain": "ethereum", "message_id": "0x02" });
+        let outer_span = json!({ "domain": 1, "status": "Success" });
+        let field_sets: Vec<_> = [event, inner_span, outer_span]
+            .into_iter()
+            .map(|v| v.as_object().unwrap().clone())
+            .collect();
+
+        // The chain of the event wins over that of its spans
+        assert_eq!(
+            chain_and_domain(&field_sets),
+            (Some(json!("arbitrum")), Some(42161))
+        );
+        // Numeric domains are ids, not chain names
+        assert_eq!(chain_and_domain(&field_sets[2..]), (None, Some(1)));
+        assert_eq!(
+            find_field(&field_sets, MESSAGE_ID_FIELDS, |_| true),
+            Some(json!("0x01"))
+        );
+        assert_eq!(
+            find_field(&field_sets, OPERATION_STATUS_FIELDS, |_| true),
+            Some(json!("Success"))
+        );
+        assert_eq!(find_field(&field_sets, OPERATION_ID_FIELDS, |_| true), None);
+
+        let merged = merge_fields(field_sets);
+        assert_eq!(merged["domain"], json!("ethereum"));
+        assert_eq!(merged["status"], json!("Success"));
+    }
+}
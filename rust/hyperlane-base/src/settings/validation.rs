@@ -0,0 +1,377 @@
+//! Checks of the parsed settings that span several fields, run before any
+//! task is started so that every problem is reported at once with its config
+//! path rather than one at a time from deep inside a task.
+
+use std::path::Path;
+
+use eyre::eyre;
+use hyperlane_core::{config::*, rpc_clients::HealthScoringConf, HyperlaneDomainProtocol, H256};
+use hyperlane_ethereum::RpcConnectionConf;
+use hyperlane_sealevel::JitoConf;
+use url::Url;
+
+use crate::metrics::{MetricsPushConf, MetricsPushTarget};
+use crate::settings::{
+    chains::{ChainConf, ChainConnectionConf},
+    signers::{KeystorePassword, SignerConf},
+    trace::OtlpConfig,
+    Settings,
+};
+
+impl Settings {
+    /// Check the chains, tracing and metrics push configuration, collecting
+    /// every problem
+    pub fn validate(&self, cwp: &ConfigPath) -> ConfigResult<()> {
+        let mut err = ConfigParsingError::default();
+        for (name, chain) in &self.chains {
+            if let Err(chain_err) = chain.validate(&(cwp + "chains" + name.as_str())) {
+                err.merge(chain_err);
+            }
+        }
+        if let Some(otlp) = &self.tracing.otlp {
+            validate_otlp(otlp, &(cwp + "log" + "otlp"), &mut err);
+        }
+        for (i, push) in self.metrics_push.iter().enumerate() {
+            validate_metrics_push(push, &(cwp + "metrics_push" + i.to_string()), &mut err);
+        }
+        err.into_result(())
+    }
+}
+
+impl ChainConf {
+    /// Check that the connection urls have the expected schemes, that the
+    /// signer is one the chain's protocol supports and that the core
+    /// contract addresses are set
+    pub fn validate(&self, cwp: &ConfigPath) -> ConfigResult<()> {
+        let mut err = ConfigParsingError::default();
+
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                match &conf.rpc_connection {
+                    RpcConnectionConf::HttpQuorum { urls }
+                    | RpcConnectionConf::HttpFallback { urls, .. } => {
+                        for url in urls {
+                            check_scheme(url, HTTP, &(cwp + "rpc_urls"), &mut err);
+                        }
+                    }
+                    RpcConnectionConf::Http { url } => {
+                        check_scheme(url, HTTP, &(cwp + "rpc_urls"), &mut err)
+                    }
+                    RpcConnectionConf::Ws { url } => {
+                        check_scheme(url, WS, &(cwp + "rpc_urls"), &mut err)
+                    }
+                }
+                if let RpcConnectionConf::HttpFallback {
+                    health_scoring: Some(scoring),
+                    ..
+                } = &conf.rpc_connection
+                {
+                    validate_health_scoring(scoring, &(cwp + "rpc_health_scoring"), &mut err);
+                }
+                if let Some(url) = &conf.log_subscription_url {
+                    check_scheme(url, WS, &(cwp + "index" + "ws_url"), &mut err);
+                }
+            }
+            ChainConnectionConf::Fuel(conf) => {
+                check_scheme(&conf.url, HTTP, &(cwp + "rpc_urls"), &mut err)
+            }
+            ChainConnectionConf::Sealevel(conf) => {
+                check_scheme(&conf.url, HTTP, &(cwp + "rpc_urls"), &mut err);
+                if let Some(fee) = &conf.priority_fee {
+                    if fee.min_micro_lamports > fee.max_micro_lamports {
+                        err.push(
+                            cwp + "priority_fee" + "min_micro_lamports",
+                            eyre!("Must not be larger than `maxMicroLamports`"),
+                        );
+                    }
+                }
+                if let Some(jito) = &conf.jito {
+                    check_scheme(&jito.url, HTTP, &(cwp + "jito" + "url"), &mut err);
+                    if jito.tip_lamports < JitoConf::MIN_TIP_LAMPORTS {
+                        err.push(
+                            cwp + "jito" + "tip_lamports",
+                            eyre!("Must be at least {} lamports", JitoConf::MIN_TIP_LAMPORTS),
+                        );
+                    }
+                }
+            }
+            ChainConnectionConf::Cosmos(conf) => {
+                for url in conf.get_grpc_urls() {
+                    check_scheme(&url, HTTP, &(cwp + "grpc_urls"), &mut err);
+                }
+                for url in conf.get_rpc_urls() {
+                    check_scheme(&url, HTTP, &(cwp + "rpc_urls"), &mut err);
+                }
+                if let Some(scoring) = conf.get_health_scoring() {
+                    validate_health_scoring(scoring, &(cwp + "rpc_health_scoring"), &mut err);
+                }
+                if let Some(granter) = conf.get_fee_granter() {
+                    let prefix = conf.get_bech32_prefix();
+                    if !granter.starts_with(&format!("{prefix}1")) {
+                        err.push(
+                            cwp + "fee_granter",
+                            eyre!("Must be a bech32 address with the `{prefix}` prefix"),
+                        );
+                    }
+                }
+            }
+            ChainConnectionConf::Aptos(conf) => {
+                check_scheme(&conf.url, HTTP, &(cwp + "rpc_urls"), &mut err);
+                if conf.max_gas_amount == 0 {
+                    err.push(cwp + "max_gas_amount", eyre!("Must be larger than 0"));
+                }
+            }
+            ChainConnectionConf::Sui(conf) => {
+                check_scheme(&conf.url, HTTP, &(cwp + "rpc_urls"), &mut err);
+                if conf.gas_budget == 0 {
+                    err.push(cwp + "gas_budget", eyre!("Must be larger than 0"));
+                }
+            }
+            ChainConnectionConf::Starknet(conf) => {
+                check_scheme(&conf.url, HTTP, &(cwp + "rpc_urls"), &mut err);
+            }
+            ChainConnectionConf::Ton(conf) => {
+                check_scheme(&conf.url, HTTP, &(cwp + "rpc_urls"), &mut err);
+                if conf.message_value == 0 {
+                    err.push(cwp + "message_value", eyre!("Must be larger than 0"));
+                }
+            }
+        }
+
+        if let Some(signer) = &self.signer {
+            validate_signer(
+                signer,
+                self.connection.protocol(),
+                &(cwp + "signer"),
+                &mut err,
+            );
+        }
+
+        if self.addresses.mailbox == H256::zero() {
+            err.push(cwp + "mailbox", eyre!("Mailbox address is not set"));
+        }
+        if self.index.chunk_size == 0 {
+            err.push(cwp + "index" + "chunk", eyre!("Must be larger than 0"));
+        }
+        if self.max_inflight_transactions == Some(0) {
+            err.push(
+                cwp + "max_inflight_transactions",
+                eyre!("Must be larger than 0"),
+            );
+        }
+        if self.confirmation_batch_size == Some(0) {
+            err.push(
+                cwp + "confirmation_batch_size",
+                eyre!("Must be larger than 0"),
+            );
+        }
+
+        err.into_result(())
+    }
+}
+
+const HTTP: &[&str] = &["http", "https"];
+const WS: &[&str] = &["ws", "wss"];
+
+fn check_scheme(url: &Url, schemes: &[&str], cwp: &ConfigPath, err: &mut ConfigParsingError) {
+    if !schemes.contains(&url.scheme()) {
+        err.push(
+            cwp.clone(),
+            eyre!(
+                "Url `{url}` has scheme `{}`, expected one of {schemes:?}",
+                url.scheme()
+            ),
+        );
+    }
+}
+
+fn validate_health_scoring(
+    scoring: &HealthScoringConf,
+    cwp: &ConfigPath,
+    err: &mut ConfigParsingError,
+) {
+    if !(0. ..=1.).contains(&scoring.max_error_rate) {
+        err.push(cwp + "max_error_rate", eyre!("Must be between 0 and 1"));
+    }
+    if scoring.min_samples > scoring.window {
+        err.push(
+            cwp + "min_samples",
+            eyre!(
+                "Must not be larger than the window of {} requests",
+                scoring.window
+            ),
+        );
+    }
+}
+
+fn validate_signer(
+    signer: &SignerConf,
+    protocol: HyperlaneDomainProtocol,
+    cwp: &ConfigPath,
+    err: &mut ConfigParsingError,
+) {
+    use HyperlaneDomainProtocol::*;
+
+    let supported = match signer {
+        SignerConf::HexKey { .. } | SignerConf::Keystore { .. } => {
+            matches!(protocol, Ethereum | Fuel | Sealevel | Aptos | Sui | Ton)
+        }
+        SignerConf::Aws { .. }
+        | SignerConf::Gcp { .. }
+        | SignerConf::Web3Signer { .. }
+        | SignerConf::Ledger { .. } => protocol == Ethereum,
+        SignerConf::CosmosKey { .. } => protocol == Cosmos,
+        SignerConf::StarkKey { .. } => protocol == Starknet,
+        SignerConf::Node => true,
+    };
+    if !supported {
+        err.push(
+            cwp + "type",
+            eyre!("This signer type is not supported on {protocol:?} chains"),
+        );
+    }
+
+    if let SignerConf::Web3Signer {
+        url,
+        client_cert,
+        ca_cert,
+        ..
+    } = signer
+    {
+        check_scheme(url, HTTP, &(cwp + "url"), err);
+        check_file(client_cert.as_deref(), &(cwp + "client_cert"), err);
+        check_file(ca_cert.as_deref(), &(cwp + "ca_cert"), err);
+    }
+
+    if let SignerConf::Keystore { path, password } = signer {
+        check_file(Some(path), &(cwp + "path"), err);
+        if let KeystorePassword::File(password_file) = password {
+            check_file(Some(password_file), &(cwp + "password_file"), err);
+        }
+    }
+}
+
+fn check_file(path: Option<&Path>, cwp: &ConfigPath, err: &mut ConfigParsingError) {
+    if let Some(path) = path {
+        if !path.is_file() {
+            err.push(
+                cwp.clone(),
+                eyre!("File `{}` does not exist", path.display()),
+            );
+        }
+    }
+}
+
+fn validate_otlp(otlp: &OtlpConfig, cwp: &ConfigPath, err: &mut ConfigParsingError) {
+    match otlp.endpoint.parse::<Url>() {
+        Ok(url) => check_scheme(&url, HTTP, &(cwp + "endpoint"), err),
+        Err(e) => err.push(cwp + "endpoint", eyre!("Invalid url: {e}")),
+    }
+    if !(0. ..=1.).contains(&otlp.sampling_ratio) {
+        err.push(cwp + "sampling_ratio", eyre!("Must be between 0 and 1"));
+    }
+}
+
+fn validate_metrics_push(push: &MetricsPushConf, cwp: &ConfigPath, err: &mut ConfigParsingError) {
+    let (MetricsPushTarget::Pushgateway { url, .. } | MetricsPushTarget::RemoteWrite { url }) =
+        &push.target;
+    check_scheme(url, HTTP, &(cwp + "url"), err);
+    if push.interval.is_zero() {
+        err.push(cwp + "interval_secs", eyre!("Must be positive"));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{config::OperationBatchConfig, HyperlaneDomain, KnownHyperlaneDomain};
+
+    use super::*;
+    use crate::settings::{chains::IndexSettings, CoreContractAddresses};
+
+    fn chain(connection: ChainConnectionConf, signer: Option<SignerConf>) -> ChainConf {
+        ChainConf {
+            domain: HyperlaneDomain::Known(KnownHyperlaneDomain::Test1),
+            signer,
+            reorg_period: 0,
+            addresses: CoreContractAddresses {
+                mailbox: H256::repeat_byte(1),
+                ..Default::default()
+            },
+            connection,
+            metrics_conf: Default::default(),
+            index: IndexSettings {
+                chunk_size: 1,
+                ..Default::default()
+            },
+            circuit_breaker: None,
+            known_contracts: Default::default(),
+            max_inflight_transactions: None,
+            confirmation_batch_size: None,
+            chain_id: None,
+        }
+    }
+
+    fn sealevel(url: &str) -> ChainConnectionConf {
+        ChainConnectionConf::Sealevel(hyperlane_sealevel::ConnectionConf {
+            url: url.parse().unwrap(),
+            operation_batch: OperationBatchConfig::default(),
+            priority_fee: None,
+            jito: None,
+        })
+    }
+
+    #[test]
+    fn test_valid_chain() {
+        let conf = chain(
+            sealevel("https://rpc.example.com"),
+            Some(SignerConf::HexKey {
+                key: H256::repeat_byte(2),
+            }),
+        );
+        assert!(conf.validate(&ConfigPath::default()).is_ok());
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let mut conf = chain(
+            sealevel("wss://rpc.example.com"),
+            Some(SignerConf::CosmosKey {
+                key: H256::repeat_byte(2),
+                prefix: "neutron".into(),
+            }),
+        );
+        conf.addresses.mailbox = H256::zero();
+        let err = conf
+            .validate(&(ConfigPath::default() + "chains" + "test1"))
+            .unwrap_err()
+            .to_string();
+        for path in [
+            "chains.test1.rpcUrls",
+            "chains.test1.signer.type",
+            "chains.test1.mailbox",
+        ] {
+            assert!(err.contains(&format!("config_path: `{path}`")), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_reports_missing_keystore_files() {
+        let conf = chain(
+            sealevel("https://rpc.example.com"),
+            Some(SignerConf::Keystore {
+                path: "/nonexistent/keystore.json".into(),
+                password: KeystorePassword::File("/nonexistent/password".into()),
+            }),
+        );
+        let err = conf
+            .validate(&(ConfigPath::default() + "chains" + "test1"))
+            .unwrap_err()
+            .to_string();
+        for path in [
+            "chains.test1.signer.path",
+            "chains.test1.signer.passwordFile",
+        ] {
+            assert!(err.contains(&format!("config_path: `{path}`")), "{err}");
+        }
+    }
+}
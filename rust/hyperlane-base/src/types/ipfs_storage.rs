@@ -0,0 +1,533 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use eyre::{bail, eyre, Context, Result};
+use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
+use prometheus::IntGauge;
+use reqwest::{multipart, Client, StatusCode};
+use serde::Deserialize;
+use tokio::{sync::Notify, task::JoinHandle, time::sleep};
+use tracing::warn;
+use url::Url;
+
+use crate::CheckpointSyncer;
+
+/// Gateway used to read checkpoints from IPFS
+pub const IPFS_GATEWAY_URL: &str = "IPFS_GATEWAY_URL";
+/// Gateway used if `IPFS_GATEWAY_URL` is not set
+pub const DEFAULT_IPFS_GATEWAY_URL: &str = "https://ipfs.io";
+
+/// Directory of the IPFS node's mutable file system the checkpoints are
+/// written to, one subdirectory per IPNS key.
+const MFS_ROOT: &str = "/hyperlane";
+
+/// Publishing to IPNS can take a while as the record is propagated through
+/// the DHT.
+const IPFS_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long to wait before publishing again after publishing failed
+const PUBLISH_RETRY_DELAY: Duration = Duration::from_secs(if cfg!(test) { 0 } else { 10 });
+
+/// Type for reading/writing checkpoints to IPFS.
+///
+/// Checkpoints are written to the mutable file system of an IPFS (kubo) node,
+/// which keeps them pinned, and the directory holding them is published under
+/// an IPNS name so its location stays the same as checkpoints are added.
+/// Anyone can read them through an IPFS gateway.
+#[derive(Clone)]
+pub struct IpfsStorage {
+    client: Client,
+    /// Gateway used to read checkpoints
+    gateway_url: Url,
+    /// IPNS name the checkpoints are published under
+    name: String,
+    /// RPC API of the IPFS node and the name of the key to publish with.
+    /// Only needed for writing.
+    writer: Option<(Url, String)>,
+    /// Publishes the checkpoints in the background, for writers
+    publisher: Option<Arc<Publisher>>,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+}
+
+/// Publishes the checkpoint directory of a writer in the background, as
+/// publishing to IPNS takes up to minutes which mustn't hold up signing.
+/// Requests made while publishing are coalesced into a single publish once
+/// it's done.
+#[derive(Debug)]
+struct Publisher {
+    requests: Arc<Notify>,
+    task: JoinHandle<()>,
+}
+
+impl Publisher {
+    fn spawn(storage: IpfsStorage) -> Self {
+        let requests = Arc::new(Notify::new());
+        let task = tokio::spawn({
+            let requests = requests.clone();
+            async move {
+                loop {
+                    requests.notified().await;
+                    while let Err(err) = storage.publish().await {
+                        warn!(?err, "Failed to publish checkpoints to IPNS, retrying");
+                        sleep(PUBLISH_RETRY_DELAY).await;
+                    }
+                }
+            }
+        });
+        Self { requests, task }
+    }
+}
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl fmt::Debug for IpfsStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpfsStorage")
+            .field("gateway_url", &self.gateway_url)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct KeyList {
+    keys: Vec<Key>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Key {
+    name: String,
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileStat {
+    hash: String,
+}
+
+impl IpfsStorage {
+    /// Create a new IPFS checkpoint syncer. Readers only need the IPNS
+    /// `name`, writers need the `api_url` of their node and the name of the
+    /// `key` to publish with, from which the IPNS name is derived if not set.
+    pub async fn new(
+        gateway_url: Url,
+        name: Option<String>,
+        api_url: Option<Url>,
+        key: Option<String>,
+        latest_index: Option<IntGauge>,
+    ) -> Result<Self> {
+        let client = Client::builder().timeout(IPFS_REQUEST_TIMEOUT).build()?;
+        let writer = api_url.zip(key);
+        let name = match (name, &writer) {
+            (Some(name), _) => name,
+            (None, Some((api_url, key))) => Self::resolve_key_name(&client, api_url, key).await?,
+            (None, None) => bail!("Either an IPNS name or an IPFS api url and key are required"),
+        };
+        let mut storage = Self {
+            client,
+            gateway_url,
+            name,
+            writer,
+            publisher: None,
+            latest_index,
+        };
+        if storage.writer.is_some() {
+            storage.publisher = Some(Arc::new(Publisher::spawn(storage.clone())));
+        }
+        Ok(storage)
+    }
+
+    /// Look up the IPNS name of a key in the node's keystore
+    async fn resolve_key_name(client: &Client, api_url: &Url, key: &str) -> Result<String> {
+        let keys: KeyList = client
+            .post(api_url.join("api/v0/key/list")?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        keys.keys
+            .into_iter()
+            .find(|k| k.name == key)
+            .map(|k| k.id)
+            .ok_or_else(|| eyre!("Key `{key}` not found in the keystore of the IPFS node"))
+    }
+
+    fn writer(&self) -> Result<&(Url, String)> {
+        self.writer
+            .as_ref()
+            .ok_or_else(|| eyre!("IPFS checkpoint syncer is read-only, no api url configured"))
+    }
+
+    fn mfs_dir(key: &str) -> String {
+        format!("{MFS_ROOT}/{key}")
+    }
+
+    async fn api_call(&self, command: &str, args: &[(&str, &str)]) -> Result<reqwest::Response> {
+        let (api_url, _) = self.writer()?;
+        let response = self
+            .client
+            .post(api_url.join(&format!("api/v0/{command}"))?)
+            .query(args)
+            .send()
+            .await?;
+        Ok(response)
+    }
+
+    async fn write_file(&self, file: String, body: Vec<u8>) -> Result<()> {
+        let (api_url, key) = self.writer()?;
+        let path = format!("{}/{file}", Self::mfs_dir(key));
+        let form = multipart::Form::new().part("file", multipart::Part::bytes(body));
+        self.client
+            .post(api_url.join("api/v0/files/write")?)
+            .query(&[
+                ("arg", path.as_str()),
+                ("create", "true"),
+                ("parents", "true"),
+                ("truncate", "true"),
+            ])
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Writing {path} to IPFS"))?;
+        Ok(())
+    }
+
+    /// Publish the current version of the checkpoint directory under the
+    /// IPNS name, making everything written so far visible to readers.
+    async fn publish(&self) -> Result<()> {
+        let (_, key) = self.writer()?;
+        let stat: FileStat = self
+            .api_call("files/stat", &[("arg", &Self::mfs_dir(key))])
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        self.api_call(
+            "name/publish",
+            &[("arg", &format!("/ipfs/{}", stat.hash)), ("key", key)],
+        )
+        .await?
+        .error_for_status()
+        .context("Publishing checkpoints to IPNS")?;
+        Ok(())
+    }
+
+    /// Read a file, from the node's own file system if this is a writer as
+    /// IPNS records take a while to propagate, or through the gateway
+    /// otherwise.
+    async fn read_file(&self, file: String) -> Result<Option<Vec<u8>>> {
+        let response = match &self.writer {
+            Some((_, key)) => {
+                let path = format!("{}/{file}", Self::mfs_dir(key));
+                let response = self.api_call("files/read", &[("arg", &path)]).await?;
+                // The api doesn't distinguish missing files by status code
+                if response.status() == StatusCode::INTERNAL_SERVER_ERROR {
+                    let body = response.text().await?;
+                    if body.contains("does not exist") {
+                        return Ok(None);
+                    }
+                    bail!("Reading {path} from IPFS failed: {body}");
+                }
+                response
+            }
+            None => {
+                let url = self
+                    .gateway_url
+                    .join(&format!("ipns/{}/{file}", self.name))?;
+                let response = self.client.get(url).send().await?;
+                if response.status() == StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+                response
+            }
+        };
+        Ok(Some(response.error_for_status()?.bytes().await?.to_vec()))
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn latest_index_key() -> String {
+        "checkpoint_latest_index.json".to_owned()
+    }
+
+    fn announcement_key() -> String {
+        "announcement.json".to_owned()
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for IpfsStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let ret = self
+            .read_file(IpfsStorage::latest_index_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into);
+
+        if let Ok(Some(latest_index)) = ret {
+            if let Some(gauge) = &self.latest_index {
+                gauge.set(latest_index as i64);
+            }
+        }
+
+        ret
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let serialized_index = serde_json::to_vec(&index)?;
+        self.write_file(IpfsStorage::latest_index_key(), serialized_index)
+            .await?;
+        // Checkpoints are written before the latest index, so publishing here
+        // makes them available in batches rather than one IPNS update each.
+        // Readers see them once the background publish is done.
+        if let Some(publisher) = &self.publisher {
+            publisher.requests.notify_one();
+        }
+        Ok(())
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.read_file(IpfsStorage::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let serialized_checkpoint = serde_json::to_vec_pretty(signed_checkpoint)?;
+        self.write_file(
+            IpfsStorage::checkpoint_key(signed_checkpoint.value.index),
+            serialized_checkpoint,
+        )
+        .await
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let serialized_announcement = serde_json::to_vec_pretty(signed_announcement)?;
+        self.write_file(IpfsStorage::announcement_key(), serialized_announcement)
+            .await?;
+        self.publish().await
+    }
+
+    fn announcement_location(&self) -> String {
+        format!("ipns://{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        body::Bytes,
+        extract::{Path, Query, State},
+        http::StatusCode,
+        routing::{get, post},
+        Json, Router,
+    };
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, H256, U256};
+    use serde_json::json;
+    use tokio::{sync::Semaphore, time::timeout};
+
+    use super::*;
+
+    const KEY: &str = "validator";
+    const NAME: &str = "k51qzi5uqu5validator";
+
+    /// The RPC api and gateway of an IPFS node with the mutable file system
+    /// as a flat map of paths, where publishing makes a snapshot of it
+    /// readable through the gateway
+    struct MockIpfsNode {
+        files: Mutex<HashMap<String, Vec<u8>>>,
+        published: Mutex<HashMap<String, Vec<u8>>>,
+        /// Publishing waits for a permit
+        publish_permits: Semaphore,
+    }
+
+    type Params = Query<HashMap<String, String>>;
+
+    fn mock_ipfs_node() -> (Url, Arc<MockIpfsNode>) {
+        let node = Arc::new(MockIpfsNode {
+            files: Default::default(),
+            published: Default::default(),
+            publish_permits: Semaphore::new(0),
+        });
+        let app =
+            Router::new()
+                .route(
+                    "/api/v0/key/list",
+                    post(|| async { Json(json!({ "Keys": [{ "Name": KEY, "Id": NAME }] })) }),
+                )
+                .route(
+                    "/api/v0/files/write",
+                    post(
+                        |State(node): State<Arc<MockIpfsNode>>,
+                         Query(params): Params,
+                         body: Bytes| async move {
+                            node.files
+                                .lock()
+                                .unwrap()
+                                .insert(params["arg"].clone(), multipart_file(&body));
+                        },
+                    ),
+                )
+                .route(
+                    "/api/v0/files/read",
+                    post(
+                        |State(node): State<Arc<MockIpfsNode>>, Query(params): Params| async move {
+                            match node.files.lock().unwrap().get(&params["arg"]) {
+                                Some(file) => (StatusCode::OK, file.clone()),
+                                None => (
+                                    StatusCode::INTERNAL_SERVER_ERROR,
+                                    b"file does not exist".to_vec(),
+                                ),
+                            }
+                        },
+                    ),
+                )
+                .route(
+                    "/api/v0/files/stat",
+                    post(|| async { Json(json!({ "Hash": "QmDirectory" })) }),
+                )
+                .route(
+                    "/api/v0/name/publish",
+                    post(|State(node): State<Arc<MockIpfsNode>>| async move {
+                        node.publish_permits.acquire().await.unwrap().forget();
+                        let files = node.files.lock().unwrap().clone();
+                        *node.published.lock().unwrap() = files;
+                        Json(json!({ "Name": NAME, "Value": "/ipfs/QmDirectory" }))
+                    }),
+                )
+                .route(
+                    "/ipns/:name/:file",
+                    get(
+                        |State(node): State<Arc<MockIpfsNode>>,
+                         Path((name, file)): Path<(String, String)>| async move {
+                            let path = format!("{MFS_ROOT}/{KEY}/{file}");
+                            match node.published.lock().unwrap().get(&path) {
+                                Some(file) if name == NAME => (StatusCode::OK, file.clone()),
+                                _ => (StatusCode::NOT_FOUND, vec![]),
+                            }
+                        },
+                    ),
+                )
+                .with_state(node.clone());
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr: SocketAddr = server.local_addr();
+        tokio::spawn(server);
+        (format!("http://{addr}/").parse().unwrap(), node)
+    }
+
+    /// The content of the single file of a multipart body
+    fn multipart_file(body: &[u8]) -> Vec<u8> {
+        let find = |needle: &[u8], from: usize| {
+            body[from..]
+                .windows(needle.len())
+                .position(|window| window == needle)
+                .map(|position| from + position)
+                .unwrap()
+        };
+        let start = find(b"\r\n\r\n", 0) + 4;
+        let end = find(b"\r\n--", start);
+        body[start..end].to_vec()
+    }
+
+    fn checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::repeat_byte(1),
+                    mailbox_domain: 1,
+                    root: H256::repeat_byte(3),
+                    index,
+                },
+                message_id: H256::repeat_byte(2),
+            },
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writer_reads_its_own_writes() {
+        let (url, _) = mock_ipfs_node();
+        let writer = IpfsStorage::new(url.clone(), None, Some(url), Some(KEY.to_owned()), None)
+            .await
+            .unwrap();
+        assert_eq!(writer.announcement_location(), format!("ipns://{NAME}"));
+        assert_eq!(writer.latest_index().await.unwrap(), None);
+
+        writer.write_checkpoint(&checkpoint(5)).await.unwrap();
+        writer.write_latest_index(5).await.unwrap();
+        assert_eq!(writer.latest_index().await.unwrap(), Some(5));
+        assert_eq!(
+            writer.fetch_checkpoint(5).await.unwrap(),
+            Some(checkpoint(5))
+        );
+        assert_eq!(writer.fetch_checkpoint(6).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_readers_see_checkpoints_once_published() {
+        let (url, node) = mock_ipfs_node();
+        let writer = IpfsStorage::new(
+            url.clone(),
+            None,
+            Some(url.clone()),
+            Some(KEY.to_owned()),
+            None,
+        )
+        .await
+        .unwrap();
+        let reader = IpfsStorage::new(url, Some(NAME.to_owned()), None, None, None)
+            .await
+            .unwrap();
+        assert!(reader.write_latest_index(5).await.is_err());
+
+        writer.write_checkpoint(&checkpoint(5)).await.unwrap();
+        // Doesn't wait for the publish, which is held up
+        timeout(Duration::from_secs(1), writer.write_latest_index(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reader.latest_index().await.unwrap(), None);
+
+        node.publish_permits.add_permits(1);
+        let published = async {
+            while reader.latest_index().await.unwrap().is_none() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        };
+        timeout(Duration::from_secs(5), published).await.unwrap();
+        assert_eq!(reader.latest_index().await.unwrap(), Some(5));
+        assert_eq!(
+            reader.fetch_checkpoint(5).await.unwrap(),
+            Some(checkpoint(5))
+        );
+    }
+}
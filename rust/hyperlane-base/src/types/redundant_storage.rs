@@ -0,0 +1,369 @@
+use std::{collections::BTreeMap, fmt, mem, sync::Mutex};
+
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use futures_util::future::join_all;
+use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
+use tracing::warn;
+
+use crate::CheckpointSyncer;
+
+/// Maximum number of checkpoints kept in memory per backend while it is
+/// failing. Beyond this the oldest are dropped and have to be backfilled by
+/// hand once the backend recovers.
+const MAX_BACKLOG_LEN: usize = 100_000;
+
+/// Writes that failed on a backend, retried before its next write.
+#[derive(Debug, Default)]
+struct Backlog {
+    checkpoints: BTreeMap<u32, SignedCheckpointWithMessageId>,
+    latest_index: Option<u32>,
+    announcement: Option<SignedAnnouncement>,
+}
+
+impl Backlog {
+    fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty() && self.latest_index.is_none() && self.announcement.is_none()
+    }
+}
+
+/// One of the checkpoint syncers written to, with the writes it missed.
+struct Backend {
+    syncer: Box<dyn CheckpointSyncer>,
+    backlog: Mutex<Backlog>,
+}
+
+impl Backend {
+    fn backlog(&self) -> std::sync::MutexGuard<'_, Backlog> {
+        self.backlog.lock().unwrap()
+    }
+
+    /// Retry the writes that previously failed, in order, stopping at the
+    /// first failure as the backend is most likely still unavailable.
+    async fn flush(&self) -> Result<()> {
+        let mut backlog = mem::take(&mut *self.backlog());
+        if backlog.is_empty() {
+            return Ok(());
+        }
+        let result = async {
+            while let Some((index, checkpoint)) = backlog.checkpoints.pop_first() {
+                if let Err(err) = self.syncer.write_checkpoint(&checkpoint).await {
+                    backlog.checkpoints.insert(index, checkpoint);
+                    return Err(err);
+                }
+            }
+            if let Some(index) = backlog.latest_index {
+                self.syncer.write_latest_index(index).await?;
+                backlog.latest_index = None;
+            }
+            if let Some(announcement) = &backlog.announcement {
+                self.syncer.write_announcement(announcement).await?;
+                backlog.announcement = None;
+            }
+            Ok(())
+        }
+        .await;
+        // Writes that failed while flushing were added to the backlog
+        // concurrently, merge them back in.
+        let mut current = self.backlog();
+        current.checkpoints.append(&mut backlog.checkpoints);
+        current.latest_index = current.latest_index.max(backlog.latest_index);
+        current.announcement = current.announcement.take().or(backlog.announcement);
+        result
+    }
+
+    async fn write_checkpoint(&self, checkpoint: &SignedCheckpointWithMessageId) -> Result<()> {
+        let result = match self.flush().await {
+            Ok(()) => self.syncer.write_checkpoint(checkpoint).await,
+            Err(err) => Err(err),
+        };
+        if result.is_err() {
+            let mut backlog = self.backlog();
+            backlog
+                .checkpoints
+                .insert(checkpoint.value.index, checkpoint.clone());
+            if backlog.checkpoints.len() > MAX_BACKLOG_LEN {
+                let dropped = backlog.checkpoints.pop_first();
+                warn!(
+                    syncer = ?self.syncer,
+                    dropped_index = ?dropped.map(|(index, _)| index),
+                    "Checkpoint backlog full, dropping the oldest checkpoint"
+                );
+            }
+        }
+        result
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let result = match self.flush().await {
+            Ok(()) => self.syncer.write_latest_index(index).await,
+            Err(err) => Err(err),
+        };
+        if result.is_err() {
+            let mut backlog = self.backlog();
+            backlog.latest_index = backlog.latest_index.max(Some(index));
+        }
+        result
+    }
+
+    async fn write_announcement(&self, announcement: &SignedAnnouncement) -> Result<()> {
+        let result = match self.flush().await {
+            Ok(()) => self.syncer.write_announcement(announcement).await,
+            Err(err) => Err(err),
+        };
+        if result.is_err() {
+            self.backlog().announcement = Some(announcement.clone());
+        }
+        result
+    }
+}
+
+/// Writes checkpoints to several checkpoint syncers so that an outage of any
+/// one of them doesn't take the validator's signatures offline.
+///
+/// The first syncer is the primary one: it is read from and its location is
+/// the one announced. The others are mirrors. A write succeeds as long as one
+/// of the syncers accepted it, and is retried on the others before their next
+/// write.
+pub struct RedundantCheckpointSyncer {
+    backends: Vec<Backend>,
+}
+
+impl fmt::Debug for RedundantCheckpointSyncer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.backends.iter().map(|backend| &backend.syncer))
+            .finish()
+    }
+}
+
+impl RedundantCheckpointSyncer {
+    /// Create a new redundant checkpoint syncer from a primary syncer and its
+    /// mirrors.
+    pub fn new(
+        primary: Box<dyn CheckpointSyncer>,
+        mirrors: impl IntoIterator<Item = Box<dyn CheckpointSyncer>>,
+    ) -> Self {
+        let backends = std::iter::once(primary)
+            .chain(mirrors)
+            .map(|syncer| Backend {
+                syncer,
+                backlog: Default::default(),
+            })
+            .collect();
+        Self { backends }
+    }
+
+    fn primary(&self) -> &dyn CheckpointSyncer {
+        self.backends[0].syncer.as_ref()
+    }
+
+    /// Succeeds if any backend succeeded, logging the failures.
+    fn any_ok(&self, results: Vec<Result<()>>, what: &str) -> Result<()> {
+        let mut succeeded = false;
+        for (backend, result) in self.backends.iter().zip(results) {
+            match result {
+                Ok(()) => succeeded = true,
+                Err(err) => {
+                    warn!(syncer = ?backend.syncer, ?err, "Failed to write {what}, will retry")
+                }
+            }
+        }
+        if succeeded {
+            Ok(())
+        } else {
+            Err(eyre!("Failed to write {what} to any checkpoint syncer"))
+        }
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for RedundantCheckpointSyncer {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        // Fall back to the mirrors while the primary is unavailable
+        let mut last_err = None;
+        for backend in &self.backends {
+            match backend.syncer.latest_index().await {
+                Ok(index) => return Ok(index),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| eyre!("No checkpoint syncers")))
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let results = join_all(
+            self.backends
+                .iter()
+                .map(|backend| backend.write_latest_index(index)),
+        )
+        .await;
+        self.any_ok(results, "latest index")
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let mut last_err = None;
+        for backend in &self.backends {
+            match backend.syncer.fetch_checkpoint(index).await {
+                Ok(checkpoint) => return Ok(checkpoint),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| eyre!("No checkpoint syncers")))
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let results = join_all(
+            self.backends
+                .iter()
+                .map(|backend| backend.write_checkpoint(signed_checkpoint)),
+        )
+        .await;
+        self.any_ok(results, "checkpoint")
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let results = join_all(
+            self.backends
+                .iter()
+                .map(|backend| backend.write_announcement(signed_announcement)),
+        )
+        .await;
+        self.any_ok(results, "announcement")
+    }
+
+    fn announcement_location(&self) -> String {
+        self.primary().announcement_location()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use eyre::bail;
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, H256, U256};
+
+    use super::*;
+    use crate::LocalStorage;
+
+    /// A syncer that fails every request while `down` is set
+    #[derive(Debug)]
+    struct FlakySyncer {
+        inner: LocalStorage,
+        down: Arc<AtomicBool>,
+    }
+
+    impl FlakySyncer {
+        fn check(&self) -> Result<()> {
+            if self.down.load(Ordering::SeqCst) {
+                bail!("syncer is down");
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl CheckpointSyncer for FlakySyncer {
+        async fn latest_index(&self) -> Result<Option<u32>> {
+            self.check()?;
+            self.inner.latest_index().await
+        }
+        async fn write_latest_index(&self, index: u32) -> Result<()> {
+            self.check()?;
+            self.inner.write_latest_index(index).await
+        }
+        async fn fetch_checkpoint(
+            &self,
+            index: u32,
+        ) -> Result<Option<SignedCheckpointWithMessageId>> {
+            self.check()?;
+            self.inner.fetch_checkpoint(index).await
+        }
+        async fn write_checkpoint(
+            &self,
+            signed_checkpoint: &SignedCheckpointWithMessageId,
+        ) -> Result<()> {
+            self.check()?;
+            self.inner.write_checkpoint(signed_checkpoint).await
+        }
+        async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+            self.check()?;
+            self.inner.write_announcement(signed_announcement).await
+        }
+        fn announcement_location(&self) -> String {
+            self.inner.announcement_location()
+        }
+    }
+
+    fn signed_checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::repeat_byte(1),
+                    mailbox_domain: 1,
+                    root: H256::repeat_byte(index as u8),
+                    index,
+                },
+                message_id: H256::repeat_byte(2),
+            },
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mirror_catches_up_after_outage() {
+        let primary_dir = tempfile::tempdir().unwrap();
+        let mirror_dir = tempfile::tempdir().unwrap();
+        let primary = LocalStorage::new(primary_dir.path().into(), None).unwrap();
+        let mirror = LocalStorage::new(mirror_dir.path().into(), None).unwrap();
+        let down = Arc::new(AtomicBool::new(true));
+        let syncer = RedundantCheckpointSyncer::new(
+            Box::new(primary.clone()),
+            [Box::new(FlakySyncer {
+                inner: mirror.clone(),
+                down: down.clone(),
+            }) as Box<dyn CheckpointSyncer>],
+        );
+
+        // Writes succeed while the mirror is down
+        syncer
+            .write_checkpoint(&signed_checkpoint(0))
+            .await
+            .unwrap();
+        syncer
+            .write_checkpoint(&signed_checkpoint(1))
+            .await
+            .unwrap();
+        syncer.write_latest_index(1).await.unwrap();
+        assert_eq!(mirror.fetch_checkpoint(0).await.unwrap(), None);
+
+        // and the mirror gets the missed writes once it is back
+        down.store(false, Ordering::SeqCst);
+        syncer
+            .write_checkpoint(&signed_checkpoint(2))
+            .await
+            .unwrap();
+        for index in 0..=2 {
+            assert_eq!(
+                mirror.fetch_checkpoint(index).await.unwrap(),
+                Some(signed_checkpoint(index))
+            );
+        }
+        assert_eq!(mirror.latest_index().await.unwrap(), Some(1));
+        assert_eq!(
+            syncer.announcement_location(),
+            primary.announcement_location()
+        );
+    }
+}
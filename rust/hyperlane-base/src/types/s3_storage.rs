@@ -1,8 +1,15 @@
-use std::{fmt, sync::OnceLock, time::Duration};
+use std::{
+    collections::HashSet,
+    fmt,
+    io::{Read, Write},
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use derive_new::new;
-use eyre::{bail, Result};
+use eyre::{bail, eyre, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use futures_util::TryStreamExt;
 use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
 use prometheus::IntGauge;
@@ -10,8 +17,12 @@ use rusoto_core::{
     credential::{Anonymous, AwsCredentials, StaticProvider},
     Region, RusotoError,
 };
-use rusoto_s3::{GetObjectError, GetObjectRequest, PutObjectRequest, S3Client, S3};
-use tokio::time::timeout;
+use rusoto_s3::{
+    Delete, DeleteObjectsRequest, GetObjectError, GetObjectRequest, HeadObjectError,
+    HeadObjectRequest, ObjectIdentifier, PutObjectRequest, S3Client, S3,
+};
+use tokio::{sync::Mutex as AsyncMutex, time::timeout};
+use tracing::{info, warn};
 
 use crate::types::utils;
 use crate::{settings::aws_credentials::AwsChainCredentialsProvider, CheckpointSyncer};
@@ -21,6 +32,27 @@ use crate::{settings::aws_credentials::AwsChainCredentialsProvider, CheckpointSy
 /// See https://github.com/rusoto/rusoto/issues/1795.
 const S3_REQUEST_TIMEOUT_SECONDS: u64 = 30;
 
+/// Number of checkpoints in each batch object.
+pub const CHECKPOINT_BATCH_SIZE: u32 = 1000;
+
+/// Maximum number of batches whose single checkpoints are pruned per write
+/// of the latest index, so that catching up on a large bucket is spread over
+/// many writes instead of holding up one.
+const MAX_PRUNED_BATCHES_PER_WRITE: u32 = 10;
+
+/// Retention of the checkpoints in a bucket. Once a checkpoint is in a batch,
+/// its single object is only needed by readers that don't know about batches
+/// yet, so all but the latest can be deleted.
+#[derive(Debug, Clone, Default)]
+pub struct CheckpointRetentionConf {
+    /// Number of latest checkpoints kept as single objects. Older ones are
+    /// deleted once their batch is written. Never deleted if not set.
+    pub keep_latest: Option<u32>,
+    /// Storage class batches are written with, e.g. `STANDARD_IA`, as they
+    /// are only read when backfilling. The bucket's default if not set.
+    pub batch_storage_class: Option<String>,
+}
+
 #[derive(Clone, new)]
 /// Type for reading/writing to S3
 pub struct S3Storage {
@@ -30,12 +62,28 @@ pub struct S3Storage {
     folder: Option<String>,
     /// The region of the bucket.
     region: Region,
+    /// Whether to also write checkpoints in gzipped batches of
+    /// `CHECKPOINT_BATCH_SIZE`. Readers use batches whenever they exist.
+    batch_checkpoints: bool,
+    /// How the checkpoints in batches are retained.
+    retention: CheckpointRetentionConf,
     /// A client with AWS credentials.
     #[new(default)]
     authenticated_client: OnceLock<S3Client>,
     /// A client without credentials for anonymous requests.
     #[new(default)]
     anonymous_client: OnceLock<S3Client>,
+    /// Checkpoint batches seen by a reader.
+    #[new(default)]
+    batch_cache: Arc<Mutex<CheckpointBatchCache>>,
+    /// Index below which single checkpoints were pruned by this writer, once
+    /// read from the bucket.
+    #[new(default)]
+    pruned_below: Arc<AsyncMutex<Option<u32>>>,
+    /// Latest index up to which this writer went through the completed
+    /// batches, once read from the bucket.
+    #[new(default)]
+    batched_through: Arc<AsyncMutex<Option<u32>>>,
     /// The latest seen signed checkpoint index.
     latest_index: Option<IntGauge>,
 }
@@ -50,13 +98,83 @@ impl fmt::Debug for S3Storage {
     }
 }
 
+/// Checkpoint batches seen by a reader, so a backfilling relayer fetches
+/// each batch once instead of every checkpoint in it.
+#[derive(Debug, Default)]
+struct CheckpointBatchCache {
+    /// The most recently fetched batch and the index of its first checkpoint.
+    batch: Option<(u32, Vec<SignedCheckpointWithMessageId>)>,
+    /// Batches that don't exist even though the latest index is past them,
+    /// e.g. because they predate the validator enabling batching.
+    missing: HashSet<u32>,
+    /// The highest latest index read from the bucket. Batches are written
+    /// before the latest index, so only batches ending at or below it can
+    /// exist.
+    latest_index: Option<u32>,
+}
+
+/// First index of the batch containing `index`
+fn batch_start(index: u32) -> u32 {
+    index - index % CHECKPOINT_BATCH_SIZE
+}
+
+/// Last index of the batch starting at `start`
+fn batch_end(start: u32) -> u32 {
+    start + (CHECKPOINT_BATCH_SIZE - 1)
+}
+
+/// First indices of the batches that were completed by moving the latest
+/// index from `previous` to `latest`.
+fn completed_batches(previous: Option<u32>, latest: u32) -> impl Iterator<Item = u32> {
+    (previous.map_or(0, batch_start)..=latest)
+        .step_by(CHECKPOINT_BATCH_SIZE as usize)
+        .filter(move |start| {
+            let end = batch_end(*start);
+            end <= latest && previous.map_or(true, |previous| end > previous)
+        })
+}
+
+/// First index of the single checkpoints kept when the latest index is
+/// `latest`: the first index of the batch of the oldest of the `keep_latest`
+/// latest checkpoints.
+fn prune_below(latest: u32, keep_latest: u32) -> u32 {
+    latest
+        .saturating_add(1)
+        .checked_sub(keep_latest)
+        .map_or(0, batch_start)
+}
+
+fn encode_batch(checkpoints: &[SignedCheckpointWithMessageId]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&serde_json::to_vec(checkpoints)?)?;
+    Ok(encoder.finish()?)
+}
+
+fn decode_batch(data: &[u8]) -> Result<Vec<SignedCheckpointWithMessageId>> {
+    let mut json = vec![];
+    GzDecoder::new(data).read_to_end(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
 impl S3Storage {
     async fn write_to_bucket(&self, key: String, body: &str) -> Result<()> {
+        self.write_bytes_to_bucket(key, Vec::from(body), "application/json", None)
+            .await
+    }
+
+    async fn write_bytes_to_bucket(
+        &self,
+        key: String,
+        body: Vec<u8>,
+        content_type: &str,
+        storage_class: Option<String>,
+    ) -> Result<()> {
         let req = PutObjectRequest {
             key: self.get_composite_key(key),
             bucket: self.bucket.clone(),
-            body: Some(Vec::from(body).into()),
-            content_type: Some("application/json".to_owned()),
+            body: Some(body.into()),
+            content_type: Some(content_type.to_owned()),
+            storage_class,
             ..Default::default()
         };
         timeout(
@@ -90,6 +208,58 @@ impl S3Storage {
         }
     }
 
+    async fn object_exists(&self, key: String) -> Result<bool> {
+        let req = HeadObjectRequest {
+            key: self.get_composite_key(key),
+            bucket: self.bucket.clone(),
+            ..Default::default()
+        };
+        let head_object_result = timeout(
+            Duration::from_secs(S3_REQUEST_TIMEOUT_SECONDS),
+            self.authenticated_client().head_object(req),
+        )
+        .await?;
+
+        match head_object_result {
+            Ok(_) => Ok(true),
+            // HEAD responses have no body, so a missing key is usually only
+            // reported by the status
+            Err(RusotoError::Service(HeadObjectError::NoSuchKey(_))) => Ok(false),
+            Err(RusotoError::Unknown(res)) if res.status.as_u16() == 404 => Ok(false),
+            Err(e) => bail!(e),
+        }
+    }
+
+    /// Delete up to 1000 objects at once.
+    async fn delete_from_bucket(&self, keys: impl Iterator<Item = String>) -> Result<()> {
+        let req = DeleteObjectsRequest {
+            bucket: self.bucket.clone(),
+            delete: Delete {
+                objects: keys
+                    .map(|key| ObjectIdentifier {
+                        key: self.get_composite_key(key),
+                        version_id: None,
+                    })
+                    .collect(),
+                quiet: Some(true),
+            },
+            ..Default::default()
+        };
+        let output = timeout(
+            Duration::from_secs(S3_REQUEST_TIMEOUT_SECONDS),
+            self.authenticated_client().delete_objects(req),
+        )
+        .await??;
+        match output.errors.unwrap_or_default().first() {
+            Some(error) => Err(eyre!(
+                "Failed to delete `{}`: {}",
+                error.key.as_deref().unwrap_or_default(),
+                error.message.as_deref().unwrap_or_default()
+            )),
+            None => Ok(()),
+        }
+    }
+
     /// Gets an authenticated S3Client, creating it if it doesn't already exist.
     fn authenticated_client(&self) -> &S3Client {
         self.authenticated_client.get_or_init(|| {
@@ -132,10 +302,166 @@ impl S3Storage {
         format!("checkpoint_{index}_with_id.json")
     }
 
+    fn checkpoint_batch_key(start: u32) -> String {
+        format!("checkpoint_batch_{start}_with_id.json.gz")
+    }
+
+    async fn fetch_single_checkpoint(
+        &self,
+        index: u32,
+    ) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.anonymously_read_from_bucket(S3Storage::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Fetch a checkpoint from the batch containing it, if that batch exists.
+    async fn fetch_checkpoint_from_batch(
+        &self,
+        index: u32,
+    ) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let start = batch_start(index);
+        let from_batch = |checkpoints: &[SignedCheckpointWithMessageId]| {
+            checkpoints
+                .get((index - start) as usize)
+                .filter(|checkpoint| checkpoint.value.index == index)
+                .cloned()
+        };
+        {
+            let cache = self.batch_cache.lock().unwrap();
+            if let Some((cached_start, checkpoints)) = &cache.batch {
+                if *cached_start == start {
+                    return Ok(from_batch(checkpoints));
+                }
+            }
+            let written = cache
+                .latest_index
+                .map_or(false, |latest| batch_end(start) <= latest);
+            if !written || cache.missing.contains(&start) {
+                return Ok(None);
+            }
+        }
+
+        let data = self
+            .anonymously_read_from_bucket(S3Storage::checkpoint_batch_key(start))
+            .await?;
+        let mut cache = self.batch_cache.lock().unwrap();
+        match data {
+            Some(data) => {
+                let checkpoints = decode_batch(&data)?;
+                let checkpoint = from_batch(&checkpoints);
+                cache.batch = Some((start, checkpoints));
+                Ok(checkpoint)
+            }
+            None => {
+                cache.missing.insert(start);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Write a batch object for every batch completed by moving the latest
+    /// index to `latest` since the last call, or since the latest index in
+    /// the bucket on the first call.
+    ///
+    /// Batches missing a checkpoint are skipped rather than retried, readers
+    /// fetch their single checkpoints instead.
+    async fn write_completed_batches(&self, latest: u32) -> Result<()> {
+        let mut batched_through = self.batched_through.lock().await;
+        let previous = match *batched_through {
+            Some(index) => Some(index),
+            None => self.latest_index().await?,
+        };
+        for start in completed_batches(previous, latest) {
+            if !self.write_batch(start).await? {
+                warn!(
+                    start,
+                    end = batch_end(start),
+                    "Checkpoints of the batch are missing, skipping it"
+                );
+            }
+        }
+        *batched_through = previous.max(Some(latest));
+        Ok(())
+    }
+
+    /// Write the batch starting at `start` from its single checkpoints.
+    /// Returns false without writing it if one of them is missing.
+    async fn write_batch(&self, start: u32) -> Result<bool> {
+        let mut checkpoints = Vec::with_capacity(CHECKPOINT_BATCH_SIZE as usize);
+        for index in start..=batch_end(start) {
+            let Some(checkpoint) = self.fetch_single_checkpoint(index).await? else {
+                return Ok(false);
+            };
+            checkpoints.push(checkpoint);
+        }
+        self.write_bytes_to_bucket(
+            S3Storage::checkpoint_batch_key(start),
+            encode_batch(&checkpoints)?,
+            "application/gzip",
+            self.retention.batch_storage_class.clone(),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Delete the single checkpoints of the batches older than the
+    /// `keep_latest` latest checkpoints, writing any of their batches that
+    /// are missing, e.g. because they predate batching, first. The progress
+    /// is kept in the bucket so that restarts carry on where they left off.
+    async fn prune_single_checkpoints(&self, latest: u32, keep_latest: u32) -> Result<()> {
+        let mut pruned_below = self.pruned_below.lock().await;
+        let from = match *pruned_below {
+            Some(index) => index,
+            None => self
+                .anonymously_read_from_bucket(S3Storage::pruned_below_key())
+                .await?
+                .map(|data| serde_json::from_slice(&data))
+                .transpose()?
+                .unwrap_or(0),
+        };
+        *pruned_below = Some(from);
+        let to = prune_below(latest, keep_latest);
+
+        for start in (from..to)
+            .step_by(CHECKPOINT_BATCH_SIZE as usize)
+            .take(MAX_PRUNED_BATCHES_PER_WRITE as usize)
+        {
+            if !self
+                .object_exists(S3Storage::checkpoint_batch_key(start))
+                .await?
+                && !self.write_batch(start).await?
+            {
+                bail!("Checkpoints of the batch starting at {start} are missing, can't prune it");
+            }
+            self.delete_from_bucket((start..=batch_end(start)).map(S3Storage::checkpoint_key))
+                .await?;
+            let next = batch_end(start) + 1;
+            self.write_to_bucket(
+                S3Storage::pruned_below_key(),
+                &serde_json::to_string(&next)?,
+            )
+            .await?;
+            *pruned_below = Some(next);
+            info!(
+                from = start,
+                to = batch_end(start),
+                "Pruned single checkpoints kept in a batch"
+            );
+        }
+        Ok(())
+    }
+
     fn latest_index_key() -> String {
         "checkpoint_latest_index.json".to_owned()
     }
 
+    fn pruned_below_key() -> String {
+        "checkpoint_pruned_below.json".to_owned()
+    }
+
     fn announcement_key() -> String {
         "announcement.json".to_owned()
     }
@@ -155,24 +481,40 @@ impl CheckpointSyncer for S3Storage {
             if let Some(gauge) = &self.latest_index {
                 gauge.set(latest_index as i64);
             }
+            let mut cache = self.batch_cache.lock().unwrap();
+            cache.latest_index = cache.latest_index.max(Some(latest_index));
         }
 
         ret
     }
 
     async fn write_latest_index(&self, index: u32) -> Result<()> {
+        // Batches have to exist before readers see the latest index past them
+        if self.batch_checkpoints {
+            self.write_completed_batches(index).await?;
+        }
         let serialized_index = serde_json::to_string(&index)?;
         self.write_to_bucket(S3Storage::latest_index_key(), &serialized_index)
             .await?;
+        // Pruning is housekeeping, a failure mustn't hold up the validator
+        if let Some(keep_latest) = self.retention.keep_latest {
+            if let Err(err) = self.prune_single_checkpoints(index, keep_latest).await {
+                warn!(?err, "Failed to prune single checkpoints, will retry");
+            }
+        }
         Ok(())
     }
 
     async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
-        self.anonymously_read_from_bucket(S3Storage::checkpoint_key(index))
-            .await?
-            .map(|data| serde_json::from_slice(&data))
-            .transpose()
-            .map_err(Into::into)
+        // Batches are only read up to the latest index, and pruned
+        // checkpoints are only in batches
+        if self.batch_cache.lock().unwrap().latest_index.is_none() {
+            self.latest_index().await?;
+        }
+        if let Some(checkpoint) = self.fetch_checkpoint_from_batch(index).await? {
+            return Ok(Some(checkpoint));
+        }
+        self.fetch_single_checkpoint(index).await
     }
 
     async fn write_checkpoint(
@@ -204,3 +546,59 @@ impl CheckpointSyncer for S3Storage {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, H256, U256};
+
+    use super::*;
+
+    #[test]
+    fn test_completed_batches() {
+        let batches = |previous, latest| completed_batches(previous, latest).collect::<Vec<_>>();
+        assert_eq!(batches(None, 998), Vec::<u32>::new());
+        assert_eq!(batches(None, 999), vec![0]);
+        assert_eq!(batches(None, 2500), vec![0, 1000]);
+        assert_eq!(batches(Some(999), 1998), Vec::<u32>::new());
+        assert_eq!(batches(Some(998), 1999), vec![0, 1000]);
+        assert_eq!(batches(Some(1500), 3999), vec![1000, 2000, 3000]);
+        assert_eq!(batches(Some(1999), 1999), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_prune_below() {
+        assert_eq!(prune_below(998, 0), 0);
+        assert_eq!(prune_below(999, 0), 1000);
+        assert_eq!(prune_below(2500, 100), 2000);
+        assert_eq!(prune_below(2500, 501), 2000);
+        assert_eq!(prune_below(2500, 502), 1000);
+        assert_eq!(prune_below(2999, 1000), 2000);
+        assert_eq!(prune_below(2999, 1001), 1000);
+        assert_eq!(prune_below(500, 1000), 0);
+    }
+
+    #[test]
+    fn test_batch_encoding_roundtrip() {
+        let checkpoints = (0..3)
+            .map(|index| SignedCheckpointWithMessageId {
+                value: CheckpointWithMessageId {
+                    checkpoint: Checkpoint {
+                        merkle_tree_hook_address: H256::repeat_byte(1),
+                        mailbox_domain: 1,
+                        root: H256::repeat_byte(index as u8),
+                        index,
+                    },
+                    message_id: H256::repeat_byte(2),
+                },
+                signature: Signature {
+                    r: U256::from(index),
+                    s: U256::one(),
+                    v: 27,
+                },
+            })
+            .collect::<Vec<_>>();
+
+        let decoded = decode_batch(&encode_batch(&checkpoints).unwrap()).unwrap();
+        assert_eq!(decoded, checkpoints);
+    }
+}
@@ -7,6 +7,8 @@ use crate::H256;
 pub mod incremental;
 /// A full incremental merkle. Suitable for running off-chain.
 pub mod merkle;
+/// An incremental merkle that proves leaves against any past root.
+pub mod prover;
 /// Utilities for manipulating proofs to reflect sparse merkle trees.
 pub mod sparse;
 
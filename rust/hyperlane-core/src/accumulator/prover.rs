@@ -1,16 +1,13 @@
-//! Prover process: generate proofs in the tree.
-//!
-//! Struct responsible for syncing Prover
+use tracing::instrument;
 
-use hyperlane_core::accumulator::{
+use crate::accumulator::{
     merkle::{merkle_root_from_branch, MerkleTree, MerkleTreeError, Proof},
     TREE_DEPTH,
 };
-use hyperlane_core::H256;
-use tracing::{error, instrument};
+use crate::H256;
 
-/// A depth-32 sparse Merkle tree capable of producing proofs for arbitrary
-/// elements.
+/// A depth-32 incremental Merkle tree that keeps every leaf, so that it can
+/// produce the root at any past index and proofs of any leaf against it.
 #[derive(Debug)]
 pub struct Prover {
     count: usize,
@@ -31,12 +28,19 @@ pub enum ProverError {
         /// The number of leaves
         count: usize,
     },
+    /// Requested proof of a leaf against a root from before it was ingested
+    #[error("Requested proof of leaf {leaf_index} against the earlier root at {root_index}")]
+    LeafAfterRoot {
+        /// The leaf index requested
+        leaf_index: usize,
+        /// The root index requested
+        root_index: usize,
+    },
     /// Bubbled up from underlying
     #[error(transparent)]
     MerkleTreeError(#[from] MerkleTreeError),
     /// Failed proof verification
     #[error("Proof verification failed. Root is {expected}, produced is {actual}")]
-    #[allow(dead_code)]
     VerificationFailed {
         /// The expected root (this tree's current root)
         expected: H256,
@@ -75,13 +79,28 @@ impl Prover {
         self.count
     }
 
-    /// Create a proof of a leaf in this tree.
+    /// Return the root hash of the tree right after the leaf at `index` was
+    /// ingested
+    pub fn root_at(&self, index: usize) -> Result<H256, ProverError> {
+        self.check_root_index(index)?;
+        Ok(self.tree.prove_against_previous(index, index).root())
+    }
+
+    /// Create a proof of the leaf at `leaf_index` against the root of the
+    /// tree right after the leaf at `root_index` was ingested.
     #[instrument(err, skip(self), fields(prover_msg_count=self.count()))]
-    pub fn prove_against_previous(
-        &self,
-        leaf_index: usize,
-        root_index: usize,
-    ) -> Result<Proof, ProverError> {
+    pub fn prove(&self, leaf_index: usize, root_index: usize) -> Result<Proof, ProverError> {
+        self.check_root_index(root_index)?;
+        if leaf_index > root_index {
+            return Err(ProverError::LeafAfterRoot {
+                leaf_index,
+                root_index,
+            });
+        }
+        Ok(self.tree.prove_against_previous(leaf_index, root_index))
+    }
+
+    fn check_root_index(&self, root_index: usize) -> Result<(), ProverError> {
         if root_index > u32::MAX as usize {
             return Err(ProverError::IndexTooHigh(root_index));
         }
@@ -92,11 +111,10 @@ impl Prover {
                 count,
             });
         }
-        Ok(self.tree.prove_against_previous(leaf_index, root_index))
+        Ok(())
     }
 
     /// Verify a proof against this tree's root.
-    #[allow(dead_code)]
     pub fn verify(&self, proof: &Proof) -> Result<(), ProverError> {
         let actual = merkle_root_from_branch(proof.leaf, &proof.path, TREE_DEPTH, proof.index);
         let expected = self.root();
@@ -141,9 +159,7 @@ impl std::iter::Extend<H256> for Prover {
 
 #[cfg(test)]
 mod test {
-    use ethers::utils::hash_message;
-
-    use hyperlane_core::test_utils;
+    use crate::{test_utils, traits::hashes::hash_message};
 
     use super::*;
 
@@ -157,7 +173,7 @@ mod test {
             // insert the leaves
             for leaf in test_case.leaves.iter() {
                 let hashed_leaf = hash_message(leaf);
-                tree.ingest(hashed_leaf.into()).unwrap();
+                tree.ingest(hashed_leaf).unwrap();
             }
 
             // assert the tree has the proper leaf count
@@ -169,7 +185,7 @@ mod test {
 
             for n in 0..test_case.leaves.len() {
                 // assert the tree generates the proper proof for this leaf
-                let proof = tree.prove_against_previous(n, tree.count() - 1).unwrap();
+                let proof = tree.prove(n, tree.count() - 1).unwrap();
                 assert_eq!(proof, test_case.proofs[n]);
 
                 // check that the tree can verify the proof for this leaf
@@ -177,4 +193,29 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn it_proves_against_past_roots() {
+        let leaves: Vec<H256> = (1..=5u8).map(H256::repeat_byte).collect();
+        let tree = Prover::from(&leaves);
+
+        for root_index in 0..leaves.len() {
+            let past = Prover::from(&leaves[..=root_index]);
+            assert_eq!(tree.root_at(root_index).unwrap(), past.root());
+            for (leaf_index, leaf) in leaves.iter().enumerate().take(root_index + 1) {
+                let proof = tree.prove(leaf_index, root_index).unwrap();
+                assert_eq!(proof.leaf, *leaf);
+                assert_eq!(proof.root(), past.root());
+            }
+        }
+
+        assert!(matches!(
+            tree.prove(3, 2),
+            Err(ProverError::LeafAfterRoot { .. })
+        ));
+        assert!(matches!(
+            tree.root_at(5),
+            Err(ProverError::ZeroProof { .. })
+        ));
+    }
 }
@@ -11,7 +11,7 @@ use num_traits::FromPrimitive;
 #[cfg(feature = "strum")]
 use strum::{EnumIter, EnumString, IntoStaticStr};
 
-use crate::{utils::many_to_one, HyperlaneProtocolError, IndexMode, H160, H256};
+use crate::{utils::many_to_one, AddressFormat, HyperlaneProtocolError, IndexMode, H256};
 
 #[derive(Debug, Clone)]
 pub struct Address(pub bytes::Bytes);
@@ -194,17 +194,21 @@ pub enum HyperlaneDomainProtocol {
     Sealevel,
     /// A Cosmos-based chain type which uses hyperlane-cosmos.
     Cosmos,
+    /// An Aptos-based chain type which uses hyperlane-aptos.
+    Aptos,
+    /// A Sui-based chain type which uses hyperlane-sui.
+    Sui,
+    /// A StarkNet-based chain type which uses hyperlane-starknet.
+    Starknet,
+    /// A TON-based chain type which uses hyperlane-ton.
+    Ton,
 }
 
 impl HyperlaneDomainProtocol {
+    /// Pretty print an address the way chains of this protocol do. Cosmos
+    /// addresses are printed as hex, as the bech32 prefix isn't known.
     pub fn fmt_address(&self, addr: H256) -> String {
-        use HyperlaneDomainProtocol::*;
-        match self {
-            Ethereum => format!("{:?}", H160::from(addr)),
-            Fuel => format!("{:?}", addr),
-            Sealevel => format!("{:?}", addr),
-            Cosmos => format!("{:?}", addr),
-        }
+        AddressFormat::for_protocol(*self, None).encode(&addr)
     }
 }
 
@@ -220,6 +224,9 @@ impl HyperlaneDomainProtocol {
 )]
 pub enum HyperlaneDomainTechnicalStack {
     ArbitrumNitro,
+    /// zkSync Era and its hyperchains, which need EIP-712 transactions and
+    /// their own fee estimation
+    ZkSync,
     #[default]
     Other,
 }
@@ -264,6 +271,18 @@ impl KnownHyperlaneDomain {
         })
     }
 
+    /// The human readable prefix of the bech32 addresses of a Cosmos chain
+    pub const fn bech32_prefix(self) -> Option<&'static str> {
+        use KnownHyperlaneDomain::*;
+
+        match self {
+            Neutron => Some("neutron"),
+            Osmosis | CosmosTest99990 | CosmosTest99991 => Some("osmo"),
+            Injective => Some("inj"),
+            _ => None,
+        }
+    }
+
     pub const fn domain_technical_stack(self) -> HyperlaneDomainTechnicalStack {
         use KnownHyperlaneDomain::*;
 
@@ -447,6 +466,13 @@ impl HyperlaneDomain {
         )
     }
 
+    pub const fn is_zksync(&self) -> bool {
+        matches!(
+            self.domain_technical_stack(),
+            HyperlaneDomainTechnicalStack::ZkSync
+        )
+    }
+
     pub const fn is_injective(&self) -> bool {
         matches!(self, Self::Known(KnownHyperlaneDomain::Injective))
     }
@@ -455,8 +481,8 @@ impl HyperlaneDomain {
         use HyperlaneDomainProtocol::*;
         let protocol = self.domain_protocol();
         many_to_one!(match protocol {
-            IndexMode::Block: [Ethereum, Cosmos],
-            IndexMode::Sequence : [Sealevel, Fuel],
+            IndexMode::Block: [Ethereum, Cosmos, Starknet, Ton],
+            IndexMode::Sequence : [Sealevel, Fuel, Aptos, Sui],
         })
     }
 }
@@ -2,6 +2,7 @@ use std::any::Any;
 use std::error::Error as StdError;
 use std::fmt::{Debug, Display, Formatter};
 use std::ops::Deref;
+use std::time::Duration;
 
 use bigdecimal::ParseBigDecimalError;
 use derive_new::new;
@@ -17,9 +18,16 @@ use crate::{Error as PrimitiveTypeError, HyperlaneSignerError, H256, U256};
 pub type ChainResult<T> = Result<T, ChainCommunicationError>;
 
 /// An "Any"-typed error.
-pub trait HyperlaneCustomError: StdError + Send + Sync + Any {}
+pub trait HyperlaneCustomError: StdError + Send + Sync + Any {
+    /// The error as `Any`, to downcast it to its concrete type
+    fn as_any(&self) -> &dyn Any;
+}
 
-impl<E: StdError + Send + Sync + Any> HyperlaneCustomError for E {}
+impl<E: StdError + Send + Sync + Any> HyperlaneCustomError for E {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
 
 /// Thin wrapper around a boxed HyperlaneCustomError; required to satisfy
 /// AsDynError implementations. Basically a trait-object adaptor.
@@ -69,9 +77,22 @@ pub enum ChainCommunicationError {
     /// An error with a contract call
     #[error(transparent)]
     ContractError(HyperlaneCustomErrorWrapper),
+    /// A simulated transaction reverted, with its revert data decoded
+    #[error("Transaction reverted with {reason}")]
+    SimulationReverted {
+        /// Name of the error, e.g. `Error` for revert strings, or its
+        /// selector if it isn't known
+        error: String,
+        /// The error with its decoded arguments
+        reason: String,
+    },
     /// A transaction was dropped from the mempool
     #[error("Transaction dropped from mempool {0:?}")]
     TransactionDropped(H256),
+    /// A stuck transaction was cancelled by replacing it with a no-op
+    /// transaction using the same nonce
+    #[error("Stuck transaction {0:?} was cancelled")]
+    TransactionCancelled(H256),
     /// Any other error; does not implement `From` to prevent
     /// conflicting/absorbing other errors.
     #[error(transparent)]
@@ -144,6 +165,10 @@ pub enum ChainCommunicationError {
     /// Rpc client error
     #[error(transparent)]
     RpcClientError(#[from] RpcClientError),
+    /// Calls to the chain fail fast after repeated failures, until the
+    /// circuit closes again
+    #[error("Circuit open after repeated failures, failing fast for another {0:?}")]
+    CircuitOpen(Duration),
     /// Tokio join error
     #[cfg(feature = "async")]
     #[error(transparent)]
@@ -257,4 +282,13 @@ pub enum HyperlaneProtocolError {
     /// Expected a gas limit and none was provided
     #[error("A gas limit was expected for `process` contract call")]
     ProcessGasLimitRequired,
+    /// Hook metadata was encoded with a variant that is not supported
+    #[error("Unsupported hook metadata variant ({0})")]
+    UnsupportedHookMetadataVariant(u16),
+    /// A dead-lettered message was stored with an unknown reason
+    #[error("Unknown dead letter reason ({0})")]
+    UnknownDeadLetterReason(u8),
+    /// A message was encoded with a version that is not supported
+    #[error("Unsupported message version ({0})")]
+    UnsupportedMessageVersion(u8),
 }
@@ -7,6 +7,9 @@ use crate::U256;
 const ETHEREUM_DECIMALS: u8 = 18;
 const COSMOS_DECIMALS: u8 = 6;
 const SOLANA_DECIMALS: u8 = 9;
+const APTOS_DECIMALS: u8 = 8;
+const SUI_DECIMALS: u8 = 9;
+const TON_DECIMALS: u8 = 9;
 
 /// Interval for querying the prometheus metrics endpoint.
 /// This should be whatever the prometheus scrape interval is
@@ -25,6 +28,9 @@ pub fn decimals_by_protocol(protocol: HyperlaneDomainProtocol) -> u8 {
     match protocol {
         HyperlaneDomainProtocol::Cosmos => COSMOS_DECIMALS,
         HyperlaneDomainProtocol::Sealevel => SOLANA_DECIMALS,
+        HyperlaneDomainProtocol::Aptos => APTOS_DECIMALS,
+        HyperlaneDomainProtocol::Sui => SUI_DECIMALS,
+        HyperlaneDomainProtocol::Ton => TON_DECIMALS,
         _ => ETHEREUM_DECIMALS,
     }
 }
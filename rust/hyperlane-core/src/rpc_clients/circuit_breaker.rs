@@ -0,0 +1,111 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tracing::{info, warn};
+
+/// When the calls to a chain fail fast instead of being made
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitBreakerConf {
+    /// Number of consecutive failed calls after which the circuit opens
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a single call is let through
+    /// to probe whether the chain recovered
+    pub cooldown: Duration,
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    /// Set while the circuit is open
+    open_until: Option<Instant>,
+}
+
+/// Fails the calls to a chain fast after repeated failures, so that the
+/// tasks depending on it back off instead of all waiting on a dead endpoint
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    conf: CircuitBreakerConf,
+    state: Mutex<CircuitState>,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker with a closed circuit
+    pub fn new(conf: CircuitBreakerConf) -> Self {
+        Self {
+            conf,
+            state: Mutex::new(CircuitState::default()),
+        }
+    }
+
+    /// Whether a call can be made. Returns how long the circuit stays open
+    /// otherwise. Once the cooldown is over a single call is let through,
+    /// the circuit staying open for the others until its outcome is recorded.
+    pub fn check(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        let Some(open_until) = state.open_until else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        if now < open_until {
+            return Err(open_until - now);
+        }
+        state.open_until = Some(now + self.conf.cooldown);
+        Ok(())
+    }
+
+    /// Record the outcome of a call
+    pub fn record(&self, success: bool) {
+        let mut state = self.state.lock().unwrap();
+        if success {
+            if state.open_until.take().is_some() {
+                info!("Closing circuit after a successful call");
+            }
+            state.consecutive_failures = 0;
+            return;
+        }
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.consecutive_failures >= self.conf.failure_threshold {
+            if state.consecutive_failures == self.conf.failure_threshold {
+                warn!(
+                    failures = state.consecutive_failures,
+                    cooldown = ?self.conf.cooldown,
+                    "Opening circuit after consecutive failed calls"
+                );
+            }
+            state.open_until = Some(Instant::now() + self.conf.cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_opens_then_probes_after_cooldown() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConf {
+            failure_threshold: 2,
+            cooldown: Duration::from_millis(20),
+        });
+        breaker.record(false);
+        assert!(breaker.check().is_ok());
+        breaker.record(false);
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        // A single probe is let through
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_err());
+        // and opens the circuit again when it fails
+        breaker.record(false);
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().is_ok());
+        breaker.record(true);
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_ok());
+    }
+}
@@ -11,11 +11,11 @@ use std::{
     time::{Duration, Instant},
 };
 use tokio;
-use tracing::{info, trace, warn_span};
+use tracing::{info, trace, warn, warn_span};
 
 use crate::ChainCommunicationError;
 
-use super::RpcClientError;
+use super::{HealthScorer, HealthScoringConf, RpcClientError};
 
 /// Read the current block number from a chain.
 #[async_trait]
@@ -64,6 +64,7 @@ pub struct FallbackProvider<T, B> {
     /// The sub-providers called by this provider
     pub inner: Arc<PrioritizedProviders<T>>,
     max_block_time: Duration,
+    health: Option<Arc<HealthScorer>>,
     _phantom: PhantomData<B>,
 }
 
@@ -72,6 +73,7 @@ impl<T, B> Clone for FallbackProvider<T, B> {
         Self {
             inner: self.inner.clone(),
             max_block_time: self.max_block_time,
+            health: self.health.clone(),
             _phantom: PhantomData,
         }
     }
@@ -129,10 +131,67 @@ where
         }
     }
 
-    /// Used to iterate the providers in a non-blocking way
+    /// Used to iterate the providers in a non-blocking way. With health
+    /// scoring, demoted providers come last.
     pub async fn take_priorities_snapshot(&self) -> Vec<PrioritizedProviderInner> {
         let read_lock = self.inner.priorities.read().await;
-        (*read_lock).clone()
+        let snapshot = (*read_lock).clone();
+        match &self.health {
+            Some(health) => {
+                let (demoted, healthy): (Vec<_>, Vec<_>) = snapshot
+                    .into_iter()
+                    .partition(|p| health.is_demoted(p.index));
+                healthy.into_iter().chain(demoted).collect()
+            }
+            None => snapshot,
+        }
+    }
+
+    /// Record the outcome of a request to a provider for health scoring.
+    /// Errors of the request itself, like reverts, should count as a success.
+    pub fn record_response(
+        &self,
+        priority: &PrioritizedProviderInner,
+        success: bool,
+        latency: Duration,
+    ) {
+        if let Some(health) = &self.health {
+            health.record_request(priority.index, success, latency);
+        }
+    }
+
+    /// With health scoring, sample the block height of every provider in
+    /// the background so their lag is scored without adding a request to
+    /// the ones made through this provider. Sampling stops once it's dropped.
+    pub fn spawn_block_height_sampler(&self)
+    where
+        T: Send + Sync + 'static,
+        B: 'static,
+    {
+        let Some(health) = &self.health else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("No runtime to sample the block heights of providers in, their lag isn't scored");
+            return;
+        };
+        let mut interval = tokio::time::interval(health.block_height_interval());
+        let inner = Arc::downgrade(&self.inner);
+        let health = Arc::downgrade(health);
+        runtime.spawn(async move {
+            loop {
+                interval.tick().await;
+                let (Some(inner), Some(health)) = (inner.upgrade(), health.upgrade()) else {
+                    return;
+                };
+                for (index, provider) in inner.providers.iter().enumerate() {
+                    let block_getter: B = provider.clone().into();
+                    if let Ok(block_height) = block_getter.get_block_number().await {
+                        health.record_block_height(index, block_height);
+                    }
+                }
+            }
+        });
     }
 
     /// De-prioritize a provider that has either timed out or returned a bad response
@@ -180,7 +239,9 @@ where
             let priorities_snapshot = self.take_priorities_snapshot().await;
             for (idx, priority) in priorities_snapshot.iter().enumerate() {
                 let provider = &self.inner.providers[priority.index];
+                let started = Instant::now();
                 let resp = f(provider.clone()).await;
+                self.record_response(priority, resp.is_ok(), started.elapsed());
                 self.handle_stalled_provider(priority, provider).await;
                 let _span =
                     warn_span!("FallbackProvider::call", fallback_count=%idx, provider_index=%priority.index, ?provider).entered();
@@ -206,6 +267,7 @@ where
 pub struct FallbackProviderBuilder<T, B> {
     providers: Vec<T>,
     max_block_time: Duration,
+    health_scoring: Option<HealthScoringConf>,
     _phantom: PhantomData<B>,
 }
 
@@ -214,6 +276,7 @@ impl<T, B> Default for FallbackProviderBuilder<T, B> {
         Self {
             providers: Vec::new(),
             max_block_time: MAX_BLOCK_TIME,
+            health_scoring: None,
             _phantom: PhantomData,
         }
     }
@@ -240,6 +303,13 @@ impl<T, B> FallbackProviderBuilder<T, B> {
         self
     }
 
+    /// Score the providers by their error rate, latency and block lag,
+    /// demoting unhealthy ones instead of only stalled ones.
+    pub fn with_health_scoring(mut self, conf: HealthScoringConf) -> Self {
+        self.health_scoring = Some(conf);
+        self
+    }
+
     /// Create a fallback provider.
     pub fn build(self) -> FallbackProvider<T, B> {
         let provider_count = self.providers.len();
@@ -255,6 +325,9 @@ impl<T, B> FallbackProviderBuilder<T, B> {
         FallbackProvider {
             inner: Arc::new(prioritized_providers),
             max_block_time: self.max_block_time,
+            health: self
+                .health_scoring
+                .map(|conf| Arc::new(HealthScorer::new(conf, provider_count))),
             _phantom: PhantomData,
         }
     }
@@ -332,3 +405,49 @@ pub mod test {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A provider reporting a fixed block height
+    #[derive(Debug, Clone)]
+    struct FixedHeight(u64);
+
+    #[async_trait]
+    impl BlockNumberGetter for FixedHeight {
+        async fn get_block_number(&self) -> Result<u64, ChainCommunicationError> {
+            Ok(self.0)
+        }
+    }
+
+    #[test]
+    fn test_samples_block_heights_in_background() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let provider = FallbackProvider::<FixedHeight, FixedHeight>::builder()
+                .add_providers([FixedHeight(100), FixedHeight(110)])
+                .with_health_scoring(HealthScoringConf {
+                    max_block_lag: 5,
+                    block_height_interval: Duration::from_millis(10),
+                    ..Default::default()
+                })
+                .build();
+            provider.spawn_block_height_sampler();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+
+            let health = provider.health.as_ref().unwrap();
+            assert!(health.is_demoted(0));
+            assert!(!health.is_demoted(1));
+            // The lagging provider is tried last
+            let snapshot = provider.take_priorities_snapshot().await;
+            assert_eq!(
+                snapshot.iter().map(|p| p.index).collect::<Vec<_>>(),
+                vec![1, 0]
+            );
+        });
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tracing::{info, warn};
+
+/// Thresholds beyond which a provider of a `FallbackProvider` is considered
+/// unhealthy and demoted to the back of the queue
+#[derive(Debug, Clone)]
+pub struct HealthScoringConf {
+    /// Number of most recent requests the error rate and latency are
+    /// computed over
+    pub window: usize,
+    /// Number of requests in the window below which the error rate and
+    /// latency are not considered yet
+    pub min_samples: usize,
+    /// Fraction of failed requests, between 0 and 1, above which a provider
+    /// is unhealthy
+    pub max_error_rate: f64,
+    /// Average request latency above which a provider is unhealthy
+    pub max_latency: Duration,
+    /// Number of blocks a provider can be behind the most advanced one
+    pub max_block_lag: u64,
+    /// How long a demoted provider stays at the back of the queue before it
+    /// is promoted back and scored afresh
+    pub probation: Duration,
+    /// How often the block height of every provider is sampled in the
+    /// background, to compute its lag
+    pub block_height_interval: Duration,
+}
+
+impl Default for HealthScoringConf {
+    fn default() -> Self {
+        Self {
+            window: 50,
+            min_samples: 10,
+            max_error_rate: 0.25,
+            max_latency: Duration::from_secs(5),
+            max_block_lag: 20,
+            probation: Duration::from_secs(5 * 60),
+            block_height_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProviderHealth {
+    /// Whether each recent request succeeded, and how long it took
+    requests: VecDeque<(bool, Duration)>,
+    /// The last block height seen and when it was queried
+    block_height: Option<(u64, Instant)>,
+    /// Set while the provider is demoted
+    demoted_until: Option<Instant>,
+}
+
+/// Scores the providers of a `FallbackProvider` by their error rate, latency
+/// and block lag, demoting the unhealthy ones until their probation is over.
+#[derive(Debug)]
+pub struct HealthScorer {
+    conf: HealthScoringConf,
+    providers: Mutex<Vec<ProviderHealth>>,
+}
+
+impl HealthScorer {
+    /// Create a scorer for `provider_count` providers
+    pub fn new(conf: HealthScoringConf, provider_count: usize) -> Self {
+        Self {
+            conf,
+            providers: Mutex::new(
+                (0..provider_count)
+                    .map(|_| ProviderHealth::default())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Record the outcome of a request to a provider
+    pub fn record_request(&self, index: usize, success: bool, latency: Duration) {
+        let mut providers = self.providers.lock().unwrap();
+        let requests = &mut providers[index].requests;
+        requests.push_back((success, latency));
+        while requests.len() > self.conf.window {
+            requests.pop_front();
+        }
+        self.evaluate(&mut providers, index);
+    }
+
+    /// Record the block height reported by a provider
+    pub fn record_block_height(&self, index: usize, block_height: u64) {
+        let mut providers = self.providers.lock().unwrap();
+        providers[index].block_height = Some((block_height, Instant::now()));
+        self.evaluate(&mut providers, index);
+    }
+
+    /// How often the block heights of the providers are to be sampled
+    pub fn block_height_interval(&self) -> Duration {
+        self.conf.block_height_interval
+    }
+
+    /// Whether a provider is demoted. Promotes it back if its probation is
+    /// over.
+    pub fn is_demoted(&self, index: usize) -> bool {
+        let mut providers = self.providers.lock().unwrap();
+        let health = &mut providers[index];
+        match health.demoted_until {
+            Some(until) if until > Instant::now() => true,
+            Some(_) => {
+                // Forget the history so the provider isn't demoted again
+                // straight away for the requests that got it demoted
+                *health = ProviderHealth::default();
+                info!(provider_index = index, "Promoting provider after probation");
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn evaluate(&self, providers: &mut [ProviderHealth], index: usize) {
+        if providers[index].demoted_until.is_some() {
+            return;
+        }
+        if let Some(reason) = self.unhealthy_reason(providers, index) {
+            warn!(
+                provider_index = index,
+                %reason,
+                probation = ?self.conf.probation,
+                "Demoting unhealthy provider"
+            );
+            providers[index].demoted_until = Some(Instant::now() + self.conf.probation);
+        }
+    }
+
+    fn unhealthy_reason(&self, providers: &[ProviderHealth], index: usize) -> Option<String> {
+        let health = &providers[index];
+        let samples = health.requests.len();
+        if samples > 0 && samples >= self.conf.min_samples {
+            let failures = health.requests.iter().filter(|(ok, _)| !ok).count();
+            let error_rate = failures as f64 / samples as f64;
+            if error_rate > self.conf.max_error_rate {
+                return Some(format!("error rate of {error_rate:.2}"));
+            }
+            let latency =
+                health.requests.iter().map(|(_, l)| *l).sum::<Duration>() / samples as u32;
+            if latency > self.conf.max_latency {
+                return Some(format!("average latency of {latency:?}"));
+            }
+        }
+        // Only compare recent heights, a provider whose height can't be
+        // sampled anymore keeps the height it had back then
+        let max_age = self.conf.block_height_interval * 2;
+        let recent_height = |p: &ProviderHealth| {
+            p.block_height
+                .filter(|(_, at)| at.elapsed() <= max_age)
+                .map(|(height, _)| height)
+        };
+        let height = recent_height(health)?;
+        let highest = providers.iter().filter_map(recent_height).max()?;
+        let lag = highest.saturating_sub(height);
+        (lag > self.conf.max_block_lag).then(|| format!("lagging {lag} blocks behind"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn conf() -> HealthScoringConf {
+        HealthScoringConf {
+            window: 4,
+            min_samples: 2,
+            max_error_rate: 0.5,
+            max_latency: Duration::from_millis(100),
+            max_block_lag: 5,
+            probation: Duration::from_millis(20),
+            block_height_interval: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_demotes_on_error_rate_and_promotes_after_probation() {
+        let scorer = HealthScorer::new(conf(), 2);
+        scorer.record_request(0, false, Duration::ZERO);
+        // Not enough samples yet
+        assert!(!scorer.is_demoted(0));
+        scorer.record_request(0, false, Duration::ZERO);
+        assert!(scorer.is_demoted(0));
+        assert!(!scorer.is_demoted(1));
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(!scorer.is_demoted(0));
+        // Scored afresh after probation
+        scorer.record_request(0, true, Duration::ZERO);
+        scorer.record_request(0, true, Duration::ZERO);
+        assert!(!scorer.is_demoted(0));
+    }
+
+    #[test]
+    fn test_demotes_on_latency() {
+        let scorer = HealthScorer::new(conf(), 1);
+        scorer.record_request(0, true, Duration::from_millis(50));
+        scorer.record_request(0, true, Duration::from_millis(50));
+        assert!(!scorer.is_demoted(0));
+        scorer.record_request(0, true, Duration::from_millis(500));
+        assert!(scorer.is_demoted(0));
+    }
+
+    #[test]
+    fn test_demotes_on_block_lag() {
+        let scorer = HealthScorer::new(conf(), 2);
+        scorer.record_block_height(0, 100);
+        scorer.record_block_height(1, 105);
+        assert!(!scorer.is_demoted(0));
+        scorer.record_block_height(1, 110);
+        // Only re-evaluated once its own height is recorded again
+        scorer.record_block_height(0, 100);
+        assert!(scorer.is_demoted(0));
+        assert!(!scorer.is_demoted(1));
+    }
+}
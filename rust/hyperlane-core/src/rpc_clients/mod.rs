@@ -1,14 +1,23 @@
+pub use self::circuit_breaker::*;
+
 pub use self::error::*;
 
+pub use self::health::*;
+
+pub use self::rate_limit::*;
+
 #[cfg(feature = "async")]
 pub use self::fallback::*;
 
 #[cfg(feature = "async")]
 pub use self::retry::*;
 
+mod circuit_breaker;
 mod error;
 #[cfg(feature = "async")]
 mod fallback;
+mod health;
+mod rate_limit;
 
 #[cfg(feature = "async")]
 mod retry;
@@ -0,0 +1,132 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock, Weak},
+    time::{Duration, Instant},
+};
+
+/// The buckets of the RPC urls in use, so that every client of a url shares
+/// its limit
+static SHARED_BUCKETS: OnceLock<Mutex<HashMap<String, Weak<TokenBucket>>>> = OnceLock::new();
+
+/// Client-side rate limit of the requests made to an RPC url
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConf {
+    /// Sustained number of requests per second
+    pub requests_per_second: f64,
+    /// Number of requests that can be made at once after a quiet period
+    pub burst: u32,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    /// Tokens left, negative once requests are queued waiting for tokens
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// A token bucket spacing out requests to stay within a `RateLimitConf`
+#[derive(Debug)]
+pub struct TokenBucket {
+    conf: RateLimitConf,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    /// Create a bucket that starts out full
+    pub fn new(conf: RateLimitConf) -> Self {
+        let tokens = conf.burst.max(1) as f64;
+        Self {
+            conf,
+            state: Mutex::new(BucketState {
+                tokens,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// The bucket of the RPC `url`, shared by every client of the url in the
+    /// process. A new bucket replaces the url's if its limit changed.
+    pub fn shared(url: &str, conf: RateLimitConf) -> Arc<Self> {
+        let mut buckets = SHARED_BUCKETS.get_or_init(Default::default).lock().unwrap();
+        if let Some(bucket) = buckets.get(url).and_then(Weak::upgrade) {
+            if bucket.conf == conf {
+                return bucket;
+            }
+        }
+        // Buckets of urls no longer in use are dropped along the way
+        buckets.retain(|_, bucket| bucket.strong_count() > 0);
+        let bucket = Arc::new(Self::new(conf));
+        buckets.insert(url.to_owned(), Arc::downgrade(&bucket));
+        bucket
+    }
+
+    /// Take a token for a request, returning how long to wait before making
+    /// it. Tokens are taken even when none are left, so that concurrent
+    /// requests are queued one after the other rather than all at once.
+    pub fn reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let refill = (now - state.refilled_at).as_secs_f64() * self.conf.requests_per_second;
+        state.tokens = (state.tokens + refill).min(self.conf.burst.max(1) as f64);
+        state.refilled_at = now;
+        state.tokens -= 1.;
+        if state.tokens >= 0. {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.conf.requests_per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bursts_then_spaces_out_requests() {
+        let bucket = TokenBucket::new(RateLimitConf {
+            requests_per_second: 10.,
+            burst: 2,
+        });
+        assert_eq!(bucket.reserve(), Duration::ZERO);
+        assert_eq!(bucket.reserve(), Duration::ZERO);
+        // Queued behind each other, 100ms apart
+        let first = bucket.reserve();
+        let second = bucket.reserve();
+        assert!(first > Duration::from_millis(90) && first <= Duration::from_millis(100));
+        assert!(second > Duration::from_millis(190) && second <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_shares_buckets_per_url() {
+        let conf = RateLimitConf {
+            requests_per_second: 10.,
+            burst: 1,
+        };
+        let bucket = TokenBucket::shared("http://rpc.test/a", conf.clone());
+        assert!(Arc::ptr_eq(
+            &bucket,
+            &TokenBucket::shared("http://rpc.test/a", conf.clone())
+        ));
+        assert!(!Arc::ptr_eq(
+            &bucket,
+            &TokenBucket::shared("http://rpc.test/b", conf.clone())
+        ));
+        // The limit of the url changed
+        let changed = TokenBucket::shared(
+            "http://rpc.test/a",
+            RateLimitConf {
+                burst: 2,
+                ..conf.clone()
+            },
+        );
+        assert!(!Arc::ptr_eq(&bucket, &changed));
+
+        // Once no client uses the url anymore its bucket starts out full again
+        changed.reserve();
+        changed.reserve();
+        drop((bucket, changed));
+        let bucket = TokenBucket::shared("http://rpc.test/a", conf);
+        assert_eq!(bucket.reserve(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,25 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::{ChainResult, HyperlaneContract, SignedCheckpointWithMessageId, TxOutcome, H256};
+
+/// Interface for the CheckpointAttestation chain contract, which stores the
+/// latest checkpoint signed by each validator. Allows abstraction over
+/// different chains
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait CheckpointAttestation: HyperlaneContract + Send + Sync + Debug {
+    /// Returns the index of the latest checkpoint of a merkle tree hook
+    /// attested by the validator, or None if it never attested one.
+    async fn latest_attested_index(
+        &self,
+        validator: H256,
+        origin: u32,
+        merkle_tree_hook: H256,
+    ) -> ChainResult<Option<u32>>;
+
+    /// Attest a signed checkpoint as the latest of its validator
+    async fn attest(&self, checkpoint: &SignedCheckpointWithMessageId) -> ChainResult<TxOutcome>;
+}
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, ops::RangeInclusive};
 
 use async_trait::async_trait;
 use auto_impl::auto_impl;
@@ -13,6 +13,13 @@ pub trait HyperlaneLogStore<T>: Send + Sync + Debug {
     /// Store a list of logs and their associated metadata
     /// Returns the number of elements that were stored.
     async fn store_logs(&self, logs: &[(Indexed<T>, LogMeta)]) -> Result<u32>;
+
+    /// Record that every log of `range` was stored, for stores that check
+    /// the ranges indexed for holes. `range` is a range of blocks, or of
+    /// sequences if the logs are indexed by sequence.
+    async fn store_indexed_range(&self, _range: RangeInclusive<u32>) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// A sequence is a monotonically increasing number that is incremented every time a message ID is indexed.
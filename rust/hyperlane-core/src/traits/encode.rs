@@ -1,7 +1,8 @@
 use std::io::{Error, ErrorKind};
 
 use crate::{
-    GasPaymentKey, HyperlaneProtocolError, Indexed, InterchainGasPayment, H160, H256, H512, U256,
+    DestinationGasUpdate, GasPaymentKey, HyperlaneProtocolError, Indexed, InterchainGasPayment,
+    H160, H256, H512, U256,
 };
 
 /// Simple trait for types with a canonical encoding
@@ -236,6 +237,33 @@ impl Decode for InterchainGasPayment {
     }
 }
 
+impl Encode for DestinationGasUpdate {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut written = 0;
+        written += self.destination.write_to(writer)?;
+        written += self.token_exchange_rate.write_to(writer)?;
+        written += self.gas_price.write_to(writer)?;
+        Ok(written)
+    }
+}
+
+impl Decode for DestinationGasUpdate {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            destination: u32::read_from(reader)?,
+            token_exchange_rate: U256::read_from(reader)?,
+            gas_price: U256::read_from(reader)?,
+        })
+    }
+}
+
 // TODO: Could generalize this implementation to support encoding arbitrary `Option<T>`
 // where T: Encode + Decode
 impl<T: Encode> Encode for Indexed<T> {
@@ -301,4 +329,18 @@ mod test {
         let decoded = super::InterchainGasPayment::read_from(&mut &encoded[..]).unwrap();
         assert_eq!(payment, decoded);
     }
+
+    #[test]
+    fn test_encoding_destination_gas_update() {
+        let update = super::DestinationGasUpdate {
+            destination: 42,
+            token_exchange_rate: 15_000_000_000u64.into(),
+            gas_price: super::U256::MAX,
+        };
+        let encoded = update.to_vec();
+        assert_eq!(encoded.len(), 4 + 32 + 32);
+        let decoded = super::DestinationGasUpdate::read_from(&mut &encoded[..]).unwrap();
+        assert_eq!(update, decoded);
+        assert!(super::DestinationGasUpdate::read_from(&mut &encoded[..36]).is_err());
+    }
 }
@@ -44,6 +44,13 @@ pub trait Indexer<T: Sized>: Send + Sync + Debug {
     ) -> ChainResult<Vec<(Indexed<T>, LogMeta)>> {
         Ok(vec![])
     }
+
+    /// Wait until new logs may have been emitted, so that indexing can react
+    /// to them instead of waiting to poll again. Never completes if the
+    /// indexer isn't notified of new logs.
+    async fn new_logs_notified(&self) {
+        std::future::pending().await
+    }
 }
 
 /// Interface for indexing data in sequence.
@@ -3,10 +3,40 @@ use std::fmt::Debug;
 use async_trait::async_trait;
 use auto_impl::auto_impl;
 
-use crate::HyperlaneContract;
+use crate::{ChainCommunicationError, ChainResult, HyperlaneContract, TxOutcome, H256, U256};
 
 /// Interface for the InterchainGasPaymaster chain contract.
 /// Allows abstraction over different chains.
 #[async_trait]
 #[auto_impl(&, Box, Arc)]
-pub trait InterchainGasPaymaster: HyperlaneContract + Send + Sync + Debug {}
+pub trait InterchainGasPaymaster: HyperlaneContract + Send + Sync + Debug {
+    /// The address that fees held by the paymaster are claimed to
+    async fn beneficiary(&self) -> ChainResult<H256> {
+        // Claiming is not supported by default
+        Err(ChainCommunicationError::from_other_str(
+            "Claiming fees is not supported by this paymaster",
+        ))
+    }
+
+    /// Send the fees held by the paymaster to its beneficiary
+    async fn claim(&self) -> ChainResult<TxOutcome> {
+        Err(ChainCommunicationError::from_other_str(
+            "Claiming fees is not supported by this paymaster",
+        ))
+    }
+
+    /// Pay for `gas_amount` of gas to deliver a message on the `destination`
+    /// domain, at the price quoted by the paymaster. Any overpayment is
+    /// refunded to the sender.
+    async fn pay_for_gas(
+        &self,
+        _message_id: H256,
+        _destination: u32,
+        _gas_amount: U256,
+    ) -> ChainResult<TxOutcome> {
+        // Paying is not supported by default
+        Err(ChainCommunicationError::from_other_str(
+            "Paying for gas is not supported by this paymaster",
+        ))
+    }
+}
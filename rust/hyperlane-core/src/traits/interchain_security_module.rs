@@ -52,6 +52,13 @@ pub trait InterchainSecurityModule: HyperlaneContract + Send + Sync + Debug {
     /// metadata offchain fetching and onchain formatting standard.
     async fn module_type(&self) -> ChainResult<ModuleType>;
 
+    /// Returns the module type the ISM reports as is, including types that
+    /// `module_type` doesn't know and reports as `Unused`. None if the chain
+    /// doesn't support it.
+    async fn raw_module_type(&self) -> ChainResult<Option<u8>> {
+        Ok(None)
+    }
+
     /// Dry runs the `verify()` ISM call and returns `Some(gas_estimate)` if the call
     /// succeeds.
     async fn dry_run_verify(
@@ -26,9 +26,31 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
     /// Fetch the status of a message
     async fn delivered(&self, id: H256) -> ChainResult<bool>;
 
+    /// Fetch the status of each of several messages, in as few calls as the
+    /// chain allows. Fetched one by one unless implemented.
+    async fn delivered_batch(&self, ids: &[H256]) -> ChainResult<Vec<bool>> {
+        let mut delivered = Vec::with_capacity(ids.len());
+        for id in ids {
+            delivered.push(self.delivered(*id).await?);
+        }
+        Ok(delivered)
+    }
+
+    /// Fetch the block number a message was processed in. None if it wasn't
+    /// delivered or the mailbox doesn't record it.
+    async fn processed_at(&self, _id: H256) -> ChainResult<Option<u64>> {
+        Ok(None)
+    }
+
     /// Fetch the current default interchain security module value
     async fn default_ism(&self) -> ChainResult<H256>;
 
+    /// Wait until the mailbox emits an event setting a new default ISM.
+    /// Returns false without waiting if the chain isn't watched for them.
+    async fn wait_for_default_ism_set(&self) -> bool {
+        false
+    }
+
     /// Get the latest checkpoint.
     async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256>;
 
@@ -40,6 +62,19 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome>;
 
+    /// Process a message like `process`, paying fees `fee_bump_percent`
+    /// higher than the chain suggests so it is included sooner. Chains without
+    /// adjustable fees process it like `process`.
+    async fn process_with_fee_bump(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+        _fee_bump_percent: u64,
+    ) -> ChainResult<TxOutcome> {
+        self.process(message, metadata, tx_gas_limit).await
+    }
+
     /// Process a message with a proof against the provided signed checkpoint
     async fn process_batch(
         &self,
@@ -56,7 +91,78 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate>;
 
+    /// Simulate processing a message with `tx_gas_limit` right before
+    /// submitting it, classifying why it would fail. None if the chain
+    /// doesn't support simulating deliveries.
+    async fn simulate_process(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+        _tx_gas_limit: U256,
+    ) -> ChainResult<Option<ProcessSimulation>> {
+        Ok(None)
+    }
+
     /// Get the calldata for a transaction to process a message with a proof
     /// against the provided signed checkpoint
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8>;
+
+    /// Dispatch a message with `body` to `recipient` on the `destination`
+    /// domain, paying the fee the mailbox quotes for it. Returns the id of the
+    /// dispatched message with the outcome of the transaction.
+    async fn dispatch(
+        &self,
+        _destination: u32,
+        _recipient: H256,
+        _body: &[u8],
+    ) -> ChainResult<(H256, TxOutcome)> {
+        // Dispatching is not supported by default
+        Err(ChainCommunicationError::from_other_str(
+            "Dispatching messages is not supported by this mailbox",
+        ))
+    }
+}
+
+/// The outcome of simulating the delivery of a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessSimulation {
+    /// The delivery would succeed
+    WouldSucceed,
+    /// The recipient's ISM rejected the metadata
+    IsmVerifyFailed {
+        /// The decoded revert
+        reason: String,
+    },
+    /// The recipient reverted when handling the message
+    RecipientReverted {
+        /// The decoded revert
+        reason: String,
+    },
+    /// The delivery would run out of gas with the gas limit it was
+    /// simulated with
+    OutOfGas,
+    /// The mailbox reverted for another reason, e.g. because the message was
+    /// already delivered
+    Reverted {
+        /// The decoded revert
+        reason: String,
+    },
+}
+
+impl ProcessSimulation {
+    /// Whether the delivery would succeed
+    pub fn would_succeed(&self) -> bool {
+        matches!(self, Self::WouldSucceed)
+    }
+
+    /// The metric label of the outcome
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WouldSucceed => "would_succeed",
+            Self::IsmVerifyFailed { .. } => "ism_verify_failed",
+            Self::RecipientReverted { .. } => "recipient_reverted",
+            Self::OutOfGas => "out_of_gas",
+            Self::Reverted { .. } => "reverted",
+        }
+    }
 }
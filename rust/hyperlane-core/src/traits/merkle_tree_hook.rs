@@ -5,7 +5,8 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 
 use crate::{
-    accumulator::incremental::IncrementalMerkle, ChainResult, Checkpoint, HyperlaneContract,
+    accumulator::incremental::IncrementalMerkle, ChainCommunicationError, ChainResult, Checkpoint,
+    FinalityTag, HyperlaneContract,
 };
 
 /// Interface for the MerkleTreeHook chain contract. Allows abstraction over different
@@ -30,4 +31,20 @@ pub trait MerkleTreeHook: HyperlaneContract + Send + Sync + Debug {
     /// - `lag` is how far behind the current block to query, if not specified
     ///   it will query at the latest block.
     async fn latest_checkpoint(&self, lag: Option<NonZeroU64>) -> ChainResult<Checkpoint>;
+
+    /// Return the incremental merkle tree in storage as of the chain's block
+    /// with the finality `tag`, for chains that expose one.
+    async fn finalized_tree(&self, tag: FinalityTag) -> ChainResult<IncrementalMerkle> {
+        Err(ChainCommunicationError::CustomError(format!(
+            "Querying the {tag} block is not supported on this chain"
+        )))
+    }
+
+    /// Get the latest checkpoint as of the chain's block with the finality
+    /// `tag`, for chains that expose one.
+    async fn latest_finalized_checkpoint(&self, tag: FinalityTag) -> ChainResult<Checkpoint> {
+        Err(ChainCommunicationError::CustomError(format!(
+            "Querying the {tag} block is not supported on this chain"
+        )))
+    }
 }
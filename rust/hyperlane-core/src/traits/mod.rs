@@ -1,5 +1,6 @@
 pub use aggregation_ism::*;
 pub use ccip_read_ism::*;
+pub use checkpoint_attestation::*;
 pub use cursor::*;
 pub use db::*;
 pub use deployed::*;
@@ -13,6 +14,8 @@ pub use multisig_ism::*;
 pub use pending_operation::*;
 pub use provider::*;
 pub use routing_ism::*;
+#[cfg(test)]
+pub(crate) use signing::hashes;
 pub use signing::*;
 pub use validator_announce::*;
 
@@ -20,6 +23,7 @@ use crate::{FixedPointNumber, H512, U256};
 
 mod aggregation_ism;
 mod ccip_read_ism;
+mod checkpoint_attestation;
 mod cursor;
 mod db;
 mod deployed;
@@ -5,8 +5,8 @@ use std::{
 };
 
 use crate::{
-    ChainResult, FixedPointNumber, HyperlaneDomain, HyperlaneMessage, TryBatchAs, TxOutcome, H256,
-    U256,
+    ChainResult, FixedPointNumber, HyperlaneDomain, HyperlaneMessage, OperationId, TryBatchAs,
+    TxOutcome, H256, U256,
 };
 use async_trait::async_trait;
 use num::CheckedDiv;
@@ -55,6 +55,24 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// Label to use for metrics granularity.
     fn app_context(&self) -> Option<String>;
 
+    /// Id the operation is logged with, which follows it across tasks and
+    /// restarts, if it has one.
+    fn operation_id(&self) -> Option<OperationId> {
+        None
+    }
+
+    /// Tenant the operation is submitted for, if any. Operations of different
+    /// tenants are signed by different keys, so are never batched together.
+    fn tenant(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the operation nears a deadline it should be completed by, so
+    /// should be attempted before operations that don't once it is ready.
+    fn is_urgent(&self) -> bool {
+        false
+    }
+
     /// Get tuple of labels for metrics.
     fn get_operation_labels(&self) -> (String, String) {
         let app_context = self.app_context().unwrap_or("Unknown".to_string());
@@ -67,6 +85,14 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// submit call.
     async fn prepare(&mut self) -> PendingOperationResult;
 
+    /// Simulate this operation right before it's submitted, alone or in a
+    /// batch. Operations that wouldn't succeed aren't submitted, and their
+    /// `confirm` handles why. Operations that can't be simulated are assumed
+    /// to succeed.
+    async fn simulate_submission(&mut self) -> bool {
+        true
+    }
+
     /// Submit this operation to the blockchain
     async fn submit(&mut self);
 
@@ -81,6 +107,11 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// which we consider it safe from reorgs.
     async fn confirm(&mut self) -> PendingOperationResult;
 
+    /// Set whether the operation was delivered, as found when checking the
+    /// deliveries of many operations at once, so that the next `confirm`
+    /// doesn't check it again. Ignored unless implemented.
+    fn set_delivered(&mut self, _delivered: bool) {}
+
     /// Record the outcome of the operation
     fn set_operation_outcome(
         &mut self,
@@ -101,6 +132,10 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// retried immediately.
     fn reset_attempts(&mut self);
 
+    /// Store what is needed to resume this operation after a restart. Called
+    /// for the operations still queued when the agent shuts down.
+    fn persist(&self) {}
+
     /// Set the number of times this operation has been retried.
     #[cfg(any(test, feature = "test-utils"))]
     fn set_retries(&mut self, retries: u32);
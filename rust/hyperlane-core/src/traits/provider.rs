@@ -30,6 +30,12 @@ pub trait HyperlaneProvider: HyperlaneChain + Send + Sync + Debug {
 
     /// Fetch metrics related to this chain
     async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>>;
+
+    /// Fetch the id the chain reports for itself, or None if the provider
+    /// can't report one
+    async fn get_chain_id(&self) -> ChainResult<Option<String>> {
+        Ok(None)
+    }
 }
 
 /// Errors when querying for provider information.
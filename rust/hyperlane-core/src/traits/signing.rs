@@ -136,7 +136,7 @@ impl<T: Signable + Debug> Debug for SignedType<T> {
 
 // Copied from https://github.com/hyperlane-xyz/ethers-rs/blob/hyperlane/ethers-core/src/utils/hash.rs
 // so that we can get EIP-191 hashing without the `ethers` feature
-mod hashes {
+pub(crate) mod hashes {
     const PREFIX: &str = "\x19Ethereum Signed Message:\n";
     use crate::H256;
     use tiny_keccak::{Hasher, Keccak};
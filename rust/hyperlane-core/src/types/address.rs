@@ -0,0 +1,427 @@
+use std::{collections::HashMap, str::FromStr, sync::OnceLock};
+
+use bech32::{FromBase32, ToBase32, Variant};
+use eyre::{bail, Result};
+use sha3::{Digest, Keccak256};
+use tracing::warn;
+
+use crate::{HyperlaneDomain, HyperlaneDomainProtocol, KnownHyperlaneDomain, H160, H256};
+
+/// How the addresses of a chain are written and stored.
+///
+/// Addresses are always handled as 32 bytes within the agents; this maps them
+/// to and from the form the chain itself uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressFormat {
+    /// 20 byte hex, e.g. an EVM address
+    Hex20,
+    /// 32 byte hex
+    Hex32,
+    /// 32 byte base58, e.g. a Solana pubkey
+    Base58,
+    /// bech32 with the given human readable prefix, e.g. a Cosmos account
+    Bech32(String),
+}
+
+impl AddressFormat {
+    /// The format of the addresses of a chain of `protocol`. Cosmos addresses
+    /// fall back to hex if the bech32 prefix isn't known.
+    pub fn for_protocol(protocol: HyperlaneDomainProtocol, bech32_prefix: Option<&str>) -> Self {
+        use HyperlaneDomainProtocol::*;
+        match (protocol, bech32_prefix) {
+            (Ethereum, _) => Self::Hex20,
+            (Sealevel, _) => Self::Base58,
+            (Cosmos, Some(prefix)) => Self::Bech32(prefix.to_owned()),
+            (Cosmos, None) | (Fuel, _) | (Aptos, _) | (Sui, _) | (Starknet, _) | (Ton, _) => {
+                Self::Hex32
+            }
+        }
+    }
+
+    /// The format of the addresses of `domain`, using the bech32 prefix of
+    /// the known Cosmos chains
+    pub fn for_domain(domain: &HyperlaneDomain) -> Self {
+        let prefix = match domain {
+            HyperlaneDomain::Known(known) => known.bech32_prefix(),
+            HyperlaneDomain::Unknown { .. } => None,
+        };
+        Self::for_protocol(domain.domain_protocol(), prefix)
+    }
+
+    /// The format of the addresses of the domain with id `domain`, if it is a
+    /// known one
+    pub fn for_domain_id(domain: u32) -> Option<Self> {
+        KnownHyperlaneDomain::try_from(domain)
+            .ok()
+            .map(|known| Self::for_domain(&HyperlaneDomain::Known(known)))
+    }
+
+    /// Write an address the way the chain does
+    pub fn encode(&self, address: &H256) -> String {
+        match self {
+            Self::Hex20 => to_checksum(&H160::from(*address)),
+            Self::Hex32 => format!("{address:?}"),
+            Self::Base58 => bs58::encode(address.as_bytes()).into_string(),
+            Self::Bech32(prefix) => {
+                // Accounts are 20 bytes, contracts 32
+                let bytes = if is_h160(address) {
+                    &address.as_bytes()[12..]
+                } else {
+                    address.as_bytes()
+                };
+                bech32::encode(prefix, bytes.to_base32(), Variant::Bech32)
+                    // Only fails for an invalid prefix
+                    .unwrap_or_else(|_| format!("{address:?}"))
+            }
+        }
+    }
+
+    /// Parse an address written the way the chain does
+    pub fn parse(&self, address: &str) -> Result<H256> {
+        match self {
+            Self::Hex20 => Ok(H160::from_str(address)?.into()),
+            Self::Hex32 => Ok(H256::from_str(address)?),
+            Self::Base58 => {
+                let bytes = bs58::decode(address).into_vec()?;
+                Self::from_bytes(&bytes)
+            }
+            Self::Bech32(prefix) => {
+                let (hrp, data, _) = bech32::decode(address)?;
+                if &hrp != prefix {
+                    bail!("Expected prefix `{prefix}`, got `{hrp}`");
+                }
+                Self::from_bytes(&Vec::<u8>::from_base32(&data)?)
+            }
+        }
+    }
+
+    /// The canonical bytes an address is stored as. Only 20 byte hex
+    /// addresses are stored without their zero padding, the addresses of
+    /// other chains are always stored as the full 32 bytes. 32 byte addresses
+    /// of an EVM chain, e.g. the recipient of a message to another chain,
+    /// aren't truncated.
+    pub fn to_bytes(&self, address: &H256) -> Vec<u8> {
+        match self {
+            Self::Hex20 if is_h160(address) => address.as_bytes()[12..].into(),
+            Self::Hex20 | Self::Hex32 | Self::Base58 | Self::Bech32(_) => address.as_bytes().into(),
+        }
+    }
+
+    /// Read an address stored as 20 or 32 bytes, left padding it with zeros
+    pub fn from_bytes(bytes: &[u8]) -> Result<H256> {
+        match bytes.len() {
+            20 => Ok(H160::from_slice(bytes).into()),
+            32 => Ok(H256::from_slice(bytes)),
+            len => bail!("Invalid address length of {len} bytes"),
+        }
+    }
+}
+
+/// Parse an address of a chain of `protocol`, rejecting strings that can't
+/// be one rather than letting a typo go unnoticed:
+/// - hex must be 20 or 32 bytes, and mixed case 20 byte hex must have a
+///   valid EIP-55 checksum. EVM addresses written as 32 bytes must be zero
+///   padded.
+/// - Cosmos addresses that aren't hex must be valid bech32 of 20 or 32 bytes
+/// - addresses of other chains that aren't hex must be base58 of 32 bytes
+pub fn validate_address(protocol: HyperlaneDomainProtocol, address: &str) -> Result<H256> {
+    validate_address_with_prefix(protocol, None, address)
+}
+
+/// Like `validate_address`, also requiring bech32 addresses to have
+/// `bech32_prefix` as their human readable part if it is given
+pub fn validate_address_with_prefix(
+    protocol: HyperlaneDomainProtocol,
+    bech32_prefix: Option<&str>,
+    address: &str,
+) -> Result<H256> {
+    if let Some(hex) = address.strip_prefix("0x") {
+        let parsed = validate_hex(address, hex)?;
+        if protocol == HyperlaneDomainProtocol::Ethereum && !is_h160(&parsed) {
+            bail!("EVM addresses are 20 bytes, got 32 bytes that aren't zero padded");
+        }
+        return Ok(parsed);
+    }
+    match protocol {
+        HyperlaneDomainProtocol::Ethereum => bail!("Expected a 0x-prefixed hex address"),
+        HyperlaneDomainProtocol::Cosmos => {
+            let (hrp, data, _) = bech32::decode(address)?;
+            if let Some(prefix) = bech32_prefix {
+                if hrp != prefix {
+                    bail!("Expected prefix `{prefix}`, got `{hrp}`");
+                }
+            }
+            AddressFormat::from_bytes(&Vec::<u8>::from_base32(&data)?)
+        }
+        _ => validate_base58(address),
+    }
+}
+
+/// Parse an address of a chain that isn't known, which may be hex or base58.
+/// Checks the same as `validate_address` does for hex and base58 addresses.
+pub fn validate_hex_or_base58_address(address: &str) -> Result<H256> {
+    match address.strip_prefix("0x") {
+        Some(hex) => validate_hex(address, hex),
+        None => validate_base58(address),
+    }
+}
+
+fn validate_hex(address: &str, hex: &str) -> Result<H256> {
+    Ok(match hex.len() {
+        40 => {
+            let h160 = H160::from_str(hex)?;
+            let is_mixed_case = hex.chars().any(|c| c.is_ascii_lowercase())
+                && hex.chars().any(|c| c.is_ascii_uppercase());
+            if is_mixed_case && to_checksum(&h160) != address {
+                bail!("Invalid EIP-55 checksum, expected `{}`", to_checksum(&h160));
+            }
+            h160.into()
+        }
+        64 => H256::from_str(hex)?,
+        len => bail!("Expected 20 or 32 bytes of hex, got {len} characters"),
+    })
+}
+
+fn validate_base58(address: &str) -> Result<H256> {
+    let bytes = bs58::decode(address).into_vec()?;
+    if bytes.len() != 32 {
+        bail!("Expected 32 bytes of base58, got {} bytes", bytes.len());
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+fn is_h160(address: &H256) -> bool {
+    address.as_bytes()[..12].iter().all(|b| *b == 0)
+}
+
+/// The EIP-55 mixed case checksum encoding of an EVM address
+fn to_checksum(address: &H160) -> String {
+    let hex = hex::encode(address);
+    let hash = Keccak256::digest(hex.as_bytes());
+    let checksummed: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0xf;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{checksummed}")
+}
+
+static ADDRESS_BOOK: OnceLock<AddressBook> = OnceLock::new();
+
+/// How the addresses of each domain are written, and the names of the
+/// addresses people know, e.g. the core contracts of a chain.
+///
+/// Agents install the address book of their config at startup, so that logs
+/// print addresses the way the chain they belong to does rather than as
+/// padded hex.
+#[derive(Debug, Clone, Default)]
+pub struct AddressBook {
+    formats: HashMap<u32, AddressFormat>,
+    names: HashMap<(u32, H256), String>,
+}
+
+impl AddressBook {
+    /// Set how the addresses of `domain` are written
+    pub fn set_format(&mut self, domain: u32, format: AddressFormat) {
+        self.formats.insert(domain, format);
+    }
+
+    /// Name an address of `domain`
+    pub fn set_name(&mut self, domain: u32, address: H256, name: impl Into<String>) {
+        self.names.insert((domain, address), name.into());
+    }
+
+    /// How the addresses of `domain` are written, if it is configured or a
+    /// known domain
+    pub fn format(&self, domain: u32) -> Option<AddressFormat> {
+        self.formats
+            .get(&domain)
+            .cloned()
+            .or_else(|| AddressFormat::for_domain_id(domain))
+    }
+
+    /// The name of an address of `domain`, if it has one
+    pub fn name(&self, domain: u32, address: &H256) -> Option<&str> {
+        self.names.get(&(domain, *address)).map(String::as_str)
+    }
+
+    /// Print an address of `domain` the way its chain does, followed by its
+    /// name if it has one, e.g. `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed
+    /// (mailbox)`. Addresses of domains of an unknown format are printed as
+    /// hex.
+    pub fn display(&self, domain: u32, address: &H256) -> String {
+        let encoded = self
+            .format(domain)
+            .map(|format| format.encode(address))
+            .unwrap_or_else(|| format!("{address:?}"));
+        match self.name(domain, address) {
+            Some(name) => format!("{encoded} ({name})"),
+            None => encoded,
+        }
+    }
+
+    /// Make this the address book of the process. Only the first address
+    /// book installed is used, and none can be installed once the address
+    /// book has been used.
+    pub fn install(self) {
+        if ADDRESS_BOOK.set(self).is_err() {
+            warn!("An address book is already installed, ignoring this one");
+        }
+    }
+
+    /// The address book of the process, empty if none was installed
+    pub fn global() -> &'static AddressBook {
+        ADDRESS_BOOK.get_or_init(AddressBook::default)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        let account = H256::from(H160::repeat_byte(0xab));
+        let contract = H256::repeat_byte(0xcd);
+        let formats = [
+            AddressFormat::Hex20,
+            AddressFormat::Hex32,
+            AddressFormat::Base58,
+            AddressFormat::Bech32("neutron".into()),
+        ];
+        for format in formats {
+            let addresses = if format == AddressFormat::Hex20 {
+                vec![account]
+            } else {
+                vec![account, contract]
+            };
+            for address in addresses {
+                let encoded = format.encode(&address);
+                assert_eq!(format.parse(&encoded).unwrap(), address, "{encoded}");
+                let bytes = format.to_bytes(&address);
+                assert_eq!(AddressFormat::from_bytes(&bytes).unwrap(), address);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encodes_like_the_chain() {
+        let account = H256::from(H160::repeat_byte(0xab));
+        assert!(AddressFormat::Bech32("osmo".into())
+            .encode(&account)
+            .starts_with("osmo1"));
+        // EIP-55 test vectors
+        for checksummed in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let address = AddressFormat::Hex20.parse(checksummed).unwrap();
+            assert_eq!(AddressFormat::Hex20.encode(&address), checksummed);
+        }
+        // Non-EVM addresses keep their padding when stored
+        assert_eq!(AddressFormat::Base58.to_bytes(&account).len(), 32);
+        assert_eq!(AddressFormat::Hex20.to_bytes(&account).len(), 20);
+        // 32 byte addresses are never truncated
+        let contract = H256::repeat_byte(0xcd);
+        assert_eq!(
+            AddressFormat::from_bytes(&AddressFormat::Hex20.to_bytes(&contract)).unwrap(),
+            contract
+        );
+        assert!(AddressFormat::Bech32("inj".into())
+            .parse(&AddressFormat::Bech32("osmo".into()).encode(&account))
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_address() {
+        use HyperlaneDomainProtocol::*;
+
+        let evm = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let expected = AddressFormat::Hex20.parse(evm).unwrap();
+        assert_eq!(validate_address(Ethereum, evm).unwrap(), expected);
+        // Single case hex has no checksum
+        assert_eq!(
+            validate_address(Ethereum, &evm.to_lowercase()).unwrap(),
+            expected
+        );
+        assert!(validate_address(Ethereum, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAED").is_err());
+        assert!(validate_address(Ethereum, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA").is_err());
+        assert_eq!(
+            validate_address(Ethereum, &format!("{expected:?}")).unwrap(),
+            expected
+        );
+        assert!(validate_address(Ethereum, &format!("{:?}", H256::repeat_byte(0xcd))).is_err());
+
+        let pubkey = H256::repeat_byte(0xcd);
+        let base58 = AddressFormat::Base58.encode(&pubkey);
+        assert_eq!(validate_address(Sealevel, &base58).unwrap(), pubkey);
+        assert!(validate_address(Sealevel, &base58[..20]).is_err());
+        assert!(validate_address(Ethereum, &base58).is_err());
+
+        let bech32 = AddressFormat::Bech32("neutron".into()).encode(&expected);
+        assert_eq!(validate_address(Cosmos, &bech32).unwrap(), expected);
+        assert_eq!(
+            validate_address_with_prefix(Cosmos, Some("neutron"), &bech32).unwrap(),
+            expected
+        );
+        assert!(validate_address_with_prefix(Cosmos, Some("osmo"), &bech32).is_err());
+        // A typo breaks the bech32 checksum
+        let last = if bech32.ends_with('q') { 'p' } else { 'q' };
+        let typo = format!("{}{last}", &bech32[..bech32.len() - 1]);
+        assert!(validate_address(Cosmos, &typo).is_err());
+    }
+
+    #[test]
+    fn test_validate_hex_or_base58_address() {
+        let evm = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let expected = AddressFormat::Hex20.parse(evm).unwrap();
+        assert_eq!(validate_hex_or_base58_address(evm).unwrap(), expected);
+        assert_eq!(
+            validate_hex_or_base58_address(&evm.to_lowercase()).unwrap(),
+            expected
+        );
+        // The last character's case is wrong
+        assert!(
+            validate_hex_or_base58_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err()
+        );
+        // 32 byte hex needn't be zero padded
+        let h256 = H256::repeat_byte(0xcd);
+        assert_eq!(
+            validate_hex_or_base58_address(&format!("{h256:?}")).unwrap(),
+            h256
+        );
+
+        let base58 = AddressFormat::Base58.encode(&h256);
+        assert_eq!(validate_hex_or_base58_address(&base58).unwrap(), h256);
+        assert!(validate_hex_or_base58_address(&base58[..20]).is_err());
+    }
+
+    #[test]
+    fn test_address_book_display() {
+        let mailbox = AddressFormat::Hex20
+            .parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+            .unwrap();
+        let mut book = AddressBook::default();
+        book.set_format(1234, AddressFormat::Bech32("osmo".into()));
+        book.set_name(1, mailbox, "mailbox");
+
+        // Known domain, named address
+        assert_eq!(
+            book.display(1, &mailbox),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed (mailbox)"
+        );
+        // Names are per domain
+        assert!(book.display(1234, &mailbox).starts_with("osmo1"));
+        // Unknown domains fall back to hex
+        assert_eq!(book.display(4321, &mailbox), format!("{mailbox:?}"));
+    }
+}
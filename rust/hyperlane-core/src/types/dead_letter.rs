@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Decode, Encode, HyperlaneProtocolError, H256};
+
+/// Why a message was moved to the dead-letter store
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum DeadLetterReason {
+    /// The message was older than the maximum age configured for its app
+    Expired = 1,
+}
+
+impl TryFrom<u8> for DeadLetterReason {
+    type Error = HyperlaneProtocolError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Expired),
+            _ => Err(HyperlaneProtocolError::UnknownDeadLetterReason(value)),
+        }
+    }
+}
+
+/// A message the relayer gave up on, kept so it can be inspected and replayed
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// 32  Id of the message
+    pub message_id: H256,
+    /// 1   Why the message was dead-lettered
+    pub reason: DeadLetterReason,
+    /// 8   Unix timestamp in seconds of when the message was dead-lettered
+    pub dead_lettered_at: u64,
+}
+
+impl Encode for DeadLetter {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        writer.write_all(self.message_id.as_ref())?;
+        writer.write_all(&[self.reason as u8])?;
+        writer.write_all(&self.dead_lettered_at.to_be_bytes())?;
+        Ok(32 + 1 + 8)
+    }
+}
+
+impl Decode for DeadLetter {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+    {
+        let mut message_id = H256::zero();
+        reader.read_exact(message_id.as_mut())?;
+
+        let mut reason = [0u8; 1];
+        reader.read_exact(&mut reason)?;
+
+        let mut dead_lettered_at = [0u8; 8];
+        reader.read_exact(&mut dead_lettered_at)?;
+
+        Ok(Self {
+            message_id,
+            reason: reason[0].try_into()?,
+            dead_lettered_at: u64::from_be_bytes(dead_lettered_at),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dead_letter_roundtrip() {
+        let dead_letter = DeadLetter {
+            message_id: H256::repeat_byte(0x42),
+            reason: DeadLetterReason::Expired,
+            dead_lettered_at: 1_700_000_000,
+        };
+        let encoded = dead_letter.to_vec();
+        assert_eq!(encoded.len(), 41);
+        assert_eq!(
+            DeadLetter::read_from(&mut encoded.as_slice()).unwrap(),
+            dead_letter
+        );
+    }
+
+    #[test]
+    fn test_dead_letter_reason_from_u8() {
+        assert_eq!(
+            DeadLetterReason::try_from(DeadLetterReason::Expired as u8).unwrap(),
+            DeadLetterReason::Expired
+        );
+        assert!(DeadLetterReason::try_from(0).is_err());
+    }
+}
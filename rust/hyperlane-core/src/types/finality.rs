@@ -0,0 +1,23 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+/// A block tag of a chain marking the blocks it considers final, which the
+/// agents can rely on instead of lagging the tip by a number of blocks
+#[derive(Copy, Debug, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum FinalityTag {
+    /// The `safe` block, unlikely to be reorged
+    Safe,
+    /// The `finalized` block, which can't be reorged
+    Finalized,
+}
+
+impl fmt::Display for FinalityTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Safe => write!(f, "safe"),
+            Self::Finalized => write!(f, "finalized"),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Decode, Encode, HyperlaneProtocolError, H160, U256};
+
+/// The only metadata variant currently understood by post-dispatch hooks
+pub const STANDARD_HOOK_METADATA_VARIANT: u16 = 1;
+
+const STANDARD_HOOK_METADATA_PREFIX_LEN: usize = 2 + 32 + 32 + 20;
+
+/// Metadata passed to post-dispatch hooks, matching the `StandardHookMetadata`
+/// library of the solidity contracts.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StandardHookMetadata {
+    /// 2   Metadata variant, always `STANDARD_HOOK_METADATA_VARIANT`
+    /// 32  Value forwarded with the message, e.g. to the IGP
+    pub msg_value: U256,
+    /// 32  Gas limit for the `handle` call on the destination
+    pub gas_limit: U256,
+    /// 20  Address that overpayments are refunded to
+    pub refund_address: H160,
+    /// 0+  Hook-specific data
+    pub custom: Vec<u8>,
+}
+
+impl Encode for StandardHookMetadata {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut word = [0u8; 32];
+        writer.write_all(&STANDARD_HOOK_METADATA_VARIANT.to_be_bytes())?;
+        self.msg_value.to_big_endian(&mut word);
+        writer.write_all(&word)?;
+        self.gas_limit.to_big_endian(&mut word);
+        writer.write_all(&word)?;
+        writer.write_all(self.refund_address.as_ref())?;
+        writer.write_all(&self.custom)?;
+        Ok(STANDARD_HOOK_METADATA_PREFIX_LEN + self.custom.len())
+    }
+}
+
+impl Decode for StandardHookMetadata {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+    {
+        let mut variant = [0u8; 2];
+        reader.read_exact(&mut variant)?;
+        let variant = u16::from_be_bytes(variant);
+        if variant != STANDARD_HOOK_METADATA_VARIANT {
+            return Err(HyperlaneProtocolError::UnsupportedHookMetadataVariant(
+                variant,
+            ));
+        }
+
+        let mut msg_value = [0u8; 32];
+        reader.read_exact(&mut msg_value)?;
+
+        let mut gas_limit = [0u8; 32];
+        reader.read_exact(&mut gas_limit)?;
+
+        let mut refund_address = H160::zero();
+        reader.read_exact(refund_address.as_mut())?;
+
+        let mut custom = vec![];
+        reader.read_to_end(&mut custom)?;
+
+        Ok(Self {
+            msg_value: U256::from_big_endian(&msg_value),
+            gas_limit: U256::from_big_endian(&gas_limit),
+            refund_address,
+            custom,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_standard_hook_metadata_roundtrip() {
+        let metadata = StandardHookMetadata {
+            msg_value: U256::zero(),
+            gas_limit: U256::from(300_000),
+            refund_address: H160::repeat_byte(0xab),
+            custom: vec![1, 2, 3],
+        };
+        let encoded = metadata.to_vec();
+        assert_eq!(encoded.len(), STANDARD_HOOK_METADATA_PREFIX_LEN + 3);
+        assert_eq!(&encoded[..2], &[0, 1]);
+        // gas limit is the big-endian word following the msg value
+        assert_eq!(&encoded[63..66], &[0x04, 0x93, 0xe0]);
+        assert_eq!(
+            StandardHookMetadata::read_from(&mut encoded.as_slice()).unwrap(),
+            metadata
+        );
+    }
+
+    #[test]
+    fn test_matches_solidity_layout() {
+        // StandardHookMetadata.formatMetadata(1 ether, 300_000, 0xab..ab, hex"cafe")
+        let expected = hex::decode(concat!(
+            "0001",
+            "0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+            "00000000000000000000000000000000000000000000000000000000000493e0",
+            "abababababababababababababababababababab",
+            "cafe",
+        ))
+        .unwrap();
+        let metadata = StandardHookMetadata {
+            msg_value: U256::exp10(18),
+            gas_limit: U256::from(300_000),
+            refund_address: H160::repeat_byte(0xab),
+            custom: vec![0xca, 0xfe],
+        };
+        assert_eq!(metadata.to_vec(), expected);
+    }
+
+    #[test]
+    fn test_unknown_variant_is_rejected() {
+        let mut encoded = StandardHookMetadata::default().to_vec();
+        encoded[1] = 2;
+        assert!(matches!(
+            StandardHookMetadata::read_from(&mut encoded.as_slice()),
+            Err(HyperlaneProtocolError::UnsupportedHookMetadataVariant(2))
+        ));
+    }
+}
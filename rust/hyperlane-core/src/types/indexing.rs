@@ -1,6 +1,8 @@
 use derive_new::new;
 
-use crate::{HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion, Sequenced};
+use crate::{
+    DestinationGasUpdate, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion, Sequenced,
+};
 
 /// Wrapper struct that adds indexing information to a type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, new)]
@@ -79,3 +81,9 @@ impl From<InterchainGasPayment> for Indexed<InterchainGasPayment> {
         Indexed::new(value)
     }
 }
+
+impl From<DestinationGasUpdate> for Indexed<DestinationGasUpdate> {
+    fn from(value: DestinationGasUpdate) -> Self {
+        Indexed::new(value)
+    }
+}
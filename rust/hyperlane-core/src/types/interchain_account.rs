@@ -0,0 +1,275 @@
+use std::io::{Error, ErrorKind};
+
+use serde::{Deserialize, Serialize};
+use sha3::{digest::Update, Digest, Keccak256};
+
+use crate::{Decode, Encode, HyperlaneProtocolError, H160, H256, U256};
+
+/// Bytecode of the minimal proxies interchain accounts are deployed as,
+/// before and after the address of their implementation, matching the
+/// `MinimalProxy` library of the solidity contracts
+const MINIMAL_PROXY_PREFIX: [u8; 20] = [
+    0x3d, 0x60, 0x2d, 0x80, 0x60, 0x0a, 0x3d, 0x39, 0x81, 0xf3, 0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d,
+    0x3d, 0x36, 0x3d, 0x73,
+];
+const MINIMAL_PROXY_SUFFIX: [u8; 15] = [
+    0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3,
+];
+
+const WORD_LEN: usize = 32;
+
+/// A call made by an interchain account, matching `CallLib.Call`
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct InterchainAccountCall {
+    /// Contract called
+    pub to: H256,
+    /// Value sent with the call
+    pub value: U256,
+    /// Calldata
+    pub data: Vec<u8>,
+}
+
+/// The body of a message sent between `InterchainAccountRouter`s, matching
+/// the `InterchainAccountMessage` library of the solidity contracts. It is
+/// ABI encoded as `(bytes32, bytes32, (bytes32, uint256, bytes)[])`.
+///
+/// Only bodies encoded the way routers encode them are decoded, so that the
+/// bodies of other apps are hardly ever mistaken for one.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct InterchainAccountMessage {
+    /// Owner of the interchain account on the origin
+    pub owner: H256,
+    /// ISM of the interchain account on the destination, or zero for the
+    /// router's default
+    pub ism: H256,
+    /// Calls the interchain account makes, in order
+    pub calls: Vec<InterchainAccountCall>,
+}
+
+impl InterchainAccountMessage {
+    /// Address of the interchain account that makes the calls, given the
+    /// routers the message was sent between. Only meaningful if the
+    /// destination is an EVM chain.
+    pub fn account(&self, origin: u32, origin_router: H256, destination_router: H256) -> H160 {
+        let destination_router = H160::from(destination_router);
+        // The implementation is the first contract the router created
+        let implementation =
+            keccak256(&[&[0xd6, 0x94], destination_router.as_bytes(), &[0x01]].concat());
+        let bytecode_hash = keccak256(
+            &[
+                &MINIMAL_PROXY_PREFIX[..],
+                &implementation[12..],
+                &MINIMAL_PROXY_SUFFIX,
+            ]
+            .concat(),
+        );
+        let ism = H256::from(H160::from(self.ism));
+        let salt = keccak256(
+            &[
+                &origin.to_be_bytes()[..],
+                self.owner.as_bytes(),
+                origin_router.as_bytes(),
+                ism.as_bytes(),
+            ]
+            .concat(),
+        );
+        let address = keccak256(
+            &[
+                &[0xff],
+                destination_router.as_bytes(),
+                salt.as_bytes(),
+                bytecode_hash.as_bytes(),
+            ]
+            .concat(),
+        );
+        H160::from(address)
+    }
+
+    fn encoded_len(&self) -> usize {
+        WORD_LEN * 4
+            + self
+                .calls
+                .iter()
+                .map(|call| WORD_LEN * 5 + padded_len(call.data.len()))
+                .sum::<usize>()
+    }
+}
+
+impl Encode for InterchainAccountMessage {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        writer.write_all(self.owner.as_bytes())?;
+        writer.write_all(self.ism.as_bytes())?;
+        write_word(writer, WORD_LEN * 3)?;
+        write_word(writer, self.calls.len())?;
+        // Offsets of the calls, from the start of the first one's offset
+        let mut offset = WORD_LEN * self.calls.len();
+        for call in &self.calls {
+            write_word(writer, offset)?;
+            offset += WORD_LEN * 4 + padded_len(call.data.len());
+        }
+        let mut word = [0u8; WORD_LEN];
+        for call in &self.calls {
+            writer.write_all(call.to.as_bytes())?;
+            call.value.to_big_endian(&mut word);
+            writer.write_all(&word)?;
+            write_word(writer, WORD_LEN * 3)?;
+            write_word(writer, call.data.len())?;
+            writer.write_all(&call.data)?;
+            writer.write_all(&word_padding(call.data.len()))?;
+        }
+        Ok(self.encoded_len())
+    }
+}
+
+impl Decode for InterchainAccountMessage {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+    {
+        let mut encoded = vec![];
+        reader.read_to_end(&mut encoded)?;
+
+        let calls_start = read_offset(&encoded, WORD_LEN * 2, 0)?;
+        let calls_len = read_offset(&encoded, calls_start, 0)?;
+        let offsets_start = calls_start + WORD_LEN;
+        let calls = (0..calls_len)
+            .map(|i| {
+                let call_start =
+                    read_offset(&encoded, offsets_start + WORD_LEN * i, offsets_start)?;
+                let data_start = read_offset(&encoded, call_start + WORD_LEN * 2, call_start)?;
+                let data_len = read_offset(&encoded, data_start, 0)?;
+                let data = encoded
+                    .get(data_start + WORD_LEN..)
+                    .and_then(|data| data.get(..data_len))
+                    .ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+                Ok(InterchainAccountCall {
+                    to: H256::from_slice(read_word(&encoded, call_start)?),
+                    value: U256::from_big_endian(read_word(&encoded, call_start + WORD_LEN)?),
+                    data: data.to_vec(),
+                })
+            })
+            .collect::<Result<_, HyperlaneProtocolError>>()?;
+
+        let message = Self {
+            owner: H256::from_slice(read_word(&encoded, 0)?),
+            ism: H256::from_slice(read_word(&encoded, WORD_LEN)?),
+            calls,
+        };
+        if message.to_vec() != encoded {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Not encoded as an interchain account message",
+            )
+            .into());
+        }
+        Ok(message)
+    }
+}
+
+fn keccak256(bytes: &[u8]) -> H256 {
+    H256::from_slice(Keccak256::new().chain(bytes).finalize().as_slice())
+}
+
+fn padded_len(len: usize) -> usize {
+    (len + WORD_LEN - 1) / WORD_LEN * WORD_LEN
+}
+
+fn word_padding(len: usize) -> Vec<u8> {
+    vec![0u8; padded_len(len) - len]
+}
+
+fn write_word<W: std::io::Write>(writer: &mut W, value: usize) -> std::io::Result<()> {
+    let mut word = [0u8; WORD_LEN];
+    U256::from(value).to_big_endian(&mut word);
+    writer.write_all(&word)
+}
+
+fn read_word(encoded: &[u8], at: usize) -> Result<&[u8], HyperlaneProtocolError> {
+    Ok(encoded
+        .get(at..at + WORD_LEN)
+        .ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?)
+}
+
+/// Read the word at `at` as an offset from `base`, or as a length if `base`
+/// is zero
+fn read_offset(encoded: &[u8], at: usize, base: usize) -> Result<usize, HyperlaneProtocolError> {
+    let offset = U256::from_big_endian(read_word(encoded, at)?);
+    // Anything past the end of the body is invalid, so larger values needn't
+    // be representable
+    if offset > U256::from(encoded.len()) {
+        return Err(Error::from(ErrorKind::UnexpectedEof).into());
+    }
+    Ok(base + offset.as_usize())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn message() -> InterchainAccountMessage {
+        InterchainAccountMessage {
+            owner: H256::repeat_byte(0x11),
+            ism: H256::zero(),
+            calls: vec![
+                InterchainAccountCall {
+                    to: H256::repeat_byte(0x22),
+                    value: U256::from(5),
+                    data: vec![0xab; 36],
+                },
+                InterchainAccountCall {
+                    to: H256::repeat_byte(0x33),
+                    value: U256::zero(),
+                    data: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_interchain_account_message_roundtrip() {
+        let message = message();
+        let encoded = message.to_vec();
+        // owner, ism, calls offset and length, call offsets, then each call
+        // with its padded data
+        assert_eq!(encoded.len(), 32 * 6 + (32 * 4 + 64) + 32 * 4);
+        assert_eq!(encoded.len(), message.encoded_len());
+        assert_eq!(
+            InterchainAccountMessage::read_from(&mut encoded.as_slice()).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn test_other_bodies_are_rejected() {
+        let mut encoded = message().to_vec();
+        encoded.push(0);
+        assert!(InterchainAccountMessage::read_from(&mut encoded.as_slice()).is_err());
+        // A warp transfer
+        let transfer = [[0x11; 32], [0; 32]].concat();
+        assert!(InterchainAccountMessage::read_from(&mut transfer.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_account_matches_router_derivation() {
+        // As derived by `getRemoteInterchainAccount` of the solidity router
+        let message = InterchainAccountMessage {
+            owner: H256::from(
+                H160::from_str("0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496").unwrap(),
+            ),
+            ..Default::default()
+        };
+        let origin_router =
+            H256::from(H160::from_str("0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f").unwrap());
+        let destination_router =
+            H256::from(H160::from_str("0x2e234DAe75C793f67A35089C9d99245E1C58470b").unwrap());
+        assert_eq!(
+            message.account(31337, origin_router, destination_router),
+            H160::from_str("0xc71a8359ab79ee4fe422c00fcd711eedb4fda9ef").unwrap()
+        );
+    }
+}
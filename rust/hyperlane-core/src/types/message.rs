@@ -20,10 +20,33 @@ impl From<&HyperlaneMessage> for RawHyperlaneMessage {
     }
 }
 
+/// The versions of the message format. V2 and V3 messages share the same
+/// layout and id computation, only the version byte tells them apart.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum HyperlaneMessageVersion {
+    /// Messages dispatched by V2 mailboxes
+    V2 = 0,
+    /// Messages dispatched by V3 mailboxes
+    V3 = 3,
+}
+
+impl TryFrom<u8> for HyperlaneMessageVersion {
+    type Error = HyperlaneProtocolError;
+
+    fn try_from(version: u8) -> Result<Self, Self::Error> {
+        match version {
+            0 => Ok(Self::V2),
+            3 => Ok(Self::V3),
+            _ => Err(HyperlaneProtocolError::UnsupportedMessageVersion(version)),
+        }
+    }
+}
+
 /// A full Hyperlane message between chains
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct HyperlaneMessage {
-    /// 1   Hyperlane version number
+    /// 1   Hyperlane version number, see `HyperlaneMessageVersion`
     pub version: u8,
     /// 4   Message nonce
     pub nonce: u32,
@@ -43,7 +66,7 @@ impl Default for HyperlaneMessage {
     fn default() -> Self {
         Self {
             // Use version 3 now that Hyperlane V3 is the default
-            version: 3,
+            version: HyperlaneMessageVersion::V3 as u8,
             nonce: 0,
             origin: 0,
             sender: H256::zero(),
@@ -164,8 +187,134 @@ impl Decode for HyperlaneMessage {
 }
 
 impl HyperlaneMessage {
-    /// Convert the message to a message id
+    /// The version of the message format
+    pub fn message_version(&self) -> Result<HyperlaneMessageVersion, HyperlaneProtocolError> {
+        self.version.try_into()
+    }
+
+    /// Convert the message to a message id. The id of every version is the
+    /// keccak256 hash of the encoded message.
     pub fn id(&self) -> H256 {
-        H256::from_slice(Keccak256::new().chain(self.to_vec()).finalize().as_slice())
+        keccak256_id(&self.to_vec())
+    }
+}
+
+/// Computes the ids messages had when dispatched by a given version of the
+/// mailbox, whatever the version byte of the messages, e.g. to compare
+/// historic V2 data against a V3 deployment.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MessageIdCalculator {
+    version: HyperlaneMessageVersion,
+}
+
+impl MessageIdCalculator {
+    /// The calculator of the ids of `version` messages
+    pub fn new(version: HyperlaneMessageVersion) -> Self {
+        Self { version }
+    }
+
+    /// The calculator of the version of `message`
+    pub fn for_message(message: &HyperlaneMessage) -> Result<Self, HyperlaneProtocolError> {
+        Ok(Self::new(message.message_version()?))
+    }
+
+    /// The version whose ids are computed
+    pub fn version(&self) -> HyperlaneMessageVersion {
+        self.version
+    }
+
+    /// The id `message` had, or would have had, if dispatched with this
+    /// version
+    pub fn id(&self, message: &HyperlaneMessage) -> H256 {
+        let mut encoded = message.to_vec();
+        encoded[0] = self.version as u8;
+        keccak256_id(&encoded)
+    }
+}
+
+/// The id of `message` if dispatched by a V2 mailbox
+pub fn message_id_v2(message: &HyperlaneMessage) -> H256 {
+    MessageIdCalculator::new(HyperlaneMessageVersion::V2).id(message)
+}
+
+/// The id of `message` if dispatched by a V3 mailbox
+pub fn message_id_v3(message: &HyperlaneMessage) -> H256 {
+    MessageIdCalculator::new(HyperlaneMessageVersion::V3).id(message)
+}
+
+fn keccak256_id(encoded: &[u8]) -> H256 {
+    H256::from_slice(Keccak256::new().chain(encoded).finalize().as_slice())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_every_version() {
+        for version in [HyperlaneMessageVersion::V2, HyperlaneMessageVersion::V3] {
+            let message = HyperlaneMessage {
+                version: version as u8,
+                nonce: 7,
+                origin: 1,
+                sender: H256::repeat_byte(1),
+                destination: 2,
+                recipient: H256::repeat_byte(2),
+                body: vec![1, 2, 3],
+            };
+            let encoded = message.to_vec();
+            assert_eq!(encoded[0], version as u8);
+            let decoded = HyperlaneMessage::read_from(&mut encoded.as_slice()).unwrap();
+            assert_eq!(decoded, message);
+            assert_eq!(decoded.message_version().unwrap(), version);
+            assert_eq!(decoded.id(), message.id());
+        }
+    }
+
+    #[test]
+    fn test_message_id_of_each_version() {
+        let v3 = HyperlaneMessage {
+            nonce: 7,
+            body: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let v2 = HyperlaneMessage {
+            version: HyperlaneMessageVersion::V2 as u8,
+            ..v3.clone()
+        };
+
+        assert_eq!(message_id_v3(&v3), v3.id());
+        assert_eq!(message_id_v2(&v2), v2.id());
+        // The id only depends on the version it's computed for
+        assert_eq!(message_id_v2(&v3), v2.id());
+        assert_eq!(message_id_v3(&v2), v3.id());
+        assert_ne!(v2.id(), v3.id());
+
+        let calculator = MessageIdCalculator::for_message(&v2).unwrap();
+        assert_eq!(calculator.version(), HyperlaneMessageVersion::V2);
+        assert_eq!(calculator.id(&v3), v2.id());
+        assert!(MessageIdCalculator::for_message(&HyperlaneMessage {
+            version: 1,
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_decodes_unknown_version() {
+        let message = HyperlaneMessage {
+            version: 1,
+            ..Default::default()
+        };
+        let encoded = message.to_vec();
+        let decoded = HyperlaneMessage::read_from(&mut encoded.as_slice()).unwrap();
+        // Decoding is lossless, the version is only checked where a message
+        // is accepted
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.id(), message.id());
+        assert!(matches!(
+            decoded.message_version(),
+            Err(HyperlaneProtocolError::UnsupportedMessageVersion(1))
+        ));
     }
 }
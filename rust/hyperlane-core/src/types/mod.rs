@@ -6,24 +6,36 @@ use std::ops::Add;
 pub use self::primitive_types::*;
 #[cfg(feature = "ethers")]
 pub use ::primitive_types as ethers_core_types;
+pub use address::*;
 pub use announcement::*;
 pub use chain_data::*;
 pub use checkpoint::*;
+pub use dead_letter::*;
+pub use finality::*;
+pub use hook_metadata::*;
 pub use indexing::*;
+pub use interchain_account::*;
 pub use log_metadata::*;
 pub use merkle_tree::*;
 pub use message::*;
+pub use operation_id::*;
 pub use transaction::*;
 
 use crate::{Decode, Encode, HyperlaneProtocolError};
 
+mod address;
 mod announcement;
 mod chain_data;
 mod checkpoint;
+mod dead_letter;
+mod finality;
+mod hook_metadata;
 mod indexing;
+mod interchain_account;
 mod log_metadata;
 mod merkle_tree;
 mod message;
+mod operation_id;
 mod serialize;
 mod transaction;
 
@@ -188,6 +200,33 @@ impl Add for InterchainGasExpenditure {
     }
 }
 
+/// Scale of the token exchange rates gas oracles quote destination gas with
+pub const TOKEN_EXCHANGE_RATE_SCALE: u64 = 10_000_000_000;
+
+/// The gas price and token exchange rate of a destination, as set in the gas
+/// oracle an origin's InterchainGasPaymaster quotes payments with.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct DestinationGasUpdate {
+    /// Destination domain the gas data is for
+    pub destination: u32,
+    /// Origin native tokens per destination native token, scaled by
+    /// `TOKEN_EXCHANGE_RATE_SCALE`
+    pub token_exchange_rate: U256,
+    /// Gas price on the destination, in its native token
+    pub gas_price: U256,
+}
+
+impl DestinationGasUpdate {
+    /// What `gas_amount` of destination gas costs in origin native tokens, as
+    /// the InterchainGasPaymaster would quote it before any overhead
+    pub fn quote(&self, gas_amount: U256) -> U256 {
+        gas_amount
+            .saturating_mul(self.gas_price)
+            .saturating_mul(self.token_exchange_rate)
+            / TOKEN_EXCHANGE_RATE_SCALE
+    }
+}
+
 /// Uniquely identifying metadata for an InterchainGasPayment
 #[derive(Debug)]
 pub struct InterchainGasPaymentMeta {
@@ -253,3 +292,25 @@ impl TxCostEstimate {
         self.l2_gas_limit.unwrap_or(self.gas_limit)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_destination_gas_update_quote() {
+        let update = DestinationGasUpdate {
+            destination: 2,
+            // 1.5 origin tokens per destination token
+            token_exchange_rate: (TOKEN_EXCHANGE_RATE_SCALE * 3 / 2).into(),
+            gas_price: 20.into(),
+        };
+        assert_eq!(update.quote(100_000.into()), 3_000_000.into());
+        assert_eq!(update.quote(U256::zero()), U256::zero());
+        // Overflowing quotes saturate rather than wrap
+        assert_eq!(
+            update.quote(U256::MAX),
+            U256::MAX / TOKEN_EXCHANGE_RATE_SCALE
+        );
+    }
+}
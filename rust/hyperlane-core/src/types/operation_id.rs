@@ -0,0 +1,81 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Decode, Encode, HyperlaneProtocolError};
+
+/// Random id given to a message when the relayer first indexes it, which
+/// every log of its relaying carries. Stored with the message, so the
+/// lifecycle of one message can be followed across tasks and restarts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct OperationId(u64);
+
+impl OperationId {
+    /// A new random id
+    pub fn random() -> Self {
+        let mut bytes = [0u8; 8];
+        getrandom::getrandom(&mut bytes).expect("Failed to get random bytes");
+        Self(u64::from_be_bytes(bytes))
+    }
+}
+
+impl fmt::Display for OperationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for OperationId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u64::from_str_radix(s, 16).map(Self)
+    }
+}
+
+impl From<OperationId> for String {
+    fn from(id: OperationId) -> Self {
+        id.to_string()
+    }
+}
+
+impl TryFrom<String> for OperationId {
+    type Error = std::num::ParseIntError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Encode for OperationId {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        self.0.write_to(writer)
+    }
+}
+
+impl Decode for OperationId {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+    {
+        u64::read_from(reader).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_operation_id_string_roundtrip() {
+        let id = OperationId(0x42);
+        assert_eq!(id.to_string(), "0000000000000042");
+        assert_eq!("0000000000000042".parse::<OperationId>().unwrap(), id);
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"0000000000000042\"");
+        assert!("not hex".parse::<OperationId>().is_err());
+    }
+}
@@ -5,7 +5,7 @@ use std::str::FromStr;
 #[cfg(feature = "float")]
 use std::time::Duration;
 
-use crate::{KnownHyperlaneDomain, H160, H256};
+use crate::{AddressBook, KnownHyperlaneDomain, H160, H256};
 
 /// Converts a hex or base58 string to an H256.
 pub fn hex_or_base58_to_h256(string: &str) -> Result<H256> {
@@ -50,11 +50,10 @@ pub fn announcement_domain_hash(address: H256, domain: impl Into<u32>) -> H256 {
     )
 }
 
-/// Pretty print an address based on the domain it is for.
+/// Pretty print an address based on the domain it is for, using the address
+/// book of the process.
 pub fn fmt_address_for_domain(domain: u32, addr: H256) -> String {
-    KnownHyperlaneDomain::try_from(domain)
-        .map(|d| d.domain_protocol().fmt_address(addr))
-        .unwrap_or_else(|_| format!("{addr:?}"))
+    AddressBook::global().display(domain, &addr)
 }
 
 /// Pretty print a byte slice, including a hex prefix
@@ -0,0 +1,62 @@
+#![allow(non_snake_case)]
+use core::fmt::Debug;
+use mockall::*;
+
+use async_trait::async_trait;
+use hyperlane_core::*;
+
+mock! {
+    pub CheckpointAttestationContract {
+        fn _domain(&self) -> &HyperlaneDomain;
+        fn _provider(&self) -> Box<dyn HyperlaneProvider>;
+        fn _address(&self) -> H256;
+        fn _latest_attested_index(
+            &self,
+            validator: H256,
+            origin: u32,
+            merkle_tree_hook: H256,
+        ) -> ChainResult<Option<u32>>;
+        fn _attest(
+            &self,
+            checkpoint: &SignedCheckpointWithMessageId,
+        ) -> ChainResult<TxOutcome>;
+    }
+}
+
+impl HyperlaneChain for MockCheckpointAttestationContract {
+    fn domain(&self) -> &HyperlaneDomain {
+        self._domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self._provider()
+    }
+}
+
+impl HyperlaneContract for MockCheckpointAttestationContract {
+    fn address(&self) -> H256 {
+        self._address()
+    }
+}
+
+impl Debug for MockCheckpointAttestationContract {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CheckpointAttestation for MockCheckpointAttestationContract {
+    async fn latest_attested_index(
+        &self,
+        validator: H256,
+        origin: u32,
+        merkle_tree_hook: H256,
+    ) -> ChainResult<Option<u32>> {
+        self._latest_attested_index(validator, origin, merkle_tree_hook)
+    }
+
+    async fn attest(&self, checkpoint: &SignedCheckpointWithMessageId) -> ChainResult<TxOutcome> {
+        self._attest(checkpoint)
+    }
+}
@@ -35,6 +35,7 @@ mock! {
         pub fn _latest_checkpoint(&self, maybe_lag: Option<NonZeroU64>) -> ChainResult<Checkpoint> {}
 
         pub fn _default_ism(&self) -> ChainResult<H256> {}
+        pub fn _wait_for_default_ism_set(&self) -> bool {}
         pub fn _recipient_ism(&self, recipient: H256) -> ChainResult<H256> {}
 
         pub fn _delivered(&self, id: H256) -> ChainResult<bool> {}
@@ -46,6 +47,11 @@ mock! {
             tx_gas_limit: Option<U256>,
         ) -> ChainResult<TxOutcome> {}
 
+        pub fn _process_batch(
+            &self,
+            messages: &[BatchItem<HyperlaneMessage>],
+        ) -> ChainResult<TxOutcome> {}
+
         pub fn process_estimate_costs(
             &self,
             message: &HyperlaneMessage,
@@ -76,6 +82,10 @@ impl Mailbox for MockMailboxContract {
         self._default_ism()
     }
 
+    async fn wait_for_default_ism_set(&self) -> bool {
+        self._wait_for_default_ism_set()
+    }
+
     async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
         self._recipient_ism(recipient)
     }
@@ -97,7 +107,7 @@ impl Mailbox for MockMailboxContract {
         &self,
         messages: &[BatchItem<HyperlaneMessage>],
     ) -> ChainResult<TxOutcome> {
-        self.process_batch(messages).await
+        self._process_batch(messages)
     }
 
     async fn process_estimate_costs(
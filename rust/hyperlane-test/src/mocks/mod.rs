@@ -1,6 +1,8 @@
 /// Mock mailbox contract
+pub mod checkpoint_attestation;
 pub mod mailbox;
 pub mod validator_announce;
 
+pub use checkpoint_attestation::MockCheckpointAttestationContract;
 pub use mailbox::MockMailboxContract;
 pub use validator_announce::MockValidatorAnnounceContract;
@@ -42,4 +42,11 @@ impl KeyPair {
     pub fn addr(&self, hrp: &str) -> String {
         pub_to_addr(&self.pub_key_to_binary(), hrp)
     }
+
+    /// The ethereum address of the key, which validators sign checkpoints as
+    pub fn eth_addr(&self) -> String {
+        let public_key = self.priv_key.verifying_key().to_encoded_point(false);
+        let hash = hyperlane_cosmwasm_interface::types::keccak256_hash(&public_key.as_bytes()[1..]);
+        hex::encode(&hash.as_slice()[12..])
+    }
 }
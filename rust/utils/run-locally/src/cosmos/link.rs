@@ -79,33 +79,17 @@ pub struct SetValidatorsMsg {
     pub validators: Vec<String>,
 }
 
-fn link_network(
+/// Configure `network` to send messages to and receive messages from
+/// `target_domain`, whose messages are verified with the signatures of
+/// `validators`
+pub fn link_network(
     cli: &OsmosisCLI,
     network: &CosmosNetwork,
-    hrp: &str,
     linker: &str,
-    validator: &KeyPair,
     target_domain: u32,
+    validators: Vec<String>,
 ) {
-    let validator_addr = validator.addr(hrp);
-
-    let dest_domain = if network.domain == 99990 {
-        99991
-    } else {
-        99990
-    };
-
-    // hook routing
-
     // link src chain
-    let public_key = validator.priv_key.verifying_key().to_encoded_point(false);
-    let public_key = public_key.as_bytes();
-
-    let hash = hyperlane_cosmwasm_interface::types::keccak256_hash(&public_key[1..]);
-
-    let mut bytes = [0u8; 20];
-    bytes.copy_from_slice(&hash.as_slice()[12..]);
-
     cli.wasm_execute(
         &network.launch_resp.endpoint,
         linker,
@@ -114,7 +98,7 @@ fn link_network(
             set_validators: SetValidatorsMsg {
                 threshold: 1,
                 domain: target_domain,
-                validators: vec![hex::encode(bytes).to_string()],
+                validators,
             },
         },
         vec![],
@@ -167,7 +151,7 @@ fn link_network(
         RemoteGasDataConfigExecute {
             set_remote_gas_data_configs: RemoteGasDataConfigExecuteInner {
                 configs: vec![RemoteGasDataConfig {
-                    remote_domain: dest_domain,
+                    remote_domain: target_domain,
                     token_exchange_rate: "10000".to_string(),
                     gas_price: "1000000000".to_string(),
                 }],
@@ -213,14 +197,6 @@ fn link_network(
         vec![],
     );
 
-    cli.bank_send(
-        &network.launch_resp.endpoint,
-        linker,
-        &validator_addr,
-        "osmo1l83956lgpak5sun7ggupls7rk7p5cr95499jdf",
-        "10000000uosmo",
-    );
-
     // TODO
     // cli.wasm_execute(
     //     &network.launch_resp.endpoint,
@@ -247,6 +223,24 @@ pub fn link_networks(
 
     let keypair = src_cli.get_keypair(validator);
 
-    link_network(&src_cli, src, "osmo", linker, &keypair, dst.domain);
-    link_network(&dst_cli, dst, "osmo", linker, &keypair, src.domain);
+    link_network(&src_cli, src, linker, dst.domain, vec![keypair.eth_addr()]);
+    fund_validator(&src_cli, src, linker, &keypair);
+    link_network(&dst_cli, dst, linker, src.domain, vec![keypair.eth_addr()]);
+    fund_validator(&dst_cli, dst, linker, &keypair);
+}
+
+/// Send the validator funds to announce itself with
+pub fn fund_validator(
+    cli: &OsmosisCLI,
+    network: &CosmosNetwork,
+    linker: &str,
+    validator: &KeyPair,
+) {
+    cli.bank_send(
+        &network.launch_resp.endpoint,
+        linker,
+        &validator.addr("osmo"),
+        "osmo1l83956lgpak5sun7ggupls7rk7p5cr95499jdf",
+        "10000000uosmo",
+    );
 }
@@ -5,6 +5,7 @@ use std::time::{Duration, Instant};
 use std::{env, fs};
 
 use cosmwasm_schema::cw_serde;
+use hyperlane_core::H256;
 use hyperlane_cosmos::RawCosmosAmount;
 use hyperlane_cosmwasm_interface::types::bech32_decode;
 use macro_rules_attribute::apply;
@@ -24,13 +25,14 @@ use rpc::*;
 use types::*;
 use utils::*;
 
-use crate::cosmos::link::link_networks;
+use crate::cosmos::link::{fund_validator, link_network, link_networks};
 use crate::logging::log;
 use crate::metrics::agent_balance_sum;
 use crate::program::Program;
 use crate::utils::{as_task, concat_path, stop_child, AgentHandles, TaskHandle};
 use crate::{fetch_metric, AGENT_BIN_PATH};
 use cli::{OsmosisCLI, OsmosisEndpoint};
+use crypto::KeyPair;
 
 use self::deploy::deploy_cw_hyperlane;
 use self::source::{CLISource, CodeSource};
@@ -57,6 +59,9 @@ fn default_keys<'a>() -> [(&'a str, &'a str); 6] {
     ]
 }
 
+/// The key the contracts are deployed, linked and messages dispatched with
+const COSMOS_LINKER: &str = KEY_VALIDATOR.0;
+
 const CW_HYPERLANE_GIT: &str = "https://github.com/hyperlane-xyz/cosmwasm";
 const CW_HYPERLANE_VERSION: &str = "v0.0.6";
 
@@ -307,6 +312,137 @@ fn launch_cosmos_relayer(
 const ENV_CLI_PATH_KEY: &str = "E2E_OSMOSIS_CLI_PATH";
 const ENV_CW_HYPERLANE_PATH_KEY: &str = "E2E_CW_HYPERLANE_PATH";
 
+/// Install the osmosis node and the hyperlane contracts, from the local paths
+/// in the environment if they're set
+pub fn install_cosmos_from_env() -> (PathBuf, BTreeMap<String, PathBuf>) {
+    let cli_src = Some(
+        env::var(ENV_CLI_PATH_KEY)
+            .as_ref()
+            .map(|v| CLISource::local(v))
+            .unwrap_or_default(),
+    );
+
+    let code_src = Some(
+        env::var(ENV_CW_HYPERLANE_PATH_KEY)
+            .as_ref()
+            .map(|v| CodeSource::local(v))
+            .unwrap_or_default(),
+    );
+
+    install_cosmos(None, cli_src, None, code_src)
+}
+
+/// Launch a single node with the hyperlane contracts deployed on it, as the
+/// chain `cosmostest{domain}`
+pub fn launch_cosmos_network(
+    osmosisd: &Path,
+    codes: BTreeMap<String, PathBuf>,
+    domain: u32,
+    node_port_base: u32,
+    metrics_port: u32,
+) -> CosmosNetwork {
+    let chain_id = format!("cosmos-test-{domain}");
+    let launch_resp = launch_cosmos_node(CosmosConfig {
+        cli_path: osmosisd.to_path_buf(),
+        home_path: None,
+        codes,
+        node_addr_base: "tcp://0.0.0.0".to_string(),
+        node_port_base,
+        moniker: "localnet".to_string(),
+        chain_id: chain_id.clone(),
+    })
+    .join();
+    let deployments = deploy_cw_hyperlane(
+        launch_resp.cli(osmosisd),
+        launch_resp.endpoint.clone(),
+        COSMOS_LINKER.to_string(),
+        launch_resp.codes.clone(),
+        domain,
+    )
+    .join();
+    (launch_resp, deployments, chain_id, metrics_port, domain).into()
+}
+
+/// Link a network to a chain of another protocol, whose messages are verified
+/// with the signatures of `validators`. Returns the key the validator of the
+/// network signs with.
+pub fn link_remote_chain(
+    osmosisd: &Path,
+    network: &CosmosNetwork,
+    remote_domain: u32,
+    validators: Vec<String>,
+) -> KeyPair {
+    let cli = network.launch_resp.cli(osmosisd);
+    let validator = cli.get_keypair(KEY_HPL_VALIDATOR.0);
+    link_network(&cli, network, COSMOS_LINKER, remote_domain, validators);
+    fund_validator(&cli, network, COSMOS_LINKER, &validator);
+    validator
+}
+
+/// The sender of the messages dispatched by `dispatch_cosmos_message`, as it
+/// appears in the messages
+pub fn cosmos_dispatcher(osmosisd: &Path, network: &CosmosNetwork) -> H256 {
+    let addr = network.launch_resp.cli(osmosisd).get_addr(COSMOS_LINKER);
+    let bytes = bech32_decode(&addr).unwrap();
+    let mut sender = H256::zero();
+    sender.as_bytes_mut()[32 - bytes.len()..].copy_from_slice(&bytes);
+    sender
+}
+
+/// Dispatch a message from the mailbox of the network, paying for its gas
+pub fn dispatch_cosmos_message(
+    osmosisd: &Path,
+    network: &CosmosNetwork,
+    destination: u32,
+    recipient: H256,
+    body: &[u8],
+) {
+    network.launch_resp.cli(osmosisd).wasm_execute(
+        &network.launch_resp.endpoint,
+        COSMOS_LINKER,
+        &network.deployments.mailbox,
+        MockDispatch {
+            dispatch: MockDispatchInner {
+                dest_domain: destination,
+                recipient_addr: hex::encode(recipient),
+                msg_body: hex::encode(body),
+                hook: None,
+                metadata: "".to_string(),
+            },
+        },
+        vec![RawCosmosAmount {
+            denom: "uosmo".to_string(),
+            amount: 25_000_000.to_string(),
+        }],
+    );
+}
+
+/// Write the agent config of the networks to `path`, returning the config
+/// of each chain by name
+pub fn write_cosmos_agent_config(
+    osmosisd: &Path,
+    networks: &[&CosmosNetwork],
+    path: &Path,
+) -> BTreeMap<String, AgentConfig> {
+    let agent_config_out = AgentConfigOut {
+        chains: networks
+            .iter()
+            .map(|network| {
+                (
+                    format!("cosmostest{}", network.domain),
+                    AgentConfig::new(osmosisd.to_path_buf(), KEY_HPL_VALIDATOR.0, network),
+                )
+            })
+            .collect(),
+    };
+    fs::write(
+        path,
+        serde_json::to_string_pretty(&agent_config_out).unwrap(),
+    )
+    .unwrap();
+    agent_config_out.chains
+}
+
 #[allow(dead_code)]
 fn run_locally() {
     const TIMEOUT_SECS: u64 = 60 * 10;
@@ -325,21 +461,7 @@ fn run_locally() {
         .run()
         .join();
 
-    let cli_src = Some(
-        env::var(ENV_CLI_PATH_KEY)
-            .as_ref()
-            .map(|v| CLISource::local(v))
-            .unwrap_or_default(),
-    );
-
-    let code_src = Some(
-        env::var(ENV_CW_HYPERLANE_PATH_KEY)
-            .as_ref()
-            .map(|v| CodeSource::local(v))
-            .unwrap_or_default(),
-    );
-
-    let (osmosisd, codes) = install_cosmos(None, cli_src, None, code_src);
+    let (osmosisd, codes) = install_cosmos_from_env();
     let addr_base = "tcp://0.0.0.0";
     let default_config = CosmosConfig {
         cli_path: osmosisd.clone(),
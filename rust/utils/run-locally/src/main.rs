@@ -44,6 +44,7 @@ use crate::{
 
 mod config;
 mod cosmos;
+mod cross_protocol;
 mod ethereum;
 mod invariants;
 mod logging;
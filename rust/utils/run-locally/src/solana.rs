@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use std::thread::sleep;
 use std::time::Duration;
 
+use hyperlane_core::H256;
 use macro_rules_attribute::apply;
 use regex::Regex;
 use tempfile::{tempdir, NamedTempFile};
@@ -66,6 +67,17 @@ const SOLANA_REMOTE_CHAIN_ID: &str = "13376";
 pub const SOLANA_CHECKPOINT_LOCATION: &str =
     "/tmp/test_sealevel_checkpoints_0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
 
+/// Address of the validator of sealeveltest1
+pub const SOLANA_VALIDATOR_ADDRESS: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+const SOLANA_LOCAL_MAILBOX_PROGRAM_ID: &str = "692KZJaoe2KRcD6uhCQDLLXnLNA5ZLnfvdqjE4aX9iu1";
+const SOLANA_LOCAL_ISM_PROGRAM_ID: &str = "2YjtZDiUoptoSsA5eVrDCcX6wxNK6YoEVW7y82x5Z2fw";
+const SOLANA_NATIVE_WARP_ROUTE_PROGRAM_ID: &str = "CGn8yNtSD3aTTqJfYhUb6s1aVTN75NzwtsFKo1e83aga";
+
+/// The native warp route of sealeveltest1, as the recipient of messages
+pub const SOLANA_NATIVE_WARP_ROUTE: &str =
+    "0xa77b4e2ed231894cc8cb8eee21adcc705d8489bccc6b2fcf40a358de23e60b7b";
+
 const SOLANA_OVERHEAD_CONFIG_FILE: &str = "sealevel/environments/local-e2e/overheads.json";
 
 // Install the CLI tools and return the path to the bin dir.
@@ -258,7 +270,7 @@ pub fn start_solana_test_validator(
         .cmd("multisig-ism-message-id")
         .cmd("set-validators-and-threshold")
         .arg("domain", SOLANA_LOCAL_CHAIN_ID)
-        .arg("validators", "0x70997970c51812dc3a010c7d01b50e0d17dc79c8")
+        .arg("validators", SOLANA_VALIDATOR_ADDRESS)
         .arg("threshold", "1")
         .arg("program-id", "4RSV6iyqW9X66Xq3RDCVsKJ7hMba5uv6XP8ttgxjVUB1")
         .run()
@@ -267,7 +279,7 @@ pub fn start_solana_test_validator(
     sealevel_client
         .cmd("validator-announce")
         .cmd("announce")
-        .arg("validator", "0x70997970c51812dc3a010c7d01b50e0d17dc79c8")
+        .arg("validator", SOLANA_VALIDATOR_ADDRESS)
         .arg(
             "storage-location",
             format!("file://{SOLANA_CHECKPOINT_LOCATION}")
@@ -286,16 +298,7 @@ pub fn initiate_solana_hyperlane_transfer(
     solana_cli_tools_path: PathBuf,
     solana_config_path: PathBuf,
 ) -> String {
-    let sender = Program::new(concat_path(&solana_cli_tools_path, "solana"))
-        .arg("config", solana_config_path.to_str().unwrap())
-        .arg("keypair", SOLANA_KEYPAIR)
-        .cmd("address")
-        .run_with_output()
-        .join()
-        .get(0)
-        .expect("failed to get sender address")
-        .trim()
-        .to_owned();
+    let sender = solana_sender_address(&solana_cli_tools_path, &solana_config_path);
 
     let output = sealevel_client(&solana_cli_tools_path, &solana_config_path)
         .cmd("token")
@@ -325,6 +328,76 @@ pub fn initiate_solana_hyperlane_transfer(
     message_id
 }
 
+/// The base58 address of the account messages are sent from
+pub fn solana_sender_address(solana_cli_tools_path: &Path, solana_config_path: &Path) -> String {
+    Program::new(concat_path(solana_cli_tools_path, "solana"))
+        .arg("config", solana_config_path.to_str().unwrap())
+        .arg("keypair", SOLANA_KEYPAIR)
+        .cmd("address")
+        .run_with_output()
+        .join()
+        .get(0)
+        .expect("failed to get sender address")
+        .trim()
+        .to_owned()
+}
+
+/// Set the validators the multisig ISM of sealeveltest1 verifies messages
+/// from `domain` with
+pub fn set_solana_ism_validators(
+    solana_cli_tools_path: &Path,
+    solana_config_path: &Path,
+    domain: u32,
+    validators: &[String],
+) {
+    sealevel_client(solana_cli_tools_path, solana_config_path)
+        .cmd("multisig-ism-message-id")
+        .cmd("set-validators-and-threshold")
+        .arg("domain", domain.to_string())
+        .arg("validators", validators.join(","))
+        .arg("threshold", "1")
+        .arg("program-id", SOLANA_LOCAL_ISM_PROGRAM_ID)
+        .run()
+        .join();
+}
+
+/// Enroll `router` as the warp route of `domain` on the native warp route of
+/// sealeveltest1, so that its transfers are accepted
+pub fn enroll_solana_remote_router(
+    solana_cli_tools_path: &Path,
+    solana_config_path: &Path,
+    domain: u32,
+    router: H256,
+) {
+    sealevel_client(solana_cli_tools_path, solana_config_path)
+        .cmd("token")
+        .cmd("enroll-remote-router")
+        .arg("program-id", SOLANA_NATIVE_WARP_ROUTE_PROGRAM_ID)
+        .cmd(domain.to_string())
+        .cmd(format!("{router:?}"))
+        .run()
+        .join();
+}
+
+/// Dispatch a message from the mailbox of sealeveltest1 without paying for it
+pub fn dispatch_solana_message(
+    solana_cli_tools_path: &Path,
+    solana_config_path: &Path,
+    destination: u32,
+    recipient: H256,
+    body: &str,
+) {
+    sealevel_client(solana_cli_tools_path, solana_config_path)
+        .cmd("mailbox")
+        .cmd("send")
+        .arg("program-id", SOLANA_LOCAL_MAILBOX_PROGRAM_ID)
+        .arg("destination", destination.to_string())
+        .arg("recipient", bs58::encode(recipient).into_string())
+        .arg("message", body)
+        .run()
+        .join();
+}
+
 fn get_message_id_from_logs(logs: Vec<String>) -> Option<String> {
     let message_id_regex = Regex::new(r"Dispatched message to \d+, ID 0x([0-9a-fA-F]+)").unwrap();
     for log in logs {
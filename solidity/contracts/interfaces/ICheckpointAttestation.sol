@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity >=0.6.11;
+
+interface ICheckpointAttestation {
+    /**
+     * @notice Emitted when a validator's signed checkpoint is attested
+     * @param validator The validator that signed the checkpoint
+     * @param origin The origin domain of the checkpoint
+     * @param merkleTreeHook The origin merkle tree hook as bytes32
+     * @param root The root of the checkpoint
+     * @param index The index of the checkpoint
+     * @param messageId The message ID of the checkpoint
+     */
+    event CheckpointAttested(
+        address indexed validator,
+        uint32 indexed origin,
+        bytes32 indexed merkleTreeHook,
+        bytes32 root,
+        uint32 index,
+        bytes32 messageId
+    );
+
+    /**
+     * @notice Records a checkpoint signed by a validator as its latest one.
+     * Anyone may submit the checkpoint, the validator is recovered from the
+     * signature.
+     * @param _origin The origin domain of the checkpoint
+     * @param _merkleTreeHook The origin merkle tree hook as bytes32
+     * @param _root The root of the checkpoint
+     * @param _index The index of the checkpoint, which must be higher than
+     * the index of the latest checkpoint attested by the validator
+     * @param _messageId The message ID of the checkpoint
+     * @param _signature The validator's signature of the checkpoint
+     * @return The validator that signed the checkpoint
+     */
+    function attest(
+        uint32 _origin,
+        bytes32 _merkleTreeHook,
+        bytes32 _root,
+        uint32 _index,
+        bytes32 _messageId,
+        bytes calldata _signature
+    ) external returns (address);
+
+    /**
+     * @notice Returns the latest checkpoint attested by a validator
+     * @param _validator The validator that signed the checkpoint
+     * @param _origin The origin domain of the checkpoint
+     * @param _merkleTreeHook The origin merkle tree hook as bytes32
+     * @return root The root of the checkpoint
+     * @return index The index of the checkpoint
+     * @return messageId The message ID of the checkpoint
+     * @return timestamp The time the checkpoint was attested at, zero if
+     * the validator never attested a checkpoint of the merkle tree hook
+     */
+    function latestCheckpoint(
+        address _validator,
+        uint32 _origin,
+        bytes32 _merkleTreeHook
+    )
+        external
+        view
+        returns (
+            bytes32 root,
+            uint32 index,
+            bytes32 messageId,
+            uint256 timestamp
+        );
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity >=0.8.0;
+
+// ============ Internal Imports ============
+import {ICheckpointAttestation} from "../../interfaces/ICheckpointAttestation.sol";
+import {CheckpointLib} from "../../libs/CheckpointLib.sol";
+
+// ============ External Imports ============
+import {ECDSA} from "@openzeppelin/contracts/utils/cryptography/ECDSA.sol";
+
+/**
+ * @title CheckpointAttestation
+ * @notice Stores the latest checkpoint signed by each validator, so their
+ * liveness can be verified on-chain and ISMs can read fresher roots than
+ * those delivered with messages.
+ */
+contract CheckpointAttestation is ICheckpointAttestation {
+    // ============ Structs ============
+
+    struct Attestation {
+        bytes32 root;
+        uint32 index;
+        bytes32 messageId;
+        uint256 timestamp;
+    }
+
+    // ============ Private Storage ============
+
+    // Latest attestation of each validator, by checkpoint domain hash
+    mapping(address => mapping(bytes32 => Attestation)) private attestations;
+
+    // ============ External Functions ============
+
+    /// @inheritdoc ICheckpointAttestation
+    function attest(
+        uint32 _origin,
+        bytes32 _merkleTreeHook,
+        bytes32 _root,
+        uint32 _index,
+        bytes32 _messageId,
+        bytes calldata _signature
+    ) external returns (address) {
+        bytes32 _digest = CheckpointLib.digest(
+            _origin,
+            _merkleTreeHook,
+            _root,
+            _index,
+            _messageId
+        );
+        address _validator = ECDSA.recover(_digest, _signature);
+
+        Attestation storage _latest = attestations[_validator][
+            CheckpointLib.domainHash(_origin, _merkleTreeHook)
+        ];
+        require(
+            _latest.timestamp == 0 || _index > _latest.index,
+            "!newer checkpoint"
+        );
+        _latest.root = _root;
+        _latest.index = _index;
+        _latest.messageId = _messageId;
+        _latest.timestamp = block.timestamp;
+
+        emit CheckpointAttested(
+            _validator,
+            _origin,
+            _merkleTreeHook,
+            _root,
+            _index,
+            _messageId
+        );
+        return _validator;
+    }
+
+    /// @inheritdoc ICheckpointAttestation
+    function latestCheckpoint(
+        address _validator,
+        uint32 _origin,
+        bytes32 _merkleTreeHook
+    )
+        external
+        view
+        returns (
+            bytes32 root,
+            uint32 index,
+            bytes32 messageId,
+            uint256 timestamp
+        )
+    {
+        Attestation storage _latest = attestations[_validator][
+            CheckpointLib.domainHash(_origin, _merkleTreeHook)
+        ];
+        return (
+            _latest.root,
+            _latest.index,
+            _latest.messageId,
+            _latest.timestamp
+        );
+    }
+}
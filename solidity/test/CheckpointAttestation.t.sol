@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.13;
+
+import "forge-std/Test.sol";
+import "../contracts/isms/multisig/CheckpointAttestation.sol";
+import {CheckpointLib} from "../contracts/libs/CheckpointLib.sol";
+
+contract CheckpointAttestationTest is Test {
+    event CheckpointAttested(
+        address indexed validator,
+        uint32 indexed origin,
+        bytes32 indexed merkleTreeHook,
+        bytes32 root,
+        uint32 index,
+        bytes32 messageId
+    );
+
+    uint32 origin = 1;
+    bytes32 merkleTreeHook = bytes32(uint256(0xabc));
+    uint256 privateKey = 123456789;
+    CheckpointAttestation attestation;
+
+    function setUp() public {
+        attestation = new CheckpointAttestation();
+    }
+
+    function sign(
+        bytes32 root,
+        uint32 index,
+        bytes32 messageId
+    ) internal view returns (bytes memory) {
+        bytes32 digest = CheckpointLib.digest(
+            origin,
+            merkleTreeHook,
+            root,
+            index,
+            messageId
+        );
+        (uint8 v, bytes32 r, bytes32 s) = vm.sign(privateKey, digest);
+        return abi.encodePacked(r, s, v);
+    }
+
+    function testAttest() public {
+        address validator = vm.addr(privateKey);
+        bytes32 root = keccak256("root");
+        bytes32 messageId = keccak256("message");
+
+        vm.warp(1000);
+        vm.expectEmit(true, true, true, true, address(attestation));
+        emit CheckpointAttested(
+            validator,
+            origin,
+            merkleTreeHook,
+            root,
+            5,
+            messageId
+        );
+        assertEq(
+            attestation.attest(
+                origin,
+                merkleTreeHook,
+                root,
+                5,
+                messageId,
+                sign(root, 5, messageId)
+            ),
+            validator
+        );
+
+        (
+            bytes32 latestRoot,
+            uint32 latestIndex,
+            bytes32 latestMessageId,
+            uint256 timestamp
+        ) = attestation.latestCheckpoint(validator, origin, merkleTreeHook);
+        assertEq(latestRoot, root);
+        assertEq(latestIndex, 5);
+        assertEq(latestMessageId, messageId);
+        assertEq(timestamp, 1000);
+
+        // Other merkle tree hooks are tracked separately
+        (, , , timestamp) = attestation.latestCheckpoint(
+            validator,
+            origin,
+            bytes32(0)
+        );
+        assertEq(timestamp, 0);
+    }
+
+    function testAttest_revertsWhenNotNewer() public {
+        bytes32 root = keccak256("root");
+        bytes32 messageId = keccak256("message");
+        bytes memory signature = sign(root, 5, messageId);
+        attestation.attest(
+            origin,
+            merkleTreeHook,
+            root,
+            5,
+            messageId,
+            signature
+        );
+
+        vm.expectRevert("!newer checkpoint");
+        attestation.attest(
+            origin,
+            merkleTreeHook,
+            root,
+            5,
+            messageId,
+            signature
+        );
+    }
+
+    function testAttest_index0() public {
+        bytes32 root = keccak256("root");
+        bytes32 messageId = keccak256("message");
+        attestation.attest(
+            origin,
+            merkleTreeHook,
+            root,
+            0,
+            messageId,
+            sign(root, 0, messageId)
+        );
+        (, uint32 index, , uint256 timestamp) = attestation.latestCheckpoint(
+            vm.addr(privateKey),
+            origin,
+            merkleTreeHook
+        );
+        assertEq(index, 0);
+        assertGt(timestamp, 0);
+    }
+}
@@ -12,6 +12,7 @@ copy() {
 copy interfaces/IMailbox && \
 copy interfaces/IInterchainGasPaymaster && \
 copy interfaces/IValidatorAnnounce && \
+copy interfaces/ICheckpointAttestation && \
 copy interfaces/IInterchainSecurityModule && \
 copy interfaces/isms/IMultisigIsm && \
 copy interfaces/isms/IRoutingIsm && \
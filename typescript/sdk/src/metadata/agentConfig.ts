@@ -34,6 +34,7 @@ export enum AgentLogLevel {
 
 export enum AgentLogFormat {
   Json = 'json',
+  Structured = 'structured',
   Compact = 'compact',
   Full = 'full',
   Pretty = 'pretty',
@@ -49,6 +50,10 @@ export enum AgentSignerKeyType {
   Hex = 'hexKey',
   Node = 'node',
   Cosmos = 'cosmosKey',
+  Gcp = 'gcp',
+  Web3Signer = 'web3signer',
+  Keystore = 'keystore',
+  Ledger = 'ledger',
 }
 
 const AgentSignerHexKeySchema = z
@@ -66,6 +71,79 @@ const AgentSignerAwsKeySchema = z
   .describe(
     'An AWS signer. Note that AWS credentials must be inserted into the env separately.',
   );
+const AgentSignerGcpKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Gcp),
+    id: z
+      .string()
+      .describe(
+        'The resource name of the GCP Cloud KMS key version, i.e. projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*',
+      ),
+  })
+  .describe(
+    'A GCP Cloud KMS signer. Note that GCP credentials are looked up from the env or metadata server separately.',
+  );
+const AgentSignerWeb3SignerSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Web3Signer),
+    url: z.string().url().describe('The url of the remote signer'),
+    address: ZHash.describe('The address of the key to sign with'),
+    clientCert: z
+      .string()
+      .optional()
+      .describe(
+        'Path to a PKCS#12 archive with the TLS client certificate and key',
+      ),
+    clientCertPassword: z
+      .string()
+      .optional()
+      .describe('The password of the client certificate archive'),
+    caCert: z
+      .string()
+      .optional()
+      .describe(
+        "Path to a PEM certificate of the CA that issued the remote signer's certificate",
+      ),
+  })
+  .describe(
+    'A remote Web3Signer-compatible signer, so that the agent never holds the key.',
+  );
+const AgentSignerKeystoreSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Keystore),
+    path: z
+      .string()
+      .describe('Path to an encrypted Web3 Secret Storage (v3) keystore file'),
+    passwordEnv: z
+      .string()
+      .optional()
+      .describe('The environment variable the keystore password is read from'),
+    passwordFile: z
+      .string()
+      .optional()
+      .describe('Path to a file the keystore password is read from'),
+  })
+  .refine(
+    ({ passwordEnv, passwordFile }) => !passwordEnv !== !passwordFile,
+    'Exactly one of passwordEnv and passwordFile is required',
+  )
+  .describe(
+    'A local key in an encrypted keystore, decrypted when the agent starts.',
+  );
+const AgentSignerLedgerSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Ledger),
+    derivationPath: z
+      .string()
+      .startsWith('m/')
+      .optional()
+      .describe(
+        "BIP-32 derivation path of the key. Defaults to m/44'/60'/0'/0/0.",
+      ),
+  })
+  .describe(
+    'A key held by a Ledger hardware wallet connected over USB. Every transaction has to be approved on the device.',
+  );
 const AgentSignerCosmosKeySchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Cosmos),
@@ -82,12 +160,22 @@ const AgentSignerNodeSchema = z
 const AgentSignerSchema = z.union([
   AgentSignerHexKeySchema,
   AgentSignerAwsKeySchema,
+  AgentSignerGcpKeySchema,
+  AgentSignerWeb3SignerSchema,
+  AgentSignerKeystoreSchema,
+  AgentSignerLedgerSchema,
   AgentSignerCosmosKeySchema,
   AgentSignerNodeSchema,
 ]);
 
 export type AgentSignerHexKey = z.infer<typeof AgentSignerHexKeySchema>;
 export type AgentSignerAwsKey = z.infer<typeof AgentSignerAwsKeySchema>;
+export type AgentSignerGcpKey = z.infer<typeof AgentSignerGcpKeySchema>;
+export type AgentSignerWeb3Signer = z.infer<
+  typeof AgentSignerWeb3SignerSchema
+>;
+export type AgentSignerKeystore = z.infer<typeof AgentSignerKeystoreSchema>;
+export type AgentSignerLedger = z.infer<typeof AgentSignerLedgerSchema>;
 export type AgentSignerCosmosKey = z.infer<typeof AgentSignerNodeSchema>;
 export type AgentSignerNode = z.infer<typeof AgentSignerNodeSchema>;
 export type AgentSigner = z.infer<typeof AgentSignerSchema>;
@@ -114,6 +202,12 @@ const AgentCosmosChainMetadataSchema = z.object({
     .positive()
     .lte(32)
     .describe('The number of bytes used to represent a contract address.'),
+  feeGranter: z
+    .string()
+    .optional()
+    .describe(
+      'The bech32 address of an account that granted the signer a fee allowance. If specified, transaction fees are paid by this account instead of the signer.',
+    ),
 });
 
 export type AgentCosmosGasPrice = z.infer<
@@ -134,6 +228,182 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
       .nativeEnum(RpcConsensusType)
       .describe('The consensus type to use when multiple RPCs are configured.')
       .optional(),
+    rpcHealthScoring: z
+      .object({
+        window: ZUint.optional().describe(
+          'Number of most recent requests the error rate and latency are computed over.',
+        ),
+        minSamples: ZUint.optional().describe(
+          'Number of requests below which the error rate and latency are not considered yet.',
+        ),
+        maxErrorRate: z
+          .number()
+          .min(0)
+          .max(1)
+          .optional()
+          .describe(
+            'Fraction of failed requests above which an RPC is demoted.',
+          ),
+        maxLatencyMs: ZUint.optional().describe(
+          'Average request latency in milliseconds above which an RPC is demoted.',
+        ),
+        maxBlockLag: ZUint.optional().describe(
+          'Number of blocks an RPC can be behind the most advanced one before it is demoted.',
+        ),
+        probationSecs: ZUint.optional().describe(
+          'How long a demoted RPC is kept at the back of the queue before it is promoted back.',
+        ),
+        blockHeightIntervalSecs: ZUint.optional().describe(
+          'How often the block height of an RPC in use is queried to compute its lag.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Demote unhealthy RPCs by their error rate, latency and block lag. Only used with the fallback consensus type, and for both the gRPC and RPC urls of Cosmos chains.',
+      ),
+    rpcRateLimits: z
+      .array(
+        z.object({
+          url: z
+            .string()
+            .url()
+            .optional()
+            .describe(
+              'The RPC url the limit applies to. If not specified, it applies to every url without a limit of its own.',
+            ),
+          requestsPerSecond: z
+            .number()
+            .positive()
+            .describe('Sustained number of requests per second.'),
+          burst: ZNzUint.optional().describe(
+            'Number of requests that can be made at once after a quiet period. Defaults to the requests per second.',
+          ),
+        }),
+      )
+      .optional()
+      .describe(
+        'Client-side rate limits of the RPC urls, so that requests are delayed rather than rejected by the RPC.',
+      ),
+    circuitBreaker: z
+      .object({
+        failureThreshold: ZNzUint.describe(
+          'Number of consecutive failed calls after which calls to the chain fail fast.',
+        ),
+        cooldownSecs: ZNzUint.optional().describe(
+          'How long calls fail fast before a single call is let through to probe the chain. Defaults to 30.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Fail the indexing and provider calls to the chain fast after repeated failures, instead of waiting on a dead endpoint.',
+      ),
+    transactionReplacement: z
+      .object({
+        stuckAfterBlocks: ZNzUint.optional().describe(
+          'Number of blocks a transaction can remain unmined before it is considered stuck. Defaults to 10.',
+        ),
+        feeBumpPercent: ZUint.optional().describe(
+          'Percentage by which the fees of a stuck transaction are bumped when replacing it. Most nodes require at least 10. Defaults to 15.',
+        ),
+        maxReplacements: ZUint.optional().describe(
+          'Maximum number of fee-bumped replacements to attempt. Defaults to 3.',
+        ),
+        cancelWhenExhausted: z
+          .boolean()
+          .optional()
+          .describe(
+            'Free up the nonce with a zero-value transfer to self once maxReplacements is reached. Defaults to true.',
+          ),
+      })
+      .optional()
+      .describe(
+        'Replace delivery transactions that are not mined in time with fee-bumped ones. Submitted transactions are never replaced if not specified. Only supported on EVM chains.',
+      ),
+    priorityFee: z
+      .object({
+        percentile: z
+          .number()
+          .int()
+          .min(0)
+          .max(100)
+          .optional()
+          .describe(
+            'Percentile of the recently paid prioritization fees to pay. Defaults to 75.',
+          ),
+        minMicroLamports: ZUint.optional().describe(
+          'Minimum compute unit price, in micro-lamports. Defaults to 0.',
+        ),
+        maxMicroLamports: ZUint.optional().describe(
+          'Maximum compute unit price, in micro-lamports. Defaults to 1000000.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Attach a compute unit price to submitted transactions, determined from recently paid prioritization fees. Transactions are sent without a priority fee if not specified. Only supported on Sealevel chains.',
+      ),
+    jito: z
+      .object({
+        url: z
+          .string()
+          .url()
+          .describe(
+            'URL of the bundles endpoint of a Jito block engine, e.g. https://mainnet.block-engine.jito.wtf/api/v1/bundles.',
+          ),
+        tipLamports: ZUint.min(1000)
+          .optional()
+          .describe(
+            'Tip paid to Jito with each bundle, in lamports. Must be at least 1000. Defaults to 10000.',
+          ),
+        bundleTimeoutSecs: ZNzUint.optional().describe(
+          'How long to wait for a bundle to land before submitting through the RPC instead. Defaults to 30.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Submit process transactions as Jito bundles with a tip, so they land during congestion. Bundles that fail or do not land in time are resubmitted through the RPC. Only supported on Sealevel chains.',
+      ),
+    maxGasAmount: ZNzUint.optional().describe(
+      'Most gas units a submitted transaction may use. Defaults to 200000. Only supported on Aptos chains.',
+    ),
+    gasUnitPrice: ZNzUint.optional().describe(
+      "Price paid per gas unit of submitted transactions, in octas. Defaults to the fullnode's estimate. Only supported on Aptos chains.",
+    ),
+    gasBudget: ZNzUint.optional().describe(
+      'Gas budget of submitted transactions, in MIST. Defaults to 50000000. Only supported on Sui chains.',
+    ),
+    apiKey: z
+      .string()
+      .optional()
+      .describe(
+        'API key sent to the toncenter RPC, if it requires one. Only supported on TON chains.',
+      ),
+    messageValue: ZNzUint.optional().describe(
+      'Value attached to messages sent to contracts, in nanotons. Defaults to 100000000. Only supported on TON chains.',
+    ),
+    revertTraceMethod: z
+      .enum(['debug_traceCall', 'trace_call'])
+      .optional()
+      .describe(
+        "RPC method tracing a delivery simulation that reverted, to find its revert data when the node doesn't return it. Only supported on EVM chains.",
+      ),
+    feeAdapter: z
+      .enum(['opStack', 'arbitrum', 'scroll', 'linea'])
+      .optional()
+      .describe(
+        "Adapter for the L2 fees of the chain that generic gas estimation doesn't account for, used to quote gas enforcement and submit transactions. Arbitrum Nitro chains default to 'arbitrum'. Only supported on EVM chains.",
+      ),
+    knownContracts: z
+      .record(ZHash)
+      .optional()
+      .describe(
+        'Addresses of known contracts of the chain by name, e.g. warp routes. Their name is printed next to their address in logs and the scraper API.',
+      ),
+    maxInflightTransactions: ZNzUint.optional().describe(
+      'Most transactions the relayer may have submitted to the chain but not confirmed yet, so that one failure does not strand a long chain of dependent nonces. Not limited if not specified.',
+    ),
+    confirmationBatchSize: ZNzUint.optional().describe(
+      'Most deliveries the relayer checks in a single call when confirming the messages it submitted to the chain, e.g. through a multicall on EVM chains. Checked one by one if not specified.',
+    ),
     signer: AgentSignerSchema.optional().describe(
       'The signer to use for this chain',
     ),
@@ -151,6 +421,13 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
           .describe(
             'The indexing method to use for this chain; will attempt to choose a suitable default if not specified.',
           ),
+        wsUrl: z
+          .string()
+          .url()
+          .optional()
+          .describe(
+            'Websocket URL to subscribe to new logs with, so that new messages are indexed without waiting for the next poll. Supported on EVM chains, and on Cosmos chains with the Tendermint RPC websocket endpoint (e.g. ws://host:26657/websocket).',
+          ),
       })
       .optional(),
   })
@@ -170,6 +447,9 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
         if (
           ![
             AgentSignerKeyType.Hex,
+            AgentSignerKeyType.Gcp,
+            AgentSignerKeyType.Web3Signer,
+            AgentSignerKeyType.Ledger,
             signerType === AgentSignerKeyType.Aws,
             signerType === AgentSignerKeyType.Node,
           ].includes(signerType)
@@ -212,6 +492,50 @@ export const AgentConfigSchema = z.object({
     .describe(
       'The port to expose prometheus metrics on. Accessible via `GET /metrics`.',
     ),
+  metricsPush: z
+    .array(
+      z.object({
+        type: z
+          .enum(['pushgateway', 'remoteWrite'])
+          .describe(
+            'Whether to push to a Pushgateway or a remote-write endpoint.',
+          ),
+        url: z
+          .string()
+          .url()
+          .describe(
+            'The base url of the Pushgateway, or the full url of the remote-write endpoint.',
+          ),
+        job: z
+          .string()
+          .optional()
+          .describe(
+            'The Pushgateway job to push to. Defaults to the name of the agent.',
+          ),
+        intervalSecs: ZNzUint.optional().describe(
+          'Seconds between two pushes. Defaults to 15.',
+        ),
+        basicAuth: z
+          .object({
+            username: z.string(),
+            password: z.string(),
+          })
+          .optional()
+          .describe('HTTP basic auth credentials sent with every push.'),
+        bearerToken: z
+          .string()
+          .optional()
+          .describe('A bearer token sent with every push.'),
+        headers: z
+          .record(z.string())
+          .optional()
+          .describe('Additional headers to send with every push.'),
+      }),
+    )
+    .optional()
+    .describe(
+      'Targets to push the metrics to as well, for when the metrics port cannot be scraped.',
+    ),
   chains: z
     .record(AgentChainMetadataSchema)
     .describe('Chain metadata for all chains that the agent will index.')
@@ -244,8 +568,51 @@ export const AgentConfigSchema = z.object({
         .nativeEnum(AgentLogLevel)
         .optional()
         .describe("The log level to use for the agent's logs."),
+      otlp: z
+        .object({
+          endpoint: z
+            .string()
+            .url()
+            .describe(
+              'The OTLP/gRPC endpoint of the collector to export spans to.',
+            ),
+          headers: z
+            .record(z.string())
+            .optional()
+            .describe('Headers to send with every export.'),
+          samplingRatio: z
+            .number()
+            .min(0)
+            .max(1)
+            .optional()
+            .describe('The fraction of traces to export. Defaults to 1.'),
+          serviceName: z
+            .string()
+            .optional()
+            .describe(
+              'The service name of the spans. Defaults to the agent name.',
+            ),
+        })
+        .optional()
+        .describe('Export spans to an OpenTelemetry collector.'),
     })
     .optional(),
+  dbMaintenance: z
+    .object({
+      compactionIntervalSecs: ZNzUint.optional().describe(
+        'How often the local dbs are fully compacted. Never if not set.',
+      ),
+      maxSizeBytes: ZNzUint.optional().describe(
+        'Size above which a local db is compacted straight away and a warning is logged.',
+      ),
+      messageRetentionSecs: ZNzUint.optional().describe(
+        'How long the relayer keeps the retry and gas bookkeeping of a delivered message. Forever if not set.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Scheduled maintenance of the local dbs of the relayer and validator.',
+    ),
 });
 
 const CommaSeperatedChainList = z.string().regex(/^[a-z0-9]+(,[a-z0-9]+)*$/);
@@ -280,6 +647,16 @@ const GasPaymentEnforcementSchema = z.union([
 ]);
 export type GasPaymentEnforcement = z.infer<typeof GasPaymentEnforcementSchema>;
 
+const IgpClaimSchema = z.object({
+  chain: z.string().min(1).describe('The chain of the paymaster to claim from'),
+  threshold: ZUWei.optional().describe(
+    'Fees are only claimed once the paymaster holds more than this amount.',
+  ),
+  beneficiary: ZHash.optional().describe(
+    'If set, fees are only claimed if the paymaster sweeps them to this address.',
+  ),
+});
+
 const MetricAppContextSchema = z.object({
   name: z.string().min(1),
   matchingList: MatchingListSchema.describe(
@@ -287,6 +664,164 @@ const MetricAppContextSchema = z.object({
   ),
 });
 
+const LaneWeightSchema = z.object({
+  origin: ZUint.describe('The domain id of the origin chain of the lane'),
+  destination: ZUint.describe(
+    'The domain id of the destination chain of the lane',
+  ),
+  weight: ZNzUint.describe(
+    'Share of the submission capacity of the destination the lane gets, relative to the other lanes to it.',
+  ),
+});
+
+const HookMetadataSchema = z.object({
+  matchingList: MatchingListSchema.describe(
+    'A matching list, any message that matches belongs to the app.',
+  ),
+  msgValue: ZUWei.optional().describe(
+    'Value forwarded to the hooks. Defaults to 0.',
+  ),
+  gasLimit: ZUWei.optional().describe(
+    'Gas limit the app expects handle to be called with. Deliveries use at least this much gas.',
+  ),
+  refundAddress: ZHash.optional().describe(
+    'Address overpayments are refunded to. Defaults to the message sender.',
+  ),
+  custom: z
+    .string()
+    .regex(/^(0x)?([0-9a-fA-F]{2})*$/)
+    .optional()
+    .describe('Hex encoded hook-specific metadata.'),
+  deadline: ZUint.optional().describe(
+    'Seconds after being dispatched the messages of the app should be delivered in.',
+  ),
+});
+
+const MessageExpirySchema = z.object({
+  matchingList: MatchingListSchema.describe(
+    'A matching list, any message that matches expires after maxAge.',
+  ),
+  maxAge: ZUint.describe(
+    'Seconds after being dispatched a message is moved to the dead-letter store instead of being retried.',
+  ),
+});
+
+const GasOverheadSchema = z.object({
+  matchingList: MatchingListSchema.describe(
+    'A matching list, any message that matches belongs to the app.',
+  ),
+  overhead: ZUWei.describe(
+    'Gas added to the estimated gas limit of deliveries of the app.',
+  ),
+});
+
+const FixedGasLimitSchema = z.object({
+  matchingList: MatchingListSchema.describe(
+    'A matching list, any message that matches belongs to the app.',
+  ),
+  gasLimit: ZUWei.describe(
+    'Gas limit deliveries of the app use instead of an estimate.',
+  ),
+});
+
+const TenantSchema = z.object({
+  name: z.string().min(1),
+  matchingList: MatchingListSchema.describe(
+    'A matching list, any message that matches will be relayed for this tenant.',
+  ),
+  gasPaymentEnforcement: z
+    .array(GasPaymentEnforcementSchema)
+    .optional()
+    .describe(
+      'The gas payment enforcement of the messages of this tenant. Defaults to that of the relayer.',
+    ),
+  signers: z
+    .array(z.object({ chain: z.string().min(1), signer: AgentSignerSchema }))
+    .optional()
+    .describe(
+      'Signers to submit the messages of this tenant with on each destination, instead of those of the relayer.',
+    ),
+  budgets: z
+    .array(
+      z.object({
+        chain: z.string().min(1),
+        maxSpend: ZUWei.describe(
+          'Most gas the tenant may spend on the chain in a period, in the smallest unit of the native token.',
+        ),
+        period: ZUint.optional().describe(
+          'Length of a budget period in seconds. Defaults to a day.',
+        ),
+      }),
+    )
+    .optional()
+    .describe(
+      'Limits on what is spent on gas delivering the messages of this tenant.',
+    ),
+});
+
+const ConfirmationDepthSchema = z.object({
+  matchingList: MatchingListSchema.optional().describe(
+    'A matching list, any message that matches will wait for these confirmations. Matches every message if not set.',
+  ),
+  minTransferAmount: ZUWei.optional().describe(
+    'If set, only warp transfers of at least this amount match.',
+  ),
+  confirmations: z
+    .union([ZUint, z.literal('finalized')])
+    .describe(
+      'Confirmations the delivery waits for, including its block, or `finalized` to wait out the reorg period of the destination.',
+    ),
+});
+
+const DeliveryDeadlineSchema = z.object({
+  matchingList: MatchingListSchema.describe(
+    'A matching list, any message that matches has this deadline.',
+  ),
+  deadline: ZNzUint.describe(
+    'Time messages should be delivered in after being dispatched, in seconds.',
+  ),
+  maxFeeBumpPercent: ZUint.optional().describe(
+    'Percentage by which fees are bumped at the deadline. Defaults to 50.',
+  ),
+});
+
+const CustomIsmSchema = z.object({
+  moduleType: ZUint.lte(255)
+    .optional()
+    .describe(
+      'Module type the ISMs report. Matches any module type the relayer does not know if not set.',
+    ),
+  isms: z
+    .array(ZHash)
+    .optional()
+    .describe(
+      'Addresses of the ISMs. Matches any ISM of the module type if not set.',
+    ),
+  strategy: z
+    .discriminatedUnion('type', [
+      z.object({ type: z.literal('null') }),
+      z.object({
+        type: z.literal('http'),
+        url: z
+          .string()
+          .url()
+          .describe(
+            'Service POSTed the ISM, message and message id, which returns the metadata or 404 if it has none yet.',
+          ),
+      }),
+      z.object({
+        type: z.literal('messageBody'),
+        offset: ZUint.optional().describe(
+          'Byte of the message body the metadata starts at. Defaults to 0.',
+        ),
+        length: ZUint.optional().describe(
+          'Length of the metadata. Defaults to the rest of the body.',
+        ),
+      }),
+    ])
+    .describe('How the metadata of the ISMs is built'),
+});
+
 export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
@@ -330,61 +865,397 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .describe(
       'If true, allows local storage based checkpoint syncers. Not intended for production use.',
     ),
+  igpClaims: z
+    .union([z.array(IgpClaimSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Chains whose interchain gas paymaster fees the relayer claims periodically.',
+    ),
+  igpClaimInterval: ZNzUint.optional().describe(
+    'Seconds between attempts to claim paymaster fees. Defaults to one hour.',
+  ),
+  shutdownTimeout: ZNzUint.optional().describe(
+    'Seconds operations already submitted are confirmed for on shutdown before the relayer exits. Defaults to 60.',
+  ),
   metricAppContexts: z
     .union([z.array(MetricAppContextSchema), z.string().min(1)])
     .optional()
     .describe(
       'A list of app contexts and their matching lists to use for metrics. A message will be classified as the first matching app context.',
     ),
+  fairScheduling: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, the submission capacity of each destination is shared between the origins sending messages to it by their lane weights, instead of processing operations in priority order.',
+    ),
+  defaultLaneWeight: ZNzUint.optional().describe(
+    'Weight of any lane without an explicit weight in laneWeights. Defaults to 1.',
+  ),
+  laneWeights: z
+    .union([z.array(LaneWeightSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Weights of (origin -> destination) lanes used by fair scheduling.',
+    ),
+  hookMetadata: z
+    .union([z.array(HookMetadataSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Hook metadata of apps. A message uses the hook metadata of the first entry it matches.',
+    ),
+  messageExpiry: z
+    .union([z.array(MessageExpirySchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Maximum age of the messages of apps before they are dead-lettered. A message expires with the first entry it matches, or never.',
+    ),
+  gasOverheads: z
+    .union([z.array(GasOverheadSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Gas overheads of apps whose deliveries use more gas than estimated. A message uses the overhead of the first entry it matches.',
+    ),
+  fixedGasLimits: z
+    .union([z.array(FixedGasLimitSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Gas limits of apps whose gas usage cannot be estimated. A message uses the gas limit of the first entry it matches.',
+    ),
+  confirmationDepths: z
+    .union([z.array(ConfirmationDepthSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'How many confirmations deliveries wait for before they are complete. A message waits for the first entry it matches, or for none.',
+    ),
+  deliveryDeadlines: z
+    .union([z.array(DeliveryDeadlineSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Time messages should be delivered in. Messages nearing it are retried sooner and submitted with higher fees. A message has the deadline of the first entry it matches.',
+    ),
+  tenants: z
+    .union([z.array(TenantSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Tenants whose messages are relayed with their own signers, gas payment enforcement and budgets. A message is relayed for the first tenant it matches.',
+    ),
+  missedDeliveryFeed: z
+    .object({
+      url: z.string().url().describe('Base URL of the scraper HTTP API.'),
+      apiKey: z
+        .string()
+        .optional()
+        .describe('API key sent to the scraper, if it requires one.'),
+      minAge: ZUint.optional().describe(
+        'Only messages dispatched more than this many seconds ago are considered missed. Defaults to an hour.',
+      ),
+      interval: ZUint.optional().describe(
+        'How often to poll the scraper, in seconds. Defaults to 5 minutes.',
+      ),
+    })
+    .optional()
+    .describe(
+      "A scraper's feed of undelivered messages, whose messages the relayer's own indexing missed are relayed too.",
+    ),
+  customIsms: z
+    .union([z.array(CustomIsmSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'ISMs of module types the relayer does not know and how to build their metadata. An ISM uses the first entry it matches.',
+    ),
+  sloDeliveryTarget: ZUint.optional().describe(
+    'Target time to deliver messages in, in seconds, of which the share of deliveries that met it is exported per lane. Defaults to 5 minutes.',
+  ),
+  sloWindow: ZUint.optional().describe(
+    'Time window over which delivery times are aggregated into the SLO metrics of each lane, in seconds. Defaults to an hour.',
+  ),
 });
 
 export type RelayerConfig = z.infer<typeof RelayerAgentConfigSchema>;
 
 export const ScraperAgentConfigSchema = AgentConfigSchema.extend({
   db: z.string().min(1).describe('Database connection string'),
+  environment: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'Environment the rows of this scraper are stored in, so that the scrapers of several deployments, e.g. mainnet and testnet, can share a database. Defaults to `default`.',
+    ),
   chainsToScrape: CommaSeperatedChainList.describe(
     'Comma separated list of chain names to scrape',
   ),
+  apiKeys: z
+    .string()
+    .optional()
+    .describe(
+      'Comma separated `<name>:<key>[:<requests per minute>]` API keys the HTTP API requires one of. If not set, the API is open.',
+    ),
+  bodyDecoders: z
+    .array(
+      z.object({
+        app: z
+          .string()
+          .min(1)
+          .describe('Name of the app, stored with the decoded bodies'),
+        addresses: z
+          .array(
+            z.object({
+              domain: ZUint.describe('Domain id of the chain of the contract'),
+              address: ZHash,
+            }),
+          )
+          .describe(
+            'Contracts of the app, a message is decoded if its sender or recipient is one of them',
+          ),
+        schema: z
+          .discriminatedUnion('type', [
+            z.object({ type: z.literal('warpRoute') }),
+            z.object({ type: z.literal('interchainAccount') }),
+            z.object({
+              type: z.literal('abi'),
+              params: z
+                .array(
+                  z.object({
+                    name: z.string().min(1),
+                    type: z
+                      .string()
+                      .min(1)
+                      .describe('Solidity type of the parameter'),
+                  }),
+                )
+                .describe('ABI encoded parameters of the body'),
+            }),
+          ])
+          .describe('How the bodies of the messages of the app are laid out'),
+      }),
+    )
+    .optional()
+    .describe(
+      'Decoders of the message bodies of known apps, stored as JSON in the `decoded_body` column of messages. If a message matches multiple apps, whichever is first in the list is used.',
+    ),
+  priceFeed: z
+    .discriminatedUnion('type', [
+      z.object({
+        type: z.literal('fixed'),
+        prices: z
+          .record(z.number().nonnegative())
+          .describe('USD price of the native token of each chain, by name'),
+      }),
+      z.object({
+        type: z.literal('coinGecko'),
+        url: z
+          .string()
+          .url()
+          .optional()
+          .describe(
+            'Base url of the CoinGecko API, defaults to https://api.coingecko.com/api/v3',
+          ),
+        apiKey: z
+          .string()
+          .optional()
+          .describe('Sent as the `x-cg-pro-api-key` header'),
+        tokenIds: z
+          .record(z.string().min(1))
+          .describe('CoinGecko id of the native token of each chain, by name'),
+      }),
+    ])
+    .optional()
+    .describe(
+      'Source of the USD prices of native tokens, stored with the gas price of messages and deliveries when they are scraped. If not set, only gas prices are stored.',
+    ),
 });
 
 export type ScraperConfig = z.infer<typeof ScraperAgentConfigSchema>;
 
+export const KathyAgentConfigSchema = AgentConfigSchema.extend({
+  routes: z
+    .array(
+      z.object({
+        origin: z.string().min(1).describe('The chain to dispatch messages on'),
+        destination: z
+          .string()
+          .min(1)
+          .describe('The chain to deliver messages to'),
+        recipient: ZHash.describe(
+          'The recipient of the messages on the destination chain',
+        ),
+      }),
+    )
+    .min(1)
+    .describe('Pairs of chains to send messages between'),
+  messagesPerMinute: ZNzUint.lte(4294967295).optional().describe(
+    'Messages dispatched per minute on each route, defaults to 1',
+  ),
+  minBodySize: ZUint.optional().describe(
+    'Smallest size in bytes of the body of the messages, defaults to 32',
+  ),
+  maxBodySize: ZUint.optional().describe(
+    'Largest size in bytes of the body of the messages, defaults to `minBodySize`',
+  ),
+  gasPayment: z
+    .discriminatedUnion('type', [
+      z.object({ type: z.literal('none') }),
+      z.object({
+        type: z.literal('igp'),
+        gasAmount: ZUWei.describe('Destination gas to pay for per message'),
+      }),
+    ])
+    .optional()
+    .describe('How the dispatched messages are paid for, defaults to none'),
+  deliveryTimeout: ZNzUint.optional().describe(
+    'Seconds to wait for a message to be delivered before counting it as undelivered, defaults to 600',
+  ),
+  pollInterval: ZNzUint.optional().describe(
+    'Seconds between polls of the destination for deliveries, defaults to 5',
+  ),
+});
+
+export type KathyConfig = z.infer<typeof KathyAgentConfigSchema>;
+
+const CheckpointSyncerSchema = z.discriminatedUnion('type', [
+  z
+    .object({
+      type: z.literal('localStorage'),
+      path: z.string().min(1).describe('Path to the local storage location'),
+    })
+    .describe('A local checkpoint syncer'),
+  z
+    .object({
+      type: z.literal('s3'),
+      bucket: z.string().min(1),
+      region: z.string().min(1),
+      folder: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'The folder/key-prefix to use, defaults to the root of the bucket',
+        ),
+      batchCheckpoints: z
+        .boolean()
+        .optional()
+        .describe(
+          'Whether to also write checkpoints in gzipped batches of 1000 for faster backfills',
+        ),
+      retention: z
+        .object({
+          keepLatest: ZUint.optional().describe(
+            'Number of latest checkpoints kept as single objects. Older ones are deleted once their batch is written. Requires batchCheckpoints.',
+          ),
+          batchStorageClass: z
+            .string()
+            .min(1)
+            .optional()
+            .describe(
+              'The storage class to write batches with, e.g. STANDARD_IA',
+            ),
+        })
+        .optional()
+        .describe('Retention of the checkpoints in the bucket'),
+    })
+    .describe('A checkpoint syncer that uses S3'),
+  z
+    .object({
+      type: z.literal('ipfs'),
+      apiUrl: z.string().url().describe('The RPC API of the IPFS node'),
+      key: z
+        .string()
+        .min(1)
+        .describe('The name of the IPNS key to publish checkpoints with'),
+      gatewayUrl: z
+        .string()
+        .url()
+        .optional()
+        .describe('The gateway to read checkpoints with'),
+    })
+    .describe('A checkpoint syncer that uses IPFS and IPNS'),
+]);
+
 export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
     .min(1)
     .optional()
     .describe('The path to the validator database.'),
+  signingDb: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'The path to the database recording every checkpoint root the validator has signed. Never share it between validators.',
+    ),
   originChainName: z
     .string()
     .min(1)
     .describe('Name of the chain to validate messages on'),
   validator: AgentSignerSchema.describe('The validator attestation signer'),
-  checkpointSyncer: z.discriminatedUnion('type', [
-    z
-      .object({
-        type: z.literal('localStorage'),
-        path: z.string().min(1).describe('Path to the local storage location'),
-      })
-      .describe('A local checkpoint syncer'),
-    z
-      .object({
-        type: z.literal('s3'),
-        bucket: z.string().min(1),
-        region: z.string().min(1),
-        folder: z
-          .string()
-          .min(1)
-          .optional()
-          .describe(
-            'The folder/key-prefix to use, defaults to the root of the bucket',
-          ),
-      })
-      .describe('A checkpoint syncer that uses S3'),
-  ]),
+  checkpointSyncer: CheckpointSyncerSchema.optional().describe(
+    'Where the validator writes its checkpoints. Required unless simulating.',
+  ),
+  checkpointSyncerMirrors: z
+    .array(CheckpointSyncerSchema)
+    .optional()
+    .describe(
+      'Checkpoint syncers every checkpoint is also written to, as mirrors of the announced checkpointSyncer.',
+    ),
   interval: ZUint.optional().describe(
     'How long to wait between checking for new checkpoints in seconds.',
   ),
+  useFinalizedTag: z
+    .boolean()
+    .optional()
+    .describe(
+      "Deprecated, set blocks.finalityTag on the origin chain instead. Only sign checkpoints behind the origin chain's finalized block instead of lagging the tip by reorgPeriod.",
+    ),
+  announceChains: z
+    .string()
+    .optional()
+    .describe(
+      'Comma separated names of chains other than the origin to announce the checkpoint storage location on.',
+    ),
+  announceInterval: ZUint.optional().describe(
+    'How long to wait between checking the announcements on all chains in seconds.',
+  ),
+  checkpointAttestation: z
+    .object({
+      chain: z
+        .string()
+        .min(1)
+        .describe('Name of the chain the attestation contract is on.'),
+      address: ZHash.describe('Address of the CheckpointAttestation contract.'),
+      interval: ZUint.optional().describe(
+        'How long to wait between posting the latest signed checkpoint in seconds.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Also post the latest signed checkpoint to a CheckpointAttestation contract, so the liveness of the validator can be verified on-chain.',
+    ),
+  simulation: z
+    .object({
+      path: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'Directory the signed checkpoints are written to, in place of the checkpointSyncer.',
+        ),
+      compareValidators: z
+        .array(ZHash)
+        .optional()
+        .describe(
+          'Validators already live on the origin whose checkpoints the signed ones are compared with.',
+        ),
+      compareInterval: ZUint.optional().describe(
+        'How long to wait between comparing checkpoints in seconds.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Rehearse validating the origin without going live: checkpoints are signed as usual but only written locally, the validator is never announced and no liveness is attested.',
+    ),
 });
 
 export type ValidatorConfig = z.infer<typeof ValidatorAgentConfigSchema>;
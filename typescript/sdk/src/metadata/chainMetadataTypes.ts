@@ -17,6 +17,7 @@ export enum ExplorerFamily {
 
 export enum ChainTechnicalStack {
   ArbitrumNitro = 'arbitrumnitro',
+  ZkSync = 'zksync',
   Other = 'other',
 }
 
@@ -177,6 +178,12 @@ export const ChainMetadataSchemaObject = z.object({
         .finite()
         .optional()
         .describe('Rough estimate of time per block in seconds.'),
+      finalityTag: z
+        .enum(['safe', 'finalized'])
+        .optional()
+        .describe(
+          'Block tag the agents read final state at instead of lagging the tip by reorgPeriod.',
+        ),
     })
     .optional()
     .describe('Block settings for the chain/deployment.'),
@@ -13,6 +13,9 @@ export const HyperlaneDeploymentArtifactsSchema = z.object({
   validatorAnnounce: ZHash.describe(
     'The address of the Validator Announce contract.',
   ),
+  storageGasOracle: ZHash.optional().describe(
+    'The address of the Storage Gas Oracle contract, whose gas data updates are indexed.',
+  ),
   interchainSecurityModule: ZHash.optional().describe(
     'The address of the Interchain Security Module (ISM) contract.',
   ),